@@ -0,0 +1,95 @@
+//! Loading the query data in `.sqlx` as the query macros do with `SQLX_OFFLINE=true`: reading
+//! the file, parsing it and deserializing the description of the query.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use serde::Deserialize;
+use sqlx::sqlite::{Sqlite, SqliteConnection};
+use sqlx::{query_data, Connection, Describe, Executor};
+
+use std::path::{Path, PathBuf};
+
+const QUERIES: &[(&str, &str)] = &[
+    ("trivial", "SELECT 1"),
+    (
+        "join",
+        "SELECT a.id, a.name, a.created_at, b.id AS post_id, b.title, b.body \
+         FROM accounts a JOIN posts b ON b.account_id = a.id WHERE a.id = ?1",
+    ),
+];
+
+#[derive(Deserialize)]
+struct DynQueryData {
+    db_name: String,
+    query: String,
+    describe: serde_json::Value,
+    hash: String,
+}
+
+/// Describe each query and save its data, as `cargo sqlx prepare` would.
+async fn init_query_data(dir: &Path) -> Vec<(&'static str, String, PathBuf)> {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+
+    conn.execute(
+        r#"
+        CREATE TABLE accounts (id INTEGER PRIMARY KEY, name TEXT NOT NULL, created_at TEXT);
+        CREATE TABLE posts (
+            id INTEGER PRIMARY KEY,
+            account_id INTEGER NOT NULL REFERENCES accounts(id),
+            title TEXT NOT NULL,
+            body TEXT
+        );
+        "#,
+    )
+    .await
+    .unwrap();
+
+    let mut files = Vec::new();
+
+    for &(name, query) in QUERIES {
+        let describe = conn.describe(query).await.unwrap();
+        let data = serde_json::json!({
+            "db_name": "SQLite",
+            "query": query,
+            "describe": describe,
+            "hash": query_data::hash_query(query),
+        });
+
+        let path = dir.join(query_data::data_file_name(query));
+        std::fs::write(&path, serde_json::to_vec_pretty(&data).unwrap()).unwrap();
+
+        files.push((name, query.to_owned(), path));
+    }
+
+    files
+}
+
+fn load(path: &Path, query: &str) -> Describe<Sqlite> {
+    let contents = std::fs::read_to_string(path).unwrap();
+    let data: DynQueryData = serde_json::from_str(&contents).unwrap();
+
+    assert_eq!(data.db_name, "SQLite");
+    assert_eq!(data.query, query);
+    assert!(!data.hash.is_empty());
+
+    serde_json::from_value(data.describe).unwrap()
+}
+
+fn offline_load(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let dir = tempdir::TempDir::new("sqlx-bench-offline").unwrap();
+    let files = runtime.block_on(init_query_data(dir.path()));
+
+    let mut group = c.benchmark_group("offline_load");
+
+    for (name, query, path) in &files {
+        group.bench_with_input(BenchmarkId::from_parameter(name), path, |b, path| {
+            b.iter(|| load(path, query));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, offline_load);
+criterion_main!(benches);
@@ -0,0 +1,44 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use futures::future::join_all;
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use sqlx::Executor;
+
+const MAX_CONNECTIONS: u32 = 4;
+
+async fn init_pool() -> SqlitePool {
+    SqlitePoolOptions::new()
+        .min_connections(MAX_CONNECTIONS)
+        .max_connections(MAX_CONNECTIONS)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap()
+}
+
+// `tasks` acquire a connection at once and run a trivial query with it, so all but
+// `MAX_CONNECTIONS` of them wait for another to release its connection
+async fn do_acquire(pool: &SqlitePool, tasks: usize) {
+    join_all((0..tasks).map(|_| async {
+        let mut conn = pool.acquire().await.unwrap();
+        conn.execute("SELECT 1").await.unwrap();
+    }))
+    .await;
+}
+
+fn acquire(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let pool = runtime.block_on(init_pool());
+
+    let mut group = c.benchmark_group("pool_acquire");
+
+    for tasks in [1, 4, 16, 64] {
+        group.bench_with_input(BenchmarkId::from_parameter(tasks), &tasks, |b, &tasks| {
+            b.to_async(&runtime).iter(|| do_acquire(&pool, tasks));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, acquire);
+criterion_main!(benches);
@@ -0,0 +1,119 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use sqlx::sqlite::SqliteConnection;
+use sqlx::{Connection, Executor, Row};
+
+use std::cell::RefCell;
+
+const ROWS: i64 = 1000;
+
+async fn init_connection() -> SqliteConnection {
+    let mut conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+
+    conn.execute(
+        r#"
+        CREATE TABLE narrow (id INTEGER PRIMARY KEY);
+
+        CREATE TABLE wide (
+            id INTEGER PRIMARY KEY,
+            a INTEGER NOT NULL, b INTEGER NOT NULL, c INTEGER NOT NULL, d INTEGER NOT NULL,
+            e REAL NOT NULL, f REAL NOT NULL, g REAL NOT NULL, h REAL NOT NULL,
+            i TEXT NOT NULL, j TEXT NOT NULL, k TEXT NOT NULL, l TEXT NOT NULL,
+            m BLOB NOT NULL, n BLOB NOT NULL, o TEXT
+        );
+
+        CREATE TABLE log (id INTEGER PRIMARY KEY, message TEXT NOT NULL);
+        "#,
+    )
+    .await
+    .unwrap();
+
+    for id in 0..ROWS {
+        sqlx::query("INSERT INTO narrow (id) VALUES (?1)")
+            .bind(id)
+            .execute(&mut conn)
+            .await
+            .unwrap();
+
+        sqlx::query(
+            "INSERT INTO wide VALUES \
+             (?1, ?1, ?1, ?1, ?1, 0.5, 0.5, 0.5, 0.5, \
+              'Hello, world!', 'Hello, world!', 'Hello, world!', 'Hello, world!', \
+              x'00ff00ff00ff00ff', x'00ff00ff00ff00ff', NULL)",
+        )
+        .bind(id)
+        .execute(&mut conn)
+        .await
+        .unwrap();
+    }
+
+    conn
+}
+
+async fn do_connect() {
+    let conn = SqliteConnection::connect("sqlite::memory:").await.unwrap();
+    conn.close().await.unwrap();
+}
+
+async fn do_execute(db: &RefCell<SqliteConnection>) {
+    sqlx::query("INSERT INTO log (message) VALUES (?1)")
+        .bind("Hello, world!")
+        .execute(&mut *db.borrow_mut())
+        .await
+        .unwrap();
+}
+
+async fn do_fetch_all(db: &RefCell<SqliteConnection>, table: &str) {
+    let query = format!("SELECT * FROM {table}");
+    let rows = sqlx::query(&query)
+        .fetch_all(&mut *db.borrow_mut())
+        .await
+        .unwrap();
+
+    for row in &rows {
+        for i in 0..row.len() {
+            let _ = row.try_get_raw(i).unwrap();
+        }
+    }
+
+    assert_eq!(rows.len() as i64, ROWS);
+}
+
+fn connect(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+
+    c.bench_function("connect", move |b| {
+        b.to_async(&runtime).iter(do_connect);
+    });
+}
+
+fn execute(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let db = RefCell::new(runtime.block_on(init_connection()));
+
+    c.bench_with_input(
+        BenchmarkId::new("execute", "prepared"),
+        &db,
+        move |b, db_ref| {
+            b.to_async(&runtime).iter(|| do_execute(db_ref));
+        },
+    );
+}
+
+fn fetch_all(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let db = RefCell::new(runtime.block_on(init_connection()));
+
+    let mut group = c.benchmark_group("fetch_all");
+
+    for table in ["narrow", "wide"] {
+        group.bench_with_input(BenchmarkId::from_parameter(table), &db, |b, db_ref| {
+            b.to_async(&runtime).iter(|| do_fetch_all(db_ref, table));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, connect, execute, fetch_all);
+criterion_main!(benches);
@@ -0,0 +1,522 @@
+//! Advice on statements that lock or rewrite tables, for `sqlx migrate advise`.
+//!
+//! This is a heuristic: the statements are recognized by their keywords, not parsed,
+//! and whether a statement actually blocks depends on the table and its contents.
+
+use std::fmt;
+
+use sqlx::{ServerFlavor, ServerVersion};
+
+use crate::lint::{self, Dialect, Statement};
+
+/// Parse a version passed to `--server-version`, e.g. `11.4`, or the result of `SELECT version()`,
+/// e.g. `PostgreSQL 15.3 on x86_64-pc-linux-gnu, ...` or `10.11.2-MariaDB`.
+pub fn parse_version(dialect: Dialect, version: &str) -> Option<ServerVersion> {
+    if !version.contains(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some(match dialect {
+        Dialect::MySql => ServerVersion::parse_mysql(version),
+        _ => ServerVersion::parse(ServerFlavor::Postgres, version),
+    })
+}
+
+/// A statement that may block reads or writes while it runs, and how to avoid it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Advice {
+    pub problem: String,
+    pub suggestion: String,
+}
+
+impl fmt::Display for Advice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n  help: {}", self.problem, self.suggestion)
+    }
+}
+
+/// Check the statements of a migration for `dialect`, assuming the oldest affected version
+/// of the server if `version` is unknown.
+pub fn advise(sql: &str, dialect: Dialect, version: Option<&ServerVersion>) -> Vec<Advice> {
+    let mut advice = Vec::new();
+
+    for statement in lint::split(sql, dialect) {
+        let Some(alter) = AlterTable::parse(&statement) else {
+            continue;
+        };
+
+        match dialect {
+            Dialect::Postgres => advise_postgres(&alter, version, &mut advice),
+            Dialect::MySql => advise_mysql(&alter, version, &mut advice),
+            Dialect::Sqlite | Dialect::Generic => (),
+        }
+    }
+
+    advice
+}
+
+/// `true` if `version` is known to be at least `mysql`, or `mariadb` for MariaDB.
+fn at_least(
+    version: Option<&ServerVersion>,
+    mysql: (u32, u32, u32),
+    mariadb: (u32, u32, u32),
+) -> bool {
+    version.is_some_and(|version| {
+        let (major, minor, patch) = if version.flavor == ServerFlavor::MariaDb {
+            mariadb
+        } else {
+            mysql
+        };
+        version.at_least(major, minor, patch)
+    })
+}
+
+const VOLATILE_FUNCTIONS: &[&str] = &[
+    "CLOCK_TIMESTAMP",
+    "GEN_RANDOM_UUID",
+    "NEXTVAL",
+    "RANDOM",
+    "TIMEOFDAY",
+    "UUID_GENERATE_V1",
+    "UUID_GENERATE_V4",
+];
+
+fn advise_postgres(
+    alter: &AlterTable<'_>,
+    version: Option<&ServerVersion>,
+    advice: &mut Vec<Advice>,
+) {
+    let table = alter.table;
+    let pg = |major| at_least(version, (major, 0, 0), (major, 0, 0));
+
+    let rewrite_suggestion = |column: &str| {
+        format!(
+            "add `{column}` without a default, set the default with \
+             `ALTER COLUMN {column} SET DEFAULT`, then backfill existing rows in batches"
+        )
+    };
+
+    for clause in &alter.clauses {
+        match clause.keyword() {
+            "ALTER" => {
+                let Some(column) = clause.column() else {
+                    continue;
+                };
+
+                if clause.contains(&["SET", "NOT", "NULL"]) {
+                    let suggestion = if pg(12) || version.is_none() {
+                        format!(
+                            "add `CHECK ({column} IS NOT NULL) NOT VALID` and `VALIDATE` it in a \
+                             separate migration, which doesn't block writes; from PostgreSQL 12, \
+                             `SET NOT NULL` then skips the scan, and the check can be dropped"
+                        )
+                    } else {
+                        format!(
+                            "use `CHECK ({column} IS NOT NULL) NOT VALID` instead, and `VALIDATE` \
+                             it in a separate migration, which doesn't block writes"
+                        )
+                    };
+
+                    advice.push(Advice {
+                        problem: format!(
+                            "`ALTER COLUMN {column} SET NOT NULL` scans all of `{table}` \
+                             while blocking reads and writes"
+                        ),
+                        suggestion,
+                    });
+                } else if clause.contains(&["TYPE"]) {
+                    advice.push(Advice {
+                        problem: format!(
+                            "changing the type of `{column}` rewrites `{table}` and its indexes \
+                             while blocking reads and writes, unless the types are binary \
+                             compatible (e.g. raising a `varchar` limit)"
+                        ),
+                        suggestion: format!(
+                            "add a column with the new type, keep it in sync with `{column}` \
+                             while backfilling it in batches, then switch over and drop `{column}`"
+                        ),
+                    });
+                }
+            }
+
+            "ADD" => match clause.added() {
+                Added::Constraint(kind @ ("FOREIGN" | "CHECK")) => {
+                    if clause.contains(&["NOT", "VALID"]) {
+                        continue;
+                    }
+
+                    let kind = if kind == "FOREIGN" {
+                        "FOREIGN KEY"
+                    } else {
+                        kind
+                    };
+
+                    advice.push(Advice {
+                        problem: format!(
+                            "adding a `{kind}` constraint scans all of `{table}` while blocking writes"
+                        ),
+                        suggestion: format!(
+                            "add it with `NOT VALID`, then `ALTER TABLE {table} VALIDATE CONSTRAINT` \
+                             in a separate migration, which doesn't block writes"
+                        ),
+                    });
+                }
+
+                Added::Constraint(kind @ ("UNIQUE" | "PRIMARY")) => {
+                    if clause.contains(&["USING", "INDEX"]) {
+                        continue;
+                    }
+
+                    let kind = if kind == "PRIMARY" {
+                        "PRIMARY KEY"
+                    } else {
+                        kind
+                    };
+
+                    advice.push(Advice {
+                        problem: format!(
+                            "adding a `{kind}` constraint builds an index on `{table}` \
+                             while blocking writes"
+                        ),
+                        suggestion: format!(
+                            "build the index with `CREATE UNIQUE INDEX CONCURRENTLY` outside of a \
+                             migration, then `ADD CONSTRAINT ... {kind} USING INDEX`"
+                        ),
+                    });
+                }
+
+                Added::Constraint(_) => (),
+
+                Added::Column(column) => {
+                    if let Some(kind) = ["SERIAL", "BIGSERIAL", "SMALLSERIAL", "IDENTITY", "STORED"]
+                        .into_iter()
+                        .find(|kind| clause.contains(&[kind]))
+                    {
+                        advice.push(Advice {
+                            problem: format!(
+                                "adding `{column}` as a `{kind}` column rewrites all of `{table}` \
+                                 while blocking reads and writes"
+                            ),
+                            suggestion: format!(
+                                "add `{column}` as a plain column and backfill it in batches \
+                                 before attaching the sequence or expression"
+                            ),
+                        });
+                    } else if clause.contains(&["DEFAULT"]) {
+                        let default = clause.after("DEFAULT").unwrap_or_default();
+
+                        if !pg(11) {
+                            advice.push(Advice {
+                                problem: format!(
+                                    "adding `{column}` with a default rewrites all of `{table}` \
+                                     while blocking reads and writes before PostgreSQL 11"
+                                ),
+                                suggestion: rewrite_suggestion(column),
+                            });
+                        } else if VOLATILE_FUNCTIONS.contains(&default) {
+                            advice.push(Advice {
+                                problem: format!(
+                                    "adding `{column}` with the volatile default `{}()` rewrites \
+                                     all of `{table}` while blocking reads and writes",
+                                    default.to_lowercase()
+                                ),
+                                suggestion: rewrite_suggestion(column),
+                            });
+                        }
+                    }
+                }
+            },
+
+            _ => (),
+        }
+    }
+}
+
+fn advise_mysql(alter: &AlterTable<'_>, version: Option<&ServerVersion>, advice: &mut Vec<Advice>) {
+    // with an explicit algorithm, MySQL fails rather than falling back to a slower one
+    if alter
+        .clauses
+        .iter()
+        .any(|clause| clause.contains(&["ALGORITHM"]))
+    {
+        return;
+    }
+
+    let table = alter.table;
+    let suggestion = || {
+        "specify `ALGORITHM=INSTANT` or `ALGORITHM=INPLACE, LOCK=NONE` so the statement fails \
+         instead of blocking, and use an online schema change tool such as gh-ost or \
+         pt-online-schema-change for large tables"
+            .to_owned()
+    };
+
+    for clause in &alter.clauses {
+        let problem = match clause.keyword() {
+            "ADD" => match clause.added() {
+                Added::Column(column) => {
+                    if !at_least(version, (8, 0, 12), (10, 3, 0)) {
+                        format!(
+                            "adding `{column}` rebuilds `{table}` before MySQL 8.0.12 \
+                             (MariaDB 10.3)"
+                        )
+                    } else if (clause.contains(&["FIRST"]) || clause.contains(&["AFTER"]))
+                        && !at_least(version, (8, 0, 29), (10, 4, 0))
+                    {
+                        format!(
+                            "adding `{column}` other than as the last column rebuilds `{table}` \
+                             before MySQL 8.0.29 (MariaDB 10.4)"
+                        )
+                    } else {
+                        continue;
+                    }
+                }
+                Added::Constraint("FOREIGN") => format!(
+                    "adding a foreign key copies `{table}` while blocking writes, \
+                     unless `foreign_key_checks` is disabled"
+                ),
+                Added::Constraint(_) => continue,
+            },
+
+            "DROP" => {
+                let Some(column) = clause.column() else {
+                    continue;
+                };
+
+                if at_least(version, (8, 0, 29), (10, 4, 0)) {
+                    continue;
+                }
+
+                format!("dropping `{column}` rebuilds `{table}` before MySQL 8.0.29 (MariaDB 10.4)")
+            }
+
+            keyword @ ("MODIFY" | "CHANGE") => {
+                let column = clause.column().unwrap_or_default();
+
+                format!(
+                    "`{keyword} {column}` copies `{table}` while blocking writes, unless only \
+                     the column's name, default or `ENUM` values change"
+                )
+            }
+
+            "CONVERT" => {
+                format!("converting the character set copies `{table}` while blocking writes")
+            }
+
+            _ => continue,
+        };
+
+        advice.push(Advice {
+            problem,
+            suggestion: suggestion(),
+        });
+    }
+}
+
+/// An `ALTER TABLE` statement split into its clauses, e.g. `ADD COLUMN ...`.
+struct AlterTable<'a> {
+    table: &'a str,
+    clauses: Vec<Clause<'a>>,
+}
+
+impl<'a> AlterTable<'a> {
+    fn parse(statement: &'a Statement<'_>) -> Option<Self> {
+        let words = &statement.words;
+
+        if words.first()? != "ALTER" || words.get(1)? != "TABLE" {
+            return None;
+        }
+
+        let mut i = 2;
+        if words.get(i)? == "IF" {
+            i += 2;
+        }
+        if words.get(i)? == "ONLY" {
+            i += 1;
+        }
+
+        let table = statement.raw_words.get(i)?;
+
+        // the column of an `ALTER COLUMN` clause may be followed by `DROP DEFAULT`, etc.
+        let mut clauses: Vec<Clause<'a>> = Vec::new();
+
+        for (j, word) in words.iter().enumerate().skip(i + 1) {
+            let starts_clause = matches!(
+                word.as_str(),
+                "ADD" | "ALTER" | "DROP" | "MODIFY" | "CHANGE" | "RENAME" | "CONVERT"
+            ) && !clauses.last().is_some_and(|clause| {
+                clause.keyword() == "ALTER" && clause.words.len() <= 3 && word == "DROP"
+            });
+
+            match clauses.last_mut() {
+                Some(clause) if !starts_clause => {
+                    clause.words = &words[clause.start..=j];
+                    clause.raw_words = &statement.raw_words[clause.start..=j];
+                }
+                _ => clauses.push(Clause {
+                    start: j,
+                    words: &words[j..=j],
+                    raw_words: &statement.raw_words[j..=j],
+                }),
+            }
+        }
+
+        Some(AlterTable { table, clauses })
+    }
+}
+
+struct Clause<'a> {
+    start: usize,
+    words: &'a [String],
+    raw_words: &'a [&'a str],
+}
+
+enum Added<'a> {
+    Column(&'a str),
+    /// The kind of constraint or index, e.g. `FOREIGN` or `INDEX`.
+    Constraint(&'a str),
+}
+
+impl<'a> Clause<'a> {
+    fn keyword(&self) -> &str {
+        &self.words[0]
+    }
+
+    fn contains(&self, keywords: &[&str]) -> bool {
+        self.words
+            .windows(keywords.len())
+            .any(|window| window.iter().zip(keywords).all(|(a, b)| a == b))
+    }
+
+    /// The word after `keyword`, if any.
+    fn after(&self, keyword: &str) -> Option<&str> {
+        let i = self.words.iter().position(|word| word == keyword)?;
+        self.words.get(i + 1).map(String::as_str)
+    }
+
+    /// The column this clause alters, drops or modifies, if it's about a column.
+    fn column(&self) -> Option<&'a str> {
+        let mut i = 1;
+
+        if self.words.get(i).map(String::as_str) == Some("COLUMN") {
+            i += 1;
+        }
+
+        if self.words.get(i).map(String::as_str) == Some("IF") {
+            i += 2;
+        }
+
+        let word = self.words.get(i)?;
+
+        let not_a_column = matches!(
+            word.as_str(),
+            "CONSTRAINT" | "INDEX" | "KEY" | "PRIMARY" | "FOREIGN" | "CHECK" | "DEFAULT"
+        );
+
+        (!not_a_column).then(|| self.raw_words[i])
+    }
+
+    /// What an `ADD` clause adds.
+    fn added(&self) -> Added<'a> {
+        let kind = |i: usize| self.words.get(i).map_or("", String::as_str);
+
+        if kind(1) == "CONSTRAINT" {
+            return Added::Constraint(kind(3));
+        }
+
+        match kind(1) {
+            "INDEX" | "KEY" | "PRIMARY" | "UNIQUE" | "FOREIGN" | "CHECK" | "FULLTEXT"
+            | "SPATIAL" => Added::Constraint(kind(1)),
+            _ => Added::Column(self.column().unwrap_or_default()),
+        }
+    }
+}
+
+#[test]
+fn test_parse_version() {
+    let version = |dialect, s| parse_version(dialect, s).map(|version| version.to_string());
+
+    assert_eq!(
+        version(Dialect::Postgres, "11.4").as_deref(),
+        Some("Postgres 11.4.0")
+    );
+    assert_eq!(
+        version(
+            Dialect::Postgres,
+            "PostgreSQL 15.3 (Debian 15.3-1.pgdg120+1) on x86_64-pc-linux-gnu"
+        )
+        .as_deref(),
+        Some("Postgres 15.3.0")
+    );
+    assert_eq!(
+        version(Dialect::MySql, "8.0.33").as_deref(),
+        Some("MySql 8.0.33")
+    );
+    assert_eq!(
+        version(
+            Dialect::MySql,
+            "5.5.5-10.11.2-MariaDB-1:10.11.2+maria~ubu2204"
+        )
+        .as_deref(),
+        Some("MariaDb 10.11.2")
+    );
+    assert_eq!(version(Dialect::MySql, "unknown"), None);
+}
+
+#[test]
+fn test_advise_postgres() {
+    let problems = |sql, version| {
+        advise(
+            sql,
+            Dialect::Postgres,
+            parse_version(Dialect::Postgres, version).as_ref(),
+        )
+        .into_iter()
+        .map(|advice| advice.problem)
+        .collect::<Vec<_>>()
+    };
+
+    let sql = "ALTER TABLE users ALTER COLUMN email SET NOT NULL, ADD COLUMN bio text DEFAULT '';\n\
+               ALTER TABLE posts ADD CONSTRAINT posts_user FOREIGN KEY (user_id) REFERENCES users;\n\
+               ALTER TABLE posts ADD CONSTRAINT posts_check CHECK (id > 0) NOT VALID;\n\
+               ALTER TABLE posts ALTER COLUMN id DROP DEFAULT, ADD created_at timestamptz DEFAULT clock_timestamp();";
+
+    assert_eq!(
+        problems(sql, "10.5"),
+        [
+            "`ALTER COLUMN email SET NOT NULL` scans all of `users` while blocking reads and writes",
+            "adding `bio` with a default rewrites all of `users` while blocking reads and writes before PostgreSQL 11",
+            "adding a `FOREIGN KEY` constraint scans all of `posts` while blocking writes",
+            "adding `created_at` with a default rewrites all of `posts` while blocking reads and writes before PostgreSQL 11",
+        ]
+    );
+
+    assert_eq!(
+        problems(sql, "16.1")[2],
+        "adding `created_at` with the volatile default `clock_timestamp()` rewrites all of `posts` while blocking reads and writes"
+    );
+    assert_eq!(problems(sql, "16.1").len(), 3);
+}
+
+#[test]
+fn test_advise_mysql() {
+    let problems = |sql, version| {
+        advise(
+            sql,
+            Dialect::MySql,
+            parse_version(Dialect::MySql, version).as_ref(),
+        )
+        .into_iter()
+        .map(|advice| advice.problem)
+        .collect::<Vec<_>>()
+    };
+
+    let sql = "ALTER TABLE users ADD COLUMN bio text AFTER email, DROP COLUMN name;\n\
+               ALTER TABLE users MODIFY email varchar(500);\n\
+               ALTER TABLE users ADD INDEX users_email (email);\n\
+               ALTER TABLE users ADD COLUMN age int, ALGORITHM=INSTANT;";
+
+    assert_eq!(problems(sql, "8.0.33"), ["`MODIFY email` copies `users` while blocking writes, unless only the column's name, default or `ENUM` values change"]);
+    assert_eq!(problems(sql, "8.0.20").len(), 3);
+    assert_eq!(problems(sql, "10.5.1-MariaDB").len(), 1);
+    assert_eq!(problems(sql, "").len(), 3);
+}
@@ -0,0 +1,244 @@
+//! `cargo sqlx bench`: run the Criterion benchmarks of a package with `cargo bench`, save their
+//! results as a baseline, and compare later runs against it to catch performance regressions.
+//!
+//! A baseline is a JSON file, `{"version": 1, "benchmarks": {"<id>": <mean in ns>}}`, small
+//! and stable enough to be committed or kept as a CI artifact.
+
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::SystemTime;
+
+use anyhow::{bail, Context};
+use console::style;
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::Metadata;
+
+/// The mean time of each benchmark, in nanoseconds, by its Criterion ID.
+#[derive(Debug, Serialize, Deserialize)]
+struct Baseline {
+    version: u32,
+    benchmarks: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Change {
+    Regressed(f64),
+    Improved(f64),
+    Unchanged(f64),
+    Added,
+    Removed,
+}
+
+pub fn run(
+    against: Option<PathBuf>,
+    save: Option<PathBuf>,
+    threshold: f64,
+    args: Vec<String>,
+) -> anyhow::Result<()> {
+    // read the baseline first, so a missing file is reported before the benchmarks run
+    let baseline = against
+        .as_deref()
+        .map(|path| {
+            let json = fs::read(path)
+                .with_context(|| format!("failed to read baseline {}", path.display()))?;
+            serde_json::from_slice::<Baseline>(&json)
+                .with_context(|| format!("invalid baseline {}", path.display()))
+        })
+        .transpose()?;
+
+    let cargo = env_cargo();
+    let started = SystemTime::now();
+
+    let status = Command::new(&cargo)
+        .arg("bench")
+        .args(&args)
+        .status()
+        .context("failed to run `cargo bench`")?;
+
+    if !status.success() {
+        bail!("`cargo bench` failed with {status}");
+    }
+
+    let metadata = Metadata::from_current_directory(&cargo)?;
+    let criterion_dir = metadata.target_directory().join("criterion");
+    let results = collect_results(&criterion_dir, started)?;
+
+    if results.is_empty() {
+        bail!(
+            "no Criterion results were found in {}; is `harness = false` set for the benchmarks?",
+            criterion_dir.display()
+        );
+    }
+
+    if let Some(path) = &save {
+        let baseline = Baseline {
+            version: 1,
+            benchmarks: results.clone(),
+        };
+
+        let mut json = serde_json::to_vec_pretty(&baseline)?;
+        json.push(b'\n');
+        fs::write(path, json)
+            .with_context(|| format!("failed to write baseline {}", path.display()))?;
+
+        println!(
+            "saved {} benchmark results to {}",
+            results.len(),
+            path.display()
+        );
+    }
+
+    let Some(baseline) = baseline else {
+        return Ok(());
+    };
+
+    let changes = compare(&baseline.benchmarks, &results, threshold);
+    let mut regressions = 0;
+
+    for (id, change) in &changes {
+        let status = match change {
+            Change::Regressed(percent) => {
+                regressions += 1;
+                style(format!("regressed {percent:+.2}%")).red()
+            }
+            Change::Improved(percent) => style(format!("improved {percent:+.2}%")).green(),
+            Change::Unchanged(percent) => style(format!("unchanged {percent:+.2}%")).dim(),
+            Change::Added => style("new".to_string()).cyan(),
+            Change::Removed => style("not run".to_string()).yellow(),
+        };
+
+        println!("{id}: {status}");
+    }
+
+    if regressions > 0 {
+        bail!("{regressions} benchmark(s) regressed by more than {threshold}%");
+    }
+
+    Ok(())
+}
+
+/// `cargo sqlx` is run by Cargo with `CARGO` set; fall back to `cargo` on the `PATH` otherwise.
+fn env_cargo() -> OsString {
+    std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into())
+}
+
+/// Read the mean time of each benchmark run since `since` from the Criterion output directory,
+/// skipping the results left over from benchmarks that weren't run this time.
+fn collect_results(dir: &Path, since: SystemTime) -> anyhow::Result<BTreeMap<String, f64>> {
+    let mut results = BTreeMap::new();
+
+    if dir.is_dir() {
+        collect_results_in(dir, since, &mut results)?;
+    }
+
+    Ok(results)
+}
+
+fn collect_results_in(
+    dir: &Path,
+    since: SystemTime,
+    results: &mut BTreeMap<String, f64>,
+) -> anyhow::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if !path.is_dir() {
+            continue;
+        }
+
+        // Criterion writes `<group>/<function>/<value>/new/{benchmark,estimates}.json`;
+        // `base` and `change` hold the previous run and its comparison
+        if path.file_name().is_some_and(|name| name == "new") {
+            let estimates = path.join("estimates.json");
+
+            if !estimates.exists() || fs::metadata(&estimates)?.modified()? < since {
+                continue;
+            }
+
+            let benchmark: serde_json::Value =
+                serde_json::from_slice(&fs::read(path.join("benchmark.json"))?)?;
+            let estimates: serde_json::Value = serde_json::from_slice(&fs::read(&estimates)?)?;
+
+            let id = benchmark["full_id"]
+                .as_str()
+                .with_context(|| format!("no `full_id` in {}", path.display()))?;
+            let mean = estimates["mean"]["point_estimate"]
+                .as_f64()
+                .with_context(|| format!("no mean estimate in {}", path.display()))?;
+
+            results.insert(id.to_owned(), mean);
+        } else {
+            collect_results_in(&path, since, results)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Compare the mean time of each benchmark to the baseline; a change of more than `threshold`
+/// percent in either direction is a regression or an improvement.
+fn compare(
+    baseline: &BTreeMap<String, f64>,
+    results: &BTreeMap<String, f64>,
+    threshold: f64,
+) -> BTreeMap<String, Change> {
+    let mut changes = BTreeMap::new();
+
+    for (id, &mean) in results {
+        let change = match baseline.get(id) {
+            Some(&base) => {
+                let percent = (mean - base) / base * 100.0;
+
+                if percent > threshold {
+                    Change::Regressed(percent)
+                } else if percent < -threshold {
+                    Change::Improved(percent)
+                } else {
+                    Change::Unchanged(percent)
+                }
+            }
+            None => Change::Added,
+        };
+
+        changes.insert(id.clone(), change);
+    }
+
+    for id in baseline.keys() {
+        if !results.contains_key(id) {
+            changes.insert(id.clone(), Change::Removed);
+        }
+    }
+
+    changes
+}
+
+#[test]
+fn test_compare() {
+    let baseline = BTreeMap::from([
+        ("fetch_all/narrow".to_owned(), 100.0),
+        ("fetch_all/wide".to_owned(), 200.0),
+        ("connect".to_owned(), 1000.0),
+        ("pool/acquire".to_owned(), 50.0),
+    ]);
+    let results = BTreeMap::from([
+        ("fetch_all/narrow".to_owned(), 110.0),
+        ("fetch_all/wide".to_owned(), 150.0),
+        ("connect".to_owned(), 1020.0),
+        ("execute".to_owned(), 10.0),
+    ]);
+
+    assert_eq!(
+        compare(&baseline, &results, 5.0),
+        BTreeMap::from([
+            ("fetch_all/narrow".to_owned(), Change::Regressed(10.0)),
+            ("fetch_all/wide".to_owned(), Change::Improved(-25.0)),
+            ("connect".to_owned(), Change::Unchanged(2.0)),
+            ("execute".to_owned(), Change::Added),
+            ("pool/acquire".to_owned(), Change::Removed),
+        ])
+    );
+}
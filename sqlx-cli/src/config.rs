@@ -0,0 +1,139 @@
+//! Project-wide settings for the CLI, read from `sqlx.toml` in the current directory or else the
+//! nearest of its ancestors, e.g. the root of the workspace, like the query macros do:
+//!
+//! ```toml
+//! [migrate]
+//! # always run `sqlx migrate run` as if `--safe` was passed
+//! safe = true
+//! # record who applied each migration in `_sqlx_migrations_log`
+//! audit-log = true
+//! # more directories for `sqlx migrate build-script` to watch
+//! build-script-watch = ["queries", "fixtures"]
+//!
+//! [prepare]
+//! # always run `sqlx prepare` as if `--tolerance` was passed
+//! tolerance = true
+//! # which schema changes keep existing query data; both default to `true`
+//! tolerate-equivalent-types = true
+//! tolerate-added-nullable-columns = true
+//! # write query data as `pretty` (the default) or `compact` JSON; also read by the query macros
+//! json-format = "compact"
+//! ```
+
+use anyhow::Context;
+use serde::Deserialize;
+use sqlx::query_data::{find_config_file, JsonFormat};
+
+/// The subset of `sqlx.toml` read by the CLI.
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config {
+    #[serde(default)]
+    pub migrate: MigrateConfig,
+
+    #[serde(default)]
+    pub prepare: PrepareConfig,
+}
+
+#[derive(Deserialize, Default, Debug)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct MigrateConfig {
+    /// Require confirmation before applying destructive migrations.
+    #[serde(default)]
+    pub safe: bool,
+
+    /// Record who applied or reverted each migration, and from where.
+    #[serde(default)]
+    pub audit_log: bool,
+
+    /// Directories that the generated build script also triggers recompilation for.
+    #[serde(default)]
+    pub build_script_watch: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PrepareConfig {
+    /// Keep existing query data if it only differs from the live database in tolerated ways.
+    #[serde(default)]
+    pub tolerance: bool,
+
+    /// Tolerate output columns whose type changed to one the query macros map to the same
+    /// Rust type, e.g. `VARCHAR` to `TEXT`.
+    #[serde(default = "default_true")]
+    pub tolerate_equivalent_types: bool,
+
+    /// Tolerate nullable output columns added after the existing ones.
+    #[serde(default = "default_true")]
+    pub tolerate_added_nullable_columns: bool,
+
+    /// How query data files are laid out.
+    #[serde(default)]
+    pub json_format: JsonFormat,
+}
+
+impl Default for PrepareConfig {
+    fn default() -> Self {
+        PrepareConfig {
+            tolerance: false,
+            tolerate_equivalent_types: true,
+            tolerate_added_nullable_columns: true,
+            json_format: JsonFormat::Pretty,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Config {
+    /// Read `sqlx.toml` in the current directory or the nearest of its ancestors, if any.
+    pub fn load() -> anyhow::Result<Self> {
+        let dir = std::env::current_dir().context("failed to get the current directory")?;
+
+        let Some(path) = find_config_file(&dir) else {
+            return Ok(Self::default());
+        };
+
+        let contents = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+
+        toml::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))
+    }
+}
+
+#[test]
+fn test_parse_config() {
+    let config: Config = toml::from_str("[migrate]\nsafe = true\n").unwrap();
+    assert!(config.migrate.safe);
+    assert!(!config.migrate.audit_log);
+
+    let config: Config = toml::from_str("[migrate]\naudit-log = true\n").unwrap();
+    assert!(config.migrate.audit_log);
+    assert!(config.migrate.build_script_watch.is_empty());
+
+    let config: Config =
+        toml::from_str("[migrate]\nbuild-script-watch = [\"queries\", \"seeds\"]\n").unwrap();
+    assert_eq!(config.migrate.build_script_watch, ["queries", "seeds"]);
+
+    // sections for the query macros are ignored
+    let config: Config = toml::from_str("[soft-delete]\ntables = [\"users\"]\n").unwrap();
+    assert!(!config.migrate.safe);
+
+    assert!(toml::from_str::<Config>("[migrate]\nsafe = \"yes\"\n").is_err());
+
+    let config: Config =
+        toml::from_str("[prepare]\ntolerance = true\ntolerate-equivalent-types = false\n").unwrap();
+    assert!(config.prepare.tolerance);
+    assert!(!config.prepare.tolerate_equivalent_types);
+    assert!(config.prepare.tolerate_added_nullable_columns);
+
+    let config: Config = toml::from_str("").unwrap();
+    assert!(!config.prepare.tolerance);
+    assert!(config.prepare.tolerate_equivalent_types);
+    assert_eq!(config.prepare.json_format, JsonFormat::Pretty);
+
+    let config: Config = toml::from_str("[prepare]\njson-format = \"compact\"\n").unwrap();
+    assert_eq!(config.prepare.json_format, JsonFormat::Compact);
+}
@@ -0,0 +1,230 @@
+//! The describe daemon, which describes queries for the query macros over a Unix socket, for
+//! builds in sandboxes that don't allow the compiler to connect to the database. The macros use it
+//! when `SQLX_DESCRIBE_SOCKET` is set to the path of the socket.
+//!
+//! Each connection sends a request per line as JSON, `{"database_url": "...", "query": "..."}`,
+//! and receives a response per line, `{"data": ...}` with the query data in the format of the
+//! files in `.sqlx`, or `{"error": "..."}`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{bail, Context};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sqlx::{query_data, Connection, Database, Describe, Executor};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+#[derive(Deserialize)]
+struct Request {
+    database_url: String,
+    query: String,
+}
+
+/// The connections to describe queries with, by database URL. Each is locked while it describes
+/// a query, so queries for other databases don't wait for it.
+type Connections = Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<Option<DescribeConnection>>>>>>;
+
+/// A connection with the driver for its database URL, as the query macros would choose.
+enum DescribeConnection {
+    #[cfg(feature = "postgres")]
+    Postgres(sqlx::PgConnection),
+    #[cfg(feature = "mysql")]
+    MySql(sqlx::MySqlConnection),
+    #[cfg(feature = "sqlite")]
+    Sqlite(sqlx::SqliteConnection),
+}
+
+impl DescribeConnection {
+    async fn connect(database_url: &str) -> anyhow::Result<Self> {
+        let scheme = database_url.split(':').next().unwrap_or_default();
+
+        Ok(match scheme {
+            #[cfg(feature = "postgres")]
+            "postgres" | "postgresql" => {
+                Self::Postgres(sqlx::PgConnection::connect(database_url).await?)
+            }
+            #[cfg(feature = "mysql")]
+            "mysql" | "mariadb" => Self::MySql(sqlx::MySqlConnection::connect(database_url).await?),
+            #[cfg(feature = "sqlite")]
+            "sqlite" => Self::Sqlite(sqlx::SqliteConnection::connect(database_url).await?),
+            _ => bail!(
+                "no database driver found matching URL scheme {scheme:?}; \
+                 the corresponding Cargo feature of sqlx-cli may need to be enabled"
+            ),
+        })
+    }
+
+    async fn describe(&mut self, query: &str) -> sqlx::Result<Value> {
+        Ok(match self {
+            #[cfg(feature = "postgres")]
+            Self::Postgres(conn) => query_data_json(query, conn.describe(query).await?),
+            #[cfg(feature = "mysql")]
+            Self::MySql(conn) => query_data_json(query, conn.describe(query).await?),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(conn) => query_data_json(query, conn.describe(query).await?),
+        })
+    }
+}
+
+/// The query data for `query`, as the macros would save it in `.sqlx`.
+fn query_data_json<DB: Database>(query: &str, describe: Describe<DB>) -> Value
+where
+    Describe<DB>: serde::Serialize,
+{
+    json!({
+        "db_name": DB::NAME,
+        "query": query,
+        "describe": describe,
+        "hash": query_data::hash_query(query),
+    })
+}
+
+/// Listen on `socket` until the process is stopped.
+pub async fn run(socket: &Path) -> anyhow::Result<()> {
+    let listener = bind(socket)?;
+
+    println!(
+        "describing queries for SQLX_DESCRIBE_SOCKET={}",
+        socket.display()
+    );
+
+    serve(listener).await
+}
+
+/// Listen on `socket` in a thread of its own for the rest of the process, e.g. while
+/// `cargo sqlx prepare --daemon` compiles the queries. The socket is removed when the returned
+/// guard is dropped.
+pub fn spawn(socket: PathBuf) -> anyhow::Result<SocketGuard> {
+    let listener = std::os::unix::net::UnixListener::bind(prepare_socket(&socket)?)
+        .with_context(|| format!("failed to listen on {}", socket.display()))?;
+    listener.set_nonblocking(true)?;
+
+    std::thread::spawn(move || {
+        let result = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("failed to start the describe daemon")
+            .and_then(|runtime| {
+                runtime.block_on(async move {
+                    let listener = UnixListener::from_std(listener)?;
+                    serve(listener).await
+                })
+            });
+
+        // the query macros can't describe their queries once the daemon stops, so say why
+        if let Err(e) = result {
+            eprintln!("describe daemon: {e:#}");
+        }
+    });
+
+    Ok(SocketGuard(socket))
+}
+
+/// Removes the socket of [`spawn()`] when dropped.
+pub struct SocketGuard(PathBuf);
+
+impl SocketGuard {
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for SocketGuard {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn bind(socket: &Path) -> anyhow::Result<UnixListener> {
+    UnixListener::bind(prepare_socket(socket)?)
+        .with_context(|| format!("failed to listen on {}", socket.display()))
+}
+
+/// Remove the socket of a daemon that's no longer running, so it can be bound again.
+fn prepare_socket(socket: &Path) -> anyhow::Result<&Path> {
+    if socket.exists() {
+        if std::os::unix::net::UnixStream::connect(socket).is_ok() {
+            bail!(
+                "a describe daemon is already listening on {}",
+                socket.display()
+            );
+        }
+
+        std::fs::remove_file(socket)
+            .with_context(|| format!("failed to remove stale socket {}", socket.display()))?;
+    }
+
+    Ok(socket)
+}
+
+async fn serve(listener: UnixListener) -> anyhow::Result<()> {
+    let connections = Connections::default();
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let connections = connections.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = handle(stream, connections).await {
+                eprintln!("describe daemon: {e:#}");
+            }
+        });
+    }
+}
+
+async fn handle(stream: UnixStream, connections: Connections) -> anyhow::Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        let response = match describe(&line, &connections).await {
+            Ok(data) => json!({ "data": data }),
+            Err(e) => json!({ "error": format!("{e:#}") }),
+        };
+
+        let mut response = serde_json::to_vec(&response)?;
+        response.push(b'\n');
+        writer.write_all(&response).await?;
+    }
+
+    Ok(())
+}
+
+async fn describe(request: &str, connections: &Connections) -> anyhow::Result<Value> {
+    let Request {
+        database_url,
+        query,
+    } = serde_json::from_str(request).context("invalid request")?;
+
+    let slot = connections
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(database_url.clone())
+        .or_default()
+        .clone();
+
+    // one query at a time per database, as with the connections the macros open in each
+    // compiler process
+    let mut slot = slot.lock().await;
+
+    let conn = match &mut *slot {
+        Some(conn) => conn,
+        None => slot.insert(DescribeConnection::connect(&database_url).await?),
+    };
+
+    match conn.describe(&query).await {
+        Ok(data) => Ok(data),
+        Err(e) => {
+            // the database rejecting the query leaves the connection usable; anything else may
+            // not, so the next request connects again
+            if !matches!(e, sqlx::Error::Database(_)) {
+                *slot = None;
+            }
+
+            Err(e.into())
+        }
+    }
+}
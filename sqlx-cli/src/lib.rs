@@ -1,22 +1,31 @@
-use std::io;
+use std::io::{self, IsTerminal};
 use std::time::Duration;
 
 use anyhow::Result;
 use futures::{Future, TryFutureExt};
+use promptly::{prompt, ReadlineError};
 
 use sqlx::{AnyConnection, Connection};
 
+use crate::lint::FormatOptions;
 use crate::opt::{Command, ConnectOpts, DatabaseCommand, MigrateCommand};
 
 mod database;
 mod metadata;
 // mod migration;
 // mod migrator;
+mod advise;
+mod bench;
 #[cfg(feature = "completions")]
 mod completions;
+mod config;
+#[cfg(unix)]
+mod daemon;
+mod lint;
 mod migrate;
 mod opt;
 mod prepare;
+mod tolerance;
 
 pub use crate::opt::Opt;
 
@@ -29,36 +38,73 @@ pub async fn run(opt: Opt) -> Result<()> {
                 reversible,
                 sequential,
                 timestamp,
-            } => migrate::add(&source, &description, reversible, sequential, timestamp).await?,
+                from_diff,
+            } => {
+                migrate::add(
+                    &source,
+                    &description,
+                    reversible,
+                    sequential,
+                    timestamp,
+                    from_diff.as_deref(),
+                )
+                .await?
+            }
+            MigrateCommand::Renumber {
+                source,
+                versions,
+                opts,
+                connect_opts,
+            } => migrate::renumber(&source, &versions, &connect_opts, &opts).await?,
+            MigrateCommand::Rebase {
+                source,
+                opts,
+                connect_opts,
+            } => migrate::rebase(&source, &connect_opts, &opts).await?,
             MigrateCommand::Run {
+                source,
+                from_archive,
+                connect_opts,
+                opts,
+            } => {
+                let migrations = match &from_archive {
+                    Some(archive) => migrate::Migrations::Archive(archive),
+                    None => migrate::Migrations::Source(&source),
+                };
+
+                migrate::run(migrations, &connect_opts, &opts).await?
+            }
+            MigrateCommand::Revert {
                 source,
                 dry_run,
                 ignore_missing,
                 connect_opts,
                 target_version,
+                verbosity,
             } => {
-                migrate::run(
+                migrate::revert(
                     &source,
                     &connect_opts,
                     dry_run,
                     *ignore_missing,
                     target_version,
+                    verbosity,
                 )
                 .await?
             }
-            MigrateCommand::Revert {
+            MigrateCommand::Redo {
                 source,
-                dry_run,
                 ignore_missing,
                 connect_opts,
                 target_version,
+                verbosity,
             } => {
-                migrate::revert(
+                migrate::redo(
                     &source,
                     &connect_opts,
-                    dry_run,
                     *ignore_missing,
                     target_version,
+                    verbosity,
                 )
                 .await?
             }
@@ -66,20 +112,61 @@ pub async fn run(opt: Opt) -> Result<()> {
                 source,
                 connect_opts,
             } => migrate::info(&source, &connect_opts).await?,
-            MigrateCommand::BuildScript { source, force } => migrate::build_script(&source, force)?,
+            MigrateCommand::Status {
+                source,
+                connect_opts,
+                exit_code,
+                verbose,
+            } => migrate::status(&source, &connect_opts, exit_code, verbose).await?,
+            MigrateCommand::History { connect_opts } => migrate::history(&connect_opts).await?,
+            MigrateCommand::Fmt {
+                source,
+                connect_opts,
+                check,
+                dialect,
+                since,
+                include_applied,
+                indent,
+                uppercase,
+            } => {
+                migrate::fmt(
+                    &source,
+                    &connect_opts,
+                    check,
+                    dialect,
+                    since,
+                    include_applied,
+                    &FormatOptions { indent, uppercase },
+                )
+                .await?
+            }
+            MigrateCommand::Advise {
+                source,
+                dialect,
+                server_version,
+                since,
+            } => migrate::advise(&source, dialect, server_version.as_deref(), since).await?,
+            MigrateCommand::BuildScript {
+                source,
+                watch,
+                validate,
+                force,
+            } => migrate::build_script(&source, &watch, validate, force)?,
         },
 
         Command::Database(database) => match database.command {
             DatabaseCommand::Create { connect_opts } => database::create(&connect_opts).await?,
             DatabaseCommand::Drop {
                 confirmation,
+                dry_run,
                 connect_opts,
-            } => database::drop(&connect_opts, !confirmation.yes).await?,
+            } => database::drop(&connect_opts, !confirmation.yes, dry_run).await?,
             DatabaseCommand::Reset {
                 confirmation,
+                dry_run,
                 source,
                 connect_opts,
-            } => database::reset(&source, &connect_opts, !confirmation.yes).await?,
+            } => database::reset(&source, &connect_opts, !confirmation.yes, dry_run).await?,
             DatabaseCommand::Setup {
                 source,
                 connect_opts,
@@ -88,10 +175,46 @@ pub async fn run(opt: Opt) -> Result<()> {
 
         Command::Prepare {
             check,
+            json,
             workspace,
+            tolerance,
+            normalize,
+            targets,
+            find,
+            daemon,
+            manifest_out,
             connect_opts,
             args,
-        } => prepare::run(check, workspace, connect_opts, args).await?,
+        } => {
+            if let Some(sql) = find {
+                prepare::find(&sql)?
+            } else if normalize {
+                prepare::normalize(workspace)?
+            } else {
+                prepare::run(
+                    check,
+                    json,
+                    workspace,
+                    tolerance,
+                    &targets,
+                    daemon,
+                    manifest_out,
+                    connect_opts,
+                    args,
+                )
+                .await?
+            }
+        }
+
+        #[cfg(unix)]
+        Command::DescribeDaemon { socket } => daemon::run(&socket).await?,
+
+        Command::Bench {
+            against,
+            save,
+            threshold,
+            args,
+        } => bench::run(against, save, threshold, args)?,
 
         #[cfg(feature = "completions")]
         Command::Completions { shell } => completions::run(shell),
@@ -100,6 +223,35 @@ pub async fn run(opt: Opt) -> Result<()> {
     Ok(())
 }
 
+/// Ask a yes or no question, returning `None` if stdin isn't a terminal to ask on.
+fn ask_yes_no(question: &str) -> Option<bool> {
+    if !io::stdin().is_terminal() {
+        return None;
+    }
+
+    loop {
+        let r: Result<String, ReadlineError> = prompt(format!("{question} (y/n)"));
+        match r {
+            Ok(response) => {
+                if response == "n" || response == "N" {
+                    return Some(false);
+                } else if response == "y" || response == "Y" {
+                    return Some(true);
+                } else {
+                    println!(
+                        "Response not recognized: {}\nPlease type 'y' or 'n' and press enter.",
+                        response
+                    );
+                }
+            }
+            Err(e) => {
+                println!("{e}");
+                return Some(false);
+            }
+        }
+    }
+}
+
 /// Attempt to connect to the database server, retrying up to `ops.connect_timeout`.
 async fn connect(opts: &ConnectOpts) -> sqlx::Result<AnyConnection> {
     retry_connect_errors(opts, AnyConnection::connect).await
@@ -108,7 +260,7 @@ async fn connect(opts: &ConnectOpts) -> sqlx::Result<AnyConnection> {
 /// Attempt an operation that may return errors like `ConnectionRefused`,
 /// retrying up until `ops.connect_timeout`.
 ///
-/// The closure is passed `&ops.database_url` for easy composition.
+/// The closure is passed `ops.database_url()` for easy composition.
 async fn retry_connect_errors<'a, F, Fut, T>(
     opts: &'a ConnectOpts,
     mut connect: F,
@@ -119,12 +271,16 @@ where
 {
     sqlx::any::install_default_drivers();
 
+    let database_url = opts
+        .database_url()
+        .map_err(|e| sqlx::Error::Configuration(e.into()))?;
+
     backoff::future::retry(
         backoff::ExponentialBackoffBuilder::new()
             .with_max_elapsed_time(Some(Duration::from_secs(opts.connect_timeout)))
             .build(),
         || {
-            connect(&opts.database_url).map_err(|e| -> backoff::Error<sqlx::Error> {
+            connect(database_url).map_err(|e| -> backoff::Error<sqlx::Error> {
                 match e {
                     sqlx::Error::Io(ref ioe) => match ioe.kind() {
                         io::ErrorKind::ConnectionRefused
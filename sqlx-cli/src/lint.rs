@@ -0,0 +1,695 @@
+//! Formatting, linting and inverting of migration files, for `sqlx migrate fmt` and
+//! `sqlx migrate add --from-diff`.
+
+use std::fmt;
+use std::ops::Range;
+
+/// The SQL dialect of the migrations, which determines how they are split into statements,
+/// which statements can be formatted safely, and which lints apply.
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Dialect {
+    Generic,
+    Postgres,
+    #[clap(name = "mysql")]
+    MySql,
+    Sqlite,
+}
+
+impl Dialect {
+    /// Infer the dialect from the scheme of a database URL.
+    pub fn from_url(url: &str) -> Self {
+        match url.split(':').next() {
+            Some("postgres" | "postgresql") => Dialect::Postgres,
+            Some("mysql" | "mariadb") => Dialect::MySql,
+            Some("sqlite") => Dialect::Sqlite,
+            _ => Dialect::Generic,
+        }
+    }
+}
+
+/// A lint for an `.up.sql` migration without a matching `.down.sql` migration.
+pub const MISSING_DOWN: &str = "missing-down";
+
+/// A lint for `DROP TABLE` or `DROP COLUMN` without `IF EXISTS`.
+pub const DROP_WITHOUT_GUARD: &str = "drop-without-guard";
+
+/// A lint for `CREATE INDEX` on an existing table without `CONCURRENTLY` (Postgres only).
+pub const NON_CONCURRENT_INDEX: &str = "non-concurrent-index";
+
+/// A lint warning for a migration.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Warning {
+    pub lint: &'static str,
+    pub message: String,
+}
+
+impl Warning {
+    fn new(lint: &'static str, message: String) -> Self {
+        Warning { lint, message }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}; silence with `-- sqlx:allow({})` [{}]",
+            self.message, self.lint, self.lint
+        )
+    }
+}
+
+/// Returns `true` if `sql` contains `-- sqlx:allow(<lint>)`, e.g. in a comment.
+pub fn is_allowed(sql: &str, lint: &str) -> bool {
+    sql.contains(&format!("sqlx:allow({lint})"))
+}
+
+#[derive(Debug)]
+pub struct FormatOptions {
+    pub indent: u8,
+    pub uppercase: bool,
+}
+
+/// Format each statement in `sql`, leaving any the formatter can't handle for `dialect` as-is.
+pub fn format(sql: &str, dialect: Dialect, options: &FormatOptions) -> String {
+    // MySQL clients change the delimiter to define procedures and triggers,
+    // which would have to be understood to find where the statements end.
+    if dialect == Dialect::MySql
+        && sql
+            .lines()
+            .any(|line| line.trim_start().to_lowercase().starts_with("delimiter "))
+    {
+        return sql.to_owned();
+    }
+
+    let format_options = sqlformat::FormatOptions {
+        indent: sqlformat::Indent::Spaces(options.indent),
+        uppercase: options.uppercase,
+        lines_between_queries: 1,
+    };
+
+    let mut formatted = String::new();
+
+    for statement in split(sql, dialect) {
+        let text = statement.text.trim();
+
+        if text.is_empty() || text == ";" {
+            continue;
+        }
+
+        if !formatted.is_empty() {
+            formatted.push_str("\n\n");
+        }
+
+        if statement.verbatim {
+            formatted.push_str(text);
+        } else {
+            let text = sqlformat::format(text, &sqlformat::QueryParams::None, format_options);
+            formatted.push_str(text.trim());
+        }
+    }
+
+    formatted.push('\n');
+    formatted
+}
+
+/// Check the SQL of a single migration file against the statement-level lints.
+pub fn lint(sql: &str, dialect: Dialect) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let mut created_tables = Vec::new();
+
+    for statement in split(sql, dialect) {
+        let words = statement.words;
+
+        for (i, word) in words.iter().enumerate() {
+            let next = |n: usize| words.get(i + n).map_or("", String::as_str);
+
+            match (word.as_str(), next(1)) {
+                ("CREATE", "TABLE") => {
+                    let name = if next(2) == "IF" { next(5) } else { next(2) };
+                    created_tables.push(unqualified(name));
+                }
+
+                ("DROP", kind @ ("TABLE" | "COLUMN"))
+                    if next(2) != "IF" && !is_allowed(sql, DROP_WITHOUT_GUARD) =>
+                {
+                    warnings.push(Warning::new(
+                        DROP_WITHOUT_GUARD,
+                        format!(
+                            "`DROP {kind} {}` without `IF EXISTS` fails if it was already \
+                             dropped, and deletes its data for good",
+                            next(2).to_lowercase()
+                        ),
+                    ));
+                }
+
+                ("CREATE", "INDEX") | ("UNIQUE", "INDEX") if dialect == Dialect::Postgres => {
+                    if next(2) == "CONCURRENTLY" || is_allowed(sql, NON_CONCURRENT_INDEX) {
+                        continue;
+                    }
+
+                    let mut after_on = words[i..].iter().skip_while(|word| *word != "ON").skip(1);
+
+                    let table = match after_on.next() {
+                        Some(word) if word == "ONLY" => after_on.next(),
+                        table => table,
+                    };
+
+                    let Some(table) = table.map(|table| unqualified(table)) else {
+                        continue;
+                    };
+
+                    // a table created in the same migration is empty, so it's locked only briefly
+                    if created_tables.contains(&table) {
+                        continue;
+                    }
+
+                    warnings.push(Warning::new(
+                        NON_CONCURRENT_INDEX,
+                        format!(
+                            "`CREATE INDEX` blocks writes to `{table}` until the index is built, \
+                             which may take a long time for a large table; \
+                             consider `CREATE INDEX CONCURRENTLY` outside of a migration"
+                        ),
+                    ));
+                }
+
+                _ => (),
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Find the statements in `sql` that delete data: `DROP TABLE`, `DROP COLUMN` and `TRUNCATE`,
+/// returning a short description of each, e.g. `DROP TABLE posts`.
+pub fn destructive_statements(sql: &str, dialect: Dialect) -> Vec<String> {
+    let mut found = Vec::new();
+
+    for statement in split(sql, dialect) {
+        let words = statement.words;
+
+        for (i, word) in words.iter().enumerate() {
+            let next = |n: usize| words.get(i + n).map_or("", String::as_str);
+
+            let description = match (word.as_str(), next(1)) {
+                ("DROP", kind @ ("TABLE" | "COLUMN")) => {
+                    let name = if next(2) == "IF" { next(4) } else { next(2) };
+                    format!("DROP {kind} {}", name.to_lowercase())
+                }
+                ("TRUNCATE", "TABLE") => format!("TRUNCATE {}", next(2).to_lowercase()),
+                // `TRUNCATE` is only a keyword at the start of a statement
+                ("TRUNCATE", name) if i == 0 => format!("TRUNCATE {}", name.to_lowercase()),
+                _ => continue,
+            };
+
+            found.push(description);
+        }
+    }
+
+    found
+}
+
+/// Draft a down migration for the up migration `sql`, undoing `CREATE TABLE`, `CREATE INDEX`
+/// and `ALTER TABLE ... ADD COLUMN` statements in reverse order, with a `TODO` comment in place of
+/// any other statement.
+pub fn invert(sql: &str, dialect: Dialect) -> String {
+    let mut statements: Vec<String> = split(sql, dialect)
+        .iter()
+        .filter(|statement| !statement.words.is_empty())
+        .map(|statement| {
+            invert_statement(statement, dialect).unwrap_or_else(|| {
+                let text: String = statement
+                    .text
+                    .trim()
+                    .lines()
+                    .map(|line| format!("\n-- {line}"))
+                    .collect();
+
+                format!("-- TODO: undo this statement from the up migration:{text}")
+            })
+        })
+        .collect();
+
+    statements.reverse();
+
+    let mut down =
+        String::from("-- Generated from the up migration; check it before running it.\n");
+
+    for statement in statements {
+        down.push('\n');
+        down.push_str(&statement);
+        down.push('\n');
+    }
+
+    down
+}
+
+fn invert_statement(statement: &Statement<'_>, dialect: Dialect) -> Option<String> {
+    let mut cursor = Cursor {
+        words: &statement.words,
+        raw_words: &statement.raw_words,
+        pos: 0,
+    };
+
+    if cursor.eat(&["CREATE", "TABLE"]) {
+        cursor.eat(&["IF", "NOT", "EXISTS"]);
+        let table = cursor.ident()?;
+
+        return Some(format!("DROP TABLE {table};"));
+    }
+
+    if cursor.eat(&["CREATE"]) {
+        cursor.eat(&["UNIQUE"]);
+
+        if !cursor.eat(&["INDEX"]) {
+            return None;
+        }
+
+        let concurrently = cursor.eat(&["CONCURRENTLY"]);
+        cursor.eat(&["IF", "NOT", "EXISTS"]);
+
+        // Postgres generates a name if there isn't one
+        if cursor.peek() == Some("ON") {
+            return None;
+        }
+
+        let index = cursor.ident()?;
+
+        return Some(match dialect {
+            Dialect::MySql => {
+                cursor.eat(&["ON"]).then_some(())?;
+                format!("DROP INDEX {index} ON {};", cursor.ident()?)
+            }
+            Dialect::Postgres if concurrently => format!("DROP INDEX CONCURRENTLY {index};"),
+            _ => format!("DROP INDEX {index};"),
+        });
+    }
+
+    if cursor.eat(&["ALTER", "TABLE"]) {
+        cursor.eat(&["IF", "EXISTS"]);
+        cursor.eat(&["ONLY"]);
+        let table = cursor.ident()?;
+
+        // anything but adding columns is too hard to undo reliably
+        let rest = &statement.words[cursor.pos..];
+        if rest.first().map(String::as_str) != Some("ADD")
+            || rest.iter().any(|word| {
+                matches!(
+                    word.as_str(),
+                    "DROP" | "RENAME" | "ALTER" | "MODIFY" | "CHANGE"
+                )
+            })
+        {
+            return None;
+        }
+
+        let mut columns = Vec::new();
+
+        while cursor.pos < statement.words.len() {
+            if !cursor.eat(&["ADD"]) {
+                cursor.pos += 1;
+                continue;
+            }
+
+            cursor.eat(&["COLUMN"]);
+            cursor.eat(&["IF", "NOT", "EXISTS"]);
+
+            if matches!(
+                cursor.peek()?,
+                "CONSTRAINT"
+                    | "INDEX"
+                    | "KEY"
+                    | "PRIMARY"
+                    | "UNIQUE"
+                    | "FOREIGN"
+                    | "CHECK"
+                    | "FULLTEXT"
+                    | "SPATIAL"
+                    | "PARTITION"
+            ) {
+                return None;
+            }
+
+            columns.push(cursor.ident()?);
+        }
+
+        let drops: Vec<String> = columns
+            .iter()
+            .rev()
+            .map(|column| format!("ALTER TABLE {table} DROP COLUMN {column};"))
+            .collect();
+
+        return Some(drops.join("\n"));
+    }
+
+    None
+}
+
+struct Cursor<'s, 'a> {
+    words: &'s [String],
+    raw_words: &'s [&'a str],
+    pos: usize,
+}
+
+impl<'a> Cursor<'_, 'a> {
+    /// Skip `keywords` if they're next.
+    fn eat(&mut self, keywords: &[&str]) -> bool {
+        let next = self.words.get(self.pos..self.pos + keywords.len());
+
+        let matches = next.is_some_and(|next| next.iter().zip(keywords).all(|(a, b)| a == b));
+
+        if matches {
+            self.pos += keywords.len();
+        }
+
+        matches
+    }
+
+    fn peek(&self) -> Option<&str> {
+        self.words.get(self.pos).map(String::as_str)
+    }
+
+    /// Take the next word as it appears in the SQL, e.g. an identifier with its quotes.
+    fn ident(&mut self) -> Option<&'a str> {
+        let word = self.raw_words.get(self.pos)?;
+        self.pos += 1;
+        Some(word)
+    }
+}
+
+/// Check that the `.up.sql` migrations in `file_names` each have a `.down.sql` migration,
+/// returning the up migrations that don't.
+pub fn missing_down(file_names: &[String]) -> Vec<&str> {
+    file_names
+        .iter()
+        .filter_map(|name| {
+            let stem = name.strip_suffix(".up.sql")?;
+            let down = format!("{stem}.down.sql");
+
+            (!file_names.contains(&down)).then_some(name.as_str())
+        })
+        .collect()
+}
+
+/// Strip the schema qualifier and lowercase an identifier.
+fn unqualified(ident: &str) -> String {
+    ident.rsplit('.').next().unwrap_or(ident).to_lowercase()
+}
+
+pub struct Statement<'a> {
+    /// Including the terminating semicolon, if any, and preceding comments.
+    pub text: &'a str,
+    /// The keywords and identifiers, with keywords uppercased and quotes removed.
+    pub words: Vec<String>,
+    /// The same words as they appear in `text`, including quotes.
+    pub raw_words: Vec<&'a str>,
+    /// The statement contains syntax the formatter doesn't understand.
+    pub verbatim: bool,
+}
+
+/// Split `sql` into statements, skipping semicolons in strings, quoted identifiers and comments.
+pub fn split(sql: &str, dialect: Dialect) -> Vec<Statement<'_>> {
+    let bytes = sql.as_bytes();
+
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut words = Vec::new();
+    let mut spans = Vec::new();
+    let mut verbatim = false;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let rest = &sql[i..];
+
+        match bytes[i] {
+            b'\'' => i += quoted_len(rest, b'\'', dialect == Dialect::MySql),
+
+            quote @ (b'"' | b'`') => {
+                let len = quoted_len(rest, quote, false);
+                let word = rest.get(1..len - 1).unwrap_or_default().to_lowercase();
+                push_word(&mut words, &mut spans, sql, i..i + len, word);
+                i += len;
+            }
+
+            b'-' if rest.starts_with("--") => i += rest.find('\n').unwrap_or(rest.len()),
+
+            // the formatter doesn't recognize these comments
+            b'#' if dialect == Dialect::MySql => {
+                verbatim = true;
+                i += rest.find('\n').unwrap_or(rest.len());
+            }
+
+            b'/' if rest.starts_with("/*") => {
+                i += rest[2..].find("*/").map_or(rest.len(), |end| end + 4);
+            }
+
+            // e.g. `$$ ... $$` or `$body$ ... $body$`; the formatter splits function bodies
+            // into lines as if they were statements
+            b'$' if dialect != Dialect::MySql => match dollar_quoted_len(rest) {
+                Some(len) => {
+                    verbatim = true;
+                    i += len;
+                }
+                None => i += 1,
+            },
+
+            b';' => {
+                i += 1;
+
+                statements.push(Statement {
+                    text: &sql[start..i],
+                    words: std::mem::take(&mut words),
+                    raw_words: raw_words(sql, std::mem::take(&mut spans)),
+                    verbatim,
+                });
+
+                start = i;
+                verbatim = false;
+            }
+
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                let len = rest
+                    .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == '$'))
+                    .unwrap_or(rest.len());
+
+                let word = rest[..len].to_uppercase();
+                push_word(&mut words, &mut spans, sql, i..i + len, word);
+                i += len;
+            }
+
+            _ => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+
+    if start < sql.len() {
+        statements.push(Statement {
+            text: &sql[start..],
+            words,
+            raw_words: raw_words(sql, spans),
+            verbatim,
+        });
+    }
+
+    statements
+}
+
+/// Add a word found at `span` of `sql`, joining it to the previous word if it's part of
+/// the same qualified name, e.g. `"public"."users"`.
+fn push_word(
+    words: &mut Vec<String>,
+    spans: &mut Vec<Range<usize>>,
+    sql: &str,
+    span: Range<usize>,
+    word: String,
+) {
+    if let (Some(last), Some(last_span)) = (words.last_mut(), spans.last_mut()) {
+        let between = &sql[last_span.end..span.start];
+
+        if between == "." || (between.is_empty() && last.ends_with('.')) {
+            if !last.ends_with('.') {
+                last.push('.');
+            }
+
+            last.push_str(&word);
+            last_span.end = span.end;
+            return;
+        }
+    }
+
+    words.push(word);
+    spans.push(span);
+}
+
+fn raw_words(sql: &str, spans: Vec<Range<usize>>) -> Vec<&str> {
+    spans.into_iter().map(|span| &sql[span]).collect()
+}
+
+/// The length of the string or identifier at the start of `s` quoted by `quote`,
+/// including the quotes, or the rest of `s` if it's unterminated.
+fn quoted_len(s: &str, quote: u8, backslash_escapes: bool) -> usize {
+    let bytes = s.as_bytes();
+    let mut i = 1;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if backslash_escapes => i += 2,
+            // a doubled quote is an escaped quote
+            c if c == quote && bytes.get(i + 1) == Some(&quote) => i += 2,
+            c if c == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+
+    bytes.len()
+}
+
+/// The length of the dollar-quoted string at the start of `s`, if it is one.
+fn dollar_quoted_len(s: &str) -> Option<usize> {
+    let tag_len = s[1..].find(|c: char| !(c.is_alphanumeric() || c == '_'))? + 2;
+
+    if !s[tag_len - 1..].starts_with('$') {
+        // a positional parameter, e.g. `$1`
+        return None;
+    }
+
+    let tag = &s[..tag_len];
+
+    if tag[1..tag_len - 1].starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let end = s[tag_len..]
+        .find(tag)
+        .map_or(s.len(), |end| tag_len + end + tag_len);
+
+    Some(end)
+}
+
+#[test]
+fn test_lint() {
+    let lints = |sql| {
+        lint(sql, Dialect::Postgres)
+            .into_iter()
+            .map(|warning| warning.lint)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        lints("ALTER TABLE users DROP COLUMN name; DROP TABLE posts;"),
+        [DROP_WITHOUT_GUARD, DROP_WITHOUT_GUARD]
+    );
+    assert!(
+        lints("ALTER TABLE users DROP COLUMN IF EXISTS name; DROP TABLE IF EXISTS p;").is_empty()
+    );
+    assert!(lints("-- sqlx:allow(drop-without-guard)\nDROP TABLE posts;").is_empty());
+
+    assert_eq!(
+        lints("CREATE UNIQUE INDEX users_email ON public.users (email);"),
+        [NON_CONCURRENT_INDEX]
+    );
+    assert!(lints("CREATE INDEX CONCURRENTLY users_email ON users (email);").is_empty());
+    assert!(lints(
+        "CREATE TABLE IF NOT EXISTS \"users\" (email text); CREATE INDEX users_email ON users (email);"
+    )
+    .is_empty());
+    assert!(lint(
+        "CREATE INDEX users_email ON users (email);",
+        Dialect::Sqlite
+    )
+    .is_empty());
+
+    // not SQL
+    assert!(
+        lints("INSERT INTO notes (body) VALUES ('DROP TABLE users;'); -- DROP TABLE posts")
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_destructive_statements() {
+    let check = |sql| destructive_statements(sql, Dialect::Postgres);
+
+    assert_eq!(
+        check(
+            "ALTER TABLE users DROP COLUMN IF EXISTS name; DROP TABLE public.posts; \
+             TRUNCATE TABLE sessions; truncate events;"
+        ),
+        [
+            "DROP COLUMN name",
+            "DROP TABLE public.posts",
+            "TRUNCATE sessions",
+            "TRUNCATE events"
+        ]
+    );
+    assert!(check("CREATE TABLE users (id bigint); DROP INDEX users_email;").is_empty());
+
+    // not SQL
+    assert!(
+        check("INSERT INTO notes (body) VALUES ('TRUNCATE users;'); -- DROP TABLE posts")
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_invert() {
+    let up = "-- Add posts\n\
+              CREATE TABLE IF NOT EXISTS \"Posts\" (id bigint PRIMARY KEY, body text);\n\
+              CREATE UNIQUE INDEX posts_body ON public.\"Posts\" (body);\n\
+              ALTER TABLE users ADD COLUMN name text NOT NULL DEFAULT '', ADD email text;\n\
+              INSERT INTO posts (body) VALUES ('hello');\n";
+
+    assert_eq!(
+        invert(up, Dialect::Postgres),
+        "-- Generated from the up migration; check it before running it.\n\n\
+         -- TODO: undo this statement from the up migration:\n\
+         -- INSERT INTO posts (body) VALUES ('hello');\n\n\
+         ALTER TABLE users DROP COLUMN email;\n\
+         ALTER TABLE users DROP COLUMN name;\n\n\
+         DROP INDEX posts_body;\n\n\
+         DROP TABLE \"Posts\";\n"
+    );
+
+    assert!(invert(
+        "CREATE INDEX users_email ON `users` (email);",
+        Dialect::MySql
+    )
+    .contains("DROP INDEX users_email ON `users`;"));
+    assert!(invert(
+        "ALTER TABLE users ADD CONSTRAINT email_unique UNIQUE (email);",
+        Dialect::Postgres
+    )
+    .contains("TODO"));
+}
+
+#[test]
+fn test_format() {
+    let options = FormatOptions {
+        indent: 4,
+        uppercase: true,
+    };
+
+    let sql = "-- Add users\ncreate table users (id bigint primary key, name text);\n\
+               CREATE FUNCTION f() RETURNS trigger AS $$ begin return new; end; $$ LANGUAGE plpgsql;";
+
+    let formatted = format(sql, Dialect::Postgres, &options);
+
+    assert_eq!(
+        formatted,
+        "-- Add users\nCREATE TABLE users (id bigint PRIMARY KEY, name text);\n\n\
+         CREATE FUNCTION f() RETURNS trigger AS $$ begin return new; end; $$ LANGUAGE plpgsql;\n"
+    );
+    assert_eq!(format(&formatted, Dialect::Postgres, &options), formatted);
+}
+
+#[test]
+fn test_missing_down() {
+    let file_names = [
+        "1_users.up.sql".to_owned(),
+        "1_users.down.sql".to_owned(),
+        "2_posts.up.sql".to_owned(),
+    ];
+
+    assert_eq!(missing_down(&file_names), ["2_posts.up.sql"]);
+}
@@ -1,13 +1,21 @@
-use crate::opt::ConnectOpts;
+use crate::advise;
+use crate::config::Config;
+use crate::lint::{self, Dialect, FormatOptions, Warning};
+use crate::opt::{ConnectOpts, RenumberOpts, RunOpts, Verbosity};
 use anyhow::{bail, Context};
-use chrono::Utc;
+use chrono::{DateTime, Local, Utc};
 use console::style;
-use sqlx::migrate::{AppliedMigration, Migrate, MigrateError, MigrationType, Migrator};
-use sqlx::Connection;
+use sqlx::migrate::{
+    AppliedMigration, AuditInfo, Migrate, MigrateError, Migration, MigrationLogEntry,
+    MigrationType, Migrator,
+};
+use sqlx::{AnyConnection, Connection};
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::cmp;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::Write;
 use std::fs::{self, File};
+use std::io::{self, Read};
 use std::path::Path;
 use std::time::Duration;
 
@@ -16,6 +24,7 @@ fn create_file(
     file_prefix: &str,
     description: &str,
     migration_type: MigrationType,
+    content: Option<&str>,
 ) -> anyhow::Result<()> {
     use std::path::PathBuf;
 
@@ -32,7 +41,8 @@ fn create_file(
 
     let mut file = File::create(&path).context("Failed to create migration file")?;
 
-    std::io::Write::write_all(&mut file, migration_type.file_content().as_bytes())?;
+    let content = content.unwrap_or_else(|| migration_type.file_content());
+    std::io::Write::write_all(&mut file, content.as_bytes())?;
 
     Ok(())
 }
@@ -48,7 +58,14 @@ impl MigrationOrdering {
     }
 
     fn sequential(version: i64) -> MigrationOrdering {
-        Self::Sequential(format!("{version:04}"))
+        Self::Sequential(Self::format_version(true, version))
+    }
+
+    fn format_version(sequential: bool, version: i64) -> String {
+        match sequential {
+            true => format!("{version:04}"),
+            false => version.to_string(),
+        }
     }
 
     fn file_prefix(&self) -> &str {
@@ -58,37 +75,30 @@ impl MigrationOrdering {
         }
     }
 
-    fn infer(sequential: bool, timestamp: bool, migrator: &Migrator) -> Self {
+    /// Infers the ordering from the versions of the up migrations, in order.
+    fn infer(sequential: bool, timestamp: bool, versions: &[i64]) -> Self {
         match (timestamp, sequential) {
             (true, true) => panic!("Impossible to specify both timestamp and sequential mode"),
             (true, false) => MigrationOrdering::timestamp(),
-            (false, true) => MigrationOrdering::sequential(
-                migrator
-                    .iter()
-                    .last()
-                    .map_or(1, |last_migration| last_migration.version + 1),
-            ),
+            (false, true) => {
+                MigrationOrdering::sequential(versions.last().map_or(1, |last| last + 1))
+            }
             (false, false) => {
                 // inferring the naming scheme
-                let migrations = migrator
-                    .iter()
-                    .filter(|migration| migration.migration_type.is_up_migration())
-                    .rev()
-                    .take(2)
-                    .collect::<Vec<_>>();
+                let migrations = versions.iter().rev().take(2).collect::<Vec<_>>();
                 if let [last, pre_last] = &migrations[..] {
                     // there are at least two migrations, compare the last twothere's only one existing migration
-                    if last.version - pre_last.version == 1 {
+                    if *last - *pre_last == 1 {
                         // their version numbers differ by 1, infer sequential
-                        MigrationOrdering::sequential(last.version + 1)
+                        MigrationOrdering::sequential(*last + 1)
                     } else {
                         MigrationOrdering::timestamp()
                     }
                 } else if let [last] = &migrations[..] {
                     // there is only one existing migration
-                    if last.version == 0 || last.version == 1 {
+                    if **last == 0 || **last == 1 {
                         // infer sequential if the version number is 0 or 1
-                        MigrationOrdering::sequential(last.version + 1)
+                        MigrationOrdering::sequential(*last + 1)
                     } else {
                         MigrationOrdering::timestamp()
                     }
@@ -106,7 +116,14 @@ pub async fn add(
     reversible: bool,
     sequential: bool,
     timestamp: bool,
+    from_diff: Option<&Path>,
 ) -> anyhow::Result<()> {
+    let up_sql = from_diff
+        .map(|path| {
+            fs::read_to_string(path).with_context(|| format!("Unable to read {}", path.display()))
+        })
+        .transpose()?;
+
     fs::create_dir_all(migration_source).context("Unable to create migrations directory")?;
 
     // if the migrations directory is empty
@@ -115,32 +132,66 @@ pub async fn add(
         .unwrap_or(false);
 
     let migrator = Migrator::new(Path::new(migration_source)).await?;
+    warn_version_conflicts(&migrator);
+
     // Type of newly created migration will be the same as the first one
     // or reversible flag if this is the first migration
     let migration_type = MigrationType::infer(&migrator, reversible);
 
-    let ordering = MigrationOrdering::infer(sequential, timestamp, &migrator);
+    let versions: Vec<_> = migrator
+        .iter()
+        .filter(|migration| migration.migration_type.is_up_migration())
+        .map(|migration| migration.version)
+        .collect();
+
+    let ordering = MigrationOrdering::infer(sequential, timestamp, &versions);
     let file_prefix = ordering.file_prefix();
 
     if migration_type.is_reversible() {
+        let down_sql = up_sql.as_deref().map(|up_sql| {
+            let dialect = std::env::var("DATABASE_URL")
+                .map_or(Dialect::Generic, |url| Dialect::from_url(&url));
+
+            lint::invert(up_sql, dialect)
+        });
+
         create_file(
             migration_source,
             &file_prefix,
             description,
             MigrationType::ReversibleUp,
+            up_sql.as_deref(),
         )?;
         create_file(
             migration_source,
             &file_prefix,
             description,
             MigrationType::ReversibleDown,
+            down_sql.as_deref(),
         )?;
+
+        if down_sql.is_some_and(|down_sql| down_sql.contains("-- TODO")) {
+            println!(
+                "{}",
+                style("Some statements couldn't be undone automatically; see the TODOs in the down migration")
+                    .yellow()
+            );
+        }
     } else {
+        if up_sql.is_some() {
+            println!(
+                "{}",
+                style("Existing migrations are not reversible, so no down migration was generated")
+                    .yellow()
+            );
+        }
+
         create_file(
             migration_source,
             &file_prefix,
             description,
             MigrationType::Simple,
+            up_sql.as_deref(),
         )?;
     }
 
@@ -172,6 +223,344 @@ See: https://docs.rs/sqlx/0.5/sqlx/macro.migrate.html
     Ok(())
 }
 
+/// The versions shared by migrations with different descriptions, e.g. after merging branches
+/// that each added a migration, with the descriptions in order.
+fn version_conflicts<'a>(
+    migrations: impl IntoIterator<Item = (i64, &'a str)>,
+) -> Vec<(i64, Vec<&'a str>)> {
+    let mut versions: BTreeMap<i64, Vec<&str>> = BTreeMap::new();
+
+    for (version, description) in migrations {
+        versions.entry(version).or_default().push(description);
+    }
+
+    versions
+        .into_iter()
+        .filter_map(|(version, mut descriptions)| {
+            descriptions.sort_unstable();
+            descriptions.dedup();
+            (descriptions.len() > 1).then_some((version, descriptions))
+        })
+        .collect()
+}
+
+fn warn_version_conflicts(migrator: &Migrator) {
+    let conflicts = version_conflicts(
+        migrator
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .map(|m| (m.version, &*m.description)),
+    );
+
+    for (version, descriptions) in &conflicts {
+        println!(
+            "{} migrations share version {}: {}",
+            style("warning:").bold().yellow(),
+            style(version).cyan(),
+            descriptions.join(", ")
+        );
+    }
+
+    if !conflicts.is_empty() {
+        println!("Give them distinct versions with `sqlx migrate renumber`");
+    }
+}
+
+/// The next `count` versions after `latest`, as file prefixes.
+fn next_versions(sequential: bool, latest: i64, count: usize) -> Vec<String> {
+    let now = Utc::now();
+    let mut version = latest;
+
+    (0..count)
+        .map(|i| {
+            version = if sequential {
+                version + 1
+            } else {
+                // a timestamp, unless the clock is behind the latest version
+                let timestamp = (now + chrono::Duration::seconds(i as i64))
+                    .format("%Y%m%d%H%M%S")
+                    .to_string()
+                    .parse()
+                    .unwrap_or(0);
+
+                cmp::max(timestamp, version + 1)
+            };
+
+            MigrationOrdering::format_version(sequential, version)
+        })
+        .collect()
+}
+
+// the version, description and name of each migration file, in order
+fn migration_files(migration_source: &str) -> anyhow::Result<Vec<(i64, String, String)>> {
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(migration_source)
+        .with_context(|| format!("Unable to read migrations directory {migration_source}"))?
+    {
+        let file_name = entry?.file_name().to_string_lossy().into_owned();
+
+        let Some((version, rest)) = file_name.split_once('_') else {
+            continue;
+        };
+
+        let (Ok(version), true) = (version.parse::<i64>(), rest.ends_with(".sql")) else {
+            continue;
+        };
+
+        let description = rest
+            .trim_end_matches(MigrationType::from_filename(rest).suffix())
+            .to_owned();
+
+        files.push((version, description, file_name));
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+/// Renames the migrations in `moved`, by version and description, to versions after those of
+/// the other migrations and `after`.
+fn move_migrations(
+    migration_source: &str,
+    files: &[(i64, String, String)],
+    moved: &BTreeSet<(i64, &str)>,
+    after: i64,
+    opts: &RenumberOpts,
+) -> anyhow::Result<()> {
+    // the versions that are kept, in order
+    let mut kept: Vec<_> = files
+        .iter()
+        .filter(|(version, description, _)| !moved.contains(&(*version, description.as_str())))
+        .map(|(version, _, _)| *version)
+        .collect();
+    kept.dedup();
+
+    let latest = cmp::max(kept.last().copied().unwrap_or(0), after);
+
+    let ordering = MigrationOrdering::infer(opts.sequential, opts.timestamp, &kept);
+    let sequential = matches!(ordering, MigrationOrdering::Sequential(_));
+    let new_versions: HashMap<_, _> = moved
+        .iter()
+        .zip(next_versions(sequential, latest, moved.len()))
+        .collect();
+
+    let mut mapping = Vec::new();
+
+    for (version, description, file_name) in files {
+        let Some(new_version) = new_versions.get(&(*version, description.as_str())) else {
+            continue;
+        };
+
+        let (_, rest) = file_name.split_once('_').unwrap_or_default();
+        let new_file_name = format!("{new_version}_{rest}");
+
+        println!(
+            "{} {} -> {}",
+            if opts.dry_run {
+                "Would rename"
+            } else {
+                "Renaming"
+            },
+            style(file_name).cyan(),
+            style(&new_file_name).cyan()
+        );
+
+        if !opts.dry_run {
+            let path = Path::new(migration_source);
+            fs::rename(path.join(file_name), path.join(&new_file_name))
+                .with_context(|| format!("Unable to rename {file_name}"))?;
+        }
+
+        // up and down migrations share an entry
+        if !file_name.ends_with(MigrationType::ReversibleDown.suffix()) {
+            mapping.push(serde_json::json!({
+                "description": description.replace('_', " "),
+                "old_version": version,
+                "new_version": new_version.parse::<i64>()?,
+            }));
+        }
+    }
+
+    if let (Some(path), false) = (&opts.mapping_file, opts.dry_run) {
+        fs::write(path, serde_json::to_string_pretty(&mapping)? + "\n")
+            .with_context(|| format!("Unable to write {}", path.display()))?;
+
+        println!("Wrote the new versions to {}", style(path.display()).cyan());
+    }
+
+    Ok(())
+}
+
+/// The migration files that were applied to the database, by version and description. Of the
+/// files sharing an applied version, the one whose checksum matches was applied, or the first by
+/// description if none does.
+fn applied_files<'a>(
+    migration_source: &str,
+    files: &'a [(i64, String, String)],
+    applied: &[AppliedMigration],
+) -> anyhow::Result<BTreeSet<(i64, &'a str)>> {
+    let mut applied_files = BTreeSet::new();
+
+    for migration in applied {
+        // down migrations aren't checksummed
+        let candidates: Vec<_> = files
+            .iter()
+            .filter(|(version, _, file_name)| {
+                *version == migration.version
+                    && !file_name.ends_with(MigrationType::ReversibleDown.suffix())
+            })
+            .collect();
+
+        let Some(&first) = candidates.first() else {
+            continue;
+        };
+
+        let mut file = first;
+
+        if candidates.len() > 1 {
+            for &candidate in &candidates {
+                let path = Path::new(migration_source).join(&candidate.2);
+                let sql = fs::read_to_string(&path)
+                    .with_context(|| format!("Unable to read {}", path.display()))?;
+                let checksum =
+                    Migration::new(0, "".into(), MigrationType::Simple, sql.into()).checksum;
+
+                if *checksum == *migration.checksum {
+                    file = candidate;
+                    break;
+                }
+            }
+        }
+
+        applied_files.insert((file.0, file.1.as_str()));
+    }
+
+    Ok(applied_files)
+}
+
+pub async fn renumber(
+    migration_source: &str,
+    versions: &[i64],
+    connect_opts: &ConnectOpts,
+    opts: &RenumberOpts,
+) -> anyhow::Result<()> {
+    let files = migration_files(migration_source)?;
+
+    let applied = if connect_opts.database_url().is_ok() {
+        let mut conn = crate::connect(connect_opts).await?;
+        let applied = applied_migrations(&mut conn).await?;
+        let _ = conn.close().await;
+        applied
+    } else {
+        println!(
+            "{}",
+            style("No database given, so migrations that were applied elsewhere may be renamed")
+                .yellow()
+        );
+        Vec::new()
+    };
+
+    let applied_files = applied_files(migration_source, &files, &applied)?;
+    let latest_applied = applied.iter().map(|m| m.version).max().unwrap_or(0);
+
+    // of the migrations sharing a version, the applied one keeps it, or else the first; the
+    // others are moved
+    let mut moved: BTreeSet<(i64, &str)> = version_conflicts(
+        files
+            .iter()
+            .map(|(version, description, _)| (*version, description.as_str())),
+    )
+    .into_iter()
+    .flat_map(|(version, descriptions)| {
+        let kept = descriptions
+            .iter()
+            .position(|&d| applied_files.contains(&(version, d)))
+            .unwrap_or(0);
+
+        descriptions
+            .into_iter()
+            .enumerate()
+            .filter(move |(i, _)| *i != kept)
+            .map(move |(_, d)| (version, d))
+    })
+    .collect();
+
+    for &version in versions {
+        if !files.iter().any(|(v, _, _)| *v == version) {
+            bail!(MigrateError::VersionNotPresent(version));
+        }
+
+        if applied_files.iter().any(|(v, _)| *v == version) {
+            bail!("migration {version} was applied to the database and can't be renumbered");
+        }
+
+        moved.extend(
+            files
+                .iter()
+                .filter(|(v, _, _)| *v == version)
+                .map(|(v, description, _)| (*v, description.as_str())),
+        );
+    }
+
+    if moved.is_empty() {
+        println!("No migrations to renumber");
+        return Ok(());
+    }
+
+    move_migrations(migration_source, &files, &moved, latest_applied, opts)?;
+
+    println!(
+        "{}",
+        style("Other databases that applied a renumbered migration will report it as missing")
+            .yellow()
+    );
+
+    Ok(())
+}
+
+pub async fn rebase(
+    migration_source: &str,
+    connect_opts: &ConnectOpts,
+    opts: &RenumberOpts,
+) -> anyhow::Result<()> {
+    let files = migration_files(migration_source)?;
+
+    let mut conn = crate::connect(connect_opts).await?;
+    let applied = applied_migrations(&mut conn).await?;
+    let _ = conn.close().await;
+
+    let applied_files = applied_files(migration_source, &files, &applied)?;
+    let latest_applied = applied.iter().map(|m| m.version).max().unwrap_or(0);
+    let moved = rebased_migrations(&files, &applied_files, latest_applied);
+
+    if moved.is_empty() {
+        println!(
+            "No pending migrations are older than the latest applied migration {}",
+            style(latest_applied).cyan()
+        );
+        return Ok(());
+    }
+
+    move_migrations(migration_source, &files, &moved, latest_applied, opts)
+}
+
+// the migrations that haven't been applied, but are older than the latest applied migration or
+// share a version with an applied one; applied migrations are never moved, so the database
+// stays consistent
+fn rebased_migrations<'a>(
+    files: &'a [(i64, String, String)],
+    applied_files: &BTreeSet<(i64, &str)>,
+    latest_applied: i64,
+) -> BTreeSet<(i64, &'a str)> {
+    files
+        .iter()
+        .map(|(version, description, _)| (*version, description.as_str()))
+        .filter(|file| file.0 <= latest_applied && !applied_files.contains(file))
+        .collect()
+}
+
 fn short_checksum(checksum: &[u8]) -> String {
     let mut s = String::with_capacity(checksum.len() * 2);
     for b in checksum {
@@ -180,6 +569,39 @@ fn short_checksum(checksum: &[u8]) -> String {
     s
 }
 
+/// Whether the migrations table exists, for commands that shouldn't change the database by
+/// creating it.
+async fn migrations_table_exists(conn: &mut AnyConnection) -> anyhow::Result<bool> {
+    let sql = match conn.backend_name() {
+        "PostgreSQL" => "SELECT COUNT(to_regclass('_sqlx_migrations'))",
+        "MySQL" => {
+            "SELECT COUNT(*) FROM information_schema.tables \
+             WHERE table_schema = DATABASE() AND table_name = '_sqlx_migrations'"
+        }
+        "SQLite" => {
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations'"
+        }
+        // the table is created for any other backend
+        _ => {
+            conn.ensure_migrations_table().await?;
+            return Ok(true);
+        }
+    };
+
+    let exists: i64 = sqlx::query_scalar(sql).fetch_one(&mut *conn).await?;
+
+    Ok(exists > 0)
+}
+
+/// The migrations applied to the database, none if the migrations table doesn't exist yet.
+async fn applied_migrations(conn: &mut AnyConnection) -> anyhow::Result<Vec<AppliedMigration>> {
+    if !migrations_table_exists(conn).await? {
+        return Ok(Vec::new());
+    }
+
+    Ok(conn.list_applied_migrations().await?)
+}
+
 pub async fn info(migration_source: &str, connect_opts: &ConnectOpts) -> anyhow::Result<()> {
     let migrator = Migrator::new(Path::new(migration_source)).await?;
     let mut conn = crate::connect(&connect_opts).await?;
@@ -239,6 +661,199 @@ pub async fn info(migration_source: &str, connect_opts: &ConnectOpts) -> anyhow:
     Ok(())
 }
 
+/// Exit codes of `migrate status --exit-code`.
+const STATUS_PENDING: i32 = 2;
+const STATUS_CHECKSUM_MISMATCH: i32 = 3;
+const STATUS_DIRTY: i32 = 4;
+
+pub async fn status(
+    migration_source: &str,
+    connect_opts: &ConnectOpts,
+    exit_code: bool,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    let migrator = Migrator::new(Path::new(migration_source)).await?;
+    let mut conn = crate::connect(connect_opts).await?;
+
+    // a missing migrations table means every migration is pending
+    let (applied_migrations, dirty_version) = if migrations_table_exists(&mut conn).await? {
+        (
+            conn.list_applied_migrations().await?,
+            conn.dirty_version().await?,
+        )
+    } else {
+        (Vec::new(), None)
+    };
+
+    let _ = conn.close().await;
+
+    let applied_migrations: HashMap<_, _> = applied_migrations
+        .into_iter()
+        .map(|m| (m.version, m))
+        .collect();
+
+    let mut pending = Vec::new();
+    let mut mismatched = Vec::new();
+
+    for migration in migrator.iter() {
+        if migration.migration_type.is_down_migration() || Some(migration.version) == dirty_version
+        {
+            continue;
+        }
+
+        match applied_migrations.get(&migration.version) {
+            Some(applied) if applied.checksum != migration.checksum => mismatched.push(migration),
+            Some(_) => {}
+            None => pending.push(migration),
+        }
+    }
+
+    if !exit_code || verbose {
+        if let Some(version) = dirty_version {
+            let description = migrator
+                .iter()
+                .find(|migration| migration.version == version)
+                .map_or("", |migration| &*migration.description);
+
+            println!(
+                "{}/{} {}",
+                style(version).cyan(),
+                style("dirty (partially applied)").red(),
+                description
+            );
+        }
+        for migration in &mismatched {
+            println!(
+                "{}/{} {}",
+                style(migration.version).cyan(),
+                style("installed (different checksum)").red(),
+                migration.description
+            );
+        }
+        for migration in &pending {
+            println!(
+                "{}/{} {}",
+                style(migration.version).cyan(),
+                style("pending").yellow(),
+                migration.description
+            );
+        }
+
+        if dirty_version.is_some() {
+            // reported as an error below
+        } else if !mismatched.is_empty() {
+            println!(
+                "{} applied migration(s) differ from the local migrations",
+                mismatched.len()
+            );
+        } else if !pending.is_empty() {
+            println!("{} migration(s) pending", pending.len());
+        } else {
+            println!("Database is fully migrated");
+        }
+    }
+
+    if let Some(version) = dirty_version {
+        if exit_code {
+            std::process::exit(STATUS_DIRTY);
+        }
+
+        bail!(MigrateError::Dirty(version));
+    }
+
+    if exit_code {
+        // Checksum drift is the more serious problem, so it takes precedence.
+        if !mismatched.is_empty() {
+            std::process::exit(STATUS_CHECKSUM_MISMATCH);
+        } else if !pending.is_empty() {
+            std::process::exit(STATUS_PENDING);
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn history(connect_opts: &ConnectOpts) -> anyhow::Result<()> {
+    let mut conn = crate::connect(connect_opts).await?;
+
+    conn.ensure_migrations_log_table().await?;
+
+    let log = conn.list_migrations_log().await?;
+
+    if log.is_empty() {
+        println!("No migrations recorded; set `audit-log = true` under `[migrate]` in sqlx.toml");
+    }
+
+    for entry in log {
+        let applied_at: DateTime<Local> = entry.applied_at.into();
+        let text = if entry.reverted {
+            style("reverted").yellow()
+        } else {
+            style("applied").green()
+        };
+
+        println!(
+            "{} {}/{} {} {}",
+            applied_at.format("%Y-%m-%d %H:%M:%S"),
+            style(entry.version).cyan(),
+            text,
+            entry.description,
+            style(format!("({:?})", entry.execution_time)).dim()
+        );
+
+        let unknown = || "unknown".to_owned();
+        println!(
+            "    by {}@{} with {}, commit {}",
+            entry.os_user.unwrap_or_else(unknown),
+            entry.hostname.unwrap_or_else(unknown),
+            entry.tool_version.unwrap_or_else(unknown),
+            entry.git_commit.unwrap_or_else(unknown),
+        );
+    }
+
+    let _ = conn.close().await;
+
+    Ok(())
+}
+
+/// Who is running the CLI and from where, for the audit log.
+fn audit_info() -> AuditInfo {
+    let audit_info = AuditInfo::from_env();
+
+    // outside of CI, ask git
+    let git_commit = audit_info.git_commit.or_else(|| {
+        let output = std::process::Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .output()
+            .ok()?;
+
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    });
+
+    AuditInfo {
+        tool_version: Some(format!("sqlx-cli {}", env!("CARGO_PKG_VERSION"))),
+        git_commit,
+        ..audit_info
+    }
+}
+
+async fn log_migration(
+    conn: &mut AnyConnection,
+    migration: &Migration,
+    elapsed: Duration,
+    audit_log: Option<&AuditInfo>,
+) -> anyhow::Result<()> {
+    if let Some(audit_log) = audit_log {
+        conn.log_migration(&MigrationLogEntry::new(migration, elapsed, audit_log))
+            .await?;
+    }
+
+    Ok(())
+}
+
 fn validate_applied_migrations(
     applied_migrations: &[AppliedMigration],
     migrator: &Migrator,
@@ -259,14 +874,80 @@ fn validate_applied_migrations(
     Ok(())
 }
 
+/// Where `run` reads the migrations from.
+#[derive(Debug, Clone, Copy)]
+pub enum Migrations<'a> {
+    /// A directory of migration files.
+    Source(&'a str),
+
+    /// A tar archive of migration files, read from stdin if the path is `-`.
+    Archive(&'a Path),
+}
+
+impl Migrations<'_> {
+    async fn resolve(self) -> anyhow::Result<Migrator> {
+        let path = match self {
+            Migrations::Source(migration_source) => {
+                return Ok(Migrator::new(Path::new(migration_source)).await?);
+            }
+            Migrations::Archive(path) => path,
+        };
+
+        let archive = if path == Path::new("-") {
+            let mut archive = Vec::new();
+            io::stdin()
+                .read_to_end(&mut archive)
+                .context("Unable to read migration archive from stdin")?;
+            archive
+        } else {
+            fs::read(path)
+                .with_context(|| format!("Unable to read migration archive {}", path.display()))?
+        };
+
+        Ok(Migrator::from_tar(&archive)?)
+    }
+}
+
+// prints each statement of `migration` and the time it took to run, for `--verbose`
+fn print_statements(migration: &Migration, dialect: Dialect, timings: &[Duration]) {
+    let statements: Vec<_> = lint::split(&migration.sql, dialect)
+        .into_iter()
+        .filter(|statement| !statement.words.is_empty())
+        .collect();
+
+    // the timings can only be matched up if the driver split the statements the same way
+    let timings = (timings.len() == statements.len()).then_some(timings);
+
+    for (i, statement) in statements.iter().enumerate() {
+        for line in statement.text.trim().lines() {
+            println!("    {}", style(line).dim());
+        }
+
+        if let Some(timings) = timings {
+            println!("    {}", style(format!("-- ({:?})", timings[i])).dim());
+        }
+    }
+}
+
 pub async fn run(
-    migration_source: &str,
+    migrations: Migrations<'_>,
     connect_opts: &ConnectOpts,
-    dry_run: bool,
-    ignore_missing: bool,
-    target_version: Option<i64>,
+    opts: &RunOpts,
 ) -> anyhow::Result<()> {
-    let migrator = Migrator::new(Path::new(migration_source)).await?;
+    let RunOpts {
+        dry_run,
+        ignore_missing,
+        target_version,
+        safe,
+        allow_destructive,
+        verbosity,
+    } = *opts;
+
+    let config = Config::load()?;
+    let safe = safe || allow_destructive || config.migrate.safe;
+    let dialect = Dialect::from_url(connect_opts.database_url()?);
+
+    let migrator = migrations.resolve().await?;
     if let Some(target_version) = target_version {
         if !migrator.iter().any(|m| target_version == m.version) {
             bail!(MigrateError::VersionNotPresent(target_version));
@@ -277,6 +958,11 @@ pub async fn run(
 
     conn.ensure_migrations_table().await?;
 
+    let audit_log = config.migrate.audit_log.then(audit_info);
+    if audit_log.is_some() {
+        conn.ensure_migrations_log_table().await?;
+    }
+
     let version = conn.dirty_version().await?;
     if let Some(version) = version {
         bail!(MigrateError::Dirty(version));
@@ -301,6 +987,77 @@ pub async fn run(
         .map(|m| (m.version, m))
         .collect();
 
+    if !verbosity.quiet {
+        warn_version_conflicts(&migrator);
+    }
+
+    // pending migrations older than the latest applied one were probably merged from another
+    // branch, and are applied out of order
+    let out_of_order: Vec<_> = migrator
+        .iter()
+        .filter(|m| !m.migration_type.is_down_migration())
+        .filter(|m| m.version < latest_version && !applied_migrations.contains_key(&m.version))
+        .map(|m| m.version.to_string())
+        .collect();
+
+    if !out_of_order.is_empty() && !verbosity.quiet {
+        println!(
+            "{} migrations {} are older than the latest applied migration {} and will be \
+             applied out of order",
+            style("warning:").bold().yellow(),
+            out_of_order.join(", "),
+            style(latest_version).cyan()
+        );
+        println!(
+            "To apply them after it instead, use `sqlx migrate rebase` or \
+             `sqlx migrate renumber {}` first",
+            out_of_order.join(" ")
+        );
+    }
+
+    if safe {
+        let destructive: Vec<_> = migrator
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .filter(|m| !applied_migrations.contains_key(&m.version))
+            .filter(
+                |m| !matches!(target_version, Some(target_version) if m.version > target_version),
+            )
+            .flat_map(|m| {
+                lint::destructive_statements(&m.sql, dialect)
+                    .into_iter()
+                    .map(move |statement| (m, statement))
+            })
+            .collect();
+
+        // the statements are still listed with `--quiet` if we have to ask about them
+        let ask = !dry_run && !allow_destructive;
+
+        if !destructive.is_empty() && (ask || !verbosity.quiet) {
+            println!("Pending migrations contain statements that delete data:");
+
+            for (migration, statement) in &destructive {
+                println!(
+                    "  {}/{} {}: {}",
+                    style(migration.version).cyan(),
+                    style(migration.migration_type.label()).green(),
+                    migration.description,
+                    style(statement).red()
+                );
+            }
+
+            if ask
+                // refuse if there's nobody to ask
+                && !crate::ask_yes_no("Apply them anyway?").unwrap_or(false)
+            {
+                bail!(
+                    "destructive migrations not applied; pass `--allow-destructive` or set \
+                     `SQLX_ALLOW_DESTRUCTIVE=1` to apply them"
+                );
+            }
+        }
+    }
+
     for migration in migrator.iter() {
         if migration.migration_type.is_down_migration() {
             // Skipping down migrations
@@ -319,11 +1076,17 @@ pub async fn run(
                     _ => false,
                 };
 
-                let elapsed = if dry_run || skip {
-                    Duration::new(0, 0)
+                let (elapsed, timings) = if dry_run || skip {
+                    (Duration::new(0, 0), Vec::new())
                 } else {
-                    conn.apply(migration).await?
+                    let (elapsed, timings) = conn.apply_timed(migration).await?;
+                    log_migration(&mut conn, migration, elapsed, audit_log.as_ref()).await?;
+                    (elapsed, timings)
                 };
+
+                if verbosity.verbose && !skip {
+                    print_statements(migration, dialect, &timings);
+                }
                 let text = if skip {
                     "Skipped"
                 } else if dry_run {
@@ -332,14 +1095,16 @@ pub async fn run(
                     "Applied"
                 };
 
-                println!(
-                    "{} {}/{} {} {}",
-                    text,
-                    style(migration.version).cyan(),
-                    style(migration.migration_type.label()).green(),
-                    migration.description,
-                    style(format!("({elapsed:?})")).dim()
-                );
+                if !verbosity.quiet {
+                    println!(
+                        "{} {}/{} {} {}",
+                        text,
+                        style(migration.version).cyan(),
+                        style(migration.migration_type.label()).green(),
+                        migration.description,
+                        style(format!("({elapsed:?})")).dim()
+                    );
+                }
             }
         }
     }
@@ -360,7 +1125,11 @@ pub async fn revert(
     dry_run: bool,
     ignore_missing: bool,
     target_version: Option<i64>,
+    verbosity: Verbosity,
 ) -> anyhow::Result<()> {
+    let config = Config::load()?;
+    let dialect = Dialect::from_url(connect_opts.database_url()?);
+
     let migrator = Migrator::new(Path::new(migration_source)).await?;
     if let Some(target_version) = target_version {
         if target_version != 0 && !migrator.iter().any(|m| target_version == m.version) {
@@ -372,6 +1141,11 @@ pub async fn revert(
 
     conn.ensure_migrations_table().await?;
 
+    let audit_log = config.migrate.audit_log.then(audit_info);
+    if audit_log.is_some() {
+        conn.ensure_migrations_log_table().await?;
+    }
+
     let version = conn.dirty_version().await?;
     if let Some(version) = version {
         bail!(MigrateError::Dirty(version));
@@ -409,11 +1183,18 @@ pub async fn revert(
                 Some(target_version) if migration.version <= target_version => true,
                 _ => false,
             };
-            let elapsed = if dry_run || skip {
-                Duration::new(0, 0)
+
+            let (elapsed, timings) = if dry_run || skip {
+                (Duration::new(0, 0), Vec::new())
             } else {
-                conn.revert(migration).await?
+                let (elapsed, timings) = conn.revert_timed(migration).await?;
+                log_migration(&mut conn, migration, elapsed, audit_log.as_ref()).await?;
+                (elapsed, timings)
             };
+
+            if verbosity.verbose && !skip {
+                print_statements(migration, dialect, &timings);
+            }
             let text = if skip {
                 "Skipped"
             } else if dry_run {
@@ -422,14 +1203,16 @@ pub async fn revert(
                 "Applied"
             };
 
-            println!(
-                "{} {}/{} {} {}",
-                text,
-                style(migration.version).cyan(),
-                style(migration.migration_type.label()).green(),
-                migration.description,
-                style(format!("({elapsed:?})")).dim()
-            );
+            if !verbosity.quiet {
+                println!(
+                    "{} {}/{} {} {}",
+                    text,
+                    style(migration.version).cyan(),
+                    style(migration.migration_type.label()).green(),
+                    migration.description,
+                    style(format!("({elapsed:?})")).dim()
+                );
+            }
 
             is_applied = true;
 
@@ -440,7 +1223,7 @@ pub async fn revert(
             }
         }
     }
-    if !is_applied {
+    if !is_applied && !verbosity.quiet {
         println!("No migrations available to revert");
     }
 
@@ -449,7 +1232,269 @@ pub async fn revert(
     Ok(())
 }
 
-pub fn build_script(migration_source: &str, force: bool) -> anyhow::Result<()> {
+pub async fn redo(
+    migration_source: &str,
+    connect_opts: &ConnectOpts,
+    ignore_missing: bool,
+    target_version: Option<i64>,
+    verbosity: Verbosity,
+) -> anyhow::Result<()> {
+    let mut conn = crate::connect(connect_opts).await?;
+
+    conn.ensure_migrations_table().await?;
+
+    let latest_version = conn
+        .list_applied_migrations()
+        .await?
+        .iter()
+        .map(|migration| migration.version)
+        .max();
+
+    let _ = conn.close().await;
+
+    let Some(latest_version) = latest_version else {
+        if !verbosity.quiet {
+            println!("No migrations available to redo");
+        }
+        return Ok(());
+    };
+
+    revert(
+        migration_source,
+        connect_opts,
+        false,
+        ignore_missing,
+        target_version,
+        verbosity,
+    )
+    .await?;
+
+    // only re-apply what was reverted, not any newer pending migrations
+    let opts = RunOpts {
+        ignore_missing,
+        target_version: Some(latest_version),
+        verbosity,
+        ..RunOpts::default()
+    };
+
+    run(Migrations::Source(migration_source), connect_opts, &opts).await
+}
+
+pub async fn fmt(
+    migration_source: &str,
+    connect_opts: &ConnectOpts,
+    check: bool,
+    dialect: Option<Dialect>,
+    since: Option<i64>,
+    include_applied: bool,
+    options: &FormatOptions,
+) -> anyhow::Result<()> {
+    let database_url = connect_opts.database_url().ok();
+
+    let dialect = dialect
+        .or_else(|| database_url.map(Dialect::from_url))
+        .unwrap_or(Dialect::Generic);
+
+    // reformatting an applied migration changes its checksum, so `migrate run` would reject it
+    let applied: Option<HashSet<i64>> = match database_url {
+        _ if include_applied => Some(HashSet::new()),
+        Some(_) => {
+            let mut conn = crate::connect(connect_opts).await?;
+
+            let applied = applied_migrations(&mut conn)
+                .await?
+                .into_iter()
+                .map(|migration| migration.version)
+                .collect();
+
+            let _ = conn.close().await;
+
+            Some(applied)
+        }
+        // `--since` says which migrations are new
+        None if since.is_some() => Some(HashSet::new()),
+        None => {
+            println!(
+                "No database to check which migrations are applied, so only linting; \
+                 pass `--since` or `--include-applied` to format them"
+            );
+            None
+        }
+    };
+
+    let mut file_names: Vec<String> = fs::read_dir(migration_source)
+        .with_context(|| format!("Unable to read migrations directory {migration_source}"))?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|file_name| file_name.ends_with(".sql"))
+        .collect();
+
+    file_names.sort();
+
+    let missing_down = lint::missing_down(&file_names);
+
+    let mut unformatted = 0;
+    let mut num_warnings = 0;
+
+    for file_name in &file_names {
+        let path = Path::new(migration_source).join(file_name);
+
+        let sql = fs::read_to_string(&path)
+            .with_context(|| format!("Unable to read migration {}", path.display()))?;
+
+        let version = file_name
+            .split_once('_')
+            .and_then(|(version, _)| version.parse::<i64>().ok());
+
+        let should_format = match (&applied, since, version) {
+            (None, ..) => false,
+            (Some(applied), _, Some(version)) if applied.contains(&version) => false,
+            (Some(_), Some(since), Some(version)) => version >= since,
+            (Some(_), Some(_), None) => false,
+            (Some(_), None, _) => true,
+        };
+
+        if should_format {
+            let formatted = lint::format(&sql, dialect, options);
+
+            if formatted != sql {
+                if check {
+                    println!("Would format {}", style(path.display()).cyan());
+                    unformatted += 1;
+                } else {
+                    fs::write(&path, formatted)
+                        .with_context(|| format!("Unable to write {}", path.display()))?;
+                    println!("Formatted {}", style(path.display()).cyan());
+                }
+            }
+        }
+
+        let mut warnings = lint::lint(&sql, dialect);
+
+        // dropping what the up migration created is what down migrations are for
+        if file_name.ends_with(".down.sql") {
+            warnings.retain(|warning| warning.lint != lint::DROP_WITHOUT_GUARD);
+        }
+
+        if missing_down.contains(&file_name.as_str()) && !lint::is_allowed(&sql, lint::MISSING_DOWN)
+        {
+            warnings.push(Warning {
+                lint: lint::MISSING_DOWN,
+                message: "reversible migration has no down migration".into(),
+            });
+        }
+
+        for warning in &warnings {
+            println!(
+                "{} {}: {}",
+                style("warning:").bold().yellow(),
+                style(path.display()).cyan(),
+                warning
+            );
+        }
+
+        num_warnings += warnings.len();
+    }
+
+    if check && (unformatted > 0 || num_warnings > 0) {
+        bail!("{unformatted} migration(s) need formatting and {num_warnings} lint warning(s) were found");
+    }
+
+    Ok(())
+}
+
+pub async fn advise(
+    migration_source: &str,
+    dialect: Option<Dialect>,
+    server_version: Option<&str>,
+    since: Option<i64>,
+) -> anyhow::Result<()> {
+    let database_url = std::env::var("DATABASE_URL").ok();
+
+    let dialect = dialect.unwrap_or_else(|| {
+        database_url
+            .as_deref()
+            .map_or(Dialect::Generic, Dialect::from_url)
+    });
+
+    if !matches!(dialect, Dialect::Postgres | Dialect::MySql) {
+        bail!("only Postgres and MySQL are supported; pass `--dialect` or set `DATABASE_URL`");
+    }
+
+    let version = match (server_version, database_url) {
+        (Some(version), _) => Some(
+            advise::parse_version(dialect, version)
+                .with_context(|| format!("invalid server version {version:?}"))?,
+        ),
+        (None, Some(database_url)) => {
+            sqlx::any::install_default_drivers();
+            let conn = AnyConnection::connect(&database_url).await?;
+
+            let version = conn.server_version();
+
+            let _ = conn.close().await;
+
+            Some(version)
+        }
+        (None, None) => None,
+    };
+
+    match &version {
+        Some(version) => println!("Checking migrations for server version {version}"),
+        None => println!(
+            "Server version unknown, so assuming the oldest affected versions; \
+             pass `--server-version` or set `DATABASE_URL` to be more specific"
+        ),
+    }
+
+    let mut file_names: Vec<String> = fs::read_dir(migration_source)
+        .with_context(|| format!("Unable to read migrations directory {migration_source}"))?
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .filter(|file_name| file_name.ends_with(".sql") && !file_name.ends_with(".down.sql"))
+        .collect();
+
+    file_names.sort();
+
+    let mut num_advice = 0;
+
+    for file_name in &file_names {
+        let migration_version = file_name
+            .split_once('_')
+            .and_then(|(version, _)| version.parse::<i64>().ok());
+
+        if since.is_some() && migration_version < since {
+            continue;
+        }
+
+        let path = Path::new(migration_source).join(file_name);
+
+        let sql = fs::read_to_string(&path)
+            .with_context(|| format!("Unable to read migration {}", path.display()))?;
+
+        for advice in advise::advise(&sql, dialect, version.as_ref()) {
+            println!(
+                "{} {}: {}",
+                style("warning:").bold().yellow(),
+                style(path.display()).cyan(),
+                advice
+            );
+
+            num_advice += 1;
+        }
+    }
+
+    if num_advice == 0 {
+        println!("No blocking statements found");
+    }
+
+    Ok(())
+}
+
+pub fn build_script(
+    migration_source: &str,
+    watch: &[String],
+    validate: bool,
+    force: bool,
+) -> anyhow::Result<()> {
     anyhow::ensure!(
         Path::new("Cargo.toml").exists(),
         "must be run in a Cargo project root"
@@ -460,17 +1505,197 @@ pub fn build_script(migration_source: &str, force: bool) -> anyhow::Result<()> {
         "build.rs already exists; use --force to overwrite"
     );
 
-    let contents = format!(
-        r#"// generated by `sqlx migrate build-script`
-fn main() {{
-    // trigger recompilation when a new migration is added
-    println!("cargo:rerun-if-changed={migration_source}");
-}}"#,
-    );
+    let config = Config::load()?;
+
+    let mut watch_dirs = config.migrate.build_script_watch;
+    watch_dirs.extend(watch.iter().cloned());
 
-    fs::write("build.rs", contents)?;
+    fs::write(
+        "build.rs",
+        build_script_contents(migration_source, &watch_dirs, validate),
+    )?;
 
     println!("Created `build.rs`; be sure to check it into version control!");
 
     Ok(())
 }
+
+fn build_script_contents(migration_source: &str, watch: &[String], validate: bool) -> String {
+    let mut contents = String::from("// generated by `sqlx migrate build-script`\nfn main() {\n");
+
+    // paths are passed as arguments, as they may have quotes, braces or backslashes
+    contents.push_str(&format!(
+        "    // trigger recompilation when a new migration is added\n    \
+         println!(\"cargo:rerun-if-changed={{}}\", {migration_source:?});\n"
+    ));
+
+    if !watch.is_empty() {
+        contents.push_str("\n    // and when these change\n");
+    }
+
+    for dir in watch {
+        contents.push_str(&format!(
+            "    println!(\"cargo:rerun-if-changed={{}}\", {dir:?});\n"
+        ));
+    }
+
+    if validate {
+        contents.push_str(&format!(
+            "\n    validate_migrations({migration_source:?});\n}}\n{VALIDATE_MIGRATIONS}"
+        ));
+    } else {
+        contents.push('}');
+    }
+
+    contents
+}
+
+// added to the build script by `--validate`; only uses `std`, as the build script can't rely on
+// the build dependencies of the crate
+const VALIDATE_MIGRATIONS: &str = r#"
+// fail the build if a migration is misnamed or shares its version with another migration,
+// instead of when the migrations are run
+fn validate_migrations(dir: &str) {
+    use std::collections::HashMap;
+
+    // the simple, up and down migrations of each version
+    let mut versions: HashMap<i64, [Option<String>; 3]> = HashMap::new();
+
+    for entry in std::fs::read_dir(dir).expect("failed to read the migrations directory") {
+        let path = entry.expect("failed to read the migrations directory").path();
+
+        if !path.is_file() || path.extension().and_then(|ext| ext.to_str()) != Some("sql") {
+            continue;
+        }
+
+        let file_name = path.file_name().unwrap().to_string_lossy().into_owned();
+
+        let version = file_name
+            .split_once('_')
+            .and_then(|(version, _)| version.parse::<i64>().ok())
+            .filter(|&version| version > 0)
+            .unwrap_or_else(|| {
+                panic!("migration {file_name:?} is not named `<VERSION>_<DESCRIPTION>.sql`")
+            });
+
+        let kind = if file_name.ends_with(".up.sql") {
+            1
+        } else if file_name.ends_with(".down.sql") {
+            2
+        } else {
+            0
+        };
+
+        let migrations = versions.entry(version).or_default();
+
+        // a simple migration can't share its version with any other migration
+        let conflict = match kind {
+            0 => migrations.iter().flatten().next(),
+            _ => migrations[0].as_ref().or(migrations[kind].as_ref()),
+        };
+
+        if let Some(other) = conflict {
+            panic!("migrations {other:?} and {file_name:?} have the same version {version}");
+        }
+
+        migrations[kind] = Some(file_name);
+    }
+}
+"#;
+
+#[test]
+fn test_build_script_contents() {
+    assert_eq!(
+        build_script_contents("migrations", &[], false),
+        "// generated by `sqlx migrate build-script`\n\
+         fn main() {\n    \
+         // trigger recompilation when a new migration is added\n    \
+         println!(\"cargo:rerun-if-changed={}\", \"migrations\");\n\
+         }"
+    );
+
+    let contents = build_script_contents(
+        "db/migrations",
+        &["queries".into(), r#"C:\sql\{"q"}"#.into()],
+        true,
+    );
+
+    assert!(contents.contains("println!(\"cargo:rerun-if-changed={}\", \"queries\");"));
+    assert!(contents.contains(r#"println!("cargo:rerun-if-changed={}", "C:\\sql\\{\"q\"}");"#));
+    assert!(contents.contains("    validate_migrations(\"db/migrations\");\n}\n"));
+    assert!(contents.ends_with(VALIDATE_MIGRATIONS));
+}
+
+#[test]
+fn test_version_conflicts() {
+    let conflicts = version_conflicts([
+        (1, "init"),
+        (2, "add users"),
+        (2, "add users"),
+        (3, "add posts"),
+        (2, "add tags"),
+    ]);
+
+    assert_eq!(conflicts, [(2, vec!["add tags", "add users"])]);
+
+    assert_eq!(next_versions(true, 9, 2), ["0010", "0011"]);
+    assert_eq!(
+        next_versions(false, 99990101000000, 2),
+        ["99990101000001", "99990101000002"]
+    );
+}
+
+#[test]
+fn test_rebased_migrations() {
+    let files: Vec<_> = [
+        (20240101000000, "init", "20240101000000_init.sql"),
+        (
+            20240201000000,
+            "add_users",
+            "20240201000000_add_users.up.sql",
+        ),
+        (
+            20240201000000,
+            "add_users",
+            "20240201000000_add_users.down.sql",
+        ),
+        (20240301000000, "add_posts", "20240301000000_add_posts.sql"),
+        (20240401000000, "add_tags", "20240401000000_add_tags.sql"),
+        (20240501000000, "add_likes", "20240501000000_add_likes.sql"),
+    ]
+    .into_iter()
+    .map(|(version, description, file_name)| {
+        (version, description.to_owned(), file_name.to_owned())
+    })
+    .collect();
+
+    let applied = BTreeSet::from([
+        (20240101000000, "init"),
+        (20240301000000, "add_posts"),
+        (20240401000000, "add_tags"),
+    ]);
+
+    assert_eq!(
+        rebased_migrations(&files, &applied, 20240401000000),
+        BTreeSet::from([(20240201000000, "add_users")])
+    );
+
+    // a migration sharing its version with an applied one is moved too
+    let mut files = files;
+    files.push((
+        20240401000000,
+        "add_tags_index".to_owned(),
+        "20240401000000_add_tags_index.sql".to_owned(),
+    ));
+    files.sort();
+
+    assert_eq!(
+        rebased_migrations(&files, &applied, 20240401000000),
+        BTreeSet::from([
+            (20240201000000, "add_users"),
+            (20240401000000, "add_tags_index")
+        ])
+    );
+
+    assert!(rebased_migrations(&files, &BTreeSet::new(), 0).is_empty());
+}
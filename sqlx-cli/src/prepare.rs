@@ -1,4 +1,4 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::env;
 use std::ffi::{OsStr, OsString};
 use std::fs;
@@ -8,10 +8,13 @@ use std::process::Command;
 use anyhow::{bail, Context};
 use console::style;
 
+use sqlx::query_data::{self, JsonFormat};
 use sqlx::Connection;
 
+use crate::config::Config;
 use crate::metadata::{manifest_dir, Metadata};
 use crate::opt::ConnectOpts;
+use crate::tolerance::Tolerance;
 
 pub struct PrepareCtx {
     pub workspace: bool,
@@ -19,6 +22,37 @@ pub struct PrepareCtx {
     pub cargo_args: Vec<String>,
     pub metadata: Metadata,
     pub connect_opts: ConnectOpts,
+    /// Which differences from the existing query data are ignored, if any.
+    pub tolerance: Option<Tolerance>,
+    /// Whether the queries are described by a describe daemon instead of the query macros.
+    pub daemon: bool,
+    /// Where to write the manifest of the query data, if anywhere.
+    pub manifest_out: Option<PathBuf>,
+}
+
+/// A kind of target to compile with `cargo check` to find queries.
+#[derive(clap::ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PrepareTarget {
+    Lib,
+    Bins,
+    Tests,
+    Examples,
+    Benches,
+    All,
+}
+
+impl PrepareTarget {
+    /// The `cargo check` flag selecting this kind of target.
+    fn cargo_arg(self) -> &'static str {
+        match self {
+            PrepareTarget::Lib => "--lib",
+            PrepareTarget::Bins => "--bins",
+            PrepareTarget::Tests => "--tests",
+            PrepareTarget::Examples => "--examples",
+            PrepareTarget::Benches => "--benches",
+            PrepareTarget::All => "--all-targets",
+        }
+    }
 }
 
 impl PrepareCtx {
@@ -32,20 +66,23 @@ impl PrepareCtx {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run(
     check: bool,
+    json: bool,
     workspace: bool,
+    tolerance: bool,
+    targets: &[PrepareTarget],
+    daemon: bool,
+    manifest_out: Option<PathBuf>,
     connect_opts: ConnectOpts,
-    cargo_args: Vec<String>,
+    mut cargo_args: Vec<String>,
 ) -> anyhow::Result<()> {
-    let cargo = env::var_os("CARGO")
-        .context("failed to get value of `CARGO`; `prepare` subcommand may only be invoked as `cargo sqlx prepare`")?;
+    let cargo = cargo()?;
+    let config = Config::load()?;
 
-    anyhow::ensure!(
-        Path::new("Cargo.toml").exists(),
-        r#"Failed to read `Cargo.toml`.
-hint: This command only works in the manifest directory of a Cargo package or workspace."#
-    );
+    // Pass the targets before any user-specified arguments, which may end with `--`.
+    cargo_args.splice(0..0, targets_args(targets));
 
     let metadata: Metadata = Metadata::from_current_directory(&cargo)?;
     let ctx = PrepareCtx {
@@ -54,27 +91,173 @@ hint: This command only works in the manifest directory of a Cargo package or wo
         cargo_args,
         metadata,
         connect_opts,
+        tolerance: Tolerance::from_config(tolerance, &config.prepare),
+        daemon,
+        manifest_out,
     };
 
     if check {
-        prepare_check(&ctx).await
+        prepare_check(&ctx, json).await
     } else {
         prepare(&ctx).await
     }
 }
 
+/// Rewrite the existing query data files with sorted keys, in the configured format.
+pub fn normalize(workspace: bool) -> anyhow::Result<()> {
+    let cargo = cargo()?;
+    let config = Config::load()?;
+
+    let prepare_dir = if workspace {
+        Metadata::from_current_directory(&cargo)?
+            .workspace_root()
+            .join(".sqlx")
+    } else {
+        manifest_dir(&cargo)?.join(".sqlx")
+    };
+
+    let query_files = glob_query_files(&prepare_dir)?;
+    for path in &query_files {
+        let json = load_json_file(path)?;
+        write_json_file(path, json, config.prepare.json_format)?;
+    }
+
+    println!("normalized {} query file(s)", query_files.len());
+    Ok(())
+}
+
+/// Print the hash of `sql` and where its query data file is, if it exists, in the `.sqlx`
+/// directories of the current package and workspace.
+pub fn find(sql: &str) -> anyhow::Result<()> {
+    let cargo = cargo()?;
+    let filename = query_data::data_file_name(sql);
+
+    println!("hash: {}", query_data::hash_query(sql));
+
+    let mut dirs = vec![manifest_dir(&cargo)?.join(".sqlx")];
+    let workspace_dir = Metadata::from_current_directory(&cargo)?
+        .workspace_root()
+        .join(".sqlx");
+    if !dirs.contains(&workspace_dir) {
+        dirs.push(workspace_dir);
+    }
+
+    let mut found = false;
+    for dir in dirs {
+        let path = dir.join(&filename);
+        if path.exists() {
+            println!("found: {}", path.display());
+            found = true;
+        }
+    }
+
+    if !found {
+        bail!("no query data found for this query ({filename}); you should re-run sqlx prepare");
+    }
+
+    Ok(())
+}
+
+/// The `cargo check` arguments selecting `targets`, without duplicates.
+fn targets_args(targets: &[PrepareTarget]) -> Vec<String> {
+    if targets.contains(&PrepareTarget::All) {
+        return vec![PrepareTarget::All.cargo_arg().into()];
+    }
+
+    let mut args: Vec<String> = Vec::new();
+    for target in targets {
+        let arg = target.cargo_arg();
+        if !args.iter().any(|existing| existing == arg) {
+            args.push(arg.into());
+        }
+    }
+    args
+}
+
+fn cargo() -> anyhow::Result<OsString> {
+    let cargo = env::var_os("CARGO")
+        .context("failed to get value of `CARGO`; `prepare` subcommand may only be invoked as `cargo sqlx prepare`")?;
+
+    anyhow::ensure!(
+        Path::new("Cargo.toml").exists(),
+        r#"Failed to read `Cargo.toml`.
+hint: This command only works in the manifest directory of a Cargo package or workspace."#
+    );
+
+    Ok(cargo)
+}
+
 async fn prepare(ctx: &PrepareCtx) -> anyhow::Result<()> {
     check_backend(&ctx.connect_opts).await?;
 
     let prepare_dir = ctx.prepare_dir()?;
+
+    // Remember the existing query data, as it's deleted before being regenerated.
+    let existing = match ctx.tolerance {
+        Some(_) => load_query_files(&prepare_dir)?,
+        None => HashMap::new(),
+    };
+
     run_prepare_step(ctx, &prepare_dir)?;
 
     // Warn if no queries were generated. Glob since the directory may contain unrelated files.
-    if glob_query_files(prepare_dir)?.is_empty() {
+    let query_files = glob_query_files(&prepare_dir)?;
+    if query_files.is_empty() {
+        // Written anyway, as build systems expect every declared output.
+        if let Some(manifest_out) = &ctx.manifest_out {
+            write_manifest(manifest_out, &query_files)?;
+        }
+
         println!("{} no queries found", style("warning:").yellow());
         return Ok(());
     }
 
+    let duplicates = match fs::read_to_string(duplicates_path(ctx)) {
+        Ok(contents) => duplicate_queries(&contents),
+        Err(_) => Vec::new(),
+    };
+
+    if !duplicates.is_empty() {
+        println!(
+            "{} of {} query file(s) are shared by more than one macro invocation:",
+            duplicates.len(),
+            query_files.len()
+        );
+
+        for (hash, invocations) in &duplicates {
+            println!("  query-{hash}.json ({invocations} invocations)");
+        }
+    }
+
+    if let Some(tolerance) = ctx.tolerance {
+        let mut kept = 0;
+
+        for path in &query_files {
+            let Some((old_json, old_bytes)) =
+                query_file_key(path).and_then(|filename| existing.get(&filename))
+            else {
+                continue;
+            };
+
+            let new_json = load_json_file(path)?;
+            if new_json != *old_json && tolerance.tolerates(old_json, &new_json) {
+                fs::write(path, old_bytes)
+                    .with_context(|| format!("failed to restore query file: {}", path.display()))?;
+                kept += 1;
+            }
+        }
+
+        if kept > 0 {
+            println!(
+                "kept {kept} query file(s) whose only differences are tolerated schema changes"
+            );
+        }
+    }
+
+    if let Some(manifest_out) = &ctx.manifest_out {
+        write_manifest(manifest_out, &query_files)?;
+    }
+
     if ctx.workspace {
         println!(
             "query data written to .sqlx in the workspace root; \
@@ -89,58 +272,144 @@ async fn prepare(ctx: &PrepareCtx) -> anyhow::Result<()> {
     Ok(())
 }
 
-async fn prepare_check(ctx: &PrepareCtx) -> anyhow::Result<()> {
+async fn prepare_check(ctx: &PrepareCtx, json: bool) -> anyhow::Result<()> {
     let _ = check_backend(&ctx.connect_opts).await?;
 
     // Re-generate and store the queries in a separate directory from both the prepared
-    // queries and the ones generated by `cargo check`, to avoid conflicts.
+    // queries and the ones generated by `cargo check`, to avoid conflicts. `.sqlx` is only read.
     let prepare_dir = ctx.prepare_dir()?;
     let cache_dir = ctx.metadata.target_directory().join("sqlx-prepare-check");
     run_prepare_step(ctx, &cache_dir)?;
 
-    // Compare .sqlx to cache.
-    let prepare_filenames: HashSet<String> = glob_query_files(&prepare_dir)?
-        .into_iter()
-        .filter_map(|path| path.file_name().map(|f| f.to_string_lossy().into_owned()))
-        .collect();
-    let cache_filenames: HashSet<String> = glob_query_files(&cache_dir)?
-        .into_iter()
-        .filter_map(|path| path.file_name().map(|f| f.to_string_lossy().into_owned()))
-        .collect();
+    let checks = compare_query_files(&prepare_dir, &cache_dir, ctx.tolerance)?;
+    let count = |status| checks.iter().filter(|check| check.status == status).count();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&checks)?);
+
+        if count(QueryStatus::Missing) > 0 || count(QueryStatus::Stale) > 0 {
+            bail!("prepare check failed; you should re-run sqlx prepare");
+        }
+        return Ok(());
+    }
 
     // Error: files in cache but not .sqlx.
-    if cache_filenames
-        .difference(&prepare_filenames)
-        .next()
-        .is_some()
-    {
+    if count(QueryStatus::Missing) > 0 {
         bail!("prepare check failed: .sqlx is missing one or more queries; you should re-run sqlx prepare");
     }
     // Warn: files in .sqlx but not cache.
-    if prepare_filenames
-        .difference(&cache_filenames)
-        .next()
-        .is_some()
-    {
+    if count(QueryStatus::Unused) > 0 {
         println!(
             "{} potentially unused queries found in .sqlx; you may want to re-run sqlx prepare",
             style("warning:").yellow()
         );
     }
 
-    // Compare file contents as JSON to ignore superficial differences.
-    // Everything in cache checked to be in .sqlx already.
-    for filename in cache_filenames {
-        let prepare_json = load_json_file(prepare_dir.join(&filename))?;
-        let cache_json = load_json_file(cache_dir.join(&filename))?;
-        if prepare_json != cache_json {
-            bail!("prepare check failed: one or more query files differ ({}); you should re-run sqlx prepare", filename);
-        }
+    if let Some(stale) = checks
+        .iter()
+        .find(|check| check.status == QueryStatus::Stale)
+    {
+        bail!(
+            "prepare check failed: one or more query files differ ({}); you should re-run sqlx prepare",
+            stale.file
+        );
+    }
+
+    let tolerated = count(QueryStatus::Tolerated);
+    if tolerated > 0 {
+        println!("{tolerated} query file(s) differ only by tolerated schema changes");
     }
 
     Ok(())
 }
 
+/// The state of a query data file in `.sqlx`, compared to the one regenerated by `--check`.
+#[derive(serde::Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum QueryStatus {
+    /// Up to date.
+    Fresh,
+    /// Only differs by schema changes tolerated by `--tolerance`.
+    Tolerated,
+    /// Out of date.
+    Stale,
+    /// Used by a query but not in `.sqlx`.
+    Missing,
+    /// In `.sqlx` but not used by any query.
+    Unused,
+}
+
+#[derive(serde::Serialize, Debug)]
+struct QueryCheck {
+    file: String,
+    status: QueryStatus,
+    /// The path of the file in `.sqlx`, whether it exists or not.
+    path: PathBuf,
+    /// The path of the regenerated file, unless `status` is `unused`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    generated_path: Option<PathBuf>,
+}
+
+/// Compare the query data files in `.sqlx` to those in `cache_dir`, in order of file name.
+fn compare_query_files(
+    prepare_dir: &Path,
+    cache_dir: &Path,
+    tolerance: Option<Tolerance>,
+) -> anyhow::Result<Vec<QueryCheck>> {
+    let filenames = |dir| -> anyhow::Result<BTreeMap<String, PathBuf>> {
+        Ok(glob_query_files(dir)?
+            .into_iter()
+            .filter_map(|path| Some((query_file_key(&path)?, path)))
+            .collect())
+    };
+
+    let prepare_filenames = filenames(prepare_dir)?;
+    let cache_filenames = filenames(cache_dir)?;
+
+    let mut checks = Vec::new();
+    let all_filenames: BTreeSet<&String> = prepare_filenames
+        .keys()
+        .chain(cache_filenames.keys())
+        .collect();
+
+    for filename in all_filenames {
+        let path = prepare_filenames
+            .get(filename)
+            .cloned()
+            .unwrap_or_else(|| prepare_dir.join(filename));
+        let generated_path = cache_filenames.get(filename).cloned();
+
+        let status = match &generated_path {
+            None => QueryStatus::Unused,
+            Some(_) if !prepare_filenames.contains_key(filename) => QueryStatus::Missing,
+            Some(generated_path) => {
+                // Compare file contents as JSON to ignore superficial differences.
+                let prepare_json = load_json_file(&path)?;
+                let cache_json = load_json_file(generated_path)?;
+
+                if prepare_json == cache_json {
+                    QueryStatus::Fresh
+                } else if tolerance
+                    .is_some_and(|tolerance| tolerance.tolerates(&prepare_json, &cache_json))
+                {
+                    QueryStatus::Tolerated
+                } else {
+                    QueryStatus::Stale
+                }
+            }
+        };
+
+        checks.push(QueryCheck {
+            file: filename.clone(),
+            status,
+            path,
+            generated_path,
+        });
+    }
+
+    Ok(checks)
+}
+
 fn run_prepare_step(ctx: &PrepareCtx, cache_dir: &Path) -> anyhow::Result<()> {
     // Create and/or clean the directory.
     fs::create_dir_all(cache_dir).context(format!(
@@ -155,6 +424,9 @@ fn run_prepare_step(ctx: &PrepareCtx, cache_dir: &Path) -> anyhow::Result<()> {
         cache_dir
     ))?;
 
+    // The macros add to the duplicate queries of this run.
+    let _ = fs::remove_file(duplicates_path(ctx));
+
     // Only delete sqlx-*.json files to avoid accidentally deleting any user data.
     for query_file in glob_query_files(cache_dir).context("Failed to read query cache files")? {
         fs::remove_file(&query_file)
@@ -165,17 +437,25 @@ fn run_prepare_step(ctx: &PrepareCtx, cache_dir: &Path) -> anyhow::Result<()> {
     // clean on error
     setup_minimal_project_recompile(&ctx.cargo, &ctx.metadata, ctx.workspace)?;
 
+    // Describe the queries for the macros, until they're compiled.
+    let daemon = ctx.daemon.then(|| start_daemon(&tmp_dir)).transpose()?;
+
     // Compile the queries.
     let check_status = {
         let mut check_command = Command::new(&ctx.cargo);
         check_command
             .arg("check")
             .args(&ctx.cargo_args)
-            .env("SQLX_TMP", tmp_dir)
-            .env("DATABASE_URL", &ctx.connect_opts.database_url)
+            .env("SQLX_TMP", &tmp_dir)
+            .env("DATABASE_URL", ctx.connect_opts.database_url()?)
             .env("SQLX_OFFLINE", "false")
             .env("SQLX_OFFLINE_DIR", cache_dir);
 
+        #[cfg(unix)]
+        if let Some(daemon) = &daemon {
+            check_command.env("SQLX_DESCRIBE_SOCKET", daemon.path());
+        }
+
         // `cargo check` recompiles on changed rust flags which can be set either via the env var
         // or through the `rustflags` field in `$CARGO_HOME/config` when the env var isn't set.
         // Because of this we only pass in `$RUSTFLAGS` when present.
@@ -192,6 +472,39 @@ fn run_prepare_step(ctx: &PrepareCtx, cache_dir: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[cfg(unix)]
+fn start_daemon(tmp_dir: &Path) -> anyhow::Result<crate::daemon::SocketGuard> {
+    crate::daemon::spawn(tmp_dir.join("describe.sock"))
+}
+
+#[cfg(not(unix))]
+fn start_daemon(_tmp_dir: &Path) -> anyhow::Result<()> {
+    bail!("`--daemon` is only supported on Unix")
+}
+
+/// The file the query macros add the hash of a query to for each duplicate invocation.
+fn duplicates_path(ctx: &PrepareCtx) -> PathBuf {
+    ctx.metadata
+        .target_directory()
+        .join("sqlx-tmp")
+        .join(query_data::DUPLICATES_FILE_NAME)
+}
+
+/// The hash of each query used by more than one invocation, with the number of invocations, from
+/// the contents of the duplicates file.
+fn duplicate_queries(contents: &str) -> Vec<(String, usize)> {
+    let mut duplicates = BTreeMap::new();
+
+    for hash in contents.lines().filter(|line| !line.is_empty()) {
+        // the first invocation isn't a duplicate
+        *duplicates.entry(hash.to_owned()).or_insert(1) += 1;
+    }
+
+    let mut duplicates: Vec<_> = duplicates.into_iter().collect();
+    duplicates.sort_by(|(_, a), (_, b)| b.cmp(a));
+    duplicates
+}
+
 #[derive(Debug, PartialEq)]
 struct ProjectRecompileAction {
     // The names of the packages
@@ -326,18 +639,103 @@ async fn check_backend(opts: &ConnectOpts) -> anyhow::Result<()> {
     Ok(())
 }
 
-/// Find all `query-*.json` files in a directory.
+/// Find all `query-*.json` files in a directory, in order of file name.
+///
+/// The name is matched without regard to case, for case-insensitive filesystems. This lists the
+/// directory rather than matching a glob pattern, which would misinterpret the `?` of the
+/// extended-length paths (`\\?\C:\...`) used on Windows.
 fn glob_query_files(path: impl AsRef<Path>) -> anyhow::Result<Vec<PathBuf>> {
     let path = path.as_ref();
-    let pattern = path.join("query-*.json");
-    glob::glob(
-        pattern
-            .to_str()
-            .context("query cache path is invalid UTF-8")?,
-    )
-    .with_context(|| format!("failed to read query cache path: {}", path.display()))?
-    .collect::<Result<Vec<_>, _>>()
-    .context("glob failed")
+
+    let entries = fs::read_dir(path)
+        .with_context(|| format!("failed to read query cache path: {}", path.display()))?;
+
+    let mut files = Vec::new();
+
+    for entry in entries {
+        let entry = entry.context("failed to read query cache path entry")?;
+
+        if entry.file_type()?.is_file() && query_file_key(&entry.path()).is_some() {
+            files.push(entry.path());
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+/// The lowercase file name of a `query-*.json` file, to compare files in different directories.
+fn query_file_key(path: &Path) -> Option<String> {
+    let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+
+    (name.starts_with("query-") && name.ends_with(".json")).then_some(name)
+}
+
+/// Load the JSON contents and raw bytes of all `query-*.json` files in a directory,
+/// keyed by lowercase file name.
+fn load_query_files(
+    path: impl AsRef<Path>,
+) -> anyhow::Result<HashMap<String, (serde_json::Value, Vec<u8>)>> {
+    let mut files = HashMap::new();
+
+    for path in glob_query_files(path)? {
+        let Some(filename) = query_file_key(&path) else {
+            continue;
+        };
+
+        let bytes =
+            fs::read(&path).with_context(|| format!("failed to load file: {}", path.display()))?;
+        let json = serde_json::from_slice(&bytes)?;
+        files.insert(filename, (json, bytes));
+    }
+
+    Ok(files)
+}
+
+/// Write the manifest of `query_files` to `path`.
+fn write_manifest(path: &Path, query_files: &[PathBuf]) -> anyhow::Result<()> {
+    let manifest = query_manifest(query_files)?;
+
+    write_json_file(path, manifest, JsonFormat::Pretty)
+        .with_context(|| format!("failed to write manifest: {}", path.display()))
+}
+
+/// A manifest of the query data files, in order of query hash: the name of each file, relative
+/// to `.sqlx`, with the SHA-256 digest of its contents.
+///
+/// Nothing else is included, so the manifest only changes with the query data.
+fn query_manifest(query_files: &[PathBuf]) -> anyhow::Result<serde_json::Value> {
+    use sha2::{Digest, Sha256};
+
+    let mut queries = BTreeMap::new();
+
+    for path in query_files {
+        let Some(key) = query_file_key(path) else {
+            continue;
+        };
+
+        let hash = key
+            .trim_start_matches("query-")
+            .trim_end_matches(".json")
+            .to_owned();
+
+        let bytes =
+            fs::read(path).with_context(|| format!("failed to load file: {}", path.display()))?;
+
+        queries.insert(
+            hash,
+            serde_json::json!({
+                "file": path.file_name().map(|name| name.to_string_lossy()),
+                "sha256": hex::encode(Sha256::digest(&bytes)),
+            }),
+        );
+    }
+
+    Ok(serde_json::json!({
+        "version": 1,
+        "queries": queries,
+    }))
 }
 
 /// Load the JSON contents of a query data file.
@@ -348,6 +746,18 @@ fn load_json_file(path: impl AsRef<Path>) -> anyhow::Result<serde_json::Value> {
     Ok(serde_json::from_slice(&file_bytes)?)
 }
 
+/// Write query data with the keys of every object sorted, matching the output of the macros.
+fn write_json_file(
+    path: impl AsRef<Path>,
+    json: serde_json::Value,
+    format: JsonFormat,
+) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    let contents = format.to_vec(json)?;
+
+    fs::write(path, contents).with_context(|| format!("failed to write file: {}", path.display()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -375,4 +785,133 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn compare_query_files_works() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("sqlx-prepare-check-{}", std::process::id()));
+        let (prepare_dir, cache_dir) = (dir.join(".sqlx"), dir.join("cache"));
+        fs::create_dir_all(&prepare_dir)?;
+        fs::create_dir_all(&cache_dir)?;
+
+        for (dir, name, contents) in [
+            (&prepare_dir, "query-fresh.json", r#"{"hash":"fresh"}"#),
+            (&cache_dir, "query-fresh.json", r#"{ "hash": "fresh" }"#),
+            (
+                &prepare_dir,
+                "query-stale.json",
+                r#"{"hash":"stale","x":1}"#,
+            ),
+            (&cache_dir, "query-stale.json", r#"{"hash":"stale","x":2}"#),
+            (&cache_dir, "query-missing.json", "{}"),
+            (&prepare_dir, "query-unused.json", "{}"),
+        ] {
+            fs::write(dir.join(name), contents)?;
+        }
+
+        let checks = compare_query_files(&prepare_dir, &cache_dir, None)?;
+        fs::remove_dir_all(&dir)?;
+
+        let statuses: Vec<_> = checks
+            .iter()
+            .map(|check| (check.file.as_str(), check.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("query-fresh.json", QueryStatus::Fresh),
+                ("query-missing.json", QueryStatus::Missing),
+                ("query-stale.json", QueryStatus::Stale),
+                ("query-unused.json", QueryStatus::Unused),
+            ]
+        );
+        assert_eq!(checks[1].path, prepare_dir.join("query-missing.json"));
+        assert_eq!(checks[3].generated_path, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn glob_query_files_works() -> anyhow::Result<()> {
+        let dir = std::env::temp_dir().join(format!("sqlx-prepare-glob-{}", std::process::id()));
+        fs::create_dir_all(dir.join("query-dir.json"))?;
+
+        for name in [
+            "query-b.json",
+            "Query-A.JSON",
+            "query-c.json.tmp",
+            "README.md",
+        ] {
+            fs::write(dir.join(name), "{}")?;
+        }
+
+        let files = glob_query_files(&dir)?;
+        fs::remove_dir_all(&dir)?;
+
+        let keys: Vec<_> = files.iter().filter_map(|f| query_file_key(f)).collect();
+        assert_eq!(keys, ["query-a.json", "query-b.json"]);
+        assert_eq!(files[0], dir.join("Query-A.JSON"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn query_manifest_works() -> anyhow::Result<()> {
+        let dir =
+            std::env::temp_dir().join(format!("sqlx-prepare-manifest-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+
+        fs::write(dir.join("query-b.json"), "{}")?;
+        fs::write(dir.join("query-a.json"), "[]")?;
+
+        let manifest = query_manifest(&glob_query_files(&dir)?)?;
+        fs::remove_dir_all(&dir)?;
+
+        assert_eq!(
+            manifest,
+            serde_json::json!({
+                "version": 1,
+                "queries": {
+                    "a": {
+                        "file": "query-a.json",
+                        "sha256": "4f53cda18c2baa0c0354bb5f9a3ecbe5ed12ab4d8e11ba873c2f11161202b945",
+                    },
+                    "b": {
+                        "file": "query-b.json",
+                        "sha256": "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a",
+                    },
+                },
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_queries_works() {
+        assert_eq!(
+            duplicate_queries("b\na\nb\n"),
+            [("b".to_owned(), 3), ("a".to_owned(), 2)]
+        );
+        assert!(duplicate_queries("").is_empty());
+    }
+
+    #[test]
+    fn targets_args_works() {
+        use PrepareTarget::*;
+
+        assert!(targets_args(&[]).is_empty());
+        assert_eq!(targets_args(&[Lib, Tests, Lib]), ["--lib", "--tests"]);
+        assert_eq!(targets_args(&[Examples, All]), ["--all-targets"]);
+    }
+
+    #[test]
+    fn json_format_sorts_keys() {
+        let json: serde_json::Value =
+            serde_json::from_str(r#"{"query":"SELECT 1","describe":{"nullable":[true],"columns":[{"type_info":"Int4","name":"x"}]},"db_name":"PostgreSQL"}"#).unwrap();
+
+        assert_eq!(
+            JsonFormat::Compact.to_vec(json).unwrap(),
+            b"{\"db_name\":\"PostgreSQL\",\"describe\":{\"columns\":[{\"name\":\"x\",\"type_info\":\"Int4\"}],\"nullable\":[true]},\"query\":\"SELECT 1\"}\n"
+        );
+    }
 }
@@ -0,0 +1,306 @@
+//! Comparison of query data files that ignores benign schema changes, for `prepare --tolerance`.
+//!
+//! Regenerated query data is tolerated, i.e. the existing file is kept, if the only
+//! differences from the existing file don't change the code the query macros generate for it:
+//!
+//! * output columns whose type changed to one the macros map to the same Rust type, e.g.
+//!   `VARCHAR(50)` to `VARCHAR(100)` or `TEXT`, but never `INT4` to `INT8`;
+//! * output columns whose nullability changed between unknown and nullable, which both map
+//!   to `Option`; and/or
+//! * nullable output columns added after the existing ones, e.g. by `SELECT *` after a new
+//!   column was added to the table.
+
+use serde_json::Value;
+
+use crate::config::PrepareConfig;
+
+/// Which differences between existing and regenerated query data are tolerated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tolerance {
+    pub equivalent_types: bool,
+    pub added_nullable_columns: bool,
+}
+
+impl Tolerance {
+    /// The policy set in `sqlx.toml`, if tolerance is enabled by `--tolerance` or the config.
+    pub fn from_config(flag: bool, config: &PrepareConfig) -> Option<Self> {
+        (flag || config.tolerance).then_some(Tolerance {
+            equivalent_types: config.tolerate_equivalent_types,
+            added_nullable_columns: config.tolerate_added_nullable_columns,
+        })
+    }
+
+    /// Returns `true` if `new` is equal to `old`, or only differs in tolerated ways.
+    pub fn tolerates(&self, old: &Value, new: &Value) -> bool {
+        if old == new {
+            return true;
+        }
+
+        let (Some(old), Some(new)) = (old.as_object(), new.as_object()) else {
+            return false;
+        };
+
+        // Everything except the description of the output columns has to match.
+        let same_keys = old.len() == new.len()
+            && old
+                .iter()
+                .all(|(key, value)| key == "describe" || new.get(key) == Some(value));
+
+        if !same_keys {
+            return false;
+        }
+
+        let db_name = old.get("db_name").and_then(Value::as_str).unwrap_or("");
+
+        match (old.get("describe"), new.get("describe")) {
+            (Some(old), Some(new)) => self.tolerates_describe(db_name, old, new),
+            _ => false,
+        }
+    }
+
+    fn tolerates_describe(&self, db_name: &str, old: &Value, new: &Value) -> bool {
+        if old.get("parameters") != new.get("parameters") {
+            return false;
+        }
+
+        let (Some(old_columns), Some(new_columns)) = (
+            old.get("columns").and_then(Value::as_array),
+            new.get("columns").and_then(Value::as_array),
+        ) else {
+            return false;
+        };
+
+        let (Some(old_nullable), Some(new_nullable)) = (
+            old.get("nullable").and_then(Value::as_array),
+            new.get("nullable").and_then(Value::as_array),
+        ) else {
+            return false;
+        };
+
+        if new_columns.len() < old_columns.len()
+            || old_nullable.len() != old_columns.len()
+            || new_nullable.len() != new_columns.len()
+        {
+            return false;
+        }
+
+        let existing_tolerated =
+            old_columns
+                .iter()
+                .zip(new_columns)
+                .enumerate()
+                .all(|(i, (old_column, new_column))| {
+                    is_nullable(&old_nullable[i]) == is_nullable(&new_nullable[i])
+                        && old_column.get("name") == new_column.get("name")
+                        && old_column.get("ordinal") == new_column.get("ordinal")
+                        && match (old_column.get("type_info"), new_column.get("type_info")) {
+                            (Some(old_type), Some(new_type)) => {
+                                old_type == new_type
+                                    || (self.equivalent_types
+                                        && is_equivalent(db_name, old_type, new_type))
+                            }
+                            _ => false,
+                        }
+                });
+
+        // Columns added after the existing ones don't change the ordinals the generated code
+        // reads, so it's unaffected by them.
+        let added_tolerated = new_nullable[old_columns.len()..]
+            .iter()
+            .all(|nullable| self.added_nullable_columns && nullable == &Value::Bool(true));
+
+        existing_tolerated && added_tolerated
+    }
+}
+
+/// Unknown nullability is treated as nullable by the query macros.
+fn is_nullable(nullable: &Value) -> bool {
+    nullable.as_bool().unwrap_or(true)
+}
+
+/// Groups of types that the query macros map to the same Rust type, e.g. `String`.
+///
+/// Types that map to different Rust types, even if one can hold every value of the other
+/// like `INT4` and `INT8`, must never be in the same group: the generated code would still
+/// decode the type recorded in the existing query data.
+const POSTGRES_EQUIVALENT_TYPES: &[&[&str]] = &[
+    &["Text", "Varchar", "Bpchar", "Name"],
+    &["TextArray", "VarcharArray", "BpcharArray", "NameArray"],
+];
+
+/// MySQL string types with the same character set; binary strings are `Vec<u8>` instead.
+const MYSQL_EQUIVALENT_TYPES: &[&[&str]] = &[&[
+    "VarChar",
+    "VarString",
+    "String",
+    "TinyBlob",
+    "Blob",
+    "MediumBlob",
+    "LongBlob",
+]];
+
+/// Returns `true` if the serialized type infos `old` and `new` map to the same Rust type.
+fn is_equivalent(db_name: &str, old: &Value, new: &Value) -> bool {
+    match db_name {
+        "PostgreSQL" => in_same_group(POSTGRES_EQUIVALENT_TYPES, old.as_str(), new.as_str()),
+        "MySQL" => {
+            let (Some(old), Some(new)) = (old.as_object(), new.as_object()) else {
+                return false;
+            };
+
+            // e.g. `UNSIGNED` or `BINARY` change the Rust type
+            if old.get("flags") != new.get("flags") || old.get("char_set") != new.get("char_set") {
+                return false;
+            }
+
+            let old_type = old.get("type").and_then(Value::as_str);
+            let new_type = new.get("type").and_then(Value::as_str);
+
+            // only the size changed, e.g. `VARCHAR(50)` to `VARCHAR(100)`
+            old_type == new_type || in_same_group(MYSQL_EQUIVALENT_TYPES, old_type, new_type)
+        }
+        _ => false,
+    }
+}
+
+fn in_same_group(groups: &[&[&str]], old: Option<&str>, new: Option<&str>) -> bool {
+    let (Some(old), Some(new)) = (old, new) else {
+        return false;
+    };
+
+    groups
+        .iter()
+        .any(|group| group.contains(&old) && group.contains(&new))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const ALL: Tolerance = Tolerance {
+        equivalent_types: true,
+        added_nullable_columns: true,
+    };
+
+    fn postgres(columns: &[(&str, &str)], nullable: &[bool]) -> Value {
+        json!({
+            "db_name": "PostgreSQL",
+            "query": "SELECT * FROM users",
+            "describe": {
+                "columns": columns
+                    .iter()
+                    .enumerate()
+                    .map(|(ordinal, (name, type_info))| json!({
+                        "ordinal": ordinal,
+                        "name": name,
+                        "type_info": type_info,
+                    }))
+                    .collect::<Vec<_>>(),
+                "parameters": { "Left": [] },
+                "nullable": nullable,
+            },
+            "hash": "abc",
+        })
+    }
+
+    #[test]
+    fn tolerates_equivalent_types() {
+        let old = postgres(&[("id", "Int4"), ("name", "Varchar")], &[false, true]);
+        let new = postgres(&[("id", "Int4"), ("name", "Text")], &[false, true]);
+
+        assert!(ALL.tolerates(&old, &new));
+        assert!(ALL.tolerates(&new, &old));
+
+        let no_equivalent_types = Tolerance {
+            equivalent_types: false,
+            ..ALL
+        };
+        assert!(!no_equivalent_types.tolerates(&old, &new));
+
+        // `i32` to `i64`
+        let new = postgres(&[("id", "Int8"), ("name", "Varchar")], &[false, true]);
+        assert!(!ALL.tolerates(&old, &new));
+
+        // `f32` to `f64`, `i64` to `BigDecimal`
+        assert!(!is_equivalent(
+            "PostgreSQL",
+            &json!("Float4"),
+            &json!("Float8")
+        ));
+        assert!(!is_equivalent(
+            "PostgreSQL",
+            &json!("Int8"),
+            &json!("Numeric")
+        ));
+        assert!(!is_equivalent("SQLite", &json!("Int"), &json!("Int64")));
+
+        // nullability changed
+        let new = postgres(&[("id", "Int4"), ("name", "Text")], &[true, true]);
+        assert!(!ALL.tolerates(&old, &new));
+    }
+
+    #[test]
+    fn tolerates_unknown_nullability_as_nullable() {
+        let old = postgres(&[("id", "Int4"), ("name", "Text")], &[false, true]);
+        let mut new = old.clone();
+        new["describe"]["nullable"] = json!([false, null]);
+
+        assert!(ALL.tolerates(&old, &new));
+        assert!(ALL.tolerates(&new, &old));
+
+        new["describe"]["nullable"] = json!([null, true]);
+        assert!(!ALL.tolerates(&old, &new));
+    }
+
+    #[test]
+    fn tolerates_added_nullable_columns() {
+        let old = postgres(&[("id", "Int4")], &[false]);
+        let new = postgres(&[("id", "Int4"), ("bio", "Text")], &[false, true]);
+
+        assert!(ALL.tolerates(&old, &new));
+
+        let no_added = Tolerance {
+            added_nullable_columns: false,
+            ..ALL
+        };
+        assert!(!no_added.tolerates(&old, &new));
+
+        // `NOT NULL`
+        let new = postgres(&[("id", "Int4"), ("bio", "Text")], &[false, false]);
+        assert!(!ALL.tolerates(&old, &new));
+
+        // removed
+        assert!(!ALL.tolerates(&new, &old));
+
+        // inserted before an existing column
+        let new = postgres(&[("bio", "Text"), ("id", "Int4")], &[true, false]);
+        assert!(!ALL.tolerates(&old, &new));
+    }
+
+    #[test]
+    fn mysql_equivalent_types() {
+        let ty = |ty: &str, max_size: u32| json!({ "type": ty, "flags": "NOT_NULL", "char_set": 224, "max_size": max_size });
+
+        assert!(is_equivalent(
+            "MySQL",
+            &ty("VarString", 200),
+            &ty("VarString", 400)
+        ));
+        assert!(is_equivalent(
+            "MySQL",
+            &ty("VarString", 400),
+            &ty("Blob", 262140)
+        ));
+        assert!(!is_equivalent(
+            "MySQL",
+            &ty("Long", 11),
+            &ty("LongLong", 20)
+        ));
+        assert!(!is_equivalent("MySQL", &ty("Float", 12), &ty("Double", 22)));
+
+        let binary = json!({ "type": "Blob", "flags": "NOT_NULL | BINARY", "char_set": 63, "max_size": 65535 });
+        assert!(!is_equivalent("MySQL", &ty("Blob", 65535), &binary));
+    }
+}
@@ -0,0 +1,128 @@
+//! Decoding values sent as text, against the plain implementations from `std` and the `hex`
+//! crate. Run it with and without `--features simd` to compare the fast paths.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use sqlx_core::io::text;
+
+fn utf8(c: &mut Criterion) {
+    let mut group = c.benchmark_group("utf8");
+
+    let inputs = [
+        ("ascii-16", "sqlx@example.com".to_owned()),
+        (
+            "ascii-1k",
+            "The quick brown fox jumps over the lazy dog. ".repeat(23),
+        ),
+        (
+            "mixed-1k",
+            "Zwölf Boxkämpfer jagen Viktor quer über den Sylter Deich. ".repeat(17),
+        ),
+    ];
+
+    for (name, input) in &inputs {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+
+        group.bench_with_input(BenchmarkId::new("std", name), input, |b, input| {
+            b.iter(|| std::str::from_utf8(black_box(input.as_bytes())).unwrap())
+        });
+
+        group.bench_with_input(BenchmarkId::new("sqlx", name), input, |b, input| {
+            b.iter(|| text::from_utf8(black_box(input.as_bytes())).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn numbers(c: &mut Criterion) {
+    let mut group = c.benchmark_group("numbers");
+
+    let ints: Vec<String> = (0..64)
+        .map(|i| (i * 7_919_i64).pow(2).to_string())
+        .collect();
+    let floats: Vec<String> = (0..64).map(|i| (f64::from(i) * 2.71).to_string()).collect();
+
+    group.bench_function("int/std", |b| {
+        b.iter(|| {
+            for int in &ints {
+                black_box(
+                    std::str::from_utf8(int.as_bytes())
+                        .unwrap()
+                        .parse::<i64>()
+                        .unwrap(),
+                );
+            }
+        })
+    });
+
+    group.bench_function("int/sqlx", |b| {
+        b.iter(|| {
+            for int in &ints {
+                black_box(text::parse_int::<i64>(int.as_bytes()).unwrap());
+            }
+        })
+    });
+
+    group.bench_function("float/std", |b| {
+        b.iter(|| {
+            for float in &floats {
+                black_box(
+                    std::str::from_utf8(float.as_bytes())
+                        .unwrap()
+                        .parse::<f64>()
+                        .unwrap(),
+                );
+            }
+        })
+    });
+
+    group.bench_function("float/sqlx", |b| {
+        b.iter(|| {
+            for float in &floats {
+                black_box(text::parse_float::<f64>(float.as_bytes()).unwrap());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn bytea(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bytea");
+
+    let bytes: Vec<u8> = (0..4096_u32).map(|i| (i * 31 % 251) as u8).collect();
+    let hex = format!("\\x{}", hex::encode(&bytes));
+
+    // mostly printable, as the escape format is used for bytes that are mostly text
+    let escaped: String = bytes
+        .iter()
+        .map(|&b| match b {
+            b'\\' => "\\\\".to_owned(),
+            0x20..=0x7E => char::from(b).to_string(),
+            _ if b % 8 == 0 => format!("\\{b:03o}"),
+            _ => "a".to_owned(),
+        })
+        .collect();
+
+    group.throughput(Throughput::Bytes(hex.len() as u64));
+
+    group.bench_function("hex/hex-crate", |b| {
+        b.iter(|| hex::decode(&black_box(hex.as_bytes())[2..]).unwrap())
+    });
+
+    group.bench_function("hex/sqlx", |b| {
+        b.iter(|| text::decode_bytea(black_box(hex.as_bytes())).unwrap())
+    });
+
+    group.throughput(Throughput::Bytes(escaped.len() as u64));
+
+    group.bench_function("escape/sqlx", |b| {
+        b.iter(|| text::decode_bytea(black_box(escaped.as_bytes())).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, utf8, numbers, bytea);
+criterion_main!(benches);
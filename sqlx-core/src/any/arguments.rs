@@ -1,5 +1,5 @@
-use crate::any::value::AnyValueKind;
-use crate::any::Any;
+use crate::any::value::{AnyArgumentValue, AnyValueKind};
+use crate::any::{Any, AnyValue};
 use crate::arguments::Arguments;
 use crate::encode::Encode;
 use crate::types::Type;
@@ -22,6 +22,19 @@ impl<'q> Arguments<'q> for AnyArguments<'q> {
     {
         let _ = value.encode(&mut self.values);
     }
+
+    fn shorten<'a>(self) -> Option<AnyArguments<'a>>
+    where
+        'q: 'a,
+    {
+        Some(self)
+    }
+}
+
+impl<'q> From<Vec<AnyValue>> for AnyArguments<'q> {
+    fn from(values: Vec<AnyValue>) -> Self {
+        AnyArguments::from_values(values)
+    }
 }
 
 pub struct AnyArgumentBuffer<'q>(#[doc(hidden)] pub Vec<AnyValueKind<'q>>);
@@ -35,6 +48,13 @@ impl<'q> Default for AnyArguments<'q> {
 }
 
 impl<'q> AnyArguments<'q> {
+    /// Arguments binding `values` in order, e.g. for a filter built at runtime.
+    pub fn from_values(values: impl IntoIterator<Item = AnyValue>) -> Self {
+        AnyArguments {
+            values: AnyArgumentBuffer(values.into_iter().map(AnyValue::into_kind).collect()),
+        }
+    }
+
     #[doc(hidden)]
     pub fn convert_to<'a, A: Arguments<'a>>(&'a self) -> A
     where
@@ -48,6 +68,7 @@ impl<'q> AnyArguments<'q> {
         f64: Type<A::Database> + Encode<'a, A::Database>,
         &'a str: Type<A::Database> + Encode<'a, A::Database>,
         &'a [u8]: Type<A::Database> + Encode<'a, A::Database>,
+        AnyArgumentValue<'a>: Type<A::Database> + Encode<'a, A::Database>,
     {
         let mut out = A::default();
 
@@ -62,6 +83,7 @@ impl<'q> AnyArguments<'q> {
                 AnyValueKind::Double(d) => out.add(d),
                 AnyValueKind::Text(t) => out.add(&**t),
                 AnyValueKind::Blob(b) => out.add(&**b),
+                other => out.add(AnyArgumentValue(other)),
             }
         }
 
@@ -0,0 +1,81 @@
+use crate::connection::{ReturningStatement, ServerFlavor, ServerVersion};
+
+/// The features supported by the database behind an [`AnyConnection`][crate::any::AnyConnection].
+///
+/// Returned by [`AnyConnection::capabilities()`][crate::any::AnyConnection::capabilities], so
+/// code that is generic over the database can check for a feature at runtime rather than
+/// matching on the backend name and assuming what each one supports.
+///
+/// ```rust,ignore
+/// if conn.capabilities().listen_notify {
+///     conn.execute("NOTIFY jobs").await?;
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct AnyCapabilities {
+    /// `INSERT`, `UPDATE` and `DELETE` all support `RETURNING`; see
+    /// [`ServerVersion::supports_returning()`] to check for each of them.
+    pub returning: bool,
+
+    /// Transactions can be nested with savepoints, as with
+    /// [`Transaction::begin()`][crate::transaction::Transaction] on a transaction.
+    pub savepoints: bool,
+
+    /// Columns and bind parameters can be arrays.
+    pub arrays: bool,
+
+    /// `LISTEN` and `NOTIFY` are supported.
+    pub listen_notify: bool,
+
+    /// A query without arguments may contain several statements separated by `;`.
+    pub multi_statement: bool,
+}
+
+impl AnyCapabilities {
+    /// The features supported by `version`.
+    pub fn from_server_version(version: &ServerVersion) -> Self {
+        let is_postgres = matches!(
+            version.flavor,
+            ServerFlavor::Postgres | ServerFlavor::CockroachDb
+        );
+
+        AnyCapabilities {
+            returning: [
+                ReturningStatement::Insert,
+                ReturningStatement::Update,
+                ReturningStatement::Delete,
+            ]
+            .into_iter()
+            .all(|statement| version.supports_returning(statement)),
+            savepoints: match version.flavor {
+                ServerFlavor::TiDb => version.at_least(6, 2, 0),
+                _ => true,
+            },
+            arrays: is_postgres,
+            listen_notify: version.flavor == ServerFlavor::Postgres,
+            multi_statement: true,
+        }
+    }
+}
+
+#[test]
+fn test_capabilities() {
+    let capabilities = |flavor, version| {
+        AnyCapabilities::from_server_version(&ServerVersion::parse(flavor, version))
+    };
+
+    let postgres = capabilities(ServerFlavor::Postgres, "16.1");
+    assert!(postgres.returning && postgres.arrays && postgres.listen_notify);
+
+    let cockroach = capabilities(ServerFlavor::CockroachDb, "23.1.0");
+    assert!(cockroach.arrays && !cockroach.listen_notify);
+
+    let mysql = capabilities(ServerFlavor::MySql, "8.0.36");
+    assert!(!mysql.returning && mysql.savepoints && !mysql.arrays);
+
+    // MariaDB doesn't support `UPDATE .. RETURNING`
+    assert!(!capabilities(ServerFlavor::MariaDb, "10.11.2").returning);
+    assert!(!capabilities(ServerFlavor::TiDb, "6.1.0").savepoints);
+    assert!(!capabilities(ServerFlavor::Sqlite, "3.30.0").returning);
+}
@@ -1,5 +1,9 @@
-use crate::any::{Any, AnyArguments, AnyQueryResult, AnyRow, AnyStatement, AnyTypeInfo};
+use crate::any::{
+    Any, AnyArguments, AnyQueryResult, AnyRow, AnyStatement, AnyTypeInfo, AnyTypeRegistry,
+};
+use crate::connection::{RunningQuery, ServerVersion};
 use crate::describe::Describe;
+use crate::transaction::TransactionOptions;
 use either::Either;
 use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
@@ -25,11 +29,34 @@ pub trait AnyConnectionBackend: std::any::Any + Debug + Send + 'static {
     /// Checks if a connection to the database is still valid.
     fn ping(&mut self) -> BoxFuture<'_, crate::Result<()>>;
 
+    /// Forward to [`Connection::is_open()`].
+    ///
+    /// [`Connection::is_open()`]: method@crate::connection::Connection::is_open
+    fn is_open(&mut self) -> bool {
+        true
+    }
+
+    /// Forward to [`Connection::is_poisoned()`].
+    ///
+    /// [`Connection::is_poisoned()`]: method@crate::connection::Connection::is_poisoned
+    fn is_poisoned(&self) -> bool {
+        false
+    }
+
     /// Begin a new transaction or establish a savepoint within the active transaction.
     ///
     /// Returns a [`Transaction`] for controlling and tracking the new transaction.
     fn begin(&mut self) -> BoxFuture<'_, crate::Result<()>>;
 
+    /// Begin a new transaction with the given isolation level and access mode.
+    ///
+    /// The default implementation ignores the options.
+    fn begin_with(&mut self, options: TransactionOptions) -> BoxFuture<'_, crate::Result<()>> {
+        let _ = options;
+
+        self.begin()
+    }
+
     fn commit(&mut self) -> BoxFuture<'_, crate::Result<()>>;
 
     fn rollback(&mut self) -> BoxFuture<'_, crate::Result<()>>;
@@ -52,6 +79,38 @@ pub trait AnyConnectionBackend: std::any::Any + Debug + Send + 'static {
     /// [`Connection::shrink_buffers()`]: method@crate::connection::Connection::shrink_buffers
     fn shrink_buffers(&mut self);
 
+    /// Forward to [`Connection::server_version()`].
+    ///
+    /// [`Connection::server_version()`]: method@crate::connection::Connection::server_version
+    fn server_version(&self) -> ServerVersion {
+        ServerVersion::unknown()
+    }
+
+    /// Forward to [`Connection::running_query()`].
+    ///
+    /// [`Connection::running_query()`]: method@crate::connection::Connection::running_query
+    fn running_query(&self) -> Option<RunningQuery> {
+        None
+    }
+
+    /// Forward to [`Connection::set_application_name()`].
+    ///
+    /// [`Connection::set_application_name()`]: method@crate::connection::Connection::set_application_name
+    fn set_application_name<'c>(
+        &'c mut self,
+        name: Option<&'c str>,
+    ) -> BoxFuture<'c, crate::Result<()>> {
+        let _ = name;
+        Box::pin(async move { Ok(()) })
+    }
+
+    /// Sets the types to map when the backend doesn't support them.
+    ///
+    /// The default implementation ignores the registry, for backends that support every type.
+    fn set_type_registry(&mut self, registry: AnyTypeRegistry) {
+        let _ = registry;
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, crate::Result<()>>;
 
@@ -0,0 +1,238 @@
+use std::fmt::Write;
+
+use crate::any::{AnyArguments, AnyConnection, AnyValue};
+use crate::connection::{
+    Connection, ReturningStatement, ServerFlavor, ServerVersion, UpsertSyntax,
+};
+use crate::error::Error;
+use crate::query::query_with;
+use crate::query_scalar::query_scalar_with;
+
+/// What to do if the row inserted by [`AnyConnection::insert_returning_id()`] conflicts with an
+/// existing row.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnyOnConflict<'a> {
+    /// Return the error of the database.
+    #[default]
+    Error,
+
+    /// Don't insert the row, and return `None`.
+    ///
+    /// On MySQL, this is `INSERT IGNORE`, which also turns some other errors into warnings.
+    Ignore,
+
+    /// Update the existing row with the inserted values, and return its key.
+    ///
+    /// The columns are those of the unique constraint that conflicts, which MySQL doesn't need as
+    /// it updates the row conflicting on any unique key.
+    Update(&'a [&'a str]),
+}
+
+impl AnyConnection {
+    /// Inserts a row with `values` for `columns` into `table`, returning the value of its `key`
+    /// column, e.g. an auto-incrementing `id` generated by the database.
+    ///
+    /// The SQL is generated for the backend: the key is returned with `RETURNING` where it's
+    /// supported, and from the last insert ID otherwise, and `on_conflict` is implemented with
+    /// `ON CONFLICT` or `ON DUPLICATE KEY UPDATE`. Returns `None` if the row wasn't inserted
+    /// because of [`AnyOnConflict::Ignore`].
+    ///
+    /// On MySQL and SQLite before 3.35, `key` must be the `AUTO_INCREMENT` or `INTEGER PRIMARY
+    /// KEY` column, as its value is the last insert ID.
+    ///
+    /// The names of the table and columns are not quoted or escaped, so they must not be
+    /// untrusted input.
+    ///
+    /// ```rust,ignore
+    /// use sqlx::any::{AnyOnConflict, AnyValue, AnyValueKind};
+    ///
+    /// let id = conn
+    ///     .insert_returning_id(
+    ///         "users",
+    ///         "id",
+    ///         &["email", "name"],
+    ///         vec![
+    ///             AnyValue::new(AnyValueKind::Text("alice@example.com".into())),
+    ///             AnyValue::new(AnyValueKind::Text("Alice".into())),
+    ///         ],
+    ///         AnyOnConflict::Update(&["email"]),
+    ///     )
+    ///     .await?;
+    /// ```
+    pub async fn insert_returning_id(
+        &mut self,
+        table: &str,
+        key: &str,
+        columns: &[&str],
+        values: Vec<AnyValue>,
+        on_conflict: AnyOnConflict<'_>,
+    ) -> Result<Option<i64>, Error> {
+        let version = self.server_version();
+        let sql = insert_sql(&version, table, key, columns, on_conflict)?;
+        let arguments = AnyArguments::from_values(values);
+
+        if returns_key(&version) {
+            return query_scalar_with(&sql, arguments)
+                .fetch_optional(self)
+                .await;
+        }
+
+        let result = query_with(&sql, arguments).execute(self).await?;
+
+        Ok(result.last_insert_id.filter(|_| result.rows_affected > 0))
+    }
+}
+
+fn is_mysql(version: &ServerVersion) -> bool {
+    matches!(
+        version.flavor,
+        ServerFlavor::MySql | ServerFlavor::MariaDb | ServerFlavor::TiDb
+    )
+}
+
+// whether the key is returned with `RETURNING`; MariaDB would return the existing row for
+// `INSERT IGNORE`, so the last insert ID is used for all of MySQL
+fn returns_key(version: &ServerVersion) -> bool {
+    version.supports_returning(ReturningStatement::Insert) && !is_mysql(version)
+}
+
+fn insert_sql(
+    version: &ServerVersion,
+    table: &str,
+    key: &str,
+    columns: &[&str],
+    on_conflict: AnyOnConflict<'_>,
+) -> Result<String, Error> {
+    let syntax = match on_conflict {
+        AnyOnConflict::Error => None,
+        _ => Some(version.upsert_syntax().ok_or_else(|| {
+            Error::Configuration(format!("upserts are not supported by {version}").into())
+        })?),
+    };
+
+    let mut sql = String::from("INSERT ");
+
+    if on_conflict == AnyOnConflict::Ignore && syntax == Some(UpsertSyntax::OnDuplicateKeyUpdate) {
+        sql.push_str("IGNORE ");
+    }
+
+    let _ = write!(sql, "INTO {table} ({}) VALUES (", columns.join(", "));
+
+    for i in 1..=columns.len() {
+        if i > 1 {
+            sql.push_str(", ");
+        }
+
+        match version.flavor {
+            ServerFlavor::Postgres | ServerFlavor::CockroachDb => {
+                let _ = write!(sql, "${i}");
+            }
+            _ => sql.push('?'),
+        }
+    }
+
+    sql.push(')');
+
+    match (on_conflict, syntax) {
+        (AnyOnConflict::Ignore, Some(UpsertSyntax::OnConflict)) => {
+            sql.push_str(" ON CONFLICT DO NOTHING");
+        }
+
+        (AnyOnConflict::Update(target), Some(UpsertSyntax::OnConflict)) => {
+            // the last insert ID isn't updated, so the key can't be returned without `RETURNING`
+            if !returns_key(version) {
+                return Err(Error::Configuration(
+                    format!(
+                        "`RETURNING` is required for an upsert, but not supported by {version}"
+                    )
+                    .into(),
+                ));
+            }
+
+            let _ = write!(sql, " ON CONFLICT ({}) DO UPDATE SET ", target.join(", "));
+
+            for (i, column) in columns.iter().enumerate() {
+                if i > 0 {
+                    sql.push_str(", ");
+                }
+
+                let _ = write!(sql, "{column} = excluded.{column}");
+            }
+        }
+
+        (AnyOnConflict::Update(_), Some(UpsertSyntax::OnDuplicateKeyUpdate)) => {
+            // makes the key of the updated row the last insert ID
+            let _ = write!(
+                sql,
+                " ON DUPLICATE KEY UPDATE {key} = LAST_INSERT_ID({key})"
+            );
+
+            for column in columns {
+                let _ = write!(sql, ", {column} = VALUES({column})");
+            }
+        }
+
+        _ => {}
+    }
+
+    if returns_key(version) {
+        let _ = write!(sql, " RETURNING {key}");
+    }
+
+    Ok(sql)
+}
+
+#[test]
+fn test_insert_sql() {
+    let sql = |flavor, version, on_conflict| {
+        insert_sql(
+            &ServerVersion::parse(flavor, version),
+            "users",
+            "id",
+            &["email", "name"],
+            on_conflict,
+        )
+        .map_err(|e| e.to_string())
+    };
+
+    assert_eq!(
+        sql(ServerFlavor::Postgres, "16.1", AnyOnConflict::Error).unwrap(),
+        "INSERT INTO users (email, name) VALUES ($1, $2) RETURNING id"
+    );
+    assert_eq!(
+        sql(ServerFlavor::Sqlite, "3.45.0", AnyOnConflict::Ignore).unwrap(),
+        "INSERT INTO users (email, name) VALUES (?, ?) ON CONFLICT DO NOTHING RETURNING id"
+    );
+    assert_eq!(
+        sql(
+            ServerFlavor::Postgres,
+            "16.1",
+            AnyOnConflict::Update(&["email"])
+        )
+        .unwrap(),
+        "INSERT INTO users (email, name) VALUES ($1, $2) ON CONFLICT (email) \
+         DO UPDATE SET email = excluded.email, name = excluded.name RETURNING id"
+    );
+    assert_eq!(
+        sql(ServerFlavor::MariaDb, "10.11.2", AnyOnConflict::Ignore).unwrap(),
+        "INSERT IGNORE INTO users (email, name) VALUES (?, ?)"
+    );
+    assert_eq!(
+        sql(
+            ServerFlavor::MySql,
+            "8.0.36",
+            AnyOnConflict::Update(&["email"])
+        )
+        .unwrap(),
+        "INSERT INTO users (email, name) VALUES (?, ?) ON DUPLICATE KEY UPDATE \
+         id = LAST_INSERT_ID(id), email = VALUES(email), name = VALUES(name)"
+    );
+
+    assert!(sql(
+        ServerFlavor::Sqlite,
+        "3.30.0",
+        AnyOnConflict::Update(&["email"])
+    )
+    .is_err());
+    assert!(sql(ServerFlavor::Sqlite, "3.20.0", AnyOnConflict::Ignore).is_err());
+}
@@ -1,16 +1,18 @@
 use futures_core::future::BoxFuture;
 
-use crate::any::{Any, AnyConnectOptions};
-use crate::connection::{ConnectOptions, Connection};
+use crate::any::{Any, AnyCapabilities, AnyConnectOptions, AnyTypeRegistry};
+use crate::connection::{ConnectOptions, Connection, RunningQuery, ServerVersion};
 use crate::error::Error;
 
 use crate::database::Database;
 pub use backend::AnyConnectionBackend;
+pub use insert::AnyOnConflict;
 
-use crate::transaction::Transaction;
+use crate::transaction::{Transaction, TransactionOptions};
 
 mod backend;
 mod executor;
+mod insert;
 
 /// A connection to _any_ SQLx database.
 ///
@@ -32,6 +34,13 @@ impl AnyConnection {
         self.backend.name()
     }
 
+    /// The features supported by the database backend, to check for at runtime.
+    ///
+    /// See [`AnyCapabilities`] for details.
+    pub fn capabilities(&self) -> AnyCapabilities {
+        AnyCapabilities::from_server_version(&self.backend.server_version())
+    }
+
     pub(crate) fn connect(options: &AnyConnectOptions) -> BoxFuture<'_, crate::Result<Self>> {
         Box::pin(async {
             let driver = crate::any::driver::from_url(&options.database_url)?;
@@ -48,16 +57,26 @@ impl AnyConnection {
             for<'a> TryFrom<&'a AnyConnectOptions, Error = Error>,
     {
         let res = TryFrom::try_from(options);
+        let type_registry = options.type_registry.clone();
 
         Box::pin(async {
             let options: <DB::Connection as Connection>::Options = res?;
 
-            Ok(AnyConnection {
-                backend: Box::new(options.connect().await?),
-            })
+            let mut backend = Box::new(options.connect().await?);
+            backend.set_type_registry(type_registry);
+
+            Ok(AnyConnection { backend })
         })
     }
 
+    /// Replaces the registry of types to map when the database backend doesn't support them,
+    /// which is initially [`AnyConnectOptions::type_registry()`].
+    ///
+    /// See [`AnyTypeRegistry`] for details.
+    pub fn set_type_registry(&mut self, registry: AnyTypeRegistry) {
+        self.backend.set_type_registry(registry);
+    }
+
     #[cfg(feature = "migrate")]
     pub(crate) fn get_migrate(
         &mut self,
@@ -83,6 +102,14 @@ impl Connection for AnyConnection {
         self.backend.ping()
     }
 
+    fn is_open(&mut self) -> bool {
+        self.backend.is_open()
+    }
+
+    fn is_poisoned(&self) -> bool {
+        self.backend.is_poisoned()
+    }
+
     fn begin(&mut self) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
     where
         Self: Sized,
@@ -90,6 +117,16 @@ impl Connection for AnyConnection {
         Transaction::begin(self)
     }
 
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
     fn cached_statements_size(&self) -> usize {
         self.backend.cached_statements_size()
     }
@@ -102,6 +139,21 @@ impl Connection for AnyConnection {
         self.backend.shrink_buffers()
     }
 
+    fn server_version(&self) -> ServerVersion {
+        self.backend.server_version()
+    }
+
+    fn running_query(&self) -> Option<RunningQuery> {
+        self.backend.running_query()
+    }
+
+    fn set_application_name<'c>(
+        &'c mut self,
+        name: Option<&'c str>,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        self.backend.set_application_name(name)
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         self.backend.flush()
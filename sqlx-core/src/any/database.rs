@@ -26,6 +26,9 @@ impl Database for Any {
     const NAME: &'static str = "Any";
 
     const URL_SCHEMES: &'static [&'static str] = &[];
+
+    // the lowest limit of the built-in drivers (SQLite)
+    const BIND_LIMIT: usize = 32766;
 }
 
 impl<'r> HasValueRef<'r> for Any {
@@ -1,4 +1,5 @@
 use crate::any::connection::AnyConnectionBackend;
+use crate::any::options::normalize_url;
 use crate::any::{AnyConnectOptions, AnyConnection};
 use crate::common::DebugFn;
 use crate::connection::Connection;
@@ -125,7 +126,7 @@ pub fn install_drivers(
 }
 
 pub(crate) fn from_url_str(url: &str) -> crate::Result<&'static AnyDriver> {
-    from_url(&url.parse().map_err(Error::config)?)
+    from_url(&normalize_url(url)?.parse().map_err(Error::config)?)
 }
 
 pub(crate) fn from_url(url: &Url) -> crate::Result<&'static AnyDriver> {
@@ -1,34 +1,43 @@
 use crate::any::driver;
+use crate::any::options::normalize_url;
 use crate::any::{Any, AnyConnection};
 use crate::error::Error;
-use crate::migrate::{AppliedMigration, Migrate, MigrateDatabase, MigrateError, Migration};
+use crate::migrate::{
+    AppliedMigration, Migrate, MigrateDatabase, MigrateError, Migration, MigrationLogEntry,
+};
 use futures_core::future::BoxFuture;
 use std::time::Duration;
 
 impl MigrateDatabase for Any {
     fn create_database(url: &str) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async {
-            driver::from_url_str(url)?
+            let url = normalize_url(url)?;
+
+            driver::from_url_str(&url)?
                 .get_migrate_database()?
-                .create_database(url)
+                .create_database(&url)
                 .await
         })
     }
 
     fn database_exists(url: &str) -> BoxFuture<'_, Result<bool, Error>> {
         Box::pin(async {
-            driver::from_url_str(url)?
+            let url = normalize_url(url)?;
+
+            driver::from_url_str(&url)?
                 .get_migrate_database()?
-                .database_exists(url)
+                .database_exists(&url)
                 .await
         })
     }
 
     fn drop_database(url: &str) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async {
-            driver::from_url_str(url)?
+            let url = normalize_url(url)?;
+
+            driver::from_url_str(&url)?
                 .get_migrate_database()?
-                .drop_database(url)
+                .drop_database(&url)
                 .await
         })
     }
@@ -70,4 +79,35 @@ impl Migrate for AnyConnection {
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
         Box::pin(async { self.get_migrate()?.revert(migration).await })
     }
+
+    fn apply_timed<'e: 'm, 'm>(
+        &'e mut self,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(Duration, Vec<Duration>), MigrateError>> {
+        Box::pin(async { self.get_migrate()?.apply_timed(migration).await })
+    }
+
+    fn revert_timed<'e: 'm, 'm>(
+        &'e mut self,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(Duration, Vec<Duration>), MigrateError>> {
+        Box::pin(async { self.get_migrate()?.revert_timed(migration).await })
+    }
+
+    fn ensure_migrations_log_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+        Box::pin(async { self.get_migrate()?.ensure_migrations_log_table().await })
+    }
+
+    fn log_migration<'e: 'm, 'm>(
+        &'e mut self,
+        entry: &'m MigrationLogEntry,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async { self.get_migrate()?.log_migration(entry).await })
+    }
+
+    fn list_migrations_log(
+        &mut self,
+    ) -> BoxFuture<'_, Result<Vec<MigrationLogEntry>, MigrateError>> {
+        Box::pin(async { self.get_migrate()?.list_migrations_log().await })
+    }
 }
@@ -3,9 +3,16 @@
 //! The underlying database drivers are chosen at runtime from the list set via
 //! [`install_drivers`][self::driver::install_drivers). Any use of `AnyConnection` or `AnyPool`
 //! without this will panic.
+//!
+//! SQL is passed to the underlying driver as is, so it must be valid for every database in use.
+//! Notably, MySQL doesn't support `RETURNING`; to get the keys generated by an `INSERT` on any
+//! database, use [`QueryBuilder::fetch_returning()`][crate::query_builder::QueryBuilder::fetch_returning],
+//! which emulates it on MySQL for plain inserts, within the limits it documents.
 use crate::executor::Executor;
+use crate::query::Query;
 
 mod arguments;
+mod capabilities;
 pub(crate) mod column;
 mod connection;
 mod database;
@@ -15,8 +22,11 @@ mod options;
 mod query_result;
 pub(crate) mod row;
 mod statement;
+#[doc(hidden)]
+pub mod text;
 mod transaction;
 pub(crate) mod type_info;
+mod type_registry;
 pub mod types;
 pub(crate) mod value;
 
@@ -26,12 +36,13 @@ pub mod driver;
 mod migrate;
 
 pub use arguments::{AnyArgumentBuffer, AnyArguments};
+pub use capabilities::AnyCapabilities;
 pub use column::AnyColumn;
 pub use connection::AnyConnection;
 // Used internally in `sqlx-macros`
 
 use crate::encode::Encode;
-pub use connection::AnyConnectionBackend;
+pub use connection::{AnyConnectionBackend, AnyOnConflict};
 pub use database::Any;
 pub use kind::AnyKind;
 pub use options::AnyConnectOptions;
@@ -40,15 +51,40 @@ pub use row::AnyRow;
 pub use statement::AnyStatement;
 pub use transaction::AnyTransactionManager;
 pub use type_info::{AnyTypeInfo, AnyTypeInfoKind};
-pub use value::{AnyValue, AnyValueRef};
+pub use type_registry::AnyTypeRegistry;
+pub use value::{AnyValue, AnyValueKind, AnyValueRef};
 
 #[doc(hidden)]
-pub use value::AnyValueKind;
+pub use value::{AnyArgumentValue, AnyDecodedValue};
 
 pub type AnyPool = crate::pool::Pool<Any>;
 
 pub type AnyPoolOptions = crate::pool::PoolOptions<Any>;
 
+/// Make a SQL query binding a list of values determined at runtime, e.g. a filter built from
+/// a request, without a concrete arguments type.
+///
+/// See also [`QueryBuilder`][crate::query_builder::QueryBuilder] for building the SQL itself.
+///
+/// ```rust,ignore
+/// use sqlx::any::{AnyValue, AnyValueKind};
+///
+/// let values = vec![
+///     AnyValue::new(AnyValueKind::Text("open".into())),
+///     AnyValue::new(AnyValueKind::BigInt(10)),
+/// ];
+///
+/// let rows = sqlx::any::query_with_values(
+///     "SELECT * FROM issues WHERE status = $1 AND priority > $2",
+///     values,
+/// )
+/// .fetch_all(&pool)
+/// .await?;
+/// ```
+pub fn query_with_values(sql: &str, values: Vec<AnyValue>) -> Query<'_, Any, AnyArguments<'_>> {
+    crate::query::query_with(sql, AnyArguments::from_values(values))
+}
+
 /// An alias for [`Executor<'_, Database = Any>`][Executor].
 pub trait AnyExecutor<'c>: Executor<'c, Database = Any> {}
 impl<'c, T: Executor<'c, Database = Any>> AnyExecutor<'c> for T {}
@@ -58,6 +94,7 @@ impl_into_arguments_for_arguments!(AnyArguments<'q>);
 // impl_executor_for_pool_connection!(Any, AnyConnection, AnyRow);
 // impl_executor_for_transaction!(Any, AnyRow);
 impl_acquire!(Any, AnyConnection);
+impl_middleware_connection!(Any);
 impl_column_index_for_row!(AnyRow);
 impl_column_index_for_statement!(AnyStatement);
 // impl_into_maybe_pool!(Any, AnyConnection);
@@ -1,5 +1,8 @@
 use crate::any::error::mismatched_types;
-use crate::any::{Any, AnyColumn, AnyTypeInfo, AnyTypeInfoKind, AnyValue, AnyValueKind};
+use crate::any::{
+    Any, AnyColumn, AnyDecodedValue, AnyTypeInfo, AnyTypeInfoKind, AnyTypeRegistry, AnyValue,
+    AnyValueKind,
+};
 use crate::column::{Column, ColumnIndex};
 use crate::database::{Database, HasValueRef};
 use crate::decode::Decode;
@@ -85,6 +88,7 @@ impl AnyRow {
     pub fn map_from<'a, R: Row>(
         row: &'a R,
         column_names: Arc<crate::HashMap<UStr, usize>>,
+        registry: &AnyTypeRegistry,
     ) -> Result<Self, Error>
     where
         usize: ColumnIndex<R>,
@@ -98,6 +102,7 @@ impl AnyRow {
         f64: Type<R::Database> + Decode<'a, R::Database>,
         String: Type<R::Database> + Decode<'a, R::Database>,
         Vec<u8>: Type<R::Database> + Decode<'a, R::Database>,
+        AnyDecodedValue: Decode<'a, R::Database>,
     {
         let mut row_out = AnyRow {
             column_names,
@@ -108,16 +113,18 @@ impl AnyRow {
         for col in row.columns() {
             let i = col.ordinal();
 
-            let any_col = AnyColumn::try_from(col)?;
+            let any_col = registry.column(col)?;
 
             let value = row.try_get_raw(i)?;
 
             // Map based on the _value_ type info, not the column type info.
             let type_info =
-                AnyTypeInfo::try_from(&value.type_info()).map_err(|e| Error::ColumnDecode {
-                    index: col.ordinal().to_string(),
-                    source: e.into(),
-                })?;
+                registry
+                    .type_info(&*value.type_info())
+                    .map_err(|e| Error::ColumnDecode {
+                        index: col.ordinal().to_string(),
+                        source: e.into(),
+                    })?;
 
             let value_kind = match type_info.kind {
                 _ if value.is_null() => AnyValueKind::Null,
@@ -130,6 +137,14 @@ impl AnyRow {
                 AnyTypeInfoKind::Double => AnyValueKind::Double(decode(value)?),
                 AnyTypeInfoKind::Blob => AnyValueKind::Blob(decode::<_, Vec<u8>>(value)?.into()),
                 AnyTypeInfoKind::Text => AnyValueKind::Text(decode::<_, String>(value)?.into()),
+                AnyTypeInfoKind::Numeric
+                | AnyTypeInfoKind::Date
+                | AnyTypeInfoKind::Time
+                | AnyTypeInfoKind::Timestamp
+                | AnyTypeInfoKind::TimestampTz
+                | AnyTypeInfoKind::Uuid
+                | AnyTypeInfoKind::Json
+                | AnyTypeInfoKind::Array => decode::<_, AnyDecodedValue>(value)?.0,
             };
 
             row_out.columns.push(any_col);
@@ -1,4 +1,4 @@
-use crate::any::{Any, AnyArguments, AnyColumn, AnyTypeInfo};
+use crate::any::{Any, AnyArguments, AnyColumn, AnyTypeInfo, AnyTypeRegistry};
 use crate::column::ColumnIndex;
 use crate::database::Database;
 use crate::error::Error;
@@ -67,6 +67,7 @@ impl<'q> AnyStatement<'q> {
         query: &'q str,
         statement: &S,
         column_names: Arc<HashMap<UStr, usize>>,
+        registry: &AnyTypeRegistry,
     ) -> crate::Result<Self>
     where
         S: Statement<'q>,
@@ -77,7 +78,7 @@ impl<'q> AnyStatement<'q> {
             Some(Either::Left(parameters)) => Some(Either::Left(
                 parameters
                     .iter()
-                    .map(AnyTypeInfo::try_from)
+                    .map(|ty| registry.type_info(ty))
                     .collect::<Result<Vec<_>, _>>()?,
             )),
             Some(Either::Right(count)) => Some(Either::Right(count)),
@@ -87,7 +88,7 @@ impl<'q> AnyStatement<'q> {
         let columns = statement
             .columns()
             .iter()
-            .map(AnyColumn::try_from)
+            .map(|column| registry.column(column))
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(Self {
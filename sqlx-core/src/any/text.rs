@@ -0,0 +1,373 @@
+//! Conversions between the `Any` representations of dates, times, UUIDs and arrays
+//! and their text forms, shared by the drivers.
+//!
+//! Dates are days since `1970-01-01`, and times and timestamps microseconds since midnight
+//! and `1970-01-01 00:00:00` respectively; see [`AnyValueKind`].
+//!
+//! [`AnyValueKind`]: crate::any::AnyValueKind
+
+use std::fmt::{self, Write};
+
+use crate::any::AnyValueKind;
+use crate::error::BoxDynError;
+
+pub const MICROS_PER_SECOND: i64 = 1_000_000;
+pub const MICROS_PER_DAY: i64 = 86_400 * MICROS_PER_SECOND;
+
+/// The number of days since `1970-01-01` of the given date in the proleptic Gregorian calendar.
+pub fn days_from_civil(year: i32, month: u32, day: u32) -> i32 {
+    // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = i64::from(year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    (era * 146_097 + day_of_era - 719_468) as i32
+}
+
+/// The year, month and day of the date `days` since `1970-01-01`.
+pub fn civil_from_days(days: i32) -> (i32, u32, u32) {
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = i64::from(days) + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year as i32, month as u32, day as u32)
+}
+
+/// Parse a `YYYY-MM-DD` date into days since `1970-01-01`.
+pub fn parse_date(s: &str) -> Result<i32, BoxDynError> {
+    let invalid = || format!("invalid date: {s:?}");
+
+    let mut parts = s.splitn(3, '-');
+    let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(invalid().into());
+    };
+
+    let year: i32 = parse_digits(year).ok_or_else(invalid)?;
+    let month: u32 = parse_digits(month).ok_or_else(invalid)?;
+    let day: u32 = parse_digits(day).ok_or_else(invalid)?;
+
+    let days = days_from_civil(year, month, day);
+
+    // out-of-range months and days wrap around, e.g. to the next month
+    if civil_from_days(days) != (year, month, day) {
+        return Err(invalid().into());
+    }
+
+    Ok(days)
+}
+
+/// Parse a `[-]HH:MM:SS[.ffffff]` time into microseconds.
+///
+/// Hours may exceed 24, and the time may be negative, as for MySQL's `TIME`.
+pub fn parse_time(s: &str) -> Result<i64, BoxDynError> {
+    let invalid = || format!("invalid time: {s:?}");
+
+    let (negative, unsigned) = match s.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, s),
+    };
+
+    let (hms, fraction) = match unsigned.split_once('.') {
+        Some((hms, fraction)) => (hms, Some(fraction)),
+        None => (unsigned, None),
+    };
+
+    let mut parts = hms.splitn(3, ':');
+    let (Some(hours), Some(minutes), Some(seconds)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid().into());
+    };
+
+    let hours: i64 = parse_digits(hours).ok_or_else(invalid)?;
+    let minutes: i64 = parse_digits(minutes)
+        .filter(|m| *m < 60)
+        .ok_or_else(invalid)?;
+    let seconds: i64 = parse_digits(seconds)
+        .filter(|s| *s < 60)
+        .ok_or_else(invalid)?;
+
+    let micros = match fraction {
+        Some(fraction) if fraction.len() > 6 => {
+            return Err(format!("time has more than microsecond precision: {s:?}").into())
+        }
+        Some(fraction) => {
+            let digits: i64 = parse_digits(fraction).ok_or_else(invalid)?;
+            digits * 10_i64.pow(6 - fraction.len() as u32)
+        }
+        None => 0,
+    };
+
+    let time = ((hours * 60 + minutes) * 60 + seconds) * MICROS_PER_SECOND + micros;
+
+    Ok(if negative { -time } else { time })
+}
+
+/// Parse a `YYYY-MM-DD HH:MM:SS[.ffffff]` timestamp into microseconds since
+/// `1970-01-01 00:00:00`. The date and time may also be separated by `T`.
+pub fn parse_timestamp(s: &str) -> Result<i64, BoxDynError> {
+    let Some((date, time)) = s.split_once([' ', 'T']) else {
+        return Err(format!("invalid timestamp: {s:?}").into());
+    };
+
+    let days = parse_date(date)?;
+    let time = parse_time(time)?;
+
+    if !(0..MICROS_PER_DAY).contains(&time) {
+        return Err(format!("invalid timestamp: {s:?}").into());
+    }
+
+    Ok(i64::from(days) * MICROS_PER_DAY + time)
+}
+
+/// Parse a timestamp with a UTC offset (`Z`, `+HH`, `+HH:MM` or `+HH:MM:SS`) into microseconds
+/// since `1970-01-01 00:00:00` UTC.
+pub fn parse_timestamptz(s: &str) -> Result<i64, BoxDynError> {
+    let invalid = || format!("invalid timestamp with time zone: {s:?}");
+
+    // the date contains `-`, so only look for the offset after it
+    let time_start = s.find([' ', 'T']).ok_or_else(invalid)?;
+    let offset_start = s[time_start..]
+        .find(['+', '-', 'Z'])
+        .map(|i| time_start + i)
+        .ok_or_else(invalid)?;
+
+    let timestamp = parse_timestamp(&s[..offset_start])?;
+
+    let offset = match &s[offset_start..] {
+        "Z" => 0,
+        offset => {
+            let (sign, offset) = offset.split_at(1);
+            let mut parts = offset.split(':');
+            let mut offset = 0;
+
+            for unit in [3_600, 60, 1] {
+                let Some(part) = parts.next() else { break };
+                let part: i64 = parse_digits(part).ok_or_else(invalid)?;
+                offset += part * unit;
+            }
+
+            if parts.next().is_some() {
+                return Err(invalid().into());
+            }
+
+            if sign == "-" {
+                -offset
+            } else {
+                offset
+            }
+        }
+    };
+
+    Ok(timestamp - offset * MICROS_PER_SECOND)
+}
+
+/// Parse a UUID in its hyphenated or simple form.
+pub fn parse_uuid(s: &str) -> Result<[u8; 16], BoxDynError> {
+    let digits = s.replace('-', "");
+    let mut uuid = [0; 16];
+
+    hex::decode_to_slice(&digits, &mut uuid).map_err(|_| format!("invalid UUID: {s:?}"))?;
+
+    Ok(uuid)
+}
+
+fn parse_digits<T: std::str::FromStr>(s: &str) -> Option<T> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+/// Write a date as `YYYY-MM-DD`.
+pub fn write_date(f: &mut impl Write, days: i32) -> fmt::Result {
+    let (year, month, day) = civil_from_days(days);
+    write!(f, "{year:04}-{month:02}-{day:02}")
+}
+
+/// Write a time as `HH:MM:SS`, followed by as many fractional digits as needed.
+pub fn write_time(f: &mut impl Write, micros: i64) -> fmt::Result {
+    if micros < 0 {
+        f.write_char('-')?;
+    }
+
+    let micros = micros.unsigned_abs();
+    let seconds = micros / MICROS_PER_SECOND as u64;
+
+    write!(
+        f,
+        "{:02}:{:02}:{:02}",
+        seconds / 3_600,
+        seconds / 60 % 60,
+        seconds % 60
+    )?;
+
+    let fraction = micros % MICROS_PER_SECOND as u64;
+
+    if fraction != 0 {
+        let fraction = format!("{fraction:06}");
+        write!(f, ".{}", fraction.trim_end_matches('0'))?;
+    }
+
+    Ok(())
+}
+
+/// Write a timestamp as `YYYY-MM-DD HH:MM:SS`, followed by as many fractional digits as needed.
+pub fn write_timestamp(f: &mut impl Write, micros: i64) -> fmt::Result {
+    let days = micros.div_euclid(MICROS_PER_DAY);
+    let days = i32::try_from(days).map_err(|_| fmt::Error)?;
+
+    write_date(f, days)?;
+    f.write_char(' ')?;
+    write_time(f, micros.rem_euclid(MICROS_PER_DAY))
+}
+
+/// Write a UUID in its hyphenated form.
+pub fn write_uuid(f: &mut impl Write, uuid: &[u8; 16]) -> fmt::Result {
+    for (i, byte) in uuid.iter().enumerate() {
+        if matches!(i, 4 | 6 | 8 | 10) {
+            f.write_char('-')?;
+        }
+
+        write!(f, "{byte:02x}")?;
+    }
+
+    Ok(())
+}
+
+/// Write the elements of an array as a JSON array, for databases without array types.
+///
+/// Dates, times and UUIDs are written as strings, and blobs as strings of hex digits.
+pub fn write_json_array(f: &mut impl Write, values: &[AnyValueKind<'_>]) -> fmt::Result {
+    f.write_char('[')?;
+
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            f.write_char(',')?;
+        }
+
+        match value {
+            AnyValueKind::Null => f.write_str("null")?,
+            AnyValueKind::Bool(b) => write!(f, "{b}")?,
+            AnyValueKind::SmallInt(i) => write!(f, "{i}")?,
+            AnyValueKind::Integer(i) => write!(f, "{i}")?,
+            AnyValueKind::BigInt(i) => write!(f, "{i}")?,
+            AnyValueKind::Real(r) if r.is_finite() => write!(f, "{r}")?,
+            AnyValueKind::Double(d) if d.is_finite() => write!(f, "{d}")?,
+            AnyValueKind::Numeric(n) if n.parse::<f64>().is_ok_and(f64::is_finite) => {
+                f.write_str(n)?
+            }
+            AnyValueKind::Json(json) => f.write_str(json)?,
+            AnyValueKind::Array { values, .. } => write_json_array(f, values)?,
+            other => write_json_string(f, &other.to_string())?,
+        }
+    }
+
+    f.write_char(']')
+}
+
+fn write_json_string(f: &mut impl Write, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+
+    for ch in s.chars() {
+        match ch {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            ch if ch.is_control() => write!(f, "\\u{:04x}", ch as u32)?,
+            ch => f.write_char(ch)?,
+        }
+    }
+
+    f.write_char('"')
+}
+
+#[test]
+fn test_dates() {
+    assert_eq!(days_from_civil(1970, 1, 1), 0);
+    assert_eq!(days_from_civil(2000, 1, 1), 10_957);
+    assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+
+    assert_eq!(parse_date("2024-02-29").unwrap(), 19_782);
+    assert!(parse_date("2023-02-29").is_err());
+    assert!(parse_date("2023-13-01").is_err());
+    assert!(parse_date("yesterday").is_err());
+
+    let mut s = String::new();
+    write_date(&mut s, 19_782).unwrap();
+    assert_eq!(s, "2024-02-29");
+}
+
+#[test]
+fn test_times() {
+    assert_eq!(parse_time("00:00:01").unwrap(), 1_000_000);
+    assert_eq!(parse_time("12:34:56.5").unwrap(), 45_296_500_000);
+    assert_eq!(parse_time("-838:59:59").unwrap(), -3_020_399_000_000);
+    assert!(parse_time("12:60:00").is_err());
+    assert!(parse_time("12:00:00.0000001").is_err());
+
+    let mut s = String::new();
+    write_time(&mut s, 45_296_500_000).unwrap();
+    assert_eq!(s, "12:34:56.5");
+
+    s.clear();
+    write_time(&mut s, -3_020_399_000_000).unwrap();
+    assert_eq!(s, "-838:59:59");
+}
+
+#[test]
+fn test_timestamps() {
+    let ts = parse_timestamp("2024-02-29 12:34:56.000789").unwrap();
+    assert_eq!(ts, 19_782 * MICROS_PER_DAY + 45_296_000_789);
+    assert_eq!(parse_timestamp("2024-02-29T12:34:56.000789").unwrap(), ts);
+    assert!(parse_timestamp("2024-02-29 24:00:00").is_err());
+
+    assert_eq!(
+        parse_timestamptz("2024-02-29 12:34:56.000789+00").unwrap(),
+        ts
+    );
+    assert_eq!(
+        parse_timestamptz("2024-02-29T12:34:56.000789Z").unwrap(),
+        ts
+    );
+    assert_eq!(
+        parse_timestamptz("2024-02-29 14:04:56.000789+01:30").unwrap(),
+        ts
+    );
+    assert_eq!(
+        parse_timestamptz("2024-02-29 11:34:56.000789-01").unwrap(),
+        ts
+    );
+
+    let mut s = String::new();
+    write_timestamp(&mut s, -1).unwrap();
+    assert_eq!(s, "1969-12-31 23:59:59.999999");
+}
+
+#[test]
+fn test_uuids() {
+    let uuid = parse_uuid("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+    assert_eq!(uuid[0], 0x67);
+    assert_eq!(uuid[15], 0xc8);
+
+    let mut s = String::new();
+    write_uuid(&mut s, &uuid).unwrap();
+    assert_eq!(s, "67e55044-10b1-426f-9247-bb680e5fe0c8");
+
+    assert!(parse_uuid("67e55044").is_err());
+}
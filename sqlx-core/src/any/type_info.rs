@@ -17,6 +17,7 @@ impl AnyTypeInfo {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum AnyTypeInfoKind {
     Null,
     Bool,
@@ -27,6 +28,14 @@ pub enum AnyTypeInfoKind {
     Double,
     Text,
     Blob,
+    Numeric,
+    Date,
+    Time,
+    Timestamp,
+    TimestampTz,
+    Uuid,
+    Json,
+    Array,
 }
 
 impl TypeInfo for AnyTypeInfo {
@@ -46,6 +55,14 @@ impl TypeInfo for AnyTypeInfo {
             Double => "DOUBLE",
             Text => "TEXT",
             Blob => "BLOB",
+            Numeric => "NUMERIC",
+            Date => "DATE",
+            Time => "TIME",
+            Timestamp => "TIMESTAMP",
+            TimestampTz => "TIMESTAMPTZ",
+            Uuid => "UUID",
+            Json => "JSON",
+            Array => "ARRAY",
             Null => "NULL",
         }
     }
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use crate::any::{AnyColumn, AnyTypeInfo, AnyTypeInfoKind};
+use crate::column::Column;
+use crate::error::Error;
+use crate::type_info::TypeInfo;
+use crate::HashMap;
+
+/// Maps database types that the `Any` driver doesn't support to a kind of value that it does, by
+/// the name of the type.
+///
+/// This lets rows with extension or user-defined types, like a Postgres `citext`, `ltree` or enum
+/// type, or a MySQL `ENUM` or `SET` column, be decoded by [`AnyConnection`][crate::any::AnyConnection]
+/// without changes to the driver. Names are the ones in [`TypeInfo::name()`] of the driver and
+/// are matched case-insensitively.
+///
+/// A value of a registered type is decoded as the Rust type of its kind, e.g. a `String` for
+/// [`AnyTypeInfoKind::Text`], so the value must be sent in the same format as that type. Only the
+/// kinds of values that are decoded as a plain Rust type can be registered: `Bool`, `SmallInt`,
+/// `Integer`, `BigInt`, `Real`, `Double`, `Text` and `Blob`.
+///
+/// Types that the driver supports are never looked up in the registry.
+///
+/// ```rust,no_run
+/// # async fn example() -> sqlx::Result<()> {
+/// use sqlx::any::{AnyConnectOptions, AnyTypeInfoKind, AnyTypeRegistry};
+/// use sqlx::ConnectOptions;
+///
+/// let registry = AnyTypeRegistry::new()
+///     .register("citext", AnyTypeInfoKind::Text)
+///     .register("mood", AnyTypeInfoKind::Text);
+///
+/// let mut conn = "postgres://localhost/db"
+///     .parse::<AnyConnectOptions>()?
+///     .type_registry(registry)
+///     .connect()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AnyTypeRegistry {
+    // shared, as every connection and query gets a copy
+    types: Arc<HashMap<String, AnyTypeInfoKind>>,
+}
+
+impl AnyTypeRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Maps the type named `name` to `kind`.
+    ///
+    /// # Panics
+    /// If values of `kind` aren't decoded as a plain Rust type; see the type-level docs.
+    pub fn register(mut self, name: impl AsRef<str>, kind: AnyTypeInfoKind) -> Self {
+        use AnyTypeInfoKind::*;
+
+        assert!(
+            matches!(
+                kind,
+                Bool | SmallInt | Integer | BigInt | Real | Double | Text | Blob
+            ),
+            "`AnyTypeInfoKind::{kind:?}` can't be registered for a type; see `AnyTypeRegistry`"
+        );
+
+        Arc::make_mut(&mut self.types).insert(name.as_ref().to_ascii_lowercase(), kind);
+        self
+    }
+
+    /// The kind registered for the type named `name`, if any.
+    pub fn get(&self, name: &str) -> Option<AnyTypeInfoKind> {
+        if self.types.is_empty() {
+            return None;
+        }
+
+        self.types.get(&name.to_ascii_lowercase()).copied()
+    }
+
+    /// Maps `ty` like the driver does, falling back to the registry for types the driver doesn't
+    /// support.
+    #[doc(hidden)]
+    pub fn type_info<T>(&self, ty: &T) -> Result<AnyTypeInfo, Error>
+    where
+        T: TypeInfo,
+        AnyTypeInfo: for<'a> TryFrom<&'a T, Error = Error>,
+    {
+        AnyTypeInfo::try_from(ty).or_else(|e| {
+            self.get(ty.name())
+                .map(|kind| AnyTypeInfo { kind })
+                .ok_or(e)
+        })
+    }
+
+    /// Maps `column` like the driver does, falling back to the registry for types the driver
+    /// doesn't support.
+    #[doc(hidden)]
+    pub fn column<C>(&self, column: &C) -> Result<AnyColumn, Error>
+    where
+        C: Column,
+        AnyColumn: for<'a> TryFrom<&'a C, Error = Error>,
+    {
+        AnyColumn::try_from(column).or_else(|e| match self.get(column.type_info().name()) {
+            Some(kind) => Ok(AnyColumn {
+                ordinal: column.ordinal(),
+                name: column.name().to_owned().into(),
+                type_info: AnyTypeInfo { kind },
+            }),
+            None => Err(e),
+        })
+    }
+}
+
+#[test]
+fn test_type_registry() {
+    let registry = AnyTypeRegistry::new()
+        .register("citext", AnyTypeInfoKind::Text)
+        .register("ENUM", AnyTypeInfoKind::Text);
+
+    assert_eq!(registry.get("CITEXT"), Some(AnyTypeInfoKind::Text));
+    assert_eq!(registry.get("enum"), Some(AnyTypeInfoKind::Text));
+    assert_eq!(registry.get("ltree"), None);
+
+    // copies don't see later registrations
+    let copy = registry.clone().register("ltree", AnyTypeInfoKind::Text);
+    assert_eq!(copy.get("ltree"), Some(AnyTypeInfoKind::Text));
+    assert_eq!(registry.get("ltree"), None);
+}
+
+#[test]
+#[should_panic]
+fn test_type_registry_unsupported_kind() {
+    let _ = AnyTypeRegistry::new().register("jsonpath", AnyTypeInfoKind::Json);
+}
@@ -11,17 +11,21 @@
 //! | `f32`                                 | FLOAT                                                |
 //! | `f64`                                 | DOUBLE                                               |
 //! | `&str`, [`String`]                    | VARCHAR, CHAR, TEXT                                  |
+//! | [`AnyValue`]                          | Any                                                  |
 //!
 //! # Nullable
 //!
 //! In addition, `Option<T>` is supported where `T` implements `Type`. An `Option<T>` represents
 //! a potentially `NULL` value from SQL.
+//!
+//! [`AnyValue`]: crate::any::AnyValue
 
 mod blob;
 mod bool;
 mod float;
 mod int;
 mod str;
+mod value;
 
 #[test]
 fn test_type_impls() {
@@ -50,4 +54,6 @@ fn test_type_impls() {
     // These imply that there are also impls for the equivalent slice types.
     has_type::<Vec<u8>>();
     has_type::<String>();
+
+    has_type::<crate::any::AnyValue>();
 }
@@ -0,0 +1,53 @@
+use crate::any::{Any, AnyTypeInfo, AnyTypeInfoKind, AnyValue, AnyValueKind};
+use crate::database::{HasArguments, HasValueRef};
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+use crate::value::ValueRef;
+
+/// An `AnyValue` may hold a value of any type, so it's compatible with every column.
+impl Type<Any> for AnyValue {
+    fn type_info() -> AnyTypeInfo {
+        AnyTypeInfo {
+            kind: AnyTypeInfoKind::Null,
+        }
+    }
+
+    fn compatible(_ty: &AnyTypeInfo) -> bool {
+        true
+    }
+}
+
+impl<'q> Encode<'q, Any> for AnyValue {
+    fn encode(self, buf: &mut <Any as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+        let is_null = self.is_null_kind();
+        buf.0.push(self.kind);
+        is_null
+    }
+
+    fn encode_by_ref(&self, buf: &mut <Any as HasArguments<'q>>::ArgumentBuffer) -> IsNull {
+        buf.0.push(self.kind.clone());
+        self.is_null_kind()
+    }
+
+    fn produces(&self) -> Option<AnyTypeInfo> {
+        Some(self.kind.type_info())
+    }
+}
+
+impl<'r> Decode<'r, Any> for AnyValue {
+    fn decode(value: <Any as HasValueRef<'r>>::ValueRef) -> Result<Self, BoxDynError> {
+        Ok(ValueRef::to_owned(&value))
+    }
+}
+
+impl AnyValue {
+    fn is_null_kind(&self) -> IsNull {
+        if let AnyValueKind::Null = self.kind {
+            IsNull::Yes
+        } else {
+            IsNull::No
+        }
+    }
+}
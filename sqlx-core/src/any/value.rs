@@ -1,12 +1,17 @@
 use std::borrow::Cow;
+use std::fmt;
 
-use crate::any::{Any, AnyTypeInfo, AnyTypeInfoKind};
+use crate::any::{text, Any, AnyTypeInfo, AnyTypeInfoKind};
 use crate::database::{Database, HasValueRef};
 use crate::error::BoxDynError;
 use crate::types::Type;
 use crate::value::{Value, ValueRef};
 
-#[derive(Clone, Debug)]
+/// A value of one of the types supported by the `Any` driver.
+///
+/// Each kind is lossless for the database types that map to it: values the drivers can't
+/// represent exactly in a standard Rust type, such as decimals, are kept in their text form.
+#[derive(Clone, Debug, PartialEq)]
 #[non_exhaustive]
 pub enum AnyValueKind<'a> {
     Null,
@@ -18,10 +23,35 @@ pub enum AnyValueKind<'a> {
     Double(f64),
     Text(Cow<'a, str>),
     Blob(Cow<'a, [u8]>),
+    /// An exact decimal number such as `-1234.5600`, with the scale of the database value.
+    ///
+    /// May also be `NaN` for Postgres.
+    Numeric(Cow<'a, str>),
+    /// A date, in days since `1970-01-01`.
+    Date(i32),
+    /// A time, in microseconds since midnight.
+    ///
+    /// For MySQL's `TIME`, this is an interval which may be negative or longer than a day.
+    Time(i64),
+    /// A date and time without a time zone, in microseconds since `1970-01-01 00:00:00`.
+    Timestamp(i64),
+    /// A point in time, in microseconds since `1970-01-01 00:00:00` UTC.
+    TimestampTz(i64),
+    Uuid([u8; 16]),
+    /// A JSON document, in its text form.
+    Json(Cow<'a, str>),
+    /// A one-dimensional array of values of the `element` type.
+    ///
+    /// Databases without array types, i.e. MySQL and SQLite, are sent arrays as JSON text.
+    Array {
+        element: AnyTypeInfoKind,
+        values: Vec<AnyValueKind<'a>>,
+    },
 }
 
 impl AnyValueKind<'_> {
-    fn type_info(&self) -> AnyTypeInfo {
+    /// The type of this value.
+    pub fn type_info(&self) -> AnyTypeInfo {
         AnyTypeInfo {
             kind: match self {
                 AnyValueKind::Null => AnyTypeInfoKind::Null,
@@ -33,6 +63,66 @@ impl AnyValueKind<'_> {
                 AnyValueKind::Double(_) => AnyTypeInfoKind::Double,
                 AnyValueKind::Text(_) => AnyTypeInfoKind::Text,
                 AnyValueKind::Blob(_) => AnyTypeInfoKind::Blob,
+                AnyValueKind::Numeric(_) => AnyTypeInfoKind::Numeric,
+                AnyValueKind::Date(_) => AnyTypeInfoKind::Date,
+                AnyValueKind::Time(_) => AnyTypeInfoKind::Time,
+                AnyValueKind::Timestamp(_) => AnyTypeInfoKind::Timestamp,
+                AnyValueKind::TimestampTz(_) => AnyTypeInfoKind::TimestampTz,
+                AnyValueKind::Uuid(_) => AnyTypeInfoKind::Uuid,
+                AnyValueKind::Json(_) => AnyTypeInfoKind::Json,
+                AnyValueKind::Array { .. } => AnyTypeInfoKind::Array,
+            },
+        }
+    }
+
+    /// Borrow this value, e.g. to bind it without copying text or blobs.
+    pub fn as_borrowed(&self) -> AnyValueKind<'_> {
+        match self {
+            AnyValueKind::Null => AnyValueKind::Null,
+            AnyValueKind::Bool(b) => AnyValueKind::Bool(*b),
+            AnyValueKind::SmallInt(i) => AnyValueKind::SmallInt(*i),
+            AnyValueKind::Integer(i) => AnyValueKind::Integer(*i),
+            AnyValueKind::BigInt(i) => AnyValueKind::BigInt(*i),
+            AnyValueKind::Real(r) => AnyValueKind::Real(*r),
+            AnyValueKind::Double(d) => AnyValueKind::Double(*d),
+            AnyValueKind::Text(t) => AnyValueKind::Text(Cow::Borrowed(t)),
+            AnyValueKind::Blob(b) => AnyValueKind::Blob(Cow::Borrowed(b)),
+            AnyValueKind::Numeric(n) => AnyValueKind::Numeric(Cow::Borrowed(n)),
+            AnyValueKind::Date(d) => AnyValueKind::Date(*d),
+            AnyValueKind::Time(t) => AnyValueKind::Time(*t),
+            AnyValueKind::Timestamp(t) => AnyValueKind::Timestamp(*t),
+            AnyValueKind::TimestampTz(t) => AnyValueKind::TimestampTz(*t),
+            AnyValueKind::Uuid(u) => AnyValueKind::Uuid(*u),
+            AnyValueKind::Json(j) => AnyValueKind::Json(Cow::Borrowed(j)),
+            AnyValueKind::Array { element, values } => AnyValueKind::Array {
+                element: *element,
+                values: values.iter().map(AnyValueKind::as_borrowed).collect(),
+            },
+        }
+    }
+
+    /// Copy any borrowed text or blobs so this value no longer borrows from a row.
+    pub fn into_owned(self) -> AnyValueKind<'static> {
+        match self {
+            AnyValueKind::Null => AnyValueKind::Null,
+            AnyValueKind::Bool(b) => AnyValueKind::Bool(b),
+            AnyValueKind::SmallInt(i) => AnyValueKind::SmallInt(i),
+            AnyValueKind::Integer(i) => AnyValueKind::Integer(i),
+            AnyValueKind::BigInt(i) => AnyValueKind::BigInt(i),
+            AnyValueKind::Real(r) => AnyValueKind::Real(r),
+            AnyValueKind::Double(d) => AnyValueKind::Double(d),
+            AnyValueKind::Text(t) => AnyValueKind::Text(Cow::Owned(t.into_owned())),
+            AnyValueKind::Blob(b) => AnyValueKind::Blob(Cow::Owned(b.into_owned())),
+            AnyValueKind::Numeric(n) => AnyValueKind::Numeric(Cow::Owned(n.into_owned())),
+            AnyValueKind::Date(d) => AnyValueKind::Date(d),
+            AnyValueKind::Time(t) => AnyValueKind::Time(t),
+            AnyValueKind::Timestamp(t) => AnyValueKind::Timestamp(t),
+            AnyValueKind::TimestampTz(t) => AnyValueKind::TimestampTz(t),
+            AnyValueKind::Uuid(u) => AnyValueKind::Uuid(u),
+            AnyValueKind::Json(j) => AnyValueKind::Json(Cow::Owned(j.into_owned())),
+            AnyValueKind::Array { element, values } => AnyValueKind::Array {
+                element,
+                values: values.into_iter().map(AnyValueKind::into_owned).collect(),
             },
         }
     }
@@ -57,33 +147,93 @@ impl AnyValueKind<'_> {
     }
 }
 
+/// Renders values for display, e.g. for exporting rows as CSV.
+///
+/// Text and numbers are written as-is, blobs as hex digits prefixed with `\x`, dates and times
+/// in ISO 8601 format, UUIDs hyphenated, and arrays as JSON arrays. This is not a SQL literal.
+impl fmt::Display for AnyValueKind<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnyValueKind::Null => f.write_str("NULL"),
+            AnyValueKind::Bool(b) => write!(f, "{b}"),
+            AnyValueKind::SmallInt(i) => write!(f, "{i}"),
+            AnyValueKind::Integer(i) => write!(f, "{i}"),
+            AnyValueKind::BigInt(i) => write!(f, "{i}"),
+            AnyValueKind::Real(r) => write!(f, "{r}"),
+            AnyValueKind::Double(d) => write!(f, "{d}"),
+            AnyValueKind::Text(t) => f.write_str(t),
+            AnyValueKind::Blob(b) => write!(f, "\\x{}", hex::encode(b)),
+            AnyValueKind::Numeric(n) => f.write_str(n),
+            AnyValueKind::Date(d) => text::write_date(f, *d),
+            AnyValueKind::Time(t) => text::write_time(f, *t),
+            AnyValueKind::Timestamp(t) => text::write_timestamp(f, *t),
+            AnyValueKind::TimestampTz(t) => {
+                text::write_timestamp(f, *t)?;
+                f.write_str("+00:00")
+            }
+            AnyValueKind::Uuid(u) => text::write_uuid(f, u),
+            AnyValueKind::Json(j) => f.write_str(j),
+            AnyValueKind::Array { values, .. } => text::write_json_array(f, values),
+        }
+    }
+}
+
+/// A value being bound to a query, for drivers to implement `Encode` for the kinds that have no
+/// standard Rust equivalent.
+#[doc(hidden)]
+pub struct AnyArgumentValue<'a>(pub &'a AnyValueKind<'a>);
+
+/// A value decoded by a driver, for the kinds that have no standard Rust equivalent.
+#[doc(hidden)]
+pub struct AnyDecodedValue(pub AnyValueKind<'static>);
+
 #[derive(Clone, Debug)]
 pub struct AnyValue {
     #[doc(hidden)]
     pub kind: AnyValueKind<'static>,
 }
 
+impl AnyValue {
+    /// Create a value, e.g. for a mock row or to bind with [`AnyArguments`][crate::any::AnyArguments].
+    pub fn new(kind: AnyValueKind<'static>) -> Self {
+        AnyValue { kind }
+    }
+
+    /// The type and contents of this value.
+    pub fn kind(&self) -> &AnyValueKind<'static> {
+        &self.kind
+    }
+
+    /// Unwrap the type and contents of this value.
+    pub fn into_kind(self) -> AnyValueKind<'static> {
+        self.kind
+    }
+}
+
+impl From<AnyValueKind<'static>> for AnyValue {
+    fn from(kind: AnyValueKind<'static>) -> Self {
+        AnyValue { kind }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct AnyValueRef<'a> {
     pub(crate) kind: AnyValueKind<'a>,
 }
 
+impl<'a> AnyValueRef<'a> {
+    /// The type and contents of this value.
+    pub fn kind(&self) -> &AnyValueKind<'a> {
+        &self.kind
+    }
+}
+
 impl Value for AnyValue {
     type Database = Any;
 
     fn as_ref(&self) -> <Self::Database as HasValueRef<'_>>::ValueRef {
         AnyValueRef {
-            kind: match &self.kind {
-                AnyValueKind::Null => AnyValueKind::Null,
-                AnyValueKind::Bool(b) => AnyValueKind::Bool(*b),
-                AnyValueKind::SmallInt(i) => AnyValueKind::SmallInt(*i),
-                AnyValueKind::Integer(i) => AnyValueKind::Integer(*i),
-                AnyValueKind::BigInt(i) => AnyValueKind::BigInt(*i),
-                AnyValueKind::Real(r) => AnyValueKind::Real(*r),
-                AnyValueKind::Double(d) => AnyValueKind::Double(*d),
-                AnyValueKind::Text(t) => AnyValueKind::Text(Cow::Borrowed(t)),
-                AnyValueKind::Blob(b) => AnyValueKind::Blob(Cow::Borrowed(b)),
-            },
+            kind: self.kind.as_borrowed(),
         }
     }
 
@@ -92,7 +242,7 @@ impl Value for AnyValue {
     }
 
     fn is_null(&self) -> bool {
-        false
+        matches!(self.kind, AnyValueKind::Null)
     }
 }
 
@@ -101,17 +251,7 @@ impl<'a> ValueRef<'a> for AnyValueRef<'a> {
 
     fn to_owned(&self) -> <Self::Database as Database>::Value {
         AnyValue {
-            kind: match &self.kind {
-                AnyValueKind::Null => AnyValueKind::Null,
-                AnyValueKind::Bool(b) => AnyValueKind::Bool(*b),
-                AnyValueKind::SmallInt(i) => AnyValueKind::SmallInt(*i),
-                AnyValueKind::Integer(i) => AnyValueKind::Integer(*i),
-                AnyValueKind::BigInt(i) => AnyValueKind::BigInt(*i),
-                AnyValueKind::Real(r) => AnyValueKind::Real(*r),
-                AnyValueKind::Double(d) => AnyValueKind::Double(*d),
-                AnyValueKind::Text(t) => AnyValueKind::Text(Cow::Owned(t.to_string())),
-                AnyValueKind::Blob(b) => AnyValueKind::Blob(Cow::Owned(b.to_vec())),
-            },
+            kind: self.kind.clone().into_owned(),
         }
     }
 
@@ -120,6 +260,6 @@ impl<'a> ValueRef<'a> for AnyValueRef<'a> {
     }
 
     fn is_null(&self) -> bool {
-        false
+        matches!(self.kind, AnyValueKind::Null)
     }
 }
@@ -2,6 +2,7 @@
 
 use crate::database::{Database, HasArguments};
 use crate::encode::Encode;
+use crate::error::Error;
 use crate::types::Type;
 use std::fmt::{self, Write};
 
@@ -21,6 +22,37 @@ pub trait Arguments<'q>: Send + Sized + Default {
     fn format_placeholder<W: Write>(&self, writer: &mut W) -> fmt::Result {
         writer.write_str("?")
     }
+
+    /// Shortens the lifetime of the arguments, e.g. to execute them with SQL that was rewritten
+    /// by [`ExecutorMiddleware`][crate::middleware::ExecutorMiddleware].
+    ///
+    /// This only exists because the compiler can't see that the arguments are covariant in `'q`
+    /// through the associated type, so implementations just return `Some(self)`. The default
+    /// implementation returns `None`, in which case executing the arguments with SQL they don't
+    /// borrow from fails with [`Error::Configuration`].
+    #[doc(hidden)]
+    fn shorten<'a>(self) -> Option<<Self::Database as HasArguments<'a>>::Arguments>
+    where
+        'q: 'a,
+    {
+        None
+    }
+}
+
+/// Shortens the lifetime of `arguments` with [`Arguments::shorten()`], or returns an error if the
+/// database doesn't support it.
+pub(crate) fn shorten<'q: 'a, 'a, DB: Database>(
+    arguments: <DB as HasArguments<'q>>::Arguments,
+) -> Result<<DB as HasArguments<'a>>::Arguments, Error> {
+    arguments.shorten().ok_or_else(|| {
+        Error::Configuration(
+            format!(
+                "{} arguments can't be executed with SQL they don't borrow from",
+                DB::NAME
+            )
+            .into(),
+        )
+    })
 }
 
 pub trait IntoArguments<'q, DB: HasArguments<'q>>: Sized + Send {
@@ -44,6 +76,23 @@ macro_rules! impl_into_arguments_for_arguments {
     };
 }
 
+/// Adds `value` to `arguments` and its placeholder to `sql`, for queries whose lifetimes don't
+/// work out with [`QueryBuilder`][crate::query_builder::QueryBuilder] in generic code.
+#[cfg(any(feature = "backfill", feature = "outbox", feature = "queue"))]
+pub(crate) fn push_bind<'q, DB, T>(
+    sql: &mut String,
+    arguments: &mut <DB as HasArguments<'q>>::Arguments,
+    value: T,
+) where
+    DB: Database,
+    T: 'q + Send + Encode<'q, DB> + Type<DB>,
+{
+    arguments.add(value);
+    arguments
+        .format_placeholder(sql)
+        .expect("error in format_placeholder");
+}
+
 /// used by the query macros to prevent supernumerary `.bind()` calls
 pub struct ImmutableArguments<'q, DB: HasArguments<'q>>(pub <DB as HasArguments<'q>>::Arguments);
 
@@ -54,4 +103,206 @@ impl<'q, DB: HasArguments<'q>> IntoArguments<'q, DB> for ImmutableArguments<'q,
 }
 
 // TODO: Impl `IntoArguments` for &[&dyn Encode]
-// TODO: Impl `IntoArguments` for (impl Encode, ...) x16
+
+// implement IntoArguments for tuples of types that implement Encode
+// up to tuples of 16 values
+
+macro_rules! impl_into_arguments_for_tuple {
+    ($( ($idx:tt) -> $T:ident );+;) => {
+        impl<'q, DB, $($T,)+> IntoArguments<'q, DB> for ($($T,)+)
+        where
+            DB: Database,
+            $($T: 'q + Send + Encode<'q, DB> + Type<DB>,)+
+        {
+            fn into_arguments(self) -> <DB as HasArguments<'q>>::Arguments {
+                let mut arguments = <DB as HasArguments<'q>>::Arguments::default();
+                $(arguments.add(self.$idx);)+
+                arguments
+            }
+        }
+    };
+}
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+    (12) -> T13;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+    (12) -> T13;
+    (13) -> T14;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+    (12) -> T13;
+    (13) -> T14;
+    (14) -> T15;
+);
+
+impl_into_arguments_for_tuple!(
+    (0) -> T1;
+    (1) -> T2;
+    (2) -> T3;
+    (3) -> T4;
+    (4) -> T5;
+    (5) -> T6;
+    (6) -> T7;
+    (7) -> T8;
+    (8) -> T9;
+    (9) -> T10;
+    (10) -> T11;
+    (11) -> T12;
+    (12) -> T13;
+    (13) -> T14;
+    (14) -> T15;
+    (15) -> T16;
+);
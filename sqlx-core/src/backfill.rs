@@ -0,0 +1,421 @@
+//! Long-running data migrations, run in small batches with their progress stored in a table so
+//! they can be stopped and resumed.
+//!
+//! Changing every row of a large table in one statement, e.g. in a migration, can hold locks and
+//! bloat the transaction log for a long time. A [`Backfill`] instead calls a handler repeatedly,
+//! each time with the key of the last row the previous batch processed, and records the key the
+//! batch returns in the same transaction as the batch. If the process is stopped or crashes, the
+//! next run resumes from the last committed batch; once the handler returns `None`, the backfill
+//! is marked as completed and later runs do nothing.
+//!
+//! ```rust,ignore
+//! use sqlx::backfill::Backfill;
+//!
+//! let backfill = Backfill::new("backfills", "users_full_name")
+//!     .batch_size(1000)
+//!     .sleep(Duration::from_millis(100));
+//!
+//! // in a migration, e.g. `migrations/<timestamp>_backfills.sql`:
+//! println!("{}", backfill.migration("PostgreSQL").unwrap());
+//!
+//! backfill
+//!     .run_until(&pool, shutdown_signal(), |conn, batch| {
+//!         Box::pin(async move {
+//!             sqlx::query_scalar(
+//!                 "WITH updated AS (
+//!                      UPDATE users SET full_name = first_name || ' ' || last_name
+//!                      WHERE id IN (
+//!                          SELECT id FROM users WHERE id > $1 ORDER BY id LIMIT $2
+//!                      )
+//!                      RETURNING id
+//!                  )
+//!                  SELECT max(id) FROM updated",
+//!             )
+//!             .bind(batch.last_key.unwrap_or(0))
+//!             .bind(i64::from(batch.size))
+//!             .fetch_one(conn)
+//!             .await
+//!         })
+//!     })
+//!     .await?;
+//! ```
+
+use std::future::Future;
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+use futures_util::{future, FutureExt};
+
+use crate::arguments::{push_bind, IntoArguments};
+use crate::database::{Database, HasArguments};
+use crate::encode::Encode;
+use crate::error::{BoxDynError, Error};
+use crate::executor::Executor;
+use crate::from_row::FromRow;
+use crate::pool::Pool;
+use crate::query::query_with;
+use crate::query_as::query_as_with;
+use crate::types::Type;
+
+/// A named backfill, the table its progress is stored in, and the settings for running it.
+///
+/// Several backfills can share a table. The table name is pushed to queries as-is, so it must
+/// not come from user input.
+#[derive(Debug, Clone)]
+pub struct Backfill {
+    table: String,
+    name: String,
+    batch_size: u32,
+    sleep: Duration,
+}
+
+/// The batch for the handler of a [`Backfill`] to process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillBatch {
+    /// The key returned for the previous batch, or `None` for the first batch.
+    pub last_key: Option<i64>,
+    /// The maximum number of rows to process.
+    pub size: u32,
+}
+
+/// The progress of a [`Backfill`], as stored in its table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BackfillProgress {
+    /// The key returned for the last batch that was committed.
+    pub last_key: Option<i64>,
+    /// The number of batches that were committed.
+    pub batches: i64,
+    /// Whether the handler reported that there are no more rows to process.
+    pub completed: bool,
+}
+
+/// An error while running a backfill.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum BackfillError {
+    #[error(transparent)]
+    Database(#[from] Error),
+
+    /// The handler failed to process a batch. The batch was rolled back, so the backfill will
+    /// resume from it.
+    #[error("backfill handler failed after key {last_key:?}: {source}")]
+    Handler {
+        last_key: Option<i64>,
+        #[source]
+        source: BoxDynError,
+    },
+}
+
+impl Backfill {
+    /// The backfill `name`, with its progress stored in `table`, run in batches of 1000 rows
+    /// with no pause between them.
+    pub fn new(table: impl Into<String>, name: impl Into<String>) -> Self {
+        Backfill {
+            table: table.into(),
+            name: name.into(),
+            batch_size: 1000,
+            sleep: Duration::ZERO,
+        }
+    }
+
+    /// The name of the backfill.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The maximum number of rows for the handler to process in each batch.
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// How long to wait between batches, to leave room for other load on the database.
+    pub fn sleep(mut self, sleep: Duration) -> Self {
+        self.sleep = sleep;
+        self
+    }
+
+    /// The SQL to create the table for the progress of backfills, for the database named
+    /// `db_name` (i.e. [`Database::NAME`]).
+    ///
+    /// Returns `None` for databases other than Postgres, MySQL and SQLite.
+    pub fn migration(&self, db_name: &str) -> Option<String> {
+        let table = &self.table;
+
+        let sql = match db_name {
+            "PostgreSQL" => format!(
+                "CREATE TABLE IF NOT EXISTS {table} (\n    \
+                    name TEXT PRIMARY KEY,\n    \
+                    last_key BIGINT,\n    \
+                    batches BIGINT NOT NULL DEFAULT 0,\n    \
+                    completed BOOLEAN NOT NULL DEFAULT FALSE,\n    \
+                    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()\n\
+                );\n"
+            ),
+            "MySQL" => format!(
+                "CREATE TABLE IF NOT EXISTS {table} (\n    \
+                    name VARCHAR(255) PRIMARY KEY,\n    \
+                    last_key BIGINT,\n    \
+                    batches BIGINT NOT NULL DEFAULT 0,\n    \
+                    completed BOOLEAN NOT NULL DEFAULT FALSE,\n    \
+                    updated_at TIMESTAMP(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6)\n\
+                );\n"
+            ),
+            "SQLite" => format!(
+                "CREATE TABLE IF NOT EXISTS {table} (\n    \
+                    name TEXT PRIMARY KEY,\n    \
+                    last_key INTEGER,\n    \
+                    batches INTEGER NOT NULL DEFAULT 0,\n    \
+                    completed BOOLEAN NOT NULL DEFAULT FALSE,\n    \
+                    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP\n\
+                );\n"
+            ),
+            _ => return None,
+        };
+
+        Some(sql)
+    }
+
+    /// The stored progress of the backfill, or the default if it hasn't been run.
+    pub async fn progress<'c, DB, E>(&self, executor: E) -> Result<BackfillProgress, Error>
+    where
+        DB: Database,
+        E: Executor<'c, Database = DB>,
+        for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+        for<'r> (Option<i64>, i64, bool): FromRow<'r, DB::Row>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    {
+        let mut sql = format!(
+            "SELECT last_key, batches, completed FROM {} WHERE name = ",
+            self.table
+        );
+        let mut arguments = <DB as HasArguments>::Arguments::default();
+        push_bind(&mut sql, &mut arguments, self.name.as_str());
+
+        let progress: Option<(Option<i64>, i64, bool)> = query_as_with(&sql, arguments)
+            .fetch_optional(executor)
+            .await?;
+
+        Ok(progress
+            .map(|(last_key, batches, completed)| BackfillProgress {
+                last_key,
+                batches,
+                completed,
+            })
+            .unwrap_or_default())
+    }
+
+    /// Deletes the stored progress, so the next run starts from the beginning.
+    pub async fn reset<'c, DB, E>(&self, executor: E) -> Result<(), Error>
+    where
+        DB: Database,
+        E: Executor<'c, Database = DB>,
+        for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    {
+        let mut sql = format!("DELETE FROM {} WHERE name = ", self.table);
+        let mut arguments = <DB as HasArguments>::Arguments::default();
+        push_bind(&mut sql, &mut arguments, self.name.as_str());
+
+        query_with(&sql, arguments).execute(executor).await?;
+
+        Ok(())
+    }
+
+    /// Runs one batch, unless the backfill is completed, and returns the progress after it.
+    ///
+    /// The handler is given a connection in the transaction that records the progress, and
+    /// returns the key of the last row it processed, or `None` if there were no rows left. On
+    /// Postgres and MySQL, the progress is locked with `FOR UPDATE` for the batch, so concurrent
+    /// runs of the same backfill process batches one after the other.
+    pub async fn run_batch<DB, F, E>(
+        &self,
+        pool: &Pool<DB>,
+        mut handler: F,
+    ) -> Result<BackfillProgress, BackfillError>
+    where
+        DB: Database,
+        F: for<'c> FnMut(
+            &'c mut DB::Connection,
+            BackfillBatch,
+        ) -> BoxFuture<'c, Result<Option<i64>, E>>,
+        E: Into<BoxDynError>,
+        for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'r> (Option<i64>, i64, bool): FromRow<'r, DB::Row>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    {
+        let mut tx = pool.begin().await?;
+
+        let mut select = format!(
+            "SELECT last_key, batches, completed FROM {} WHERE name = ",
+            self.table
+        );
+        let mut arguments = <DB as HasArguments>::Arguments::default();
+        push_bind(&mut select, &mut arguments, self.name.as_str());
+
+        if matches!(DB::NAME, "PostgreSQL" | "MySQL") {
+            select.push_str(" FOR UPDATE");
+        }
+
+        let progress: Option<(Option<i64>, i64, bool)> = query_as_with(&select, arguments)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let progress = match progress {
+            Some((last_key, batches, completed)) => BackfillProgress {
+                last_key,
+                batches,
+                completed,
+            },
+            None => {
+                let mut insert = format!("INSERT INTO {} (name) VALUES (", self.table);
+                let mut arguments = <DB as HasArguments>::Arguments::default();
+                push_bind(&mut insert, &mut arguments, self.name.as_str());
+                insert.push(')');
+
+                query_with(&insert, arguments).execute(&mut *tx).await?;
+
+                BackfillProgress::default()
+            }
+        };
+
+        if progress.completed {
+            return Ok(progress);
+        }
+
+        let batch = BackfillBatch {
+            last_key: progress.last_key,
+            size: self.batch_size,
+        };
+
+        let last_key = handler(&mut tx, batch)
+            .await
+            .map_err(|e| BackfillError::Handler {
+                last_key: progress.last_key,
+                source: e.into(),
+            })?;
+
+        let mut update = format!("UPDATE {} SET batches = batches + 1", self.table);
+        let mut arguments = <DB as HasArguments>::Arguments::default();
+
+        match last_key {
+            Some(last_key) => {
+                update.push_str(", last_key = ");
+                push_bind(&mut update, &mut arguments, last_key);
+            }
+            None => update.push_str(", completed = TRUE"),
+        }
+
+        update.push_str(&format!(", updated_at = {} WHERE name = ", now::<DB>()));
+        push_bind(&mut update, &mut arguments, self.name.as_str());
+
+        query_with(&update, arguments).execute(&mut *tx).await?;
+
+        tx.commit().await?;
+
+        let progress = BackfillProgress {
+            last_key: last_key.or(progress.last_key),
+            batches: progress.batches + 1,
+            completed: last_key.is_none(),
+        };
+
+        tracing::debug!(
+            backfill = %self.name,
+            last_key = progress.last_key,
+            batches = progress.batches,
+            completed = progress.completed,
+            "backfill batch committed"
+        );
+
+        Ok(progress)
+    }
+
+    /// Runs batches until the backfill is completed, and returns its progress.
+    pub async fn run<DB, F, E>(
+        &self,
+        pool: &Pool<DB>,
+        handler: F,
+    ) -> Result<BackfillProgress, BackfillError>
+    where
+        DB: Database,
+        F: for<'c> FnMut(
+            &'c mut DB::Connection,
+            BackfillBatch,
+        ) -> BoxFuture<'c, Result<Option<i64>, E>>,
+        E: Into<BoxDynError>,
+        for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'r> (Option<i64>, i64, bool): FromRow<'r, DB::Row>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    {
+        self.run_until(pool, future::pending(), handler).await
+    }
+
+    /// Like [`Self::run()`], but stops after the current batch once `cancel` completes, e.g. on a
+    /// shutdown signal, so the backfill can be resumed by a later run.
+    ///
+    /// Returns the progress, which isn't completed if the backfill was cancelled.
+    pub async fn run_until<DB, C, F, E>(
+        &self,
+        pool: &Pool<DB>,
+        cancel: C,
+        mut handler: F,
+    ) -> Result<BackfillProgress, BackfillError>
+    where
+        DB: Database,
+        C: Future<Output = ()>,
+        F: for<'c> FnMut(
+            &'c mut DB::Connection,
+            BackfillBatch,
+        ) -> BoxFuture<'c, Result<Option<i64>, E>>,
+        E: Into<BoxDynError>,
+        for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'r> (Option<i64>, i64, bool): FromRow<'r, DB::Row>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    {
+        futures_util::pin_mut!(cancel);
+
+        loop {
+            let progress = self.run_batch(pool, &mut handler).await?;
+
+            if progress.completed || cancel.as_mut().now_or_never().is_some() {
+                return Ok(progress);
+            }
+
+            if self.sleep > Duration::ZERO {
+                let sleep = crate::rt::sleep(self.sleep);
+                futures_util::pin_mut!(sleep);
+
+                if let future::Either::Right(_) = future::select(sleep, cancel.as_mut()).await {
+                    return Ok(progress);
+                }
+            }
+        }
+    }
+}
+
+fn now<DB: Database>() -> &'static str {
+    match DB::NAME {
+        "PostgreSQL" => "now()",
+        "MySQL" => "CURRENT_TIMESTAMP(6)",
+        _ => "CURRENT_TIMESTAMP",
+    }
+}
+
+#[test]
+fn test_migration() {
+    let backfill = Backfill::new("backfills", "users_full_name");
+
+    assert!(backfill
+        .migration("PostgreSQL")
+        .unwrap()
+        .starts_with("CREATE TABLE IF NOT EXISTS backfills (\n    name TEXT PRIMARY KEY,\n"));
+    assert!(backfill.migration("MySQL").is_some());
+    assert!(backfill.migration("SQLite").is_some());
+    assert!(backfill.migration("MSSQL").is_none());
+}
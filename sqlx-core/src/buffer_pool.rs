@@ -0,0 +1,349 @@
+//! A pool of byte buffers shared by the drivers for encoding query arguments and reading rows.
+//!
+//! The arguments of a query are encoded into a buffer which grows as needed and is freed once
+//! the query is sent. With the pool, the buffer is returned when its arguments are dropped and
+//! taken by the next query instead, so a service running many queries reuses a few buffers that
+//! have already grown to fit its arguments. Arguments only take a buffer once a value is added.
+//!
+//! Rows are decoded in place from the read buffer of their connection, which is replaced rather
+//! than grown while rows still refer to it; the replacement is taken from the pool, and the read
+//! buffer of a connection is returned to it once no rows refer to it.
+//!
+//! The free lists are split into shards, and each thread uses one of them, so taking and
+//! returning a buffer rarely contends with other threads; a thread that finds its shard in use
+//! skips the pool. Buffers are kept in tiers by capacity, and each tier holds a limited number
+//! of them, so the memory held by the pool stays bounded: by default, at most
+//! [`DEFAULT_MAX_BUFFERS`] buffers of up to [`DEFAULT_MAX_CAPACITY`] bytes per tier and shard.
+//! The limits are set with [`BufferPool::set_options()`], which can also turn the pool off.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// The least capacity of a buffer in each tier. Smaller buffers are freed.
+const TIERS: [usize; 4] = [256, 4 * 1024, 64 * 1024, 1024 * 1024];
+
+/// The number of shards of the free lists.
+const SHARDS: usize = 16;
+
+/// The default of [`BufferPoolOptions::max_buffers()`].
+pub const DEFAULT_MAX_BUFFERS: usize = 8;
+
+/// The default of [`BufferPoolOptions::max_capacity()`].
+pub const DEFAULT_MAX_CAPACITY: usize = 256 * 1024;
+
+static BUFFER_POOL: BufferPool = BufferPool::new(BufferPoolOptions::new());
+
+static NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static SHARD: usize = NEXT_SHARD.fetch_add(1, Ordering::Relaxed) % SHARDS;
+}
+
+/// The buffer pool shared by the drivers.
+pub fn buffer_pool() -> &'static BufferPool {
+    &BUFFER_POOL
+}
+
+/// A pool of byte buffers, in tiers by capacity. See the [module documentation](self).
+pub struct BufferPool {
+    max_buffers: AtomicUsize,
+    max_capacity: AtomicUsize,
+    taken: AtomicU64,
+    reused: AtomicU64,
+    recycled: AtomicU64,
+    discarded: AtomicU64,
+    pooled: AtomicUsize,
+    shards: [Mutex<Tiers>; SHARDS],
+}
+
+/// The limits of a [`BufferPool`], set with [`BufferPool::set_options()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferPoolOptions {
+    max_buffers: usize,
+    max_capacity: usize,
+}
+
+impl Default for BufferPoolOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BufferPoolOptions {
+    /// The default limits: [`DEFAULT_MAX_BUFFERS`] and [`DEFAULT_MAX_CAPACITY`].
+    pub const fn new() -> Self {
+        BufferPoolOptions {
+            max_buffers: DEFAULT_MAX_BUFFERS,
+            max_capacity: DEFAULT_MAX_CAPACITY,
+        }
+    }
+
+    /// Limits that keep no buffers, so every buffer is allocated and freed as without the pool.
+    pub fn disabled() -> Self {
+        BufferPoolOptions {
+            max_buffers: 0,
+            max_capacity: 0,
+        }
+    }
+
+    /// Set the most buffers held in each tier, by each shard. `0` turns the pool off.
+    pub fn max_buffers(mut self, max: usize) -> Self {
+        self.max_buffers = max;
+        self
+    }
+
+    /// Set the most capacity of a pooled buffer, in bytes. Larger buffers are freed.
+    pub fn max_capacity(mut self, max: usize) -> Self {
+        self.max_capacity = max;
+        self
+    }
+
+    /// Get the most buffers held in each tier, by each shard.
+    pub fn get_max_buffers(&self) -> usize {
+        self.max_buffers
+    }
+
+    /// Get the most capacity of a pooled buffer, in bytes.
+    pub fn get_max_capacity(&self) -> usize {
+        self.max_capacity
+    }
+}
+
+/// A snapshot of the counters of a [`BufferPool`], from [`BufferPool::metrics()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct BufferPoolMetrics {
+    /// The number of buffers taken from the pool.
+    pub taken: u64,
+    /// The number of buffers taken that were reused rather than newly allocated.
+    pub reused: u64,
+    /// The number of buffers returned to the pool to be reused.
+    pub recycled: u64,
+    /// The number of buffers returned to the pool that were freed instead, as they were too
+    /// small or too large, or their tier was full.
+    pub discarded: u64,
+    /// The number of buffers in the pool, over all shards.
+    pub pooled: usize,
+}
+
+impl BufferPoolMetrics {
+    /// The fraction of buffers taken that were reused, from `0.0` to `1.0`.
+    pub fn reuse_rate(&self) -> f64 {
+        if self.taken == 0 {
+            return 0.0;
+        }
+
+        self.reused as f64 / self.taken as f64
+    }
+}
+
+/// The free lists of one shard.
+struct Tiers([Vec<Vec<u8>>; TIERS.len()]);
+
+impl BufferPool {
+    /// Create a pool with the given limits.
+    ///
+    /// The drivers use the pool from [`buffer_pool()`]; others are only useful on their own.
+    pub const fn new(options: BufferPoolOptions) -> Self {
+        BufferPool {
+            max_buffers: AtomicUsize::new(options.max_buffers),
+            max_capacity: AtomicUsize::new(options.max_capacity),
+            taken: AtomicU64::new(0),
+            reused: AtomicU64::new(0),
+            recycled: AtomicU64::new(0),
+            discarded: AtomicU64::new(0),
+            pooled: AtomicUsize::new(0),
+            shards: [const { Mutex::new(Tiers([const { Vec::new() }; TIERS.len()])) }; SHARDS],
+        }
+    }
+
+    /// Set the limits of the pool.
+    ///
+    /// Lowering them doesn't free buffers right away; a shard drops the buffers over the new
+    /// limits the next time a buffer is returned to it.
+    pub fn set_options(&self, options: BufferPoolOptions) {
+        self.max_buffers
+            .store(options.max_buffers, Ordering::Relaxed);
+        self.max_capacity
+            .store(options.max_capacity, Ordering::Relaxed);
+    }
+
+    /// The limits of the pool.
+    pub fn options(&self) -> BufferPoolOptions {
+        BufferPoolOptions {
+            max_buffers: self.max_buffers.load(Ordering::Relaxed),
+            max_capacity: self.max_capacity.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Take an empty buffer from the pool with at least `size_hint` bytes of capacity, or a new
+    /// one with that capacity if the pool has none.
+    ///
+    /// The buffer is taken from the lowest tier that may fit `size_hint`, so a small hint gets
+    /// the smallest buffer there is.
+    pub fn take(&self, size_hint: usize) -> Vec<u8> {
+        self.taken.fetch_add(1, Ordering::Relaxed);
+
+        let first = TIERS.iter().rposition(|&min| size_hint >= min).unwrap_or(0);
+
+        let buf = self.with_shard(|tiers| {
+            tiers.0[first..].iter_mut().find_map(|tier| {
+                let i = tier.iter().rposition(|buf| buf.capacity() >= size_hint)?;
+                Some(tier.swap_remove(i))
+            })
+        });
+
+        match buf.flatten() {
+            Some(buf) => {
+                self.reused.fetch_add(1, Ordering::Relaxed);
+                self.pooled.fetch_sub(1, Ordering::Relaxed);
+                buf
+            }
+
+            None => Vec::with_capacity(size_hint),
+        }
+    }
+
+    /// Reserve room for `additional` more bytes in `buf`.
+    ///
+    /// If `buf` is empty and too small, it's swapped for a pooled buffer that fits instead of
+    /// growing it.
+    pub fn reserve(&self, buf: &mut Vec<u8>, additional: usize) {
+        if buf.is_empty() && buf.capacity() < additional {
+            let taken = self.take(additional);
+            self.recycle(std::mem::replace(buf, taken));
+        }
+
+        buf.reserve(additional);
+    }
+
+    /// Return a buffer to the pool, to be taken again after it's cleared.
+    ///
+    /// Buffers that never allocated are ignored.
+    pub fn recycle(&self, mut buf: Vec<u8>) {
+        let capacity = buf.capacity();
+
+        if capacity == 0 {
+            return;
+        }
+
+        let BufferPoolOptions {
+            max_buffers,
+            max_capacity,
+        } = self.options();
+
+        let tier = TIERS.iter().rposition(|&min| capacity >= min);
+
+        let recycled = self.with_shard(|tiers| {
+            // drop what's over the limits, if they were lowered
+            for (tier, &min) in tiers.0.iter_mut().zip(&TIERS) {
+                let len = tier.len();
+
+                if min > max_capacity {
+                    tier.clear();
+                } else {
+                    tier.truncate(max_buffers);
+                }
+
+                self.pooled.fetch_sub(len - tier.len(), Ordering::Relaxed);
+            }
+
+            let tier = match tier {
+                Some(tier) if capacity <= max_capacity => &mut tiers.0[tier],
+                _ => return false,
+            };
+
+            if tier.len() >= max_buffers {
+                return false;
+            }
+
+            buf.clear();
+            tier.push(buf);
+            self.pooled.fetch_add(1, Ordering::Relaxed);
+
+            true
+        });
+
+        if recycled == Some(true) {
+            self.recycled.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.discarded.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// The counters of the pool since it was created, and the number of buffers in it.
+    pub fn metrics(&self) -> BufferPoolMetrics {
+        BufferPoolMetrics {
+            taken: self.taken.load(Ordering::Relaxed),
+            reused: self.reused.load(Ordering::Relaxed),
+            recycled: self.recycled.load(Ordering::Relaxed),
+            discarded: self.discarded.load(Ordering::Relaxed),
+            pooled: self.pooled.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Run `f` with the free lists of the shard of this thread, or return `None` if another
+    /// thread is using them.
+    fn with_shard<R>(&self, f: impl FnOnce(&mut Tiers) -> R) -> Option<R> {
+        // a thread that's exiting uses the first shard
+        let shard = SHARD.try_with(|shard| *shard).unwrap_or(0);
+        let mut tiers = self.shards[shard].try_lock().ok()?;
+
+        Some(f(&mut tiers))
+    }
+}
+
+#[test]
+fn test_buffer_pool() {
+    let pool = BufferPool::new(BufferPoolOptions::new());
+
+    assert_eq!(pool.take(0).capacity(), 0);
+    assert!(pool.take(100).capacity() >= 100);
+
+    let mut buf = Vec::with_capacity(8 * 1024);
+    buf.extend_from_slice(b"SELECT 1");
+    pool.recycle(buf);
+
+    // too small, too large, and never allocated
+    pool.recycle(Vec::with_capacity(16));
+    pool.recycle(Vec::with_capacity(DEFAULT_MAX_CAPACITY + 1));
+    pool.recycle(Vec::new());
+
+    pool.recycle(Vec::with_capacity(512));
+    pool.recycle(Vec::with_capacity(1024));
+
+    // the buffer that fits the hint, not the smallest one
+    let buf = pool.take(6 * 1024);
+    assert!(buf.is_empty());
+    assert_eq!(buf.capacity(), 8 * 1024);
+
+    // the smallest tier that may fit, skipping buffers too small
+    assert_eq!(pool.take(800).capacity(), 1024);
+    assert_eq!(pool.take(0).capacity(), 512);
+
+    // an empty buffer is swapped for a pooled one rather than grown
+    pool.recycle(buf);
+    let mut buf = Vec::new();
+    pool.reserve(&mut buf, 5000);
+    assert_eq!(buf.capacity(), 8 * 1024);
+    pool.recycle(buf);
+
+    let metrics = pool.metrics();
+    assert_eq!(metrics.taken, 6);
+    assert_eq!(metrics.reused, 4);
+    assert_eq!(metrics.recycled, 5);
+    assert_eq!(metrics.discarded, 2);
+    assert_eq!(metrics.pooled, 1);
+
+    // buffers returned by other threads are counted too
+    std::thread::scope(|scope| {
+        scope.spawn(|| pool.recycle(Vec::with_capacity(2048)));
+    });
+    assert_eq!(pool.metrics().pooled, 2);
+
+    // turning the pool off drops what a shard holds on the next return to it
+    pool.set_options(BufferPoolOptions::disabled());
+    pool.recycle(Vec::with_capacity(512));
+    assert_eq!(pool.metrics().discarded, 3);
+    assert_eq!(pool.take(0).capacity(), 0);
+}
@@ -43,6 +43,11 @@ impl<T> StatementCache<T> {
         self.inner.len()
     }
 
+    /// Removes the statement for the given key from the cache, returning it if it was there.
+    pub fn remove(&mut self, k: &str) -> Option<T> {
+        self.inner.remove(k)
+    }
+
     /// Removes the least recently used item from the cache.
     pub fn remove_lru(&mut self) -> Option<T> {
         self.inner.remove_lru().map(|(_, v)| v)
@@ -1,11 +1,16 @@
-use crate::database::{Database, HasStatementCache};
+use crate::arguments::IntoArguments;
+use crate::database::{Database, HasArguments, HasStatementCache};
 use crate::error::Error;
+use crate::executor::Executor;
+use crate::middleware::{ExecutorMiddleware, WithMiddleware};
+use crate::query::query_with;
 
-use crate::transaction::Transaction;
+use crate::transaction::{Transaction, TransactionOptions};
 use futures_core::future::BoxFuture;
 use log::LevelFilter;
 use std::fmt::Debug;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use url::Url;
 
@@ -42,6 +47,30 @@ pub trait Connection: Send {
     /// Checks if a connection to the database is still valid.
     fn ping(&mut self) -> BoxFuture<'_, Result<(), Error>>;
 
+    /// Checks that the server hasn't closed the connection, without a round trip.
+    ///
+    /// This is much cheaper than [`ping()`][Self::ping], but only notices connections that were
+    /// closed or reset, not ones that stopped responding. It's used by
+    /// [`AcquireTest::Fast`][crate::pool::AcquireTest::Fast].
+    ///
+    /// The default implementation returns `true`, for connections without a socket.
+    fn is_open(&mut self) -> bool {
+        true
+    }
+
+    /// Returns `true` if the connection may be out of sync with the server because a future using
+    /// it was dropped partway through an operation, e.g. by a timeout, or the operation failed.
+    ///
+    /// A poisoned connection can't be used reliably and should be closed; a [`Pool`] closes
+    /// them rather than taking them back.
+    ///
+    /// The default implementation returns `false`, for connections that can't be poisoned.
+    ///
+    /// [`Pool`]: crate::pool::Pool
+    fn is_poisoned(&self) -> bool {
+        false
+    }
+
     /// Begin a new transaction or establish a savepoint within the active transaction.
     ///
     /// Returns a [`Transaction`] for controlling and tracking the new transaction.
@@ -49,6 +78,34 @@ pub trait Connection: Send {
     where
         Self: Sized;
 
+    /// Begin a new transaction with the given isolation level and access mode.
+    ///
+    /// See [`TransactionOptions`] for details.
+    ///
+    /// The default implementation begins the transaction with [`begin`][Self::begin] and then
+    /// applies the options with a `SET TRANSACTION` statement.
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+        for<'c> &'c mut <Self::Database as Database>::Connection:
+            Executor<'c, Database = Self::Database>,
+    {
+        Box::pin(async move {
+            let mut transaction = self.begin().await?;
+
+            if let Some(modes) = options.to_sql() {
+                (&mut *transaction)
+                    .execute(&*format!("SET TRANSACTION {modes}"))
+                    .await?;
+            }
+
+            Ok(transaction)
+        })
+    }
+
     /// Execute the function inside a transaction.
     ///
     /// If the function returns an error, the transaction will be rolled back. If it does not
@@ -95,6 +152,112 @@ pub trait Connection: Send {
         })
     }
 
+    /// Execute the function inside a transaction begun with the given options.
+    ///
+    /// Behaves like [`transaction`][Self::transaction], but begins the transaction with
+    /// [`begin_with`][Self::begin_with]. The options are `Copy`, so the same options can be
+    /// passed again when retrying after a serialization failure.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sqlx::postgres::PgConnection;
+    /// use sqlx::{Connection, IsolationLevel, TransactionOptions};
+    ///
+    /// # pub async fn _f(conn: &mut PgConnection) -> sqlx::Result<i64> {
+    /// let options = TransactionOptions::new().isolation(IsolationLevel::Serializable);
+    ///
+    /// loop {
+    ///     let res = conn
+    ///         .transaction_with(options, |txn| Box::pin(async move {
+    ///             sqlx::query_scalar("select count(*) from ..").fetch_one(&mut **txn).await
+    ///         }))
+    ///         .await;
+    ///
+    ///     match res {
+    ///         Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some("40001") => continue,
+    ///         res => return res,
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    fn transaction_with<'a, F, R, E>(
+        &'a mut self,
+        options: TransactionOptions,
+        callback: F,
+    ) -> BoxFuture<'a, Result<R, E>>
+    where
+        for<'c> F: FnOnce(&'c mut Transaction<'_, Self::Database>) -> BoxFuture<'c, Result<R, E>>
+            + 'a
+            + Send
+            + Sync,
+        Self: Sized,
+        for<'c> &'c mut <Self::Database as Database>::Connection:
+            Executor<'c, Database = Self::Database>,
+        R: Send,
+        E: From<Error> + Send,
+    {
+        Box::pin(async move {
+            let mut transaction = self.begin_with(options).await?;
+            let ret = callback(&mut transaction).await;
+
+            match ret {
+                Ok(ret) => {
+                    transaction.commit().await?;
+
+                    Ok(ret)
+                }
+                Err(err) => {
+                    transaction.rollback().await?;
+
+                    Err(err)
+                }
+            }
+        })
+    }
+
+    /// Executes `sql` once with each set of `arguments`, returning the total rows affected.
+    ///
+    /// See [`Query::execute_many_args()`][crate::query::Query::execute_many_args]. The
+    /// arguments are taken from the iterator as they're executed. The default implementation
+    /// executes them one at a time; drivers may pipeline them instead.
+    #[doc(hidden)]
+    fn execute_batch<'e, 'q: 'e>(
+        &'e mut self,
+        sql: &'q str,
+        arguments: Box<
+            dyn Iterator<Item = <Self::Database as HasArguments<'q>>::Arguments> + Send + 'e,
+        >,
+    ) -> BoxFuture<'e, Result<<Self::Database as Database>::QueryResult, Error>>
+    where
+        Self: Sized,
+        for<'c> &'c mut Self: Executor<'c, Database = Self::Database>,
+        for<'a> <Self::Database as HasArguments<'a>>::Arguments: IntoArguments<'a, Self::Database>,
+    {
+        Box::pin(async move {
+            let mut result = <Self::Database as Database>::QueryResult::default();
+
+            for arguments in arguments {
+                result.extend(Some(self.execute(query_with(sql, arguments)).await?));
+            }
+
+            Ok(result)
+        })
+    }
+
+    /// Runs every query executed through the returned executor through `middleware`.
+    ///
+    /// See [`ExecutorMiddleware`] for details.
+    fn with_middleware(
+        &mut self,
+        middleware: impl ExecutorMiddleware<Self::Database>,
+    ) -> WithMiddleware<&mut Self, Self::Database>
+    where
+        Self: Sized,
+    {
+        WithMiddleware::new(self, middleware)
+    }
+
     /// The number of statements currently cached in the connection.
     fn cached_statements_size(&self) -> usize
     where
@@ -126,6 +289,46 @@ pub trait Connection: Send {
     /// allow the buffers to shrink.
     fn shrink_buffers(&mut self);
 
+    /// Change the name the database server reports for this connection, if supported, or with
+    /// `None`, restore the name it was opened with.
+    ///
+    /// This is used to apply [`PoolOptions::checkout_label()`] and otherwise works like
+    /// [`ConnectOptions::application_name()`], except that it takes effect immediately.
+    ///
+    /// The default implementation does nothing.
+    ///
+    /// [`PoolOptions::checkout_label()`]: crate::pool::PoolOptions::checkout_label
+    fn set_application_name<'c>(
+        &'c mut self,
+        name: Option<&'c str>,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        let _ = name;
+        Box::pin(async move { Ok(()) })
+    }
+
+    /// The product and version of the database server, as reported when connecting.
+    ///
+    /// This allows code that is generic over the database, or that has to support several
+    /// versions of it, to check which syntax is available without querying `version()`.
+    ///
+    /// The default implementation returns [`ServerVersion::unknown()`], for which no optional
+    /// syntax is assumed to be supported.
+    fn server_version(&self) -> ServerVersion {
+        ServerVersion::unknown()
+    }
+
+    /// A handle to the SQL this connection is currently executing, if the driver tracks it.
+    ///
+    /// The pool uses this to list the queries holding its connections when [`Pool::acquire()`]
+    /// times out.
+    ///
+    /// The default implementation returns `None`.
+    ///
+    /// [`Pool::acquire()`]: crate::pool::Pool::acquire
+    fn running_query(&self) -> Option<RunningQuery> {
+        None
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>>;
 
@@ -155,12 +358,271 @@ pub trait Connection: Send {
     }
 }
 
+/// The product and version of a database server, returned by [`Connection::server_version()`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerVersion {
+    pub flavor: ServerFlavor,
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+
+    /// The full version string reported by the server, which may include build information.
+    pub raw: String,
+}
+
+/// The database product behind a connection, including wire-compatible derivatives.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ServerFlavor {
+    Postgres,
+    CockroachDb,
+    MySql,
+    MariaDb,
+    TiDb,
+    Sqlite,
+
+    /// A driver that doesn't report the product behind its connections.
+    Unknown,
+}
+
+/// A statement that may support a `RETURNING` clause; see [`ServerVersion::supports_returning()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ReturningStatement {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// The syntax for inserting a row or updating it if it already exists.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum UpsertSyntax {
+    /// `INSERT .. ON CONFLICT (..) DO UPDATE SET ..`
+    OnConflict,
+
+    /// `INSERT .. ON DUPLICATE KEY UPDATE ..`
+    OnDuplicateKeyUpdate,
+}
+
+impl ServerVersion {
+    /// The version of a server whose product and version aren't known, `0.0.0` of
+    /// [`ServerFlavor::Unknown`].
+    pub fn unknown() -> Self {
+        ServerVersion {
+            flavor: ServerFlavor::Unknown,
+            major: 0,
+            minor: 0,
+            patch: 0,
+            raw: String::new(),
+        }
+    }
+
+    /// Parse the first `MAJOR[.MINOR[.PATCH]]` found in `version`, e.g. `"16.1 (Debian 16.1-1)"`.
+    ///
+    /// Missing components are zero.
+    pub fn parse(flavor: ServerFlavor, version: &str) -> Self {
+        let start = version
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(version.len());
+
+        let numbers = &version[start..];
+        let end = numbers
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(numbers.len());
+
+        let mut parts = numbers[..end]
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0));
+
+        ServerVersion {
+            flavor,
+            major: parts.next().unwrap_or(0),
+            minor: parts.next().unwrap_or(0),
+            patch: parts.next().unwrap_or(0),
+            raw: version.to_owned(),
+        }
+    }
+
+    /// Parse the version reported by a MySQL-compatible server, detecting MariaDB and TiDB.
+    ///
+    /// MariaDB prefixes its version with `5.5.5-` for compatibility with old replication clients,
+    /// and TiDB reports the version of MySQL it is compatible with followed by its own.
+    pub fn parse_mysql(version: &str) -> Self {
+        if version.contains("MariaDB") {
+            let numbers = version.strip_prefix("5.5.5-").unwrap_or(version);
+
+            ServerVersion {
+                raw: version.to_owned(),
+                ..ServerVersion::parse(ServerFlavor::MariaDb, numbers)
+            }
+        } else if let Some((_, numbers)) = version.split_once("TiDB-") {
+            ServerVersion {
+                raw: version.to_owned(),
+                ..ServerVersion::parse(ServerFlavor::TiDb, numbers)
+            }
+        } else {
+            ServerVersion::parse(ServerFlavor::MySql, version)
+        }
+    }
+
+    /// Returns `true` if this version is at least `major.minor.patch`.
+    pub fn at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        (self.major, self.minor, self.patch) >= (major, minor, patch)
+    }
+
+    /// Returns `true` if `statement` supports a `RETURNING` clause.
+    ///
+    /// MariaDB supports it for `DELETE` since 10.0.5 and `INSERT` since 10.5, but not `UPDATE`.
+    pub fn supports_returning(&self, statement: ReturningStatement) -> bool {
+        match self.flavor {
+            ServerFlavor::Postgres | ServerFlavor::CockroachDb => true,
+            ServerFlavor::MariaDb => match statement {
+                ReturningStatement::Insert => self.at_least(10, 5, 0),
+                ReturningStatement::Delete => self.at_least(10, 0, 5),
+                ReturningStatement::Update => false,
+            },
+            ServerFlavor::Sqlite => self.at_least(3, 35, 0),
+            ServerFlavor::MySql | ServerFlavor::TiDb | ServerFlavor::Unknown => false,
+        }
+    }
+
+    /// The syntax to use for upserts, or `None` if the server doesn't support any.
+    pub fn upsert_syntax(&self) -> Option<UpsertSyntax> {
+        match self.flavor {
+            ServerFlavor::Postgres if !self.at_least(9, 5, 0) => None,
+            ServerFlavor::Sqlite if !self.at_least(3, 24, 0) => None,
+            ServerFlavor::Postgres | ServerFlavor::CockroachDb | ServerFlavor::Sqlite => {
+                Some(UpsertSyntax::OnConflict)
+            }
+            ServerFlavor::MySql | ServerFlavor::MariaDb | ServerFlavor::TiDb => {
+                Some(UpsertSyntax::OnDuplicateKeyUpdate)
+            }
+            ServerFlavor::Unknown => None,
+        }
+    }
+
+    /// Returns `true` if `SELECT .. FOR UPDATE` supports `SKIP LOCKED`, for job queues.
+    pub fn supports_skip_locked(&self) -> bool {
+        match self.flavor {
+            ServerFlavor::Postgres => self.at_least(9, 5, 0),
+            ServerFlavor::MySql => self.at_least(8, 0, 1),
+            ServerFlavor::MariaDb => self.at_least(10, 6, 0),
+            ServerFlavor::CockroachDb => self.at_least(21, 2, 0),
+            ServerFlavor::TiDb | ServerFlavor::Sqlite | ServerFlavor::Unknown => false,
+        }
+    }
+}
+
+impl std::fmt::Display for ServerVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} {}.{}.{}",
+            self.flavor, self.major, self.minor, self.patch
+        )
+    }
+}
+
+#[test]
+fn test_parse_server_version() {
+    let v = ServerVersion::parse(ServerFlavor::Postgres, "16.1 (Debian 16.1-1.pgdg120+1)");
+    assert_eq!((v.major, v.minor, v.patch), (16, 1, 0));
+
+    let v = ServerVersion::parse(
+        ServerFlavor::MariaDb,
+        "10.11.2-MariaDB-1:10.11.2+maria~ubu2204",
+    );
+    assert_eq!((v.major, v.minor, v.patch), (10, 11, 2));
+
+    let v = ServerVersion::parse(
+        ServerFlavor::CockroachDb,
+        "CockroachDB CCL v23.1.11 (x86_64)",
+    );
+    assert_eq!((v.major, v.minor, v.patch), (23, 1, 11));
+
+    let v = ServerVersion::parse(ServerFlavor::Sqlite, "3.45.0");
+    assert!(v.supports_returning(ReturningStatement::Update));
+    assert_eq!(v.upsert_syntax(), Some(UpsertSyntax::OnConflict));
+
+    let v = ServerVersion::parse_mysql("5.5.5-10.11.2-MariaDB-1:10.11.2+maria~ubu2204");
+    assert_eq!(v.flavor, ServerFlavor::MariaDb);
+    assert_eq!((v.major, v.minor, v.patch), (10, 11, 2));
+
+    let v = ServerVersion::parse_mysql("8.0.11-TiDB-v7.5.0");
+    assert_eq!(v.flavor, ServerFlavor::TiDb);
+    assert_eq!((v.major, v.minor, v.patch), (7, 5, 0));
+
+    let v = ServerVersion::parse_mysql("8.0.36");
+    assert_eq!(v.flavor, ServerFlavor::MySql);
+    assert!(!v.supports_returning(ReturningStatement::Insert));
+    assert!(v.at_least(8, 0, 1));
+
+    let v = ServerVersion::parse(ServerFlavor::MariaDb, "10.11.2-MariaDB");
+    assert!(v.supports_returning(ReturningStatement::Insert));
+    assert!(v.supports_returning(ReturningStatement::Delete));
+    assert!(!v.supports_returning(ReturningStatement::Update));
+
+    let v = ServerVersion::unknown();
+    assert!(!v.supports_returning(ReturningStatement::Delete));
+    assert_eq!(v.upsert_syntax(), None);
+}
+
+/// A shared handle to the SQL a connection is currently executing,
+/// returned by [`Connection::running_query()`].
+///
+/// The SQL is copied into a buffer that's reused by the next query, so recording it doesn't
+/// allocate once the buffer has grown to fit.
+#[derive(Clone, Debug, Default)]
+pub struct RunningQuery(Arc<Mutex<RunningQueryInner>>);
+
+#[derive(Debug, Default)]
+struct RunningQueryInner {
+    running: bool,
+    sql: String,
+}
+
+/// Clears the [`RunningQuery`] when dropped.
+#[derive(Debug)]
+pub struct RunningQueryGuard(RunningQuery);
+
+impl RunningQuery {
+    /// Record `sql` as running until the returned guard is dropped.
+    pub fn start(&self, sql: &str) -> RunningQueryGuard {
+        let mut inner = self.lock();
+
+        inner.sql.clear();
+        inner.sql.push_str(sql);
+        inner.running = true;
+
+        RunningQueryGuard(self.clone())
+    }
+
+    /// The SQL currently running, if any.
+    pub fn get(&self) -> Option<String> {
+        let inner = self.lock();
+
+        inner.running.then(|| inner.sql.clone())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, RunningQueryInner> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Drop for RunningQueryGuard {
+    fn drop(&mut self) {
+        self.0.lock().running = false;
+    }
+}
+
 #[derive(Clone, Debug)]
 #[non_exhaustive]
 pub struct LogSettings {
     pub statements_level: LevelFilter,
     pub slow_statements_level: LevelFilter,
     pub slow_statements_duration: Duration,
+    pub wire_stats_level: LevelFilter,
 }
 
 impl Default for LogSettings {
@@ -169,6 +631,7 @@ impl Default for LogSettings {
             statements_level: LevelFilter::Debug,
             slow_statements_level: LevelFilter::Warn,
             slow_statements_duration: Duration::from_secs(1),
+            wire_stats_level: LevelFilter::Off,
         }
     }
 }
@@ -181,6 +644,28 @@ impl LogSettings {
         self.slow_statements_level = level;
         self.slow_statements_duration = duration;
     }
+    pub fn log_wire_stats(&mut self, level: LevelFilter) {
+        self.wire_stats_level = level;
+    }
+}
+
+/// Read a password from a file, e.g. a Docker or Kubernetes secret, for the `password-file`
+/// connection URL parameter. A trailing newline is ignored.
+#[doc(hidden)]
+pub fn read_password_file(path: impl AsRef<std::path::Path>) -> Result<String, Error> {
+    let path = path.as_ref();
+    let mut password = std::fs::read_to_string(path).map_err(|e| {
+        Error::Configuration(format!("failed to read password file {}: {e}", path.display()).into())
+    })?;
+
+    if password.ends_with('\n') {
+        password.pop();
+        if password.ends_with('\r') {
+            password.pop();
+        }
+    }
+
+    Ok(password)
 }
 
 pub trait ConnectOptions: 'static + Send + Sync + FromStr<Err = Error> + Debug + Clone {
@@ -201,6 +686,38 @@ pub trait ConnectOptions: 'static + Send + Sync + FromStr<Err = Error> + Debug +
     /// at the specified `level`.
     fn log_slow_statements(self, level: LevelFilter, duration: Duration) -> Self;
 
+    /// Log the bytes sent and received, round-trips, and parse/describe/execute phases
+    /// of each statement at the specified `level`, under the `sqlx::query::wire` target.
+    ///
+    /// This is disabled by default. It's useful for diagnosing chatty query patterns,
+    /// e.g. statements that aren't being cached or that could be batched.
+    ///
+    /// The default implementation does nothing, as SQLite has no wire protocol.
+    fn log_wire_stats(self, level: LevelFilter) -> Self {
+        let _ = level;
+        self
+    }
+
+    /// Set the name the database server reports for connections opened with these options,
+    /// so that DBAs can attribute connections to the service that opened them.
+    ///
+    /// * Postgres: sets [`application_name`](https://www.postgresql.org/docs/current/runtime-config-logging.html#GUC-APPLICATION-NAME),
+    ///   visible in `pg_stat_activity`.
+    /// * MySQL: sets the `program_name` connection attribute,
+    ///   visible in `performance_schema.session_connect_attrs`.
+    /// * SQLite: ignored, as there is no server.
+    ///
+    /// The default implementation ignores the name.
+    fn application_name(self, name: &str) -> Self {
+        let _ = name;
+        self
+    }
+
+    /// Get the name set by [`application_name()`][Self::application_name], if any.
+    fn get_application_name(&self) -> Option<&str> {
+        None
+    }
+
     /// Entirely disables statement logging (both slow and regular).
     fn disable_statement_logging(self) -> Self {
         self.log_statements(LevelFilter::Off)
@@ -105,6 +105,14 @@ pub trait Database:
 
     /// The schemes for database URLs that should match this driver.
     const URL_SCHEMES: &'static [&'static str];
+
+    /// The default maximum number of bind parameters allowed in a single statement.
+    ///
+    /// The effective limit may be lower if the server or library is configured differently.
+    ///
+    /// Defaults to 999, the lowest limit of the databases supported by SQLx (that of SQLite
+    /// before 3.32.0); drivers should override it with the limit of their database.
+    const BIND_LIMIT: usize = 999;
 }
 
 /// Associate [`Database`] with a [`ValueRef`](crate::value::ValueRef) of a generic lifetime.
@@ -58,17 +58,18 @@ impl<DB: Database> Describe<DB> {
 #[cfg(feature = "any")]
 impl<DB: Database> Describe<DB> {
     #[doc(hidden)]
-    pub fn try_into_any(self) -> crate::Result<Describe<crate::any::Any>>
+    pub fn try_into_any(
+        self,
+        registry: &crate::any::AnyTypeRegistry,
+    ) -> crate::Result<Describe<crate::any::Any>>
     where
         crate::any::AnyColumn: for<'a> TryFrom<&'a DB::Column, Error = crate::Error>,
         crate::any::AnyTypeInfo: for<'a> TryFrom<&'a DB::TypeInfo, Error = crate::Error>,
     {
-        use crate::any::AnyTypeInfo;
-
         let columns = self
             .columns
             .iter()
-            .map(crate::any::AnyColumn::try_from)
+            .map(|column| registry.column(column))
             .collect::<Result<Vec<_>, _>>()?;
 
         let parameters = match self.parameters {
@@ -77,7 +78,7 @@ impl<DB: Database> Describe<DB> {
                     .iter()
                     .enumerate()
                     .map(|(i, type_info)| {
-                        AnyTypeInfo::try_from(type_info).map_err(|_| {
+                        registry.type_info(type_info).map_err(|_| {
                             crate::Error::AnyDriverError(
                                 format!(
                                     "Any driver does not support type {type_info} of parameter {i}"
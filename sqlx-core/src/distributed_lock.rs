@@ -0,0 +1,189 @@
+//! Locks shared by every process connected to the same database, e.g. for leader election.
+//!
+//! [`DistributedLock`] uses the database's session-level named locks: advisory locks in
+//! Postgres and `GET_LOCK()` in MySQL. A lock is held by the connection that acquired it, so it's
+//! released automatically if that process crashes or loses its connection to the database.
+
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+
+use crate::connection::Connection;
+use crate::database::Database;
+use crate::error::Error;
+use crate::pool::{Pool, PoolConnection};
+
+/// Session-level named locks, implemented by the connection types of databases that support
+/// [`DistributedLock`].
+pub trait DistributedLocking: Connection {
+    /// Tries to acquire the lock named `name` for this connection, returning immediately with
+    /// `false` if another connection holds it.
+    fn try_lock<'e>(&'e mut self, name: &'e str) -> BoxFuture<'e, Result<bool, Error>>;
+
+    /// Releases the lock named `name`, returning `false` if this connection didn't hold it.
+    fn unlock<'e>(&'e mut self, name: &'e str) -> BoxFuture<'e, Result<bool, Error>>;
+
+    /// Checks whether this connection holds the lock named `name`.
+    fn is_locked<'e>(&'e mut self, name: &'e str) -> BoxFuture<'e, Result<bool, Error>>;
+}
+
+/// A named lock shared by every process connected to the same database.
+///
+/// Acquiring the lock takes a connection from the pool and holds it until the lock is released.
+///
+/// Session-level locks don't expire, so there's no heartbeat to renew them. However, the server
+/// or a proxy may close a connection that's idle for too long, e.g. after MySQL's `wait_timeout`
+/// or Postgres' `idle_session_timeout`, which releases the lock. Calling
+/// [`DistributedLockGuard::is_held()`] periodically, as below, both keeps the connection active
+/// and notices if the lock was lost.
+///
+/// ```rust,ignore
+/// use sqlx::sync::DistributedLock;
+///
+/// let lock = DistributedLock::new(&pool, "scheduler-leader");
+///
+/// // wait until this process is the leader
+/// let mut guard = lock.acquire().await?;
+///
+/// loop {
+///     run_scheduled_jobs().await?;
+///
+///     // stop if the connection holding the lock was lost, and another process took over
+///     if !guard.is_held().await? {
+///         break;
+///     }
+///
+///     sleep(Duration::from_secs(10)).await;
+/// }
+/// ```
+#[derive(Debug)]
+pub struct DistributedLock<DB: Database> {
+    pool: Pool<DB>,
+    name: String,
+    retry_interval: Duration,
+}
+
+/// A held [`DistributedLock`].
+///
+/// Dropping the guard closes the connection holding the lock, which releases it. To release it
+/// and return the connection to the pool instead, call [`Self::release()`].
+#[derive(Debug)]
+pub struct DistributedLockGuard<DB: Database> {
+    // `None` once released
+    conn: Option<PoolConnection<DB>>,
+    name: String,
+}
+
+impl<DB> DistributedLock<DB>
+where
+    DB: Database,
+    DB::Connection: DistributedLocking,
+{
+    /// The lock named `name`, acquired with connections from `pool`.
+    ///
+    /// MySQL limits lock names to 64 characters.
+    pub fn new(pool: &Pool<DB>, name: impl Into<String>) -> Self {
+        DistributedLock {
+            pool: pool.clone(),
+            name: name.into(),
+            retry_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// How often [`Self::acquire()`] tries to acquire the lock while another process holds it.
+    pub fn retry_interval(mut self, retry_interval: Duration) -> Self {
+        self.retry_interval = retry_interval;
+        self
+    }
+
+    /// The name of the lock.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Tries to acquire the lock, returning `None` immediately if another process holds it.
+    pub async fn try_acquire(&self) -> Result<Option<DistributedLockGuard<DB>>, Error> {
+        // The server may grant the lock even if this fails or is cancelled before the result is
+        // read, in which case dropping the guard closes the connection rather than returning it
+        // to the pool still holding the lock.
+        let mut guard = DistributedLockGuard {
+            conn: Some(self.pool.acquire().await?),
+            name: self.name.clone(),
+        };
+
+        let conn = guard.conn.as_mut().expect("BUG: connection just acquired");
+
+        if !conn.try_lock(&self.name).await? {
+            // the lock isn't held, so the connection can go back to the pool
+            drop(guard.conn.take());
+            return Ok(None);
+        }
+
+        Ok(Some(guard))
+    }
+
+    /// Acquires the lock, waiting until any other process holding it releases it.
+    pub async fn acquire(&self) -> Result<DistributedLockGuard<DB>, Error> {
+        loop {
+            if let Some(guard) = self.try_acquire().await? {
+                return Ok(guard);
+            }
+
+            crate::rt::sleep(self.retry_interval).await;
+        }
+    }
+}
+
+impl<DB> DistributedLockGuard<DB>
+where
+    DB: Database,
+    DB::Connection: DistributedLocking,
+{
+    /// The name of the lock.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Checks that the lock is still held, i.e. that the connection holding it is still alive.
+    ///
+    /// Call this periodically to notice if the lock was lost, e.g. because the connection was
+    /// killed or timed out, and another process may have acquired it.
+    pub async fn is_held(&mut self) -> Result<bool, Error> {
+        let conn = self.conn.as_mut().expect("BUG: lock already released");
+
+        match conn.is_locked(&self.name).await {
+            Ok(held) => Ok(held),
+            // the connection was lost, and the lock with it
+            Err(Error::Io(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Releases the lock and returns the connection holding it to the pool.
+    ///
+    /// Returns `false` if the lock was no longer held by this connection, in which case another
+    /// process may have acquired it in the meantime.
+    ///
+    /// If this returns an error or is cancelled, the connection is closed instead, which
+    /// releases the lock.
+    pub async fn release(mut self) -> Result<bool, Error> {
+        let conn = self.conn.as_mut().expect("BUG: lock already released");
+
+        // if this fails, dropping `self` closes the connection
+        let held = conn.unlock(&self.name).await?;
+
+        // the connection no longer holds the lock, so it can go back to the pool
+        drop(self.conn.take());
+
+        Ok(held)
+    }
+}
+
+impl<DB: Database> Drop for DistributedLockGuard<DB> {
+    fn drop(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            // closing the connection releases the lock without any asynchronous work
+            drop(conn.detach());
+        }
+    }
+}
@@ -78,6 +78,10 @@ pub enum Error {
         source: BoxDynError,
     },
 
+    /// Error occurred while encoding a value.
+    #[error("error occurred while encoding a value: {0}")]
+    Encode(#[source] BoxDynError),
+
     /// Error occurred while decoding a value.
     #[error("error occurred while decoding: {0}")]
     Decode(#[source] BoxDynError),
@@ -89,7 +93,11 @@ pub enum Error {
     /// A [`Pool::acquire`] timed out due to connections not becoming available or
     /// because another task encountered too many errors while trying to open a new connection.
     ///
+    /// [`Pool::last_timeout_report`] describes the state of the pool when it timed out, to help
+    /// diagnose what is holding its connections.
+    ///
     /// [`Pool::acquire`]: crate::pool::Pool::acquire
+    /// [`Pool::last_timeout_report`]: crate::pool::Pool::last_timeout_report
     #[error("pool timed out while waiting for an open connection")]
     PoolTimedOut,
 
@@ -100,6 +108,28 @@ pub enum Error {
     #[error("attempted to acquire a connection on a closed pool")]
     PoolClosed,
 
+    /// A [`ResilientPool`]'s circuit breaker is open, so the query was rejected without being run.
+    ///
+    /// [`ResilientPool`]: crate::pool::ResilientPool
+    #[error("circuit breaker is open; the query was not run")]
+    CircuitOpen,
+
+    /// A row returned by the database was larger than the connection allows.
+    ///
+    /// The row was skipped without buffering it, and the rest of the result is discarded.
+    /// Set on Postgres with `PgConnectOptions::max_row_size()`, and on MySQL with
+    /// `MySqlConnectOptions::max_row_size()`.
+    #[error("row of {size} bytes is larger than the limit of {limit} bytes")]
+    RowTooLarge { size: usize, limit: usize },
+
+    /// The rows returned by a query added up to more than the connection allows.
+    ///
+    /// The rest of the result is discarded. Set on Postgres with
+    /// `PgConnectOptions::max_result_size()`, and on MySQL with
+    /// `MySqlConnectOptions::max_result_size()`.
+    #[error("result set is larger than the limit of {limit} bytes")]
+    ResultTooLarge { limit: usize },
+
     /// A background worker has crashed.
     #[error("attempted to communicate with a crashed background worker")]
     WorkerCrashed,
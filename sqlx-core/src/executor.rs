@@ -198,6 +198,20 @@ pub trait Execute<'q, DB: Database>: Send + Sized {
 
     /// Returns `true` if the statement should be cached.
     fn persistent(&self) -> bool;
+
+    /// Returns the number of rows to fetch from the database at a time, if limited.
+    ///
+    /// See [`Query::fetch_size()`](super::query::Query::fetch_size).
+    fn fetch_size(&self) -> Option<u32> {
+        None
+    }
+
+    /// Returns `false` if the row and result size limits of the connection don't apply.
+    ///
+    /// See [`Query::without_size_limits()`](super::query::Query::without_size_limits).
+    fn size_limited(&self) -> bool {
+        true
+    }
 }
 
 // NOTE: `Execute` is explicitly not implemented for String and &String to make it slightly more
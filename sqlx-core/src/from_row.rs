@@ -46,6 +46,24 @@ use crate::{error::Error, row::Row};
 ///
 /// will read the content of the column `description` into the field `about_me`.
 ///
+/// #### `alias`
+///
+/// When a column may appear under more than one name, such as while migrating away from a
+/// legacy column name, each alternative can be given with `alias`. The field's own name (after
+/// `rename` or `rename_all`) is tried first, then each alias in order, and the first column
+/// present in the row is used:
+///
+/// ```rust,ignore
+/// #[derive(sqlx::FromRow)]
+/// struct User {
+///     id: i32,
+///     #[sqlx(alias = "username", alias = "legacy_name")]
+///     name: String,
+/// }
+/// ```
+///
+/// Aliases are used verbatim and are not affected by `rename_all`.
+///
 /// #### `rename_all`
 /// By default, field names are expected verbatim (with the exception of the raw identifier prefix `r#`, if present).
 /// Placed at the struct level, this attribute changes how the field name is mapped to its SQL column name:
@@ -91,6 +109,33 @@ use crate::{error::Error, row::Row};
 /// will set the value of the field `location` to the default value of `Option<String>`,
 /// which is `None`.
 ///
+/// #### `allow_missing`
+///
+/// Placed at the struct level, this treats every field as if it had the `default` attribute,
+/// so any field whose column is missing from the row is set to its default value.
+///
+/// #### `deny_unknown_columns`
+///
+/// Placed at the struct level, this makes `from_row` return [`Error::Decode`][crate::Error::Decode]
+/// if the row contains a column that is not read by any field, which helps to catch schema drift
+/// when using `SELECT *`:
+///
+/// ```rust,ignore
+/// #[derive(sqlx::FromRow)]
+/// #[sqlx(deny_unknown_columns)]
+/// struct User {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// // fails if a column has since been added to `users`
+/// let users: Vec<User> = sqlx::query_as("SELECT * FROM users").fetch_all(&mut conn).await?;
+/// ```
+///
+/// For enums, the tag column and the columns read by the selected variant are allowed.
+/// This cannot be combined with `flatten`, as the columns read by the flattened type
+/// aren't known.
+///
 /// ### `flatten`
 ///
 /// If you want to handle a field that implements [`FromRow`],
@@ -251,6 +296,38 @@ use crate::{error::Error, row::Row};
 ///     }
 /// }
 /// ```
+///
+/// ### Enums
+///
+/// `FromRow` can also be derived for enums where the variant is selected by the value of a
+/// discriminator column named with `#[sqlx(tag = "..")]`. This is useful for tables that store
+/// several kinds of records, each using a different subset of the columns.
+///
+/// The tag value for each variant is its name, which can be changed with `#[sqlx(rename = "..")]`
+/// on the variant. Fields of struct variants are read like the fields of a struct, and support
+/// the same attributes. Tuple variants are not supported.
+///
+/// ```rust,ignore
+/// #[derive(sqlx::FromRow)]
+/// #[sqlx(tag = "kind")]
+/// enum Shape {
+///     #[sqlx(rename = "circle")]
+///     Circle { radius: f64 },
+///     #[sqlx(rename = "rect")]
+///     Rectangle { width: f64, height: f64 },
+///     #[sqlx(rename = "point")]
+///     Point,
+/// }
+/// ```
+///
+/// Given a query such as:
+///
+/// ```sql
+/// SELECT kind, radius, width, height FROM shapes;
+/// ```
+///
+/// A row with `kind = 'rect'` will be decoded as `Shape::Rectangle`. A value of `kind` that
+/// doesn't match any variant results in [`Error::Decode`][crate::error::Error::Decode].
 pub trait FromRow<'r, R: Row>: Sized {
     fn from_row(row: &'r R) -> Result<Self, Error>;
 }
@@ -4,6 +4,7 @@ mod buf_mut;
 mod decode;
 mod encode;
 mod read_buf;
+pub mod text;
 // mod write_and_flush;
 
 pub use buf::BufExt;
@@ -0,0 +1,233 @@
+//! Decoding of values sent as text by the drivers: UTF-8 validation of text columns, numbers,
+//! and Postgres `BYTEA`.
+//!
+//! With the `simd` feature, these take faster paths for the common cases: UTF-8 is validated by
+//! `simdutf8` and the escapes of `BYTEA` in the escape format are found by `memchr`, both with the
+//! widest vector instructions of the CPU, picked at runtime, and hexadecimal is decoded with a
+//! lookup table.
+
+use std::str::FromStr;
+
+use atoi::FromRadix10SignedChecked;
+use memchr::memchr;
+
+use crate::error::BoxDynError;
+
+/// Validate `bytes` as UTF-8, as [`std::str::from_utf8()`].
+#[inline]
+pub fn from_utf8(bytes: &[u8]) -> Result<&str, BoxDynError> {
+    #[cfg(feature = "simd")]
+    {
+        Ok(simdutf8::compat::from_utf8(bytes)?)
+    }
+
+    #[cfg(not(feature = "simd"))]
+    Ok(std::str::from_utf8(bytes)?)
+}
+
+/// Parse an integer straight from its bytes, without validating them as UTF-8 first.
+///
+/// Falls back to [`str::parse()`] for its error if the bytes aren't a number.
+pub fn parse_int<T>(bytes: &[u8]) -> Result<T, BoxDynError>
+where
+    T: FromRadix10SignedChecked + FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    match T::from_radix_10_signed_checked(bytes) {
+        (Some(n), len) if len == bytes.len() && len > 0 => Ok(n),
+        _ => Ok(std::str::from_utf8(bytes)?.parse()?),
+    }
+}
+
+/// Parse a floating-point number, or any other value that's written in ASCII.
+pub fn parse_float<T>(bytes: &[u8]) -> Result<T, BoxDynError>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    Ok(from_utf8(bytes)?.parse()?)
+}
+
+/// Decode a Postgres `BYTEA` in the text format: either `\x` followed by hexadecimal, or the
+/// escape format of `bytea_output = 'escape'`.
+pub fn decode_bytea(text: &[u8]) -> Result<Vec<u8>, BoxDynError> {
+    match text.strip_prefix(b"\\x") {
+        Some(hex) => decode_hex(hex),
+        None => decode_escape(text),
+    }
+}
+
+/// Decode hexadecimal into bytes.
+pub fn decode_hex(hex: &[u8]) -> Result<Vec<u8>, BoxDynError> {
+    #[cfg(feature = "simd")]
+    {
+        let mut bytes = vec![0; hex.len() / 2];
+        decode_hex_to_slice(hex, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    #[cfg(not(feature = "simd"))]
+    Ok(hex::decode(hex)?)
+}
+
+/// Decode hexadecimal into `out`, which must be half its length.
+pub fn decode_hex_to_slice(hex: &[u8], out: &mut [u8]) -> Result<(), BoxDynError> {
+    #[cfg(feature = "simd")]
+    {
+        if hex.len() % 2 == 1 {
+            return Err(hex::FromHexError::OddLength.into());
+        }
+
+        if hex.len() / 2 != out.len() {
+            return Err(hex::FromHexError::InvalidStringLength.into());
+        }
+
+        for (i, (pair, byte)) in hex.chunks_exact(2).zip(out.iter_mut()).enumerate() {
+            let high = HEX_DIGITS[usize::from(pair[0])];
+            let low = HEX_DIGITS[usize::from(pair[1])];
+
+            // invalid digits are 0xFF in the table
+            if (high | low) > 0xF {
+                let index = if high > 0xF { 2 * i } else { 2 * i + 1 };
+
+                return Err(hex::FromHexError::InvalidHexCharacter {
+                    c: char::from(hex[index]),
+                    index,
+                }
+                .into());
+            }
+
+            *byte = (high << 4) | low;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(feature = "simd"))]
+    Ok(hex::decode_to_slice(hex, out)?)
+}
+
+/// The value of each hexadecimal digit, and `0xFF` for other bytes.
+#[cfg(feature = "simd")]
+const HEX_DIGITS: [u8; 256] = {
+    let mut digits = [0xFF; 256];
+    let mut i = 0;
+
+    while i < 10 {
+        digits[b'0' as usize + i] = i as u8;
+        i += 1;
+    }
+
+    let mut i = 0;
+
+    while i < 6 {
+        digits[b'a' as usize + i] = 10 + i as u8;
+        digits[b'A' as usize + i] = 10 + i as u8;
+        i += 1;
+    }
+
+    digits
+};
+
+/// Decode the escape format of `BYTEA`, where `\\` is a backslash and `\` followed by three
+/// octal digits is the byte they encode. Other bytes are themselves.
+pub fn decode_escape(mut text: &[u8]) -> Result<Vec<u8>, BoxDynError> {
+    let mut bytes = Vec::with_capacity(text.len());
+
+    while let Some(i) = memchr(b'\\', text) {
+        bytes.extend_from_slice(&text[..i]);
+        text = &text[i + 1..];
+
+        match text {
+            [b'\\', rest @ ..] => {
+                bytes.push(b'\\');
+                text = rest;
+            }
+
+            [a @ b'0'..=b'3', b @ b'0'..=b'7', c @ b'0'..=b'7', rest @ ..] => {
+                bytes.push(((a - b'0') << 6) | ((b - b'0') << 3) | (c - b'0'));
+                text = rest;
+            }
+
+            _ => return Err("invalid escape in BYTEA".into()),
+        }
+    }
+
+    bytes.extend_from_slice(text);
+
+    Ok(bytes)
+}
+
+#[test]
+fn test_from_utf8() {
+    let ascii = "SELECT 1 -- a comment long enough to be checked a few words at a time";
+    assert_eq!(from_utf8(ascii.as_bytes()).unwrap(), ascii);
+
+    // non-ASCII after a run of ASCII, and before one
+    let text = format!("{ascii} héllo");
+    assert_eq!(from_utf8(text.as_bytes()).unwrap(), text);
+    let text = format!("é{ascii}");
+    assert_eq!(from_utf8(text.as_bytes()).unwrap(), text);
+
+    assert!(from_utf8(&[b'a'; 40].iter().copied().chain([0xFF]).collect::<Vec<_>>()).is_err());
+    assert!(from_utf8(b"\xC3").is_err());
+}
+
+#[test]
+fn test_parse_int() {
+    assert_eq!(parse_int::<i64>(b"-9223372036854775808").unwrap(), i64::MIN);
+    assert_eq!(parse_int::<u64>(b"18446744073709551615").unwrap(), u64::MAX);
+    assert_eq!(parse_int::<i64>(b"+42").unwrap(), 42);
+
+    assert_eq!(
+        parse_int::<i64>(b"9223372036854775808")
+            .unwrap_err()
+            .to_string(),
+        "number too large to fit in target type"
+    );
+    assert_eq!(
+        parse_int::<u64>(b"-1").unwrap_err().to_string(),
+        "invalid digit found in string"
+    );
+    assert!(parse_int::<i64>(b"").is_err());
+    assert!(parse_int::<i64>(b"1.5").is_err());
+}
+
+#[test]
+fn test_parse_float() {
+    assert_eq!(parse_float::<f64>(b"1.5e3").unwrap(), 1500.0);
+    assert_eq!(parse_float::<f32>(b"-Infinity").unwrap(), f32::NEG_INFINITY);
+    assert!(parse_float::<f64>(b"NaN").unwrap().is_nan());
+    assert!(parse_float::<f64>(b"1,5").is_err());
+}
+
+#[test]
+fn test_decode_bytea() {
+    assert_eq!(decode_bytea(b"\\x").unwrap(), b"");
+    assert_eq!(
+        decode_bytea(b"\\xDEADbeef00").unwrap(),
+        b"\xDE\xAD\xBE\xEF\x00"
+    );
+    assert_eq!(
+        decode_bytea(b"\\xdeadbeeg").unwrap_err().to_string(),
+        "Invalid character 'g' at position 7"
+    );
+    assert_eq!(
+        decode_bytea(b"\\xabc").unwrap_err().to_string(),
+        "Odd number of digits"
+    );
+
+    assert_eq!(decode_bytea(b"abc").unwrap(), b"abc");
+    assert_eq!(
+        decode_bytea(b"a\\\\b\\000\\377c").unwrap(),
+        b"a\\b\x00\xFFc"
+    );
+    assert!(decode_bytea(b"a\\").is_err());
+    assert!(decode_bytea(b"\\400").is_err());
+
+    let mut out = [0; 2];
+    decode_hex_to_slice(b"0aff", &mut out).unwrap();
+    assert_eq!(out, [0x0A, 0xFF]);
+    assert!(decode_hex_to_slice(b"0aff00", &mut out).is_err());
+}
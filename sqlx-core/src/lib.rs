@@ -62,17 +62,25 @@ pub mod column;
 #[macro_use]
 pub mod statement;
 
+pub mod buffer_pool;
 pub mod common;
 pub mod database;
 pub mod describe;
+pub mod distributed_lock;
 pub mod executor;
 pub mod from_row;
 pub mod fs;
 pub mod io;
 pub mod logger;
+#[macro_use]
+pub mod middleware;
 pub mod net;
+pub mod pagination;
 pub mod query_as;
 pub mod query_builder;
+#[cfg(feature = "offline")]
+pub mod query_data;
+pub mod query_grouped;
 pub mod query_scalar;
 pub mod row;
 pub mod rt;
@@ -83,6 +91,15 @@ pub mod value;
 #[cfg(feature = "migrate")]
 pub mod migrate;
 
+#[cfg(feature = "backfill")]
+pub mod backfill;
+
+#[cfg(feature = "outbox")]
+pub mod outbox;
+
+#[cfg(feature = "queue")]
+pub mod queue;
+
 #[cfg(feature = "any")]
 pub mod any;
 
@@ -102,6 +119,13 @@ pub use url::{self, Url};
 
 pub use bytes;
 
+#[doc(hidden)]
+pub use futures_core;
+
+#[cfg(feature = "json")]
+#[doc(hidden)]
+pub use serde;
+
 //type HashMap<K, V> = std::collections::HashMap<K, V, ahash::RandomState>;
 
 /// Helper module to get drivers compiling again that used to be in this crate,
@@ -56,10 +56,49 @@ pub fn private_level_filter_to_levels(
 
 pub use sqlformat;
 
+/// Protocol-level statistics for a statement, logged at the level set by
+/// [`ConnectOptions::log_wire_stats()`][crate::connection::ConnectOptions::log_wire_stats].
+///
+/// A statement that takes many round-trips, or prepares statements it could have cached,
+/// is a sign of a chatty query pattern that would benefit from batching.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct WireStats {
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+
+    /// The number of times the client sent data and then waited for the server to respond.
+    pub round_trips: u64,
+
+    /// The number of statements parsed (prepared) by the server.
+    pub parses: u64,
+
+    /// The number of statements described, to get their parameter and column types.
+    pub describes: u64,
+
+    /// The number of statements executed.
+    pub executes: u64,
+}
+
+impl WireStats {
+    /// The statistics accumulated since `earlier`, taken from the same connection.
+    pub fn since(&self, earlier: &WireStats) -> WireStats {
+        WireStats {
+            bytes_sent: self.bytes_sent.saturating_sub(earlier.bytes_sent),
+            bytes_received: self.bytes_received.saturating_sub(earlier.bytes_received),
+            round_trips: self.round_trips.saturating_sub(earlier.round_trips),
+            parses: self.parses.saturating_sub(earlier.parses),
+            describes: self.describes.saturating_sub(earlier.describes),
+            executes: self.executes.saturating_sub(earlier.executes),
+        }
+    }
+}
+
 pub struct QueryLogger<'q> {
     sql: &'q str,
     rows_returned: u64,
     rows_affected: u64,
+    wire_stats: Option<WireStats>,
     start: Instant,
     settings: LogSettings,
 }
@@ -70,6 +109,7 @@ impl<'q> QueryLogger<'q> {
             sql,
             rows_returned: 0,
             rows_affected: 0,
+            wire_stats: None,
             start: Instant::now(),
             settings,
         }
@@ -83,9 +123,16 @@ impl<'q> QueryLogger<'q> {
         self.rows_affected += n;
     }
 
+    /// Record the protocol-level statistics for this statement, if the driver has them.
+    pub fn set_wire_stats(&mut self, wire_stats: WireStats) {
+        self.wire_stats = Some(wire_stats);
+    }
+
     pub fn finish(&self) {
         let elapsed = self.start.elapsed();
 
+        self.log_wire_stats();
+
         let lvl = if elapsed >= self.settings.slow_statements_duration {
             self.settings.slow_statements_level
         } else {
@@ -128,6 +175,37 @@ impl<'q> QueryLogger<'q> {
     }
 }
 
+impl QueryLogger<'_> {
+    fn log_wire_stats(&self) {
+        let Some(stats) = &self.wire_stats else {
+            return;
+        };
+
+        let Some((tracing_level, log_level)) =
+            private_level_filter_to_levels(self.settings.wire_stats_level)
+        else {
+            return;
+        };
+
+        let log_is_enabled = log::log_enabled!(target: "sqlx::query::wire", log_level)
+            || private_tracing_dynamic_enabled!(target: "sqlx::query::wire", tracing_level);
+
+        if log_is_enabled {
+            private_tracing_dynamic_event!(
+                target: "sqlx::query::wire",
+                tracing_level,
+                summary = parse_query_summary(self.sql),
+                bytes_sent = stats.bytes_sent,
+                bytes_received = stats.bytes_received,
+                round_trips = stats.round_trips,
+                parses = stats.parses,
+                describes = stats.describes,
+                executes = stats.executes,
+            );
+        }
+    }
+}
+
 impl<'q> Drop for QueryLogger<'q> {
     fn drop(&mut self) {
         self.finish();
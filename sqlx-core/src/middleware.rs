@@ -0,0 +1,504 @@
+//! Middleware around query execution, for cross-cutting concerns like tagging queries with
+//! comments, collecting metrics, caching results or routing queries to other databases.
+//!
+//! An [`ExecutorMiddleware`] sees each query before it's executed, and can rewrite its SQL and
+//! arguments, wrap the stream of its results, or answer it without executing it at all.
+//! Middleware is added to a [`Pool`][crate::pool::Pool] or a
+//! [`Connection`][crate::connection::Connection] with `with_middleware()`, which returns an
+//! [`Executor`] that runs every query through it:
+//!
+//! ```rust,ignore
+//! use sqlx::middleware::{ExecutorMiddleware, MiddlewareQuery, Next, QueryResults};
+//! use sqlx::Database;
+//!
+//! /// Tags every query with the name of the service, like sqlcommenter.
+//! struct Tag(&'static str);
+//!
+//! impl<DB: Database> ExecutorMiddleware<DB> for Tag {
+//!     fn fetch_many<'e, 'q: 'e>(
+//!         &'e self,
+//!         mut query: MiddlewareQuery<'q, DB>,
+//!         next: Next<'e, 'q, DB>,
+//!     ) -> QueryResults<'e, DB> {
+//!         let sql = format!("{} /* service='{}' */", query.sql(), self.0);
+//!         query.set_sql(sql);
+//!         next.run(query)
+//!     }
+//! }
+//!
+//! let pool = pool.with_middleware(Tag("billing"));
+//!
+//! sqlx::query("SELECT 1").execute(&pool).await?;
+//! ```
+
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use either::Either;
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+use futures_util::TryStreamExt;
+
+use crate::arguments::shorten;
+use crate::database::{Database, HasArguments, HasStatement};
+use crate::describe::Describe;
+use crate::error::Error;
+use crate::executor::{Execute, Executor};
+use crate::pool::Pool;
+
+/// The results of a query, as returned by [`Executor::fetch_many()`].
+pub type QueryResults<'e, DB> =
+    BoxStream<'e, Result<Either<<DB as Database>::QueryResult, <DB as Database>::Row>, Error>>;
+
+/// Hooks around the execution of every query run through [`WithMiddleware`].
+///
+/// Every method of [`Executor`] is run through [`Self::fetch_many()`]; e.g. for `fetch_one()`,
+/// the stream is dropped after the first row.
+pub trait ExecutorMiddleware<DB: Database>: Send + Sync + 'static {
+    /// Executes `query`, usually by passing it on to [`Next::run()`], which runs the rest of the
+    /// middleware and then the query itself.
+    ///
+    /// The middleware can also return results without calling `next`, e.g. from a cache, or
+    /// execute the query somewhere else with [`MiddlewareQuery::fetch_many()`].
+    fn fetch_many<'e, 'q: 'e>(
+        &'e self,
+        query: MiddlewareQuery<'q, DB>,
+        next: Next<'e, 'q, DB>,
+    ) -> QueryResults<'e, DB>;
+}
+
+/// A query being executed through [`ExecutorMiddleware`].
+///
+/// Queries of prepared statements are executed by their SQL, which is prepared again if the
+/// connection doesn't have it cached.
+pub struct MiddlewareQuery<'q, DB: Database> {
+    sql: Cow<'q, str>,
+    arguments: Option<<DB as HasArguments<'q>>::Arguments>,
+    persistent: bool,
+    fetch_size: Option<u32>,
+    size_limited: bool,
+}
+
+/// The rest of the middleware after the current one, and then the executor.
+pub struct Next<'e, 'q, DB: Database> {
+    middleware: &'e [Arc<dyn ExecutorMiddleware<DB>>],
+    executor: Box<dyn FnOnce(MiddlewareQuery<'q, DB>) -> QueryResults<'e, DB> + Send + 'e>,
+}
+
+/// An executor that runs every query through a chain of [`ExecutorMiddleware`].
+///
+/// Created with [`Pool::with_middleware()`][crate::pool::Pool::with_middleware] or
+/// [`Connection::with_middleware()`][crate::connection::Connection::with_middleware].
+/// `&WithMiddleware<Pool<DB>, DB>` and `&mut WithMiddleware<&mut DB::Connection, DB>` are
+/// executors.
+pub struct WithMiddleware<X, DB: Database> {
+    inner: X,
+    middleware: Vec<Arc<dyn ExecutorMiddleware<DB>>>,
+}
+
+impl<'q, DB: Database> MiddlewareQuery<'q, DB> {
+    fn new<E: Execute<'q, DB>>(mut query: E) -> Self {
+        MiddlewareQuery {
+            sql: Cow::Borrowed(query.sql()),
+            arguments: query.take_arguments(),
+            persistent: query.persistent(),
+            fetch_size: query.fetch_size(),
+            size_limited: query.size_limited(),
+        }
+    }
+
+    /// The SQL of the query.
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    /// Replaces the SQL of the query.
+    pub fn set_sql(&mut self, sql: impl Into<String>) {
+        self.sql = Cow::Owned(sql.into());
+    }
+
+    /// The arguments bound to the query, if any, which can also be replaced or added to.
+    pub fn arguments_mut(&mut self) -> &mut Option<<DB as HasArguments<'q>>::Arguments> {
+        &mut self.arguments
+    }
+
+    /// Whether the query is cached as a prepared statement on the connection.
+    pub fn persistent(&self) -> bool {
+        self.persistent
+    }
+
+    /// Sets whether the query is cached as a prepared statement on the connection.
+    pub fn set_persistent(&mut self, persistent: bool) {
+        self.persistent = persistent;
+    }
+
+    /// The number of rows to fetch from the database at a time, if limited with
+    /// [`Query::fetch_size()`][crate::query::Query::fetch_size].
+    pub fn fetch_size(&self) -> Option<u32> {
+        self.fetch_size
+    }
+
+    /// Whether the row and result size limits of the connection apply to the query.
+    pub fn size_limited(&self) -> bool {
+        self.size_limited
+    }
+
+    /// Executes the query with `executor`, bypassing the rest of the middleware.
+    pub fn fetch_many<'e, 'c: 'e, E>(self, executor: E) -> QueryResults<'e, DB>
+    where
+        'q: 'e,
+        E: 'e + Executor<'c, Database = DB>,
+    {
+        let MiddlewareQuery {
+            sql,
+            arguments,
+            persistent,
+            fetch_size,
+            size_limited,
+        } = self;
+
+        Box::pin(try_stream! {
+            // the SQL may be owned by this stream now, so the arguments can't live any longer
+            let query = Rewritten::<DB> {
+                sql: &sql,
+                arguments: arguments.map(shorten::<DB>).transpose()?,
+                persistent,
+                fetch_size,
+                size_limited,
+            };
+
+            let mut s = executor.fetch_many(query);
+
+            while let Some(v) = s.try_next().await? {
+                r#yield!(v);
+            }
+
+            Ok(())
+        })
+    }
+}
+
+impl<DB: Database> Debug for MiddlewareQuery<'_, DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MiddlewareQuery")
+            .field("sql", &self.sql)
+            .field("persistent", &self.persistent)
+            .field("fetch_size", &self.fetch_size)
+            .field("size_limited", &self.size_limited)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'e, 'q: 'e, DB: Database> Next<'e, 'q, DB> {
+    /// Executes `query` with the rest of the middleware and then the executor.
+    pub fn run(self, query: MiddlewareQuery<'q, DB>) -> QueryResults<'e, DB> {
+        match self.middleware.split_first() {
+            Some((middleware, rest)) => middleware.fetch_many(
+                query,
+                Next {
+                    middleware: rest,
+                    executor: self.executor,
+                },
+            ),
+            None => (self.executor)(query),
+        }
+    }
+}
+
+impl<X, DB: Database> WithMiddleware<X, DB> {
+    pub(crate) fn new(inner: X, middleware: impl ExecutorMiddleware<DB>) -> Self {
+        WithMiddleware {
+            inner,
+            middleware: vec![Arc::new(middleware)],
+        }
+    }
+
+    /// Adds another middleware, which runs after the ones added before it.
+    pub fn with_middleware(mut self, middleware: impl ExecutorMiddleware<DB>) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// The wrapped pool or connection, for executing queries without the middleware.
+    pub fn inner(&self) -> &X {
+        &self.inner
+    }
+
+    /// Unwraps the pool or connection.
+    pub fn into_inner(self) -> X {
+        self.inner
+    }
+}
+
+impl<X: Debug, DB: Database> Debug for WithMiddleware<X, DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WithMiddleware")
+            .field("inner", &self.inner)
+            .field("middleware", &self.middleware.len())
+            .finish()
+    }
+}
+
+impl<X: Clone, DB: Database> Clone for WithMiddleware<X, DB> {
+    fn clone(&self) -> Self {
+        WithMiddleware {
+            inner: self.inner.clone(),
+            middleware: self.middleware.clone(),
+        }
+    }
+}
+
+fn run<'e, 'q: 'e, DB, E, X>(
+    middleware: &'e [Arc<dyn ExecutorMiddleware<DB>>],
+    query: E,
+    executor: X,
+) -> QueryResults<'e, DB>
+where
+    DB: Database,
+    E: 'q + Execute<'q, DB>,
+    X: 'e + Send + FnOnce(MiddlewareQuery<'q, DB>) -> QueryResults<'e, DB>,
+{
+    Next {
+        middleware,
+        executor: Box::new(executor),
+    }
+    .run(MiddlewareQuery::new(query))
+}
+
+async fn first_row<DB: Database>(
+    mut results: QueryResults<'_, DB>,
+) -> Result<Option<DB::Row>, Error> {
+    while let Some(step) = results.try_next().await? {
+        if let Either::Right(row) = step {
+            return Ok(Some(row));
+        }
+    }
+
+    Ok(None)
+}
+
+impl<'c, DB> Executor<'c> for &'c WithMiddleware<Pool<DB>, DB>
+where
+    DB: Database,
+    for<'x> &'x mut DB::Connection: Executor<'x, Database = DB>,
+{
+    type Database = DB;
+
+    fn fetch_many<'e, 'q: 'e, E>(self, query: E) -> QueryResults<'e, DB>
+    where
+        'c: 'e,
+        E: 'q + Execute<'q, DB>,
+    {
+        run(&self.middleware, query, move |query| {
+            query.fetch_many(&self.inner)
+        })
+    }
+
+    fn fetch_optional<'e, 'q: 'e, E>(
+        self,
+        query: E,
+    ) -> BoxFuture<'e, Result<Option<DB::Row>, Error>>
+    where
+        'c: 'e,
+        E: 'q + Execute<'q, DB>,
+    {
+        Box::pin(first_row::<DB>(self.fetch_many(query)))
+    }
+
+    fn prepare_with<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+        parameters: &'e [DB::TypeInfo],
+    ) -> BoxFuture<'e, Result<<DB as HasStatement<'q>>::Statement, Error>>
+    where
+        'c: 'e,
+    {
+        (&self.inner).prepare_with(sql, parameters)
+    }
+
+    #[doc(hidden)]
+    fn describe<'e, 'q: 'e>(self, sql: &'q str) -> BoxFuture<'e, Result<Describe<DB>, Error>>
+    where
+        'c: 'e,
+    {
+        (&self.inner).describe(sql)
+    }
+}
+
+/// Executes queries on connections to this database, for `&mut WithMiddleware<&mut
+/// DB::Connection, DB>`.
+///
+/// Implemented by each driver with `impl_middleware_connection!()`, since requiring
+/// `&mut DB::Connection: Executor` instead sends trait resolution into infinite recursion
+/// wherever that bound is required for an unknown `DB`.
+#[doc(hidden)]
+pub trait MiddlewareConnection: Database {
+    fn fetch_many<'e, 'q: 'e, E>(
+        conn: &'e mut Self::Connection,
+        query: E,
+    ) -> QueryResults<'e, Self>
+    where
+        E: 'q + Execute<'q, Self>;
+
+    fn prepare_with<'e, 'q: 'e>(
+        conn: &'e mut Self::Connection,
+        sql: &'q str,
+        parameters: &'e [Self::TypeInfo],
+    ) -> BoxFuture<'e, Result<<Self as HasStatement<'q>>::Statement, Error>>;
+
+    fn describe<'e, 'q: 'e>(
+        conn: &'e mut Self::Connection,
+        sql: &'q str,
+    ) -> BoxFuture<'e, Result<Describe<Self>, Error>>;
+}
+
+#[macro_export]
+macro_rules! impl_middleware_connection {
+    ($DB:ident) => {
+        impl $crate::middleware::MiddlewareConnection for $DB {
+            fn fetch_many<'e, 'q: 'e, E>(
+                conn: &'e mut <$DB as $crate::database::Database>::Connection,
+                query: E,
+            ) -> $crate::middleware::QueryResults<'e, $DB>
+            where
+                E: 'q + $crate::executor::Execute<'q, $DB>,
+            {
+                $crate::executor::Executor::fetch_many(conn, query)
+            }
+
+            fn prepare_with<'e, 'q: 'e>(
+                conn: &'e mut <$DB as $crate::database::Database>::Connection,
+                sql: &'q str,
+                parameters: &'e [<$DB as $crate::database::Database>::TypeInfo],
+            ) -> $crate::futures_core::future::BoxFuture<
+                'e,
+                Result<
+                    <$DB as $crate::database::HasStatement<'q>>::Statement,
+                    $crate::error::Error,
+                >,
+            > {
+                $crate::executor::Executor::prepare_with(conn, sql, parameters)
+            }
+
+            fn describe<'e, 'q: 'e>(
+                conn: &'e mut <$DB as $crate::database::Database>::Connection,
+                sql: &'q str,
+            ) -> $crate::futures_core::future::BoxFuture<
+                'e,
+                Result<$crate::describe::Describe<$DB>, $crate::error::Error>,
+            > {
+                $crate::executor::Executor::describe(conn, sql)
+            }
+        }
+    };
+}
+
+impl<'c, DB> Executor<'c> for &'c mut WithMiddleware<&'_ mut DB::Connection, DB>
+where
+    DB: MiddlewareConnection,
+    DB::Connection: Debug,
+{
+    type Database = DB;
+
+    fn fetch_many<'e, 'q: 'e, E>(self, query: E) -> QueryResults<'e, DB>
+    where
+        'c: 'e,
+        E: 'q + Execute<'q, DB>,
+    {
+        let conn = &mut *self.inner;
+
+        run(&self.middleware, query, move |query| {
+            let MiddlewareQuery {
+                sql,
+                arguments,
+                persistent,
+                fetch_size,
+                size_limited,
+            } = query;
+
+            Box::pin(try_stream! {
+                let query = Rewritten::<DB> {
+                    sql: &sql,
+                    arguments: arguments.map(shorten::<DB>).transpose()?,
+                    persistent,
+                    fetch_size,
+                    size_limited,
+                };
+
+                let mut s = DB::fetch_many(conn, query);
+
+                while let Some(v) = s.try_next().await? {
+                    r#yield!(v);
+                }
+
+                Ok(())
+            })
+        })
+    }
+
+    fn fetch_optional<'e, 'q: 'e, E>(
+        self,
+        query: E,
+    ) -> BoxFuture<'e, Result<Option<DB::Row>, Error>>
+    where
+        'c: 'e,
+        E: 'q + Execute<'q, DB>,
+    {
+        Box::pin(first_row::<DB>(self.fetch_many(query)))
+    }
+
+    fn prepare_with<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+        parameters: &'e [DB::TypeInfo],
+    ) -> BoxFuture<'e, Result<<DB as HasStatement<'q>>::Statement, Error>>
+    where
+        'c: 'e,
+    {
+        DB::prepare_with(self.inner, sql, parameters)
+    }
+
+    #[doc(hidden)]
+    fn describe<'e, 'q: 'e>(self, sql: &'q str) -> BoxFuture<'e, Result<Describe<DB>, Error>>
+    where
+        'c: 'e,
+    {
+        DB::describe(self.inner, sql)
+    }
+}
+
+/// A [`MiddlewareQuery`] with a lifetime that `Execute` can work with.
+struct Rewritten<'q, DB: Database> {
+    sql: &'q str,
+    arguments: Option<<DB as HasArguments<'q>>::Arguments>,
+    persistent: bool,
+    fetch_size: Option<u32>,
+    size_limited: bool,
+}
+
+impl<'q, DB: Database> Execute<'q, DB> for Rewritten<'q, DB> {
+    fn sql(&self) -> &'q str {
+        self.sql
+    }
+
+    fn statement(&self) -> Option<&<DB as HasStatement<'q>>::Statement> {
+        None
+    }
+
+    fn take_arguments(&mut self) -> Option<<DB as HasArguments<'q>>::Arguments> {
+        self.arguments.take()
+    }
+
+    fn persistent(&self) -> bool {
+        self.persistent
+    }
+
+    fn fetch_size(&self) -> Option<u32> {
+        self.fetch_size
+    }
+
+    fn size_limited(&self) -> bool {
+        self.size_limited
+    }
+}
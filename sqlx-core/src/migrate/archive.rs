@@ -0,0 +1,211 @@
+use crate::error::BoxDynError;
+use crate::migrate::source::parse_file_name;
+use crate::migrate::Migration;
+use std::borrow::Cow;
+use std::str;
+
+// tar archives are made of 512-byte blocks: a header for each entry followed by its data
+const BLOCK: usize = 512;
+
+/// Resolves the migrations in a tar archive of migration files, e.g. one created with
+/// `tar -cf migrations.tar migrations`.
+///
+/// Files are matched by name like those in a directory, wherever they are in the archive.
+pub(crate) fn resolve_tar(archive: &[u8]) -> Result<Vec<Migration>, BoxDynError> {
+    if archive.starts_with(&[0x1f, 0x8b]) {
+        return Err("the migration archive is compressed with gzip; decompress it first".into());
+    }
+
+    let mut migrations = Vec::new();
+
+    // the name of the next entry, if too long for its header
+    let mut long_name: Option<String> = None;
+
+    let mut rest = archive;
+
+    while rest.len() >= BLOCK {
+        let (header, body) = rest.split_at(BLOCK);
+
+        // the archive ends with empty blocks
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+
+        if parse_octal(&header[148..156])? != checksum(header) {
+            return Err("the migration archive is not a tar archive, or is corrupted".into());
+        }
+
+        let size = parse_octal(&header[124..136])? as usize;
+        let data = body
+            .get(..size)
+            .ok_or("the migration archive is truncated")?;
+
+        rest = body.get(size.div_ceil(BLOCK) * BLOCK..).unwrap_or_default();
+
+        match header[156] {
+            // a regular file
+            b'0' | b'\0' | b'7' => {
+                let name = match long_name.take() {
+                    Some(name) => name,
+                    None => field(&header[..100])?.to_owned(),
+                };
+
+                let file_name = name.rsplit('/').next().unwrap_or_default();
+
+                // resource forks added by `tar` on macOS
+                if file_name.starts_with("._") {
+                    continue;
+                }
+
+                let Some((version, description, migration_type)) = parse_file_name(file_name)?
+                else {
+                    continue;
+                };
+
+                let sql = str::from_utf8(data)
+                    .map_err(|e| format!("migration {file_name:?} is not valid UTF-8: {e}"))?;
+
+                migrations.push(Migration::new(
+                    version,
+                    Cow::Owned(description),
+                    migration_type,
+                    Cow::Owned(sql.to_owned()),
+                ));
+            }
+
+            // a GNU long name
+            b'L' => long_name = Some(field(data)?.to_owned()),
+
+            // pax extended attributes, which may have the path
+            b'x' => long_name = pax_path(data)?.or(long_name),
+
+            // directories, links and global pax attributes
+            _ => long_name = None,
+        }
+    }
+
+    // ensure that we are sorted by `VERSION ASC`
+    migrations.sort_by_key(|m| m.version);
+
+    Ok(migrations)
+}
+
+// the sum of the bytes of the header, with its checksum field as spaces
+fn checksum(header: &[u8]) -> u64 {
+    header
+        .iter()
+        .enumerate()
+        .map(|(i, &b)| match i {
+            148..=155 => u64::from(b' '),
+            _ => u64::from(b),
+        })
+        .sum()
+}
+
+fn parse_octal(field: &[u8]) -> Result<u64, BoxDynError> {
+    // GNU tar stores large numbers in big-endian base-256, flagged by the high bit
+    if matches!(field.first(), Some(&b) if b & 0x80 != 0) {
+        return Ok(field[1..].iter().fold(0, |n, &b| (n << 8) | u64::from(b)));
+    }
+
+    let s = str::from_utf8(field)?.trim_matches(|c| c == '\0' || c == ' ');
+
+    if s.is_empty() {
+        return Ok(0);
+    }
+
+    Ok(u64::from_str_radix(s, 8)?)
+}
+
+// a string field, padded with NUL bytes
+fn field(bytes: &[u8]) -> Result<&str, BoxDynError> {
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+
+    Ok(str::from_utf8(&bytes[..len])?)
+}
+
+// the `path` in pax records of the format `<LENGTH> <KEY>=<VALUE>\n`
+fn pax_path(mut records: &[u8]) -> Result<Option<String>, BoxDynError> {
+    let mut path = None;
+
+    while let Some(space) = records.iter().position(|&b| b == b' ') {
+        let len: usize = str::from_utf8(&records[..space])?.parse()?;
+
+        let record = records
+            .get(space + 1..len)
+            .ok_or("the migration archive has an invalid pax record")?;
+        records = &records[len..];
+
+        let record = str::from_utf8(record)?.trim_end_matches('\n');
+
+        if let Some(value) = record.strip_prefix("path=") {
+            path = Some(value.to_owned());
+        }
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+fn tar_entry(name: &str, type_flag: u8, data: &[u8]) -> Vec<u8> {
+    let mut header = [0u8; BLOCK];
+
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[124..136].copy_from_slice(format!("{:011o}\0", data.len()).as_bytes());
+    header[156] = type_flag;
+    header[257..265].copy_from_slice(b"ustar\x0000");
+
+    let checksum = checksum(&header);
+    header[148..156].copy_from_slice(format!("{checksum:06o}\0 ").as_bytes());
+
+    let mut entry = header.to_vec();
+    entry.extend_from_slice(data);
+    entry.resize(entry.len().div_ceil(BLOCK) * BLOCK, 0);
+    entry
+}
+
+#[test]
+fn test_resolve_tar() {
+    let long_name = format!("migrations/{}/2_add_users.up.sql", "a".repeat(100));
+
+    let mut archive = [
+        tar_entry("migrations/", b'5', b""),
+        tar_entry("migrations/3_seed.sql", b'0', b"INSERT INTO t VALUES (1);"),
+        tar_entry("migrations/._3_seed.sql", b'0', b"\0\x05\x16\x07"),
+        tar_entry("././@LongLink", b'L', long_name.as_bytes()),
+        tar_entry(
+            "migrations/2_add_users.up",
+            b'0',
+            b"CREATE TABLE t (id INT);",
+        ),
+        tar_entry("migrations/README.md", b'0', b"# Migrations"),
+        tar_entry("migrations/1_init.sql", b'0', b""),
+    ]
+    .concat();
+    archive.extend_from_slice(&[0; BLOCK * 2]);
+
+    let migrations = resolve_tar(&archive).unwrap();
+
+    let resolved: Vec<_> = migrations
+        .iter()
+        .map(|m| (m.version, &*m.description, &*m.sql))
+        .collect();
+
+    assert_eq!(
+        resolved,
+        [
+            (1, "init", ""),
+            (2, "add users", "CREATE TABLE t (id INT);"),
+            (3, "seed", "INSERT INTO t VALUES (1);"),
+        ]
+    );
+
+    assert_eq!(
+        pax_path(b"30 mtime=1700000000.123456789\n27 path=migrations/1_a.sql\n").unwrap(),
+        Some("migrations/1_a.sql".to_owned())
+    );
+
+    archive[0] = b'x';
+    assert!(resolve_tar(&archive).is_err());
+    assert!(resolve_tar(&[0x1f, 0x8b, 0x08]).is_err());
+}
@@ -0,0 +1,127 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::Migration;
+
+/// Who applied migrations and from where, recorded in the `_sqlx_migrations_log` table
+/// if set with [`Migrator::set_audit_log()`][crate::migrate::Migrator::set_audit_log].
+#[derive(Debug, Clone, Default)]
+pub struct AuditInfo {
+    /// The host name of the machine applying the migrations.
+    pub hostname: Option<String>,
+    /// The operating system user applying the migrations.
+    pub os_user: Option<String>,
+    /// The tool and version applying the migrations, e.g. `sqlx-cli 0.7.1`.
+    pub tool_version: Option<String>,
+    /// The commit the migrations were applied from.
+    pub git_commit: Option<String>,
+}
+
+impl AuditInfo {
+    /// Read the host name, user and commit from the environment.
+    ///
+    /// The commit is read from the `GIT_COMMIT`, `GITHUB_SHA` or `CI_COMMIT_SHA` environment
+    /// variables, one of which is set by most CI services.
+    pub fn from_env() -> Self {
+        let hostname = env_var(&["HOSTNAME", "COMPUTERNAME"]).or_else(|| {
+            let hostname = std::fs::read_to_string("/etc/hostname").ok()?;
+            Some(hostname.trim().to_owned()).filter(|hostname| !hostname.is_empty())
+        });
+
+        AuditInfo {
+            hostname,
+            os_user: env_var(&["USER", "USERNAME"]),
+            tool_version: None,
+            git_commit: env_var(&["GIT_COMMIT", "GITHUB_SHA", "CI_COMMIT_SHA"]),
+        }
+    }
+}
+
+fn env_var(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()))
+}
+
+/// A row of the `_sqlx_migrations_log` table, recording a migration being applied or reverted.
+#[derive(Debug, Clone)]
+pub struct MigrationLogEntry {
+    pub version: i64,
+    pub description: String,
+    /// `true` if the migration was reverted rather than applied.
+    pub reverted: bool,
+    /// When the migration finished, by the clock of the machine that applied it.
+    pub applied_at: SystemTime,
+    pub execution_time: Duration,
+    pub hostname: Option<String>,
+    pub os_user: Option<String>,
+    pub tool_version: Option<String>,
+    pub git_commit: Option<String>,
+}
+
+/// The columns of `_sqlx_migrations_log` in the order drivers select them, with `applied_at`
+/// converted to seconds since the Unix epoch and `execution_time` in nanoseconds.
+#[doc(hidden)]
+pub type MigrationLogRow = (
+    i64,
+    String,
+    bool,
+    i64,
+    i64,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+impl From<MigrationLogRow> for MigrationLogEntry {
+    fn from(row: MigrationLogRow) -> Self {
+        let (
+            version,
+            description,
+            reverted,
+            applied_at,
+            execution_time,
+            hostname,
+            os_user,
+            tool_version,
+            git_commit,
+        ) = row;
+
+        MigrationLogEntry {
+            version,
+            description,
+            reverted,
+            applied_at: UNIX_EPOCH + Duration::from_secs(applied_at.try_into().unwrap_or(0)),
+            execution_time: Duration::from_nanos(execution_time.try_into().unwrap_or(0)),
+            hostname,
+            os_user,
+            tool_version,
+            git_commit,
+        }
+    }
+}
+
+impl MigrationLogEntry {
+    /// An entry for `migration` finishing now, after `execution_time`.
+    pub fn new(migration: &Migration, execution_time: Duration, audit: &AuditInfo) -> Self {
+        MigrationLogEntry {
+            version: migration.version,
+            description: migration.description.to_string(),
+            reverted: migration.migration_type.is_down_migration(),
+            applied_at: SystemTime::now(),
+            execution_time,
+            hostname: audit.hostname.clone(),
+            os_user: audit.os_user.clone(),
+            tool_version: audit.tool_version.clone(),
+            git_commit: audit.git_commit.clone(),
+        }
+    }
+
+    /// `applied_at` in seconds since the Unix epoch, for drivers to convert to a timestamp.
+    #[doc(hidden)]
+    pub fn applied_at_secs(&self) -> i64 {
+        self.applied_at
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs() as i64)
+    }
+}
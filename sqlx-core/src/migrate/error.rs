@@ -33,4 +33,7 @@ pub enum MigrateError {
         "migration {0} is partially applied; fix and remove row from `_sqlx_migrations` table"
     )]
     Dirty(i64),
+
+    #[error("the migrations audit log is not supported by this database driver")]
+    AuditLogUnsupported,
 }
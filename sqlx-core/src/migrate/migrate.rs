@@ -1,7 +1,9 @@
 use crate::error::Error;
-use crate::migrate::{AppliedMigration, MigrateError, Migration};
+use crate::executor::Executor;
+use crate::migrate::{AppliedMigration, MigrateError, Migration, MigrationLogEntry};
 use futures_core::future::BoxFuture;
-use std::time::Duration;
+use futures_util::TryStreamExt;
+use std::time::{Duration, Instant};
 
 pub trait MigrateDatabase {
     // create database in url
@@ -56,4 +58,70 @@ pub trait Migrate {
         &'e mut self,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>>;
+
+    // like `apply`, but also returns the time each statement of the migration SQL took to run,
+    // in order; none if the driver doesn't time them
+    fn apply_timed<'e: 'm, 'm>(
+        &'e mut self,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(Duration, Vec<Duration>), MigrateError>> {
+        let apply = self.apply(migration);
+        Box::pin(async move { Ok((apply.await?, Vec::new())) })
+    }
+
+    // like `revert`, but also returns the time each statement of the migration SQL took to run,
+    // in order; none if the driver doesn't time them
+    fn revert_timed<'e: 'm, 'm>(
+        &'e mut self,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(Duration, Vec<Duration>), MigrateError>> {
+        let revert = self.revert(migration);
+        Box::pin(async move { Ok((revert.await?, Vec::new())) })
+    }
+
+    // ensure the [_migrations_log] audit table exists
+    // returns `MigrateError::AuditLogUnsupported` unless the driver implements the audit log
+    fn ensure_migrations_log_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+        Box::pin(async { Err(MigrateError::AuditLogUnsupported) })
+    }
+
+    // insert a row into the [_migrations_log] table for a migration that was applied or reverted
+    fn log_migration<'e: 'm, 'm>(
+        &'e mut self,
+        _entry: &'m MigrationLogEntry,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async { Err(MigrateError::AuditLogUnsupported) })
+    }
+
+    // Return the rows of the [_migrations_log] table in the order they were inserted
+    fn list_migrations_log(
+        &mut self,
+    ) -> BoxFuture<'_, Result<Vec<MigrationLogEntry>, MigrateError>> {
+        Box::pin(async { Err(MigrateError::AuditLogUnsupported) })
+    }
+}
+
+/// Executes the statements of `sql`, returning the time each of them took to run, in order.
+#[doc(hidden)]
+pub fn execute_timed<'c, 'q: 'c, E>(
+    executor: E,
+    sql: &'q str,
+) -> BoxFuture<'c, Result<Vec<Duration>, Error>>
+where
+    E: Executor<'c>,
+{
+    let mut results = executor.execute_many(sql);
+
+    Box::pin(async move {
+        let mut timings = Vec::new();
+        let mut start = Instant::now();
+
+        // each statement yields its result once it has run
+        while results.try_next().await?.is_some() {
+            timings.push(start.elapsed());
+            start = Instant::now();
+        }
+
+        Ok(timings)
+    })
 }
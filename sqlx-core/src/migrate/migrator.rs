@@ -1,5 +1,9 @@
 use crate::acquire::Acquire;
-use crate::migrate::{AppliedMigration, Migrate, MigrateError, Migration, MigrationSource};
+use crate::migrate::archive::resolve_tar;
+use crate::migrate::{
+    AppliedMigration, AuditInfo, Migrate, MigrateError, Migration, MigrationLogEntry,
+    MigrationSource,
+};
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::ops::Deref;
@@ -11,6 +15,10 @@ pub struct Migrator {
     pub migrations: Cow<'static, [Migration]>,
     pub ignore_missing: bool,
     pub locking: bool,
+    pub audit_log: Option<AuditInfo>,
+    /// Versions of migrations removed by [`Migrator::filter()`], which are neither applied nor
+    /// reported as missing.
+    pub skipped: Cow<'static, [i64]>,
 }
 
 fn validate_applied_migrations(
@@ -21,7 +29,11 @@ fn validate_applied_migrations(
         return Ok(());
     }
 
-    let migrations: HashSet<_> = migrator.iter().map(|m| m.version).collect();
+    let migrations: HashSet<_> = migrator
+        .iter()
+        .map(|m| m.version)
+        .chain(migrator.skipped.iter().copied())
+        .collect();
 
     for applied_migration in applied_migrations {
         if !migrations.contains(&applied_migration.version) {
@@ -59,9 +71,66 @@ impl Migrator {
             migrations: Cow::Owned(source.resolve().await.map_err(MigrateError::Source)?),
             ignore_missing: false,
             locking: true,
+            audit_log: None,
+            skipped: Cow::Borrowed(&[]),
         })
     }
 
+    /// Creates a new instance with the migrations in a tar archive.
+    ///
+    /// The archive holds the migration files, in any directory, like those of
+    /// [`Migrator::new()`], so a single bundle can be shipped with a binary and applied
+    /// without a checkout of the sources. It can't be compressed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// // created with `tar -cf migrations.tar migrations`
+    /// let m = Migrator::from_tar(include_bytes!("../migrations.tar"))?;
+    /// ```
+    pub fn from_tar(archive: &[u8]) -> Result<Self, MigrateError> {
+        Ok(Self {
+            migrations: Cow::Owned(resolve_tar(archive).map_err(MigrateError::Source)?),
+            ignore_missing: false,
+            locking: true,
+            audit_log: None,
+            skipped: Cow::Borrowed(&[]),
+        })
+    }
+
+    /// Returns a copy with only the migrations for which `predicate` returns `true`, e.g. to skip
+    /// data backfills in tests.
+    ///
+    /// The other migrations are neither applied nor reverted, and aren't reported as missing if
+    /// they were applied before.
+    ///
+    /// ```rust,ignore
+    /// static MIGRATOR: Migrator = sqlx::migrate!();
+    ///
+    /// MIGRATOR
+    ///     .filter(|m| !m.description.starts_with("backfill"))
+    ///     .run(&pool)
+    ///     .await?;
+    /// ```
+    pub fn filter(&self, mut predicate: impl FnMut(&Migration) -> bool) -> Migrator {
+        let (migrations, skipped): (Vec<_>, Vec<_>) =
+            self.iter().cloned().partition(|m| predicate(m));
+
+        Migrator {
+            migrations: Cow::Owned(migrations),
+            ignore_missing: self.ignore_missing,
+            locking: self.locking,
+            audit_log: self.audit_log.clone(),
+            skipped: self
+                .skipped
+                .iter()
+                .copied()
+                .chain(skipped.iter().map(|m| m.version))
+                .collect::<Vec<_>>()
+                .into(),
+        }
+    }
+
     /// Specify whether applied migrations that are missing from the resolved migrations should be ignored.
     pub fn set_ignore_missing(&mut self, ignore_missing: bool) -> &Self {
         self.ignore_missing = ignore_missing;
@@ -81,6 +150,30 @@ impl Migrator {
         self
     }
 
+    /// Record who applied or reverted each migration, and from where, in the `_sqlx_migrations_log`
+    /// table, which is created if needed. Disabled by default.
+    ///
+    /// The row is inserted after the migration's transaction commits, so it may be lost if the
+    /// process dies in between.
+    ///
+    /// ```rust,no_run
+    /// # use sqlx_core::migrate::{AuditInfo, MigrateError, Migrator};
+    /// # fn main() -> Result<(), MigrateError> {
+    /// # sqlx::__rt::test_block_on(async move {
+    /// let mut m = Migrator::new(std::path::Path::new("./migrations")).await?;
+    /// m.set_audit_log(Some(AuditInfo {
+    ///     tool_version: Some(format!("my-app {}", env!("CARGO_PKG_VERSION"))),
+    ///     ..AuditInfo::from_env()
+    /// }));
+    /// # Ok(())
+    /// # })
+    /// # }
+    /// ```
+    pub fn set_audit_log(&mut self, audit_log: Option<AuditInfo>) -> &Self {
+        self.audit_log = audit_log;
+        self
+    }
+
     /// Get an iterator over all known migrations.
     pub fn iter(&self) -> slice::Iter<'_, Migration> {
         self.migrations.iter()
@@ -113,9 +206,31 @@ impl Migrator {
         self.run_direct(&mut *conn).await
     }
 
+    /// Run the pending migrations up to and including version `target`, like [`Migrator::run()`]
+    /// does for all of them.
+    ///
+    /// ```rust,ignore
+    /// MIGRATOR.up_to(&pool, 20240101000000).await?;
+    /// ```
+    pub async fn up_to<'a, A>(&self, migrator: A, target: i64) -> Result<(), MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate,
+    {
+        let mut conn = migrator.acquire().await?;
+        self.run_to(&mut *conn, target).await
+    }
+
     // Getting around the annoying "implementation of `Acquire` is not general enough" error
     #[doc(hidden)]
     pub async fn run_direct<C>(&self, conn: &mut C) -> Result<(), MigrateError>
+    where
+        C: Migrate,
+    {
+        self.run_to(conn, i64::MAX).await
+    }
+
+    async fn run_to<C>(&self, conn: &mut C, target: i64) -> Result<(), MigrateError>
     where
         C: Migrate,
     {
@@ -128,6 +243,10 @@ impl Migrator {
         // eventually this will likely migrate previous versions of the table
         conn.ensure_migrations_table().await?;
 
+        if self.audit_log.is_some() {
+            conn.ensure_migrations_log_table().await?;
+        }
+
         let version = conn.dirty_version().await?;
         if let Some(version) = version {
             return Err(MigrateError::Dirty(version));
@@ -141,7 +260,7 @@ impl Migrator {
             .map(|m| (m.version, m))
             .collect();
 
-        for migration in self.iter() {
+        for migration in self.iter().filter(|m| m.version <= target) {
             if migration.migration_type.is_down_migration() {
                 continue;
             }
@@ -153,7 +272,12 @@ impl Migrator {
                     }
                 }
                 None => {
-                    conn.apply(migration).await?;
+                    let elapsed = conn.apply(migration).await?;
+
+                    if let Some(audit_log) = &self.audit_log {
+                        conn.log_migration(&MigrationLogEntry::new(migration, elapsed, audit_log))
+                            .await?;
+                    }
                 }
             }
         }
@@ -185,6 +309,20 @@ impl Migrator {
     /// # }
     /// ```
     pub async fn undo<'a, A>(&self, migrator: A, target: i64) -> Result<(), MigrateError>
+    where
+        A: Acquire<'a>,
+        <A::Connection as Deref>::Target: Migrate,
+    {
+        self.down_to(migrator, target).await
+    }
+
+    /// Revert the applied migrations newer than version `target`; the counterpart of
+    /// [`Migrator::up_to()`], and the same as [`Migrator::undo()`].
+    ///
+    /// ```rust,ignore
+    /// MIGRATOR.down_to(&pool, 20240101000000).await?;
+    /// ```
+    pub async fn down_to<'a, A>(&self, migrator: A, target: i64) -> Result<(), MigrateError>
     where
         A: Acquire<'a>,
         <A::Connection as Deref>::Target: Migrate,
@@ -200,6 +338,10 @@ impl Migrator {
         // eventually this will likely migrate previous versions of the table
         conn.ensure_migrations_table().await?;
 
+        if self.audit_log.is_some() {
+            conn.ensure_migrations_log_table().await?;
+        }
+
         let version = conn.dirty_version().await?;
         if let Some(version) = version {
             return Err(MigrateError::Dirty(version));
@@ -220,7 +362,12 @@ impl Migrator {
             .filter(|m| applied_migrations.contains_key(&m.version))
             .filter(|m| m.version > target)
         {
-            conn.revert(migration).await?;
+            let elapsed = conn.revert(migration).await?;
+
+            if let Some(audit_log) = &self.audit_log {
+                conn.log_migration(&MigrationLogEntry::new(migration, elapsed, audit_log))
+                    .await?;
+            }
         }
 
         // unlock the migrator to allow other migrators to run
@@ -1,3 +1,5 @@
+mod archive;
+mod audit;
 mod error;
 #[allow(clippy::module_inception)]
 mod migrate;
@@ -6,8 +8,9 @@ mod migration_type;
 mod migrator;
 mod source;
 
+pub use audit::{AuditInfo, MigrationLogEntry, MigrationLogRow};
 pub use error::MigrateError;
-pub use migrate::{Migrate, MigrateDatabase};
+pub use migrate::{execute_timed, Migrate, MigrateDatabase};
 pub use migration::{AppliedMigration, Migration};
 pub use migration_type::MigrationType;
 pub use migrator::Migrator;
@@ -40,21 +40,10 @@ impl<'s> MigrationSource<'s> for &'s Path {
 
                 let file_name = entry.file_name.to_string_lossy();
 
-                let parts = file_name.splitn(2, '_').collect::<Vec<_>>();
-
-                if parts.len() != 2 || !parts[1].ends_with(".sql") {
-                    // not of the format: <VERSION>_<DESCRIPTION>.sql; ignore
+                let Some((version, description, migration_type)) = parse_file_name(&file_name)?
+                else {
                     continue;
-                }
-
-                let version: i64 = parts[0].parse()?;
-
-                let migration_type = MigrationType::from_filename(parts[1]);
-                // remove the `.sql` and replace `_` with ` `
-                let description = parts[1]
-                    .trim_end_matches(migration_type.suffix())
-                    .replace('_', " ")
-                    .to_owned();
+                };
 
                 let sql = fs::read_to_string(&entry.path).await?;
 
@@ -79,3 +68,26 @@ impl MigrationSource<'static> for PathBuf {
         Box::pin(async move { self.as_path().resolve().await })
     }
 }
+
+/// Parses the version, description and type of a migration from its file name, or returns `None`
+/// if it isn't of the format `<VERSION>_<DESCRIPTION>.sql`.
+pub(crate) fn parse_file_name(
+    file_name: &str,
+) -> Result<Option<(i64, String, MigrationType)>, BoxDynError> {
+    let parts = file_name.splitn(2, '_').collect::<Vec<_>>();
+
+    if parts.len() != 2 || !parts[1].ends_with(".sql") {
+        // not of the format: <VERSION>_<DESCRIPTION>.sql; ignore
+        return Ok(None);
+    }
+
+    let version: i64 = parts[0].parse()?;
+
+    let migration_type = MigrationType::from_filename(parts[1]);
+    // remove the `.sql` and replace `_` with ` `
+    let description = parts[1]
+        .trim_end_matches(migration_type.suffix())
+        .replace('_', " ");
+
+    Ok(Some((version, description, migration_type)))
+}
@@ -1,10 +1,15 @@
+use crate::buffer_pool::buffer_pool;
 use crate::net::Socket;
-use bytes::BytesMut;
-use std::{cmp, io};
+use bytes::{Bytes, BytesMut};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::{cmp, io, mem};
 
 use crate::error::Error;
 
 use crate::io::{Decode, Encode};
+use crate::logger::WireStats;
 
 // Tokio, async-std, and std all use this as the default capacity for their buffered I/O.
 const DEFAULT_BUF_SIZE: usize = 8192;
@@ -13,6 +18,11 @@ pub struct BufferedSocket<S> {
     socket: S,
     write_buf: WriteBuffer,
     read_buf: ReadBuffer,
+    wire_stats: WireStats,
+    /// Set when data is flushed, so the next read counts as a round-trip.
+    awaiting_response: bool,
+    /// Set between [`BufferedSocket::begin_atomic()`] and [`BufferedSocket::end_atomic()`].
+    poisoned: bool,
 }
 
 pub struct WriteBuffer {
@@ -40,13 +50,46 @@ impl<S: Socket> BufferedSocket<S> {
             },
             read_buf: ReadBuffer {
                 read: BytesMut::new(),
-                available: BytesMut::with_capacity(DEFAULT_BUF_SIZE),
+                available: take_pooled(DEFAULT_BUF_SIZE),
             },
+            wire_stats: WireStats::default(),
+            awaiting_response: false,
+            poisoned: false,
         }
     }
 
     pub async fn read_buffered(&mut self, len: usize) -> io::Result<BytesMut> {
-        self.read_buf.read(len, &mut self.socket).await
+        if self.awaiting_response {
+            self.awaiting_response = false;
+            self.wire_stats.round_trips += 1;
+        }
+
+        let buf = self.read_buf.read(len, &mut self.socket).await?;
+        self.wire_stats.bytes_received += buf.len() as u64;
+
+        Ok(buf)
+    }
+
+    /// Reads and discards `len` bytes, a buffer at a time, so they're never all in memory.
+    pub async fn skip(&mut self, mut len: usize) -> io::Result<()> {
+        while len > 0 {
+            let chunk = cmp::min(len, DEFAULT_BUF_SIZE);
+            self.read_buffered(chunk).await?;
+            len -= chunk;
+        }
+
+        Ok(())
+    }
+
+    /// The totals for this socket so far.
+    ///
+    /// Drivers record the statement phases with [`wire_stats_mut()`][Self::wire_stats_mut].
+    pub fn wire_stats(&self) -> WireStats {
+        self.wire_stats
+    }
+
+    pub fn wire_stats_mut(&mut self) -> &mut WireStats {
+        &mut self.wire_stats
     }
 
     pub fn write_buffer(&self) -> &WriteBuffer {
@@ -92,6 +135,9 @@ impl<S: Socket> BufferedSocket<S> {
             let written = self.socket.write(self.write_buf.get()).await?;
             self.write_buf.consume(written);
             self.write_buf.sanity_check();
+
+            self.wire_stats.bytes_sent += written as u64;
+            self.awaiting_response = true;
         }
 
         self.socket.flush().await?;
@@ -104,6 +150,39 @@ impl<S: Socket> BufferedSocket<S> {
         self.socket.shutdown().await
     }
 
+    /// Reads whatever the peer has sent without waiting for more, returning `false` if it has
+    /// closed the connection or the socket returned an error.
+    ///
+    /// This is a cheap liveness check for an idle connection, as it doesn't need a round trip.
+    /// Data that was read (e.g. an error the server sent before closing) stays buffered.
+    pub fn is_open(&mut self) -> bool {
+        match self.read_buf.read_available(&mut self.socket) {
+            Ok((read, open)) => {
+                self.wire_stats.bytes_received += read as u64;
+                open
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Marks the start of a step that leaves the connection out of sync with the server if the
+    /// future running it is dropped partway, such as reading the body of a message after its
+    /// header. The socket is poisoned until [`end_atomic()`][Self::end_atomic] marks its end, so
+    /// it stays poisoned if the step is cancelled or fails.
+    pub fn begin_atomic(&mut self) {
+        self.poisoned = true;
+    }
+
+    /// Marks the end of the step started by [`begin_atomic()`][Self::begin_atomic].
+    pub fn end_atomic(&mut self) {
+        self.poisoned = false;
+    }
+
+    /// Returns `true` if a step started by [`begin_atomic()`][Self::begin_atomic] never ended.
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned
+    }
+
     pub fn shrink_buffers(&mut self) {
         // Won't drop data still in the buffer.
         self.write_buf.shrink();
@@ -119,6 +198,9 @@ impl<S: Socket> BufferedSocket<S> {
             socket: Box::new(self.socket),
             write_buf: self.write_buf,
             read_buf: self.read_buf,
+            wire_stats: self.wire_stats,
+            awaiting_response: self.awaiting_response,
+            poisoned: self.poisoned,
         }
     }
 }
@@ -246,8 +328,42 @@ impl ReadBuffer {
         Ok(self.drain(len))
     }
 
+    /// Reads until the socket would block, returning the number of bytes read and `false` at EOF.
+    fn read_available(&mut self, socket: &mut impl Socket) -> io::Result<(usize, bool)> {
+        let mut cx = Context::from_waker(futures_util::task::noop_waker_ref());
+        let mut total = 0;
+
+        loop {
+            self.reserve(DEFAULT_BUF_SIZE);
+
+            match Pin::new(&mut socket.read(&mut self.available)).poll(&mut cx) {
+                Poll::Pending => return Ok((total, true)),
+                Poll::Ready(Ok(0)) => return Ok((total, false)),
+                Poll::Ready(Ok(read)) => {
+                    self.advance(read);
+                    total += read;
+                }
+                Poll::Ready(Err(e)) => return Err(e),
+            }
+        }
+    }
+
     fn reserve(&mut self, amt: usize) {
         if let Some(additional) = amt.checked_sub(self.available.capacity()) {
+            if self.read.is_empty() {
+                // `read` still refers to the allocation, which would keep it from being reclaimed
+                self.read = BytesMut::new();
+
+                // rows decoded from the buffer may still refer to it, in which case the buffer
+                // is replaced by a pooled one, rather than a new allocation
+                if !self.available.try_reclaim(amt) {
+                    let available = mem::replace(&mut self.available, take_pooled(amt));
+                    recycle_pooled(available);
+                }
+
+                return;
+            }
+
             self.available.reserve(additional);
         }
     }
@@ -273,7 +389,29 @@ impl ReadBuffer {
             // but that's also kind of unavoidable.
             //
             // We should be warning the user not to call this often.
-            self.available = BytesMut::with_capacity(DEFAULT_BUF_SIZE);
+            let available = mem::replace(&mut self.available, take_pooled(DEFAULT_BUF_SIZE));
+            recycle_pooled(available);
         }
     }
 }
+
+impl Drop for ReadBuffer {
+    fn drop(&mut self) {
+        self.read = BytesMut::new();
+        recycle_pooled(mem::take(&mut self.available));
+    }
+}
+
+/// Take a buffer of at least `capacity` bytes from the buffer pool.
+fn take_pooled(capacity: usize) -> BytesMut {
+    // converting an empty `Vec` with spare capacity keeps its allocation
+    Bytes::from(buffer_pool().take(capacity)).into()
+}
+
+/// Return `buf` to the buffer pool if no rows refer to its allocation anymore.
+fn recycle_pooled(buf: BytesMut) {
+    // a shared buffer is copied by the conversion, which doesn't allocate if it's empty
+    if buf.is_empty() {
+        buffer_pool().recycle(buf.into());
+    }
+}
@@ -190,6 +190,12 @@ pub async fn connect_tcp<Ws: WithSocket>(
     // IPv6 addresses in URLs will be wrapped in brackets and the `url` crate doesn't trim those.
     let host = host.trim_matches(&['[', ']'][..]);
 
+    if let Some(runtime) = crate::rt::installed() {
+        let socket = runtime.connect_tcp(host, port).await?;
+
+        return Ok(with_socket.with_socket(socket));
+    }
+
     #[cfg(feature = "_rt-tokio")]
     if crate::rt::rt_tokio::available() {
         use tokio::net::TcpStream;
@@ -229,6 +235,12 @@ pub async fn connect_uds<P: AsRef<Path>, Ws: WithSocket>(
     path: P,
     with_socket: Ws,
 ) -> crate::Result<Ws::Output> {
+    if let Some(runtime) = crate::rt::installed() {
+        let socket = runtime.connect_uds(path.as_ref()).await?;
+
+        return Ok(with_socket.with_socket(socket));
+    }
+
     #[cfg(not(unix))]
     {
         return Err(io::Error::new(
@@ -0,0 +1,344 @@
+//! The [transactional outbox] pattern: messages to be published are written to an outbox table
+//! in the same transaction as the changes they describe, and delivered by a separate consumer.
+//!
+//! This ensures a message is published if and only if its transaction is committed, without a
+//! distributed transaction between the database and the message broker. Delivery is
+//! at-least-once: a message is only deleted from the outbox in the same transaction that the
+//! consumer's handler succeeded in, so it's delivered again if the handler fails or the consumer
+//! crashes, and handlers should be idempotent.
+//!
+//! ```rust,ignore
+//! use sqlx::outbox::Outbox;
+//!
+//! let outbox = Outbox::new("outbox");
+//!
+//! // in a migration, e.g. `migrations/<timestamp>_outbox.sql`:
+//! println!("{}", outbox.migration("PostgreSQL").unwrap());
+//!
+//! let mut tx = pool.begin().await?;
+//! sqlx::query("INSERT INTO orders (id, total) VALUES ($1, $2)")
+//!     .bind(order.id)
+//!     .bind(order.total)
+//!     .execute(&mut *tx)
+//!     .await?;
+//! outbox.enqueue(&mut tx, "order-created", &serde_json::to_string(&order)?).await?;
+//! tx.commit().await?;
+//!
+//! // elsewhere
+//! outbox
+//!     .run(&pool, |message| async move { broker.publish(&message.topic, &message.payload).await })
+//!     .await?;
+//! ```
+//!
+//! On Postgres, [`Outbox::notify()`] sends a notification when a message is enqueued, so a
+//! consumer can use [`Outbox::run_with_wakeups()`] with a `PgListener` to deliver it right away
+//! instead of at the next poll:
+//!
+//! ```rust,ignore
+//! let outbox = Outbox::new("outbox").notify("outbox");
+//!
+//! let mut listener = PgListener::connect_with(&pool).await?;
+//! listener.listen("outbox").await?;
+//!
+//! outbox.run_with_wakeups(&pool, listener.into_stream(), handler).await?;
+//! ```
+//!
+//! [transactional outbox]: https://microservices.io/patterns/data/transactional-outbox.html
+
+use std::future::Future;
+use std::time::Duration;
+
+use futures_core::stream::{FusedStream, Stream};
+use futures_util::{future, StreamExt};
+
+use crate::arguments::{push_bind, IntoArguments};
+use crate::database::{Database, HasArguments};
+use crate::encode::Encode;
+use crate::error::{BoxDynError, Error};
+use crate::executor::Executor;
+use crate::from_row::FromRow;
+use crate::pool::Pool;
+use crate::query::query_with;
+use crate::query_as::query_as;
+use crate::transaction::Transaction;
+use crate::types::Type;
+
+/// An outbox table, and the settings for consuming it.
+///
+/// The table name is pushed to queries as-is, so it must not come from user input.
+#[derive(Debug, Clone)]
+pub struct Outbox {
+    table: String,
+    channel: Option<String>,
+    batch_size: u32,
+    poll_interval: Duration,
+}
+
+/// A message read from the outbox.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxMessage {
+    /// Increases in the order the messages were enqueued.
+    pub id: i64,
+    pub topic: String,
+    pub payload: String,
+}
+
+/// An error while consuming the outbox.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum OutboxError {
+    #[error(transparent)]
+    Database(#[from] Error),
+
+    /// The handler failed to process a message. The batch it was in was rolled back, so the
+    /// message will be delivered again.
+    #[error("outbox handler failed for message {id}: {source}")]
+    Handler {
+        id: i64,
+        #[source]
+        source: BoxDynError,
+    },
+}
+
+impl Outbox {
+    /// An outbox stored in `table`, consumed in batches of 100 messages polled every second.
+    pub fn new(table: impl Into<String>) -> Self {
+        Outbox {
+            table: table.into(),
+            channel: None,
+            batch_size: 100,
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Notify `channel` with `pg_notify()` when a message is enqueued. Ignored on other databases.
+    pub fn notify(mut self, channel: impl Into<String>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+
+    /// The maximum number of messages to process in one transaction.
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// How long to wait before checking for new messages once the outbox is empty.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// The SQL to create the outbox table, for the database named `db_name` (i.e.
+    /// [`Database::NAME`]).
+    ///
+    /// Returns `None` for databases other than Postgres, MySQL and SQLite.
+    pub fn migration(&self, db_name: &str) -> Option<String> {
+        let table = &self.table;
+
+        let sql = match db_name {
+            "PostgreSQL" => format!(
+                "CREATE TABLE IF NOT EXISTS {table} (\n    \
+                    id BIGSERIAL PRIMARY KEY,\n    \
+                    topic TEXT NOT NULL,\n    \
+                    payload TEXT NOT NULL,\n    \
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT now()\n\
+                );\n"
+            ),
+            "MySQL" => format!(
+                "CREATE TABLE IF NOT EXISTS {table} (\n    \
+                    id BIGINT PRIMARY KEY AUTO_INCREMENT,\n    \
+                    topic VARCHAR(255) NOT NULL,\n    \
+                    payload LONGTEXT NOT NULL,\n    \
+                    created_at TIMESTAMP(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6)\n\
+                );\n"
+            ),
+            "SQLite" => format!(
+                "CREATE TABLE IF NOT EXISTS {table} (\n    \
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,\n    \
+                    topic TEXT NOT NULL,\n    \
+                    payload TEXT NOT NULL,\n    \
+                    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP\n\
+                );\n"
+            ),
+            _ => return None,
+        };
+
+        Some(sql)
+    }
+
+    /// Adds a message to the outbox as part of `tx`, so it's only delivered if `tx` is committed.
+    pub async fn enqueue<DB>(
+        &self,
+        tx: &mut Transaction<'_, DB>,
+        topic: &str,
+        payload: &str,
+    ) -> Result<(), Error>
+    where
+        DB: Database,
+        for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    {
+        let mut sql = format!("INSERT INTO {} (topic, payload) VALUES (", self.table);
+        let mut arguments = <DB as HasArguments>::Arguments::default();
+        push_bind(&mut sql, &mut arguments, topic);
+        sql.push_str(", ");
+        push_bind(&mut sql, &mut arguments, payload);
+        sql.push(')');
+
+        query_with(&sql, arguments).execute(&mut **tx).await?;
+
+        if let Some(channel) = self.channel.as_ref().filter(|_| DB::NAME == "PostgreSQL") {
+            let mut sql = String::from("SELECT pg_notify(");
+            let mut arguments = <DB as HasArguments>::Arguments::default();
+            push_bind(&mut sql, &mut arguments, channel.as_str());
+            sql.push_str(", ");
+            push_bind(&mut sql, &mut arguments, topic);
+            sql.push(')');
+
+            query_with(&sql, arguments).execute(&mut **tx).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Delivers one batch of messages to `handler` in order, deleting them if it succeeds for
+    /// all of them, and returns how many were delivered.
+    ///
+    /// If the handler fails, the messages delivered so far are kept, and will be delivered again.
+    /// On Postgres and MySQL, the messages are locked with `FOR UPDATE SKIP LOCKED`, so several
+    /// consumers can process the outbox concurrently, although then messages may be delivered
+    /// out of order.
+    pub async fn process_batch<DB, F, Fut, E>(
+        &self,
+        pool: &Pool<DB>,
+        mut handler: F,
+    ) -> Result<usize, OutboxError>
+    where
+        DB: Database,
+        F: FnMut(OutboxMessage) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: Into<BoxDynError>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'r> (i64, String, String): FromRow<'r, DB::Row>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    {
+        let mut tx = pool.begin().await?;
+
+        let mut select = format!(
+            "SELECT id, topic, payload FROM {} ORDER BY id LIMIT {}",
+            self.table, self.batch_size
+        );
+
+        if matches!(DB::NAME, "PostgreSQL" | "MySQL") {
+            select.push_str(" FOR UPDATE SKIP LOCKED");
+        }
+
+        let messages: Vec<(i64, String, String)> = query_as(&select).fetch_all(&mut *tx).await?;
+
+        let count = messages.len();
+
+        for (id, topic, payload) in messages {
+            handler(OutboxMessage { id, topic, payload })
+                .await
+                .map_err(|e| OutboxError::Handler {
+                    id,
+                    source: e.into(),
+                })?;
+
+            let mut delete = format!("DELETE FROM {} WHERE id = ", self.table);
+            let mut arguments = <DB as HasArguments>::Arguments::default();
+            push_bind(&mut delete, &mut arguments, id);
+
+            query_with(&delete, arguments).execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(count)
+    }
+
+    /// Delivers messages to `handler` until an error occurs, waiting for the poll interval
+    /// whenever the outbox is empty.
+    pub async fn run<DB, F, Fut, E>(&self, pool: &Pool<DB>, handler: F) -> Result<(), OutboxError>
+    where
+        DB: Database,
+        F: FnMut(OutboxMessage) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: Into<BoxDynError>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'r> (i64, String, String): FromRow<'r, DB::Row>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    {
+        self.run_with_wakeups(
+            pool,
+            futures_util::stream::pending::<Result<(), Error>>(),
+            handler,
+        )
+        .await
+    }
+
+    /// Like [`Self::run()`], but also checks for new messages as soon as `wakeups` yields an item,
+    /// e.g. a notification from a `PgListener` listening on the channel set with
+    /// [`Self::notify()`].
+    ///
+    /// The poll interval still applies, so messages whose notification was missed are still
+    /// delivered. If `wakeups` ends, only the poll interval is used from then on.
+    pub async fn run_with_wakeups<DB, S, T, F, Fut, E>(
+        &self,
+        pool: &Pool<DB>,
+        wakeups: S,
+        mut handler: F,
+    ) -> Result<(), OutboxError>
+    where
+        DB: Database,
+        S: Stream<Item = Result<T, Error>>,
+        F: FnMut(OutboxMessage) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: Into<BoxDynError>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'r> (i64, String, String): FromRow<'r, DB::Row>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    {
+        let wakeups = wakeups.fuse();
+        futures_util::pin_mut!(wakeups);
+
+        loop {
+            // a full batch means there may be more messages waiting
+            while self.process_batch(pool, &mut handler).await? >= self.batch_size as usize {}
+
+            let sleep = crate::rt::sleep(self.poll_interval);
+            futures_util::pin_mut!(sleep);
+
+            // once the wakeups end, fall back to polling on the interval alone
+            if wakeups.is_terminated() {
+                sleep.await;
+                continue;
+            }
+
+            match future::select(sleep, wakeups.next()).await {
+                future::Either::Right((Some(wakeup), _)) => {
+                    wakeup?;
+                }
+                future::Either::Right((None, sleep)) => sleep.await,
+                future::Either::Left(_) => {}
+            }
+        }
+    }
+}
+
+#[test]
+fn test_migration() {
+    let outbox = Outbox::new("my_outbox");
+
+    assert!(outbox
+        .migration("PostgreSQL")
+        .unwrap()
+        .starts_with("CREATE TABLE IF NOT EXISTS my_outbox (\n    id BIGSERIAL PRIMARY KEY,\n"));
+    assert!(outbox.migration("SQLite").is_some());
+    assert!(outbox.migration("MSSQL").is_none());
+}
@@ -0,0 +1,381 @@
+//! Helpers for paginating query results with [`QueryBuilder`].
+//!
+//! [`Keyset`] pagination continues from the sort key of the last row of the previous page, so
+//! each page is as fast to fetch as the first and rows aren't skipped or repeated when others are
+//! inserted or deleted concurrently. [`Offset`] pagination is simpler but has neither property.
+//!
+//! Both hand out an opaque cursor with each page to be passed back when fetching the next one.
+
+use std::fmt::Write;
+
+use crate::database::Database;
+use crate::encode::Encode;
+use crate::error::Error;
+use crate::query_builder::{QueryBuilder, Separated};
+use crate::types::Type;
+
+/// The direction to sort and paginate in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Order {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// Keyset ("seek") pagination over a set of columns.
+///
+/// The columns must uniquely identify a row, which generally means ending them with the primary
+/// key; otherwise rows with equal sort keys that straddle two pages will be skipped. Every
+/// column is sorted in the same direction so the condition can be a single row-value comparison,
+/// e.g. `(created_at, id) > ($1, $2)`, which databases can satisfy with an index on those columns.
+///
+/// The column names are pushed to the query as-is, so they must not come from user input.
+///
+/// ```rust,ignore
+/// use sqlx::pagination::{Keyset, KeysetPage, Order};
+///
+/// let keyset = Keyset::new(&["created_at", "id"], 50).order(Order::Descending);
+///
+/// // the cursor from the previous page, if any
+/// let after: Option<(OffsetDateTime, i64)> = cursor.map(Keyset::decode_cursor).transpose()?;
+///
+/// let mut query = QueryBuilder::new("SELECT * FROM posts WHERE author_id = ");
+/// query.push_bind(author_id);
+/// keyset.push_and(&mut query, after);
+/// keyset.push_order_by(&mut query);
+///
+/// let posts: Vec<Post> = query.build_query_as().fetch_all(&mut conn).await?;
+///
+/// let page: KeysetPage<Post> = keyset.page(posts, |post| (post.created_at, post.id))?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Keyset<'a> {
+    columns: &'a [&'a str],
+    limit: u64,
+    order: Order,
+}
+
+impl<'a> Keyset<'a> {
+    /// Paginate over `columns`, returning at most `limit` rows per page.
+    ///
+    /// ### Panics
+    /// If `columns` is empty or `limit` is zero.
+    pub fn new(columns: &'a [&'a str], limit: u64) -> Self {
+        assert!(!columns.is_empty(), "keyset must have at least one column");
+        assert_ne!(limit, 0, "page limit must be greater than zero");
+
+        Keyset {
+            columns,
+            limit,
+            order: Order::Ascending,
+        }
+    }
+
+    /// Set the direction to sort and paginate in. Defaults to [`Order::Ascending`].
+    pub fn order(mut self, order: Order) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Push a condition selecting the rows after `after`, e.g. `(a, b) > ($1, $2)`.
+    ///
+    /// ### Panics
+    /// If `after` does not have one value for each column.
+    pub fn push_condition<'args, DB, K>(&self, query: &mut QueryBuilder<'args, DB>, after: K)
+    where
+        DB: Database,
+        K: KeysetKey<'args, DB>,
+    {
+        assert_eq!(
+            K::LEN,
+            self.columns.len(),
+            "keyset has {} columns but the key has {} values",
+            self.columns.len(),
+            K::LEN
+        );
+
+        query.push("(");
+        query.push(self.columns.join(", "));
+        query.push(match self.order {
+            Order::Ascending => ") > (",
+            Order::Descending => ") < (",
+        });
+
+        after.push_binds(&mut query.separated(", "));
+
+        query.push(")");
+    }
+
+    /// Push ` WHERE` and the condition from [`push_condition()`][Self::push_condition]
+    /// if `after` is `Some`.
+    pub fn push_where<'args, DB, K>(&self, query: &mut QueryBuilder<'args, DB>, after: Option<K>)
+    where
+        DB: Database,
+        K: KeysetKey<'args, DB>,
+    {
+        if let Some(after) = after {
+            query.push(" WHERE ");
+            self.push_condition(query, after);
+        }
+    }
+
+    /// Push ` AND` and the condition from [`push_condition()`][Self::push_condition]
+    /// if `after` is `Some`, for queries that already have a `WHERE` clause.
+    pub fn push_and<'args, DB, K>(&self, query: &mut QueryBuilder<'args, DB>, after: Option<K>)
+    where
+        DB: Database,
+        K: KeysetKey<'args, DB>,
+    {
+        if let Some(after) = after {
+            query.push(" AND ");
+            self.push_condition(query, after);
+        }
+    }
+
+    /// Push the `ORDER BY` and `LIMIT` clauses.
+    ///
+    /// One more row than the limit is fetched to tell if there is a next page,
+    /// which [`page()`][Self::page] removes.
+    pub fn push_order_by<DB: Database>(&self, query: &mut QueryBuilder<'_, DB>) {
+        let direction = match self.order {
+            Order::Ascending => "",
+            Order::Descending => " DESC",
+        };
+
+        let mut sql = String::from(" ORDER BY ");
+
+        for (i, column) in self.columns.iter().enumerate() {
+            if i > 0 {
+                sql.push_str(", ");
+            }
+
+            let _ = write!(sql, "{column}{direction}");
+        }
+
+        let _ = write!(sql, " LIMIT {}", self.limit + 1);
+
+        query.push(sql);
+    }
+
+    /// Build a page from the rows of a query built with
+    /// [`push_order_by()`][Self::push_order_by], with a cursor for the next page
+    /// made from the `key` of its last row.
+    #[cfg(feature = "json")]
+    pub fn page<T, K>(
+        &self,
+        mut rows: Vec<T>,
+        key: impl FnOnce(&T) -> K,
+    ) -> Result<KeysetPage<T>, Error>
+    where
+        K: serde::Serialize,
+    {
+        let next_cursor = if rows.len() as u64 > self.limit {
+            rows.truncate(self.limit as usize);
+
+            rows.last()
+                .map(|row| encode_cursor(&key(row)))
+                .transpose()?
+        } else {
+            None
+        };
+
+        Ok(KeysetPage {
+            items: rows,
+            next_cursor,
+        })
+    }
+
+    /// Decode a cursor from [`KeysetPage::next_cursor`] back into the key of the last row.
+    ///
+    /// Returns [`Error::Decode`] if the cursor is invalid, which should be treated as a bad request.
+    #[cfg(feature = "json")]
+    pub fn decode_cursor<K>(cursor: impl AsRef<str>) -> Result<K, Error>
+    where
+        K: serde::de::DeserializeOwned,
+    {
+        let bytes = hex::decode(cursor.as_ref()).map_err(invalid_cursor)?;
+
+        serde_json::from_slice(&bytes).map_err(invalid_cursor)
+    }
+}
+
+/// A page of rows from [`Keyset::page()`].
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeysetPage<T> {
+    pub items: Vec<T>,
+
+    /// The cursor to fetch the next page with, or `None` if this is the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// Offset pagination, using `LIMIT` and `OFFSET`.
+///
+/// The database still has to read every row before the offset, so deep pages get
+/// progressively slower; prefer [`Keyset`] for large tables.
+///
+/// ```rust,ignore
+/// use sqlx::pagination::Offset;
+///
+/// let offset = Offset::new(50);
+///
+/// let mut query = QueryBuilder::new("SELECT * FROM posts ORDER BY id");
+/// offset.push_limit(&mut query, cursor.as_deref())?;
+///
+/// let posts: Vec<Post> = query.build_query_as().fetch_all(&mut conn).await?;
+///
+/// let page = offset.page(posts, cursor.as_deref())?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct Offset {
+    limit: u64,
+}
+
+impl Offset {
+    /// Return at most `limit` rows per page.
+    ///
+    /// ### Panics
+    /// If `limit` is zero, or not less than `i64::MAX`.
+    pub fn new(limit: u64) -> Self {
+        assert_ne!(limit, 0, "page limit must be greater than zero");
+        assert!(limit < MAX_OFFSET, "page limit must be less than i64::MAX");
+
+        Offset { limit }
+    }
+
+    /// Push the `LIMIT` and `OFFSET` clauses for the page at `cursor`, or the first page if `None`.
+    ///
+    /// One more row than the limit is fetched to tell if there is a next page,
+    /// which [`page()`][Self::page] removes.
+    pub fn push_limit<DB: Database>(
+        &self,
+        query: &mut QueryBuilder<'_, DB>,
+        cursor: Option<&str>,
+    ) -> Result<(), Error> {
+        let offset = cursor.map(decode_offset).transpose()?.unwrap_or(0);
+
+        query.push(format_args!(" LIMIT {} OFFSET {}", self.limit + 1, offset));
+
+        Ok(())
+    }
+
+    /// Build a page from the rows of a query built with [`push_limit()`][Self::push_limit]
+    /// for the same `cursor`.
+    pub fn page<T>(&self, mut rows: Vec<T>, cursor: Option<&str>) -> Result<OffsetPage<T>, Error> {
+        let offset = cursor.map(decode_offset).transpose()?.unwrap_or(0);
+
+        let next_cursor = if rows.len() as u64 > self.limit {
+            rows.truncate(self.limit as usize);
+
+            let next = offset
+                .checked_add(self.limit)
+                .filter(|&next| next <= MAX_OFFSET)
+                .ok_or_else(|| invalid_cursor("the next offset is out of range"))?;
+
+            Some(format!("{next:x}"))
+        } else {
+            None
+        };
+
+        Ok(OffsetPage {
+            items: rows,
+            next_cursor,
+        })
+    }
+}
+
+/// A page of rows from [`Offset::page()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetPage<T> {
+    pub items: Vec<T>,
+
+    /// The cursor to fetch the next page with, or `None` if this is the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// The values of a [`Keyset`] for one row, as a tuple with one value per column.
+pub trait KeysetKey<'args, DB: Database> {
+    /// The number of values in the key.
+    const LEN: usize;
+
+    /// Push a bind parameter for each value of the key.
+    fn push_binds(self, separated: &mut Separated<'_, 'args, DB, &'static str>);
+}
+
+macro_rules! impl_keyset_key_for_tuple {
+    ($len:literal; $($idx:tt : $T:ident),+) => {
+        impl<'args, DB, $($T,)+> KeysetKey<'args, DB> for ($($T,)+)
+        where
+            DB: Database,
+            $($T: 'args + Encode<'args, DB> + Send + Type<DB>,)+
+        {
+            const LEN: usize = $len;
+
+            fn push_binds(self, separated: &mut Separated<'_, 'args, DB, &'static str>) {
+                $(separated.push_bind(self.$idx);)+
+            }
+        }
+    };
+}
+
+impl_keyset_key_for_tuple!(1; 0: T1);
+impl_keyset_key_for_tuple!(2; 0: T1, 1: T2);
+impl_keyset_key_for_tuple!(3; 0: T1, 1: T2, 2: T3);
+impl_keyset_key_for_tuple!(4; 0: T1, 1: T2, 2: T3, 3: T4);
+impl_keyset_key_for_tuple!(5; 0: T1, 1: T2, 2: T3, 3: T4, 4: T5);
+impl_keyset_key_for_tuple!(6; 0: T1, 1: T2, 2: T3, 3: T4, 4: T5, 5: T6);
+
+#[cfg(feature = "json")]
+fn encode_cursor<K: serde::Serialize>(key: &K) -> Result<String, Error> {
+    let json = serde_json::to_vec(key)
+        .map_err(|e| Error::Encode(format!("failed to encode pagination cursor: {e}").into()))?;
+
+    Ok(hex::encode(json))
+}
+
+/// The largest offset that databases accept, as `OFFSET` is a signed 64-bit integer.
+const MAX_OFFSET: u64 = i64::MAX as u64;
+
+fn decode_offset(cursor: &str) -> Result<u64, Error> {
+    let offset = u64::from_str_radix(cursor, 16).map_err(invalid_cursor)?;
+
+    if offset > MAX_OFFSET {
+        return Err(invalid_cursor("the offset is out of range"));
+    }
+
+    Ok(offset)
+}
+
+fn invalid_cursor(e: impl std::fmt::Display) -> Error {
+    Error::Decode(format!("invalid pagination cursor: {e}").into())
+}
+
+#[test]
+fn test_offset_cursor() {
+    let offset = Offset::new(2);
+
+    let page = offset.page(vec![1, 2, 3], None).unwrap();
+    assert_eq!(page.items, [1, 2]);
+    assert_eq!(page.next_cursor.as_deref(), Some("2"));
+
+    let page = offset.page(vec![3, 4], Some("2")).unwrap();
+    assert_eq!(page.next_cursor, None);
+
+    // cursors come from clients, so out-of-range offsets are an error rather than a panic
+    assert!(matches!(
+        offset.page(vec![1, 2, 3], Some("7ffffffffffffffe")),
+        Err(Error::Decode(_))
+    ));
+    assert!(matches!(
+        offset.page(Vec::<i32>::new(), Some("8000000000000000")),
+        Err(Error::Decode(_))
+    ));
+    assert!(matches!(
+        offset.page(vec![1, 2, 3], Some("ffffffffffffffff")),
+        Err(Error::Decode(_))
+    ));
+    assert!(matches!(
+        offset.page(Vec::<i32>::new(), Some("not hex")),
+        Err(Error::Decode(_))
+    ));
+}
@@ -18,12 +18,20 @@ use std::future::Future;
 /// Will be returned to the pool on-drop.
 pub struct PoolConnection<DB: Database> {
     live: Option<Live<DB>>,
+    /// Set if the pool has leak detection enabled.
+    /// `None` once the connection has been returned, closed or detached.
+    checkout_id: Option<u64>,
     pub(crate) pool: Arc<PoolInner<DB>>,
 }
 
 pub(super) struct Live<DB: Database> {
     pub(super) raw: DB::Connection,
     pub(super) created_at: Instant,
+    /// `max_lifetime` minus this connection's jitter.
+    pub(super) lifetime: Option<Duration>,
+    /// The name set by the last [checkout label][crate::pool::PoolOptions::checkout_label], or
+    /// `None` if the connection has the name it was opened with.
+    pub(super) application_name: Option<String>,
 }
 
 pub(super) struct Idle<DB: Database> {
@@ -111,14 +119,23 @@ impl<DB: Database> PoolConnection<DB> {
     }
 
     fn take_live(&mut self) -> Live<DB> {
+        self.end_checkout();
         self.live.take().expect(EXPECT_MSG)
     }
 
+    fn end_checkout(&mut self) {
+        if let Some(id) = self.checkout_id.take() {
+            self.pool.checkouts.end(id);
+        }
+    }
+
     /// Test the connection to make sure it is still live before returning it to the pool.
     ///
     /// This effectively runs the drop handler eagerly instead of spawning a task to do it.
     #[doc(hidden)]
     pub fn return_to_pool(&mut self) -> impl Future<Output = ()> + Send + 'static {
+        self.end_checkout();
+
         // float the connection in the pool before we move into the task
         // in case the returned `Future` isn't executed, like if it's spawned into a dying runtime
         // https://github.com/launchbadge/sqlx/issues/1396
@@ -208,6 +225,8 @@ impl<DB: Database> Floating<DB, Live<DB>> {
             inner: Live {
                 raw: conn,
                 created_at: Instant::now(),
+                lifetime: guard.pool.options.connection_lifetime(),
+                application_name: None,
             },
             guard,
         }
@@ -219,8 +238,16 @@ impl<DB: Database> Floating<DB, Live<DB>> {
         let pool = Arc::clone(&guard.pool);
 
         guard.cancel();
+
+        let checkout_id = pool
+            .options
+            .leak_detection_threshold
+            .is_some()
+            .then(|| pool.checkouts.start(inner.raw.running_query()));
+
         PoolConnection {
             live: Some(inner),
+            checkout_id,
             pool,
         }
     }
@@ -239,6 +266,15 @@ impl<DB: Database> Floating<DB, Live<DB>> {
             return false;
         }
 
+        // a future using the connection was dropped partway through an operation, so the
+        // connection is out of sync with the server and can't even be pinged reliably
+        if self.raw.is_poisoned() {
+            tracing::warn!("closing a connection poisoned by a cancelled or failed operation");
+
+            self.close_hard().await;
+            return false;
+        }
+
         if let Some(test) = &self.guard.pool.options.after_release {
             let meta = self.metadata();
             match (test)(&mut self.inner.raw, meta).await {
@@ -1,17 +1,18 @@
 use super::connection::{Floating, Idle, Live};
+use super::leak::{Checkouts, ConnectionLeak, PoolTimeoutReport};
 use crate::connection::ConnectOptions;
 use crate::connection::Connection;
 use crate::database::Database;
 use crate::error::Error;
-use crate::pool::{deadline_as_timeout, CloseEvent, Pool, PoolOptions};
+use crate::pool::{deadline_as_timeout, AcquireTest, CloseEvent, Pool, PoolOptions};
 use crossbeam_queue::ArrayQueue;
 
 use crate::sync::{AsyncSemaphore, AsyncSemaphoreReleaser};
 
 use std::cmp;
 use std::future::Future;
-use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
 use std::task::Poll;
 
 use crate::pool::options::PoolConnectionMetadata;
@@ -25,8 +26,20 @@ pub(crate) struct PoolInner<DB: Database> {
     pub(super) semaphore: AsyncSemaphore,
     pub(super) size: AtomicU32,
     pub(super) num_idle: AtomicUsize,
+    /// The number of connections to keep open; only differs from `options.min_connections`
+    /// if `options.autoscale` is set.
+    min_size: AtomicU32,
+    /// The longest `acquire()` call since the last autoscaling check, in microseconds.
+    peak_acquire_micros: AtomicU64,
     is_closed: AtomicBool,
     pub(super) on_closed: event_listener::Event,
+    /// Notified when the size drops below `min_size`, to wake the maintenance task.
+    on_shrunk: event_listener::Event,
+    pub(super) checkouts: Checkouts,
+    /// The state of the pool when `acquire()` last timed out.
+    last_timeout_report: Mutex<Option<PoolTimeoutReport>>,
+    /// The number of tasks in `acquire()`.
+    num_waiting: AtomicUsize,
     pub(super) options: PoolOptions<DB>,
 }
 
@@ -52,14 +65,21 @@ impl<DB: Database> PoolInner<DB> {
             semaphore: AsyncSemaphore::new(options.fair, semaphore_capacity),
             size: AtomicU32::new(0),
             num_idle: AtomicUsize::new(0),
+            min_size: AtomicU32::new(options.min_connections),
+            peak_acquire_micros: AtomicU64::new(0),
             is_closed: AtomicBool::new(false),
             on_closed: event_listener::Event::new(),
+            on_shrunk: event_listener::Event::new(),
+            checkouts: Checkouts::default(),
+            last_timeout_report: Mutex::new(None),
+            num_waiting: AtomicUsize::new(0),
             options,
         };
 
         let pool = Arc::new(pool);
 
         spawn_maintenance_tasks(&pool);
+        spawn_leak_detector(&pool);
 
         pool
     }
@@ -77,6 +97,11 @@ impl<DB: Database> PoolInner<DB> {
         self.num_idle.load(Ordering::Acquire)
     }
 
+    /// The number of connections the pool tries to keep open at all times.
+    pub(super) fn min_size(&self) -> u32 {
+        self.min_size.load(Ordering::Acquire)
+    }
+
     pub(super) fn is_closed(&self) -> bool {
         self.is_closed.load(Ordering::Acquire)
     }
@@ -89,6 +114,13 @@ impl<DB: Database> PoolInner<DB> {
     pub(super) fn close<'a>(self: &'a Arc<Self>) -> impl Future<Output = ()> + 'a {
         self.mark_closed();
 
+        // `close()` will wait for these, possibly forever
+        if let Some(threshold) = self.options.leak_detection_threshold {
+            for leak in self.checkouts.find_leaks_on_close(threshold) {
+                self.report_leak(&leak);
+            }
+        }
+
         async move {
             for permits in 1..=self.options.max_connections {
                 // Close any currently idle connections in the pool.
@@ -106,6 +138,25 @@ impl<DB: Database> PoolInner<DB> {
         }
     }
 
+    pub(super) fn report_leak(&self, leak: &ConnectionLeak) {
+        if let Some(callback) = &self.options.on_connection_leak {
+            callback(leak);
+            return;
+        }
+
+        let message = if leak.pool_closed {
+            "connection still checked out when closing the pool"
+        } else {
+            "connection checked out for longer than the leak detection threshold"
+        };
+
+        tracing::warn!(
+            held_for = ?leak.held_for,
+            "{message}; acquired at:\n{}",
+            leak.backtrace
+        );
+    }
+
     pub(crate) fn close_event(&self) -> CloseEvent {
         CloseEvent {
             listener: (!self.is_closed()).then(|| self.on_closed.listen()),
@@ -241,12 +292,15 @@ impl<DB: Database> PoolInner<DB> {
             return Err(Error::PoolClosed);
         }
 
-        let deadline = Instant::now() + self.options.acquire_timeout;
+        let _waiting = WaitingGuard::new(&self.num_waiting);
 
-        crate::rt::timeout(
+        let started_at = Instant::now();
+        let deadline = started_at + self.options.acquire_timeout;
+
+        let res = crate::rt::timeout(
             self.options.acquire_timeout,
             async {
-                loop {
+                let live = loop {
                     // Handles the close-event internally
                     let permit = self.acquire_permit().await?;
 
@@ -258,7 +312,7 @@ impl<DB: Database> PoolInner<DB> {
                         Ok(conn) => match check_idle_conn(conn, &self.options).await {
 
                             // All good!
-                            Ok(live) => return Ok(live),
+                            Ok(live) => break live,
 
                             // if the connection isn't usable for one reason or another,
                             // we get the `DecrementSizeGuard` back to open a new one
@@ -281,12 +335,101 @@ impl<DB: Database> PoolInner<DB> {
                     };
 
                     // Attempt to connect...
-                    return self.connect(deadline, guard).await;
-                }
+                    break self.connect(deadline, guard).await?;
+                };
+
+                self.apply_checkout_label(live).await
             }
         )
-            .await
-            .map_err(|_| Error::PoolTimedOut)?
+            .await;
+
+        self.record_acquire_latency(started_at.elapsed());
+
+        match res {
+            Ok(Err(Error::PoolTimedOut)) | Err(_) => {
+                let report = PoolTimeoutReport {
+                    size: self.size(),
+                    num_idle: self.num_idle(),
+                    num_waiting: self.num_waiting.load(Ordering::Acquire),
+                    oldest_checkout: self.checkouts.oldest(),
+                    running_queries: self.checkouts.running_queries(),
+                };
+
+                tracing::debug!(%report, "pool timed out while waiting for an open connection");
+
+                *self.lock_last_timeout_report() = Some(report);
+
+                Err(Error::PoolTimedOut)
+            }
+            Ok(res) => res,
+        }
+    }
+
+    pub(super) fn last_timeout_report(&self) -> Option<PoolTimeoutReport> {
+        self.lock_last_timeout_report().clone()
+    }
+
+    fn lock_last_timeout_report(&self) -> MutexGuard<'_, Option<PoolTimeoutReport>> {
+        // only ever replaced whole, so it can't be left inconsistent by a panic
+        self.last_timeout_report
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Scale up the pool if `acquire()` took longer than the autoscaling target.
+    fn record_acquire_latency(self: &Arc<Self>, latency: Duration) {
+        let Some(target) = self.options.autoscale else {
+            return;
+        };
+
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        self.peak_acquire_micros.fetch_max(micros, Ordering::AcqRel);
+
+        if latency <= target {
+            return;
+        }
+
+        // Keep one more connection open than we currently have.
+        let min_size = cmp::min(self.options.max_connections, self.size().saturating_add(1));
+
+        if self.min_size.fetch_max(min_size, Ordering::AcqRel) < min_size {
+            tracing::debug!(
+                ?latency,
+                min_size,
+                "acquire exceeded target latency; scaling up"
+            );
+
+            let pool = Arc::downgrade(self);
+
+            crate::rt::spawn(async move {
+                if let Some(pool) = pool.upgrade() {
+                    pool.min_connections_maintenance(None).await;
+                }
+            });
+        }
+    }
+
+    /// Lower the number of connections to keep open by one if `acquire()` was consistently fast
+    /// since the last call, returning `true` if so.
+    fn autoscale_down(&self) -> bool {
+        let Some(target) = self.options.autoscale else {
+            return false;
+        };
+
+        let peak = Duration::from_micros(self.peak_acquire_micros.swap(0, Ordering::AcqRel));
+
+        // Scale down at a lower threshold than we scale up so the pool doesn't flap.
+        if peak >= target / 2 {
+            return false;
+        }
+
+        let _ = self
+            .min_size
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |min_size| {
+                (min_size > self.options.min_connections).then(|| min_size - 1)
+            });
+
+        true
     }
 
     pub(super) async fn connect(
@@ -363,28 +506,94 @@ impl<DB: Database> PoolInner<DB> {
         }
     }
 
+    /// Update the application name of a connection being checked out,
+    /// if [`PoolOptions::checkout_label`] is set.
+    async fn apply_checkout_label(
+        &self,
+        mut conn: Floating<DB, Live<DB>>,
+    ) -> Result<Floating<DB, Live<DB>>, Error> {
+        let Some(checkout_label) = &self.options.checkout_label else {
+            return Ok(conn);
+        };
+
+        let application_name = self
+            .connect_options
+            .read()
+            .expect("write-lock holder panicked")
+            .get_application_name()
+            .map(str::to_owned);
+
+        // `None` restores the name the connection was opened with, rather than setting an empty
+        // one which would hide it
+        let name = match (
+            application_name,
+            checkout_label().filter(|label| !label.is_empty()),
+        ) {
+            (Some(application_name), Some(label)) => Some(format!("{application_name}:{label}")),
+            (None, Some(label)) => Some(label),
+            (_, None) => None,
+        };
+
+        // most checkouts by the same task have the same label, so only changes cost a round-trip
+        if conn.application_name == name {
+            return Ok(conn);
+        }
+
+        if let Err(error) = conn.raw.set_application_name(name.as_deref()).await {
+            // The connection may be in an inconsistent state, don't try to close nicely.
+            conn.close_hard().await;
+            return Err(error);
+        }
+
+        conn.application_name = name;
+
+        Ok(conn)
+    }
+
     /// Try to maintain `min_connections`, returning any errors (including `PoolTimedOut`).
     pub async fn try_min_connections(self: &Arc<Self>, deadline: Instant) -> Result<(), Error> {
-        while self.size() < self.options.min_connections {
+        self.warm(self.min_size(), deadline).await
+    }
+
+    /// Open connections concurrently until there are at least `n`, returning the first error
+    /// after adding the connections that could be opened.
+    pub(super) async fn warm(self: &Arc<Self>, n: u32, deadline: Instant) -> Result<(), Error> {
+        let mut guards = Vec::new();
+
+        while self.size() < n {
             // Don't wait for a semaphore permit.
             //
             // If no extra permits are available then we shouldn't be trying to spin up
             // connections anyway.
             let Some(permit) = self.semaphore.try_acquire(1) else {
-                return Ok(());
+                break;
             };
 
             // We must always obey `max_connections`.
-            let Some(guard) = self.try_increment_size(permit).ok() else {
-                return Ok(());
+            let Ok(guard) = self.try_increment_size(permit) else {
+                break;
             };
 
-            // We skip `after_release` since the connection was never provided to user code
-            // besides `after_connect`, if they set it.
-            self.release(self.connect(deadline, guard).await?);
+            guards.push(guard);
         }
 
-        Ok(())
+        let connects = guards
+            .into_iter()
+            .map(|guard| self.connect(deadline, guard));
+
+        let mut result = Ok(());
+
+        for res in future::join_all(connects).await {
+            match res {
+                // We skip `after_release` since the connection was never provided to user code
+                // besides `after_connect`, if they set it.
+                Ok(conn) => self.release(conn),
+                Err(error) if result.is_ok() => result = Err(error),
+                Err(_) => {}
+            }
+        }
+
+        result
     }
 
     /// Attempt to maintain `min_connections`, logging if unable.
@@ -416,10 +625,10 @@ impl<DB: Database> Drop for PoolInner<DB> {
     }
 }
 
-/// Returns `true` if the connection has exceeded `options.max_lifetime` if set, `false` otherwise.
-fn is_beyond_max_lifetime<DB: Database>(live: &Live<DB>, options: &PoolOptions<DB>) -> bool {
-    options
-        .max_lifetime
+/// Returns `true` if the connection has exceeded its lifetime, which is `options.max_lifetime`
+/// minus its jitter, if set, `false` otherwise.
+fn is_beyond_max_lifetime<DB: Database>(live: &Live<DB>) -> bool {
+    live.lifetime
         .map_or(false, |max| live.created_at.elapsed() > max)
 }
 
@@ -436,11 +645,21 @@ async fn check_idle_conn<DB: Database>(
 ) -> Result<Floating<DB, Live<DB>>, DecrementSizeGuard<DB>> {
     // If the connection we pulled has expired, close the connection and
     // immediately create a new connection
-    if is_beyond_max_lifetime(&conn, options) {
+    if is_beyond_max_lifetime(&conn) {
         return Err(conn.close().await);
     }
 
-    if options.test_before_acquire {
+    let test = match options.acquire_test_after_idle {
+        Some(after) if conn.idle_since.elapsed() <= after => AcquireTest::Skip,
+        _ => options.acquire_test,
+    };
+
+    if test == AcquireTest::Fast && !conn.live.raw.is_open() {
+        tracing::info!("idle connection was closed by the server");
+        return Err(conn.close_hard().await);
+    }
+
+    if test == AcquireTest::Ping {
         // Check that the connection is still live
         if let Err(error) = conn.ping().await {
             // an error here means the other end has hung up or we lost connectivity
@@ -479,23 +698,24 @@ fn spawn_maintenance_tasks<DB: Database>(pool: &Arc<PoolInner<DB>>) {
     // they don't keep `PoolInner` from being dropped.
     let pool_weak = Arc::downgrade(&pool);
 
-    let period = match (pool.options.max_lifetime, pool.options.idle_timeout) {
-        (Some(it), None) | (None, Some(it)) => it,
-
-        (Some(a), Some(b)) => cmp::min(a, b),
-
-        (None, None) => {
-            if pool.options.min_connections > 0 {
-                crate::rt::spawn(async move {
-                    if let Some(pool) = pool_weak.upgrade() {
-                        pool.min_connections_maintenance(None).await;
-                    }
-                });
-            }
-
-            return;
-        }
-    };
+    let period = [
+        pool.options.max_lifetime,
+        pool.options.idle_timeout,
+        pool.options.autoscale.map(|_| AUTOSCALE_PERIOD),
+        // check often enough to recycle connections as their jittered lifetimes expire
+        pool.options
+            .max_lifetime
+            .and(pool.options.max_lifetime_jitter)
+            .filter(|jitter| !jitter.is_zero())
+            .map(|jitter| cmp::max(jitter / RECYCLE_STEPS, MIN_RECYCLE_PERIOD)),
+    ]
+    .into_iter()
+    .flatten()
+    .min();
+
+    if period.is_none() && pool.options.min_connections == 0 {
+        return;
+    }
 
     // Immediately cancel this task if the pool is closed.
     let mut close_event = pool.close_event();
@@ -511,44 +731,118 @@ fn spawn_maintenance_tasks<DB: Database>(pool: &Arc<PoolInner<DB>>) {
                         return;
                     }
 
-                    // Don't run the reaper right away.
-                    if slept && !pool.idle_conns.is_empty() {
-                        do_reap(&pool).await;
+                    // Don't run the reaper right away, or when woken up to replace connections.
+                    if slept && period.is_some() {
+                        let scaled_down = pool.autoscale_down();
+
+                        if !pool.idle_conns.is_empty() {
+                            do_reap(&pool, scaled_down).await;
+                        }
                     }
 
-                    let next_run = Instant::now() + period;
+                    // Listen first so connections closed during maintenance aren't missed.
+                    let shrunk = pool.on_shrunk.listen();
+
+                    let mut next_run = period.map(|period| Instant::now() + period);
 
-                    pool.min_connections_maintenance(Some(next_run)).await;
+                    pool.min_connections_maintenance(next_run).await;
+
+                    // If connecting failed, try again soon rather than waiting for the next
+                    // connection to be closed.
+                    if pool.size() < pool.min_size() {
+                        let retry = Instant::now() + MIN_CONNECTIONS_RETRY;
+                        next_run =
+                            Some(next_run.map_or(retry, |next_run| cmp::min(next_run, retry)));
+                    }
 
                     // Don't hold a reference to the pool while sleeping.
                     drop(pool);
 
-                    if let Some(duration) = next_run.checked_duration_since(Instant::now()) {
-                        // `async-std` doesn't have a `sleep_until()`
-                        crate::rt::sleep(duration).await;
-                    } else {
-                        // `next_run` is in the past, just yield.
-                        crate::rt::yield_now().await;
-                    }
+                    slept = match next_run {
+                        Some(next_run) => {
+                            // `async-std` doesn't have a `sleep_until()`
+                            let duration = next_run.saturating_duration_since(Instant::now());
+                            let sleep = Box::pin(crate::rt::sleep(duration));
 
-                    slept = true;
+                            matches!(future::select(sleep, shrunk).await, future::Either::Left(_))
+                        }
+                        None => {
+                            shrunk.await;
+                            false
+                        }
+                    };
                 }
             })
             .await;
     });
 }
 
-async fn do_reap<DB: Database>(pool: &Arc<PoolInner<DB>>) {
-    // reap at most the current size minus the minimum idle
-    let max_reaped = pool.size().saturating_sub(pool.options.min_connections);
+fn spawn_leak_detector<DB: Database>(pool: &Arc<PoolInner<DB>>) {
+    let Some(threshold) = pool.options.leak_detection_threshold else {
+        return;
+    };
+
+    // Check often enough that leaks are reported soon after they cross the threshold.
+    let period = cmp::max(threshold / 4, Duration::from_millis(10));
+
+    let pool_weak = Arc::downgrade(pool);
+    let mut close_event = pool.close_event();
+
+    crate::rt::spawn(async move {
+        let _ = close_event
+            .do_until(async {
+                loop {
+                    crate::rt::sleep(period).await;
+
+                    let Some(pool) = pool_weak.upgrade() else {
+                        return;
+                    };
+
+                    for leak in pool.checkouts.find_leaks(threshold) {
+                        pool.report_leak(&leak);
+                    }
+                }
+            })
+            .await;
+    });
+}
+
+/// How often the pool checks whether it can scale down, if autoscaling is enabled.
+const AUTOSCALE_PERIOD: Duration = Duration::from_secs(5);
+
+/// How many times the reaper runs within `max_lifetime_jitter`, so connections are recycled a
+/// few at a time as they expire.
+const RECYCLE_STEPS: u32 = 10;
+
+/// The reaper doesn't run more often than this for `max_lifetime_jitter`.
+const MIN_RECYCLE_PERIOD: Duration = Duration::from_secs(1);
+
+/// How long to wait before trying to open connections again after failing to maintain
+/// `min_connections`.
+const MIN_CONNECTIONS_RETRY: Duration = Duration::from_secs(1);
+
+async fn do_reap<DB: Database>(pool: &Arc<PoolInner<DB>>, scaled_down: bool) {
+    // reap idle connections down to at most the minimum idle
+    let mut max_reaped = pool.size().saturating_sub(pool.min_size());
 
     // collect connections to reap
-    let (reap, keep) = (0..max_reaped)
+    let (reap, keep) = (0..pool.num_idle())
         // only connections waiting in the queue
         .filter_map(|_| pool.try_acquire())
         .partition::<Vec<_>, _>(|conn| {
-            is_beyond_idle_timeout(conn, &pool.options)
-                || is_beyond_max_lifetime(conn, &pool.options)
+            // expired connections are closed even below the minimum, as the maintenance task
+            // replaces them right after
+            if is_beyond_max_lifetime(conn) {
+                return true;
+            }
+
+            // if the pool is scaling down, any idle connection above the minimum can go
+            if max_reaped > 0 && (scaled_down || is_beyond_idle_timeout(conn, &pool.options)) {
+                max_reaped -= 1;
+                return true;
+            }
+
+            false
         });
 
     for conn in keep {
@@ -601,10 +895,30 @@ impl<DB: Database> DecrementSizeGuard<DB> {
 impl<DB: Database> Drop for DecrementSizeGuard<DB> {
     fn drop(&mut self) {
         if !self.cancelled {
-            self.pool.size.fetch_sub(1, Ordering::AcqRel);
+            let size = self.pool.size.fetch_sub(1, Ordering::AcqRel) - 1;
 
             // and here we release the permit we got on construction
             self.pool.semaphore.release(1);
+
+            if size < self.pool.min_size() && !self.pool.is_closed() {
+                self.pool.on_shrunk.notify(1);
+            }
         }
     }
 }
+
+/// Counts a task as waiting in `acquire()` until dropped, including if it's cancelled.
+struct WaitingGuard<'a>(&'a AtomicUsize);
+
+impl<'a> WaitingGuard<'a> {
+    fn new(num_waiting: &'a AtomicUsize) -> Self {
+        num_waiting.fetch_add(1, Ordering::AcqRel);
+        WaitingGuard(num_waiting)
+    }
+}
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
@@ -0,0 +1,190 @@
+//! The connections checked out of a pool, for connection leak detection, enabled by
+//! [`PoolOptions::leak_detection_threshold()`], and the report of the pool's state in
+//! [`Pool::last_timeout_report()`].
+//!
+//! [`PoolOptions::leak_detection_threshold()`]: crate::pool::PoolOptions::leak_detection_threshold
+//! [`Pool::last_timeout_report()`]: crate::pool::Pool::last_timeout_report
+
+use std::backtrace::Backtrace;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::connection::RunningQuery;
+use crate::HashMap;
+
+/// A connection that was checked out of the pool for longer than
+/// [`PoolOptions::leak_detection_threshold()`][crate::pool::PoolOptions::leak_detection_threshold].
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ConnectionLeak {
+    /// How long the connection has been checked out.
+    pub held_for: Duration,
+
+    /// Where the connection was acquired.
+    pub backtrace: Arc<Backtrace>,
+
+    /// `true` if this is being reported because the pool was closed
+    /// while the connection was still checked out.
+    pub pool_closed: bool,
+}
+
+/// The state of a pool when [`Pool::acquire()`] timed out with [`Error::PoolTimedOut`], returned
+/// by [`Pool::last_timeout_report()`].
+///
+/// [`Pool::acquire()`]: crate::pool::Pool::acquire
+/// [`Pool::last_timeout_report()`]: crate::pool::Pool::last_timeout_report
+/// [`Error::PoolTimedOut`]: crate::error::Error::PoolTimedOut
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PoolTimeoutReport {
+    /// The number of connections open, whether idle or checked out.
+    pub size: u32,
+
+    /// The number of idle connections.
+    pub num_idle: usize,
+
+    /// The number of tasks waiting in `acquire()`, including the one that timed out.
+    pub num_waiting: usize,
+
+    /// How long the longest-held connection has been checked out, if any are.
+    ///
+    /// Only tracked with
+    /// [`PoolOptions::leak_detection_threshold()`][crate::pool::PoolOptions::leak_detection_threshold]
+    /// set; `None` otherwise.
+    pub oldest_checkout: Option<Duration>,
+
+    /// The SQL running on checked-out connections.
+    ///
+    /// Only tracked with
+    /// [`PoolOptions::leak_detection_threshold()`][crate::pool::PoolOptions::leak_detection_threshold]
+    /// set, and only known for connections whose driver implements
+    /// [`Connection::running_query()`][crate::connection::Connection::running_query].
+    pub running_queries: Vec<String>,
+}
+
+impl fmt::Display for PoolTimeoutReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} connections, {} idle, {} tasks waiting",
+            self.size, self.num_idle, self.num_waiting
+        )?;
+
+        if let Some(oldest_checkout) = self.oldest_checkout {
+            write!(f, ", oldest checked out for {oldest_checkout:?}")?;
+        }
+
+        if !self.running_queries.is_empty() {
+            write!(f, "; running queries: {:?}", self.running_queries)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The connections currently checked out of a pool, only registered with leak detection enabled
+/// to keep the lock off the path of `acquire()` and release otherwise.
+#[derive(Default)]
+pub(super) struct Checkouts {
+    next_id: AtomicU64,
+    checkouts: Mutex<HashMap<u64, Checkout>>,
+}
+
+struct Checkout {
+    acquired_at: Instant,
+    backtrace: Arc<Backtrace>,
+    running_query: Option<RunningQuery>,
+    reported: bool,
+}
+
+impl Checkouts {
+    /// Record a connection being checked out, returning the ID to pass to [`end()`][Self::end].
+    pub fn start(&self, running_query: Option<RunningQuery>) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        let checkout = Checkout {
+            acquired_at: Instant::now(),
+            // the threshold is opt-in, so always capture regardless of `RUST_BACKTRACE`
+            backtrace: Arc::new(Backtrace::force_capture()),
+            running_query,
+            reported: false,
+        };
+
+        self.lock().insert(id, checkout);
+
+        id
+    }
+
+    /// Record a connection being returned to the pool, closed or detached.
+    pub fn end(&self, id: u64) {
+        self.lock().remove(&id);
+    }
+
+    /// Return the connections checked out for longer than `threshold`
+    /// that haven't already been reported.
+    pub fn find_leaks(&self, threshold: Duration) -> Vec<ConnectionLeak> {
+        let now = Instant::now();
+
+        self.lock()
+            .values_mut()
+            .filter(|checkout| !checkout.reported)
+            .filter_map(|checkout| {
+                let held_for = now.saturating_duration_since(checkout.acquired_at);
+
+                (held_for > threshold).then(|| {
+                    checkout.reported = true;
+
+                    ConnectionLeak {
+                        held_for,
+                        backtrace: checkout.backtrace.clone(),
+                        pool_closed: false,
+                    }
+                })
+            })
+            .collect()
+    }
+
+    /// Return every connection checked out for longer than `threshold`,
+    /// whether or not it was already reported, as the pool is being closed.
+    pub fn find_leaks_on_close(&self, threshold: Duration) -> Vec<ConnectionLeak> {
+        let now = Instant::now();
+
+        self.lock()
+            .values()
+            .filter_map(|checkout| {
+                let held_for = now.saturating_duration_since(checkout.acquired_at);
+
+                (held_for > threshold).then(|| ConnectionLeak {
+                    held_for,
+                    backtrace: checkout.backtrace.clone(),
+                    pool_closed: true,
+                })
+            })
+            .collect()
+    }
+
+    /// How long the longest-held connection has been checked out, if any are.
+    pub fn oldest(&self) -> Option<Duration> {
+        self.lock()
+            .values()
+            .map(|checkout| checkout.acquired_at.elapsed())
+            .max()
+    }
+
+    /// The SQL currently running on checked-out connections, where the driver reports it.
+    pub fn running_queries(&self) -> Vec<String> {
+        self.lock()
+            .values()
+            .filter_map(|checkout| checkout.running_query.as_ref()?.get())
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, Checkout>> {
+        // the map can't be left in an inconsistent state by a panic
+        self.checkouts
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
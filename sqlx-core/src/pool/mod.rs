@@ -68,7 +68,8 @@ use crate::any::{Any, AnyKind};
 use crate::connection::Connection;
 use crate::database::Database;
 use crate::error::Error;
-use crate::transaction::Transaction;
+use crate::middleware::{ExecutorMiddleware, WithMiddleware};
+use crate::transaction::{Transaction, TransactionOptions};
 use event_listener::EventListener;
 use futures_core::FusedFuture;
 use futures_util::FutureExt;
@@ -87,10 +88,20 @@ pub mod maybe;
 
 mod connection;
 mod inner;
+mod leak;
 mod options;
+mod replica;
+mod resilient;
+mod sharded;
 
 pub use self::connection::PoolConnection;
-pub use self::options::{PoolConnectionMetadata, PoolOptions};
+pub use self::leak::{ConnectionLeak, PoolTimeoutReport};
+pub use self::options::{AcquireTest, PoolConnectionMetadata, PoolOptions};
+pub use self::replica::{ReplicaPool, ReplicaSession, ReplicationPosition};
+pub use self::resilient::{
+    CircuitState, ClassMetrics, ResilientPool, ResilientPoolMetrics, ResilientPoolOptions,
+};
+pub use self::sharded::{ShardKey, ShardedPool};
 
 #[doc(hidden)]
 pub use self::maybe::MaybePoolConnection;
@@ -342,7 +353,8 @@ impl<DB: Database> Pool<DB> {
     ///
     /// The total time this method is allowed to execute is capped by
     /// [`PoolOptions::acquire_timeout`].
-    /// If that timeout elapses, this will return [`Error::PoolClosed`].
+    /// If that timeout elapses, this will return [`Error::PoolTimedOut`], and
+    /// [`Self::last_timeout_report()`] will return a report on the state of the pool.
     ///
     /// ### Note: Cancellation/Timeout May Drop Connections
     /// If `acquire` is cancelled or times out after it acquires a connection from the idle queue or
@@ -374,11 +386,57 @@ impl<DB: Database> Pool<DB> {
         self.0.try_acquire().map(|conn| conn.into_live().reattach())
     }
 
+    /// Opens connections concurrently until the pool has at least `n`, e.g. at startup so the
+    /// first requests don't each wait for a connection to be established.
+    ///
+    /// Fewer are opened if `n` is more than [`max_connections`][PoolOptions::max_connections],
+    /// or if other tasks are using all the connections the pool may open. Connecting is retried
+    /// with backoff like in [`acquire()`][Self::acquire], for up to
+    /// [`acquire_timeout`][PoolOptions::acquire_timeout]. The first error is returned after the
+    /// connections that could be opened are added to the pool.
+    ///
+    /// To keep a number of connections open, set
+    /// [`min_connections`][PoolOptions::min_connections] instead, which are also opened
+    /// concurrently, and replaced in the background when they're closed.
+    pub async fn warm(&self, n: u32) -> Result<(), Error> {
+        if self.0.is_closed() {
+            return Err(Error::PoolClosed);
+        }
+
+        let deadline = Instant::now() + self.0.options.acquire_timeout;
+
+        self.0.warm(n, deadline).await
+    }
+
     /// Retrieves a connection and immediately begins a new transaction.
     pub async fn begin(&self) -> Result<Transaction<'static, DB>, Error> {
         Ok(Transaction::begin(MaybePoolConnection::PoolConnection(self.acquire().await?)).await?)
     }
 
+    /// Retrieves a connection and immediately begins a new transaction with the given options.
+    ///
+    /// See [`TransactionOptions`] for details.
+    pub async fn begin_with(
+        &self,
+        options: TransactionOptions,
+    ) -> Result<Transaction<'static, DB>, Error> {
+        Ok(Transaction::begin_with(
+            MaybePoolConnection::PoolConnection(self.acquire().await?),
+            options,
+        )
+        .await?)
+    }
+
+    /// Runs every query executed through the returned executor through `middleware`.
+    ///
+    /// See [`ExecutorMiddleware`] for details.
+    pub fn with_middleware(
+        &self,
+        middleware: impl ExecutorMiddleware<DB>,
+    ) -> WithMiddleware<Self, DB> {
+        WithMiddleware::new(self.clone(), middleware)
+    }
+
     /// Attempts to retrieve a connection and immediately begins a new transaction if successful.
     pub async fn try_begin(&self) -> Result<Option<Transaction<'static, DB>>, Error> {
         match self.try_acquire() {
@@ -512,6 +570,14 @@ impl<DB: Database> Pool<DB> {
         self.0.num_idle()
     }
 
+    /// Returns the state of the pool when [`Self::acquire()`] last timed out, if it has, to help
+    /// diagnose what is holding its connections.
+    ///
+    /// The report is also logged at the `DEBUG` level when the timeout happens.
+    pub fn last_timeout_report(&self) -> Option<PoolTimeoutReport> {
+        self.0.last_timeout_report()
+    }
+
     /// Gets a clone of the connection options for this pool
     pub fn connect_options(&self) -> Arc<<DB::Connection as Connection>::Options> {
         self.0
@@ -2,8 +2,10 @@ use crate::connection::Connection;
 use crate::database::Database;
 use crate::error::Error;
 use crate::pool::inner::PoolInner;
-use crate::pool::Pool;
+use crate::pool::{ConnectionLeak, Pool};
 use futures_core::future::BoxFuture;
+use rand::Rng;
+use std::cmp;
 use std::fmt::{self, Debug, Formatter};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -42,7 +44,8 @@ use std::time::{Duration, Instant};
 /// so having the closure return `Pin<Box<dyn Future>` directly is the path of least resistance from
 /// the perspectives of both API designer and consumer.
 pub struct PoolOptions<DB: Database> {
-    pub(crate) test_before_acquire: bool,
+    pub(crate) acquire_test: AcquireTest,
+    pub(crate) acquire_test_after_idle: Option<Duration>,
     pub(crate) after_connect: Option<
         Arc<
             dyn Fn(&mut DB::Connection, PoolConnectionMetadata) -> BoxFuture<'_, Result<(), Error>>
@@ -73,11 +76,16 @@ pub struct PoolOptions<DB: Database> {
                 + Sync,
         >,
     >,
+    pub(crate) checkout_label: Option<Arc<dyn Fn() -> Option<String> + 'static + Send + Sync>>,
+    pub(crate) leak_detection_threshold: Option<Duration>,
+    pub(crate) on_connection_leak: Option<Arc<dyn Fn(&ConnectionLeak) + 'static + Send + Sync>>,
     pub(crate) max_connections: u32,
     pub(crate) acquire_timeout: Duration,
     pub(crate) min_connections: u32,
     pub(crate) max_lifetime: Option<Duration>,
+    pub(crate) max_lifetime_jitter: Option<Duration>,
     pub(crate) idle_timeout: Option<Duration>,
+    pub(crate) autoscale: Option<Duration>,
     pub(crate) fair: bool,
 
     pub(crate) parent_pool: Option<Pool<DB>>,
@@ -89,21 +97,47 @@ pub struct PoolOptions<DB: Database> {
 impl<DB: Database> Clone for PoolOptions<DB> {
     fn clone(&self) -> Self {
         PoolOptions {
-            test_before_acquire: self.test_before_acquire,
+            acquire_test: self.acquire_test,
+            acquire_test_after_idle: self.acquire_test_after_idle,
             after_connect: self.after_connect.clone(),
             before_acquire: self.before_acquire.clone(),
             after_release: self.after_release.clone(),
+            checkout_label: self.checkout_label.clone(),
+            leak_detection_threshold: self.leak_detection_threshold,
+            on_connection_leak: self.on_connection_leak.clone(),
             max_connections: self.max_connections,
             acquire_timeout: self.acquire_timeout,
             min_connections: self.min_connections,
             max_lifetime: self.max_lifetime,
+            max_lifetime_jitter: self.max_lifetime_jitter,
             idle_timeout: self.idle_timeout,
+            autoscale: self.autoscale,
             fair: self.fair,
             parent_pool: self.parent_pool.as_ref().map(Pool::clone),
         }
     }
 }
 
+/// How an idle connection is checked before [`Pool::acquire()`] returns it.
+///
+/// Set with [`PoolOptions::acquire_test()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AcquireTest {
+    /// Return idle connections without checking them.
+    Skip,
+
+    /// Check that the server hasn't closed the connection with [`Connection::is_open()`],
+    /// which doesn't need a round trip.
+    ///
+    /// This catches connections closed by the server or a proxy (e.g. after a restart or an idle
+    /// timeout), but not ones to a server that stopped responding.
+    Fast,
+
+    /// Make a round trip to the server with [`Connection::ping()`].
+    Ping,
+}
+
 /// Metadata for the connection being processed by a [`PoolOptions`] callback.
 #[derive(Debug)] // Don't want to commit to any other trait impls yet.
 #[non_exhaustive] // So we can safely add fields in the future.
@@ -139,13 +173,19 @@ impl<DB: Database> PoolOptions<DB> {
             after_connect: None,
             before_acquire: None,
             after_release: None,
-            test_before_acquire: true,
+            checkout_label: None,
+            leak_detection_threshold: None,
+            on_connection_leak: None,
+            acquire_test: AcquireTest::Ping,
+            acquire_test_after_idle: None,
             // A production application will want to set a higher limit than this.
             max_connections: 10,
             min_connections: 0,
             acquire_timeout: Duration::from_secs(30),
             idle_timeout: Some(Duration::from_secs(10 * 60)),
             max_lifetime: Some(Duration::from_secs(30 * 60)),
+            max_lifetime_jitter: None,
+            autoscale: None,
             fair: true,
             parent_pool: None,
         }
@@ -198,6 +238,39 @@ impl<DB: Database> PoolOptions<DB> {
         self.min_connections
     }
 
+    /// Automatically resize the pool between `min` and `max` connections based on load.
+    ///
+    /// This sets [`min_connections`] and [`max_connections`], and additionally keeps track of
+    /// how long [`Pool::acquire()`] takes to return a connection:
+    ///
+    /// * Whenever an acquire takes longer than `target_acquire_latency`, the number of
+    ///   connections the pool keeps open is raised by one and a new connection is opened in the
+    ///   background, so it is ready for the next caller.
+    /// * Once acquires have been consistently fast (under half of `target_acquire_latency`)
+    ///   for a few seconds, idle connections above the current floor are closed and the floor
+    ///   is lowered by one, down to `min`. This happens in addition to [`idle_timeout`].
+    ///
+    /// The gap between the two thresholds keeps the pool from flapping between sizes
+    /// under steady load.
+    ///
+    /// Static sizing works well for steady workloads, but is hard to tune for bursty ones;
+    /// this lets the pool keep warm connections around only while they're needed.
+    ///
+    /// [`min_connections`]: Self::min_connections
+    /// [`max_connections`]: Self::max_connections
+    /// [`idle_timeout`]: Self::idle_timeout
+    pub fn autoscale(mut self, min: u32, max: u32, target_acquire_latency: Duration) -> Self {
+        self.min_connections = min;
+        self.max_connections = max;
+        self.autoscale = Some(target_acquire_latency);
+        self
+    }
+
+    /// Get the target acquire latency set by [`autoscale`][Self::autoscale], if enabled.
+    pub fn get_autoscale_target(&self) -> Option<Duration> {
+        self.autoscale
+    }
+
     /// Set the maximum amount of time to spend waiting for a connection in [`Pool::acquire()`].
     ///
     /// Caps the total amount of time `Pool::acquire()` can spend waiting across multiple phases:
@@ -245,6 +318,37 @@ impl<DB: Database> PoolOptions<DB> {
         self.max_lifetime
     }
 
+    /// Shorten the [`max_lifetime`][Self::max_lifetime] of each connection by a random duration
+    /// up to `jitter`, so connections opened together aren't all closed together.
+    ///
+    /// Without jitter, the connections opened when the pool starts (e.g. after a deploy) all
+    /// expire at about the same time, and the application reconnects all of them at once.
+    /// With jitter, they expire one by one over the `jitter` window, and the reaper checks for
+    /// expired connections often enough to close and replace them as they do.
+    ///
+    /// `jitter` is capped to `max_lifetime`. Has no effect if `max_lifetime` is `None`.
+    pub fn max_lifetime_jitter(mut self, jitter: impl Into<Option<Duration>>) -> Self {
+        self.max_lifetime_jitter = jitter.into();
+        self
+    }
+
+    /// Get the maximum random duration each connection's lifetime is shortened by.
+    pub fn get_max_lifetime_jitter(&self) -> Option<Duration> {
+        self.max_lifetime_jitter
+    }
+
+    /// The lifetime of a new connection, which is `max_lifetime` minus a random jitter.
+    pub(crate) fn connection_lifetime(&self) -> Option<Duration> {
+        let max_lifetime = self.max_lifetime?;
+
+        let jitter = match self.max_lifetime_jitter {
+            Some(jitter) if !jitter.is_zero() => cmp::min(jitter, max_lifetime),
+            _ => return Some(max_lifetime),
+        };
+
+        Some(max_lifetime - rand::thread_rng().gen_range(Duration::ZERO..=jitter))
+    }
+
     /// Set a maximum idle duration for individual connections.
     ///
     /// Any connection that remains in the idle queue longer than this will be closed.
@@ -263,15 +367,50 @@ impl<DB: Database> PoolOptions<DB> {
     /// If true, the health of a connection will be verified by a call to [`Connection::ping`]
     /// before returning the connection.
     ///
-    /// Defaults to `true`.
+    /// Defaults to `true`. This is shorthand for [`acquire_test()`][Self::acquire_test] with
+    /// [`AcquireTest::Ping`] or [`AcquireTest::Skip`].
     pub fn test_before_acquire(mut self, test: bool) -> Self {
-        self.test_before_acquire = test;
+        self.acquire_test = if test {
+            AcquireTest::Ping
+        } else {
+            AcquireTest::Skip
+        };
         self
     }
 
     /// Get's whether `test_before_acquire` is currently set.
     pub fn get_test_before_acquire(&self) -> bool {
-        self.test_before_acquire
+        self.acquire_test != AcquireTest::Skip
+    }
+
+    /// Set how the health of an idle connection is verified before returning it.
+    ///
+    /// Defaults to [`AcquireTest::Ping`], which adds a round trip to each acquire. In low-latency
+    /// setups, [`AcquireTest::Fast`] only checks that the server hasn't closed the connection,
+    /// and an error from a connection that's broken otherwise is left to the query.
+    pub fn acquire_test(mut self, test: AcquireTest) -> Self {
+        self.acquire_test = test;
+        self
+    }
+
+    /// Get how the health of an idle connection is verified before returning it.
+    pub fn get_acquire_test(&self) -> AcquireTest {
+        self.acquire_test
+    }
+
+    /// Only test connections that have been idle for longer than `idle`, with the
+    /// [`acquire_test`][Self::acquire_test].
+    ///
+    /// A connection that was just returned to the pool is most likely still healthy, so with a
+    /// busy pool this skips most of the tests. Defaults to `None`, testing every connection.
+    pub fn acquire_test_after_idle(mut self, idle: impl Into<Option<Duration>>) -> Self {
+        self.acquire_test_after_idle = idle.into();
+        self
+    }
+
+    /// Get how long a connection must be idle before it's tested, if set.
+    pub fn get_acquire_test_after_idle(&self) -> Option<Duration> {
+        self.acquire_test_after_idle
     }
 
     /// If set to `true`, calls to `acquire()` are fair and connections  are issued
@@ -453,6 +592,112 @@ impl<DB: Database> PoolOptions<DB> {
         self
     }
 
+    /// Label each connection with the task it is checked out to in server-side monitoring.
+    ///
+    /// Every time a connection is acquired, `label` is invoked and its result is appended to the
+    /// [application name][crate::connection::ConnectOptions::application_name] of the connection
+    /// as `{application_name}:{label}`. If `label` returns `None` or an empty label, the
+    /// connection is reset to the name it was opened with. This allows attributing server
+    /// activity to, for example, the HTTP endpoint being served, typically by reading a
+    /// task-local.
+    ///
+    /// Applying a label takes a round-trip to the server (`set_config()` on Postgres) when it
+    /// differs from the connection's label at its last checkout, so this is not enabled by
+    /// default.
+    /// Drivers which cannot change the name of an open connection (MySQL, SQLite) ignore it.
+    ///
+    /// Connections returned by [`Pool::try_acquire()`] are not labelled, as that would
+    /// require waiting on the server.
+    ///
+    /// # Example (Postgres)
+    /// ```no_run
+    /// # async fn f() -> Result<(), Box<dyn std::error::Error>> {
+    /// use sqlx::ConnectOptions;
+    /// use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+    ///
+    /// tokio::task_local! {
+    ///     static ENDPOINT: &'static str;
+    /// }
+    ///
+    /// let pool = PgPoolOptions::new()
+    ///     .checkout_label(|| ENDPOINT.try_with(|endpoint| endpoint.to_string()).ok())
+    ///     .connect_with(PgConnectOptions::new().application_name("billing-service"))
+    ///     .await?;
+    ///
+    /// // Shows up as `billing-service:/invoices` in `pg_stat_activity`
+    /// ENDPOINT.scope("/invoices", async {
+    ///     sqlx::query("SELECT 1").execute(&pool).await
+    /// }).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn checkout_label<F>(mut self, label: F) -> Self
+    where
+        F: Fn() -> Option<String> + 'static + Send + Sync,
+    {
+        self.checkout_label = Some(Arc::new(label));
+        self
+    }
+
+    /// Report connections that are checked out of the pool for longer than `threshold`.
+    ///
+    /// A backtrace is captured whenever a connection is acquired, and if the connection hasn't
+    /// been returned after `threshold`, it is passed to
+    /// [`on_connection_leak`][Self::on_connection_leak] or, if that isn't set, logged at `WARN`.
+    /// Each checkout is reported once, and again when [`Pool::close()`] is called if it still
+    /// hasn't been returned, as that will wait for it.
+    ///
+    /// Connections taken out of the pool with [`PoolConnection::detach()`] or
+    /// [`PoolConnection::leak()`] are no longer tracked.
+    ///
+    /// Capturing a backtrace on every acquire is expensive, so this is disabled by default.
+    /// The threshold should be set well above the longest you expect a connection to be held,
+    /// such as for a long-running transaction.
+    ///
+    /// [`PoolConnection::detach()`]: crate::pool::PoolConnection::detach
+    /// [`PoolConnection::leak()`]: crate::pool::PoolConnection::leak
+    pub fn leak_detection_threshold(mut self, threshold: impl Into<Option<Duration>>) -> Self {
+        self.leak_detection_threshold = threshold.into();
+        self
+    }
+
+    /// Get the leak detection threshold, if set.
+    pub fn get_leak_detection_threshold(&self) -> Option<Duration> {
+        self.leak_detection_threshold
+    }
+
+    /// Call `callback` with each leak found by
+    /// [`leak_detection_threshold`][Self::leak_detection_threshold], instead of logging it.
+    ///
+    /// The callback is run on a background task and must not block.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # async fn f() -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::time::Duration;
+    /// use sqlx::postgres::PgPoolOptions;
+    ///
+    /// let pool = PgPoolOptions::new()
+    ///     .leak_detection_threshold(Duration::from_secs(30))
+    ///     .on_connection_leak(|leak| {
+    ///         eprintln!(
+    ///             "connection held for {:?}, acquired at:\n{}",
+    ///             leak.held_for, leak.backtrace
+    ///         );
+    ///     })
+    ///     .connect("postgres:// …")
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_connection_leak<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&ConnectionLeak) + 'static + Send + Sync,
+    {
+        self.on_connection_leak = Some(Arc::new(callback));
+        self
+    }
+
     /// Set the parent `Pool` from which the new pool will inherit its semaphore.
     ///
     /// This is currently an internal-only API.
@@ -542,8 +787,34 @@ impl<DB: Database> Debug for PoolOptions<DB> {
             .field("min_connections", &self.min_connections)
             .field("connect_timeout", &self.acquire_timeout)
             .field("max_lifetime", &self.max_lifetime)
+            .field("max_lifetime_jitter", &self.max_lifetime_jitter)
             .field("idle_timeout", &self.idle_timeout)
-            .field("test_before_acquire", &self.test_before_acquire)
+            .field("autoscale", &self.autoscale)
+            .field("leak_detection_threshold", &self.leak_detection_threshold)
+            .field("acquire_test", &self.acquire_test)
+            .field("acquire_test_after_idle", &self.acquire_test_after_idle)
             .finish()
     }
 }
+
+#[cfg(feature = "any")]
+#[test]
+fn test_connection_lifetime() {
+    let max_lifetime = Duration::from_secs(60);
+    let options = PoolOptions::<crate::any::Any>::new().max_lifetime(max_lifetime);
+
+    assert_eq!(options.connection_lifetime(), Some(max_lifetime));
+
+    let options = options.max_lifetime_jitter(Duration::from_secs(10));
+
+    for _ in 0..100 {
+        let lifetime = options.connection_lifetime().unwrap();
+        assert!(lifetime >= Duration::from_secs(50) && lifetime <= max_lifetime);
+    }
+
+    // the jitter can't be more than the lifetime
+    let options = options.max_lifetime_jitter(Duration::from_secs(600));
+    assert!(options.connection_lifetime().unwrap() <= max_lifetime);
+
+    assert_eq!(options.max_lifetime(None).connection_lifetime(), None);
+}
@@ -0,0 +1,533 @@
+//! [`ReplicaPool`]: routing reads to read replicas, with read-your-writes sessions.
+
+use std::fmt::{self, Debug, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use either::Either;
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+use futures_util::TryStreamExt;
+
+use crate::connection::Connection;
+use crate::database::{Database, HasStatement};
+use crate::describe::Describe;
+use crate::error::Error;
+use crate::executor::{Execute, Executor};
+use crate::pool::resilient::leading_keyword;
+use crate::pool::Pool;
+
+/// Positions in a primary's replication log, implemented by the connection types of databases
+/// that support [`ReplicaSession`].
+pub trait ReplicationPosition: Connection {
+    /// The current position of this primary in its replication log, e.g. its WAL LSN or the set
+    /// of GTIDs it has executed.
+    fn replication_position(&mut self) -> BoxFuture<'_, Result<String, Error>>;
+
+    /// Checks whether this replica has replayed its primary's log up to `position`.
+    fn has_replayed<'e>(&'e mut self, position: &'e str) -> BoxFuture<'e, Result<bool, Error>>;
+}
+
+/// A primary pool and pools of its read replicas, routing queries that only read to the
+/// replicas in turn and everything else to the primary.
+///
+/// Routing is conservative: only a single `SELECT` statement is routed to a replica, unless it
+/// locks rows (e.g. `FOR UPDATE`), selects `INTO` a table or variable, or calls a function that's
+/// known to write or to depend on the session, like `nextval()` or `LAST_INSERT_ID()`. Anything
+/// else, including `WITH` queries, which may modify data, goes to the primary.
+///
+/// Functions with side effects defined by the application can't be detected, so queries calling
+/// them must use the [primary][Self::primary()] directly, as must transactions. To route a
+/// query to a replica explicitly instead, run it on [`Self::replica()`], or
+/// [`ReplicaSession::read_pool()`] in a session.
+///
+/// Replicas lag behind the primary, so a read right after a write may not see it. Queries that
+/// need to see their own writes can be run through a [`ReplicaSession`].
+///
+/// ```rust,ignore
+/// use sqlx::pool::ReplicaPool;
+///
+/// let pool = ReplicaPool::new(primary, vec![replica_1, replica_2]);
+///
+/// // runs on a replica
+/// let users: Vec<User> = sqlx::query_as("SELECT * FROM users").fetch_all(&pool).await?;
+///
+/// let session = pool.session();
+///
+/// // runs on the primary
+/// sqlx::query("UPDATE users SET name = $1 WHERE id = $2")
+///     .bind(name)
+///     .bind(id)
+///     .execute(&session)
+///     .await?;
+///
+/// // runs on a replica that has replayed the update, or the primary if none has yet
+/// let user: User = sqlx::query_as("SELECT * FROM users WHERE id = $1")
+///     .bind(id)
+///     .fetch_one(&session)
+///     .await?;
+/// ```
+pub struct ReplicaPool<DB: Database> {
+    inner: Arc<ReplicaPoolInner<DB>>,
+}
+
+struct ReplicaPoolInner<DB: Database> {
+    primary: Pool<DB>,
+    replicas: Vec<Pool<DB>>,
+    next: AtomicUsize,
+}
+
+/// A logical session on a [`ReplicaPool`] that always reads its own writes.
+///
+/// After a write through the session, the session records the primary's position in its
+/// replication log (its WAL LSN on Postgres, or its executed GTID set on MySQL, which requires
+/// `gtid_mode=ON`). Reads are then only routed to replicas that have replayed the log up to that
+/// position, falling back to the primary if none has.
+///
+/// Writes made outside of the session, e.g. in a transaction on the primary, can be recorded
+/// afterwards with [`Self::record_write()`].
+pub struct ReplicaSession<DB: Database> {
+    pool: ReplicaPool<DB>,
+    state: Mutex<SessionState>,
+}
+
+#[derive(Default)]
+struct SessionState {
+    position: Option<String>,
+    // indexes of the replicas known to have replayed `position`
+    caught_up: Vec<usize>,
+}
+
+impl<DB: Database> ReplicaPool<DB> {
+    /// Routes reads across `replicas` and everything else to `primary`.
+    ///
+    /// With no replicas, everything is routed to the primary.
+    pub fn new(primary: Pool<DB>, replicas: Vec<Pool<DB>>) -> Self {
+        ReplicaPool {
+            inner: Arc::new(ReplicaPoolInner {
+                primary,
+                replicas,
+                next: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// The pool of the primary.
+    pub fn primary(&self) -> &Pool<DB> {
+        &self.inner.primary
+    }
+
+    /// The pools of the replicas.
+    pub fn replicas(&self) -> &[Pool<DB>] {
+        &self.inner.replicas
+    }
+
+    /// The pool of the next replica in turn, or of the primary if there are no replicas.
+    pub fn replica(&self) -> &Pool<DB> {
+        self.replicas()
+            .get(self.next_replica())
+            .unwrap_or(self.primary())
+    }
+
+    /// Starts a session that reads its own writes.
+    pub fn session(&self) -> ReplicaSession<DB> {
+        ReplicaSession {
+            pool: self.clone(),
+            state: Mutex::new(SessionState::default()),
+        }
+    }
+
+    /// Closes the pools of the primary and every replica.
+    pub async fn close(&self) {
+        self.primary().close().await;
+
+        for replica in self.replicas() {
+            replica.close().await;
+        }
+    }
+
+    fn next_replica(&self) -> usize {
+        match self.replicas().len() {
+            0 => 0,
+            len => self.inner.next.fetch_add(1, Ordering::Relaxed) % len,
+        }
+    }
+
+    fn route(&self, sql: &str) -> &Pool<DB> {
+        if is_read(sql) {
+            self.replica()
+        } else {
+            self.primary()
+        }
+    }
+}
+
+impl<DB: Database> Clone for ReplicaPool<DB> {
+    fn clone(&self) -> Self {
+        ReplicaPool {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<DB: Database> Debug for ReplicaPool<DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReplicaPool")
+            .field("primary", self.primary())
+            .field("replicas", &self.replicas())
+            .finish()
+    }
+}
+
+impl<DB> ReplicaSession<DB>
+where
+    DB: Database,
+    DB::Connection: ReplicationPosition,
+{
+    /// The primary's position after the last write recorded by this session, if any.
+    pub fn position(&self) -> Option<String> {
+        self.state.lock().unwrap().position.clone()
+    }
+
+    /// Records the primary's current position, so later reads see every write committed so far.
+    pub async fn record_write(&self) -> Result<(), Error> {
+        let position = self
+            .pool
+            .primary()
+            .acquire()
+            .await?
+            .replication_position()
+            .await?;
+
+        *self.state.lock().unwrap() = SessionState {
+            position: Some(position),
+            caught_up: Vec::new(),
+        };
+
+        Ok(())
+    }
+
+    /// The pool to read from: the next replica that has replayed the last write, or the primary.
+    ///
+    /// Replicas that can't be checked, e.g. because they're down, are skipped.
+    pub async fn read_pool(&self) -> &Pool<DB> {
+        let replicas = self.pool.replicas();
+
+        let position = {
+            let state = self.state.lock().unwrap();
+
+            match &state.position {
+                Some(position) => position.clone(),
+                None => return self.pool.replica(),
+            }
+        };
+
+        let start = self.pool.next_replica();
+
+        for offset in 0..replicas.len() {
+            let index = (start + offset) % replicas.len();
+
+            if self.state.lock().unwrap().caught_up.contains(&index) {
+                return &replicas[index];
+            }
+
+            let replayed = match replicas[index].acquire().await {
+                Ok(mut conn) => conn.has_replayed(&position).await.unwrap_or(false),
+                Err(_) => false,
+            };
+
+            if replayed {
+                let mut state = self.state.lock().unwrap();
+
+                // unless a write was recorded in the meantime
+                if state.position.as_ref() == Some(&position) {
+                    state.caught_up.push(index);
+                }
+
+                return &replicas[index];
+            }
+        }
+
+        self.pool.primary()
+    }
+}
+
+impl<DB: Database> Debug for ReplicaSession<DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReplicaSession")
+            .field("pool", &self.pool)
+            .field("position", &self.state.lock().unwrap().position)
+            .finish()
+    }
+}
+
+/// Checks whether `sql` only reads, so it can run on a replica.
+///
+/// Words in literals, quoted identifiers and comments are skipped; anything that can't be
+/// scanned reliably, e.g. a string literal with a backslash, whose meaning depends on the
+/// database and its settings, is treated as a write.
+fn is_read(sql: &str) -> bool {
+    if leading_keyword(sql) != "select" {
+        return false;
+    }
+
+    let sql = sql.as_bytes();
+    let is_word_byte = |c: &u8| c.is_ascii_alphanumeric() || *c == b'_';
+    let find = |haystack: &[u8], needle: &[u8]| {
+        haystack
+            .windows(needle.len())
+            .position(|window| window == needle)
+    };
+
+    let mut i = 0;
+    let mut statement_ended = false;
+
+    while i < sql.len() {
+        let rest = &sql[i..];
+
+        match rest[0] {
+            quote @ (b'\'' | b'"' | b'`') => {
+                // a doubled quote is read as two adjacent literals, which is just as good
+                let Some(len) = rest[1..].iter().position(|&c| c == quote) else {
+                    return false;
+                };
+
+                if rest[1..=len].contains(&b'\\') {
+                    return false;
+                }
+
+                i += len + 2;
+            }
+            b'-' if rest.starts_with(b"--") => {
+                i += rest.iter().position(|&c| c == b'\n').unwrap_or(rest.len());
+            }
+            b'/' if rest.starts_with(b"/*") => match find(&rest[2..], b"*/") {
+                Some(len) => i += len + 4,
+                None => return false,
+            },
+            // a Postgres dollar-quoted string, e.g. `$$text$$` or `$tag$text$tag$`
+            b'$' if !rest.get(1).is_some_and(u8::is_ascii_digit) => {
+                let tag_len = rest[1..]
+                    .iter()
+                    .position(|c| !is_word_byte(c))
+                    .unwrap_or(rest.len() - 1);
+
+                if rest.get(tag_len + 1) != Some(&b'$') {
+                    i += 1;
+                    continue;
+                }
+
+                let tag = &rest[..tag_len + 2];
+
+                match find(&rest[tag.len()..], tag) {
+                    Some(len) => i += len + tag.len() * 2,
+                    None => return false,
+                }
+            }
+            b';' => {
+                statement_ended = true;
+                i += 1;
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                let len = rest
+                    .iter()
+                    .position(|c| !is_word_byte(c))
+                    .unwrap_or(rest.len());
+                let word = std::str::from_utf8(&rest[..len])
+                    .expect("BUG: words are ASCII")
+                    .to_ascii_lowercase();
+
+                // another statement follows
+                if statement_ended || is_write_word(&word) {
+                    return false;
+                }
+
+                i += len;
+            }
+            _ => i += 1,
+        }
+    }
+
+    true
+}
+
+/// Words that make a `SELECT` a write, or tie it to the primary's session.
+fn is_write_word(word: &str) -> bool {
+    matches!(
+        word,
+        // `FOR UPDATE`, `FOR [KEY] SHARE`, `LOCK IN SHARE MODE`
+        "update"
+            | "share"
+            | "lock"
+            // `SELECT .. INTO` a new table, variables or a file
+            | "into"
+            // Postgres
+            | "nextval"
+            | "setval"
+            | "currval"
+            | "lastval"
+            | "pg_notify"
+            | "set_config"
+            // MySQL
+            | "last_insert_id"
+            | "found_rows"
+            | "row_count"
+            | "get_lock"
+            | "release_lock"
+            | "release_all_locks"
+    ) || word.starts_with("pg_advisory")
+        || word.starts_with("pg_try_advisory")
+}
+
+impl<'p, DB: Database> Executor<'p> for &'_ ReplicaPool<DB>
+where
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    type Database = DB;
+
+    fn fetch_many<'e, 'q: 'e, E>(
+        self,
+        query: E,
+    ) -> BoxStream<'e, Result<Either<DB::QueryResult, DB::Row>, Error>>
+    where
+        E: 'q + Execute<'q, Self::Database>,
+    {
+        self.route(query.sql()).fetch_many(query)
+    }
+
+    fn fetch_optional<'e, 'q: 'e, E>(
+        self,
+        query: E,
+    ) -> BoxFuture<'e, Result<Option<DB::Row>, Error>>
+    where
+        E: 'q + Execute<'q, Self::Database>,
+    {
+        self.route(query.sql()).fetch_optional(query)
+    }
+
+    fn prepare_with<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+        parameters: &'e [<Self::Database as Database>::TypeInfo],
+    ) -> BoxFuture<'e, Result<<Self::Database as HasStatement<'q>>::Statement, Error>> {
+        self.primary().prepare_with(sql, parameters)
+    }
+
+    #[doc(hidden)]
+    fn describe<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+    ) -> BoxFuture<'e, Result<Describe<Self::Database>, Error>> {
+        self.primary().describe(sql)
+    }
+}
+
+impl<'c, DB: Database> Executor<'c> for &'c ReplicaSession<DB>
+where
+    DB::Connection: ReplicationPosition,
+    for<'x> &'x mut DB::Connection: Executor<'x, Database = DB>,
+{
+    type Database = DB;
+
+    fn fetch_many<'e, 'q: 'e, E>(
+        self,
+        query: E,
+    ) -> BoxStream<'e, Result<Either<DB::QueryResult, DB::Row>, Error>>
+    where
+        'c: 'e,
+        E: 'q + Execute<'q, Self::Database>,
+    {
+        Box::pin(try_stream! {
+            if is_read(query.sql()) {
+                let mut s = self.read_pool().await.fetch_many(query);
+
+                while let Some(v) = s.try_next().await? {
+                    r#yield!(v);
+                }
+            } else {
+                let mut s = self.pool.primary().fetch_many(query);
+
+                while let Some(v) = s.try_next().await? {
+                    r#yield!(v);
+                }
+
+                drop(s);
+                self.record_write().await?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn fetch_optional<'e, 'q: 'e, E>(
+        self,
+        query: E,
+    ) -> BoxFuture<'e, Result<Option<DB::Row>, Error>>
+    where
+        'c: 'e,
+        E: 'q + Execute<'q, Self::Database>,
+    {
+        Box::pin(async move {
+            if is_read(query.sql()) {
+                return self.read_pool().await.fetch_optional(query).await;
+            }
+
+            let row = self.pool.primary().fetch_optional(query).await?;
+            self.record_write().await?;
+
+            Ok(row)
+        })
+    }
+
+    fn prepare_with<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+        parameters: &'e [<Self::Database as Database>::TypeInfo],
+    ) -> BoxFuture<'e, Result<<Self::Database as HasStatement<'q>>::Statement, Error>>
+    where
+        'c: 'e,
+    {
+        self.pool.primary().prepare_with(sql, parameters)
+    }
+
+    #[doc(hidden)]
+    fn describe<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+    ) -> BoxFuture<'e, Result<Describe<Self::Database>, Error>>
+    where
+        'c: 'e,
+    {
+        self.pool.primary().describe(sql)
+    }
+}
+
+#[test]
+fn test_is_read() {
+    assert!(is_read("SELECT * FROM users WHERE id = $1"));
+    assert!(is_read("select count(*) from users;\n"));
+    assert!(is_read(
+        "SELECT 'for update', \"into\", `lock` FROM t -- FOR UPDATE\n/* nextval() */"
+    ));
+    assert!(is_read("SELECT $$ FOR UPDATE $$, $tag$ ' $tag$"));
+    assert!(is_read("SELECT 'it''s', \"a\"\"b\" FROM t"));
+
+    assert!(!is_read("INSERT INTO users (name) VALUES ($1)"));
+    assert!(!is_read(
+        "WITH d AS (DELETE FROM t RETURNING *) SELECT * FROM d"
+    ));
+    assert!(!is_read("SELECT * FROM t FOR UPDATE"));
+    assert!(!is_read("SELECT * FROM t FOR NO KEY UPDATE SKIP LOCKED"));
+    assert!(!is_read("SELECT * FROM t FOR KEY SHARE"));
+    assert!(!is_read("SELECT * FROM t LOCK IN SHARE MODE"));
+    assert!(!is_read("SELECT * INTO archive FROM t"));
+    assert!(!is_read("SELECT nextval('seq')"));
+    assert!(!is_read("SELECT LAST_INSERT_ID()"));
+    assert!(!is_read("SELECT pg_advisory_lock(1)"));
+    assert!(!is_read("SELECT 1; DELETE FROM t"));
+
+    // ambiguous without knowing the database's settings
+    assert!(!is_read("SELECT 'a\\' FROM t"));
+    assert!(!is_read("SELECT 'unterminated"));
+    assert!(!is_read("SELECT 1 /* unterminated"));
+}
@@ -0,0 +1,537 @@
+//! [`ResilientPool`]: concurrency limits and a circuit breaker in front of a [`Pool`].
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use either::Either;
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+use futures_util::TryStreamExt;
+
+use crate::database::{Database, HasStatement};
+use crate::describe::Describe;
+use crate::error::Error;
+use crate::executor::{Execute, Executor};
+use crate::pool::Pool;
+use crate::sync::{AsyncSemaphore, AsyncSemaphoreReleaser};
+
+/// A wrapper around a [`Pool`] that limits how many queries of each class run at once and sheds
+/// load with a circuit breaker while the database is failing.
+///
+/// Queries are classified by a function of their SQL, by default the statement's leading keyword
+/// in lowercase, e.g. `select` or `insert`. Classes with a [limit][ResilientPoolOptions::limit]
+/// wait for one of their permits before acquiring a connection, so e.g. expensive reports can't
+/// use up every connection in the pool.
+///
+/// The circuit breaker counts queries that fail with a connection-level error (I/O, TLS,
+/// protocol or pool timeout errors, not errors returned by the database for the query itself)
+/// or take longer than the [slow threshold][ResilientPoolOptions::slow_threshold]. If too many
+/// queries in a window fail, the breaker opens and every query fails immediately with
+/// [`Error::CircuitOpen`] until the open duration has passed. Then a single trial query is let
+/// through, which closes the breaker again if it succeeds.
+///
+/// `&ResilientPool` implements [`Executor`], so it can be used wherever a `&Pool` can be:
+///
+/// ```rust,ignore
+/// use sqlx::pool::{ResilientPool, ResilientPoolOptions};
+///
+/// let pool = ResilientPoolOptions::new()
+///     .limit("select", 16)
+///     .slow_threshold(Duration::from_secs(5))
+///     .build(pool);
+///
+/// let user: User = sqlx::query_as("SELECT * FROM users WHERE id = $1")
+///     .bind(id)
+///     .fetch_one(&pool)
+///     .await?;
+/// ```
+///
+/// Transactions and connections acquired from the inner pool with [`Self::pool()`] bypass both the
+/// limits and the circuit breaker.
+pub struct ResilientPool<DB: Database> {
+    pool: Pool<DB>,
+    inner: Arc<ResilientInner>,
+}
+
+/// Configuration for a [`ResilientPool`].
+#[derive(Clone)]
+pub struct ResilientPoolOptions {
+    limits: BTreeMap<String, usize>,
+    classify: Arc<dyn Fn(&str) -> String + Send + Sync>,
+    failure_rate: f64,
+    min_requests: u32,
+    window: Duration,
+    slow_threshold: Option<Duration>,
+    open_duration: Duration,
+}
+
+/// The state of a [`ResilientPool`]'s circuit breaker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Queries are let through.
+    Closed,
+    /// Queries fail immediately.
+    Open,
+    /// A trial query is let through to test whether the database has recovered.
+    HalfOpen,
+}
+
+/// A snapshot of a [`ResilientPool`]'s state, from [`ResilientPool::metrics()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ResilientPoolMetrics {
+    pub circuit: CircuitState,
+    /// The classes with a limit, in order of name.
+    pub classes: Vec<ClassMetrics>,
+}
+
+/// The state of a class of queries with a limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ClassMetrics {
+    pub class: String,
+    pub limit: usize,
+    /// The number of queries running.
+    pub running: usize,
+    /// The number of queries waiting for a permit.
+    pub waiting: usize,
+}
+
+struct ResilientInner {
+    options: ResilientPoolOptions,
+    classes: BTreeMap<String, Class>,
+    breaker: Mutex<Breaker>,
+}
+
+struct Class {
+    limit: usize,
+    semaphore: AsyncSemaphore,
+    running: AtomicUsize,
+    waiting: AtomicUsize,
+}
+
+enum Breaker {
+    Closed {
+        window_start: Instant,
+        requests: u32,
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    HalfOpen {
+        // if the trial query is cancelled, another is let through after the open duration
+        since: Instant,
+    },
+}
+
+impl Default for ResilientPoolOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResilientPoolOptions {
+    /// No limits, and a circuit breaker that opens for 30 seconds when at least half of at least
+    /// 20 queries in a 10 second window fail.
+    pub fn new() -> Self {
+        ResilientPoolOptions {
+            limits: BTreeMap::new(),
+            classify: Arc::new(leading_keyword),
+            failure_rate: 0.5,
+            min_requests: 20,
+            window: Duration::from_secs(10),
+            slow_threshold: None,
+            open_duration: Duration::from_secs(30),
+        }
+    }
+
+    /// Run at most `max_concurrent` queries of `class` at once.
+    pub fn limit(mut self, class: impl Into<String>, max_concurrent: usize) -> Self {
+        self.limits.insert(class.into(), max_concurrent);
+        self
+    }
+
+    /// Classify queries with `classify` instead of by their leading keyword.
+    pub fn classify(mut self, classify: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        self.classify = Arc::new(classify);
+        self
+    }
+
+    /// Open the circuit breaker when at least this fraction of queries in a window fail.
+    pub fn failure_rate(mut self, failure_rate: f64) -> Self {
+        self.failure_rate = failure_rate;
+        self
+    }
+
+    /// The minimum number of queries in a window before the circuit breaker can open.
+    pub fn min_requests(mut self, min_requests: u32) -> Self {
+        self.min_requests = min_requests;
+        self
+    }
+
+    /// How long the failure rate is measured over.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Count queries that take longer than `slow_threshold` as failures.
+    pub fn slow_threshold(mut self, slow_threshold: Duration) -> Self {
+        self.slow_threshold = Some(slow_threshold);
+        self
+    }
+
+    /// How long the circuit breaker stays open before letting a trial query through.
+    pub fn open_duration(mut self, open_duration: Duration) -> Self {
+        self.open_duration = open_duration;
+        self
+    }
+
+    /// Wrap `pool`.
+    pub fn build<DB: Database>(self, pool: Pool<DB>) -> ResilientPool<DB> {
+        let classes = self
+            .limits
+            .iter()
+            .map(|(class, &limit)| {
+                (
+                    class.clone(),
+                    Class {
+                        limit,
+                        semaphore: AsyncSemaphore::new(true, limit),
+                        running: AtomicUsize::new(0),
+                        waiting: AtomicUsize::new(0),
+                    },
+                )
+            })
+            .collect();
+
+        ResilientPool {
+            pool,
+            inner: Arc::new(ResilientInner {
+                options: self,
+                classes,
+                breaker: Mutex::new(Breaker::closed()),
+            }),
+        }
+    }
+}
+
+impl Debug for ResilientPoolOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResilientPoolOptions")
+            .field("limits", &self.limits)
+            .field("failure_rate", &self.failure_rate)
+            .field("min_requests", &self.min_requests)
+            .field("window", &self.window)
+            .field("slow_threshold", &self.slow_threshold)
+            .field("open_duration", &self.open_duration)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<DB: Database> ResilientPool<DB> {
+    /// The wrapped pool.
+    pub fn pool(&self) -> &Pool<DB> {
+        &self.pool
+    }
+
+    /// The state of the circuit breaker and of each class with a limit.
+    pub fn metrics(&self) -> ResilientPoolMetrics {
+        let circuit = match &*self.inner.breaker.lock().unwrap() {
+            Breaker::Closed { .. } => CircuitState::Closed,
+            Breaker::Open { until } if Instant::now() < *until => CircuitState::Open,
+            Breaker::Open { .. } | Breaker::HalfOpen { .. } => CircuitState::HalfOpen,
+        };
+
+        let classes = self
+            .inner
+            .classes
+            .iter()
+            .map(|(class, state)| ClassMetrics {
+                class: class.clone(),
+                limit: state.limit,
+                running: state.running.load(Ordering::Acquire),
+                waiting: state.waiting.load(Ordering::Acquire),
+            })
+            .collect();
+
+        ResilientPoolMetrics { circuit, classes }
+    }
+}
+
+impl<DB: Database> Clone for ResilientPool<DB> {
+    fn clone(&self) -> Self {
+        ResilientPool {
+            pool: self.pool.clone(),
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<DB: Database> Debug for ResilientPool<DB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResilientPool")
+            .field("pool", &self.pool)
+            .field("metrics", &self.metrics())
+            .finish()
+    }
+}
+
+impl ResilientInner {
+    /// Waits for a permit for the class of `sql`, if it has a limit.
+    async fn permit(&self, sql: &str) -> Option<Permit<'_>> {
+        let class = self.classes.get(&(self.options.classify)(sql))?;
+
+        // the query may be cancelled while it waits, so the count is decremented on drop
+        let waiting = Waiting::new(class);
+        let releaser = class.semaphore.acquire(1).await;
+        drop(waiting);
+        class.running.fetch_add(1, Ordering::AcqRel);
+
+        Some(Permit {
+            class,
+            _releaser: releaser,
+        })
+    }
+
+    /// Checks whether a query may run, returning [`Error::CircuitOpen`] if not.
+    fn check(&self) -> Result<(), Error> {
+        let mut breaker = self.breaker.lock().unwrap();
+
+        let trial = match &*breaker {
+            Breaker::Closed { .. } => return Ok(()),
+            Breaker::Open { until } => Instant::now() >= *until,
+            Breaker::HalfOpen { since } => since.elapsed() >= self.options.open_duration,
+        };
+
+        if !trial {
+            return Err(Error::CircuitOpen);
+        }
+
+        *breaker = Breaker::HalfOpen {
+            since: Instant::now(),
+        };
+
+        Ok(())
+    }
+
+    fn record(&self, started: Instant, result: Result<(), &Error>) {
+        let failed = match result {
+            Ok(()) => false,
+            Err(e) => is_failure(e),
+        } || self
+            .options
+            .slow_threshold
+            .is_some_and(|threshold| started.elapsed() > threshold);
+
+        let options = &self.options;
+        let mut breaker = self.breaker.lock().unwrap();
+
+        match &mut *breaker {
+            Breaker::Closed {
+                window_start,
+                requests,
+                failures,
+            } => {
+                if window_start.elapsed() > options.window {
+                    *window_start = Instant::now();
+                    *requests = 0;
+                    *failures = 0;
+                }
+
+                *requests += 1;
+                *failures += failed as u32;
+
+                if *requests >= options.min_requests
+                    && f64::from(*failures) >= options.failure_rate * f64::from(*requests)
+                {
+                    tracing::warn!(
+                        requests = *requests,
+                        failures = *failures,
+                        "circuit breaker opened"
+                    );
+
+                    *breaker = Breaker::Open {
+                        until: Instant::now() + options.open_duration,
+                    };
+                }
+            }
+            Breaker::HalfOpen { .. } if failed => {
+                *breaker = Breaker::Open {
+                    until: Instant::now() + options.open_duration,
+                };
+            }
+            Breaker::HalfOpen { .. } => {
+                tracing::info!("circuit breaker closed");
+
+                *breaker = Breaker::closed();
+            }
+            // a query that started before the breaker opened
+            Breaker::Open { .. } => {}
+        }
+    }
+}
+
+impl Breaker {
+    fn closed() -> Self {
+        Breaker::Closed {
+            window_start: Instant::now(),
+            requests: 0,
+            failures: 0,
+        }
+    }
+}
+
+struct Waiting<'a> {
+    class: &'a Class,
+}
+
+impl<'a> Waiting<'a> {
+    fn new(class: &'a Class) -> Self {
+        class.waiting.fetch_add(1, Ordering::AcqRel);
+        Waiting { class }
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.class.waiting.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+struct Permit<'a> {
+    class: &'a Class,
+    _releaser: AsyncSemaphoreReleaser<'a>,
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        self.class.running.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Errors that indicate a problem with the database or the connection to it, rather than with
+/// the query.
+fn is_failure(error: &Error) -> bool {
+    matches!(
+        error,
+        Error::Io(_)
+            | Error::Tls(_)
+            | Error::Protocol(_)
+            | Error::PoolTimedOut
+            | Error::WorkerCrashed
+    )
+}
+
+pub(super) fn leading_keyword(sql: &str) -> String {
+    sql.trim_start()
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+impl<'p, DB: Database> Executor<'p> for &'_ ResilientPool<DB>
+where
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    type Database = DB;
+
+    fn fetch_many<'e, 'q: 'e, E>(
+        self,
+        query: E,
+    ) -> BoxStream<'e, Result<Either<DB::QueryResult, DB::Row>, Error>>
+    where
+        E: 'q + Execute<'q, Self::Database>,
+    {
+        let pool = self.pool.clone();
+        let inner = self.inner.clone();
+
+        Box::pin(try_stream! {
+            let _permit = inner.permit(query.sql()).await;
+            inner.check()?;
+
+            let started = Instant::now();
+            let mut conn = match pool.acquire().await {
+                Ok(conn) => conn,
+                Err(e) => {
+                    inner.record(started, Err(&e));
+                    return Err(e);
+                }
+            };
+            let mut s = conn.fetch_many(query);
+
+            loop {
+                match s.try_next().await {
+                    Ok(Some(v)) => r#yield!(v),
+                    Ok(None) => break,
+                    Err(e) => {
+                        inner.record(started, Err(&e));
+                        return Err(e);
+                    }
+                }
+            }
+
+            inner.record(started, Ok(()));
+
+            Ok(())
+        })
+    }
+
+    fn fetch_optional<'e, 'q: 'e, E>(
+        self,
+        query: E,
+    ) -> BoxFuture<'e, Result<Option<DB::Row>, Error>>
+    where
+        E: 'q + Execute<'q, Self::Database>,
+    {
+        let pool = self.pool.clone();
+        let inner = self.inner.clone();
+
+        Box::pin(async move {
+            let _permit = inner.permit(query.sql()).await;
+            inner.check()?;
+
+            let started = Instant::now();
+            let result = match pool.acquire().await {
+                Ok(mut conn) => conn.fetch_optional(query).await,
+                Err(e) => Err(e),
+            };
+
+            inner.record(started, result.as_ref().map(|_| ()));
+
+            result
+        })
+    }
+
+    fn prepare_with<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+        parameters: &'e [<Self::Database as Database>::TypeInfo],
+    ) -> BoxFuture<'e, Result<<Self::Database as HasStatement<'q>>::Statement, Error>> {
+        (&self.pool).prepare_with(sql, parameters)
+    }
+
+    #[doc(hidden)]
+    fn describe<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+    ) -> BoxFuture<'e, Result<Describe<Self::Database>, Error>> {
+        (&self.pool).describe(sql)
+    }
+}
+
+#[test]
+fn test_leading_keyword() {
+    assert_eq!(leading_keyword("SELECT * FROM users"), "select");
+    assert_eq!(leading_keyword("\n  insert into users"), "insert");
+    assert_eq!(
+        leading_keyword("WITH x AS (SELECT 1) SELECT * FROM x"),
+        "with"
+    );
+    assert_eq!(leading_keyword(""), "");
+}
@@ -0,0 +1,227 @@
+//! [`ShardedPool`]: routing queries across pools for horizontally partitioned databases.
+
+use std::borrow::Cow;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+
+use futures_util::future::{self, try_join_all};
+
+use crate::database::Database;
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::pool::Pool;
+use crate::transaction::Transaction;
+
+/// A set of pools, one per shard, that routes queries by a shard key of type `K`.
+///
+/// By default, a key is mapped to a shard by the 64-bit FNV-1a hash of its
+/// [bytes][ShardKey::key_bytes] modulo the number of shards, so the same key is always routed to
+/// the same shard, in every process, as long as the shards are given in the same order. Changing
+/// the number of shards moves most keys to another shard. To route keys another way, e.g. by
+/// ranges or a lookup table, pass a router to [`Self::with_router()`].
+///
+/// Queries are routed by the key they bind, by executing them on [`Self::shard()`] or in
+/// [`Self::transaction_on_shard()`]:
+///
+/// ```rust,ignore
+/// use sqlx::pool::ShardedPool;
+///
+/// let pool = ShardedPool::new(vec![shard_0, shard_1, shard_2]);
+///
+/// // statements without a shard key, like DDL, are broadcast to every shard
+/// pool.broadcast("CREATE TABLE IF NOT EXISTS orders (tenant BIGINT, total BIGINT)").await?;
+///
+/// sqlx::query("INSERT INTO orders VALUES ($1, $2)")
+///     .bind(tenant)
+///     .bind(total)
+///     .execute(pool.shard(&tenant))
+///     .await?;
+///
+/// let mut tx = pool.transaction_on_shard(&tenant).await?;
+/// // ...
+/// tx.commit().await?;
+/// ```
+pub struct ShardedPool<DB: Database, K: ?Sized> {
+    shards: Arc<[Pool<DB>]>,
+    router: Arc<dyn Fn(&K) -> usize + Send + Sync>,
+}
+
+/// A value that queries can be routed across the shards of a [`ShardedPool`] by.
+pub trait ShardKey {
+    /// The bytes that are hashed to pick the shard for the key.
+    ///
+    /// These must never change for a given key, or it would be routed to another shard. Integers
+    /// are widened to 64 bits and encoded as little-endian, so the same number is routed the same
+    /// regardless of its type.
+    fn key_bytes(&self) -> Cow<'_, [u8]>;
+}
+
+impl<DB: Database, K: ShardKey + ?Sized + 'static> ShardedPool<DB, K> {
+    /// Routes queries across `shards` by the hash of their key.
+    ///
+    /// # Panics
+    /// If `shards` is empty.
+    pub fn new(shards: Vec<Pool<DB>>) -> Self {
+        let len = shards.len() as u64;
+
+        Self::with_router(shards, move |key: &K| {
+            (fnv1a(&key.key_bytes()) % len) as usize
+        })
+    }
+}
+
+impl<DB: Database, K: ?Sized> ShardedPool<DB, K> {
+    /// Routes queries across `shards` by the index of the shard that `router` returns for their
+    /// key.
+    ///
+    /// The key doesn't need to implement [`ShardKey`] here, as it's only passed to `router`.
+    ///
+    /// # Panics
+    /// If `shards` is empty. Routing a key panics if `router` returns an index out of range.
+    pub fn with_router(
+        shards: Vec<Pool<DB>>,
+        router: impl Fn(&K) -> usize + Send + Sync + 'static,
+    ) -> Self {
+        assert!(!shards.is_empty(), "a ShardedPool needs at least one shard");
+
+        ShardedPool {
+            shards: shards.into(),
+            router: Arc::new(router),
+        }
+    }
+
+    /// The pools of every shard, in order.
+    pub fn shards(&self) -> &[Pool<DB>] {
+        &self.shards
+    }
+
+    /// The index of the shard that `key` is routed to.
+    pub fn shard_index(&self, key: &K) -> usize {
+        let index = (self.router)(key);
+
+        assert!(
+            index < self.shards.len(),
+            "the router of a ShardedPool returned shard {index}, but there are {}",
+            self.shards.len()
+        );
+
+        index
+    }
+
+    /// The pool of the shard that `key` is routed to.
+    pub fn shard(&self, key: &K) -> &Pool<DB> {
+        &self.shards[self.shard_index(key)]
+    }
+
+    /// Begins a transaction on the shard that `key` is routed to.
+    pub async fn transaction_on_shard(&self, key: &K) -> Result<Transaction<'static, DB>, Error> {
+        self.shard(key).begin().await
+    }
+
+    /// Executes `sql` on every shard concurrently, e.g. for DDL, returning the results in the
+    /// order of the shards.
+    ///
+    /// This is not atomic: if it fails on some shards, it may still have succeeded on others, so
+    /// statements should be safe to run again, e.g. `CREATE TABLE IF NOT EXISTS`.
+    pub async fn broadcast(&self, sql: &str) -> Result<Vec<DB::QueryResult>, Error>
+    where
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    {
+        try_join_all(self.shards.iter().map(|pool| pool.execute(sql))).await
+    }
+
+    /// Closes the pools of every shard.
+    pub async fn close(&self) {
+        future::join_all(self.shards.iter().map(|pool| pool.close())).await;
+    }
+}
+
+impl<DB: Database, K: ?Sized> Clone for ShardedPool<DB, K> {
+    fn clone(&self) -> Self {
+        ShardedPool {
+            shards: self.shards.clone(),
+            router: self.router.clone(),
+        }
+    }
+}
+
+impl<DB: Database, K: ?Sized> Debug for ShardedPool<DB, K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ShardedPool")
+            .field("shards", &self.shards)
+            .finish_non_exhaustive()
+    }
+}
+
+macro_rules! impl_shard_key_for_int {
+    ($($ty:ty => $wide:ty),*) => {
+        $(
+            impl ShardKey for $ty {
+                fn key_bytes(&self) -> Cow<'_, [u8]> {
+                    Cow::Owned(<$wide>::from(*self).to_le_bytes().to_vec())
+                }
+            }
+        )*
+    };
+}
+
+impl_shard_key_for_int!(
+    i8 => i64, i16 => i64, i32 => i64, i64 => i64,
+    u8 => u64, u16 => u64, u32 => u64, u64 => u64
+);
+
+impl ShardKey for str {
+    fn key_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+}
+
+impl ShardKey for String {
+    fn key_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+}
+
+impl ShardKey for [u8] {
+    fn key_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl ShardKey for Vec<u8> {
+    fn key_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl ShardKey for uuid::Uuid {
+    fn key_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+}
+
+impl<T: ShardKey + ?Sized> ShardKey for &T {
+    fn key_bytes(&self) -> Cow<'_, [u8]> {
+        (**self).key_bytes()
+    }
+}
+
+/// 64-bit FNV-1a.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[test]
+fn test_fnv1a() {
+    // from the reference test vectors; must never change, or keys would move to other shards
+    assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+
+    assert_eq!(1i32.key_bytes(), 1i64.key_bytes());
+    assert_eq!(1u8.key_bytes(), 1u64.key_bytes());
+    assert_eq!("tenant-1".key_bytes(), String::from("tenant-1").key_bytes());
+}
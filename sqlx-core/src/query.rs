@@ -1,10 +1,13 @@
+use std::cmp;
 use std::marker::PhantomData;
 
 use either::Either;
 use futures_core::stream::BoxStream;
 use futures_util::{future, StreamExt, TryFutureExt, TryStreamExt};
 
+use crate::acquire::Acquire;
 use crate::arguments::{Arguments, IntoArguments};
+use crate::connection::Connection;
 use crate::database::{Database, HasArguments, HasStatement, HasStatementCache};
 use crate::encode::Encode;
 use crate::error::Error;
@@ -19,6 +22,10 @@ pub struct Query<'q, DB: Database, A> {
     pub(crate) arguments: Option<A>,
     pub(crate) database: PhantomData<DB>,
     pub(crate) persistent: bool,
+    pub(crate) fetch_size: Option<u32>,
+    pub(crate) size_limited: bool,
+    /// Whether values were bound to the query, or arguments were given with it.
+    pub(crate) bound: bool,
 }
 
 /// SQL query that will map its results to owned Rust types.
@@ -65,6 +72,16 @@ where
     fn persistent(&self) -> bool {
         self.persistent
     }
+
+    #[inline]
+    fn fetch_size(&self) -> Option<u32> {
+        self.fetch_size
+    }
+
+    #[inline]
+    fn size_limited(&self) -> bool {
+        self.size_limited
+    }
 }
 
 impl<'q, DB: Database> Query<'q, DB, <DB as HasArguments<'q>>::Arguments> {
@@ -81,6 +98,7 @@ impl<'q, DB: Database> Query<'q, DB, <DB as HasArguments<'q>>::Arguments> {
             arguments.add(value);
         }
 
+        self.bound = true;
         self
     }
 }
@@ -103,6 +121,41 @@ where
     }
 }
 
+impl<'q, DB: Database, A> Query<'q, DB, A> {
+    /// Fetch the rows of the query from the database `rows` at a time, as they're consumed.
+    ///
+    /// By default, the database sends every row of the result as fast as the connection allows,
+    /// so a stream from [`fetch()`][Self::fetch] that is consumed slowly leaves the rest of the
+    /// result buffered in the socket or driver. With a fetch size, the driver asks for the next
+    /// batch only once the stream gets to it, which bounds the memory used by a large result at
+    /// the cost of a round-trip per batch:
+    ///
+    /// * Postgres executes the query in a portal, with `rows` as the row limit of each
+    ///   `Execute` message.
+    /// * SQLite buffers up to `rows` rows between the worker thread and the stream, instead of
+    ///   [`SqliteConnectOptions::row_buffer_size()`][crate::sqlite::SqliteConnectOptions].
+    /// * MySQL and `Any` connections ignore it, as the MySQL protocol only fetches in batches
+    ///   with server-side cursors.
+    ///
+    /// A size of `0` is treated as `1`.
+    pub fn fetch_size(mut self, rows: u32) -> Self {
+        self.fetch_size = Some(cmp::max(rows, 1));
+        self
+    }
+
+    /// Don't apply the row and result size limits of the connection to this query, e.g. for
+    /// a query that's known to return large values, on a connection that otherwise protects
+    /// against them.
+    ///
+    /// The limits are set with `max_row_size()` and `max_result_size()` on
+    /// [`PgConnectOptions`][crate::postgres::PgConnectOptions] and
+    /// [`MySqlConnectOptions`][crate::mysql::MySqlConnectOptions].
+    pub fn without_size_limits(mut self) -> Self {
+        self.size_limited = false;
+        self
+    }
+}
+
 impl<'q, DB, A: Send> Query<'q, DB, A>
 where
     DB: Database,
@@ -167,6 +220,56 @@ where
         executor.execute_many(self)
     }
 
+    /// Execute the query once for each set of arguments in `arguments`, in a transaction, and
+    /// return the total number of rows affected.
+    ///
+    /// The statement is prepared once and cached on the connection, regardless of
+    /// [`persistent`](Query::persistent), then executed with each set of arguments; this is much
+    /// faster than executing the query in a loop outside of a transaction. The sets of arguments
+    /// are taken from the iterator as they're executed, so they don't all have to be in memory.
+    /// Postgres pipelines the executions, sending a window of them before reading their results.
+    /// If any execution fails, the transaction is rolled back and none of the changes are kept.
+    ///
+    /// Each set of arguments may be a tuple of values, or the arguments of the database. Values
+    /// can't also be bound to the query itself; that fails with [`Error::Configuration`].
+    ///
+    /// ```rust,ignore
+    /// let result = sqlx::query("INSERT INTO users (id, name) VALUES ($1, $2)")
+    ///     .execute_many_args(&pool, users.iter().map(|u| (u.id, &u.name)))
+    ///     .await?;
+    ///
+    /// assert_eq!(result.rows_affected(), users.len() as u64);
+    /// ```
+    pub async fn execute_many_args<'c, C, I>(
+        self,
+        conn: C,
+        arguments: I,
+    ) -> Result<DB::QueryResult, Error>
+    where
+        C: Acquire<'c, Database = DB>,
+        I: IntoIterator,
+        I::IntoIter: Send,
+        I::Item: 'q + IntoArguments<'q, DB>,
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+        for<'a> <DB as HasArguments<'a>>::Arguments: IntoArguments<'a, DB>,
+    {
+        if self.bound {
+            return Err(Error::Configuration(
+                "values can't be bound to a query executed with `execute_many_args()`; \
+                 pass them with the sets of arguments instead"
+                    .into(),
+            ));
+        }
+
+        let arguments = arguments.into_iter().map(IntoArguments::into_arguments);
+
+        let mut tx = conn.begin().await?;
+        let result = tx.execute_batch(self.sql(), Box::new(arguments)).await?;
+        tx.commit().await?;
+
+        Ok(result)
+    }
+
     /// Execute the query and return the generated results as a stream.
     #[inline]
     pub fn fetch<'e, 'c: 'e, E>(self, executor: E) -> BoxStream<'e, Result<DB::Row, Error>>
@@ -251,6 +354,34 @@ where
     fn persistent(&self) -> bool {
         self.inner.arguments.is_some()
     }
+
+    #[inline]
+    fn fetch_size(&self) -> Option<u32> {
+        self.inner.fetch_size
+    }
+
+    #[inline]
+    fn size_limited(&self) -> bool {
+        self.inner.size_limited
+    }
+}
+
+impl<'q, DB: Database, F, A> Map<'q, DB, F, A> {
+    /// Fetch the rows of the query from the database `rows` at a time, as they're consumed.
+    ///
+    /// See [`Query::fetch_size`](Query::fetch_size).
+    pub fn fetch_size(mut self, rows: u32) -> Self {
+        self.inner = self.inner.fetch_size(rows);
+        self
+    }
+
+    /// Don't apply the row and result size limits of the connection to this query.
+    ///
+    /// See [`Query::without_size_limits`](Query::without_size_limits).
+    pub fn without_size_limits(mut self) -> Self {
+        self.inner = self.inner.without_size_limits();
+        self
+    }
 }
 
 impl<'q, DB, F, O, A> Map<'q, DB, F, A>
@@ -406,6 +537,9 @@ where
         arguments: Some(Default::default()),
         statement: Either::Right(statement),
         persistent: true,
+        fetch_size: None,
+        size_limited: true,
+        bound: false,
     }
 }
 
@@ -423,6 +557,9 @@ where
         arguments: Some(arguments),
         statement: Either::Right(statement),
         persistent: true,
+        fetch_size: None,
+        size_limited: true,
+        bound: true,
     }
 }
 
@@ -436,6 +573,9 @@ where
         arguments: Some(Default::default()),
         statement: Either::Left(sql),
         persistent: true,
+        fetch_size: None,
+        size_limited: true,
+        bound: false,
     }
 }
 
@@ -450,5 +590,8 @@ where
         arguments: Some(arguments),
         statement: Either::Left(sql),
         persistent: true,
+        fetch_size: None,
+        size_limited: true,
+        bound: true,
     }
 }
@@ -45,6 +45,16 @@ where
     fn persistent(&self) -> bool {
         self.inner.persistent()
     }
+
+    #[inline]
+    fn fetch_size(&self) -> Option<u32> {
+        Execute::fetch_size(&self.inner)
+    }
+
+    #[inline]
+    fn size_limited(&self) -> bool {
+        self.inner.size_limited()
+    }
 }
 
 impl<'q, DB: Database, O> QueryAs<'q, DB, O, <DB as HasArguments<'q>>::Arguments> {
@@ -75,6 +85,24 @@ where
     }
 }
 
+impl<'q, DB: Database, O, A> QueryAs<'q, DB, O, A> {
+    /// Fetch the rows of the query from the database `rows` at a time, as they're consumed.
+    ///
+    /// See [`Query::fetch_size`](Query::fetch_size).
+    pub fn fetch_size(mut self, rows: u32) -> Self {
+        self.inner = self.inner.fetch_size(rows);
+        self
+    }
+
+    /// Don't apply the row and result size limits of the connection to this query.
+    ///
+    /// See [`Query::without_size_limits`](Query::without_size_limits).
+    pub fn without_size_limits(mut self) -> Self {
+        self.inner = self.inner.without_size_limits();
+        self
+    }
+}
+
 // FIXME: This is very close, nearly 1:1 with `Map`
 // noinspection DuplicatedCode
 impl<'q, DB, O, A> QueryAs<'q, DB, O, A>
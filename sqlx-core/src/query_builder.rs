@@ -4,11 +4,15 @@ use std::fmt::Display;
 use std::fmt::Write;
 use std::marker::PhantomData;
 
-use crate::arguments::{Arguments, IntoArguments};
+use crate::acquire::Acquire;
+use crate::arguments::{shorten, Arguments, IntoArguments};
+use crate::connection::{Connection, ReturningStatement, ServerFlavor};
 use crate::database::{Database, HasArguments};
 use crate::encode::Encode;
+use crate::error::Error;
+use crate::executor::Executor;
 use crate::from_row::FromRow;
-use crate::query::Query;
+use crate::query::{query_with, Query};
 use crate::query_as::QueryAs;
 use crate::query_scalar::QueryScalar;
 use crate::types::Type;
@@ -28,6 +32,10 @@ where
     query: String,
     init_len: usize,
     arguments: Option<<DB as HasArguments<'args>>::Arguments>,
+    // the number of values added with `.push_bind()`
+    bind_count: usize,
+    // whether the builder was created with `with_arguments()`, whose values aren't counted
+    has_initial_arguments: bool,
 }
 
 impl<'args, DB: Database> Default for QueryBuilder<'args, DB> {
@@ -36,6 +44,8 @@ impl<'args, DB: Database> Default for QueryBuilder<'args, DB> {
             init_len: 0,
             query: String::default(),
             arguments: Some(Default::default()),
+            bind_count: 0,
+            has_initial_arguments: false,
         }
     }
 }
@@ -57,6 +67,8 @@ where
             init_len: init.len(),
             query: init,
             arguments: Some(Default::default()),
+            bind_count: 0,
+            has_initial_arguments: false,
         }
     }
 
@@ -75,6 +87,8 @@ where
             init_len: init.len(),
             query: init,
             arguments: Some(arguments.into_arguments()),
+            bind_count: 0,
+            has_initial_arguments: true,
         }
     }
 
@@ -86,6 +100,14 @@ where
         );
     }
 
+    // the arguments can't be used with a borrow of `self.query` shorter than `'args` otherwise
+    fn take_arguments<'q>(&mut self) -> Result<<DB as HasArguments<'q>>::Arguments, Error>
+    where
+        'args: 'q,
+    {
+        shorten::<DB>(self.arguments.take().expect("BUG: Arguments taken already"))
+    }
+
     /// Append a SQL fragment to the query.
     ///
     /// May be a string or anything that implements `Display`.
@@ -121,6 +143,28 @@ where
         self
     }
 
+    /// Push a condition excluding soft-deleted rows of `table`, i.e. `<table>.<column> IS NULL`.
+    ///
+    /// `table` may also be an alias. Like [`.push()`][Self::push], neither is sanitized.
+    ///
+    /// The query macros can warn about queries missing this filter; see the
+    /// `[soft-delete]` section of `sqlx.toml` in the docs for `query!()`. With the `macros`
+    /// feature, `sqlx::soft_delete_column!()` expands to the column configured there.
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "mysql")] {
+    /// use sqlx::{Execute, MySql, QueryBuilder};
+    ///
+    /// let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("SELECT * FROM users u WHERE ");
+    /// query_builder.push_not_deleted("u", "deleted_at");
+    ///
+    /// assert_eq!(query_builder.sql(), "SELECT * FROM users u WHERE u.deleted_at IS NULL");
+    /// # }
+    /// ```
+    pub fn push_not_deleted(&mut self, table: impl Display, column: impl Display) -> &mut Self {
+        self.push(format_args!("{table}.{column} IS NULL"))
+    }
+
     /// Push a bind argument placeholder (`?` or `$N` for Postgres) and bind a value to it.
     ///
     /// ### Note: Database-specific Limits
@@ -161,6 +205,42 @@ where
             .format_placeholder(&mut self.query)
             .expect("error in format_placeholder");
 
+        self.bind_count += 1;
+
+        self
+    }
+
+    /// If `value` is `Some`, push `sql` followed by a bind argument for the value; otherwise
+    /// push nothing.
+    ///
+    /// This makes optional filters simple, though they aren't checked at compile time;
+    /// `query_filtered!()` checks each combination of them instead.
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "mysql")] {
+    /// use sqlx::{MySql, QueryBuilder};
+    ///
+    /// let name: Option<&str> = None;
+    /// let min_id: Option<i64> = Some(10);
+    ///
+    /// let mut query_builder: QueryBuilder<MySql> =
+    ///     QueryBuilder::new("SELECT * FROM accounts WHERE is_active");
+    ///
+    /// query_builder
+    ///     .push_maybe_bind(" AND name = ", name)
+    ///     .push_maybe_bind(" AND id >= ", min_id);
+    ///
+    /// assert_eq!(query_builder.sql(), "SELECT * FROM accounts WHERE is_active AND id >= ?");
+    /// # }
+    /// ```
+    pub fn push_maybe_bind<T>(&mut self, sql: impl Display, value: Option<T>) -> &mut Self
+    where
+        T: 'args + Encode<'args, DB> + Send + Type<DB>,
+    {
+        if let Some(value) = value {
+            self.push(sql).push_bind(value);
+        }
+
         self
     }
 
@@ -216,7 +296,9 @@ where
     ///
     /// To be safe, you can do `tuples.into_iter().take(N)` where `N` is the limit for your database
     /// divided by the number of fields in each tuple; since integer division always rounds down,
-    /// this will ensure that you don't exceed the limit.
+    /// this will ensure that you don't exceed the limit. Alternatively,
+    /// [`.execute_values_chunked()`][Self::execute_values_chunked] will split `tuples` across
+    /// as many statements as necessary.
     ///
     /// ### Notes
     ///
@@ -326,6 +408,329 @@ where
         separated.query_builder
     }
 
+    /// Execute a statement with a `VALUES` clause for all of `tuples`, automatically splitting it
+    /// into multiple statements to stay under [`DB::BIND_LIMIT`][Database::BIND_LIMIT].
+    ///
+    /// Each statement consists of the SQL pushed to this builder so far followed by a `VALUES`
+    /// clause generated exactly like [`.push_values()`][Self::push_values]. The number of tuples
+    /// per statement is determined by the number of values bound for the first tuple, so
+    /// `push_tuple` must bind the same number of values for every tuple, e.g. not with
+    /// [`Separated::push_maybe_bind()`], as a tuple that binds more could make a statement exceed
+    /// the limit. Otherwise, [`Error::Configuration`] is returned and nothing is inserted.
+    ///
+    /// All statements are executed in a single transaction (or savepoint, if `conn` is already in
+    /// a transaction) so either all of `tuples` are inserted or none of them are. The results of
+    /// the statements are combined into the returned `QueryResult`.
+    ///
+    /// If `tuples` is empty, nothing is executed.
+    ///
+    /// Only a trailing `VALUES` clause is split. A list of values for `IN (...)` can't be split
+    /// across statements without changing what the query means, so for large lists, bind an
+    /// array instead where the database supports it, e.g. `= ANY($1)` in Postgres.
+    ///
+    /// ### Note: Bind Arguments
+    /// The SQL preceding the `VALUES` clause is repeated for every statement, but bind arguments
+    /// cannot be duplicated, so this builder must not have any arguments when this is called,
+    /// nor have been created with [`with_arguments()`][Self::with_arguments]; an error is
+    /// returned otherwise. It is left unchanged afterwards.
+    ///
+    /// Because each statement is built separately, values bound in `push_tuple` must be owned
+    /// rather than borrowed.
+    ///
+    /// ### Example (SQLite)
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::SqliteConnection) -> sqlx::Result<()> {
+    /// use sqlx::{QueryBuilder, Sqlite};
+    ///
+    /// let users = (0..100_000).map(|i| (i, format!("test_user_{i}")));
+    ///
+    /// // 100,000 rows * 2 parameters is well over SQLite's limit of 32,766 parameters
+    /// let result = QueryBuilder::<Sqlite>::new("INSERT INTO users(id, username) ")
+    ///     .execute_values_chunked(conn, users, |mut b, (id, username)| {
+    ///         b.push_bind(id).push_bind(username);
+    ///     })
+    ///     .await?;
+    ///
+    /// assert_eq!(result.rows_affected(), 100_000);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_values_chunked<'c, A, I, F>(
+        &self,
+        conn: A,
+        tuples: I,
+        mut push_tuple: F,
+    ) -> Result<DB::QueryResult, Error>
+    where
+        A: Acquire<'c, Database = DB>,
+        I: IntoIterator,
+        F: for<'qb> FnMut(Separated<'_, 'qb, DB, &'static str>, I::Item),
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    {
+        self.sanity_check();
+
+        // the initial arguments of `with_arguments()` may bind any number of values, which
+        // would also throw off the number of tuples per statement
+        if self.bind_count > 0 || self.has_initial_arguments {
+            return Err(Error::Configuration(
+                "QueryBuilder::execute_values_chunked() cannot repeat bind arguments \
+                 pushed before the `VALUES` clause or passed to `with_arguments()`"
+                    .into(),
+            ));
+        }
+
+        let mut tuples = tuples.into_iter().peekable();
+        let mut result = DB::QueryResult::default();
+
+        if tuples.peek().is_none() {
+            return Ok(result);
+        }
+
+        let mut tx = conn.begin().await?;
+
+        // set by the first tuple, which the others must match
+        let mut binds_per_tuple = None;
+
+        while tuples.peek().is_some() {
+            // `chunk` is taken apart so that the query only has to borrow the SQL string;
+            // the arguments may not outlive a borrow of the builder that owns them
+            let (query, arguments) = {
+                let mut chunk = QueryBuilder::<DB>::new(&self.query);
+
+                chunk.push("VALUES ");
+
+                let mut separated = chunk.separated(", ");
+
+                loop {
+                    let bind_count = separated.query_builder.bind_count;
+
+                    // always push at least one tuple, otherwise we'd never make progress
+                    if bind_count > 0 && bind_count + binds_per_tuple.unwrap_or(0) > DB::BIND_LIMIT
+                    {
+                        break;
+                    }
+
+                    let tuple = match tuples.next() {
+                        Some(tuple) => tuple,
+                        None => break,
+                    };
+
+                    separated.push("(");
+
+                    push_tuple(separated.query_builder.separated(", "), tuple);
+
+                    separated.push_unseparated(")");
+
+                    let binds = separated.query_builder.bind_count - bind_count;
+
+                    match binds_per_tuple {
+                        None => binds_per_tuple = Some(binds),
+                        // dropping `tx` rolls back the statements executed so far
+                        Some(expected) if binds != expected => {
+                            return Err(Error::Configuration(
+                                format!(
+                                    "every tuple passed to QueryBuilder::execute_values_chunked() \
+                                     must bind the same number of values; expected {expected}, \
+                                     got {binds}"
+                                )
+                                .into(),
+                            ));
+                        }
+                        Some(_) => (),
+                    }
+                }
+
+                (chunk.query, chunk.arguments)
+            };
+            let arguments = arguments.expect("BUG: Arguments taken already");
+
+            result.extend([query_with(&query, arguments).execute(&mut *tx).await?]);
+        }
+
+        tx.commit().await?;
+
+        Ok(result)
+    }
+
+    /// Execute the `INSERT`, `UPDATE` or `DELETE` statement built so far and return the values of
+    /// `columns` for the rows it affected, e.g. keys generated by the database.
+    ///
+    /// If the server [supports `RETURNING`][crate::connection::ServerVersion::supports_returning] for the statement,
+    /// a `RETURNING` clause for `columns` is pushed to this builder. Otherwise, an `INSERT` on
+    /// MySQL (or MariaDB before 10.5) is executed as is, and the inserted rows are selected again
+    /// in the same transaction by their `AUTO_INCREMENT` keys:
+    ///
+    /// * the first of `columns` must be the `AUTO_INCREMENT` column of the table, and its values
+    ///   must be generated by the statement rather than bound;
+    /// * MySQL only reports the first key it generated, so the keys are assumed to follow it
+    ///   `auto_increment_increment` apart. That only holds for a plain `INSERT` of one row, or
+    ///   of many rows with `innodb_autoinc_lock_mode` below `2` (the default of MySQL 8 is `2`),
+    ///   so the statement is rolled back and [`Error::Configuration`] is returned if it inserted
+    ///   more than one row otherwise, with `INSERT IGNORE`, or on TiDB;
+    /// * `INSERT .. ON DUPLICATE KEY UPDATE` returns [`Error::Configuration`], as it doesn't
+    ///   generate a key for the rows it updates.
+    ///
+    /// Any other statement returns [`Error::Configuration`] if `RETURNING` isn't supported.
+    ///
+    /// The syntax is chosen by the [server version][Connection::server_version] of the
+    /// connection, so this also works with the `Any` driver.
+    ///
+    /// Like [`.build()`][Self::build], this takes the arguments of the builder, so it must be
+    /// [reset][Self::reset] before it's reused.
+    ///
+    /// ### Example (MySQL)
+    ///
+    /// ```rust,no_run
+    /// # async fn example(conn: &mut sqlx::MySqlConnection) -> sqlx::Result<()> {
+    /// use sqlx::{MySql, QueryBuilder, Row};
+    ///
+    /// let rows = QueryBuilder::<MySql>::new("INSERT INTO users(username) ")
+    ///     .push_values(["alice", "bob"], |mut b, username| {
+    ///         b.push_bind(username);
+    ///     })
+    ///     .fetch_returning(conn, &["id", "created_at"])
+    ///     .await?;
+    ///
+    /// let ids: Vec<u64> = rows.iter().map(|row| row.get("id")).collect();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_returning<'c, A>(
+        &mut self,
+        conn: A,
+        columns: &[&str],
+    ) -> Result<Vec<DB::Row>, Error>
+    where
+        A: Acquire<'c, Database = DB>,
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    {
+        self.sanity_check();
+
+        let mut conn = conn.acquire().await?;
+        let version = conn.server_version();
+
+        let mut words = self.query.split_whitespace();
+        let command = words.next().unwrap_or_default();
+
+        let statement = match command.to_ascii_uppercase().as_str() {
+            "INSERT" => Some(ReturningStatement::Insert),
+            "UPDATE" => Some(ReturningStatement::Update),
+            "DELETE" => Some(ReturningStatement::Delete),
+            _ => None,
+        };
+
+        let supported = match statement {
+            Some(statement) => version.supports_returning(statement),
+            // e.g. a `WITH` clause, which may precede any of them
+            None => [
+                ReturningStatement::Insert,
+                ReturningStatement::Update,
+                ReturningStatement::Delete,
+            ]
+            .into_iter()
+            .all(|statement| version.supports_returning(statement)),
+        };
+
+        if supported {
+            self.push(" RETURNING ");
+            self.push(columns.join(", "));
+
+            let arguments = self.take_arguments()?;
+
+            return query_with(&self.query, arguments)
+                .fetch_all(&mut *conn)
+                .await;
+        }
+
+        let is_mysql = matches!(
+            version.flavor,
+            ServerFlavor::MySql | ServerFlavor::MariaDb | ServerFlavor::TiDb
+        );
+
+        // `INSERT [IGNORE] INTO table[(columns)] ..`
+        let table = words
+            .skip_while(|word| !word.eq_ignore_ascii_case("INTO"))
+            .nth(1)
+            .and_then(|table| table.split('(').next())
+            .filter(|table| !table.is_empty());
+
+        let (key, table) = match (columns.first(), table) {
+            (Some(key), Some(table)) if is_mysql && command.eq_ignore_ascii_case("INSERT") => {
+                (key, table)
+            }
+            _ => {
+                return Err(Error::Configuration(
+                    format!("`RETURNING` is not supported by {version} for this statement").into(),
+                ))
+            }
+        };
+
+        let words: Vec<String> = self
+            .query
+            .split_whitespace()
+            .map(|word| word.to_ascii_uppercase())
+            .collect();
+
+        // updated rows keep their keys, and `ROW_COUNT()` counts them twice
+        if words.windows(2).any(|pair| pair == ["ON", "DUPLICATE"]) {
+            return Err(Error::Configuration(
+                format!("`RETURNING` is not supported by {version} for `ON DUPLICATE KEY`").into(),
+            ));
+        }
+
+        // the keys of a multi-row insert are only contiguous if the server reserves them all up
+        // front: not for ignored rows, with "interleaved" locking, or on TiDB
+        let contiguous = if version.flavor == ServerFlavor::TiDb
+            || words.get(1).map(String::as_str) == Some("IGNORE")
+        {
+            "FALSE"
+        } else {
+            "@@innodb_autoinc_lock_mode < 2"
+        };
+
+        // the keys are `auto_increment_increment` apart, which is usually but not always 1
+        let select = format!(
+            "SELECT {} FROM {table} WHERE {key} >= LAST_INSERT_ID() \
+             AND {key} < LAST_INSERT_ID() + @sqlx_returning_rows * @@auto_increment_increment \
+             AND ({key} - LAST_INSERT_ID()) % @@auto_increment_increment = 0 ORDER BY {key}",
+            columns.join(", ")
+        );
+
+        let arguments = self.take_arguments()?;
+        let mut tx = conn.begin().await?;
+
+        query_with(&self.query, arguments).execute(&mut *tx).await?;
+
+        // `ROW_COUNT()` is reset by the next statement
+        tx.execute("SET @sqlx_returning_rows = ROW_COUNT()").await?;
+
+        let check = tx
+            .fetch_optional(&*format!(
+                "SELECT 1 FROM DUAL WHERE @sqlx_returning_rows <= 1 OR {contiguous}"
+            ))
+            .await?;
+
+        if check.is_none() {
+            // dropping `tx` rolls the insert back
+            return Err(Error::Configuration(
+                format!(
+                    "`RETURNING` is not supported by {version}, and the keys of this insert \
+                     may not be contiguous to select its rows again; insert one row at a time"
+                )
+                .into(),
+            ));
+        }
+
+        let rows = tx.fetch_all(&*select).await?;
+
+        tx.commit().await?;
+
+        Ok(rows)
+    }
+
     /// Creates `((a, b), (..)` statements, from `tuples`.
     ///
     /// This can be used to construct a bulk `SELECT` statement like this:
@@ -453,6 +858,9 @@ where
             arguments: self.arguments.take(),
             database: PhantomData,
             persistent: true,
+            fetch_size: None,
+            size_limited: true,
+            bound: true,
         }
     }
 
@@ -508,6 +916,7 @@ where
     pub fn reset(&mut self) -> &mut Self {
         self.query.truncate(self.init_len);
         self.arguments = Some(Default::default());
+        self.bind_count = 0;
 
         self
     }
@@ -581,6 +990,39 @@ where
         self
     }
 
+    /// If `value` is `Some`, push the separator if applicable, then `sql` and a bind argument
+    /// for the value; otherwise push nothing.
+    ///
+    /// With `" AND "` as the separator, this joins the filters that are given:
+    ///
+    /// ```rust
+    /// # #[cfg(feature = "mysql")] {
+    /// use sqlx::{MySql, QueryBuilder};
+    ///
+    /// let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("SELECT * FROM accounts WHERE ");
+    ///
+    /// let mut filters = query_builder.separated(" AND ");
+    /// filters.push("is_active");
+    /// filters.push_maybe_bind("name = ", None::<&str>);
+    /// filters.push_maybe_bind("id >= ", Some(10i64));
+    ///
+    /// assert_eq!(query_builder.sql(), "SELECT * FROM accounts WHERE is_active AND id >= ?");
+    /// # }
+    /// ```
+    ///
+    /// See [`QueryBuilder::push_maybe_bind()`] for details.
+    pub fn push_maybe_bind<T>(&mut self, sql: impl Display, value: Option<T>) -> &mut Self
+    where
+        T: 'args + Encode<'args, DB> + Send + Type<DB>,
+    {
+        if let Some(value) = value {
+            self.push(sql);
+            self.query_builder.push_bind(value);
+        }
+
+        self
+    }
+
     /// Push a bind argument placeholder (`?` or `$N` for Postgres) and bind a value to it
     /// without a separator.
     ///
@@ -642,6 +1084,28 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_push_maybe_bind() {
+        let mut qb: QueryBuilder<'_, Postgres> =
+            QueryBuilder::new("SELECT * FROM users WHERE id > ");
+
+        qb.push_bind(0i32)
+            .push_maybe_bind(" AND name = ", None::<&str>)
+            .push_maybe_bind(" AND membership_level = ", Some(3i32));
+
+        let mut filters = qb.separated(" OR ");
+        filters.push_unseparated(" AND (");
+        filters.push_maybe_bind("age < ", Some(18i32));
+        filters.push_maybe_bind("age > ", None::<i32>);
+        filters.push_maybe_bind("age > ", Some(65i32));
+        filters.push_unseparated(")");
+
+        assert_eq!(
+            qb.query,
+            "SELECT * FROM users WHERE id > $1 AND membership_level = $2 AND (age < $3 OR age > $4)"
+        );
+    }
+
     #[test]
     fn test_build() {
         let mut qb: QueryBuilder<'_, Postgres> = QueryBuilder::new("SELECT * FROM users");
@@ -0,0 +1,147 @@
+//! How the query macros find the offline data for a query, written to `.sqlx` by
+//! `cargo sqlx prepare`.
+//!
+//! The data for a query is stored in a file named after the SHA-256 hash of its SQL, exactly as
+//! written in the macro invocation (or, for `query_file!()` and friends, as read from the file),
+//! so a change to the query that doesn't change its meaning, e.g. to whitespace, still changes
+//! the file name. The one exception is line endings: the hash is of the SQL with `\r\n` replaced
+//! by `\n`, as is the query saved in the file, so a checkout of query files with `\r\n` line
+//! endings, as made by Git on Windows, finds and writes the same data as other checkouts. The
+//! query itself is still described and executed exactly as written.
+
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// The lowercase hex-encoded SHA-256 hash of `sql`, which identifies its offline data.
+pub fn hash_query(sql: &str) -> String {
+    hex::encode(Sha256::digest(normalize_line_endings(sql).as_bytes()))
+}
+
+/// The name of the file in `.sqlx` holding the offline data for `sql`, i.e. `query-<hash>.json`.
+pub fn data_file_name(sql: &str) -> String {
+    format!("query-{}.json", hash_query(sql))
+}
+
+/// The name of the file in the temporary directory of `cargo sqlx prepare` (`SQLX_TMP`) that the
+/// query macros add the hash of a query to, one per line, each time another invocation in the
+/// same crate uses it, so `prepare` can report duplicate queries.
+pub const DUPLICATES_FILE_NAME: &str = "duplicate-queries";
+
+/// Find the `sqlx.toml` that applies to `dir`: the one in `dir` or else the nearest of its
+/// ancestors, so a file at the root of a workspace applies to all of its members.
+///
+/// The query macros search from the directory of the crate being compiled and the CLI from the
+/// current directory, so both find the same file when the CLI is run in that crate or the root
+/// of its workspace.
+pub fn find_config_file(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join("sqlx.toml"))
+        .find(|path| path.is_file())
+}
+
+/// The layout of query data files, set with `json-format` under `[prepare]` in `sqlx.toml`.
+///
+/// Both `cargo sqlx prepare` and the query macros write files in this format.
+#[derive(serde::Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum JsonFormat {
+    #[default]
+    Pretty,
+    Compact,
+}
+
+impl JsonFormat {
+    /// Serialize query data in this format, followed by a newline.
+    ///
+    /// The keys of every object are sorted so the output doesn't depend on the field order of
+    /// the driver's types, which may change between versions.
+    pub fn to_vec(self, value: serde_json::Value) -> serde_json::Result<Vec<u8>> {
+        let value = canonicalize(value);
+
+        let mut contents = match self {
+            JsonFormat::Pretty => serde_json::to_vec_pretty(&value)?,
+            JsonFormat::Compact => serde_json::to_vec(&value)?,
+        };
+
+        // avoid accidental modification by editors, and make diffs nicer
+        contents.push(b'\n');
+
+        Ok(contents)
+    }
+}
+
+/// Rebuild `value` with the keys of every object in sorted order.
+fn canonicalize(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    match value {
+        Value::Object(object) => {
+            let mut entries: Vec<_> = object.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonicalize(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(canonicalize).collect()),
+        value => value,
+    }
+}
+
+/// Replace `\r\n` line endings in `sql` with `\n`, as it's hashed and saved in query data.
+pub fn normalize_line_endings(sql: &str) -> Cow<'_, str> {
+    if sql.contains("\r\n") {
+        Cow::Owned(sql.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(sql)
+    }
+}
+
+#[test]
+fn test_data_file_name() {
+    assert_eq!(
+        data_file_name("SELECT 1"),
+        "query-e004ebd5b5532a4b85984a62f8ad48a81aa3460c1ca07701f386135d72cdecf5.json"
+    );
+
+    // whitespace is significant
+    assert_ne!(hash_query("SELECT 1"), hash_query("SELECT  1"));
+}
+
+#[test]
+fn test_find_config_file() -> std::io::Result<()> {
+    let dir = std::env::temp_dir().join(format!("sqlx-find-config-{}", std::process::id()));
+    let member = dir.join("member");
+    std::fs::create_dir_all(member.join("src"))?;
+
+    std::fs::write(dir.join("sqlx.toml"), "")?;
+    let workspace_config = find_config_file(&member.join("src"));
+
+    std::fs::write(member.join("sqlx.toml"), "")?;
+    let member_config = find_config_file(&member.join("src"));
+
+    std::fs::remove_dir_all(&dir)?;
+
+    assert_eq!(workspace_config, Some(dir.join("sqlx.toml")));
+    assert_eq!(member_config, Some(member.join("sqlx.toml")));
+
+    Ok(())
+}
+
+#[test]
+fn test_normalize_line_endings() {
+    assert_eq!(
+        normalize_line_endings("SELECT 1\r\nFROM t\r\n"),
+        "SELECT 1\nFROM t\n"
+    );
+    assert_eq!(normalize_line_endings("SELECT '\r'"), "SELECT '\r'");
+
+    assert_eq!(
+        hash_query("SELECT 1\r\nFROM t"),
+        hash_query("SELECT 1\nFROM t")
+    );
+}
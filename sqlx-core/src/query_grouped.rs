@@ -0,0 +1,108 @@
+use std::hash::Hash;
+
+use futures_util::TryStreamExt;
+use indexmap::map::Entry;
+use indexmap::IndexMap;
+
+use crate::arguments::IntoArguments;
+use crate::database::Database;
+use crate::error::Error;
+use crate::executor::Executor;
+use crate::query::Map;
+
+/// SQL query that will group joined rows into parent records, each with a list of children.
+///
+/// Returned by `query_grouped_as!()`. Each row is mapped to the key of its parent, the parent
+/// itself and optionally a child. Rows with the same key are merged into the first parent
+/// seen with that key, with their children appended to the list returned by `children`.
+///
+/// As the parents are not complete until every row has been read, this only
+/// supports [`fetch_all`](Self::fetch_all).
+#[must_use = "query must be executed to affect database"]
+pub struct Grouped<'q, DB: Database, F, A, G> {
+    inner: Map<'q, DB, F, A>,
+    children: G,
+}
+
+impl<'q, DB, F, A, G, K, P, C> Grouped<'q, DB, F, A, G>
+where
+    DB: Database,
+    F: FnMut(DB::Row) -> Result<(K, P, Option<C>), Error> + Send,
+    G: FnMut(&mut P) -> &mut Vec<C>,
+    A: 'q + Send + IntoArguments<'q, DB>,
+    K: Eq + Hash + Send + Unpin,
+    P: Send + Unpin,
+    C: Send + Unpin,
+{
+    #[doc(hidden)]
+    pub fn new(inner: Map<'q, DB, F, A>, children: G) -> Self {
+        Grouped { inner, children }
+    }
+
+    /// Execute the query and return the parents in the order they were first seen.
+    pub async fn fetch_all<'e, 'c: 'e, E>(mut self, executor: E) -> Result<Vec<P>, Error>
+    where
+        'q: 'e,
+        E: 'e + Executor<'c, Database = DB>,
+        DB: 'e,
+        F: 'e,
+        K: 'e,
+        P: 'e,
+        C: 'e,
+    {
+        let mut parents = IndexMap::new();
+        let mut rows = self.inner.fetch(executor);
+
+        while let Some((key, parent, child)) = rows.try_next().await? {
+            let parent = match parents.entry(key) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => entry.insert(parent),
+            };
+
+            if let Some(child) = child {
+                (self.children)(parent).push(child);
+            }
+        }
+
+        Ok(parents.into_values().collect())
+    }
+}
+
+/// Group `children` under the parent with the same key, preserving the order of both.
+///
+/// This is the counterpart to `query_grouped_as!()` for when parents and children are fetched
+/// with separate queries, such as with `WHERE parent_id = ANY($1)`. Children without a matching
+/// parent are discarded.
+///
+/// ```rust
+/// # struct User { id: i64 }
+/// # struct Post { user_id: i64 }
+/// # let users = vec![User { id: 1 }, User { id: 2 }];
+/// # let posts = vec![Post { user_id: 2 }, Post { user_id: 2 }, Post { user_id: 3 }];
+/// let users_with_posts = sqlx::collect_children(users, posts, |u| u.id, |p| p.user_id);
+///
+/// assert_eq!(users_with_posts[0].1.len(), 0);
+/// assert_eq!(users_with_posts[1].1.len(), 2);
+/// ```
+pub fn collect_children<K, P, C>(
+    parents: impl IntoIterator<Item = P>,
+    children: impl IntoIterator<Item = C>,
+    mut parent_key: impl FnMut(&P) -> K,
+    mut child_key: impl FnMut(&C) -> K,
+) -> Vec<(P, Vec<C>)>
+where
+    K: Eq + Hash,
+{
+    let mut groups: IndexMap<K, (P, Vec<C>)> = parents
+        .into_iter()
+        .map(|parent| (parent_key(&parent), (parent, Vec::new())))
+        .collect();
+
+    for child in children {
+        if let Some((_, group)) = groups.get_mut(&child_key(&child)) {
+            group.push(child);
+        }
+    }
+
+    groups.into_values().collect()
+}
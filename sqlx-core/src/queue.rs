@@ -0,0 +1,452 @@
+//! A minimal job queue stored in a database table, for Postgres and MySQL 8+.
+//!
+//! Workers claim jobs with `SELECT ... FOR UPDATE SKIP LOCKED`, so any number of them can take
+//! jobs from the same queue concurrently without blocking each other or claiming the same job.
+//!
+//! A claimed job is hidden from other workers for the queue's visibility timeout. If the worker
+//! [completes][Queue::complete] it, it's deleted; if the worker [fails][Queue::fail] it or doesn't
+//! finish within the timeout, e.g. because it crashed, it's retried until it has been attempted
+//! `max_attempts` times. Jobs that ran out of attempts stay in the table, with the error they last
+//! failed with, until they're deleted manually. Since a job may run more than once, jobs should be
+//! idempotent.
+//!
+//! ```rust,ignore
+//! use sqlx::queue::Queue;
+//!
+//! let queue = Queue::new("jobs", "emails").max_attempts(5);
+//!
+//! // in a migration, e.g. `migrations/<timestamp>_jobs.sql`:
+//! println!("{}", queue.migration("PostgreSQL").unwrap());
+//!
+//! queue.enqueue(&pool, &serde_json::to_string(&email)?).await?;
+//!
+//! // in a worker
+//! queue
+//!     .run(&pool, |job| async move {
+//!         let email: Email = serde_json::from_str(&job.payload)?;
+//!         mailer.send(email).await
+//!     })
+//!     .await?;
+//! ```
+
+use std::future::Future;
+use std::time::Duration;
+
+use crate::acquire::Acquire;
+use crate::arguments::{push_bind, IntoArguments};
+use crate::database::{Database, HasArguments};
+use crate::encode::Encode;
+use crate::error::{BoxDynError, Error};
+use crate::executor::Executor;
+use crate::from_row::FromRow;
+use crate::pool::Pool;
+use crate::query::query_with;
+use crate::query_as::query_as_with;
+use crate::transaction::Transaction;
+use crate::types::Type;
+
+/// A named queue of jobs stored in a table, and the settings for processing them.
+///
+/// Several queues can share a table. The table name is pushed to queries as-is, so it must not
+/// come from user input.
+#[derive(Debug, Clone)]
+pub struct Queue {
+    table: String,
+    name: String,
+    visibility_timeout: Duration,
+    max_attempts: i32,
+    retry_delay: Duration,
+    poll_interval: Duration,
+}
+
+/// A job claimed from a [`Queue`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Job {
+    pub id: i64,
+    pub payload: String,
+    /// The number of times the job has been claimed, including this time.
+    pub attempts: i32,
+}
+
+impl Queue {
+    /// The queue `name`, stored in `table`.
+    ///
+    /// Jobs are hidden for 5 minutes once claimed, attempted up to 3 times, and retried 10
+    /// seconds after failing. Workers poll for jobs every second while the queue is empty.
+    pub fn new(table: impl Into<String>, name: impl Into<String>) -> Self {
+        Queue {
+            table: table.into(),
+            name: name.into(),
+            visibility_timeout: Duration::from_secs(5 * 60),
+            max_attempts: 3,
+            retry_delay: Duration::from_secs(10),
+            poll_interval: Duration::from_secs(1),
+        }
+    }
+
+    /// The table the queue is stored in.
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    /// The name of the queue.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// How long a claimed job is hidden from other workers before it's assumed that the worker
+    /// that claimed it crashed, and it's retried.
+    ///
+    /// This should be longer than any job takes to run.
+    pub fn visibility_timeout(mut self, visibility_timeout: Duration) -> Self {
+        self.visibility_timeout = visibility_timeout;
+        self
+    }
+
+    /// The number of times a job is claimed before it's given up on.
+    pub fn max_attempts(mut self, max_attempts: i32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// How long to wait before retrying a failed job.
+    pub fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// How long [`Self::run()`] waits before checking for jobs again once the queue is empty.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// The SQL to create the table for the queue, for the database named `db_name` (i.e.
+    /// [`Database::NAME`]).
+    ///
+    /// Returns `None` for databases other than Postgres and MySQL.
+    pub fn migration(&self, db_name: &str) -> Option<String> {
+        let table = &self.table;
+
+        let sql = match db_name {
+            "PostgreSQL" => format!(
+                "CREATE TABLE IF NOT EXISTS {table} (\n    \
+                    id BIGSERIAL PRIMARY KEY,\n    \
+                    queue TEXT NOT NULL,\n    \
+                    payload TEXT NOT NULL,\n    \
+                    attempts INTEGER NOT NULL DEFAULT 0,\n    \
+                    visible_at TIMESTAMPTZ NOT NULL DEFAULT now(),\n    \
+                    last_error TEXT,\n    \
+                    created_at TIMESTAMPTZ NOT NULL DEFAULT now()\n\
+                );\n\
+                \n\
+                CREATE INDEX IF NOT EXISTS {table}_queue_visible_at ON {table} (queue, visible_at);\n"
+            ),
+            "MySQL" => format!(
+                "CREATE TABLE IF NOT EXISTS {table} (\n    \
+                    id BIGINT PRIMARY KEY AUTO_INCREMENT,\n    \
+                    queue VARCHAR(255) NOT NULL,\n    \
+                    payload LONGTEXT NOT NULL,\n    \
+                    attempts INT NOT NULL DEFAULT 0,\n    \
+                    visible_at TIMESTAMP(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),\n    \
+                    last_error TEXT,\n    \
+                    created_at TIMESTAMP(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),\n    \
+                    INDEX {table}_queue_visible_at (queue, visible_at)\n\
+                );\n"
+            ),
+            _ => return None,
+        };
+
+        Some(sql)
+    }
+
+    /// Adds a job to the queue.
+    ///
+    /// Pass a transaction to only add the job if the transaction is committed.
+    pub async fn enqueue<'c, DB, E>(&self, executor: E, payload: &str) -> Result<(), Error>
+    where
+        DB: Database,
+        E: Executor<'c, Database = DB>,
+        for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+    {
+        check_database::<DB>()?;
+
+        let mut sql = format!("INSERT INTO {} (queue, payload) VALUES (", self.table);
+        let mut arguments = <DB as HasArguments>::Arguments::default();
+        push_bind(&mut sql, &mut arguments, self.name.as_str());
+        sql.push_str(", ");
+        push_bind(&mut sql, &mut arguments, payload);
+        sql.push(')');
+
+        query_with(&sql, arguments).execute(executor).await?;
+
+        Ok(())
+    }
+
+    /// Claims the oldest visible job in the queue, if any, hiding it from other workers for the
+    /// visibility timeout.
+    pub async fn dequeue<DB>(&self, pool: &Pool<DB>) -> Result<Option<Job>, Error>
+    where
+        DB: Database,
+        for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+        for<'q> i32: Encode<'q, DB> + Type<DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'r> (i64, String, i32): FromRow<'r, DB::Row>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    {
+        check_database::<DB>()?;
+
+        let mut tx = pool.begin().await?;
+
+        let mut select = format!(
+            "SELECT id, payload, attempts FROM {} WHERE queue = ",
+            self.table
+        );
+        let mut arguments = <DB as HasArguments>::Arguments::default();
+        push_bind(&mut select, &mut arguments, self.name.as_str());
+        select.push_str(" AND attempts < ");
+        push_bind(&mut select, &mut arguments, self.max_attempts);
+        select.push_str(&format!(
+            " AND visible_at <= {} ORDER BY id LIMIT 1 FOR UPDATE SKIP LOCKED",
+            now::<DB>()
+        ));
+
+        let job: Option<(i64, String, i32)> = query_as_with(&select, arguments)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+        let Some((id, payload, attempts)) = job else {
+            return Ok(None);
+        };
+
+        let mut update = format!(
+            "UPDATE {} SET attempts = attempts + 1, visible_at = ",
+            self.table
+        );
+        let mut arguments = <DB as HasArguments>::Arguments::default();
+        push_after::<DB>(&mut update, &mut arguments, self.visibility_timeout);
+        update.push_str(" WHERE id = ");
+        push_bind(&mut update, &mut arguments, id);
+
+        query_with(&update, arguments).execute(&mut *tx).await?;
+
+        tx.commit().await?;
+
+        Ok(Some(Job {
+            id,
+            payload,
+            attempts: attempts + 1,
+        }))
+    }
+
+    /// Deletes a job that was processed successfully.
+    ///
+    /// Returns `false`, without deleting the job, if the worker no longer holds the job: it
+    /// wasn't completed within the visibility timeout and has since been claimed again.
+    ///
+    /// Pass a transaction to only delete the job if the transaction is committed.
+    pub async fn complete<'c, DB, A>(&self, conn: A, job: &Job) -> Result<bool, Error>
+    where
+        DB: Database,
+        A: Acquire<'c, Database = DB>,
+        for<'q> i32: Encode<'q, DB> + Type<DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'r> (i64,): FromRow<'r, DB::Row>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+    {
+        check_database::<DB>()?;
+
+        let mut tx = conn.begin().await?;
+
+        if !self.lock_claimed(&mut tx, job).await? {
+            return Ok(false);
+        }
+
+        let mut sql = format!("DELETE FROM {} WHERE id = ", self.table);
+        let mut arguments = <DB as HasArguments>::Arguments::default();
+        push_bind(&mut sql, &mut arguments, job.id);
+
+        query_with(&sql, arguments).execute(&mut *tx).await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Records that a job failed with `error`, making it visible again after the retry delay
+    /// unless it has run out of attempts.
+    ///
+    /// Returns `false`, without updating the job, if the worker no longer holds the job, as for
+    /// [`Self::complete()`].
+    pub async fn fail<'c, DB, A>(&self, conn: A, job: &Job, error: &str) -> Result<bool, Error>
+    where
+        DB: Database,
+        A: Acquire<'c, Database = DB>,
+        for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+        for<'q> i32: Encode<'q, DB> + Type<DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'r> (i64,): FromRow<'r, DB::Row>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+    {
+        check_database::<DB>()?;
+
+        let mut tx = conn.begin().await?;
+
+        if !self.lock_claimed(&mut tx, job).await? {
+            return Ok(false);
+        }
+
+        let mut sql = format!("UPDATE {} SET last_error = ", self.table);
+        let mut arguments = <DB as HasArguments>::Arguments::default();
+        push_bind(&mut sql, &mut arguments, error);
+        sql.push_str(", visible_at = ");
+        push_after::<DB>(&mut sql, &mut arguments, self.retry_delay);
+        sql.push_str(" WHERE id = ");
+        push_bind(&mut sql, &mut arguments, job.id);
+
+        query_with(&sql, arguments).execute(&mut *tx).await?;
+
+        tx.commit().await?;
+
+        Ok(true)
+    }
+
+    /// Locks `job` if it hasn't been claimed again since the worker claimed it, returning
+    /// whether it was locked.
+    ///
+    /// Every claim increments `attempts`, so it serves as the token for the worker's lease.
+    async fn lock_claimed<DB>(&self, tx: &mut Transaction<'_, DB>, job: &Job) -> Result<bool, Error>
+    where
+        DB: Database,
+        for<'q> i32: Encode<'q, DB> + Type<DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'r> (i64,): FromRow<'r, DB::Row>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'e> &'e mut DB::Connection: Executor<'e, Database = DB>,
+    {
+        let mut select = format!("SELECT id FROM {} WHERE id = ", self.table);
+        let mut arguments = <DB as HasArguments>::Arguments::default();
+        push_bind(&mut select, &mut arguments, job.id);
+        select.push_str(" AND attempts = ");
+        push_bind(&mut select, &mut arguments, job.attempts);
+        select.push_str(" FOR UPDATE");
+
+        let locked: Option<(i64,)> = query_as_with(&select, arguments)
+            .fetch_optional(&mut **tx)
+            .await?;
+
+        Ok(locked.is_some())
+    }
+
+    /// Processes jobs with `handler` until a database error occurs, completing each job if the
+    /// handler succeeds and failing it with the handler's error otherwise.
+    ///
+    /// Jobs are processed one at a time; run several workers to process jobs concurrently.
+    pub async fn run<DB, F, Fut, E>(&self, pool: &Pool<DB>, mut handler: F) -> Result<(), Error>
+    where
+        DB: Database,
+        F: FnMut(Job) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: Into<BoxDynError>,
+        for<'q> &'q str: Encode<'q, DB> + Type<DB>,
+        for<'q> i32: Encode<'q, DB> + Type<DB>,
+        for<'q> i64: Encode<'q, DB> + Type<DB>,
+        for<'r> (i64, String, i32): FromRow<'r, DB::Row>,
+        for<'r> (i64,): FromRow<'r, DB::Row>,
+        for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+        for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+    {
+        loop {
+            let Some(job) = self.dequeue(pool).await? else {
+                crate::rt::sleep(self.poll_interval).await;
+                continue;
+            };
+
+            let held = match handler(job.clone()).await {
+                Ok(()) => self.complete(pool, &job).await?,
+                Err(e) => {
+                    let error = e.into().to_string();
+
+                    tracing::warn!(
+                        queue = %self.name,
+                        job = job.id,
+                        attempts = job.attempts,
+                        error,
+                        "job failed"
+                    );
+
+                    self.fail(pool, &job, &error).await?
+                }
+            };
+
+            if !held {
+                tracing::warn!(
+                    queue = %self.name,
+                    job = job.id,
+                    attempts = job.attempts,
+                    "job outlived the visibility timeout and was claimed again"
+                );
+            }
+        }
+    }
+}
+
+fn check_database<DB: Database>() -> Result<(), Error> {
+    if !matches!(DB::NAME, "PostgreSQL" | "MySQL") {
+        return Err(Error::Configuration(
+            format!("sqlx::queue is not supported for {}", DB::NAME).into(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn now<DB: Database>() -> &'static str {
+    match DB::NAME {
+        "MySQL" => "CURRENT_TIMESTAMP(6)",
+        _ => "now()",
+    }
+}
+
+/// Pushes an expression for `delay` from now.
+fn push_after<'q, DB>(
+    sql: &mut String,
+    arguments: &mut <DB as HasArguments<'q>>::Arguments,
+    delay: Duration,
+) where
+    DB: Database,
+    i64: Encode<'q, DB> + Type<DB>,
+{
+    let micros = i64::try_from(delay.as_micros()).unwrap_or(i64::MAX);
+
+    sql.push_str(now::<DB>());
+
+    match DB::NAME {
+        "MySQL" => {
+            sql.push_str(" + INTERVAL ");
+            push_bind(sql, arguments, micros);
+            sql.push_str(" MICROSECOND");
+        }
+        _ => {
+            sql.push_str(" + make_interval(secs => ");
+            push_bind(sql, arguments, micros);
+            sql.push_str(" / 1000000.0)");
+        }
+    }
+}
+
+#[test]
+fn test_migration() {
+    let queue = Queue::new("jobs", "emails");
+
+    assert!(queue
+        .migration("PostgreSQL")
+        .unwrap()
+        .contains("CREATE INDEX IF NOT EXISTS jobs_queue_visible_at ON jobs (queue, visible_at);"));
+    assert!(queue.migration("MySQL").is_some());
+    assert!(queue.migration("SQLite").is_none());
+}
@@ -0,0 +1,73 @@
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use futures_core::future::BoxFuture;
+use once_cell::sync::OnceCell;
+
+use crate::net::Socket;
+
+static RUNTIME: OnceCell<&'static dyn Runtime> = OnceCell::new();
+
+/// An async runtime for SQLx to spawn tasks, sleep and open sockets with, for applications on an
+/// executor other than Tokio or async-std, e.g. that of a game engine.
+///
+/// Once installed with [`install_runtime()`], it's used instead of the runtimes enabled with the
+/// `runtime-*` features, none of which need to be enabled.
+pub trait Runtime: Send + Sync + 'static {
+    /// Run `future` to completion in the background.
+    fn spawn(&self, future: BoxFuture<'static, ()>);
+
+    /// Run `f` where it may block, e.g. to read migration files.
+    ///
+    /// Runs it on a new thread by default.
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send + 'static>) {
+        std::thread::spawn(f);
+    }
+
+    /// A future which completes after `duration`.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+
+    /// Connect a TCP socket to `host` at `port`.
+    ///
+    /// The socket is typically a wrapper implementing [`Socket`] for the TCP stream of the
+    /// runtime. TLS, if enabled, is negotiated by SQLx over this socket.
+    fn connect_tcp<'a>(
+        &'a self,
+        host: &'a str,
+        port: u16,
+    ) -> BoxFuture<'a, io::Result<Box<dyn Socket>>>;
+
+    /// Connect a Unix domain socket at `path`.
+    ///
+    /// Unsupported by default.
+    fn connect_uds<'a>(&'a self, path: &'a Path) -> BoxFuture<'a, io::Result<Box<dyn Socket>>> {
+        let _ = path;
+
+        Box::pin(async {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Unix domain sockets are not supported by the installed runtime",
+            ))
+        })
+    }
+}
+
+/// Install the runtime for SQLx to use instead of Tokio or async-std.
+///
+/// Must be called before any connection is opened.
+///
+/// ### Errors
+/// If called more than once.
+pub fn install_runtime(
+    runtime: &'static dyn Runtime,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+    RUNTIME
+        .set(runtime)
+        .map_err(|_| "runtime already installed".into())
+}
+
+/// The runtime installed with [`install_runtime()`], if any.
+pub(crate) fn installed() -> Option<&'static dyn Runtime> {
+    RUNTIME.get().copied()
+}
@@ -1,15 +1,30 @@
+//! The runtime abstraction: spawning tasks, sleeping and timeouts with whichever runtime is
+//! enabled and running, Tokio first if both are, or with the [`Runtime`] installed by the
+//! application, which takes precedence over both.
+//!
+//! Code in SQLx must use these rather than the APIs of a runtime, so every feature works with
+//! both `runtime-tokio` and `runtime-async-std`; `clippy.toml` disallows the runtime-specific
+//! ones. The `runtime-async-std` feature also supports applications on `smol`, which shares its
+//! reactor (`async-io`) with `async-std`; tasks are spawned on the executor of `async-std`.
+
 use std::future::Future;
 use std::marker::PhantomData;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::time::Duration;
 
+mod custom;
+
 #[cfg(feature = "_rt-async-std")]
 pub mod rt_async_std;
 
 #[cfg(feature = "_rt-tokio")]
 pub mod rt_tokio;
 
+pub use custom::{install_runtime, Runtime};
+
+pub(crate) use custom::installed;
+
 #[derive(Debug, thiserror::Error)]
 #[error("operation timed out")]
 pub struct TimeoutError(());
@@ -19,11 +34,24 @@ pub enum JoinHandle<T> {
     AsyncStd(async_std::task::JoinHandle<T>),
     #[cfg(feature = "_rt-tokio")]
     Tokio(tokio::task::JoinHandle<T>),
+    Custom(futures_channel::oneshot::Receiver<T>),
     // `PhantomData<T>` requires `T: Unpin`
     _Phantom(PhantomData<fn() -> T>),
 }
 
+#[allow(clippy::disallowed_methods)]
 pub async fn timeout<F: Future>(duration: Duration, f: F) -> Result<F::Output, TimeoutError> {
+    if let Some(runtime) = installed() {
+        use futures_util::future::{select, Either};
+
+        futures_util::pin_mut!(f);
+
+        return match select(f, runtime.sleep(duration)).await {
+            Either::Left((output, _)) => Ok(output),
+            Either::Right(_) => Err(TimeoutError(())),
+        };
+    }
+
     #[cfg(feature = "_rt-tokio")]
     if rt_tokio::available() {
         return tokio::time::timeout(duration, f)
@@ -42,7 +70,12 @@ pub async fn timeout<F: Future>(duration: Duration, f: F) -> Result<F::Output, T
     missing_rt((duration, f))
 }
 
+#[allow(clippy::disallowed_methods)]
 pub async fn sleep(duration: Duration) {
+    if let Some(runtime) = installed() {
+        return runtime.sleep(duration).await;
+    }
+
     #[cfg(feature = "_rt-tokio")]
     if rt_tokio::available() {
         return tokio::time::sleep(duration).await;
@@ -57,12 +90,21 @@ pub async fn sleep(duration: Duration) {
     missing_rt(duration)
 }
 
+#[allow(clippy::disallowed_methods)]
 #[track_caller]
 pub fn spawn<F>(fut: F) -> JoinHandle<F::Output>
 where
     F: Future + Send + 'static,
     F::Output: Send + 'static,
 {
+    if let Some(runtime) = installed() {
+        let (tx, rx) = futures_channel::oneshot::channel();
+        runtime.spawn(Box::pin(async move {
+            let _ = tx.send(fut.await);
+        }));
+        return JoinHandle::Custom(rx);
+    }
+
     #[cfg(feature = "_rt-tokio")]
     if let Ok(handle) = tokio::runtime::Handle::try_current() {
         return JoinHandle::Tokio(handle.spawn(fut));
@@ -77,12 +119,21 @@ where
     missing_rt(fut)
 }
 
+#[allow(clippy::disallowed_methods)]
 #[track_caller]
 pub fn spawn_blocking<F, R>(f: F) -> JoinHandle<R>
 where
     F: FnOnce() -> R + Send + 'static,
     R: Send + 'static,
 {
+    if let Some(runtime) = installed() {
+        let (tx, rx) = futures_channel::oneshot::channel();
+        runtime.spawn_blocking(Box::new(move || {
+            let _ = tx.send(f());
+        }));
+        return JoinHandle::Custom(rx);
+    }
+
     #[cfg(feature = "_rt-tokio")]
     if let Ok(handle) = tokio::runtime::Handle::try_current() {
         return JoinHandle::Tokio(handle.spawn_blocking(f));
@@ -97,7 +148,24 @@ where
     missing_rt(f)
 }
 
+#[allow(clippy::disallowed_methods)]
 pub async fn yield_now() {
+    if installed().is_some() {
+        // wake and return `Pending` once, so the executor can run other tasks first
+        let mut yielded = false;
+
+        return std::future::poll_fn(|cx| {
+            if yielded {
+                return Poll::Ready(());
+            }
+
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })
+        .await;
+    }
+
     #[cfg(feature = "_rt-tokio")]
     if rt_tokio::available() {
         return tokio::task::yield_now().await;
@@ -141,7 +209,10 @@ pub fn missing_rt<T>(_unused: T) -> ! {
         panic!("this functionality requires a Tokio context")
     }
 
-    panic!("either the `runtime-async-std` or `runtime-tokio` feature must be enabled")
+    panic!(
+        "either the `runtime-async-std` or `runtime-tokio` feature must be enabled, \
+         or a runtime installed with `sqlx::rt::install_runtime()`"
+    )
 }
 
 impl<T: Send + 'static> Future for JoinHandle<T> {
@@ -156,6 +227,9 @@ impl<T: Send + 'static> Future for JoinHandle<T> {
             Self::Tokio(handle) => Pin::new(handle)
                 .poll(cx)
                 .map(|res| res.expect("spawned task panicked")),
+            Self::Custom(rx) => Pin::new(rx)
+                .poll(cx)
+                .map(|res| res.expect("spawned task panicked or was dropped")),
             Self::_Phantom(_) => {
                 let _ = cx;
                 unreachable!("runtime should have been checked on spawn")
@@ -20,7 +20,7 @@ pub struct AsyncSemaphore {
     // and there are some soundness concerns (although it turns out any intrusive future is unsound
     // in MIRI due to the necessitated mutable aliasing):
     // https://github.com/launchbadge/sqlx/issues/1668
-    #[cfg(all(feature = "_rt-async-std", not(feature = "_rt-tokio")))]
+    #[cfg(not(feature = "_rt-tokio"))]
     inner: futures_intrusive::sync::Semaphore,
 
     #[cfg(feature = "_rt-tokio")]
@@ -30,12 +30,8 @@ pub struct AsyncSemaphore {
 impl AsyncSemaphore {
     #[track_caller]
     pub fn new(fair: bool, permits: usize) -> Self {
-        if cfg!(not(any(feature = "_rt-async-std", feature = "_rt-tokio"))) {
-            crate::rt::missing_rt((fair, permits));
-        }
-
         AsyncSemaphore {
-            #[cfg(all(feature = "_rt-async-std", not(feature = "_rt-tokio")))]
+            #[cfg(not(feature = "_rt-tokio"))]
             inner: futures_intrusive::sync::Semaphore::new(fair, permits),
             #[cfg(feature = "_rt-tokio")]
             inner: {
@@ -46,18 +42,15 @@ impl AsyncSemaphore {
     }
 
     pub fn permits(&self) -> usize {
-        #[cfg(all(feature = "_rt-async-std", not(feature = "_rt-tokio")))]
+        #[cfg(not(feature = "_rt-tokio"))]
         return self.inner.permits();
 
         #[cfg(feature = "_rt-tokio")]
         return self.inner.available_permits();
-
-        #[cfg(not(any(feature = "_rt-async-std", feature = "_rt-tokio")))]
-        crate::rt::missing_rt(())
     }
 
     pub async fn acquire(&self, permits: u32) -> AsyncSemaphoreReleaser<'_> {
-        #[cfg(all(feature = "_rt-async-std", not(feature = "_rt-tokio")))]
+        #[cfg(not(feature = "_rt-tokio"))]
         return AsyncSemaphoreReleaser {
             inner: self.inner.acquire(permits as usize).await,
         };
@@ -72,13 +65,10 @@ impl AsyncSemaphore {
                 .await
                 .expect("BUG: we do not expose the `.close()` method"),
         };
-
-        #[cfg(not(any(feature = "_rt-async-std", feature = "_rt-tokio")))]
-        crate::rt::missing_rt(permits)
     }
 
     pub fn try_acquire(&self, permits: u32) -> Option<AsyncSemaphoreReleaser<'_>> {
-        #[cfg(all(feature = "_rt-async-std", not(feature = "_rt-tokio")))]
+        #[cfg(not(feature = "_rt-tokio"))]
         return Some(AsyncSemaphoreReleaser {
             inner: self.inner.try_acquire(permits as usize)?,
         });
@@ -87,20 +77,14 @@ impl AsyncSemaphore {
         return Some(AsyncSemaphoreReleaser {
             inner: self.inner.try_acquire_many(permits).ok()?,
         });
-
-        #[cfg(not(any(feature = "_rt-async-std", feature = "_rt-tokio")))]
-        crate::rt::missing_rt(permits)
     }
 
     pub fn release(&self, permits: usize) {
-        #[cfg(all(feature = "_rt-async-std", not(feature = "_rt-tokio")))]
+        #[cfg(not(feature = "_rt-tokio"))]
         return self.inner.release(permits);
 
         #[cfg(feature = "_rt-tokio")]
         return self.inner.add_permits(permits);
-
-        #[cfg(not(any(feature = "_rt-async-std", feature = "_rt-tokio")))]
-        crate::rt::missing_rt(permits)
     }
 }
 
@@ -114,19 +98,16 @@ pub struct AsyncSemaphoreReleaser<'a> {
     // and there are some soundness concerns (although it turns out any intrusive future is unsound
     // in MIRI due to the necessitated mutable aliasing):
     // https://github.com/launchbadge/sqlx/issues/1668
-    #[cfg(all(feature = "_rt-async-std", not(feature = "_rt-tokio")))]
+    #[cfg(not(feature = "_rt-tokio"))]
     inner: futures_intrusive::sync::SemaphoreReleaser<'a>,
 
     #[cfg(feature = "_rt-tokio")]
     inner: tokio::sync::SemaphorePermit<'a>,
-
-    #[cfg(not(any(feature = "_rt-async-std", feature = "_rt-tokio")))]
-    _phantom: std::marker::PhantomData<&'a ()>,
 }
 
 impl AsyncSemaphoreReleaser<'_> {
     pub fn disarm(self) {
-        #[cfg(all(feature = "_rt-async-std", not(feature = "_rt-tokio")))]
+        #[cfg(not(feature = "_rt-tokio"))]
         {
             let mut this = self;
             this.inner.disarm();
@@ -138,8 +119,5 @@ impl AsyncSemaphoreReleaser<'_> {
             self.inner.forget();
             return;
         }
-
-        #[cfg(not(any(feature = "_rt-async-std", feature = "_rt-tokio")))]
-        crate::rt::missing_rt(())
     }
 }
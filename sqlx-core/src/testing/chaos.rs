@@ -0,0 +1,383 @@
+//! An in-process TCP proxy for fault-injection tests.
+//!
+//! [`ChaosProxy`] forwards connections to the database and injects faults into them on demand:
+//! latency, slow reads, truncated responses, disconnects and refused connections. Drivers
+//! connect to the proxy instead of the database, so tests can check how they and the pool
+//! recover, e.g. that a connection whose query was cancelled by a timeout isn't reused with the
+//! response of that query still pending.
+//!
+//! The proxy runs on threads of its own with blocking sockets, so it works with any runtime.
+//!
+//! ```rust,no_run
+//! # fn example() -> std::io::Result<()> {
+//! use sqlx::testing::chaos::ChaosProxy;
+//! use std::time::Duration;
+//!
+//! let proxy = ChaosProxy::start("localhost:5432")?;
+//! let url = format!("postgres://postgres@{}/postgres", proxy.local_addr());
+//!
+//! // ... connect to `url` ...
+//!
+//! proxy.set_latency(Duration::from_millis(100));
+//! proxy.disconnect_all();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+/// A TCP proxy to the database which injects faults. See the [module documentation](self).
+///
+/// Faults apply to new and open connections alike. The proxy stops and closes its connections
+/// when dropped.
+pub struct ChaosProxy {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+}
+
+struct Shared {
+    upstream: Vec<SocketAddr>,
+    faults: Mutex<Faults>,
+    // both ends of each open connection, to close them with `disconnect_all()`
+    connections: Mutex<HashMap<u64, (TcpStream, TcpStream)>>,
+    next_id: AtomicU64,
+    stopped: AtomicBool,
+}
+
+#[derive(Default)]
+struct Faults {
+    latency: Duration,
+    slow_reads: Option<(usize, Duration)>,
+    truncate_after: Option<usize>,
+    refuse: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    ToDatabase,
+    FromDatabase,
+}
+
+impl ChaosProxy {
+    /// Start a proxy to the database at `upstream` on a random port of the loopback interface.
+    pub fn start(upstream: impl ToSocketAddrs) -> io::Result<Self> {
+        let upstream: Vec<SocketAddr> = upstream.to_socket_addrs()?.collect();
+
+        if upstream.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "no addresses to proxy to",
+            ));
+        }
+
+        let listener = TcpListener::bind(("127.0.0.1", 0))?;
+        let addr = listener.local_addr()?;
+
+        let shared = Arc::new(Shared {
+            upstream,
+            faults: Mutex::default(),
+            connections: Mutex::default(),
+            next_id: AtomicU64::new(0),
+            stopped: AtomicBool::new(false),
+        });
+
+        let accept_shared = shared.clone();
+        thread::Builder::new()
+            .name("sqlx-chaos-proxy".into())
+            .spawn(move || accept(listener, accept_shared))?;
+
+        Ok(ChaosProxy { addr, shared })
+    }
+
+    /// The address to connect to instead of the database.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Delay each chunk of data by `latency`, in both directions.
+    pub fn set_latency(&self, latency: Duration) {
+        self.shared.faults().latency = latency;
+    }
+
+    /// Forward the responses of the database `chunk_size` bytes at a time with `delay` after
+    /// each chunk, as if the driver read them from a slow network.
+    pub fn set_slow_reads(&self, chunk_size: usize, delay: Duration) {
+        assert_ne!(chunk_size, 0, "chunk_size must not be zero");
+
+        self.shared.faults().slow_reads = Some((chunk_size, delay));
+    }
+
+    /// Forward the next `bytes` bytes from the database, on any connection, then close the
+    /// connection they were sent on, truncating the response in the middle of a packet.
+    pub fn truncate_after(&self, bytes: usize) {
+        self.shared.faults().truncate_after = Some(bytes);
+    }
+
+    /// Accept new connections only to close them right away, as a database that's down would,
+    /// if `refuse` is `true`.
+    pub fn refuse_connections(&self, refuse: bool) {
+        self.shared.faults().refuse = refuse;
+    }
+
+    /// Close every open connection, in the middle of whatever is being sent on it.
+    pub fn disconnect_all(&self) {
+        let connections: Vec<_> = self.shared.connections().drain().collect();
+
+        for (_, (client, server)) in connections {
+            let _ = client.shutdown(Shutdown::Both);
+            let _ = server.shutdown(Shutdown::Both);
+        }
+    }
+
+    /// Stop injecting faults. Connections closed by earlier faults stay closed.
+    pub fn clear_faults(&self) {
+        *self.shared.faults() = Faults::default();
+    }
+
+    /// The number of open connections through the proxy.
+    pub fn connections(&self) -> usize {
+        self.shared.connections().len()
+    }
+}
+
+impl Drop for ChaosProxy {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::Release);
+
+        // wake the thread blocked on `accept()` so it sees it's stopped
+        let _ = TcpStream::connect(self.addr);
+
+        self.disconnect_all();
+    }
+}
+
+impl Shared {
+    fn faults(&self) -> MutexGuard<'_, Faults> {
+        self.faults.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn connections(&self) -> MutexGuard<'_, HashMap<u64, (TcpStream, TcpStream)>> {
+        self.connections.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn disconnect(&self, id: u64) {
+        if let Some((client, server)) = self.connections().remove(&id) {
+            let _ = client.shutdown(Shutdown::Both);
+            let _ = server.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+fn accept(listener: TcpListener, shared: Arc<Shared>) {
+    for client in listener.incoming() {
+        if shared.stopped.load(Ordering::Acquire) {
+            break;
+        }
+
+        let Ok(client) = client else { continue };
+
+        if shared.faults().refuse {
+            continue;
+        }
+
+        if let Err(e) = proxy(client, &shared) {
+            log::debug!("chaos proxy failed to forward a connection: {e}");
+        }
+    }
+}
+
+fn proxy(client: TcpStream, shared: &Arc<Shared>) -> io::Result<()> {
+    let server = TcpStream::connect(&shared.upstream[..])?;
+
+    client.set_nodelay(true)?;
+    server.set_nodelay(true)?;
+
+    let id = shared.next_id.fetch_add(1, Ordering::Relaxed);
+
+    shared
+        .connections()
+        .insert(id, (client.try_clone()?, server.try_clone()?));
+
+    let pumps = [
+        (
+            client.try_clone()?,
+            server.try_clone()?,
+            Direction::ToDatabase,
+        ),
+        (server, client, Direction::FromDatabase),
+    ];
+
+    for (from, to, direction) in pumps {
+        let shared = shared.clone();
+
+        thread::Builder::new()
+            .name("sqlx-chaos-proxy".into())
+            .spawn(move || {
+                pump(&shared, from, to, direction);
+                shared.disconnect(id);
+            })?;
+    }
+
+    Ok(())
+}
+
+/// Forward data from `from` to `to`, injecting the faults set when each chunk is read, until
+/// either is closed.
+fn pump(shared: &Shared, mut from: TcpStream, mut to: TcpStream, direction: Direction) {
+    let mut buf = [0u8; 8192];
+
+    loop {
+        let mut chunk = match from.read(&mut buf) {
+            Ok(0) | Err(_) => return,
+            Ok(read) => &buf[..read],
+        };
+
+        let (latency, slow_reads, truncated) = {
+            let mut faults = shared.faults();
+            let mut truncated = false;
+
+            if direction == Direction::FromDatabase {
+                if let Some(remaining) = &mut faults.truncate_after {
+                    if chunk.len() >= *remaining {
+                        chunk = &chunk[..*remaining];
+                        faults.truncate_after = None;
+                        truncated = true;
+                    } else {
+                        *remaining -= chunk.len();
+                    }
+                }
+            }
+
+            let slow_reads = faults
+                .slow_reads
+                .filter(|_| direction == Direction::FromDatabase);
+
+            (faults.latency, slow_reads, truncated)
+        };
+
+        if !latency.is_zero() {
+            thread::sleep(latency);
+        }
+
+        let written = match slow_reads {
+            Some((chunk_size, delay)) => chunk.chunks(chunk_size).try_for_each(|piece| {
+                to.write_all(piece)?;
+                thread::sleep(delay);
+                Ok(())
+            }),
+            None => to.write_all(chunk),
+        };
+
+        if written.is_err() || truncated {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+fn echo_server() -> SocketAddr {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+
+            thread::spawn(move || {
+                let mut reader = stream.try_clone().unwrap();
+                let _ = io::copy(&mut reader, &mut stream);
+            });
+        }
+    });
+
+    addr
+}
+
+#[cfg(test)]
+fn connect(proxy: &ChaosProxy) -> TcpStream {
+    let stream = TcpStream::connect(proxy.local_addr()).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    stream
+}
+
+#[cfg(test)]
+fn echo(stream: &mut TcpStream, data: &[u8]) -> io::Result<Vec<u8>> {
+    stream.write_all(data)?;
+
+    let mut echoed = vec![0; data.len()];
+    stream.read_exact(&mut echoed)?;
+    Ok(echoed)
+}
+
+#[test]
+fn test_chaos_proxy_forwards() {
+    let proxy = ChaosProxy::start(echo_server()).unwrap();
+    let mut stream = connect(&proxy);
+
+    assert_eq!(echo(&mut stream, b"hello").unwrap(), b"hello");
+    assert_eq!(proxy.connections(), 1);
+}
+
+#[test]
+fn test_chaos_proxy_latency_and_slow_reads() {
+    let proxy = ChaosProxy::start(echo_server()).unwrap();
+    let mut stream = connect(&proxy);
+
+    proxy.set_latency(Duration::from_millis(50));
+    let start = std::time::Instant::now();
+    assert_eq!(echo(&mut stream, b"hello").unwrap(), b"hello");
+    assert!(start.elapsed() >= Duration::from_millis(100));
+
+    proxy.clear_faults();
+    proxy.set_slow_reads(2, Duration::from_millis(50));
+    let start = std::time::Instant::now();
+    assert_eq!(echo(&mut stream, b"hello").unwrap(), b"hello");
+    // the last chunk arrives after the delays of the first two
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+#[test]
+fn test_chaos_proxy_truncates() {
+    let proxy = ChaosProxy::start(echo_server()).unwrap();
+    let mut stream = connect(&proxy);
+
+    assert_eq!(echo(&mut stream, b"hello").unwrap(), b"hello");
+
+    proxy.truncate_after(3);
+    stream.write_all(b"hello").unwrap();
+
+    let mut echoed = Vec::new();
+    stream.read_to_end(&mut echoed).unwrap();
+    assert_eq!(echoed, b"hel");
+
+    // the fault is spent
+    let mut stream = connect(&proxy);
+    assert_eq!(echo(&mut stream, b"hello").unwrap(), b"hello");
+}
+
+#[test]
+fn test_chaos_proxy_disconnects_and_refuses() {
+    let proxy = ChaosProxy::start(echo_server()).unwrap();
+    let mut stream = connect(&proxy);
+    assert_eq!(echo(&mut stream, b"hello").unwrap(), b"hello");
+
+    proxy.disconnect_all();
+    assert!(echo(&mut stream, b"hello").is_err());
+    assert_eq!(proxy.connections(), 0);
+
+    proxy.refuse_connections(true);
+    let mut stream = connect(&proxy);
+    assert!(echo(&mut stream, b"hello").is_err());
+
+    proxy.refuse_connections(false);
+    let mut stream = connect(&proxy);
+    assert_eq!(echo(&mut stream, b"hello").unwrap(), b"hello");
+}
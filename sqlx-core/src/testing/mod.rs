@@ -12,6 +12,8 @@ use crate::executor::Executor;
 use crate::migrate::{Migrate, Migrator};
 use crate::pool::{Pool, PoolConnection, PoolOptions};
 
+pub mod chaos;
+
 mod fixtures;
 
 pub trait TestSupport: Database {
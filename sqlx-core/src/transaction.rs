@@ -20,6 +20,18 @@ pub trait TransactionManager {
         conn: &mut <Self::Database as Database>::Connection,
     ) -> BoxFuture<'_, Result<(), Error>>;
 
+    /// Begin a new transaction with the given isolation level and access mode.
+    ///
+    /// The default implementation ignores the options, for databases that don't support them.
+    fn begin_with(
+        conn: &mut <Self::Database as Database>::Connection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        let _ = options;
+
+        Self::begin(conn)
+    }
+
     /// Commit the active transaction or release the most recent savepoint.
     fn commit(
         conn: &mut <Self::Database as Database>::Connection,
@@ -34,6 +46,115 @@ pub trait TransactionManager {
     fn start_rollback(conn: &mut <Self::Database as Database>::Connection);
 }
 
+/// The isolation level of a transaction.
+///
+/// See [`TransactionOptions::isolation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IsolationLevel {
+    ReadUncommitted,
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl IsolationLevel {
+    /// The name of this isolation level in SQL, e.g. `REPEATABLE READ`.
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            IsolationLevel::ReadUncommitted => "READ UNCOMMITTED",
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+/// Options for beginning a transaction with [`Connection::begin_with`] or [`Pool::begin_with`].
+///
+/// Options that aren't set use the database's defaults. SQLite ignores the options, since its
+/// transactions are always serializable. The options can't be applied to a savepoint, so beginning
+/// a nested transaction with any options set returns an error.
+///
+/// ```rust,no_run
+/// # async fn example(conn: &mut sqlx::PgConnection) -> sqlx::Result<()> {
+/// use sqlx::{Connection, IsolationLevel, TransactionOptions};
+///
+/// let options = TransactionOptions::new()
+///     .isolation(IsolationLevel::Serializable)
+///     .read_only(true);
+///
+/// let mut tx = conn.begin_with(options).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Connection::begin_with`]: crate::connection::Connection::begin_with
+/// [`Pool::begin_with`]: crate::pool::Pool::begin_with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TransactionOptions {
+    isolation: Option<IsolationLevel>,
+    read_only: Option<bool>,
+}
+
+impl TransactionOptions {
+    /// Options for a transaction with the database's default isolation level and access mode.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the isolation level of the transaction.
+    pub fn isolation(mut self, isolation: IsolationLevel) -> Self {
+        self.isolation = Some(isolation);
+        self
+    }
+
+    /// Sets whether the transaction is `READ ONLY` or `READ WRITE`.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = Some(read_only);
+        self
+    }
+
+    /// The isolation level of the transaction, if set.
+    pub fn get_isolation(&self) -> Option<IsolationLevel> {
+        self.isolation
+    }
+
+    /// Whether the transaction is read-only, if set.
+    pub fn get_read_only(&self) -> Option<bool> {
+        self.read_only
+    }
+
+    /// The options as a comma-separated list of transaction modes, as accepted by
+    /// `BEGIN` in Postgres and `SET TRANSACTION` in Postgres and MySQL, e.g.
+    /// `ISOLATION LEVEL SERIALIZABLE, READ ONLY`.
+    ///
+    /// Returns `None` if no options are set.
+    pub fn to_sql(&self) -> Option<String> {
+        let modes: Vec<String> = self
+            .isolation
+            .map(|isolation| format!("ISOLATION LEVEL {}", isolation.as_sql()))
+            .into_iter()
+            .chain(
+                self.read_only
+                    .map(|read_only| if read_only { "READ ONLY" } else { "READ WRITE" }.to_owned()),
+            )
+            .collect();
+
+        (!modes.is_empty()).then(|| modes.join(", "))
+    }
+
+    #[doc(hidden)]
+    pub fn check_not_nested(&self, depth: usize) -> Result<(), Error> {
+        if depth > 0 {
+            return Err(Error::Configuration(
+                "transaction options cannot be applied to a nested transaction (savepoint)".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
 /// An in-progress database transaction or savepoint.
 ///
 /// A transaction starts with a call to [`Pool::begin`] or [`Connection::begin`].
@@ -78,6 +199,32 @@ where
         })
     }
 
+    #[doc(hidden)]
+    pub fn begin_with(
+        conn: impl Into<MaybePoolConnection<'c, DB>>,
+        options: TransactionOptions,
+    ) -> BoxFuture<'c, Result<Self, Error>> {
+        let mut conn = conn.into();
+
+        Box::pin(async move {
+            DB::TransactionManager::begin_with(&mut conn, options).await?;
+
+            Ok(Self {
+                connection: conn,
+                open: true,
+            })
+        })
+    }
+
+    /// Marks this transaction as ended without committing or rolling it back, for drivers which
+    /// can end a transaction in other ways, e.g. `PREPARE TRANSACTION` in Postgres.
+    ///
+    /// The driver is responsible for updating the connection's transaction state.
+    #[doc(hidden)]
+    pub fn forget(mut self) {
+        self.open = false;
+    }
+
     /// Commits this transaction or savepoint.
     pub async fn commit(mut self) -> Result<(), Error> {
         DB::TransactionManager::commit(&mut self.connection).await?;
@@ -266,3 +413,23 @@ pub fn rollback_ansi_transaction_sql(depth: usize) -> Cow<'static, str> {
         ))
     }
 }
+
+#[test]
+fn test_transaction_options_to_sql() {
+    assert_eq!(TransactionOptions::new().to_sql(), None);
+    assert_eq!(
+        TransactionOptions::new()
+            .isolation(IsolationLevel::Serializable)
+            .read_only(true)
+            .to_sql()
+            .as_deref(),
+        Some("ISOLATION LEVEL SERIALIZABLE, READ ONLY")
+    );
+    assert_eq!(
+        TransactionOptions::new()
+            .read_only(false)
+            .to_sql()
+            .as_deref(),
+        Some("READ WRITE")
+    );
+}
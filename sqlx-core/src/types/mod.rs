@@ -28,11 +28,18 @@ pub mod bstr;
 #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
 mod json;
 
+#[cfg(feature = "uuid")]
+mod uuid_v7;
+
 #[cfg(feature = "uuid")]
 #[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
 #[doc(no_inline)]
 pub use uuid::{self, Uuid};
 
+#[cfg(feature = "uuid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "uuid")))]
+pub use uuid_v7::new_uuid_v7;
+
 #[cfg(feature = "chrono")]
 #[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
 pub mod chrono {
@@ -123,23 +130,34 @@ pub use json::{Json, JsonRawValue, JsonValue};
 ///    .await?;
 /// ```
 ///
-/// However, if you are wrapping a type that does not implement `PgHasArrayType`
-/// (e.g. `Vec` itself, because we don't currently support multidimensional arrays),
-/// you may receive an error:
+/// The impl is skipped automatically if the wrapped type is itself an array, such as `Vec<T>`,
+/// `[T; N]` or `Option<Vec<T>>`, as we don't currently support multidimensional arrays.
+/// `Vec<u8>` and `[u8; N]` are not skipped since they're encoded as `BYTEA`:
 ///
 /// ```rust,ignore
-/// #[derive(sqlx::Type)] // ERROR: `Vec<i64>` does not implement `PgHasArrayType`
+/// #[derive(sqlx::Type)] // no `PgHasArrayType` impl
 /// #[sqlx(transparent)]
 /// struct UserIds(Vec<i64>);
 /// ```
 ///
+/// However, if you are wrapping some other type that does not implement `PgHasArrayType`,
+/// or an alias of an array type that the derive can't recognize, you may receive an error:
+///
+/// ```rust,ignore
+/// type Ids = Vec<i64>;
+///
+/// #[derive(sqlx::Type)] // ERROR: `Vec<i64>` does not implement `PgHasArrayType`
+/// #[sqlx(transparent)]
+/// struct UserIds(Ids);
+/// ```
+///
 /// To remedy this, add `#[sqlx(no_pg_array)]`, which disables the generation
 /// of the `PgHasArrayType` impl:
 ///
 /// ```rust,ignore
 /// #[derive(sqlx::Type)]
 /// #[sqlx(transparent, no_pg_array)]
-/// struct UserIds(Vec<i64>);
+/// struct UserIds(Ids);
 /// ```
 ///
 /// ##### Attributes
@@ -152,6 +170,49 @@ pub use json::{Json, JsonRawValue, JsonValue};
 /// * `#[sqlx(rename_all = "<strategy>")]` on struct definition: See [`derive docs in FromRow`](crate::from_row::FromRow#rename_all)
 /// * `#[sqlx(no_pg_array)]`: do not emit a `PgHasArrayType` impl (see above).
 ///
+/// ### Identifiers
+///
+/// `#[derive(sqlx::Id)]` is a shorthand for strongly-typed identifiers wrapping an `i32`, `i64`
+/// or `Uuid`. In addition to the transparent `Type`, `Encode` and `Decode` impls above, it
+/// generates `Display`, `FromStr`, `From` conversions to and from the inner type, and, with the
+/// `json` feature, `Serialize` and `Deserialize` impls that delegate to the inner type.
+///
+/// The query macros accept an `Id` (or a reference or `Option` of one) anywhere a bind parameter
+/// of the inner type is expected, and an output column can be decoded into one with a type
+/// override:
+///
+/// ```rust,ignore
+/// #[derive(sqlx::Id)]
+/// struct UserId(i64);
+///
+/// let user_id: UserId = path_segment.parse()?;
+///
+/// let user = sqlx::query!(r#"SELECT id as "id: UserId", name FROM users WHERE id = $1"#, user_id)
+///     .fetch_one(&mut conn)
+///     .await?;
+/// ```
+///
+/// ### JSON
+///
+/// Structs with `#[sqlx(json)]` are encoded to and decoded from the database's JSON type
+/// (`JSONB` in Postgres, `JSON` in MySQL and `TEXT` in SQLite) using their `serde`
+/// implementations, the same as if they were wrapped in `Json<T>`. Requires the `json` feature.
+///
+/// ```rust,ignore
+/// #[derive(serde::Serialize, serde::Deserialize, sqlx::Type)]
+/// #[sqlx(json)]
+/// struct Preferences {
+///     theme: String,
+///     notifications: bool,
+/// }
+///
+/// sqlx::query("UPDATE users SET preferences = $1 WHERE id = $2")
+///     .bind(&preferences)
+///     .bind(user_id)
+///     .execute(&mut conn)
+///     .await?;
+/// ```
+///
 /// ### Enumeration
 ///
 /// Enumerations may be defined in Rust and can match SQL by
@@ -0,0 +1,87 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand::Rng;
+use uuid::Uuid;
+
+// the timestamp of the last UUID in milliseconds, shifted left by 12 bits, plus its counter
+static LAST: AtomicU64 = AtomicU64::new(0);
+
+/// Generates a UUIDv7: a 48-bit Unix timestamp in milliseconds, then random bits.
+///
+/// UUIDv7 keys are ordered by creation time, which keeps inserts into a B-tree index on the key
+/// at its end, unlike random UUIDv4 keys. UUIDs generated by this function in the same process are
+/// strictly increasing, even within the same millisecond or if the system clock goes back, as
+/// the 12 bits after the timestamp are a counter ([RFC 9562, Method 1][method-1]).
+///
+/// Generating the key on the client lets it be bound in an `INSERT` like any other column,
+/// instead of being returned from a `DEFAULT` expression:
+///
+/// ```rust,ignore
+/// use sqlx::types::new_uuid_v7;
+///
+/// let id = new_uuid_v7();
+///
+/// sqlx::query!("INSERT INTO users (id, name) VALUES ($1, $2)", id, name)
+///     .execute(&pool)
+///     .await?;
+/// ```
+///
+/// Columns with a `DEFAULT`, e.g. `id UUID PRIMARY KEY DEFAULT gen_random_uuid()`, can also just
+/// be left out of the `INSERT`: the query macros only check the parameters and the returned
+/// columns of a query. A `RETURNING id` column is inferred to be non-null if `id` is `NOT NULL`,
+/// which a primary key always is.
+///
+/// [method-1]: https://www.rfc-editor.org/rfc/rfc9562#section-6.2
+pub fn new_uuid_v7() -> Uuid {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_millis() as u64);
+
+    let mut last = LAST.load(Ordering::Relaxed);
+
+    let next = loop {
+        let next = if now > last >> 12 {
+            // start the counter at a random value, leaving room for it to increase
+            now << 12 | u64::from(rand::thread_rng().gen::<u16>() & 0x07ff)
+        } else {
+            // if the counter overflows, this moves on to the next millisecond
+            last + 1
+        };
+
+        match LAST.compare_exchange_weak(last, next, Ordering::Relaxed, Ordering::Relaxed) {
+            Ok(_) => break next,
+            Err(actual) => last = actual,
+        }
+    };
+
+    let mut bytes = [0u8; 16];
+    bytes[..6].copy_from_slice(&(next >> 12).to_be_bytes()[2..]);
+    bytes[6..8].copy_from_slice(&(0x7000 | (next & 0x0fff) as u16).to_be_bytes());
+    rand::thread_rng().fill(&mut bytes[8..]);
+
+    // the RFC 4122 variant
+    bytes[8] = 0x80 | (bytes[8] & 0x3f);
+
+    Uuid::from_bytes(bytes)
+}
+
+#[test]
+fn test_new_uuid_v7() {
+    let before = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64;
+
+    let uuids: Vec<Uuid> = (0..10_000).map(|_| new_uuid_v7()).collect();
+
+    for uuid in &uuids {
+        assert_eq!(uuid.get_version_num(), 7);
+        assert_eq!(uuid.get_variant(), uuid::Variant::RFC4122);
+    }
+
+    assert!(uuids.windows(2).all(|w| w[0] < w[1]));
+
+    let millis = u64::from_be_bytes(uuids[0].as_bytes()[..8].try_into().unwrap()) >> 16;
+    assert!(millis >= before);
+}
@@ -1,8 +1,11 @@
 use std::collections::hash_map;
 use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
 use std::sync::Mutex;
 
 use once_cell::sync::Lazy;
+use serde::Deserialize;
 
 use sqlx_core::connection::Connection;
 use sqlx_core::database::Database;
@@ -16,6 +19,157 @@ pub enum ParamChecking {
     Weak,
 }
 
+/// The crate whose types are used for dates and times, if both `chrono` and `time` are enabled.
+///
+/// Otherwise, Postgres and SQLite use `chrono` and MySQL uses `time`.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DateTimeCrate {
+    Chrono,
+    Time,
+}
+
+/// The crate whose type is used for `NUMERIC` and `DECIMAL`, if both `bigdecimal` and
+/// `rust_decimal` are enabled.
+///
+/// Otherwise, `bigdecimal` is used.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecimalCrate {
+    #[serde(rename = "bigdecimal")]
+    BigDecimal,
+    #[serde(rename = "rust_decimal")]
+    RustDecimal,
+}
+
+/// The crates chosen for kinds of types that more than one crate supports.
+///
+/// Set for all queries under `[macros]` in `sqlx.toml`, or with environment variables, and for a
+/// column with an override naming the crate, e.g. `"foo: time"`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TypeCrates {
+    pub datetime: Option<DateTimeCrate>,
+    pub decimal: Option<DecimalCrate>,
+}
+
+impl DateTimeCrate {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DateTimeCrate::Chrono => "chrono",
+            DateTimeCrate::Time => "time",
+        }
+    }
+
+    // the path segment in the types of the crate that wasn't chosen
+    fn excluded_segment(&self) -> &'static str {
+        match self {
+            DateTimeCrate::Chrono => "time",
+            DateTimeCrate::Time => "chrono",
+        }
+    }
+}
+
+impl DecimalCrate {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DecimalCrate::BigDecimal => "bigdecimal",
+            DecimalCrate::RustDecimal => "rust_decimal",
+        }
+    }
+
+    fn excluded_segment(&self) -> &'static str {
+        match self {
+            DecimalCrate::BigDecimal => "Decimal",
+            DecimalCrate::RustDecimal => "BigDecimal",
+        }
+    }
+}
+
+impl TypeCrates {
+    /// Parses the name of a crate, as in a column override.
+    pub fn parse_crate(name: &str) -> Option<Self> {
+        let mut crates = TypeCrates::default();
+
+        match name {
+            "chrono" => crates.datetime = Some(DateTimeCrate::Chrono),
+            "time" => crates.datetime = Some(DateTimeCrate::Time),
+            "bigdecimal" => crates.decimal = Some(DecimalCrate::BigDecimal),
+            "rust_decimal" => crates.decimal = Some(DecimalCrate::RustDecimal),
+            _ => return None,
+        }
+
+        Some(crates)
+    }
+
+    /// The crates of `self`, or else of `fallback`.
+    pub fn or(self, fallback: Self) -> Self {
+        TypeCrates {
+            datetime: self.datetime.or(fallback.datetime),
+            decimal: self.decimal.or(fallback.decimal),
+        }
+    }
+
+    /// Whether the Rust type `ty` isn't from a crate that wasn't chosen.
+    pub fn allows(&self, ty: &str) -> bool {
+        self.required_feature(ty).is_none()
+    }
+
+    /// The feature of the chosen crate, if `ty` is from another one.
+    pub fn required_feature(&self, ty: &str) -> Option<&'static str> {
+        let mut segments = ty.split(|c: char| !(c.is_alphanumeric() || c == '_'));
+
+        segments.find_map(|segment| match (self.datetime, self.decimal) {
+            (Some(datetime), _) if segment == datetime.excluded_segment() => {
+                Some(datetime.as_str())
+            }
+            (_, Some(decimal)) if segment == decimal.excluded_segment() => Some(decimal.as_str()),
+            _ => None,
+        })
+    }
+}
+
+impl FromStr for DateTimeCrate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match TypeCrates::parse_crate(s).and_then(|crates| crates.datetime) {
+            Some(datetime) => Ok(datetime),
+            None => Err(format!("expected `chrono` or `time`, got {s:?}")),
+        }
+    }
+}
+
+impl FromStr for DecimalCrate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match TypeCrates::parse_crate(s).and_then(|crates| crates.decimal) {
+            Some(decimal) => Ok(decimal),
+            None => Err(format!(
+                "expected `bigdecimal` or `rust_decimal`, got {s:?}"
+            )),
+        }
+    }
+}
+
+impl Display for DateTimeCrate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Display for DecimalCrate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What the query macros need to know about a database to check queries against it: the paths
+/// of its types in the generated code, and the Rust types for its SQL types.
+///
+/// Implemented with [`impl_database_ext!`][crate::impl_database_ext], including by drivers
+/// outside of SQLx, which can then check queries in their own proc-macro crate with
+/// [`QueryDriver::new()`][crate::query::QueryDriver::new] and
+/// [`expand_input()`][crate::query::expand_input].
 pub trait DatabaseExt: Database {
     const DATABASE_PATH: &'static str;
     const ROW_PATH: &'static str;
@@ -30,21 +184,26 @@ pub trait DatabaseExt: Database {
         syn::parse_str(Self::ROW_PATH).unwrap()
     }
 
-    fn param_type_for_id(id: &Self::TypeInfo) -> Option<&'static str>;
+    fn param_type_for_id(id: &Self::TypeInfo, crates: TypeCrates) -> Option<&'static str>;
 
-    fn return_type_for_id(id: &Self::TypeInfo) -> Option<&'static str>;
+    fn return_type_for_id(id: &Self::TypeInfo, crates: TypeCrates) -> Option<&'static str>;
 
     fn get_feature_gate(info: &Self::TypeInfo) -> Option<&'static str>;
 
     fn describe_blocking(query: &str, database_url: &str) -> sqlx_core::Result<Describe<Self>>;
+
+    /// The placeholder for the bind parameter at `index`, counting from 1.
+    fn placeholder(index: usize) -> String {
+        let _ = index;
+        "?".into()
+    }
 }
 
-#[allow(dead_code)]
+/// Describes queries with a connection per database URL, kept for the rest of the compilation.
 pub struct CachingDescribeBlocking<DB: DatabaseExt> {
     connections: Lazy<Mutex<HashMap<String, DB::Connection>>>,
 }
 
-#[allow(dead_code)]
 impl<DB: DatabaseExt> CachingDescribeBlocking<DB> {
     pub const fn new() -> Self {
         CachingDescribeBlocking {
@@ -74,6 +233,41 @@ impl<DB: DatabaseExt> CachingDescribeBlocking<DB> {
     }
 }
 
+impl<DB: DatabaseExt> Default for CachingDescribeBlocking<DB> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Implements [`DatabaseExt`][crate::database::DatabaseExt] for a database, given the Rust
+/// types the query macros map its SQL types to, in order of preference.
+///
+/// A type is chosen for a column or parameter if its `Type::type_info()` equals the SQL type,
+/// or else if it's `Type::compatible()` with it. `T | U` uses `U` for parameters instead of `T`,
+/// e.g. `&str` instead of `String`. The paths are emitted in the code generated by the macros,
+/// so they must resolve in the crates using them, e.g. by being re-exported from the driver.
+///
+/// By default, queries are described with a connection per database URL, kept for the rest of
+/// the compilation; `describe-blocking` names a function to use instead. Bind parameters are `?`
+/// unless `placeholder` gives an expression for the parameter at an index counting from 1.
+///
+/// ```rust,ignore
+/// sqlx_macros_core::impl_database_ext! {
+///     my_driver::MyDb {
+///         bool,
+///         i64,
+///         String | &str,
+///         my_driver::types::Interval,
+///     },
+///     ParamChecking::Strong,
+///     feature-types: _info => None,
+///     row: my_driver::MyDbRow,
+/// }
+///
+/// // in the driver's proc-macro crate, expanding its `query!()`:
+/// sqlx_macros_core::query::expand_input(input, &[QueryDriver::new::<my_driver::MyDb>()])
+/// ```
+#[macro_export]
 macro_rules! impl_database_ext {
     (
         $database:path {
@@ -82,6 +276,7 @@ macro_rules! impl_database_ext {
         ParamChecking::$param_checking:ident,
         feature-types: $ty_info:ident => $get_gate:expr,
         row: $row:path,
+        $(placeholder: $index:ident => $placeholder:expr,)?
         $(describe-blocking: $describe:path,)?
     ) => {
         impl $crate::database::DatabaseExt for $database {
@@ -89,29 +284,39 @@ macro_rules! impl_database_ext {
             const ROW_PATH: &'static str = stringify!($row);
             const PARAM_CHECKING: $crate::database::ParamChecking = $crate::database::ParamChecking::$param_checking;
 
-            fn param_type_for_id(info: &Self::TypeInfo) -> Option<&'static str> {
+            fn param_type_for_id(
+                info: &Self::TypeInfo,
+                crates: $crate::database::TypeCrates,
+            ) -> Option<&'static str> {
                 match () {
                     $(
                         $(#[$meta])?
-                        _ if <$ty as sqlx_core::types::Type<$database>>::type_info() == *info => Some(input_ty!($ty $(, $input)?)),
+                        _ if crates.allows(stringify!($ty))
+                            && <$ty as $crate::sqlx_core::types::Type<$database>>::type_info() == *info => Some($crate::input_ty!($ty $(, $input)?)),
                     )*
                     $(
                         $(#[$meta])?
-                        _ if <$ty as sqlx_core::types::Type<$database>>::compatible(info) => Some(input_ty!($ty $(, $input)?)),
+                        _ if crates.allows(stringify!($ty))
+                            && <$ty as $crate::sqlx_core::types::Type<$database>>::compatible(info) => Some($crate::input_ty!($ty $(, $input)?)),
                     )*
                     _ => None
                 }
             }
 
-            fn return_type_for_id(info: &Self::TypeInfo) -> Option<&'static str> {
+            fn return_type_for_id(
+                info: &Self::TypeInfo,
+                crates: $crate::database::TypeCrates,
+            ) -> Option<&'static str> {
                 match () {
                     $(
                         $(#[$meta])?
-                        _ if <$ty as sqlx_core::types::Type<$database>>::type_info() == *info => return Some(stringify!($ty)),
+                        _ if crates.allows(stringify!($ty))
+                            && <$ty as $crate::sqlx_core::types::Type<$database>>::type_info() == *info => return Some(stringify!($ty)),
                     )*
                     $(
                         $(#[$meta])?
-                        _ if <$ty as sqlx_core::types::Type<$database>>::compatible(info) => return Some(stringify!($ty)),
+                        _ if crates.allows(stringify!($ty))
+                            && <$ty as $crate::sqlx_core::types::Type<$database>>::compatible(info) => return Some(stringify!($ty)),
                     )*
                     _ => None
                 }
@@ -121,17 +326,25 @@ macro_rules! impl_database_ext {
                 $get_gate
             }
 
-            impl_describe_blocking!($database, $($describe)?);
+            $(
+                fn placeholder($index: usize) -> String {
+                    $placeholder
+                }
+            )?
+
+            $crate::impl_describe_blocking!($database, $($describe)?);
         }
     }
 }
 
+#[doc(hidden)]
+#[macro_export]
 macro_rules! impl_describe_blocking {
     ($database:path $(,)?) => {
         fn describe_blocking(
             query: &str,
             database_url: &str,
-        ) -> sqlx_core::Result<sqlx_core::describe::Describe<Self>> {
+        ) -> $crate::sqlx_core::Result<$crate::sqlx_core::describe::Describe<Self>> {
             use $crate::database::CachingDescribeBlocking;
 
             // This can't be a provided method because the `static` can't reference `Self`.
@@ -144,12 +357,14 @@ macro_rules! impl_describe_blocking {
         fn describe_blocking(
             query: &str,
             database_url: &str,
-        ) -> sqlx_core::Result<sqlx_core::describe::Describe<Self>> {
+        ) -> $crate::sqlx_core::Result<$crate::sqlx_core::describe::Describe<Self>> {
             $describe(query, database_url)
         }
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
 macro_rules! input_ty {
     ($ty:ty, $input:ty) => {
         stringify!($input)
@@ -180,3 +395,33 @@ mod fake_sqlx {
     #[cfg(feature = "sqlite")]
     pub use sqlx_sqlite as sqlite;
 }
+
+#[test]
+fn test_type_crates_allows() {
+    let time = TypeCrates::parse_crate("time").unwrap();
+    let chrono = TypeCrates {
+        datetime: Some(DateTimeCrate::Chrono),
+        decimal: Some(DecimalCrate::RustDecimal),
+    };
+
+    assert!(chrono.allows("sqlx::types::chrono::NaiveDate"));
+    assert!(!chrono.allows("sqlx::types::time::Date"));
+    assert!(!time.allows("Vec<sqlx :: types :: chrono :: NaiveDate>"));
+    assert!(time.allows("sqlx::postgres::types::PgTimeTz"));
+    assert!(TypeCrates::default().allows("sqlx::types::time::Date"));
+
+    assert!(chrono.allows("sqlx::types::Decimal"));
+    assert_eq!(
+        chrono.required_feature("Vec<sqlx::types::BigDecimal>"),
+        Some("rust_decimal")
+    );
+    assert!(time.allows("sqlx::types::BigDecimal"));
+
+    assert_eq!(time.or(chrono).datetime, Some(DateTimeCrate::Time));
+    assert_eq!(time.or(chrono).decimal, Some(DecimalCrate::RustDecimal));
+
+    assert_eq!("time".parse(), Ok(DateTimeCrate::Time));
+    assert_eq!("rust_decimal".parse(), Ok(DecimalCrate::RustDecimal));
+    assert!("jiff".parse::<DateTimeCrate>().is_err());
+    assert!("time".parse::<DecimalCrate>().is_err());
+}
@@ -20,18 +20,7 @@ impl_database_ext! {
         // BINARY, VAR_BINARY, BLOB
         Vec<u8>,
 
-        #[cfg(all(feature = "chrono", not(feature = "time")))]
-        sqlx::types::chrono::NaiveTime,
-
-        #[cfg(all(feature = "chrono", not(feature = "time")))]
-        sqlx::types::chrono::NaiveDate,
-
-        #[cfg(all(feature = "chrono", not(feature = "time")))]
-        sqlx::types::chrono::NaiveDateTime,
-
-        #[cfg(all(feature = "chrono", not(feature = "time")))]
-        sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>,
-
+        // `time` is preferred if both are enabled, unless `TypeCrates` says otherwise
         #[cfg(feature = "time")]
         sqlx::types::time::Time,
 
@@ -44,6 +33,18 @@ impl_database_ext! {
         #[cfg(feature = "time")]
         sqlx::types::time::OffsetDateTime,
 
+        #[cfg(feature = "chrono")]
+        sqlx::types::chrono::NaiveTime,
+
+        #[cfg(feature = "chrono")]
+        sqlx::types::chrono::NaiveDate,
+
+        #[cfg(feature = "chrono")]
+        sqlx::types::chrono::NaiveDateTime,
+
+        #[cfg(feature = "chrono")]
+        sqlx::types::chrono::DateTime<sqlx::types::chrono::Utc>,
+
         #[cfg(feature = "bigdecimal")]
         sqlx::types::BigDecimal,
 
@@ -23,6 +23,14 @@ impl_database_ext! {
 
         sqlx::postgres::types::PgLQuery,
 
+        sqlx::postgres::types::PgHstore,
+
+        sqlx::postgres::types::PgCiText,
+
+        sqlx::postgres::types::PgXml,
+
+        sqlx::postgres::types::PgVector,
+
         #[cfg(feature = "uuid")]
         sqlx::types::Uuid,
 
@@ -204,4 +212,5 @@ impl_database_ext! {
     ParamChecking::Strong,
     feature-types: info => info.__type_feature_gate(),
     row: sqlx::postgres::PgRow,
+    placeholder: index => format!("${index}"),
 }
@@ -57,10 +57,15 @@ pub struct SqlxContainerAttributes {
     pub rename_all: Option<RenameAll>,
     pub repr: Option<Ident>,
     pub no_pg_array: bool,
+    pub tag: Option<String>,
+    pub json: bool,
+    pub deny_unknown_columns: bool,
+    pub allow_missing: bool,
 }
 
 pub struct SqlxChildAttributes {
     pub rename: Option<String>,
+    pub aliases: Vec<String>,
     pub default: bool,
     pub flatten: bool,
     pub try_from: Option<Type>,
@@ -74,6 +79,10 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
     let mut type_name = None;
     let mut rename_all = None;
     let mut no_pg_array = None;
+    let mut tag = None;
+    let mut json = None;
+    let mut deny_unknown_columns = None;
+    let mut allow_missing = None;
 
     for attr in input
         .iter()
@@ -95,6 +104,18 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
                                 try_set!(no_pg_array, true, value);
                             }
 
+                            Meta::Path(p) if p.is_ident("json") => {
+                                try_set!(json, true, value);
+                            }
+
+                            Meta::Path(p) if p.is_ident("deny_unknown_columns") => {
+                                try_set!(deny_unknown_columns, true, value);
+                            }
+
+                            Meta::Path(p) if p.is_ident("allow_missing") => {
+                                try_set!(allow_missing, true, value);
+                            }
+
                             Meta::NameValue(MetaNameValue {
                                 path,
                                 lit: Lit::Str(val),
@@ -114,6 +135,12 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
                                 try_set!(rename_all, val, value)
                             }
 
+                            Meta::NameValue(MetaNameValue {
+                                path,
+                                lit: Lit::Str(val),
+                                ..
+                            }) if path.is_ident("tag") => try_set!(tag, val.value(), value),
+
                             Meta::NameValue(MetaNameValue {
                                 path,
                                 lit: Lit::Str(val),
@@ -156,11 +183,16 @@ pub fn parse_container_attributes(input: &[Attribute]) -> syn::Result<SqlxContai
         type_name,
         rename_all,
         no_pg_array: no_pg_array.unwrap_or(false),
+        tag,
+        json: json.unwrap_or(false),
+        deny_unknown_columns: deny_unknown_columns.unwrap_or(false),
+        allow_missing: allow_missing.unwrap_or(false),
     })
 }
 
 pub fn parse_child_attributes(input: &[Attribute]) -> syn::Result<SqlxChildAttributes> {
     let mut rename = None;
+    let mut aliases = Vec::new();
     let mut default = false;
     let mut try_from = None;
     let mut flatten = false;
@@ -181,6 +213,11 @@ pub fn parse_child_attributes(input: &[Attribute]) -> syn::Result<SqlxChildAttri
                             lit: Lit::Str(val),
                             ..
                         }) if path.is_ident("rename") => try_set!(rename, val.value(), value),
+                        Meta::NameValue(MetaNameValue {
+                            path,
+                            lit: Lit::Str(val),
+                            ..
+                        }) if path.is_ident("alias") => aliases.push(val.value()),
                         Meta::NameValue(MetaNameValue {
                             path,
                             lit: Lit::Str(val),
@@ -207,6 +244,7 @@ pub fn parse_child_attributes(input: &[Attribute]) -> syn::Result<SqlxChildAttri
 
     Ok(SqlxChildAttributes {
         rename,
+        aliases,
         default,
         flatten,
         try_from,
@@ -221,6 +259,12 @@ pub fn check_transparent_attributes(
 ) -> syn::Result<SqlxContainerAttributes> {
     let attributes = parse_container_attributes(&input.attrs)?;
 
+    assert_attribute!(
+        attributes.tag.is_none(),
+        "unexpected #[sqlx(tag = ..)]",
+        field
+    );
+
     assert_attribute!(
         attributes.rename_all.is_none(),
         "unexpected #[sqlx(rename_all = ..)]",
@@ -241,6 +285,12 @@ pub fn check_transparent_attributes(
 pub fn check_enum_attributes(input: &DeriveInput) -> syn::Result<SqlxContainerAttributes> {
     let attributes = parse_container_attributes(&input.attrs)?;
 
+    assert_attribute!(
+        attributes.tag.is_none(),
+        "unexpected #[sqlx(tag = ..)]",
+        input
+    );
+
     assert_attribute!(
         !attributes.transparent,
         "unexpected #[sqlx(transparent)]",
@@ -300,6 +350,12 @@ pub fn check_struct_attributes<'a>(
 ) -> syn::Result<SqlxContainerAttributes> {
     let attributes = parse_container_attributes(&input.attrs)?;
 
+    assert_attribute!(
+        attributes.tag.is_none(),
+        "unexpected #[sqlx(tag = ..)]",
+        input
+    );
+
     assert_attribute!(
         !attributes.transparent,
         "unexpected #[sqlx(transparent)]",
@@ -332,3 +388,29 @@ pub fn check_struct_attributes<'a>(
 
     Ok(attributes)
 }
+
+pub fn check_json_attributes(input: &DeriveInput) -> syn::Result<SqlxContainerAttributes> {
+    let attributes = parse_container_attributes(&input.attrs)?;
+
+    assert_attribute!(
+        !attributes.transparent,
+        "unexpected #[sqlx(transparent)]; use one of #[sqlx(json)] or #[sqlx(transparent)]",
+        input
+    );
+
+    assert_attribute!(
+        attributes.type_name.is_none(),
+        "unexpected #[sqlx(type_name = ..)]; #[sqlx(json)] always uses the JSON type",
+        input
+    );
+
+    assert_attribute!(
+        attributes.rename_all.is_none(),
+        "unexpected #[sqlx(rename_all = ..)]; use #[serde(rename_all = ..)] instead",
+        input
+    );
+
+    assert_attribute!(attributes.repr.is_none(), "unexpected #[repr(..)]", input);
+
+    Ok(attributes)
+}
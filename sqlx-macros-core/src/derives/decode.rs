@@ -1,6 +1,7 @@
 use super::attributes::{
-    check_strong_enum_attributes, check_struct_attributes, check_transparent_attributes,
-    check_weak_enum_attributes, parse_child_attributes, parse_container_attributes,
+    check_json_attributes, check_strong_enum_attributes, check_struct_attributes,
+    check_transparent_attributes, check_weak_enum_attributes, parse_child_attributes,
+    parse_container_attributes,
 };
 use super::rename_all;
 use proc_macro2::TokenStream;
@@ -15,6 +16,7 @@ use syn::{
 pub fn expand_derive_decode(input: &DeriveInput) -> syn::Result<TokenStream> {
     let attrs = parse_container_attributes(&input.attrs)?;
     match &input.data {
+        Data::Struct(_) if attrs.json => expand_derive_decode_json(input),
         Data::Struct(DataStruct {
             fields: Fields::Unnamed(FieldsUnnamed { unnamed, .. }),
             ..
@@ -91,6 +93,43 @@ fn expand_derive_decode_transparent(
     Ok(tts)
 }
 
+fn expand_derive_decode_json(input: &DeriveInput) -> syn::Result<TokenStream> {
+    check_json_attributes(input)?;
+
+    let ident = &input.ident;
+
+    let generics = &input.generics;
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    let mut generics = generics.clone();
+    generics
+        .params
+        .insert(0, parse_quote!(DB: ::sqlx::Database));
+    generics.params.insert(0, parse_quote!('r));
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(::sqlx::types::Json<Self>: ::sqlx::decode::Decode<'r, DB>));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote!(
+        #[automatically_derived]
+        impl #impl_generics ::sqlx::decode::Decode<'r, DB> for #ident #ty_generics #where_clause {
+            fn decode(
+                value: <DB as ::sqlx::database::HasValueRef<'r>>::ValueRef,
+            ) -> ::std::result::Result<
+                Self,
+                ::std::boxed::Box<
+                    dyn ::std::error::Error + 'static + ::std::marker::Send + ::std::marker::Sync,
+                >,
+            > {
+                <::sqlx::types::Json<Self> as ::sqlx::decode::Decode<'r, DB>>::decode(value)
+                    .map(|json| json.0)
+            }
+        }
+    ))
+}
+
 fn expand_derive_decode_weak_enum(
     input: &DeriveInput,
     variants: &Punctuated<Variant, Comma>,
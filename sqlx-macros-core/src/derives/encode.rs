@@ -1,6 +1,7 @@
 use super::attributes::{
-    check_strong_enum_attributes, check_struct_attributes, check_transparent_attributes,
-    check_weak_enum_attributes, parse_child_attributes, parse_container_attributes,
+    check_json_attributes, check_strong_enum_attributes, check_struct_attributes,
+    check_transparent_attributes, check_weak_enum_attributes, parse_child_attributes,
+    parse_container_attributes,
 };
 use super::rename_all;
 use proc_macro2::{Span, TokenStream};
@@ -16,6 +17,7 @@ pub fn expand_derive_encode(input: &DeriveInput) -> syn::Result<TokenStream> {
     let args = parse_container_attributes(&input.attrs)?;
 
     match &input.data {
+        Data::Struct(_) if args.json => expand_derive_encode_json(input),
         Data::Struct(DataStruct {
             fields: Fields::Unnamed(FieldsUnnamed { unnamed, .. }),
             ..
@@ -100,6 +102,46 @@ fn expand_derive_encode_transparent(
     ))
 }
 
+fn expand_derive_encode_json(input: &DeriveInput) -> syn::Result<TokenStream> {
+    check_json_attributes(input)?;
+
+    let ident = &input.ident;
+
+    let generics = &input.generics;
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    let lifetime = Lifetime::new("'q", Span::call_site());
+    let mut generics = generics.clone();
+    generics
+        .params
+        .insert(0, LifetimeDef::new(lifetime.clone()).into());
+
+    generics
+        .params
+        .insert(0, parse_quote!(DB: ::sqlx::Database));
+    generics.make_where_clause().predicates.push(parse_quote!(
+        for<'__json> ::sqlx::types::Json<&'__json Self>: ::sqlx::encode::Encode<#lifetime, DB>
+    ));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote!(
+        #[automatically_derived]
+        impl #impl_generics ::sqlx::encode::Encode<#lifetime, DB> for #ident #ty_generics
+        #where_clause
+        {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <DB as ::sqlx::database::HasArguments<#lifetime>>::ArgumentBuffer,
+            ) -> ::sqlx::encode::IsNull {
+                <::sqlx::types::Json<&Self> as ::sqlx::encode::Encode<#lifetime, DB>>::encode(
+                    ::sqlx::types::Json(self),
+                    buf,
+                )
+            }
+        }
+    ))
+}
+
 fn expand_derive_encode_weak_enum(
     input: &DeriveInput,
     variants: &Punctuated<Variant, Comma>,
@@ -0,0 +1,104 @@
+use super::attributes::parse_container_attributes;
+use super::expand_derive_type_encode_decode;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DataStruct, DeriveInput, Fields, FieldsUnnamed};
+
+pub fn expand_derive_id(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let ty =
+        match &input.data {
+            Data::Struct(DataStruct {
+                fields: Fields::Unnamed(FieldsUnnamed { unnamed, .. }),
+                ..
+            }) if unnamed.len() == 1 => &unnamed.first().unwrap().ty,
+            _ => return Err(syn::Error::new_spanned(
+                input,
+                "#[derive(Id)] is only supported on newtype structs, e.g. `struct UserId(i64);`",
+            )),
+        };
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "#[derive(Id)] does not support generic parameters",
+        ));
+    }
+
+    let ident = &input.ident;
+
+    // `Type`, `Encode` and `Decode` are the same as a transparent newtype
+    let mut transparent = input.clone();
+
+    if !parse_container_attributes(&input.attrs)?.transparent {
+        transparent.attrs.push(parse_quote!(#[sqlx(transparent)]));
+    }
+
+    let type_encode_decode = expand_derive_type_encode_decode(&transparent)?;
+
+    let serde = if cfg!(feature = "json") {
+        quote!(
+            #[automatically_derived]
+            impl ::sqlx::__serde::Serialize for #ident {
+                fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+                where
+                    S: ::sqlx::__serde::Serializer,
+                {
+                    <#ty as ::sqlx::__serde::Serialize>::serialize(&self.0, serializer)
+                }
+            }
+
+            #[automatically_derived]
+            impl<'de> ::sqlx::__serde::Deserialize<'de> for #ident {
+                fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+                where
+                    D: ::sqlx::__serde::Deserializer<'de>,
+                {
+                    <#ty as ::sqlx::__serde::Deserialize<'de>>::deserialize(deserializer).map(Self)
+                }
+            }
+        )
+    } else {
+        quote!()
+    };
+
+    Ok(quote!(
+        #type_encode_decode
+
+        #serde
+
+        #[automatically_derived]
+        impl ::sqlx::ty_match::IdType for #ident {
+            type Inner = #ty;
+        }
+
+        #[automatically_derived]
+        impl ::std::fmt::Display for #ident {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        #[automatically_derived]
+        impl ::std::str::FromStr for #ident {
+            type Err = <#ty as ::std::str::FromStr>::Err;
+
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                <#ty as ::std::str::FromStr>::from_str(s).map(Self)
+            }
+        }
+
+        #[automatically_derived]
+        impl ::std::convert::From<#ty> for #ident {
+            fn from(id: #ty) -> Self {
+                Self(id)
+            }
+        }
+
+        #[automatically_derived]
+        impl ::std::convert::From<#ident> for #ty {
+            fn from(id: #ident) -> Self {
+                id.0
+            }
+        }
+    ))
+}
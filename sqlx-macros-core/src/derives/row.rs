@@ -1,12 +1,15 @@
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use syn::{
-    parse_quote, punctuated::Punctuated, token::Comma, Data, DataStruct, DeriveInput, Expr, Field,
-    Fields, FieldsNamed, FieldsUnnamed, Lifetime, Stmt,
+    parse_quote, punctuated::Punctuated, token::Comma, Data, DataEnum, DataStruct, DeriveInput,
+    Expr, Field, Fields, FieldsNamed, FieldsUnnamed, Lifetime, Stmt, Variant, WherePredicate,
 };
 
 use super::{
-    attributes::{parse_child_attributes, parse_container_attributes},
+    attributes::{
+        parse_child_attributes, parse_container_attributes, SqlxChildAttributes,
+        SqlxContainerAttributes,
+    },
     rename_all,
 };
 
@@ -30,7 +33,7 @@ pub fn expand_derive_from_row(input: &DeriveInput) -> syn::Result<TokenStream> {
             "unit structs are not supported",
         )),
 
-        Data::Enum(_) => Err(syn::Error::new_spanned(input, "enums are not supported")),
+        Data::Enum(DataEnum { variants, .. }) => expand_derive_from_row_enum(input, variants),
 
         Data::Union(_) => Err(syn::Error::new_spanned(input, "unions are not supported")),
     }
@@ -65,7 +68,50 @@ fn expand_derive_from_row_struct(
 
     let container_attributes = parse_container_attributes(&input.attrs)?;
 
-    let reads: Vec<Stmt> = fields
+    if container_attributes.tag.is_some() {
+        return Err(syn::Error::new_spanned(
+            input,
+            "#[sqlx(tag = ..)] is only supported on enums",
+        ));
+    }
+
+    let check = if container_attributes.deny_unknown_columns {
+        deny_unknown_columns(fields, &container_attributes, vec![], &ident.to_string())?
+    } else {
+        quote!()
+    };
+
+    let reads = field_reads(fields, &container_attributes, &lifetime, predicates);
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let names = fields.iter().map(|field| &field.ident);
+
+    Ok(quote!(
+        #[automatically_derived]
+        impl #impl_generics ::sqlx::FromRow<#lifetime, R> for #ident #ty_generics #where_clause {
+            fn from_row(row: &#lifetime R) -> ::sqlx::Result<Self> {
+                #check
+
+                #(#reads)*
+
+                ::std::result::Result::Ok(#ident {
+                    #(#names),*
+                })
+            }
+        }
+    ))
+}
+
+/// Generate a `let` statement reading each of the named `fields` from `row`,
+/// adding the trait bounds required to do so to `predicates`.
+fn field_reads(
+    fields: &Punctuated<Field, Comma>,
+    container_attributes: &SqlxContainerAttributes,
+    lifetime: &Lifetime,
+    predicates: &mut Punctuated<WherePredicate, Comma>,
+) -> Vec<Stmt> {
+    fields
         .iter()
         .filter_map(|field| -> Option<Stmt> {
             let id = &field.ident.as_ref()?;
@@ -78,14 +124,22 @@ fn expand_derive_from_row_struct(
                 ));
             }
 
-            let id_s = attributes
-                .rename
-                .or_else(|| Some(id.to_string().trim_start_matches("r#").to_owned()))
-                .map(|s| match container_attributes.rename_all {
-                    Some(pattern) => rename_all(&s, pattern),
-                    None => s,
-                })
-                .unwrap();
+            let id_s = column_name(id, &attributes, container_attributes);
+
+            // with aliases, read from the first of the names present in the row
+            let column: Expr = if attributes.aliases.is_empty() {
+                parse_quote!(#id_s)
+            } else {
+                let aliases = &attributes.aliases;
+
+                parse_quote!(
+                    [#id_s, #(#aliases),*]
+                        .iter()
+                        .copied()
+                        .find(|name| ::sqlx::Row::try_column(row, *name).is_ok())
+                        .unwrap_or(#id_s)
+                )
+            };
 
             let expr: Expr = match (attributes.flatten, attributes.try_from, attributes.json) {
                 // <No attributes>
@@ -94,7 +148,7 @@ fn expand_derive_from_row_struct(
                         .push(parse_quote!(#ty: ::sqlx::decode::Decode<#lifetime, R::Database>));
                     predicates.push(parse_quote!(#ty: ::sqlx::types::Type<R::Database>));
 
-                    parse_quote!(row.try_get(#id_s))
+                    parse_quote!(row.try_get(#column))
                 }
                 // Flatten
                 (true, None, false) => {
@@ -116,7 +170,7 @@ fn expand_derive_from_row_struct(
                         .push(parse_quote!(#try_from: ::sqlx::decode::Decode<#lifetime, R::Database>));
                     predicates.push(parse_quote!(#try_from: ::sqlx::types::Type<R::Database>)); 
 
-                    parse_quote!(row.try_get(#id_s).and_then(|v| <#ty as ::std::convert::TryFrom::<#try_from>>::try_from(v).map_err(|e| ::sqlx::Error::ColumnNotFound("FromRow: try_from failed".to_string()))))
+                    parse_quote!(row.try_get(#column).and_then(|v| <#ty as ::std::convert::TryFrom::<#try_from>>::try_from(v).map_err(|e| ::sqlx::Error::ColumnNotFound("FromRow: try_from failed".to_string()))))
                 }
                 // Try from + Json
                 (false, Some(try_from), true) => {
@@ -125,7 +179,7 @@ fn expand_derive_from_row_struct(
                     predicates.push(parse_quote!(::sqlx::types::Json<#try_from>: ::sqlx::types::Type<R::Database>));
 
                     parse_quote!(
-                        row.try_get::<::sqlx::types::Json<_>, _>(#id_s).and_then(|v|
+                        row.try_get::<::sqlx::types::Json<_>, _>(#column).and_then(|v|
                             <#ty as ::std::convert::TryFrom::<#try_from>>::try_from(v.0)
                             .map_err(|e| ::sqlx::Error::ColumnNotFound("FromRow: try_from failed".to_string()))
                         )
@@ -137,11 +191,11 @@ fn expand_derive_from_row_struct(
                         .push(parse_quote!(::sqlx::types::Json<#ty>: ::sqlx::decode::Decode<#lifetime, R::Database>));
                     predicates.push(parse_quote!(::sqlx::types::Json<#ty>: ::sqlx::types::Type<R::Database>));
 
-                    parse_quote!(row.try_get::<::sqlx::types::Json<_>, _>(#id_s).map(|x| x.0))
+                    parse_quote!(row.try_get::<::sqlx::types::Json<_>, _>(#column).map(|x| x.0))
                 },
             };
 
-            if attributes.default {
+            if attributes.default || container_attributes.allow_missing {
                 Some(parse_quote!(let #id: #ty = #expr.or_else(|e| match e {
                 ::sqlx::Error::ColumnNotFound(_) => {
                     ::std::result::Result::Ok(Default::default())
@@ -154,30 +208,80 @@ fn expand_derive_from_row_struct(
                 ))
             }
         })
-        .collect();
+        .collect()
+}
 
-    let (impl_generics, _, where_clause) = generics.split_for_impl();
+/// The name of the column read into a field, before any aliases.
+fn column_name(
+    id: &Ident,
+    attributes: &SqlxChildAttributes,
+    container_attributes: &SqlxContainerAttributes,
+) -> String {
+    attributes
+        .rename
+        .clone()
+        .or_else(|| Some(id.to_string().trim_start_matches("r#").to_owned()))
+        .map(|s| match container_attributes.rename_all {
+            Some(pattern) => rename_all(&s, pattern),
+            None => s,
+        })
+        .unwrap()
+}
 
-    let names = fields.iter().map(|field| &field.ident);
+/// Generate a check that every column in `row` is one of `known` for `#[sqlx(deny_unknown_columns)]`,
+/// adding the names of the columns read by the named `fields` to `known`.
+fn deny_unknown_columns(
+    fields: &Punctuated<Field, Comma>,
+    container_attributes: &SqlxContainerAttributes,
+    mut known: Vec<String>,
+    ident_s: &str,
+) -> syn::Result<TokenStream> {
+    for field in fields {
+        let attributes = parse_child_attributes(&field.attrs)?;
 
-    Ok(quote!(
-        #[automatically_derived]
-        impl #impl_generics ::sqlx::FromRow<#lifetime, R> for #ident #ty_generics #where_clause {
-            fn from_row(row: &#lifetime R) -> ::sqlx::Result<Self> {
-                #(#reads)*
+        if attributes.flatten {
+            return Err(syn::Error::new_spanned(
+                field,
+                "#[sqlx(flatten)] cannot be used with #[sqlx(deny_unknown_columns)]",
+            ));
+        }
 
-                ::std::result::Result::Ok(#ident {
-                    #(#names),*
-                })
+        if attributes.skip {
+            continue;
+        }
+
+        if let Some(id) = &field.ident {
+            known.push(column_name(id, &attributes, container_attributes));
+            known.extend(attributes.aliases);
+        }
+    }
+
+    Ok(quote! {
+        for column in ::sqlx::Row::columns(row) {
+            let name = ::sqlx::Column::name(column);
+
+            if ![#(#known),*].contains(&name) {
+                return ::std::result::Result::Err(::sqlx::Error::Decode(
+                    ::std::format!("unexpected column {:?} for {}", name, #ident_s).into(),
+                ));
             }
         }
-    ))
+    })
 }
 
 fn expand_derive_from_row_struct_unnamed(
     input: &DeriveInput,
     fields: &Punctuated<Field, Comma>,
 ) -> syn::Result<TokenStream> {
+    let container_attributes = parse_container_attributes(&input.attrs)?;
+
+    if container_attributes.deny_unknown_columns || container_attributes.allow_missing {
+        return Err(syn::Error::new_spanned(
+            input,
+            "#[sqlx(deny_unknown_columns)] and #[sqlx(allow_missing)] require named fields",
+        ));
+    }
+
     let ident = &input.ident;
 
     let generics = &input.generics;
@@ -228,3 +332,117 @@ fn expand_derive_from_row_struct_unnamed(
         }
     ))
 }
+
+fn expand_derive_from_row_enum(
+    input: &DeriveInput,
+    variants: &Punctuated<Variant, Comma>,
+) -> syn::Result<TokenStream> {
+    let ident = &input.ident;
+    let ident_s = ident.to_string();
+
+    let generics = &input.generics;
+
+    let (lifetime, provided) = generics
+        .lifetimes()
+        .next()
+        .map(|def| (def.lifetime.clone(), false))
+        .unwrap_or_else(|| (Lifetime::new("'a", Span::call_site()), true));
+
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    let mut generics = generics.clone();
+    generics.params.insert(0, parse_quote!(R: ::sqlx::Row));
+
+    if provided {
+        generics.params.insert(0, parse_quote!(#lifetime));
+    }
+
+    let predicates = &mut generics.make_where_clause().predicates;
+
+    predicates.push(parse_quote!(&#lifetime ::std::primitive::str: ::sqlx::ColumnIndex<R>));
+    predicates.push(parse_quote!(
+        ::std::string::String: ::sqlx::decode::Decode<#lifetime, R::Database>
+    ));
+    predicates.push(parse_quote!(::std::string::String: ::sqlx::types::Type<R::Database>));
+
+    let container_attributes = parse_container_attributes(&input.attrs)?;
+
+    let tag =
+        match &container_attributes.tag {
+            Some(tag) => tag,
+            None => return Err(syn::Error::new_spanned(
+                input,
+                "enums require #[sqlx(tag = \"..\")] naming the column that selects the variant",
+            )),
+        };
+
+    let mut arms = Vec::with_capacity(variants.len());
+
+    for variant in variants {
+        let variant_ident = &variant.ident;
+
+        let tag_value = parse_child_attributes(&variant.attrs)?
+            .rename
+            .unwrap_or_else(|| variant_ident.to_string());
+
+        let empty = Punctuated::new();
+
+        let fields = match &variant.fields {
+            Fields::Named(FieldsNamed { named, .. }) => named,
+            Fields::Unit => &empty,
+            Fields::Unnamed(_) => {
+                return Err(syn::Error::new_spanned(
+                    variant,
+                    "tuple variants are not supported",
+                ))
+            }
+        };
+
+        let check = if container_attributes.deny_unknown_columns {
+            let variant_s = format!("{ident_s}::{variant_ident}");
+
+            deny_unknown_columns(fields, &container_attributes, vec![tag.clone()], &variant_s)?
+        } else {
+            quote!()
+        };
+
+        let reads = field_reads(fields, &container_attributes, &lifetime, predicates);
+        let names = fields.iter().map(|field| &field.ident);
+
+        // braces are also accepted for unit variants
+        arms.push(quote! {
+            #tag_value => {
+                #check
+
+                #(#reads)*
+
+                ::std::result::Result::Ok(#ident::#variant_ident {
+                    #(#names),*
+                })
+            }
+        });
+    }
+
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote!(
+        #[automatically_derived]
+        impl #impl_generics ::sqlx::FromRow<#lifetime, R> for #ident #ty_generics #where_clause {
+            fn from_row(row: &#lifetime R) -> ::sqlx::Result<Self> {
+                let tag: ::std::string::String = row.try_get(#tag)?;
+
+                match &*tag {
+                    #(#arms)*
+
+                    _ => ::std::result::Result::Err(::sqlx::Error::Decode(
+                        ::std::format!(
+                            "invalid value {:?} in column {:?} for enum {}",
+                            tag, #tag, #ident_s
+                        )
+                        .into(),
+                    )),
+                }
+            }
+        }
+    ))
+}
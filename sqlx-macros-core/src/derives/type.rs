@@ -1,6 +1,6 @@
 use super::attributes::{
-    check_strong_enum_attributes, check_struct_attributes, check_transparent_attributes,
-    check_weak_enum_attributes, parse_container_attributes, TypeName,
+    check_json_attributes, check_strong_enum_attributes, check_struct_attributes,
+    check_transparent_attributes, check_weak_enum_attributes, parse_container_attributes, TypeName,
 };
 use proc_macro2::{Ident, TokenStream};
 use quote::{quote, quote_spanned};
@@ -8,12 +8,13 @@ use syn::punctuated::Punctuated;
 use syn::token::Comma;
 use syn::{
     parse_quote, Data, DataEnum, DataStruct, DeriveInput, Field, Fields, FieldsNamed,
-    FieldsUnnamed, Variant,
+    FieldsUnnamed, GenericArgument, PathArguments, Type, Variant,
 };
 
 pub fn expand_derive_type(input: &DeriveInput) -> syn::Result<TokenStream> {
     let attrs = parse_container_attributes(&input.attrs)?;
     match &input.data {
+        Data::Struct(_) if attrs.json => expand_derive_has_sql_type_json(input),
         Data::Struct(DataStruct {
             fields: Fields::Unnamed(FieldsUnnamed { unnamed, .. }),
             ..
@@ -90,7 +91,7 @@ fn expand_derive_has_sql_type_transparent(
             }
         );
 
-        if cfg!(feature = "postgres") && !attr.no_pg_array {
+        if cfg!(feature = "postgres") && !attr.no_pg_array && !is_pg_array(ty) {
             tokens.extend(quote!(
                 #[automatically_derived]
                 impl #array_impl_generics ::sqlx::postgres::PgHasArrayType for #ident #ty_generics
@@ -123,6 +124,101 @@ fn expand_derive_has_sql_type_transparent(
     Ok(tts)
 }
 
+fn expand_derive_has_sql_type_json(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let attr = check_json_attributes(input)?;
+
+    let ident = &input.ident;
+
+    let generics = &input.generics;
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    let mut generics = generics.clone();
+    let array_generics = generics.clone();
+
+    generics
+        .params
+        .insert(0, parse_quote!(DB: ::sqlx::Database));
+    generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(::sqlx::types::Json<Self>: ::sqlx::Type<DB>));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let mut tokens = quote!(
+        #[automatically_derived]
+        impl #impl_generics ::sqlx::Type<DB> for #ident #ty_generics #where_clause {
+            fn type_info() -> DB::TypeInfo {
+                <::sqlx::types::Json<Self> as ::sqlx::Type<DB>>::type_info()
+            }
+
+            fn compatible(ty: &DB::TypeInfo) -> ::std::primitive::bool {
+                <::sqlx::types::Json<Self> as ::sqlx::Type<DB>>::compatible(ty)
+            }
+        }
+    );
+
+    if cfg!(feature = "postgres") && !attr.no_pg_array {
+        let (array_impl_generics, _, array_where_clause) = array_generics.split_for_impl();
+
+        tokens.extend(quote!(
+            #[automatically_derived]
+            impl #array_impl_generics ::sqlx::postgres::PgHasArrayType for #ident #ty_generics
+            #array_where_clause {
+                fn array_type_info() -> ::sqlx::postgres::PgTypeInfo {
+                    <::sqlx::types::Json<Self> as ::sqlx::postgres::PgHasArrayType>::array_type_info()
+                }
+            }
+        ));
+    }
+
+    Ok(tokens)
+}
+
+/// Returns `true` if `ty` is (or is an `Option` of) a type that Postgres would encode as an array.
+///
+/// Postgres doesn't support arrays of arrays, so a `PgHasArrayType` impl delegating to one of
+/// these would have an impossible-to-satisfy `where` bound and fail to compile.
+/// `Vec<u8>` and `[u8; N]` are the exception as they're encoded as `BYTEA`.
+fn is_pg_array(ty: &Type) -> bool {
+    fn is_u8(ty: &Type) -> bool {
+        matches!(ty, Type::Path(path) if path.path.is_ident("u8"))
+    }
+
+    fn first_type_arg(args: &PathArguments) -> Option<&Type> {
+        match args {
+            PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+                GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            }),
+            _ => None,
+        }
+    }
+
+    match ty {
+        Type::Array(array) => !is_u8(&array.elem),
+        Type::Slice(slice) => !is_u8(&slice.elem),
+        Type::Reference(reference) => is_pg_array(&reference.elem),
+        Type::Group(group) => is_pg_array(&group.elem),
+        Type::Paren(paren) => is_pg_array(&paren.elem),
+        Type::Path(path) => {
+            let segment = match path.path.segments.last() {
+                Some(segment) => segment,
+                None => return false,
+            };
+
+            match (
+                segment.ident.to_string().as_str(),
+                first_type_arg(&segment.arguments),
+            ) {
+                ("Vec", Some(elem)) => !is_u8(elem),
+                ("Option", Some(inner)) => is_pg_array(inner),
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
 fn expand_derive_has_sql_type_weak_enum(
     input: &DeriveInput,
     variants: &Punctuated<Variant, Comma>,
@@ -167,7 +263,7 @@ fn expand_derive_has_sql_type_strong_enum(
                 }
 
                 fn compatible(ty: &::sqlx::mysql::MySqlTypeInfo) -> ::std::primitive::bool {
-                    *ty == ::sqlx::mysql::MySqlTypeInfo::__enum()
+                    ::sqlx::mysql::MySqlTypeInfo::__enum_compatible(ty)
                 }
             }
         ));
@@ -26,7 +26,7 @@ pub type Error = Box<dyn std::error::Error>;
 pub type Result<T> = std::result::Result<T, Error>;
 
 mod common;
-mod database;
+pub mod database;
 
 pub mod derives;
 pub mod query;
@@ -37,6 +37,10 @@ pub mod test_attr;
 #[cfg(feature = "migrate")]
 pub mod migrate;
 
+// for `impl_database_ext!()` in drivers outside of SQLx
+#[doc(hidden)]
+pub use sqlx_core;
+
 pub const FOSS_DRIVERS: &[QueryDriver] = &[
     #[cfg(feature = "mysql")]
     QueryDriver::new::<sqlx_mysql::MySql>(),
@@ -150,6 +150,8 @@ pub(crate) fn expand_migrator(path: &Path) -> crate::Result<TokenStream> {
             ]),
             ignore_missing: false,
             locking: true,
+            audit_log: None,
+            skipped: ::std::borrow::Cow::Borrowed(&[]),
         }
     })
 }
@@ -1,4 +1,5 @@
-use crate::database::DatabaseExt;
+use crate::database::{DatabaseExt, TypeCrates};
+use crate::query::input::RecordType;
 use crate::query::QueryMacroInput;
 use either::Either;
 use proc_macro2::{Ident, TokenStream};
@@ -12,6 +13,7 @@ use syn::{Expr, ExprCast, ExprGroup, ExprType, Type};
 pub fn quote_args<DB: DatabaseExt>(
     input: &QueryMacroInput,
     info: &Describe<DB>,
+    crates: TypeCrates,
 ) -> crate::Result<TokenStream> {
     let db_path = DB::db_path();
 
@@ -43,65 +45,26 @@ pub fn quote_args<DB: DatabaseExt>(
             TokenStream::new()
         }
 
-        Some(Either::Left(params)) => {
-            params
-                .iter()
-                .zip(arg_names.iter().zip(&input.arg_exprs))
-                .enumerate()
-                .map(|(i, (param_ty, (name, expr)))| -> crate::Result<_> {
-                    let param_ty = match get_type_override(expr) {
-                        // cast will fail to compile if the type does not match
-                        // and we strip casts to wildcard
-                        Some((_, false)) => return Ok(quote!()),
-                        // type ascription is deprecated
-                        Some((ty, true)) => return Ok(create_warning(name.clone(), &ty, &expr)),
-                        None => {
-                            DB::param_type_for_id(&param_ty)
-                                .ok_or_else(|| {
-                                    if let Some(feature_gate) = <DB as DatabaseExt>::get_feature_gate(&param_ty) {
-                                        format!(
-                                            "optional sqlx feature `{}` required for type {} of param #{}",
-                                            feature_gate,
-                                            param_ty,
-                                            i + 1,
-                                        )
-                                    } else {
-                                        format!("unsupported type {} for param #{}", param_ty, i + 1)
-                                    }
-                                })?
-                                .parse::<TokenStream>()
-                                .map_err(|_| format!("Rust type mapping for {param_ty} not parsable"))?
-
-                        }
-                    };
-
-                    Ok(quote_spanned!(expr.span() =>
-                        // this shouldn't actually run
-                        if false {
-                            use ::sqlx::ty_match::{WrapSameExt as _, MatchBorrowExt as _};
-
-                            // evaluate the expression only once in case it contains moves
-                            let expr = ::sqlx::ty_match::dupe_value(#name);
-
-                            // if `expr` is `Option<T>`, get `Option<$ty>`, otherwise `$ty`
-                            let ty_check = ::sqlx::ty_match::WrapSame::<#param_ty, _>::new(&expr).wrap_same();
-
-                            // if `expr` is `&str`, convert `String` to `&str`
-                            let (mut _ty_check, match_borrow) = ::sqlx::ty_match::MatchBorrow::new(ty_check, &expr);
-
-                            _ty_check = match_borrow.match_borrow();
-
-                            // this causes move-analysis to effectively ignore this block
-                            ::std::panic!();
-                        }
-                    ))
-                })
-                .collect::<crate::Result<TokenStream>>()?
-        }
+        Some(Either::Left(params)) => params
+            .iter()
+            .zip(arg_names.iter().zip(&input.arg_exprs))
+            .enumerate()
+            .map(|(i, (param_ty, (name, expr)))| {
+                quote_arg_check::<DB>(i, param_ty, name, expr, crates)
+            })
+            .collect::<crate::Result<TokenStream>>()?,
     };
 
     let args_count = input.arg_exprs.len();
 
+    // the declared `query()` moves its arguments into the query it returns
+    let add_args = if matches!(input.record_type, RecordType::Declared(_)) {
+        let arg_expr = &input.arg_exprs;
+        quote!(#(query_args.add(#arg_expr);)*)
+    } else {
+        quote!(#(query_args.add(#arg_name);)*)
+    };
+
     Ok(quote! {
         #arg_bindings
 
@@ -112,10 +75,67 @@ pub fn quote_args<DB: DatabaseExt>(
             #args_count,
             0 #(+ ::sqlx::encode::Encode::<#db_path>::size_hint(#arg_name))*
         );
-        #(query_args.add(#arg_name);)*
+        #add_args
     })
 }
 
+/// Returns a tokenstream which typechecks the argument `name`, bound to `expr`, against the type
+/// of parameter `i` of the query.
+pub fn quote_arg_check<DB: DatabaseExt>(
+    i: usize,
+    param_ty: &DB::TypeInfo,
+    name: &Ident,
+    expr: &Expr,
+    crates: TypeCrates,
+) -> crate::Result<TokenStream> {
+    let param_ty = match get_type_override(expr) {
+        // cast will fail to compile if the type does not match
+        // and we strip casts to wildcard
+        Some((_, false)) => return Ok(quote!()),
+        // type ascription is deprecated
+        Some((ty, true)) => return Ok(create_warning(name.clone(), &ty, &expr)),
+        None => DB::param_type_for_id(&param_ty, crates)
+            .ok_or_else(|| {
+                if let Some(feature_gate) = <DB as DatabaseExt>::get_feature_gate(&param_ty) {
+                    format!(
+                        "optional sqlx feature `{}` required for type {} of param #{}",
+                        feature_gate,
+                        param_ty,
+                        i + 1,
+                    )
+                } else {
+                    format!("unsupported type {} for param #{}", param_ty, i + 1)
+                }
+            })?
+            .parse::<TokenStream>()
+            .map_err(|_| format!("Rust type mapping for {param_ty} not parsable"))?,
+    };
+
+    Ok(quote_spanned!(expr.span() =>
+        // this shouldn't actually run
+        if false {
+            use ::sqlx::ty_match::{WrapSameExt as _, MatchBorrowExt as _, MatchIdExt as _};
+
+            // evaluate the expression only once in case it contains moves
+            let expr = ::sqlx::ty_match::dupe_value(#name);
+
+            // if `expr` is a `#[derive(Id)]` newtype, check its inner type instead
+            let expr = ::sqlx::ty_match::MatchId::new(&expr).match_id();
+
+            // if `expr` is `Option<T>`, get `Option<$ty>`, otherwise `$ty`
+            let ty_check = ::sqlx::ty_match::WrapSame::<#param_ty, _>::new(&expr).wrap_same();
+
+            // if `expr` is `&str`, convert `String` to `&str`
+            let (mut _ty_check, match_borrow) = ::sqlx::ty_match::MatchBorrow::new(ty_check, &expr);
+
+            _ty_check = match_borrow.match_borrow();
+
+            // this causes move-analysis to effectively ignore this block
+            ::std::panic!();
+        }
+    ))
+}
+
 fn create_warning(name: Ident, ty: &Type, expr: &Expr) -> TokenStream {
     let Expr::Type(ExprType { expr: stripped, .. }) = expr else {
         return quote!();
@@ -156,7 +176,7 @@ fn get_type_override(expr: &Expr) -> Option<(&Type, bool)> {
     }
 }
 
-fn strip_wildcard(expr: Expr) -> Expr {
+pub fn strip_wildcard(expr: Expr) -> Expr {
     match expr {
         Expr::Group(ExprGroup {
             attrs,
@@ -0,0 +1,103 @@
+//! The subset of `sqlx.toml` read by the query macros, from the directory of the crate being
+//! compiled or else the nearest of its ancestors, e.g. the root of its workspace:
+//!
+//! ```toml
+//! [soft-delete]
+//! tables = ["users", "posts"]
+//! # optional, defaults to `deleted_at`
+//! column = "deleted_at"
+//!
+//! [prepare]
+//! json-format = "compact"
+//!
+//! [macros]
+//! datetime-crate = "chrono"
+//! decimal-crate = "rust_decimal"
+//! ```
+
+use std::path::Path;
+
+use serde::Deserialize;
+use sqlx_core::query_data::JsonFormat;
+
+use crate::database::{DateTimeCrate, DecimalCrate};
+
+/// The soft-delete column if `column` isn't set under `[soft-delete]`.
+pub const DEFAULT_SOFT_DELETE_COLUMN: &str = "deleted_at";
+
+/// The subset of `sqlx.toml` read by the query macros.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct Config {
+    pub soft_delete: Option<SoftDeleteConfig>,
+    #[serde(default)]
+    pub prepare: PrepareConfig,
+    #[serde(default)]
+    pub macros: MacrosConfig,
+}
+
+/// The `[prepare]` section, most of which is only read by `sqlx prepare`.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct PrepareConfig {
+    #[serde(default)]
+    pub json_format: JsonFormat,
+}
+
+/// The `[macros]` section.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct MacrosConfig {
+    pub datetime_crate: Option<DateTimeCrate>,
+    pub decimal_crate: Option<DecimalCrate>,
+}
+
+/// The `[soft-delete]` section, for the soft-delete lint and `soft_delete_column!()`.
+#[derive(Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct SoftDeleteConfig {
+    pub tables: Vec<String>,
+    #[serde(default = "default_column")]
+    pub column: String,
+}
+
+fn default_column() -> String {
+    DEFAULT_SOFT_DELETE_COLUMN.into()
+}
+
+impl Config {
+    /// Read the `sqlx.toml` at `path`.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+
+        toml::from_str(&contents).map_err(|e| format!("failed to parse {}: {e}", path.display()))
+    }
+
+    /// The column marking soft-deleted rows.
+    pub fn soft_delete_column(&self) -> &str {
+        self.soft_delete
+            .as_ref()
+            .map_or(DEFAULT_SOFT_DELETE_COLUMN, |soft_delete| {
+                &soft_delete.column
+            })
+    }
+}
+
+#[test]
+fn test_parse_json_format() {
+    let config: Config = toml::from_str("[prepare]\ntolerance = true\n").unwrap();
+    assert_eq!(config.prepare.json_format, JsonFormat::Pretty);
+
+    let config: Config = toml::from_str("[prepare]\njson-format = \"compact\"\n").unwrap();
+    assert_eq!(config.prepare.json_format, JsonFormat::Compact);
+}
+
+#[test]
+fn test_soft_delete_column() {
+    assert_eq!(Config::default().soft_delete_column(), "deleted_at");
+
+    let config: Config =
+        toml::from_str("[soft-delete]\ntables = [\"users\"]\ncolumn = \"removed_at\"\n").unwrap();
+    assert_eq!(config.soft_delete_column(), "removed_at");
+}
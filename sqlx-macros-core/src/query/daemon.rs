@@ -0,0 +1,71 @@
+//! Describing queries with the describe daemon of `sqlx-cli` instead of connecting to the
+//! database, for builds in sandboxes that don't allow the compiler network access, e.g. with
+//! Bazel or Nix. The daemon is started with `sqlx describe-daemon --socket <path>`, or by
+//! `cargo sqlx prepare --daemon`, and used by setting `SQLX_DESCRIBE_SOCKET` to the path.
+//!
+//! The daemon reads a request per line as JSON, `{"database_url": "...", "query": "..."}`, and
+//! writes a response per line, `{"data": ...}` with the query data in the format of the files in
+//! `.sqlx`, or `{"error": "..."}`.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::query::data::DynQueryData;
+
+#[derive(Serialize)]
+struct Request<'a> {
+    database_url: &'a str,
+    query: &'a str,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Response {
+    Data(DynQueryData),
+    Error(String),
+}
+
+/// Describe `query` with the daemon listening on `socket`, which connects to `database_url`.
+#[cfg(unix)]
+pub fn describe(socket: &Path, database_url: &str, query: &str) -> crate::Result<DynQueryData> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket).map_err(|e| {
+        format!(
+            "failed to connect to the describe daemon at {} (SQLX_DESCRIBE_SOCKET); \
+             is `sqlx describe-daemon` running? {e}",
+            socket.display()
+        )
+    })?;
+
+    let mut request = serde_json::to_vec(&Request {
+        database_url,
+        query,
+    })?;
+    request.push(b'\n');
+    stream.write_all(&request)?;
+
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)?;
+
+    if response.is_empty() {
+        return Err("the describe daemon closed the connection without a response".into());
+    }
+
+    match serde_json::from_str(&response)? {
+        Response::Data(data) if data.query == query => Ok(data),
+        Response::Data(_) => {
+            Err("the describe daemon responded with data for another query".into())
+        }
+        Response::Error(e) => Err(e.into()),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn describe(socket: &Path, database_url: &str, query: &str) -> crate::Result<DynQueryData> {
+    let _ = (socket, database_url, query);
+
+    Err("SQLX_DESCRIBE_SOCKET is set, but the describe daemon is only supported on Unix".into())
+}
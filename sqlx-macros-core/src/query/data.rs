@@ -1,6 +1,8 @@
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display, Formatter};
-use std::fs;
+use std::fs::{self, OpenOptions};
+use std::hash::{Hash, Hasher};
 use std::io::Write as _;
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
@@ -11,15 +13,17 @@ use serde::{Serialize, Serializer};
 
 use sqlx_core::database::Database;
 use sqlx_core::describe::Describe;
+use sqlx_core::query_data::{self, JsonFormat};
 
 use crate::database::DatabaseExt;
+use crate::query::daemon;
 
 #[derive(serde::Serialize)]
 #[serde(bound(serialize = "Describe<DB>: serde::Serialize"))]
 #[derive(Debug)]
 pub struct QueryData<DB: Database> {
     db_name: SerializeDbName<DB>,
-    #[allow(dead_code)]
+    #[serde(serialize_with = "serialize_query")]
     pub(super) query: String,
     pub(super) describe: Describe<DB>,
     pub(super) hash: String,
@@ -31,11 +35,19 @@ impl<DB: Database> QueryData<DB> {
             db_name: SerializeDbName::default(),
             query: query.into(),
             describe,
-            hash: hash_string(query),
+            hash: query_data::hash_query(query),
         }
     }
 }
 
+/// Saves the query with `\n` line endings, so its data is the same for every checkout.
+fn serialize_query<S>(query: &str, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&query_data::normalize_line_endings(query))
+}
+
 struct SerializeDbName<DB>(PhantomData<DB>);
 
 impl<DB> Default for SerializeDbName<DB> {
@@ -68,6 +80,52 @@ impl<DB: Database> Serialize for SerializeDbName<DB> {
 static OFFLINE_DATA_CACHE: Lazy<Mutex<HashMap<PathBuf, DynQueryData>>> =
     Lazy::new(Default::default);
 
+/// The query data described from the database, by migrations fingerprint, database URL and
+/// query, so invocations with the same query only describe it once.
+///
+/// A proc-macro server that outlives one compilation, like rust-analyzer's, keeps this cache;
+/// keying it on the migrations describes the queries again once the schema is migrated.
+static LIVE_DATA_CACHE: Lazy<Mutex<HashMap<(u64, String, String), DynQueryData>>> =
+    Lazy::new(Default::default);
+
+/// The query data files saved by this compilation.
+static SAVED_DATA_FILES: Lazy<Mutex<HashSet<PathBuf>>> = Lazy::new(Default::default);
+
+/// The path to read or write query data at, canonicalized so that different spellings of the
+/// same path share the caches above, e.g. differing in case on a case-insensitive filesystem.
+///
+/// On Windows, this is also the extended-length form of the path (`\\?\C:\...`), which isn't
+/// limited to 260 characters like the paths of deeply nested workspaces may otherwise be.
+/// The path is used as-is if it doesn't exist.
+fn resolve_data_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_owned())
+}
+
+/// A fingerprint of the migrations directory `dir`, from the name, size and modification time of
+/// each file in it, which changes when a migration is added, removed or edited.
+fn migrations_fingerprint(dir: &Path) -> u64 {
+    let mut files: Vec<_> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok()?;
+            Some((entry.file_name(), metadata.len(), metadata.modified().ok()))
+        })
+        .collect();
+    files.sort();
+
+    let mut hasher = DefaultHasher::new();
+    dir.hash(&mut hasher);
+    files.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether `query` is the query of saved data, which has `\n` line endings.
+fn same_query(query: &str, saved: &str) -> bool {
+    query_data::normalize_line_endings(query) == query_data::normalize_line_endings(saved)
+}
+
 /// Offline query data
 #[derive(Clone, serde::Deserialize)]
 pub struct DynQueryData {
@@ -81,7 +139,7 @@ impl DynQueryData {
     /// Loads a query given the path to its "query-<hash>.json" file. Subsequent calls for the same
     /// path are retrieved from an in-memory cache.
     pub fn from_data_file(path: impl AsRef<Path>, query: &str) -> crate::Result<Self> {
-        let path = path.as_ref();
+        let path = &*resolve_data_path(path.as_ref());
 
         let mut cache = OFFLINE_DATA_CACHE
             .lock()
@@ -92,7 +150,7 @@ impl DynQueryData {
                 guard
             });
         if let Some(cached) = cache.get(path).cloned() {
-            if query != cached.query {
+            if !same_query(query, &cached.query) {
                 return Err("hash collision for saved query data".into());
             }
             return Ok(cached);
@@ -115,7 +173,7 @@ impl DynQueryData {
             .map_err(|e| format!("failed to read saved query path {}: {}", path.display(), e))?;
         let dyn_data: DynQueryData = serde_json::from_str(&offline_data_contents)?;
 
-        if query != dyn_data.query {
+        if !same_query(query, &dyn_data.query) {
             return Err("hash collision for saved query data".into());
         }
 
@@ -128,6 +186,51 @@ impl<DB: DatabaseExt> QueryData<DB>
 where
     Describe<DB>: serde::Serialize + serde::de::DeserializeOwned,
 {
+    /// Describes `query` with the database at `database_url`, or reuses the description from an
+    /// earlier invocation with the same query and migrations.
+    ///
+    /// With `SQLX_DESCRIBE_SOCKET` set, the query is described by the describe daemon instead;
+    /// see [`daemon`].
+    pub fn from_db(query: &str, database_url: &str) -> crate::Result<Self> {
+        let migrations =
+            Path::new(&super::env("CARGO_MANIFEST_DIR").unwrap_or_default()).join("migrations");
+        let key = (
+            migrations_fingerprint(&migrations),
+            database_url.to_owned(),
+            query.to_owned(),
+        );
+
+        let cached = LIVE_DATA_CACHE
+            .lock()
+            .ok()
+            .and_then(|cache| cache.get(&key).cloned());
+
+        if let Some(dyn_data) = cached {
+            return Self::from_dyn_data(dyn_data);
+        }
+
+        let data = match super::env("SQLX_DESCRIBE_SOCKET") {
+            Ok(socket) => {
+                Self::from_dyn_data(daemon::describe(socket.as_ref(), database_url, query)?)?
+            }
+            Err(_) => Self::from_describe(query, DB::describe_blocking(query, database_url)?),
+        };
+
+        if let Ok(mut cache) = LIVE_DATA_CACHE.lock() {
+            cache.insert(
+                key,
+                DynQueryData {
+                    db_name: DB::NAME.to_owned(),
+                    query: data.query.clone(),
+                    describe: serde_json::to_value(&data.describe)?,
+                    hash: data.hash.clone(),
+                },
+            );
+        }
+
+        Ok(data)
+    }
+
     pub fn from_dyn_data(dyn_data: DynQueryData) -> crate::Result<Self> {
         assert!(!dyn_data.db_name.is_empty());
         assert!(!dyn_data.hash.is_empty());
@@ -154,35 +257,127 @@ where
         &self,
         dir: impl AsRef<Path>,
         tmp_dir: impl AsRef<Path>,
+        format: JsonFormat,
     ) -> crate::Result<()> {
+        let path = resolve_data_path(dir.as_ref()).join(format!("query-{}.json", self.hash));
+        let tmp_dir = resolve_data_path(tmp_dir.as_ref());
+
+        // Another invocation with the same query already saved the same data.
+        let saved = SAVED_DATA_FILES
+            .lock()
+            .is_ok_and(|mut saved| !saved.insert(path.clone()));
+
+        if saved {
+            // `cargo sqlx prepare` reports duplicate queries from this file; failing to write to
+            // it doesn't affect the query data
+            let _ = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(tmp_dir.join(query_data::DUPLICATES_FILE_NAME))
+                .and_then(|mut file| writeln!(file, "{}", self.hash));
+
+            return Ok(());
+        }
+
         // Output to a temporary file first, then move it atomically to avoid clobbering
         // other invocations trying to write to the same path.
 
         // Use a temp directory inside the workspace to avoid potential issues
-        // with persisting the file across filesystems.
-        let mut tmp_file = tempfile::NamedTempFile::new_in(tmp_dir)
+        // with persisting the file across filesystems, with a directory for each process
+        // so that concurrent builds don't see each other's partially written files.
+        let staging_dir = tmp_dir.join(format!("staging-{}", std::process::id()));
+        fs::create_dir_all(&staging_dir)
+            .map_err(|err| format!("failed to create query file staging directory: {err:?}"))?;
+
+        let mut tmp_file = tempfile::NamedTempFile::new_in(&staging_dir)
             .map_err(|err| format!("failed to create query file: {err:?}"))?;
 
-        serde_json::to_writer_pretty(tmp_file.as_file_mut(), self)
-            .map_err(|err| format!("failed to serialize query data to file: {err:?}"))?;
-        // Ensure there is a newline at the end of the JSON file to avoid accidental modification by IDE
-        // and make github diff tool happier
+        let contents = serde_json::to_value(self)
+            .and_then(|value| format.to_vec(value))
+            .map_err(|err| format!("failed to serialize query data: {err:?}"))?;
+
         tmp_file
             .as_file_mut()
-            .write_all(b"\n")
-            .map_err(|err| format!("failed to append a newline to file: {err:?}"))?;
+            .write_all(&contents)
+            .map_err(|err| format!("failed to write query data to file: {err:?}"))?;
 
+        // The file must be complete on disk before it replaces the old one.
         tmp_file
-            .persist(dir.as_ref().join(format!("query-{}.json", self.hash)))
-            .map_err(|err| format!("failed to move query file: {err:?}"))?;
+            .as_file()
+            .sync_all()
+            .map_err(|err| format!("failed to write query file: {err:?}"))?;
+
+        let result = persist(tmp_file, &path, &tmp_dir);
+
+        // this process may be saving no other query; fails if it is
+        let _ = fs::remove_dir(&staging_dir);
+
+        result
+            .map_err(|err| format!("failed to move query file to {}: {err:?}", path.display()))?;
 
         Ok(())
     }
 }
 
-pub(super) fn hash_string(query: &str) -> String {
-    // picked `sha2` because it's already in the dependency tree for both MySQL and Postgres
-    use sha2::{Digest, Sha256};
+/// How many times to try to replace a query file that another process has open, which Windows
+/// doesn't allow.
+const PERSIST_ATTEMPTS: u32 = 5;
+
+/// Move `tmp_file` to `path`, holding a lock shared by every process saving query data to
+/// `tmp_dir` so they replace files one at a time.
+///
+/// If the staged file is on another filesystem than `path`, it's copied next to `path` first,
+/// so the final rename is still atomic.
+fn persist(tmp_file: tempfile::NamedTempFile, path: &Path, tmp_dir: &Path) -> std::io::Result<()> {
+    let lock = fs::File::create(tmp_dir.join("save.lock"))?;
+    lock.lock()?;
+
+    let mut tmp_file = tmp_file;
+    let mut attempt = 1;
+
+    loop {
+        let err = match tmp_file.persist(path) {
+            Ok(_) => return Ok(()),
+            Err(err) => err,
+        };
+
+        match err.error.kind() {
+            std::io::ErrorKind::CrossesDevices => {
+                let dir = path.parent().unwrap_or(Path::new("."));
+                let mut copy = tempfile::NamedTempFile::new_in(dir)?;
+
+                std::io::copy(&mut err.file.reopen()?, copy.as_file_mut())?;
+                copy.as_file().sync_all()?;
+
+                tmp_file = copy;
+            }
+            std::io::ErrorKind::PermissionDenied if attempt < PERSIST_ATTEMPTS => {
+                std::thread::sleep(std::time::Duration::from_millis(10 << attempt));
+                tmp_file = err.file;
+            }
+            _ => return Err(err.error),
+        }
+
+        attempt += 1;
+
+        if attempt > PERSIST_ATTEMPTS {
+            return Err(std::io::Error::other(
+                "too many attempts to move the query file",
+            ));
+        }
+    }
+}
+
+#[test]
+fn test_migrations_fingerprint() {
+    let dir = tempfile::tempdir().unwrap();
+    let fingerprint = migrations_fingerprint(dir.path());
+    assert_eq!(migrations_fingerprint(dir.path()), fingerprint);
+
+    fs::write(dir.path().join("1_init.sql"), "CREATE TABLE a (id INT);").unwrap();
+    let added = migrations_fingerprint(dir.path());
+    assert_ne!(added, fingerprint);
 
-    hex::encode(Sha256::digest(query.as_bytes()))
+    fs::write(dir.path().join("1_init.sql"), "CREATE TABLE a (id BIGINT);").unwrap();
+    assert_ne!(migrations_fingerprint(dir.path()), added);
 }
@@ -0,0 +1,164 @@
+//! The variants of `query_filtered!()`: its query with and without each optional filter, all
+//! of which are checked at compile time, with one picked at runtime by the filters that are `Some`.
+
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use sqlx_core::column::Column;
+use sqlx_core::describe::Describe;
+
+use either::Either;
+
+use crate::database::{DatabaseExt, TypeCrates};
+use crate::query::args;
+use crate::query::data::QueryData;
+use crate::query::QueryMacroInput;
+
+/// Each filter doubles the number of variants to describe.
+const MAX_FILTERS: usize = 6;
+
+/// The SQL of every variant of the query, indexed by the set of filters it includes as bits.
+///
+/// Each included filter is followed by the placeholder for its value, after those of the query.
+pub fn variant_sql<DB: DatabaseExt>(input: &QueryMacroInput) -> crate::Result<Vec<String>> {
+    let filters = &input.filters;
+
+    if filters.len() > MAX_FILTERS {
+        return Err(format!(
+            "`query_filtered!()` supports at most {MAX_FILTERS} filters, got {}",
+            filters.len()
+        )
+        .into());
+    }
+
+    let variants = (0..1usize << filters.len())
+        .map(|included| {
+            let mut sql = input.sql.clone();
+            let mut index = input.arg_exprs.len();
+
+            for (i, (filter, _)) in filters.iter().enumerate() {
+                if included & (1 << i) != 0 {
+                    index += 1;
+                    sql.push_str(filter);
+                    sql.push_str(&DB::placeholder(index));
+                }
+            }
+
+            sql
+        })
+        .collect();
+
+    Ok(variants)
+}
+
+/// Returns a tokenstream which typechecks the filters, binds those that are `Some` to
+/// `query_args` after the arguments of the query, and picks the SQL of the variant as
+/// `filtered_sql`.
+///
+/// `variants` are the described variants after the query itself, in the order of [`variant_sql`].
+pub fn quote_filters<DB: DatabaseExt>(
+    input: &QueryMacroInput,
+    data: &QueryData<DB>,
+    variants: &[QueryData<DB>],
+    crates: TypeCrates,
+) -> crate::Result<TokenStream> {
+    for (i, variant) in variants.iter().enumerate() {
+        check_variant(input, &data.describe, i + 1, &variant.describe)?;
+    }
+
+    let names: Vec<_> = (0..input.filters.len())
+        .map(|i| format_ident!("filter{}", i))
+        .collect();
+
+    let exprs: Vec<_> = input.filters.iter().map(|(_, expr)| expr.clone()).collect();
+
+    // the variant with every filter has a parameter for each
+    let all = variants.last().map_or(&data.describe, |v| &v.describe);
+
+    let checks = match all.parameters() {
+        Some(Either::Left(params)) if input.checked => params[input.arg_exprs.len()..]
+            .iter()
+            .zip(names.iter().zip(&exprs))
+            .enumerate()
+            .map(|(i, (param_ty, (name, expr)))| {
+                args::quote_arg_check::<DB>(input.arg_exprs.len() + i, param_ty, name, expr, crates)
+            })
+            .collect::<crate::Result<TokenStream>>()?,
+        _ => TokenStream::new(),
+    };
+
+    let exprs = exprs.into_iter().map(args::strip_wildcard);
+    let bits = (0..names.len()).map(|i| 1usize << i);
+
+    let sql = std::iter::once(&data.query).chain(variants.iter().map(|variant| &variant.query));
+
+    Ok(quote! {
+        #(let #names = &(#exprs);)*
+
+        #checks
+
+        let mut query_args = query_args;
+        let mut variant = 0usize;
+
+        #(
+            if let ::std::option::Option::Some(value) = #names {
+                query_args.add(value);
+                variant |= #bits;
+            }
+        )*
+
+        let filtered_sql = [#(#sql),*][variant];
+    })
+}
+
+/// Every variant must have the columns of the query, to be read into the same record.
+fn check_variant<DB: DatabaseExt>(
+    input: &QueryMacroInput,
+    describe: &Describe<DB>,
+    included: usize,
+    variant: &Describe<DB>,
+) -> crate::Result<()> {
+    let filters = input
+        .filters
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| included & (1 << i) != 0)
+        .map(|(_, (filter, _))| format!("{filter:?}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let same_columns = describe.columns().len() == variant.columns().len()
+        && describe.columns().iter().enumerate().all(|(i, column)| {
+            let other = variant.column(i);
+
+            column.name() == other.name()
+                && column.type_info() == other.type_info()
+                && describe.nullable(i) == variant.nullable(i)
+        });
+
+    if !same_columns {
+        return Err(format!(
+            "the columns of the query differ with the filters {filters}; \
+             filters may only change which rows are returned"
+        )
+        .into());
+    }
+
+    let expected = input.arg_exprs.len() + included.count_ones() as usize;
+
+    let num_parameters = match variant.parameters() {
+        Some(Either::Left(params)) => Some(params.len()),
+        Some(Either::Right(num)) => Some(num),
+        None => None,
+    };
+
+    if num_parameters.is_some_and(|num| num != expected) {
+        return Err(format!(
+            "expected each filter to have one parameter, for its value; \
+             the query with the filters {filters} has {} parameters instead of {expected}",
+            num_parameters.unwrap_or_default(),
+        )
+        .into());
+    }
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ use std::fs;
 use proc_macro2::{Ident, Span};
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
-use syn::{Expr, LitBool, LitStr, Token};
+use syn::{Attribute, Expr, LitBool, LitStr, Token, Visibility};
 use syn::{ExprArray, Type};
 
 /// Macro input shared by `query!()` and `query_file!()`
@@ -14,8 +14,18 @@ pub struct QueryMacroInput {
 
     pub(super) record_type: RecordType,
 
+    /// For `query_grouped_as!()`, the field of the record holding the children and their type.
+    pub(super) group: Option<(Ident, Type)>,
+
+    /// For `query_as!()`, the columns of the output not read into a field of the record.
+    pub(super) skip: Vec<Ident>,
+
     pub(super) arg_exprs: Vec<Expr>,
 
+    /// For `query_filtered!()`, the SQL appended to the query for each optional argument, along
+    /// with the argument, which is bound after it if it's `Some`.
+    pub(super) filters: Vec<(String, Expr)>,
+
     pub(super) checked: bool,
 
     pub(super) file_path: Option<String>,
@@ -30,13 +40,66 @@ pub enum RecordType {
     Given(Type),
     Scalar,
     Generated,
+    /// For `query!(as Name, ..)`, a struct to declare in place of the anonymous record.
+    Declared(RecordDecl),
+}
+
+/// The attributes, visibility and name of the struct declared by `query!(as Name, ..)`, and the
+/// names and types of the parameters of its `query()` function.
+pub struct RecordDecl {
+    pub(super) attrs: Vec<Attribute>,
+    pub(super) vis: Visibility,
+    pub(super) ident: Ident,
+    pub(super) params: Vec<(Ident, Type)>,
+}
+
+/// `<name>: <type>`
+struct Param {
+    name: Ident,
+    ty: Type,
+}
+
+impl Parse for Param {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+
+        Ok(Param { name, ty })
+    }
+}
+
+impl Parse for RecordDecl {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis = input.parse()?;
+        input.parse::<Token![struct]>()?;
+        let ident = input.parse()?;
+
+        let content;
+        syn::parenthesized!(content in input);
+        let params = Punctuated::<Param, Token![,]>::parse_terminated(&content)?
+            .into_iter()
+            .map(|param| (param.name, param.ty))
+            .collect();
+
+        Ok(RecordDecl {
+            attrs,
+            vis,
+            ident,
+            params,
+        })
+    }
 }
 
 impl Parse for QueryMacroInput {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut query_src: Option<(QuerySrc, Span)> = None;
         let mut args: Option<Vec<Expr>> = None;
+        let mut filters = Vec::new();
         let mut record_type = RecordType::Generated;
+        let mut group = None;
+        let mut skip = Vec::new();
         let mut checked = true;
 
         let mut expect_comma = false;
@@ -63,15 +126,32 @@ impl Parse for QueryMacroInput {
             } else if key == "args" {
                 let exprs = input.parse::<ExprArray>()?;
                 args = Some(exprs.elems.into_iter().collect())
+            } else if key == "filters" {
+                let content;
+                syn::bracketed!(content in input);
+                filters = Punctuated::<Filter, Token![,]>::parse_terminated(&content)?
+                    .into_iter()
+                    .map(|filter| (filter.sql.value(), filter.value))
+                    .collect();
             } else if key == "record" {
                 if !matches!(record_type, RecordType::Generated) {
-                    return Err(input.error("colliding `scalar` or `record` key"));
+                    return Err(input.error("colliding `scalar`, `record` or `declare` key"));
                 }
 
                 record_type = RecordType::Given(input.parse()?);
+            } else if key == "group" {
+                let field: Ident = input.parse()?;
+                input.parse::<Token![:]>()?;
+                group = Some((field, input.parse()?));
+            } else if key == "skip" {
+                let content;
+                syn::bracketed!(content in input);
+                skip = Punctuated::<Ident, Token![,]>::parse_terminated(&content)?
+                    .into_iter()
+                    .collect();
             } else if key == "scalar" {
                 if !matches!(record_type, RecordType::Generated) {
-                    return Err(input.error("colliding `scalar` or `record` key"));
+                    return Err(input.error("colliding `scalar`, `record` or `declare` key"));
                 }
 
                 // we currently expect only `scalar = _`
@@ -79,6 +159,14 @@ impl Parse for QueryMacroInput {
                 // of the column in SQL
                 input.parse::<syn::Token![_]>()?;
                 record_type = RecordType::Scalar;
+            } else if key == "declare" {
+                if !matches!(record_type, RecordType::Generated) {
+                    return Err(input.error("colliding `scalar`, `record` or `declare` key"));
+                }
+
+                let content;
+                syn::braced!(content in input);
+                record_type = RecordType::Declared(content.parse()?);
             } else if key == "checked" {
                 let lit_bool = input.parse::<LitBool>()?;
                 checked = lit_bool.value;
@@ -93,7 +181,34 @@ impl Parse for QueryMacroInput {
         let (src, src_span) =
             query_src.ok_or_else(|| input.error("expected `source` or `source_file` key"))?;
 
-        let arg_exprs = args.unwrap_or_default();
+        if group.is_some() && !matches!(record_type, RecordType::Given(_)) {
+            return Err(input.error("`group` key requires a `record` key"));
+        }
+
+        if !skip.is_empty() && (group.is_some() || !matches!(record_type, RecordType::Given(_))) {
+            return Err(input.error("`skip` key requires a `record` key and no `group` key"));
+        }
+
+        if !filters.is_empty()
+            && (group.is_some() || matches!(record_type, RecordType::Declared(_)))
+        {
+            return Err(
+                input.error("`filters` key is not supported with `group` or `declare` keys")
+            );
+        }
+
+        // the parameters of the declared `query()` are bound in order
+        let arg_exprs = match &record_type {
+            RecordType::Declared(decl) if args.is_none() => decl
+                .params
+                .iter()
+                .map(|(name, _)| syn::parse_quote!(#name))
+                .collect(),
+            RecordType::Declared(_) => {
+                return Err(input.error("`args` key is not supported with `declare` key"))
+            }
+            _ => args.unwrap_or_default(),
+        };
 
         let file_path = src.file_path(src_span)?;
 
@@ -101,13 +216,32 @@ impl Parse for QueryMacroInput {
             sql: src.resolve(src_span)?,
             src_span,
             record_type,
+            group,
+            skip,
             arg_exprs,
+            filters,
             checked,
             file_path,
         })
     }
 }
 
+/// `"<sql>" => <value>`
+struct Filter {
+    sql: LitStr,
+    value: Expr,
+}
+
+impl Parse for Filter {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let sql = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let value = input.parse()?;
+
+        Ok(Filter { sql, value })
+    }
+}
+
 impl QuerySrc {
     /// If the query source is a file, read it to a string. Otherwise return the query string.
     fn resolve(self, source_span: Span) -> syn::Result<String> {
@@ -9,18 +9,23 @@ use syn::Type;
 pub use input::QueryMacroInput;
 use quote::{format_ident, quote};
 use sqlx_core::database::Database;
+use sqlx_core::query_data;
 use sqlx_core::{column::Column, describe::Describe, type_info::TypeInfo};
 
-use crate::database::DatabaseExt;
-use crate::query::data::{hash_string, DynQueryData, QueryData};
+use crate::database::{DatabaseExt, TypeCrates};
+use crate::query::data::{DynQueryData, QueryData};
 use crate::query::input::RecordType;
 use either::Either;
 use url::Url;
 
 mod args;
+mod config;
+mod daemon;
 mod data;
+mod filter;
 mod input;
 mod output;
+mod soft_delete;
 
 #[derive(Copy, Clone)]
 pub struct QueryDriver {
@@ -72,11 +77,25 @@ struct Metadata {
     #[allow(unused)]
     manifest_dir: PathBuf,
     offline: bool,
+    /// Set by `SQLX_OFFLINE=error`: missing query data is an error naming the expected file.
+    require_offline_data: bool,
     database_url: Option<String>,
+    /// Set by `SQLX_BIND_LIMIT`; an invalid value is reported by each macro that checks it.
+    bind_limit: Result<Option<usize>, String>,
+    /// `sqlx.toml`; an error reading it is reported by each macro invocation.
+    config: Result<config::Config, String>,
+    /// Set by `SQLX_MACROS_DATETIME_CRATE` and `SQLX_MACROS_DECIMAL_CRATE`, or else
+    /// `datetime-crate` and `decimal-crate` in `sqlx.toml`. An invalid value is reported by each
+    /// macro invocation.
+    type_crates: Result<TypeCrates, String>,
     workspace_root: Arc<Mutex<Option<PathBuf>>>,
 }
 
 impl Metadata {
+    fn config(&self) -> crate::Result<&config::Config> {
+        Ok(self.config.as_ref().map_err(Clone::clone)?)
+    }
+
     pub fn workspace_root(&self) -> PathBuf {
         let mut root = self.workspace_root.lock().unwrap();
         if root.is_none() {
@@ -135,24 +154,83 @@ static METADATA: Lazy<Metadata> = Lazy::new(|| {
         proc_macro::tracked_path::path(env_path);
     }
 
-    let offline = env("SQLX_OFFLINE")
-        .map(|s| s.eq_ignore_ascii_case("true") || s == "1")
-        .unwrap_or(false);
+    let offline_var = env("SQLX_OFFLINE").unwrap_or_default();
+    let require_offline_data = offline_var.eq_ignore_ascii_case("error");
+    let offline =
+        require_offline_data || offline_var.eq_ignore_ascii_case("true") || offline_var == "1";
 
     let database_url = env("DATABASE_URL").ok();
 
+    let bind_limit = env("SQLX_BIND_LIMIT")
+        .ok()
+        .map(|s| {
+            s.parse()
+                .map_err(|_| format!("`SQLX_BIND_LIMIT` must be a number, got {s:?}"))
+        })
+        .transpose();
+
+    let config_path = sqlx_core::query_data::find_config_file(&manifest_dir);
+
+    // tell the compiler to watch `sqlx.toml` for changes, if applicable
+    #[cfg(any(sqlx_macros_unstable, procmacro2_semver_exempt))]
+    if let Some(path) = config_path.as_ref().and_then(|path| path.to_str()) {
+        proc_macro::tracked_path::path(path);
+    }
+
+    let config = config_path.map_or_else(
+        || Ok(config::Config::default()),
+        |path| config::Config::load(&path),
+    );
+
+    let type_crates = type_crates(config.as_ref().ok());
+
     Metadata {
         manifest_dir,
         offline,
+        require_offline_data,
         database_url,
+        bind_limit,
+        config,
+        type_crates,
         workspace_root: Arc::new(Mutex::new(None)),
     }
 });
 
+/// Choose the crates for date/time and decimal types: `SQLX_MACROS_DATETIME_CRATE` and
+/// `SQLX_MACROS_DECIMAL_CRATE` take precedence over `sqlx.toml`.
+fn type_crates(config: Option<&config::Config>) -> Result<TypeCrates, String> {
+    let datetime = match env("SQLX_MACROS_DATETIME_CRATE") {
+        Ok(s) => Some(
+            s.parse()
+                .map_err(|e| format!("`SQLX_MACROS_DATETIME_CRATE`: {e}"))?,
+        ),
+        Err(_) => config.and_then(|c| c.macros.datetime_crate),
+    };
+
+    let decimal = match env("SQLX_MACROS_DECIMAL_CRATE") {
+        Ok(s) => Some(
+            s.parse()
+                .map_err(|e| format!("`SQLX_MACROS_DECIMAL_CRATE`: {e}"))?,
+        ),
+        Err(_) => config.and_then(|c| c.macros.decimal_crate),
+    };
+
+    Ok(TypeCrates { datetime, decimal })
+}
+
+/// The column marking soft-deleted rows configured in `sqlx.toml`, as a string literal.
+pub fn expand_soft_delete_column() -> crate::Result<TokenStream> {
+    let column = METADATA.config()?.soft_delete_column();
+
+    Ok(quote!(#column))
+}
+
 pub fn expand_input<'a>(
     input: QueryMacroInput,
     drivers: impl IntoIterator<Item = &'a QueryDriver>,
 ) -> crate::Result<TokenStream> {
+    METADATA.config()?;
+
     let data_source = match &*METADATA {
         Metadata {
             offline: false,
@@ -160,38 +238,7 @@ pub fn expand_input<'a>(
             ..
         } => QueryDataSource::live(db_url)?,
 
-        _ => {
-            // Try load the cached query metadata file.
-            let filename = format!("query-{}.json", hash_string(&input.sql));
-
-            // Check SQLX_OFFLINE_DIR, then local .sqlx, then workspace .sqlx.
-            let data_file_path = if let Some(sqlx_offline_dir_path) = env("SQLX_OFFLINE_DIR")
-                .ok()
-                .map(PathBuf::from)
-                .map(|path| path.join(&filename))
-                .filter(|path| path.exists())
-            {
-                sqlx_offline_dir_path
-            } else if let Some(local_path) =
-                Some(METADATA.manifest_dir.join(".sqlx").join(&filename))
-                    .filter(|path| path.exists())
-            {
-                local_path
-            } else if let Some(workspace_path) =
-                Some(METADATA.workspace_root().join(".sqlx").join(&filename))
-                    .filter(|path| path.exists())
-            {
-                workspace_path
-            } else {
-                return Err(
-                    "`DATABASE_URL` must be set, or `cargo sqlx prepare` must have been run \
-                     and .sqlx must exist, to use query macros"
-                        .into(),
-                );
-            };
-
-            QueryDataSource::Cached(DynQueryData::from_data_file(&data_file_path, &input.sql)?)
-        }
+        _ => QueryDataSource::Cached(load_cached_data(&input.sql)?),
     };
 
     for driver in drivers {
@@ -217,6 +264,59 @@ pub fn expand_input<'a>(
     }
 }
 
+/// Load the cached data for `sql` from the first query data directory that has it.
+fn load_cached_data(sql: &str) -> crate::Result<DynQueryData> {
+    // Try load the cached query metadata file.
+    let filename = query_data::data_file_name(sql);
+
+    // Check SQLX_OFFLINE_DIR, then local .sqlx, then workspace .sqlx.
+    let candidates = env("SQLX_OFFLINE_DIR")
+        .ok()
+        .map(|dir| PathBuf::from(dir).join(&filename))
+        .into_iter()
+        .chain([METADATA.manifest_dir.join(".sqlx").join(&filename)]);
+
+    let mut checked = Vec::new();
+    let mut data_file_path = None;
+    for path in candidates.chain(std::iter::once_with(|| {
+        METADATA.workspace_root().join(".sqlx").join(&filename)
+    })) {
+        if path.exists() {
+            data_file_path = Some(path);
+            break;
+        }
+        if !checked.contains(&path) {
+            checked.push(path);
+        }
+    }
+
+    let Some(data_file_path) = data_file_path else {
+        if METADATA.require_offline_data {
+            let checked = checked
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            return Err(format!(
+                "`SQLX_OFFLINE=error` is set but no query data was found for this query \
+                 (hash {hash}); expected one of: {checked}. \
+                 Run `cargo sqlx prepare` and commit the `.sqlx` directory",
+                hash = query_data::hash_query(sql),
+            )
+            .into());
+        }
+
+        return Err(
+            "`DATABASE_URL` must be set, or `cargo sqlx prepare` must have been run \
+             and .sqlx must exist, to use query macros"
+                .into(),
+        );
+    };
+
+    DynQueryData::from_data_file(&data_file_path, sql)
+}
+
 fn expand_with<DB: DatabaseExt>(
     input: QueryMacroInput,
     data_source: QueryDataSource,
@@ -224,15 +324,26 @@ fn expand_with<DB: DatabaseExt>(
 where
     Describe<DB>: DescribeExt,
 {
+    // the variants of `query_filtered!()` with at least one filter; the first is the query itself
+    let variants = filter::variant_sql::<DB>(&input)?
+        .iter()
+        .skip(1)
+        .map(|sql| match data_source {
+            QueryDataSource::Cached(_) => QueryData::from_dyn_data(load_cached_data(sql)?),
+            QueryDataSource::Live {
+                ref database_url, ..
+            } => QueryData::from_db(sql, database_url),
+        })
+        .collect::<crate::Result<Vec<_>>>()?;
+
     let (query_data, offline): (QueryData<DB>, bool) = match data_source {
         QueryDataSource::Cached(dyn_data) => (QueryData::from_dyn_data(dyn_data)?, true),
         QueryDataSource::Live { database_url, .. } => {
-            let describe = DB::describe_blocking(&input.sql, &database_url)?;
-            (QueryData::from_describe(&input.sql, describe), false)
+            (QueryData::from_db(&input.sql, &database_url)?, false)
         }
     };
 
-    expand_with_data(input, query_data, offline)
+    expand_with_data(input, query_data, variants, offline)
 }
 
 // marker trait for `Describe` that lets us conditionally require it to be `Serialize + Deserialize`
@@ -246,11 +357,14 @@ impl<DB: Database> DescribeExt for Describe<DB> where
 fn expand_with_data<DB: DatabaseExt>(
     input: QueryMacroInput,
     data: QueryData<DB>,
+    variants: Vec<QueryData<DB>>,
     offline: bool,
 ) -> crate::Result<TokenStream>
 where
     Describe<DB>: DescribeExt,
 {
+    let type_crates = METADATA.type_crates.clone()?;
+
     // validate at the minimum that our args match the query's input parameters
     let num_parameters = match data.describe.parameters() {
         Some(Either::Left(params)) => Some(params.len()),
@@ -267,7 +381,52 @@ where
         }
     }
 
-    let args_tokens = args::quote_args(&input, &data.describe)?;
+    // the database would reject the query anyway, but only once it's executed
+    let bind_limit = METADATA.bind_limit.clone()?.unwrap_or(DB::BIND_LIMIT);
+
+    if input.arg_exprs.len() > bind_limit {
+        return Err(format!(
+            "{} bind parameters exceeds the limit of {} for {}; \
+             consider binding an array or using `QueryBuilder::execute_values_chunked()` instead \
+             (the limit can be changed with the `SQLX_BIND_LIMIT` environment variable)",
+            input.arg_exprs.len(),
+            bind_limit,
+            DB::NAME,
+        )
+        .into());
+    }
+
+    let soft_delete_warnings = match &METADATA.config()?.soft_delete {
+        Some(config) => soft_delete::quote_warnings(&input.sql, config, input.src_span),
+        None => quote!(),
+    };
+
+    let mut args_tokens = args::quote_args(&input, &data.describe, type_crates)?;
+
+    // `query!(as Name, ..)` declares the record and a function running the query, as items
+    if let RecordType::Declared(ref decl) = input.record_type {
+        args_tokens = quote! {
+            #soft_delete_warnings
+
+            #args_tokens
+        };
+
+        let columns = output::columns_to_rust::<DB>(&data.describe, type_crates)?;
+        let ret_tokens = output::quote_record_decl::<DB>(&input, decl, &args_tokens, &columns)?;
+
+        save_query_data(&data, offline)?;
+
+        return Ok(ret_tokens);
+    }
+
+    if !input.filters.is_empty() {
+        args_tokens.extend(filter::quote_filters(
+            &input,
+            &data,
+            &variants,
+            type_crates,
+        )?);
+    }
 
     let query_args = format_ident!("query_args");
 
@@ -286,7 +445,7 @@ where
     } else {
         match input.record_type {
             RecordType::Generated => {
-                let columns = output::columns_to_rust::<DB>(&data.describe)?;
+                let columns = output::columns_to_rust::<DB>(&data.describe, type_crates)?;
 
                 let record_name: Type = syn::parse_str("Record").unwrap();
 
@@ -324,14 +483,33 @@ where
 
                 record_tokens
             }
-            RecordType::Given(ref out_ty) => {
-                let columns = output::columns_to_rust::<DB>(&data.describe)?;
+            RecordType::Given(ref out_ty) => match input.group {
+                Some((ref field, ref child_ty)) => {
+                    let (columns, child_columns) =
+                        output::columns_to_rust_grouped::<DB>(&data.describe, field, type_crates)?;
+
+                    output::quote_query_grouped_as::<DB>(
+                        &input,
+                        out_ty,
+                        field,
+                        child_ty,
+                        &query_args,
+                        &columns,
+                        &child_columns,
+                    )
+                }
+                None => {
+                    let columns = output::columns_to_rust::<DB>(&data.describe, type_crates)?;
 
-                output::quote_query_as::<DB>(&input, out_ty, &query_args, &columns)
-            }
+                    output::check_skipped(&input, &columns)?;
+
+                    output::quote_query_as::<DB>(&input, out_ty, &query_args, &columns)
+                }
+            },
             RecordType::Scalar => {
-                output::quote_query_scalar::<DB>(&input, &query_args, &data.describe)?
+                output::quote_query_scalar::<DB>(&input, &query_args, &data.describe, type_crates)?
             }
+            RecordType::Declared(_) => unreachable!("record declarations are expanded above"),
         }
     };
 
@@ -341,6 +519,8 @@ where
             {
                 use ::sqlx::Arguments as _;
 
+                #soft_delete_warnings
+
                 #args_tokens
 
                 #output
@@ -348,6 +528,19 @@ where
         }
     };
 
+    save_query_data(&data, offline)?;
+
+    for variant in &variants {
+        save_query_data(variant, offline)?;
+    }
+
+    Ok(ret_tokens)
+}
+
+fn save_query_data<DB: DatabaseExt>(data: &QueryData<DB>, offline: bool) -> crate::Result<()>
+where
+    Describe<DB>: DescribeExt,
+{
     // Store query metadata only if offline support is enabled but the current build is online.
     // If the build is offline, the cache is our input so it's pointless to also write data for it.
     if !offline {
@@ -387,13 +580,13 @@ where
                     }
 
                     // .sqlx exists and is a directory, store data.
-                    data.save_in(path, tmp_dir)?;
+                    data.save_in(path, tmp_dir, METADATA.config()?.prepare.json_format)?;
                 }
             }
         }
     }
 
-    Ok(ret_tokens)
+    Ok(())
 }
 
 /// Get the value of an environment variable, telling the compiler about it if applicable.
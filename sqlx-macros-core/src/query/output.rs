@@ -1,12 +1,14 @@
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, ToTokens, TokenStreamExt};
+use syn::ext::IdentExt;
 use syn::Type;
 
 use sqlx_core::column::Column;
 use sqlx_core::describe::Describe;
 
-use crate::database::DatabaseExt;
+use crate::database::{DatabaseExt, TypeCrates};
 
+use crate::query::input::RecordDecl;
 use crate::query::QueryMacroInput;
 use std::fmt::{self, Display, Formatter};
 use syn::parse::{Parse, ParseStream};
@@ -66,6 +68,8 @@ enum ColumnNullabilityOverride {
 enum ColumnTypeOverride {
     Exact(Type),
     Wildcard,
+    // `foo: chrono`, `foo: time`, `foo: bigdecimal` or `foo: rust_decimal`
+    Crate(TypeCrates),
     None,
 }
 
@@ -75,17 +79,33 @@ impl Display for DisplayColumn<'_> {
     }
 }
 
-pub fn columns_to_rust<DB: DatabaseExt>(describe: &Describe<DB>) -> crate::Result<Vec<RustColumn>> {
+pub fn columns_to_rust<DB: DatabaseExt>(
+    describe: &Describe<DB>,
+    crates: TypeCrates,
+) -> crate::Result<Vec<RustColumn>> {
     (0..describe.columns().len())
-        .map(|i| column_to_rust(describe, i))
+        .map(|i| column_to_rust(describe, i, crates))
         .collect::<crate::Result<Vec<_>>>()
 }
 
-fn column_to_rust<DB: DatabaseExt>(describe: &Describe<DB>, i: usize) -> crate::Result<RustColumn> {
+fn column_to_rust<DB: DatabaseExt>(
+    describe: &Describe<DB>,
+    i: usize,
+    crates: TypeCrates,
+) -> crate::Result<RustColumn> {
+    column_to_rust_named(describe, i, describe.columns()[i].name(), crates)
+}
+
+fn column_to_rust_named<DB: DatabaseExt>(
+    describe: &Describe<DB>,
+    i: usize,
+    name: &str,
+    crates: TypeCrates,
+) -> crate::Result<RustColumn> {
     let column = &describe.columns()[i];
 
     // add raw prefix to all identifiers
-    let decl = ColumnDecl::parse(&column.name())
+    let decl = ColumnDecl::parse(name)
         .map_err(|e| format!("column name {:?} is invalid: {}", column.name(), e))?;
 
     let ColumnOverride { nullability, type_ } = decl.r#override;
@@ -95,6 +115,12 @@ fn column_to_rust<DB: DatabaseExt>(describe: &Describe<DB>, i: usize) -> crate::
         ColumnNullabilityOverride::Nullable => true,
         ColumnNullabilityOverride::None => describe.nullable(i).unwrap_or(true),
     };
+
+    let crates = match type_ {
+        ColumnTypeOverride::Crate(column_crates) => column_crates.or(crates),
+        _ => crates,
+    };
+
     let type_ = match (type_, nullable) {
         (ColumnTypeOverride::Exact(type_), false) => ColumnType::Exact(type_.to_token_stream()),
         (ColumnTypeOverride::Exact(type_), true) => {
@@ -104,8 +130,8 @@ fn column_to_rust<DB: DatabaseExt>(describe: &Describe<DB>, i: usize) -> crate::
         (ColumnTypeOverride::Wildcard, false) => ColumnType::Wildcard,
         (ColumnTypeOverride::Wildcard, true) => ColumnType::OptWildcard,
 
-        (ColumnTypeOverride::None, _) => {
-            let type_ = get_column_type::<DB>(i, column);
+        (ColumnTypeOverride::Crate(_) | ColumnTypeOverride::None, _) => {
+            let type_ = get_column_type::<DB>(i, column, crates);
             if !nullable {
                 ColumnType::Exact(type_)
             } else {
@@ -123,53 +149,57 @@ fn column_to_rust<DB: DatabaseExt>(describe: &Describe<DB>, i: usize) -> crate::
     })
 }
 
+/// Check that every column given to `skip` is in the output of the query, so the list doesn't
+/// outlive the columns it was written for.
+pub fn check_skipped(input: &QueryMacroInput, columns: &[RustColumn]) -> crate::Result<()> {
+    for skipped in &input.skip {
+        if !columns
+            .iter()
+            .any(|col| col.ident.unraw() == skipped.unraw())
+        {
+            return Err(syn::Error::new_spanned(
+                skipped,
+                format!("skipped column `{skipped}` is not in the output of the query"),
+            )
+            .into());
+        }
+    }
+
+    Ok(())
+}
+
+// column names are parsed as raw identifiers
+fn is_skipped(input: &QueryMacroInput, ident: &Ident) -> bool {
+    input
+        .skip
+        .iter()
+        .any(|skipped| skipped.unraw() == ident.unraw())
+}
+
 pub fn quote_query_as<DB: DatabaseExt>(
     input: &QueryMacroInput,
     out_ty: &Type,
     bind_args: &Ident,
     columns: &[RustColumn],
 ) -> TokenStream {
-    let instantiations = columns.iter().enumerate().map(
-        |(
-            i,
-            &RustColumn {
-                ref var_name,
-                ref type_,
-                ..
-            },
-        )| {
-            match (input.checked, type_) {
-                // we guarantee the type is valid so we can skip the runtime check
-                (true, ColumnType::Exact(type_)) => quote! {
-                    // binding to a `let` avoids confusing errors about
-                    // "try expression alternatives have incompatible types"
-                    // it doesn't seem to hurt inference in the other branches
-                    let #var_name = row.try_get_unchecked::<#type_, _>(#i)?.into();
-                },
-                // type was overridden to be a wildcard so we fallback to the runtime check
-                (true, ColumnType::Wildcard) => quote! ( let #var_name = row.try_get(#i)?; ),
-                (true, ColumnType::OptWildcard) => {
-                    quote! ( let #var_name = row.try_get::<::std::option::Option<_>, _>(#i)?; )
-                }
-                // macro is the `_unchecked!()` variant so this will die in decoding if it's wrong
-                (false, _) => quote!( let #var_name = row.try_get_unchecked(#i)?; ),
-            }
-        },
-    );
+    // skipped columns keep their index in the row, they just aren't read
+    let columns: Vec<_> = columns
+        .iter()
+        .enumerate()
+        .filter(|(_, col)| !is_skipped(input, &col.ident))
+        .collect();
+
+    let instantiations = columns
+        .iter()
+        .map(|&(i, column)| quote_instantiation(input, i, column));
 
-    let ident = columns.iter().map(|col| &col.ident);
-    let var_name = columns.iter().map(|col| &col.var_name);
+    let ident = columns.iter().map(|(_, col)| &col.ident);
+    let var_name = columns.iter().map(|(_, col)| &col.var_name);
 
     let db_path = DB::db_path();
     let row_path = DB::row_path();
 
-    // if this query came from a file, use `include_str!()` to tell the compiler where it came from
-    let sql = if let Some(ref path) = &input.file_path {
-        quote::quote_spanned! { input.src_span => include_str!(#path) }
-    } else {
-        let sql = &input.sql;
-        quote! { #sql }
-    };
+    let sql = quote_sql(input);
 
     quote! {
         ::sqlx::query_with::<#db_path, _>(#sql, #bind_args).try_map(|row: #row_path| {
@@ -182,10 +212,229 @@ pub fn quote_query_as<DB: DatabaseExt>(
     }
 }
 
+/// Declare the struct of `query!(as Name, ..)`, with a field for each column of the query, and
+/// its `query()` function, which binds `args_tokens` and reads each row into the struct.
+///
+/// Unless the struct is already documented, its doc comment shows the query.
+pub fn quote_record_decl<DB: DatabaseExt>(
+    input: &QueryMacroInput,
+    decl: &RecordDecl,
+    args_tokens: &TokenStream,
+    columns: &[RustColumn],
+) -> crate::Result<TokenStream> {
+    if columns.is_empty() {
+        return Err("`query!(as Name, ..)` requires a query that returns columns".into());
+    }
+
+    if columns.iter().any(|col| col.type_.is_wildcard()) {
+        return Err(
+            "wildcard overrides are not allowed in `query!(as Name, ..)`; \
+                    the declared struct needs the type of every column"
+                .into(),
+        );
+    }
+
+    let RecordDecl {
+        attrs,
+        vis,
+        ident,
+        params,
+    } = decl;
+
+    let doc = if attrs.iter().any(|attr| attr.path.is_ident("doc")) {
+        quote!()
+    } else {
+        let doc = format!(
+            " A record of the output of the query:\n\n```sql\n{}\n```",
+            input.sql
+        );
+        quote!(#[doc = #doc])
+    };
+
+    let fields = columns
+        .iter()
+        .map(|RustColumn { ident, type_, .. }| quote!(#vis #ident: #type_,));
+
+    let params = params.iter().map(|(name, ty)| quote!(#name: #ty));
+
+    let db_path = DB::db_path();
+    let row_path = DB::row_path();
+
+    let query = quote_query_as::<DB>(
+        input,
+        &syn::parse_quote!(Self),
+        &Ident::new("query_args", Span::call_site()),
+        columns,
+    );
+
+    Ok(quote! {
+        #doc
+        #(#attrs)*
+        #vis struct #ident {
+            #(#fields)*
+        }
+
+        impl #ident {
+            /// Runs the query with the given arguments, reading each row into this struct.
+            #[allow(clippy::all)]
+            #vis fn query<'q>(#(#params),*) -> ::sqlx::query::Map<
+                'q,
+                #db_path,
+                impl FnMut(#row_path) -> ::std::result::Result<Self, ::sqlx::Error> + Send,
+                <#db_path as ::sqlx::database::HasArguments<'q>>::Arguments,
+            > {
+                use ::sqlx::Arguments as _;
+
+                #args_tokens
+
+                #query
+            }
+        }
+    })
+}
+
+fn quote_sql(input: &QueryMacroInput) -> TokenStream {
+    if !input.filters.is_empty() {
+        // chosen at runtime from the variants of the query; see `filter::quote_filters()`
+        quote! { filtered_sql }
+    } else if let Some(ref path) = &input.file_path {
+        // if this query came from a file, use `include_str!()` to tell the compiler where it came from
+        quote::quote_spanned! { input.src_span => include_str!(#path) }
+    } else {
+        let sql = &input.sql;
+        quote! { #sql }
+    }
+}
+
+fn quote_instantiation(input: &QueryMacroInput, i: usize, column: &RustColumn) -> TokenStream {
+    let RustColumn {
+        ref var_name,
+        ref type_,
+        ..
+    } = *column;
+
+    match (input.checked, type_) {
+        // we guarantee the type is valid so we can skip the runtime check
+        (true, ColumnType::Exact(type_)) => quote! {
+            // binding to a `let` avoids confusing errors about
+            // "try expression alternatives have incompatible types"
+            // it doesn't seem to hurt inference in the other branches
+            let #var_name = row.try_get_unchecked::<#type_, _>(#i)?.into();
+        },
+        // type was overridden to be a wildcard so we fallback to the runtime check
+        (true, ColumnType::Wildcard) => quote! ( let #var_name = row.try_get(#i)?; ),
+        (true, ColumnType::OptWildcard) => {
+            quote! ( let #var_name = row.try_get::<::std::option::Option<_>, _>(#i)?; )
+        }
+        // macro is the `_unchecked!()` variant so this will die in decoding if it's wrong
+        (false, _) => quote!( let #var_name = row.try_get_unchecked(#i)?; ),
+    }
+}
+
+/// Columns along with their index in the output of the query.
+pub type IndexedColumns = Vec<(usize, RustColumn)>;
+
+/// Split the columns for `query_grouped_as!()` into those of the parent record and those
+/// of the child, which are prefixed with the name of the field holding the children.
+pub fn columns_to_rust_grouped<DB: DatabaseExt>(
+    describe: &Describe<DB>,
+    field: &Ident,
+    crates: TypeCrates,
+) -> crate::Result<(IndexedColumns, IndexedColumns)> {
+    let prefix = format!("{}.", field.to_string().trim_start_matches("r#"));
+
+    let mut columns = Vec::new();
+    let mut child_columns = Vec::new();
+
+    for (i, column) in describe.columns().iter().enumerate() {
+        match column.name().strip_prefix(&prefix) {
+            Some(name) => child_columns.push((i, column_to_rust_named(describe, i, name, crates)?)),
+            None => columns.push((i, column_to_rust(describe, i, crates)?)),
+        }
+    }
+
+    if columns.is_empty() {
+        return Err("expected at least one column for the parent record, \
+                    the first of which is used to group the rows"
+            .into());
+    }
+
+    if child_columns.is_empty() {
+        return Err(format!(
+            "expected at least one column for the children, named like \"{prefix}<field>\""
+        )
+        .into());
+    }
+
+    Ok((columns, child_columns))
+}
+
+pub fn quote_query_grouped_as<DB: DatabaseExt>(
+    input: &QueryMacroInput,
+    out_ty: &Type,
+    field: &Ident,
+    child_ty: &Type,
+    bind_args: &Ident,
+    columns: &[(usize, RustColumn)],
+    child_columns: &[(usize, RustColumn)],
+) -> TokenStream {
+    let instantiations = columns
+        .iter()
+        .map(|(i, column)| quote_instantiation(input, *i, column));
+
+    let child_instantiations = child_columns
+        .iter()
+        .map(|(i, column)| quote_instantiation(input, *i, column));
+
+    let key = &columns[0].1.var_name;
+    let child_idx = child_columns[0].0;
+
+    let ident = columns.iter().map(|(_, col)| &col.ident);
+    let var_name = columns.iter().map(|(_, col)| &col.var_name);
+
+    let child_ident = child_columns.iter().map(|(_, col)| &col.ident);
+    let child_var_name = child_columns.iter().map(|(_, col)| &col.var_name);
+
+    let db_path = DB::db_path();
+    let row_path = DB::row_path();
+
+    let sql = quote_sql(input);
+
+    quote! {
+        ::sqlx::query::Grouped::new(
+            ::sqlx::query_with::<#db_path, _>(#sql, #bind_args).try_map(|row: #row_path| {
+                use ::sqlx::Row as _;
+                use ::sqlx::ValueRef as _;
+
+                #(#instantiations)*
+
+                let key = ::std::clone::Clone::clone(&#key);
+
+                // a `NULL` in the first child column means there is no child, as with a `LEFT JOIN`
+                let child = if row.try_get_raw(#child_idx)?.is_null() {
+                    ::std::option::Option::None
+                } else {
+                    #(#child_instantiations)*
+
+                    ::std::option::Option::Some(#child_ty { #(#child_ident: #child_var_name),* })
+                };
+
+                ::std::result::Result::Ok((
+                    key,
+                    #out_ty { #(#ident: #var_name,)* #field: ::std::vec::Vec::new() },
+                    child,
+                ))
+            }),
+            |parent: &mut #out_ty| &mut parent.#field,
+        )
+    }
+}
+
 pub fn quote_query_scalar<DB: DatabaseExt>(
     input: &QueryMacroInput,
     bind_args: &Ident,
     describe: &Describe<DB>,
+    crates: TypeCrates,
 ) -> crate::Result<TokenStream> {
     let columns = describe.columns();
 
@@ -198,10 +447,10 @@ pub fn quote_query_scalar<DB: DatabaseExt>(
     }
 
     // attempt to parse a column override, otherwise fall back to the inferred type of the column
-    let ty = if let Ok(rust_col) = column_to_rust(describe, 0) {
+    let ty = if let Ok(rust_col) = column_to_rust(describe, 0, crates) {
         rust_col.type_.to_token_stream()
     } else if input.checked {
-        let ty = get_column_type::<DB>(0, &columns[0]);
+        let ty = get_column_type::<DB>(0, &columns[0], crates);
         if describe.nullable(0).unwrap_or(true) {
             quote! { ::std::option::Option<#ty> }
         } else {
@@ -212,39 +461,52 @@ pub fn quote_query_scalar<DB: DatabaseExt>(
     };
 
     let db = DB::db_path();
-    let query = &input.sql;
+    let query = if input.filters.is_empty() {
+        let sql = &input.sql;
+        quote! { #sql }
+    } else {
+        quote! { filtered_sql }
+    };
 
     Ok(quote! {
         ::sqlx::query_scalar_with::<#db, #ty, _>(#query, #bind_args)
     })
 }
 
-fn get_column_type<DB: DatabaseExt>(i: usize, column: &DB::Column) -> TokenStream {
+fn get_column_type<DB: DatabaseExt>(
+    i: usize,
+    column: &DB::Column,
+    crates: TypeCrates,
+) -> TokenStream {
     let type_info = &*column.type_info();
 
-    <DB as DatabaseExt>::return_type_for_id(&type_info).map_or_else(
+    <DB as DatabaseExt>::return_type_for_id(&type_info, crates).map_or_else(
         || {
-            let message =
-                if let Some(feature_gate) = <DB as DatabaseExt>::get_feature_gate(&type_info) {
-                    format!(
-                        "optional sqlx feature `{feat}` required for type {ty} of {col}",
-                        ty = &type_info,
-                        feat = feature_gate,
-                        col = DisplayColumn {
-                            idx: i,
-                            name: &*column.name()
-                        }
-                    )
-                } else {
-                    format!(
-                        "unsupported type {ty} of {col}",
-                        ty = type_info,
-                        col = DisplayColumn {
-                            idx: i,
-                            name: &*column.name()
-                        }
-                    )
-                };
+            // the type is supported, just not by the chosen crate
+            let feature_gate = DB::return_type_for_id(&type_info, TypeCrates::default())
+                .and_then(|ty| crates.required_feature(ty))
+                .or_else(|| <DB as DatabaseExt>::get_feature_gate(&type_info));
+
+            let message = if let Some(feature_gate) = feature_gate {
+                format!(
+                    "optional sqlx feature `{feat}` required for type {ty} of {col}",
+                    ty = &type_info,
+                    feat = feature_gate,
+                    col = DisplayColumn {
+                        idx: i,
+                        name: &*column.name()
+                    }
+                )
+            } else {
+                format!(
+                    "unsupported type {ty} of {col}",
+                    ty = type_info,
+                    col = DisplayColumn {
+                        idx: i,
+                        name: &*column.name()
+                    }
+                )
+            };
             syn::Error::new(Span::call_site(), message).to_compile_error()
         },
         |t| t.parse().unwrap(),
@@ -299,10 +561,19 @@ impl Parse for ColumnOverride {
 
             let ty = Type::parse(input)?;
 
-            if let Type::Infer(_) = ty {
-                ColumnTypeOverride::Wildcard
-            } else {
-                ColumnTypeOverride::Exact(ty)
+            match ty {
+                Type::Infer(_) => ColumnTypeOverride::Wildcard,
+                Type::Path(ref path) if path.qself.is_none() => {
+                    match path
+                        .path
+                        .get_ident()
+                        .and_then(|ident| TypeCrates::parse_crate(&ident.to_string()))
+                    {
+                        Some(crates) => ColumnTypeOverride::Crate(crates),
+                        None => ColumnTypeOverride::Exact(ty),
+                    }
+                }
+                _ => ColumnTypeOverride::Exact(ty),
             }
         } else {
             ColumnTypeOverride::None
@@ -0,0 +1,121 @@
+//! Compile-time lint for queries against soft-deleted tables, configured under `[soft-delete]`
+//! in `sqlx.toml`; see [`SoftDeleteConfig`].
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote_spanned};
+
+use super::config::SoftDeleteConfig;
+
+/// Including this anywhere in the query, e.g. in a comment, silences the lint.
+const ALLOW_MARKER: &str = "sqlx:include-deleted";
+
+/// Emit a warning for each configured table that `sql` selects from
+/// without filtering on the soft-delete column.
+pub fn quote_warnings(sql: &str, config: &SoftDeleteConfig, span: Span) -> TokenStream {
+    unfiltered_tables(sql, config)
+        .into_iter()
+        .enumerate()
+        .map(|(i, table)| {
+            let name = format_ident!("soft_delete_warning_{}", i, span = span);
+
+            let message = format!(
+                "query selects from soft-deleted table `{table}` without filtering on `{column} IS NULL`; \
+                 add the filter, or `{ALLOW_MARKER}` in a comment if deleted rows are intended",
+                column = config.column,
+            );
+
+            quote_spanned!(span =>
+                // this shouldn't actually run
+                if false {
+                    #[deprecated(note = #message)]
+                    #[allow(non_upper_case_globals)]
+                    const #name: () = ();
+                    let _ = #name;
+                }
+            )
+        })
+        .collect()
+}
+
+fn unfiltered_tables(sql: &str, config: &SoftDeleteConfig) -> Vec<String> {
+    if sql.contains(ALLOW_MARKER) {
+        return vec![];
+    }
+
+    let sql = sql.to_lowercase();
+
+    let tokens: Vec<&str> = sql
+        .split(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '.' | '"' | '`')))
+        .filter(|token| !token.is_empty())
+        .collect();
+
+    // inserting or hard-deleting rows doesn't need the filter
+    if matches!(tokens.first(), Some(&"insert" | &"delete")) {
+        return vec![];
+    }
+
+    let column = config.column.to_lowercase();
+
+    let filtered = tokens.windows(3).any(|window| {
+        let is_column = unqualified(window[0]) == column;
+
+        is_column && window[1] == "is" && matches!(window[2], "null" | "not")
+    });
+
+    if filtered {
+        return vec![];
+    }
+
+    let mut tables = Vec::new();
+
+    for window in tokens.windows(2) {
+        if !matches!(window[0], "from" | "join") {
+            continue;
+        }
+
+        let table = unqualified(window[1]);
+
+        if let Some(configured) = config
+            .tables
+            .iter()
+            .find(|configured| unqualified(&configured.to_lowercase()) == table)
+        {
+            if !tables.contains(configured) {
+                tables.push(configured.clone());
+            }
+        }
+    }
+
+    tables
+}
+
+/// Strip the schema or table qualifier and any quotes from an identifier.
+fn unqualified(ident: &str) -> String {
+    ident
+        .rsplit('.')
+        .next()
+        .unwrap_or(ident)
+        .trim_matches(|c| c == '"' || c == '`')
+        .to_owned()
+}
+
+#[test]
+fn test_unfiltered_tables() {
+    let config = SoftDeleteConfig {
+        tables: vec!["users".into(), "public.posts".into()],
+        column: "deleted_at".into(),
+    };
+
+    let check = |sql| unfiltered_tables(sql, &config);
+
+    assert_eq!(check("SELECT * FROM users WHERE id = $1"), ["users"]);
+    assert_eq!(
+        check("SELECT * FROM users u JOIN \"posts\" p ON p.user_id = u.id"),
+        ["users", "public.posts"]
+    );
+    assert!(check("SELECT * FROM users WHERE deleted_at IS NULL").is_empty());
+    assert!(check("SELECT * FROM users u WHERE u.deleted_at is not null").is_empty());
+    assert!(check("SELECT * FROM comments").is_empty());
+    assert!(check("DELETE FROM users WHERE id = $1").is_empty());
+    assert!(check("SELECT * FROM users -- sqlx:include-deleted").is_empty());
+}
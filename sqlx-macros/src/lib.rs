@@ -15,12 +15,23 @@ pub fn expand_query(input: TokenStream) -> TokenStream {
                 parse_err.to_compile_error().into()
             } else {
                 let msg = e.to_string();
-                quote!(::std::compile_error!(#msg)).into()
+                quote!(::std::compile_error! { #msg }).into()
             }
         }
     }
 }
 
+#[proc_macro]
+pub fn expand_soft_delete_column(_input: TokenStream) -> TokenStream {
+    match query::expand_soft_delete_column() {
+        Ok(ts) => ts.into(),
+        Err(e) => {
+            let msg = e.to_string();
+            quote!(::std::compile_error! { #msg }).into()
+        }
+    }
+}
+
 #[proc_macro_derive(Encode, attributes(sqlx))]
 pub fn derive_encode(tokenstream: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(tokenstream as syn::DeriveInput);
@@ -48,6 +59,15 @@ pub fn derive_type(tokenstream: TokenStream) -> TokenStream {
     }
 }
 
+#[proc_macro_derive(Id, attributes(sqlx))]
+pub fn derive_id(tokenstream: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(tokenstream as syn::DeriveInput);
+    match derives::expand_derive_id(&input) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
 #[proc_macro_derive(FromRow, attributes(sqlx))]
 pub fn derive_from_row(input: TokenStream) -> TokenStream {
     let input = syn::parse_macro_input!(input as syn::DeriveInput);
@@ -1,21 +1,30 @@
+use crate::io::MySqlBufMutExt;
 use crate::protocol::text::ColumnType;
 use crate::{
-    MySql, MySqlColumn, MySqlConnectOptions, MySqlConnection, MySqlQueryResult, MySqlRow,
-    MySqlTransactionManager, MySqlTypeInfo,
+    MySql, MySqlArguments, MySqlColumn, MySqlConnectOptions, MySqlConnection, MySqlQueryResult,
+    MySqlRow, MySqlTransactionManager, MySqlTypeInfo,
 };
+use crate::{MySqlValueFormat, MySqlValueRef};
 use either::Either;
 use futures_core::future::BoxFuture;
 use futures_core::stream::BoxStream;
 use futures_util::{StreamExt, TryFutureExt, TryStreamExt};
 use sqlx_core::any::{
-    Any, AnyArguments, AnyColumn, AnyConnectOptions, AnyConnectionBackend, AnyQueryResult, AnyRow,
-    AnyStatement, AnyTypeInfo, AnyTypeInfoKind,
+    text, Any, AnyArgumentValue, AnyArguments, AnyColumn, AnyConnectOptions, AnyConnectionBackend,
+    AnyDecodedValue, AnyQueryResult, AnyRow, AnyStatement, AnyTypeInfo, AnyTypeInfoKind,
+    AnyTypeRegistry, AnyValueKind,
 };
-use sqlx_core::connection::Connection;
+use sqlx_core::bytes::Buf;
+use sqlx_core::connection::{ConnectOptions, Connection, RunningQuery, ServerVersion};
 use sqlx_core::database::Database;
+use sqlx_core::decode::Decode;
 use sqlx_core::describe::Describe;
+use sqlx_core::encode::{Encode, IsNull};
+use sqlx_core::error::BoxDynError;
 use sqlx_core::executor::Executor;
-use sqlx_core::transaction::TransactionManager;
+use sqlx_core::transaction::{TransactionManager, TransactionOptions};
+use sqlx_core::types::Type;
+use sqlx_core::value::ValueRef;
 
 sqlx_core::declare_driver_with_optional_migrate!(DRIVER = MySql);
 
@@ -36,10 +45,22 @@ impl AnyConnectionBackend for MySqlConnection {
         Connection::ping(self)
     }
 
+    fn is_open(&mut self) -> bool {
+        Connection::is_open(self)
+    }
+
+    fn is_poisoned(&self) -> bool {
+        Connection::is_poisoned(self)
+    }
+
     fn begin(&mut self) -> BoxFuture<'_, sqlx_core::Result<()>> {
         MySqlTransactionManager::begin(self)
     }
 
+    fn begin_with(&mut self, options: TransactionOptions) -> BoxFuture<'_, sqlx_core::Result<()>> {
+        MySqlTransactionManager::begin_with(self, options)
+    }
+
     fn commit(&mut self) -> BoxFuture<'_, sqlx_core::Result<()>> {
         MySqlTransactionManager::commit(self)
     }
@@ -56,6 +77,18 @@ impl AnyConnectionBackend for MySqlConnection {
         Connection::shrink_buffers(self);
     }
 
+    fn server_version(&self) -> ServerVersion {
+        Connection::server_version(self)
+    }
+
+    fn running_query(&self) -> Option<RunningQuery> {
+        Connection::running_query(self)
+    }
+
+    fn set_type_registry(&mut self, registry: AnyTypeRegistry) {
+        self.any_type_registry = registry;
+    }
+
     fn flush(&mut self) -> BoxFuture<'_, sqlx_core::Result<()>> {
         Connection::flush(self)
     }
@@ -77,15 +110,21 @@ impl AnyConnectionBackend for MySqlConnection {
         arguments: Option<AnyArguments<'q>>,
     ) -> BoxStream<'q, sqlx_core::Result<Either<AnyQueryResult, AnyRow>>> {
         let persistent = arguments.is_some();
-        let args = arguments.as_ref().map(AnyArguments::convert_to);
+        let args = arguments.as_ref().map(mysql_arguments).transpose();
+        let registry = self.any_type_registry.clone();
 
         Box::pin(
-            self.run(query, args, persistent)
+            futures_util::future::ready(args)
+                .and_then(move |args| self.run(query, args, persistent, true))
                 .try_flatten_stream()
-                .map(|res| {
+                .map(move |res| {
                     Ok(match res? {
                         Either::Left(result) => Either::Left(map_result(result)),
-                        Either::Right(row) => Either::Right(AnyRow::try_from(&row)?),
+                        Either::Right(row) => Either::Right(AnyRow::map_from(
+                            &row,
+                            row.column_names.clone(),
+                            &registry,
+                        )?),
                     })
                 }),
         )
@@ -97,14 +136,20 @@ impl AnyConnectionBackend for MySqlConnection {
         arguments: Option<AnyArguments<'q>>,
     ) -> BoxFuture<'q, sqlx_core::Result<Option<AnyRow>>> {
         let persistent = arguments.is_some();
-        let args = arguments.as_ref().map(AnyArguments::convert_to);
+        let args = arguments.as_ref().map(mysql_arguments).transpose();
+        let registry = self.any_type_registry.clone();
 
         Box::pin(async move {
-            let stream = self.run(query, args, persistent).await?;
+            let args = args?;
+            let stream = self.run(query, args, persistent, true).await?;
             futures_util::pin_mut!(stream);
 
             if let Some(Either::Right(row)) = stream.try_next().await? {
-                return Ok(Some(AnyRow::try_from(&row)?));
+                return Ok(Some(AnyRow::map_from(
+                    &row,
+                    row.column_names.clone(),
+                    &registry,
+                )?));
             }
 
             Ok(None)
@@ -116,20 +161,25 @@ impl AnyConnectionBackend for MySqlConnection {
         sql: &'q str,
         _parameters: &[AnyTypeInfo],
     ) -> BoxFuture<'c, sqlx_core::Result<AnyStatement<'q>>> {
+        let registry = self.any_type_registry.clone();
+
         Box::pin(async move {
             let statement = Executor::prepare_with(self, sql, &[]).await?;
             AnyStatement::try_from_statement(
                 sql,
                 &statement,
                 statement.metadata.column_names.clone(),
+                &registry,
             )
         })
     }
 
     fn describe<'q>(&'q mut self, sql: &'q str) -> BoxFuture<'q, sqlx_core::Result<Describe<Any>>> {
+        let registry = self.any_type_registry.clone();
+
         Box::pin(async move {
             let describe = Executor::describe(self, sql).await?;
-            describe.try_into_any()
+            describe.try_into_any(&registry)
         })
     }
 }
@@ -153,9 +203,18 @@ impl<'a> TryFrom<&'a MySqlTypeInfo> for AnyTypeInfo {
                 ColumnType::String | ColumnType::VarString | ColumnType::VarChar => {
                     AnyTypeInfoKind::Text
                 }
+                ColumnType::Decimal | ColumnType::NewDecimal => AnyTypeInfoKind::Numeric,
+                ColumnType::Date => AnyTypeInfoKind::Date,
+                ColumnType::Time => AnyTypeInfoKind::Time,
+                ColumnType::Datetime | ColumnType::Timestamp => AnyTypeInfoKind::Timestamp,
+                ColumnType::Json => AnyTypeInfoKind::Json,
                 _ => {
                     return Err(sqlx_core::Error::AnyDriverError(
-                        format!("Any driver does not support MySql type {type_info:?}").into(),
+                        format!(
+                            "Any driver does not support MySql type {type_info:?}; \
+                             it can be mapped with `AnyTypeRegistry`"
+                        )
+                        .into(),
                     ))
                 }
             },
@@ -181,7 +240,7 @@ impl<'a> TryFrom<&'a MySqlRow> for AnyRow {
     type Error = sqlx_core::Error;
 
     fn try_from(row: &'a MySqlRow) -> Result<Self, Self::Error> {
-        AnyRow::map_from(row, row.column_names.clone())
+        AnyRow::map_from(row, row.column_names.clone(), &AnyTypeRegistry::default())
     }
 }
 
@@ -191,10 +250,269 @@ impl<'a> TryFrom<&'a AnyConnectOptions> for MySqlConnectOptions {
     fn try_from(any_opts: &'a AnyConnectOptions) -> Result<Self, Self::Error> {
         let mut opts = Self::parse_from_url(&any_opts.database_url)?;
         opts.log_settings = any_opts.log_settings.clone();
+        if let Some(application_name) = any_opts.get_application_name() {
+            opts = opts.connect_attr("program_name", application_name);
+        }
         Ok(opts)
     }
 }
 
+impl Type<MySql> for AnyArgumentValue<'_> {
+    fn type_info() -> MySqlTypeInfo {
+        // overridden by `produces()`
+        MySqlTypeInfo::binary(ColumnType::String)
+    }
+}
+
+impl<'q> Encode<'q, MySql> for AnyArgumentValue<'q> {
+    fn produces(&self) -> Option<MySqlTypeInfo> {
+        Some(match self.0 {
+            AnyValueKind::Bool(_) => <bool as Type<MySql>>::type_info(),
+            AnyValueKind::SmallInt(_) => <i16 as Type<MySql>>::type_info(),
+            AnyValueKind::Integer(_) => <i32 as Type<MySql>>::type_info(),
+            AnyValueKind::BigInt(_) => <i64 as Type<MySql>>::type_info(),
+            AnyValueKind::Real(_) => <f32 as Type<MySql>>::type_info(),
+            AnyValueKind::Double(_) => <f64 as Type<MySql>>::type_info(),
+            AnyValueKind::Blob(_) | AnyValueKind::Uuid(_) => {
+                MySqlTypeInfo::binary(ColumnType::Blob)
+            }
+            AnyValueKind::Numeric(_) => MySqlTypeInfo::binary(ColumnType::NewDecimal),
+            AnyValueKind::Date(_) => MySqlTypeInfo::binary(ColumnType::Date),
+            AnyValueKind::Time(_) => MySqlTypeInfo::binary(ColumnType::Time),
+            AnyValueKind::Timestamp(_) | AnyValueKind::TimestampTz(_) => {
+                MySqlTypeInfo::binary(ColumnType::Datetime)
+            }
+            AnyValueKind::Null => MySqlTypeInfo::binary(ColumnType::Null),
+            // JSON is sent as `CHAR`, like `Json<T>`, and arrays as JSON
+            _ => MySqlTypeInfo::binary(ColumnType::String),
+        })
+    }
+
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        match self.0 {
+            AnyValueKind::Null => return IsNull::Yes,
+            AnyValueKind::Bool(b) => return Encode::<MySql>::encode_by_ref(b, buf),
+            AnyValueKind::SmallInt(i) => return Encode::<MySql>::encode_by_ref(i, buf),
+            AnyValueKind::Integer(i) => return Encode::<MySql>::encode_by_ref(i, buf),
+            AnyValueKind::BigInt(i) => return Encode::<MySql>::encode_by_ref(i, buf),
+            AnyValueKind::Real(r) => return Encode::<MySql>::encode_by_ref(r, buf),
+            AnyValueKind::Double(d) => return Encode::<MySql>::encode_by_ref(d, buf),
+            AnyValueKind::Text(t) | AnyValueKind::Numeric(t) | AnyValueKind::Json(t) => {
+                buf.put_str_lenenc(t)
+            }
+            AnyValueKind::Blob(b) => buf.put_bytes_lenenc(b),
+            AnyValueKind::Uuid(u) => buf.put_bytes_lenenc(u),
+            AnyValueKind::Date(days) => {
+                buf.push(4);
+                encode_date(*days, buf);
+            }
+            AnyValueKind::Time(micros) => {
+                let negative = *micros < 0;
+                let micros = micros.unsigned_abs();
+                let days = micros / text::MICROS_PER_DAY as u64;
+                let (len, time) = time_len(micros % text::MICROS_PER_DAY as u64);
+
+                buf.push(len + 5);
+                buf.push(negative as u8);
+                buf.extend_from_slice(
+                    &u32::try_from(days)
+                        .expect("BUG: time not checked by `check_argument()`")
+                        .to_le_bytes(),
+                );
+                encode_time(time, len, buf);
+            }
+            AnyValueKind::Timestamp(micros) | AnyValueKind::TimestampTz(micros) => {
+                let days = timestamp_days(*micros);
+                let (len, time) = time_len(micros.rem_euclid(text::MICROS_PER_DAY) as u64);
+
+                buf.push(if len == 0 { 4 } else { len + 4 });
+                encode_date(days, buf);
+                encode_time(time, len, buf);
+            }
+            AnyValueKind::Array { .. } => buf.put_str_lenenc(&self.0.to_string()),
+            // AnyValueKind is `#[non_exhaustive]` but we should have covered everything
+            value => unreachable!("BUG: missing mapping for {value:?}"),
+        }
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, MySql> for AnyDecodedValue {
+    fn decode(value: MySqlValueRef<'r>) -> Result<Self, BoxDynError> {
+        if value.is_null() {
+            return Ok(AnyDecodedValue(AnyValueKind::Null));
+        }
+
+        let kind = AnyTypeInfo::try_from(&value.type_info)?.kind;
+        let binary = matches!(value.format(), MySqlValueFormat::Binary);
+
+        Ok(AnyDecodedValue(match kind {
+            AnyTypeInfoKind::Numeric => AnyValueKind::Numeric(value.as_str()?.to_owned().into()),
+            AnyTypeInfoKind::Json => AnyValueKind::Json(value.as_str()?.to_owned().into()),
+            AnyTypeInfoKind::Date if binary => {
+                let buf = value.as_bytes()?;
+                AnyValueKind::Date(decode_date(buf.get(1..).unwrap_or_default())?)
+            }
+            AnyTypeInfoKind::Date => AnyValueKind::Date(text::parse_date(value.as_str()?)?),
+            AnyTypeInfoKind::Time if binary => {
+                let mut buf = value.as_bytes()?;
+
+                // MySQL specifies that if the time is 0, the length is 0 and no further data is sent
+                let time = match buf.get_u8() {
+                    0 => 0,
+                    len @ (8 | 12) if buf.len() >= usize::from(len) => {
+                        let negative = buf.get_u8() != 0;
+                        let days = i64::from(buf.get_u32_le());
+                        let time = days * text::MICROS_PER_DAY + decode_time(len - 5, buf);
+
+                        if negative {
+                            -time
+                        } else {
+                            time
+                        }
+                    }
+                    len => return Err(format!("invalid length for MySQL TIME: {len}").into()),
+                };
+
+                AnyValueKind::Time(time)
+            }
+            AnyTypeInfoKind::Time => AnyValueKind::Time(text::parse_time(value.as_str()?)?),
+            AnyTypeInfoKind::Timestamp if binary => {
+                let buf = value.as_bytes()?;
+                let len = buf.first().copied().unwrap_or_default();
+
+                if !matches!(len, 4 | 7 | 11) || buf.len() <= usize::from(len) {
+                    return Err(format!("invalid length for MySQL DATETIME: {len}").into());
+                }
+
+                let days = decode_date(&buf[1..])?;
+                let time = if len > 4 {
+                    decode_time(len - 4, &buf[5..])
+                } else {
+                    0
+                };
+
+                AnyValueKind::Timestamp(i64::from(days) * text::MICROS_PER_DAY + time)
+            }
+            AnyTypeInfoKind::Timestamp => {
+                AnyValueKind::Timestamp(text::parse_timestamp(value.as_str()?)?)
+            }
+            _ => return Err(format!("unexpected MySQL value of type {}", value.type_info).into()),
+        }))
+    }
+}
+
+/// The largest magnitude of a MySQL `TIME`, `838:59:59`, in microseconds.
+const MAX_TIME_MICROS: u64 = (838 * 3_600 + 59 * 60 + 59) * text::MICROS_PER_SECOND as u64;
+
+/// Convert `arguments`, first checking that each can be encoded for MySQL, as `Encode` can't
+/// return an error.
+fn mysql_arguments(arguments: &AnyArguments<'_>) -> sqlx_core::Result<MySqlArguments> {
+    arguments
+        .values
+        .0
+        .iter()
+        .try_for_each(check_argument)
+        .map_err(sqlx_core::Error::Encode)?;
+
+    Ok(arguments.convert_to())
+}
+
+fn check_argument(value: &AnyValueKind<'_>) -> Result<(), BoxDynError> {
+    // MySQL supports years from 1000 - 9999
+    let year_in_range = |days| (1000..=9999).contains(&text::civil_from_days(days).0);
+
+    let (in_range, type_name) = match value {
+        AnyValueKind::Date(days) => (year_in_range(*days), "DATE"),
+        AnyValueKind::Time(micros) => (micros.unsigned_abs() <= MAX_TIME_MICROS, "TIME"),
+        AnyValueKind::Timestamp(micros) | AnyValueKind::TimestampTz(micros) => {
+            (year_in_range(timestamp_days(*micros)), "DATETIME")
+        }
+        _ => return Ok(()),
+    };
+
+    if !in_range {
+        return Err(format!("{value} out of range for MySQL {type_name}").into());
+    }
+
+    Ok(())
+}
+
+/// The days since `1970-01-01` of a timestamp, which always fit in an `i32`.
+fn timestamp_days(micros: i64) -> i32 {
+    micros.div_euclid(text::MICROS_PER_DAY) as i32
+}
+
+fn encode_date(days: i32, buf: &mut Vec<u8>) {
+    let (year, month, day) = text::civil_from_days(days);
+
+    let year = u16::try_from(year).expect("BUG: date not checked by `check_argument()`");
+
+    buf.extend_from_slice(&year.to_le_bytes());
+    buf.push(month as u8);
+    buf.push(day as u8);
+}
+
+fn decode_date(mut buf: &[u8]) -> Result<i32, BoxDynError> {
+    // MySQL specifies that if there are no bytes, this is all zeros
+    if buf.len() < 4 {
+        return Err("MySQL zero dates are not supported".into());
+    }
+
+    let year = i32::from(buf.get_u16_le());
+    let month = u32::from(buf[0]);
+    let day = u32::from(buf[1]);
+
+    let days = text::days_from_civil(year, month, day);
+
+    if text::civil_from_days(days) != (year, month, day) {
+        return Err(format!("server returned invalid date: {year}/{month}/{day}").into());
+    }
+
+    Ok(days)
+}
+
+/// The length of the time fields to send, `0`, `3` or `7`, and the time in microseconds.
+fn time_len(micros: u64) -> (u8, u64) {
+    match micros {
+        0 => (0, micros),
+        _ if micros % text::MICROS_PER_SECOND as u64 == 0 => (3, micros),
+        _ => (7, micros),
+    }
+}
+
+fn encode_time(micros: u64, len: u8, buf: &mut Vec<u8>) {
+    if len == 0 {
+        return;
+    }
+
+    let seconds = micros / text::MICROS_PER_SECOND as u64;
+
+    buf.push((seconds / 3_600) as u8);
+    buf.push((seconds / 60 % 60) as u8);
+    buf.push((seconds % 60) as u8);
+
+    if len > 3 {
+        buf.extend_from_slice(&((micros % text::MICROS_PER_SECOND as u64) as u32).to_le_bytes());
+    }
+}
+
+/// Decode the hours, minutes, seconds and optional microseconds of a time, given their length.
+fn decode_time(len: u8, mut buf: &[u8]) -> i64 {
+    let hours = i64::from(buf.get_u8());
+    let minutes = i64::from(buf.get_u8());
+    let seconds = i64::from(buf.get_u8());
+
+    let micros = if len > 3 {
+        i64::from(buf.get_u32_le())
+    } else {
+        0
+    };
+
+    ((hours * 60 + minutes) * 60 + seconds) * text::MICROS_PER_SECOND + micros
+}
+
 fn map_result(result: MySqlQueryResult) -> AnyQueryResult {
     AnyQueryResult {
         rows_affected: result.rows_affected,
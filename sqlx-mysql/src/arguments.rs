@@ -1,7 +1,10 @@
+use std::mem;
+
 use crate::encode::{Encode, IsNull};
 use crate::types::Type;
 use crate::{MySql, MySqlTypeInfo};
 pub(crate) use sqlx_core::arguments::*;
+use sqlx_core::buffer_pool::buffer_pool;
 
 /// Implementation of [`Arguments`] for MySQL.
 #[derive(Debug, Default, Clone)]
@@ -19,6 +22,11 @@ impl MySqlArguments {
         let ty = value.produces().unwrap_or_else(T::type_info);
         let index = self.types.len();
 
+        // the first value takes a pooled buffer, unless one was reserved
+        if self.values.capacity() == 0 {
+            self.values = buffer_pool().take(0);
+        }
+
         self.types.push(ty);
         self.null_bitmap.resize((index / 8) + 1, 0);
 
@@ -33,12 +41,18 @@ impl MySqlArguments {
     }
 }
 
+impl Drop for MySqlArguments {
+    fn drop(&mut self) {
+        buffer_pool().recycle(mem::take(&mut self.values));
+    }
+}
+
 impl<'q> Arguments<'q> for MySqlArguments {
     type Database = MySql;
 
     fn reserve(&mut self, len: usize, size: usize) {
         self.types.reserve(len);
-        self.values.reserve(size);
+        buffer_pool().reserve(&mut self.values, size);
     }
 
     fn add<T>(&mut self, value: T)
@@ -47,4 +61,11 @@ impl<'q> Arguments<'q> for MySqlArguments {
     {
         self.add(value)
     }
+
+    fn shorten<'a>(self) -> Option<MySqlArguments>
+    where
+        'q: 'a,
+    {
+        Some(self)
+    }
 }
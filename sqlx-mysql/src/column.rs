@@ -12,6 +12,10 @@ pub struct MySqlColumn {
 
     #[cfg_attr(feature = "offline", serde(skip))]
     pub(crate) flags: Option<ColumnFlags>,
+
+    // the table or its alias in the query, for inferring nullability
+    #[cfg_attr(feature = "offline", serde(skip))]
+    pub(crate) table: Option<UStr>,
 }
 
 impl Column for MySqlColumn {
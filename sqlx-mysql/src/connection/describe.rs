@@ -0,0 +1,406 @@
+use crate::protocol::text::ColumnFlags;
+use crate::MySqlColumn;
+
+// aggregates that are NULL if there are no rows
+const AGGREGATES: &[&str] = &[
+    "AVG",
+    "BIT_XOR",
+    "GROUP_CONCAT",
+    "JSON_ARRAYAGG",
+    "JSON_OBJECTAGG",
+    "MAX",
+    "MIN",
+    "STD",
+    "STDDEV",
+    "STDDEV_POP",
+    "STDDEV_SAMP",
+    "SUM",
+    "VARIANCE",
+    "VAR_POP",
+    "VAR_SAMP",
+];
+
+// keywords that can follow a table or an expression, and so can't be an alias
+const RESERVED: &[&str] = &[
+    "AND",
+    "AS",
+    "ASC",
+    "CROSS",
+    "DESC",
+    "EXCEPT",
+    "FOR",
+    "FORCE",
+    "FROM",
+    "FULL",
+    "GROUP",
+    "HAVING",
+    "IGNORE",
+    "INNER",
+    "INTERSECT",
+    "INTO",
+    "JOIN",
+    "LATERAL",
+    "LEFT",
+    "LIMIT",
+    "LOCK",
+    "NATURAL",
+    "ON",
+    "OR",
+    "ORDER",
+    "OUTER",
+    "OVER",
+    "PARTITION",
+    "RETURNING",
+    "RIGHT",
+    "STRAIGHT_JOIN",
+    "UNION",
+    "USE",
+    "USING",
+    "WHERE",
+    "WINDOW",
+];
+
+/// Infers the nullability of the columns of `sql` from the flags MySQL reports for them.
+///
+/// MySQL may not clear `NOT_NULL` for columns from the nullable side of an outer join, or for
+/// aggregates without `GROUP BY`, which are `NULL` if there are no rows. These are also found by
+/// scanning the SQL, which may produce false positives but those don't cause runtime errors.
+///
+/// The columns of a MariaDB `RETURNING` clause are described like those of a `SELECT`. MariaDB
+/// doesn't set `NOT_NULL` for the next value of a sequence, which is never `NULL`, so those are
+/// found by scanning the SQL too.
+pub(super) fn infer_nullable(sql: &str, columns: &[MySqlColumn]) -> Vec<Option<bool>> {
+    let nullability = Nullability::scan(sql);
+
+    columns
+        .iter()
+        .map(|column| {
+            if nullability.is_nullable(column) {
+                return Some(true);
+            }
+
+            if nullability.is_next_value(column) {
+                return Some(false);
+            }
+
+            column
+                .flags
+                .map(|flags| !flags.contains(ColumnFlags::NOT_NULL))
+        })
+        .collect()
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Nullability<'a> {
+    // the names or aliases of tables on the nullable side of an outer join
+    tables: Vec<&'a str>,
+
+    // the aliases of aggregates in `AGGREGATES`
+    aggregates: Vec<&'a str>,
+
+    // the aliases of `NEXTVAL(seq)` and `NEXT VALUE FOR seq`
+    next_values: Vec<&'a str>,
+
+    group_by: bool,
+}
+
+impl<'a> Nullability<'a> {
+    fn scan(sql: &'a str) -> Self {
+        let tokens = tokenize(sql);
+
+        let mut nullability = Nullability::default();
+
+        // the tables joined so far in the current `FROM` clause
+        let mut joined = Vec::new();
+
+        for (i, token) in tokens.iter().enumerate() {
+            let Token::Word(word) = *token else {
+                continue;
+            };
+
+            let next = &tokens[i + 1..];
+
+            if word.eq_ignore_ascii_case("FROM") {
+                joined.clear();
+                joined.extend(table_ref(next));
+            } else if word.eq_ignore_ascii_case("JOIN")
+                || word.eq_ignore_ascii_case("STRAIGHT_JOIN")
+            {
+                let table = table_ref(next);
+
+                let mut prev = tokens[..i]
+                    .iter()
+                    .rev()
+                    .skip_while(|token| token.is_keyword("OUTER"));
+
+                match prev.next() {
+                    Some(token) if token.is_keyword("LEFT") => nullability.tables.extend(table),
+                    // everything joined before is on the nullable side
+                    Some(token) if token.is_keyword("RIGHT") => {
+                        nullability.tables.extend(joined.iter().copied())
+                    }
+                    _ => {}
+                }
+
+                joined.extend(table);
+            } else if word.eq_ignore_ascii_case("GROUP")
+                && next.first().map_or(false, |token| token.is_keyword("BY"))
+            {
+                nullability.group_by = true;
+            } else if is_aggregate(word) && next.first() == Some(&Token::Punct(b'(')) {
+                nullability.aggregates.extend(alias(after_parens(next)));
+            } else if word.eq_ignore_ascii_case("NEXTVAL")
+                && next.first() == Some(&Token::Punct(b'('))
+            {
+                nullability.next_values.extend(alias(after_parens(next)));
+            } else if word.eq_ignore_ascii_case("NEXT")
+                && next.len() >= 3
+                && next[0].is_keyword("VALUE")
+                && next[1].is_keyword("FOR")
+            {
+                // `NEXT VALUE FOR [database.]sequence`
+                let rest = match &next[2..] {
+                    [_, Token::Punct(b'.'), _, rest @ ..] => rest,
+                    [_, rest @ ..] => rest,
+                    [] => &[],
+                };
+
+                nullability.next_values.extend(alias(rest));
+            }
+        }
+
+        nullability
+    }
+
+    fn is_nullable(&self, column: &MySqlColumn) -> bool {
+        if let Some(table) = &column.table {
+            if self.tables.iter().any(|t| t.eq_ignore_ascii_case(table)) {
+                return true;
+            }
+        }
+
+        if self.group_by {
+            return false;
+        }
+
+        // without an alias, the name of the column is the expression
+        let is_call = match column.name.split_once('(') {
+            Some((function, _)) => is_aggregate(function.trim_end()),
+            None => false,
+        };
+
+        is_call
+            || self
+                .aggregates
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(&column.name))
+    }
+
+    fn is_next_value(&self, column: &MySqlColumn) -> bool {
+        // without an alias, the name of the column is the expression
+        let name = column.name.trim_start();
+
+        let is_call = name
+            .get(..8)
+            .map_or(false, |prefix| prefix.eq_ignore_ascii_case("NEXTVAL("))
+            || name.get(..15).map_or(false, |prefix| {
+                prefix.eq_ignore_ascii_case("NEXT VALUE FOR ")
+            });
+
+        is_call
+            || self
+                .next_values
+                .iter()
+                .any(|alias| alias.eq_ignore_ascii_case(&column.name))
+    }
+}
+
+fn is_aggregate(word: &str) -> bool {
+    AGGREGATES.iter().any(|a| word.eq_ignore_ascii_case(a))
+}
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    // a keyword, unquoted identifier or number
+    Word(&'a str),
+    // a quoted identifier
+    Quoted(&'a str),
+    // a string literal
+    Literal,
+    Punct(u8),
+}
+
+impl Token<'_> {
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self, Token::Word(word) if word.eq_ignore_ascii_case(keyword))
+    }
+}
+
+fn tokenize(sql: &str) -> Vec<Token<'_>> {
+    let bytes = sql.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    // the index of the next `end` at or after `from`, or of the end of the SQL
+    let find = |from: usize, end: &str| sql[from..].find(end).map_or(sql.len(), |n| from + n);
+
+    let is_word = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || !b.is_ascii();
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b if b.is_ascii_whitespace() => i += 1,
+
+            b'#' => i = find(i, "\n"),
+
+            b'-' if sql[i..].starts_with("--")
+                && bytes.get(i + 2).map_or(true, u8::is_ascii_whitespace) =>
+            {
+                i = find(i, "\n")
+            }
+
+            b'/' if sql[i..].starts_with("/*") => i = find(i + 2, "*/") + 2,
+
+            quote @ (b'\'' | b'"') => {
+                i += 1;
+
+                while i < bytes.len() {
+                    match bytes[i] {
+                        b'\\' => i += 2,
+                        b if b == quote && bytes.get(i + 1) == Some(&quote) => i += 2,
+                        b if b == quote => break,
+                        _ => i += 1,
+                    }
+                }
+
+                i += 1;
+                tokens.push(Token::Literal);
+            }
+
+            b'`' => {
+                let end = find(i + 1, "`");
+                tokens.push(Token::Quoted(&sql[i + 1..end]));
+                i = end + 1;
+            }
+
+            b if is_word(b) => {
+                let start = i;
+
+                while i < bytes.len() && is_word(bytes[i]) {
+                    i += 1;
+                }
+
+                tokens.push(Token::Word(&sql[start..i]));
+            }
+
+            b => {
+                tokens.push(Token::Punct(b));
+                i += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+/// The tokens after the parentheses that `tokens` starts with.
+fn after_parens<'t, 'a>(tokens: &'t [Token<'a>]) -> &'t [Token<'a>] {
+    let mut depth = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            Token::Punct(b'(') => depth += 1,
+            Token::Punct(b')') => depth -= 1,
+            _ => {}
+        }
+
+        if depth == 0 {
+            return &tokens[i + 1..];
+        }
+    }
+
+    &[]
+}
+
+/// The alias or name of the table that `tokens` starts with.
+fn table_ref<'a>(tokens: &[Token<'a>]) -> Option<&'a str> {
+    let (name, rest) = match tokens {
+        [Token::Punct(b'('), ..] => (None, after_parens(tokens)),
+        // `database.table`
+        [_, Token::Punct(b'.'), name, rest @ ..] => (ident(name), rest),
+        [name, rest @ ..] => (ident(name), rest),
+        [] => return None,
+    };
+
+    alias(rest).or(name)
+}
+
+/// The alias at the start of `tokens`.
+fn alias<'a>(tokens: &[Token<'a>]) -> Option<&'a str> {
+    match tokens {
+        [as_, alias, ..] if as_.is_keyword("AS") => ident(alias),
+        [alias, ..] => ident(alias),
+        [] => None,
+    }
+}
+
+fn ident<'a>(token: &Token<'a>) -> Option<&'a str> {
+    match *token {
+        Token::Word(word) if !RESERVED.iter().any(|r| word.eq_ignore_ascii_case(r)) => Some(word),
+        Token::Quoted(ident) => Some(ident),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_scan_nullability() {
+    let scan = |sql| {
+        let Nullability {
+            tables,
+            aggregates,
+            group_by,
+            ..
+        } = Nullability::scan(sql);
+
+        (tables, aggregates, group_by)
+    };
+
+    assert_eq!(
+        scan(
+            "SELECT a.id, b.id FROM tweet a LEFT OUTER JOIN `db`.`tweet` AS b ON a.id = b.id \
+             LEFT JOIN accounts USING (id) JOIN users u ON true"
+        ),
+        (vec!["b", "accounts"], vec![], false)
+    );
+
+    assert_eq!(
+        scan("SELECT * FROM tweet a JOIN (SELECT 1) b ON true RIGHT JOIN tweet c ON false"),
+        (vec!["a", "b"], vec![], false)
+    );
+
+    assert_eq!(
+        scan(
+            "SELECT MAX(id) AS max_id, COUNT(*) count, sum(id) -- LEFT JOIN a\n\
+             FROM tweet WHERE text != 'LEFT JOIN b'"
+        ),
+        (vec![], vec!["max_id"], false)
+    );
+
+    assert_eq!(
+        scan("SELECT owner_id, MAX(id) FROM tweet GROUP BY owner_id"),
+        (vec![], vec![], true)
+    );
+}
+
+#[test]
+fn test_scan_next_values() {
+    let scan = |sql| Nullability::scan(sql).next_values;
+
+    assert_eq!(
+        scan("SELECT NEXTVAL(s) AS id, NEXT VALUE FOR db.s next_id, nextval(s)"),
+        ["id", "next_id"]
+    );
+    assert_eq!(
+        scan("INSERT INTO t (id) VALUES (NEXTVAL(s)) RETURNING id"),
+        Vec::<&str>::new()
+    );
+}
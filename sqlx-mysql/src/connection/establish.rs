@@ -4,14 +4,16 @@ use futures_core::future::BoxFuture;
 
 use crate::collation::{CharSet, Collation};
 use crate::common::StatementCache;
-use crate::connection::{tls, MySqlStream, MAX_PACKET_SIZE};
+use crate::connection::{tls, MySqlStream, RunningQuery, MAX_PACKET_SIZE};
 use crate::error::Error;
 use crate::net::{Socket, WithSocket};
+use crate::protocol::auth::AuthPlugin;
 use crate::protocol::connect::{
     AuthSwitchRequest, AuthSwitchResponse, Handshake, HandshakeResponse,
 };
 use crate::protocol::Capabilities;
 use crate::{MySqlConnectOptions, MySqlConnection, MySqlSslMode};
+use sqlx_core::connection::ServerVersion;
 
 impl MySqlConnection {
     pub(crate) async fn establish(options: &MySqlConnectOptions) -> Result<Self, Error> {
@@ -29,6 +31,13 @@ impl MySqlConnection {
             transaction_depth: 0,
             cache_statement: StatementCache::new(options.statement_cache_capacity),
             log_settings: options.log_settings.clone(),
+            compatibility_mode: options.compatibility_mode,
+            exact_decimals: options.exact_decimals,
+            max_row_size: options.max_row_size,
+            max_result_size: options.max_result_size,
+            #[cfg(feature = "any")]
+            any_type_registry: Default::default(),
+            running_query: RunningQuery::default(),
         })
     }
 }
@@ -80,36 +89,16 @@ impl<'a> DoHandshake<'a> {
         let handshake: Handshake = stream.recv_packet().await?.decode()?;
 
         let mut plugin = handshake.auth_plugin;
-        let nonce = handshake.auth_plugin_data;
-
-        // FIXME: server version parse is a bit ugly
-        // expecting MAJOR.MINOR.PATCH
-
-        let mut server_version = handshake.server_version.split('.');
-
-        let server_version_major: u16 = server_version
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .unwrap_or(0);
 
-        let server_version_minor: u16 = server_version
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .unwrap_or(0);
-
-        let server_version_patch: u16 = server_version
-            .next()
-            .unwrap_or_default()
-            .parse()
-            .unwrap_or(0);
+        // some servers speaking the MySQL protocol, such as older TiDB versions and Vitess,
+        // advertise `caching_sha2_password` without supporting it; a server that does will
+        // ask us to switch back to it
+        if options.compatibility_mode && matches!(plugin, Some(AuthPlugin::CachingSha2Password)) {
+            plugin = Some(AuthPlugin::MySqlNativePassword);
+        }
+        let nonce = handshake.auth_plugin_data;
 
-        stream.server_version = (
-            server_version_major,
-            server_version_minor,
-            server_version_patch,
-        );
+        stream.server_version = ServerVersion::parse_mysql(&handshake.server_version);
 
         stream.capabilities &= handshake.server_capabilities;
         stream.capabilities |= Capabilities::PROTOCOL_41;
@@ -129,6 +118,7 @@ impl<'a> DoHandshake<'a> {
             database: options.database.as_deref(),
             auth_plugin: plugin,
             auth_response: auth_response.as_deref(),
+            connect_attrs: &options.connect_attrs,
         });
 
         stream.flush().await?;
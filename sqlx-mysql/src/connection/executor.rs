@@ -1,3 +1,4 @@
+use super::describe::infer_nullable;
 use super::MySqlStream;
 use crate::connection::stream::Waiting;
 use crate::describe::Describe;
@@ -10,7 +11,7 @@ use crate::protocol::response::Status;
 use crate::protocol::statement::{
     BinaryRow, Execute as StatementExecute, Prepare, PrepareOk, StmtClose,
 };
-use crate::protocol::text::{ColumnDefinition, ColumnFlags, Query, TextRow};
+use crate::protocol::text::{ColumnDefinition, Query, TextRow};
 use crate::statement::{MySqlStatement, MySqlStatementMetadata};
 use crate::HashMap;
 use crate::{
@@ -39,6 +40,9 @@ impl MySqlConnection {
         // https://dev.mysql.com/doc/internals/en/com-stmt-prepare-response.html#packet-COM_STMT_PREPARE_OK
 
         self.stream.send_packet(Prepare { query: sql }).await?;
+        // the response to `COM_STMT_PREPARE` also describes the statement
+        self.stream.wire_stats_mut().parses += 1;
+        self.stream.wire_stats_mut().describes += 1;
 
         let ok: PrepareOk = self.stream.recv().await?;
 
@@ -88,14 +92,26 @@ impl MySqlConnection {
         sql: &'q str,
         arguments: Option<MySqlArguments>,
         persistent: bool,
+        size_limited: bool,
     ) -> Result<impl Stream<Item = Result<Either<MySqlQueryResult, MySqlRow>, Error>> + 'e, Error>
     {
         let mut logger = QueryLogger::new(sql, self.log_settings.clone());
+        let running_query = self.running_query.start(sql);
 
         self.stream.wait_until_ready().await?;
         self.stream.waiting.push_back(Waiting::Result);
 
+        // anything before this belongs to the previous query
+        let wire_stats_start = self.stream.wire_stats();
+
+        // unless the query opted out, rows over the limits are rejected
+        let max_row_size = self.max_row_size.filter(|_| size_limited);
+        let max_result_size = self.max_result_size.filter(|_| size_limited);
+
         Ok(Box::pin(try_stream! {
+            // cleared once the query completes or the stream is dropped
+            let _running_query = running_query;
+
             // make a slot for the shared column data
             // as long as a reference to a row is not held past one iteration, this enables us
             // to re-use this memory freely between result sets
@@ -115,15 +131,25 @@ impl MySqlConnection {
                         arguments: &arguments,
                     })
                     .await?;
+                self.stream.wire_stats_mut().executes += 1;
 
                 (metadata.column_names, MySqlValueFormat::Binary, false)
             } else {
                 // https://dev.mysql.com/doc/internals/en/com-query.html
                 self.stream.send_packet(Query(sql)).await?;
+                self.stream.wire_stats_mut().executes += 1;
 
                 (Arc::default(), MySqlValueFormat::Text, true)
             };
 
+            // MariaDB's `INSERT .. RETURNING` and `DELETE .. RETURNING` end their result set
+            // like a `SELECT`, without the number of affected rows, but it's the number
+            // of rows returned. Only the first statement is checked.
+            let mut counts_returned_rows = is_returning_dml(sql);
+
+            // the size of the rows received so far, over all result sets
+            let mut result_size = 0;
+
             loop {
                 // query response is a meta-packet which may be one of:
                 //  Ok, Err, ResultSet, or (unhandled) LocalInfileRequest
@@ -149,6 +175,7 @@ impl MySqlConnection {
                     }
 
                     self.stream.waiting.pop_front();
+                    logger.set_wire_stats(self.stream.wire_stats().since(&wire_stats_start));
                     return Ok(());
                 }
 
@@ -168,14 +195,20 @@ impl MySqlConnection {
                 }
 
                 // finally, there will be none or many result-rows
+                let mut rows_returned = 0;
+
                 loop {
-                    let packet = self.stream.recv_packet().await?;
+                    let packet = self.stream.recv_row_packet(max_row_size).await?;
 
                     if packet[0] == 0xfe && packet.len() < 9 {
                         let eof = packet.eof(self.stream.capabilities)?;
 
+                        let rows_affected = if counts_returned_rows { rows_returned } else { 0 };
+                        counts_returned_rows = false;
+
+                        logger.increase_rows_affected(rows_affected);
                         r#yield!(Either::Left(MySqlQueryResult {
-                            rows_affected: 0,
+                            rows_affected,
                             last_insert_id: 0,
                         }));
 
@@ -186,9 +219,17 @@ impl MySqlConnection {
                         }
 
                         self.stream.waiting.pop_front();
+                        logger.set_wire_stats(self.stream.wire_stats().since(&wire_stats_start));
                         return Ok(());
                     }
 
+                    result_size += packet.len();
+
+                    if let Some(limit) = max_result_size.filter(|&limit| result_size > limit) {
+                        // the rest of the rows are discarded before the next query
+                        return Err(Error::ResultTooLarge { limit });
+                    }
+
                     let row = match format {
                         MySqlValueFormat::Binary => packet.decode_with::<BinaryRow, _>(&columns)?.0,
                         MySqlValueFormat::Text => packet.decode_with::<TextRow, _>(&columns)?.0,
@@ -199,9 +240,11 @@ impl MySqlConnection {
                         format,
                         columns: Arc::clone(&columns),
                         column_names: Arc::clone(&column_names),
+                        exact_decimals: self.exact_decimals,
                     });
 
                     logger.increment_rows_returned();
+                    rows_returned += 1;
 
                     r#yield!(v);
                 }
@@ -224,9 +267,10 @@ impl<'c> Executor<'c> for &'c mut MySqlConnection {
         let sql = query.sql();
         let arguments = query.take_arguments();
         let persistent = query.persistent();
+        let size_limited = query.size_limited();
 
         Box::pin(try_stream! {
-            let s = self.run(sql, arguments, persistent).await?;
+            let s = self.run(sql, arguments, persistent, size_limited).await?;
             pin_mut!(s);
 
             while let Some(v) = s.try_next().await? {
@@ -291,13 +335,7 @@ impl<'c> Executor<'c> for &'c mut MySqlConnection {
 
             let columns = (&*metadata.columns).clone();
 
-            let nullable = columns
-                .iter()
-                .map(|col| {
-                    col.flags
-                        .map(|flags| !flags.contains(ColumnFlags::NOT_NULL))
-                })
-                .collect();
+            let nullable = infer_nullable(sql, &columns);
 
             Ok(Describe {
                 parameters: Some(Either::Right(metadata.parameters)),
@@ -308,6 +346,19 @@ impl<'c> Executor<'c> for &'c mut MySqlConnection {
     }
 }
 
+/// Returns `true` if `sql` starts with a statement that can have a `RETURNING` clause in MariaDB.
+fn is_returning_dml(sql: &str) -> bool {
+    let keyword = sql
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '(')
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or_default();
+
+    ["INSERT", "REPLACE", "DELETE"]
+        .iter()
+        .any(|dml| keyword.eq_ignore_ascii_case(dml))
+}
+
 async fn recv_result_columns(
     stream: &mut MySqlStream,
     num_columns: usize,
@@ -337,11 +388,17 @@ fn recv_next_result_column(def: &ColumnDefinition, ordinal: usize) -> Result<MyS
 
     let type_info = MySqlTypeInfo::from_column(&def);
 
+    let table = match def.table_alias()? {
+        "" => None,
+        table => Some(UStr::new(table)),
+    };
+
     Ok(MySqlColumn {
         name,
         type_info,
         ordinal,
         flags: Some(def.flags),
+        table,
     })
 }
 
@@ -10,10 +10,11 @@ use crate::error::Error;
 use crate::protocol::statement::StmtClose;
 use crate::protocol::text::{Ping, Quit};
 use crate::statement::MySqlStatementMetadata;
-use crate::transaction::Transaction;
+use crate::transaction::{Transaction, TransactionOptions};
 use crate::{MySql, MySqlConnectOptions};
 
 mod auth;
+mod describe;
 mod establish;
 mod executor;
 mod stream;
@@ -35,6 +36,23 @@ pub struct MySqlConnection {
     cache_statement: StatementCache<(u32, MySqlStatementMetadata)>,
 
     log_settings: LogSettings,
+
+    // see `MySqlConnectOptions::compatibility_mode()`
+    pub(crate) compatibility_mode: bool,
+
+    // see `MySqlConnectOptions::exact_decimals()`
+    pub(crate) exact_decimals: bool,
+
+    // see `MySqlConnectOptions::max_row_size()` and `MySqlConnectOptions::max_result_size()`
+    pub(crate) max_row_size: Option<usize>,
+    pub(crate) max_result_size: Option<usize>,
+
+    // see `AnyConnection::set_type_registry()`
+    #[cfg(feature = "any")]
+    pub(crate) any_type_registry: sqlx_core::any::AnyTypeRegistry,
+
+    // the query being executed, for `Connection::running_query()`
+    pub(crate) running_query: RunningQuery,
 }
 
 impl Debug for MySqlConnection {
@@ -74,6 +92,14 @@ impl Connection for MySqlConnection {
         })
     }
 
+    fn is_open(&mut self) -> bool {
+        self.stream.socket.is_open()
+    }
+
+    fn is_poisoned(&self) -> bool {
+        self.stream.socket.is_poisoned()
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         self.stream.wait_until_ready().boxed()
@@ -109,7 +135,25 @@ impl Connection for MySqlConnection {
         Transaction::begin(self)
     }
 
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
     fn shrink_buffers(&mut self) {
         self.stream.shrink_buffers();
     }
+
+    fn server_version(&self) -> ServerVersion {
+        self.stream.server_version.clone()
+    }
+
+    fn running_query(&self) -> Option<RunningQuery> {
+        Some(self.running_query.clone())
+    }
 }
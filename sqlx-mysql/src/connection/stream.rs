@@ -11,11 +11,12 @@ use crate::net::{BufferedSocket, Socket};
 use crate::protocol::response::{EofPacket, ErrPacket, OkPacket, Status};
 use crate::protocol::{Capabilities, Packet};
 use crate::{MySqlConnectOptions, MySqlDatabaseError};
+use sqlx_core::connection::{ServerFlavor, ServerVersion};
 
 pub struct MySqlStream<S = Box<dyn Socket>> {
     // Wrapping the socket in `Box` allows us to unsize in-place.
     pub(crate) socket: BufferedSocket<S>,
-    pub(crate) server_version: (u16, u16, u16),
+    pub(crate) server_version: ServerVersion,
     pub(super) capabilities: Capabilities,
     pub(crate) sequence_id: u8,
     pub(crate) waiting: VecDeque<Waiting>,
@@ -57,10 +58,14 @@ impl<S: Socket> MySqlStream<S> {
             capabilities |= Capabilities::CONNECT_WITH_DB;
         }
 
+        if !options.connect_attrs.is_empty() {
+            capabilities |= Capabilities::CONNECT_ATTRS;
+        }
+
         Self {
             waiting: VecDeque::new(),
             capabilities,
-            server_version: (0, 0, 0),
+            server_version: ServerVersion::parse(ServerFlavor::MySql, ""),
             sequence_id: 0,
             collation,
             charset,
@@ -76,7 +81,12 @@ impl<S: Socket> MySqlStream<S> {
 
         while !self.waiting.is_empty() {
             while self.waiting.front() == Some(&Waiting::Row) {
-                let packet = self.recv_packet().await?;
+                // with a limit of zero, the rows are discarded without reading them into memory
+                let packet = match self.recv_row_packet(Some(0)).await {
+                    Ok(packet) => packet,
+                    Err(Error::RowTooLarge { .. }) => continue,
+                    Err(e) => return Err(e),
+                };
 
                 if !packet.is_empty() && packet[0] == 0xfe && packet.len() < 9 {
                     let eof = packet.eof(self.capabilities)?;
@@ -129,6 +139,15 @@ impl<S: Socket> MySqlStream<S> {
     // receive the next packet from the database server
     // may block (async) on more data from the server
     pub(crate) async fn recv_packet(&mut self) -> Result<Packet<Bytes>, Error> {
+        self.recv_row_packet(None).await
+    }
+
+    // like `recv_packet()`, but a row larger than `max_size` is skipped as it arrives and
+    // returns `Error::RowTooLarge`; the `ERR` or `EOF` packet that ends the rows is still read
+    pub(crate) async fn recv_row_packet(
+        &mut self,
+        max_size: Option<usize>,
+    ) -> Result<Packet<Bytes>, Error> {
         // https://dev.mysql.com/doc/dev/mysql-server/8.0.12/page_protocol_basic_packets.html
         // https://mariadb.com/kb/en/library/0-packet/#standard-packet
 
@@ -139,7 +158,36 @@ impl<S: Socket> MySqlStream<S> {
 
         self.sequence_id = sequence_id.wrapping_add(1);
 
-        let payload: Bytes = self.socket.read(packet_size).await?;
+        // the header is consumed, so the stream is out of sync if the payload isn't read
+        self.socket.begin_atomic();
+
+        let payload: Bytes = match max_size.filter(|&limit| packet_size > limit) {
+            Some(limit) => {
+                let mut payload = self.socket.read_buffered(1).await?;
+                let is_row = match payload[0] {
+                    0xff => false,
+                    0xfe => packet_size >= 9,
+                    _ => true,
+                };
+
+                if is_row {
+                    self.socket.skip(packet_size - 1).await?;
+                    self.socket.end_atomic();
+
+                    return Err(Error::RowTooLarge {
+                        size: packet_size,
+                        limit,
+                    });
+                }
+
+                payload.unsplit(self.socket.read_buffered(packet_size - 1).await?);
+                payload.freeze()
+            }
+
+            None => self.socket.read(packet_size).await?,
+        };
+
+        self.socket.end_atomic();
 
         // TODO: packet compression
         // TODO: packet joining
@@ -6,10 +6,11 @@ use crate::net::{tls, BufferedSocket, Socket, WithSocket};
 use crate::protocol::connect::SslRequest;
 use crate::protocol::Capabilities;
 use crate::{MySqlConnectOptions, MySqlSslMode};
+use sqlx_core::connection::ServerVersion;
 use std::collections::VecDeque;
 
 struct MapStream {
-    server_version: (u16, u16, u16),
+    server_version: ServerVersion,
     capabilities: Capabilities,
     sequence_id: u8,
     waiting: VecDeque<Waiting>,
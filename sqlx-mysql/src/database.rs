@@ -29,6 +29,8 @@ impl Database for MySql {
     const NAME: &'static str = "MySQL";
 
     const URL_SCHEMES: &'static [&'static str] = &["mysql", "mariadb"];
+
+    const BIND_LIMIT: usize = 65535;
 }
 
 impl<'r> HasValueRef<'r> for MySql {
@@ -0,0 +1,49 @@
+use futures_core::future::BoxFuture;
+use sqlx_core::distributed_lock::DistributedLocking;
+
+use crate::error::Error;
+use crate::query_scalar::query_scalar;
+use crate::MySqlConnection;
+
+/// Named locks for [`DistributedLock`][sqlx_core::distributed_lock::DistributedLock], using
+/// `GET_LOCK()`.
+impl DistributedLocking for MySqlConnection {
+    fn try_lock<'e>(&'e mut self, name: &'e str) -> BoxFuture<'e, Result<bool, Error>> {
+        Box::pin(async move {
+            // `NULL` if an error occurred, e.g. the thread was killed
+            // language=MySQL
+            let locked: Option<i64> = query_scalar("SELECT GET_LOCK(?, 0)")
+                .bind(name)
+                .fetch_one(self)
+                .await?;
+
+            Ok(locked == Some(1))
+        })
+    }
+
+    fn unlock<'e>(&'e mut self, name: &'e str) -> BoxFuture<'e, Result<bool, Error>> {
+        Box::pin(async move {
+            // `0` if another connection holds the lock, `NULL` if nobody does
+            // language=MySQL
+            let released: Option<i64> = query_scalar("SELECT RELEASE_LOCK(?)")
+                .bind(name)
+                .fetch_one(self)
+                .await?;
+
+            Ok(released == Some(1))
+        })
+    }
+
+    fn is_locked<'e>(&'e mut self, name: &'e str) -> BoxFuture<'e, Result<bool, Error>> {
+        Box::pin(async move {
+            // language=MySQL
+            let locked: Option<i64> =
+                query_scalar("SELECT CAST(IS_USED_LOCK(?) = CONNECTION_ID() AS SIGNED)")
+                    .bind(name)
+                    .fetch_one(self)
+                    .await?;
+
+            Ok(locked == Some(1))
+        })
+    }
+}
@@ -154,4 +154,9 @@ pub(crate) mod error_codes {
     ///
     /// Only available after 8.0.16.
     pub const ER_CHECK_CONSTRAINT_VIOLATED: u16 = 3819;
+
+    /// Caused by a feature the server doesn't support, e.g. `GET_LOCK()` through Vitess.
+    pub const ER_NOT_SUPPORTED_YET: u16 = 1235;
+    /// Caused by calling a function that doesn't exist.
+    pub const ER_SP_DOES_NOT_EXIST: u16 = 1305;
 }
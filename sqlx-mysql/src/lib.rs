@@ -15,11 +15,13 @@ mod collation;
 mod column;
 mod connection;
 mod database;
+mod distributed_lock;
 mod error;
 mod io;
 mod options;
 mod protocol;
 mod query_result;
+mod replication;
 mod row;
 mod statement;
 mod transaction;
@@ -59,6 +61,7 @@ impl<'c, T: Executor<'c, Database = MySql>> MySqlExecutor<'c> for T {}
 // NOTE: required due to the lack of lazy normalization
 impl_into_arguments_for_arguments!(MySqlArguments);
 impl_acquire!(MySql, MySqlConnection);
+impl_middleware_connection!(MySql);
 impl_column_index_for_row!(MySqlRow);
 impl_column_index_for_statement!(MySqlStatement);
 
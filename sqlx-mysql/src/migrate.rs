@@ -6,12 +6,26 @@ use futures_core::future::BoxFuture;
 pub(crate) use sqlx_core::migrate::*;
 
 use crate::connection::{ConnectOptions, Connection};
-use crate::error::Error;
+use crate::error::{error_codes, Error};
 use crate::executor::Executor;
 use crate::query::query;
 use crate::query_as::query_as;
 use crate::query_scalar::query_scalar;
-use crate::{MySql, MySqlConnectOptions, MySqlConnection};
+use crate::{MySql, MySqlConnectOptions, MySqlConnection, MySqlDatabaseError};
+
+/// Returns `true` if `error` is the server rejecting `GET_LOCK()` or `RELEASE_LOCK()` as
+/// unsupported, as some MySQL-compatible servers and proxies do.
+fn is_unsupported(error: &Error) -> bool {
+    error
+        .as_database_error()
+        .and_then(|e| e.try_downcast_ref::<MySqlDatabaseError>())
+        .map_or(false, |e| {
+            matches!(
+                e.number(),
+                error_codes::ER_NOT_SUPPORTED_YET | error_codes::ER_SP_DOES_NOT_EXIST
+            )
+        })
+}
 
 fn parse_for_maintenance(url: &str) -> Result<(MySqlConnectOptions, String), Error> {
     let mut options = MySqlConnectOptions::from_str(url)?;
@@ -143,12 +157,23 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
             // https://www.postgresql.org/docs/current/functions-admin.html#FUNCTIONS-ADVISORY-LOCKS-TABLE
 
             // language=MySQL
-            let _ = query("SELECT GET_LOCK(?, -1)")
+            let res = query("SELECT GET_LOCK(?, -1)")
                 .bind(lock_id)
-                .execute(self)
-                .await?;
-
-            Ok(())
+                .execute(&mut *self)
+                .await;
+
+            match res {
+                Ok(_) => Ok(()),
+                Err(e) if self.compatibility_mode && is_unsupported(&e) => {
+                    tracing::warn!(
+                        "migration lock is not supported by the server, continuing without it \
+                         as compatibility mode is enabled: {e}"
+                    );
+
+                    Ok(())
+                }
+                Err(e) => Err(e.into()),
+            }
         })
     }
 
@@ -158,12 +183,17 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
             let lock_id = generate_lock_id(&database_name);
 
             // language=MySQL
-            let _ = query("SELECT RELEASE_LOCK(?)")
+            let res = query("SELECT RELEASE_LOCK(?)")
                 .bind(lock_id)
-                .execute(self)
-                .await?;
-
-            Ok(())
+                .execute(&mut *self)
+                .await;
+
+            match res {
+                Ok(_) => Ok(()),
+                // the lock wasn't acquired in the first place
+                Err(e) if self.compatibility_mode && is_unsupported(&e) => Ok(()),
+                Err(e) => Err(e.into()),
+            }
         })
     }
 
@@ -171,6 +201,13 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         &'e mut self,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
+        Box::pin(async move { Ok(self.apply_timed(migration).await?.0) })
+    }
+
+    fn apply_timed<'e: 'm, 'm>(
+        &'e mut self,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(Duration, Vec<Duration>), MigrateError>> {
         Box::pin(async move {
             // Use a single transaction for the actual migration script and the essential bookeeping so we never
             // execute migrations twice. See https://github.com/launchbadge/sqlx/issues/1966.
@@ -199,7 +236,7 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
             .execute(&mut *tx)
             .await?;
 
-            let _ = tx.execute(&*migration.sql).await?;
+            let statements = execute_timed(&mut *tx, &migration.sql).await?;
 
             // language=MySQL
             let _ = query(
@@ -233,7 +270,7 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
             .execute(self)
             .await?;
 
-            Ok(elapsed)
+            Ok((elapsed, statements))
         })
     }
 
@@ -241,6 +278,13 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         &'e mut self,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
+        Box::pin(async move { Ok(self.revert_timed(migration).await?.0) })
+    }
+
+    fn revert_timed<'e: 'm, 'm>(
+        &'e mut self,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(Duration, Vec<Duration>), MigrateError>> {
         Box::pin(async move {
             // Use a single transaction for the actual migration script and the essential bookeeping so we never
             // execute migrations twice. See https://github.com/launchbadge/sqlx/issues/1966.
@@ -265,7 +309,7 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
             .execute(&mut *tx)
             .await?;
 
-            tx.execute(&*migration.sql).await?;
+            let statements = execute_timed(&mut *tx, &migration.sql).await?;
 
             // language=SQL
             let _ = query(r#"DELETE FROM _sqlx_migrations WHERE version = ?"#)
@@ -277,7 +321,79 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
 
             let elapsed = start.elapsed();
 
-            Ok(elapsed)
+            Ok((elapsed, statements))
+        })
+    }
+
+    fn ensure_migrations_log_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=MySQL
+            self.execute(
+                r#"
+CREATE TABLE IF NOT EXISTS _sqlx_migrations_log (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    version BIGINT NOT NULL,
+    description TEXT NOT NULL,
+    reverted BOOLEAN NOT NULL,
+    applied_at TIMESTAMP NOT NULL,
+    execution_time BIGINT NOT NULL,
+    hostname TEXT,
+    os_user TEXT,
+    tool_version TEXT,
+    git_commit TEXT
+);
+                "#,
+            )
+            .await?;
+
+            Ok(())
+        })
+    }
+
+    fn log_migration<'e: 'm, 'm>(
+        &'e mut self,
+        entry: &'m MigrationLogEntry,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=MySQL
+            let _ = query(
+                r#"
+    INSERT INTO _sqlx_migrations_log ( version, description, reverted, applied_at, execution_time, hostname, os_user, tool_version, git_commit )
+    VALUES ( ?, ?, ?, FROM_UNIXTIME(?), ?, ?, ?, ?, ? )
+                "#,
+            )
+            .bind(entry.version)
+            .bind(&entry.description)
+            .bind(entry.reverted)
+            .bind(entry.applied_at_secs())
+            .bind(entry.execution_time.as_nanos() as i64)
+            .bind(&entry.hostname)
+            .bind(&entry.os_user)
+            .bind(&entry.tool_version)
+            .bind(&entry.git_commit)
+            .execute(self)
+            .await?;
+
+            Ok(())
+        })
+    }
+
+    fn list_migrations_log(
+        &mut self,
+    ) -> BoxFuture<'_, Result<Vec<MigrationLogEntry>, MigrateError>> {
+        Box::pin(async move {
+            // language=MySQL
+            let rows: Vec<MigrationLogRow> = query_as(
+                r#"
+    SELECT version, description, reverted, CAST(UNIX_TIMESTAMP(applied_at) AS SIGNED), execution_time, hostname, os_user, tool_version, git_commit
+    FROM _sqlx_migrations_log
+    ORDER BY id
+                "#,
+            )
+            .fetch_all(self)
+            .await?;
+
+            Ok(rows.into_iter().map(MigrationLogEntry::from).collect())
         })
     }
 }
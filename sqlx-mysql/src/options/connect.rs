@@ -47,19 +47,27 @@ impl ConnectOptions for MySqlConnectOptions {
             // https://mathiasbynens.be/notes/mysql-utf8mb4
 
             let mut options = String::new();
-            if self.pipes_as_concat {
-                options.push_str(r#"SET sql_mode=(SELECT CONCAT(@@sql_mode, ',PIPES_AS_CONCAT,NO_ENGINE_SUBSTITUTION')),"#);
+            if self.compatibility_mode {
+                // TiDB and Vitess don't all support a subquery in `SET` or `COLLATE` in `SET NAMES`
+                options.push_str(&format!(
+                    r#"SET time_zone='+00:00', NAMES {};"#,
+                    conn.stream.charset.as_str()
+                ));
             } else {
-                options.push_str(
-                    r#"SET sql_mode=(SELECT CONCAT(@@sql_mode, ',NO_ENGINE_SUBSTITUTION')),"#,
-                );
+                if self.pipes_as_concat {
+                    options.push_str(r#"SET sql_mode=(SELECT CONCAT(@@sql_mode, ',PIPES_AS_CONCAT,NO_ENGINE_SUBSTITUTION')),"#);
+                } else {
+                    options.push_str(
+                        r#"SET sql_mode=(SELECT CONCAT(@@sql_mode, ',NO_ENGINE_SUBSTITUTION')),"#,
+                    );
+                }
+                options.push_str(r#"time_zone='+00:00',"#);
+                options.push_str(&format!(
+                    r#"NAMES {} COLLATE {};"#,
+                    conn.stream.charset.as_str(),
+                    conn.stream.collation.as_str()
+                ));
             }
-            options.push_str(r#"time_zone='+00:00',"#);
-            options.push_str(&format!(
-                r#"NAMES {} COLLATE {};"#,
-                conn.stream.charset.as_str(),
-                conn.stream.collation.as_str()
-            ));
 
             conn.execute(&*options).await?;
 
@@ -76,4 +84,17 @@ impl ConnectOptions for MySqlConnectOptions {
         self.log_settings.log_slow_statements(level, duration);
         self
     }
+
+    fn log_wire_stats(mut self, level: LevelFilter) -> Self {
+        self.log_settings.log_wire_stats(level);
+        self
+    }
+
+    fn application_name(self, name: &str) -> Self {
+        self.connect_attr("program_name", name)
+    }
+
+    fn get_application_name(&self) -> Option<&str> {
+        self.connect_attrs.get("program_name").map(String::as_str)
+    }
 }
@@ -5,6 +5,7 @@ mod parse;
 mod ssl_mode;
 
 use crate::{connection::LogSettings, net::tls::CertificateInput};
+use sqlx_core::IndexMap;
 pub use ssl_mode::MySqlSslMode;
 
 /// Options and flags which can be used to configure a MySQL connection.
@@ -29,6 +30,11 @@ pub use ssl_mode::MySqlSslMode;
 /// | `ssl-ca` | `None` | Sets the name of a file containing a list of trusted SSL Certificate Authorities. |
 /// | `statement-cache-capacity` | `100` | The maximum number of prepared statements stored in the cache. Set to `0` to disable. |
 /// | `socket` | `None` | Path to the unix domain socket, which will be used instead of TCP if set. |
+/// | `password-file` (or `passfile`) | `None` | Path to a file containing only the password, e.g. a Docker or Kubernetes secret. A trailing newline is ignored. |
+/// | `compatibility-mode` | `false` | Tolerate MySQL-compatible servers and proxies such as TiDB and Vitess. See [`MySqlConnectOptions::compatibility_mode()`]. |
+/// | `exact-decimals` | `false` | Return an error instead of rounding a `DECIMAL` decoded as `rust_decimal::Decimal`. See [`MySqlConnectOptions::exact_decimals()`]. |
+/// | `max-row-size` | `None` | The largest row in bytes that a query may return. See [`MySqlConnectOptions::max_row_size()`]. |
+/// | `max-result-size` | `None` | The most bytes of rows that a query may return. See [`MySqlConnectOptions::max_result_size()`]. |
 ///
 /// # Example
 ///
@@ -77,6 +83,11 @@ pub struct MySqlConnectOptions {
     pub(crate) log_settings: LogSettings,
     pub(crate) pipes_as_concat: bool,
     pub(crate) enable_cleartext_plugin: bool,
+    pub(crate) compatibility_mode: bool,
+    pub(crate) connect_attrs: IndexMap<String, String>,
+    pub(crate) exact_decimals: bool,
+    pub(crate) max_row_size: Option<usize>,
+    pub(crate) max_result_size: Option<usize>,
 }
 
 impl Default for MySqlConnectOptions {
@@ -105,6 +116,11 @@ impl MySqlConnectOptions {
             log_settings: Default::default(),
             pipes_as_concat: true,
             enable_cleartext_plugin: false,
+            compatibility_mode: false,
+            connect_attrs: IndexMap::new(),
+            exact_decimals: false,
+            max_row_size: None,
+            max_result_size: None,
         }
     }
 
@@ -333,4 +349,103 @@ impl MySqlConnectOptions {
         self.enable_cleartext_plugin = flag_val;
         self
     }
+
+    /// Tolerate servers that speak the MySQL protocol without behaving exactly like MySQL,
+    /// such as TiDB, or proxies such as Vitess.
+    ///
+    /// When enabled:
+    ///
+    /// * Authentication starts with `mysql_native_password` even if the server advertises
+    ///   `caching_sha2_password`, as some of these servers don't actually support it.
+    ///   A server that does will ask to switch back.
+    /// * The session is initialized with a plain `SET time_zone = .., NAMES ..` instead of
+    ///   modifying `sql_mode` with a subquery, so [`pipes_as_concat()`][Self::pipes_as_concat]
+    ///   has no effect.
+    /// * Migrations continue without the advisory lock if `GET_LOCK()` is not supported,
+    ///   so they must not be run concurrently.
+    ///
+    /// Note that [`MySqlQueryResult::last_insert_id()`][crate::MySqlQueryResult::last_insert_id]
+    /// may not be reliable through a proxy regardless; use a sequence or a client-generated key
+    /// instead.
+    ///
+    /// Can also be set with the `compatibility-mode` URL parameter. Defaults to `false`.
+    pub fn compatibility_mode(mut self, flag_val: bool) -> Self {
+        self.compatibility_mode = flag_val;
+        self
+    }
+
+    /// Sets a connection attribute which is sent to the server when connecting.
+    ///
+    /// Connection attributes are visible to DBAs in the
+    /// [`performance_schema.session_connect_attrs`](https://dev.mysql.com/doc/refman/8.0/en/performance-schema-connection-attribute-tables.html)
+    /// table, and can be used to attribute connections to the service that opened them.
+    /// The `program_name` attribute is set by
+    /// [`ConnectOptions::application_name()`][crate::connection::ConnectOptions::application_name].
+    ///
+    /// Attributes are ignored by servers which do not support them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// let options = MySqlConnectOptions::new()
+    ///     .connect_attr("program_name", "billing-service")
+    ///     .connect_attr("region", "eu-west-1");
+    /// ```
+    pub fn connect_attr(mut self, key: &str, value: &str) -> Self {
+        self.connect_attrs.insert(key.to_owned(), value.to_owned());
+        self
+    }
+
+    /// Sets whether decoding a `DECIMAL` as `rust_decimal::Decimal` returns an error if the
+    /// value can't be represented exactly, instead of rounding it.
+    ///
+    /// `Decimal` holds at most 28 digits after the point, while a `DECIMAL` may have 30, so
+    /// those are rounded by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// let options = MySqlConnectOptions::new()
+    ///     .exact_decimals(true);
+    /// ```
+    pub fn exact_decimals(mut self, exact: bool) -> Self {
+        self.exact_decimals = exact;
+        self
+    }
+
+    /// Sets the largest row, in bytes, that a query may return.
+    ///
+    /// A larger row is skipped without reading it into memory, and the query returns
+    /// [`Error::RowTooLarge`][crate::Error::RowTooLarge]. The connection stays usable.
+    ///
+    /// Can also be set with the `max-row-size` URL parameter. Defaults to `None`, for no limit.
+    /// A query can opt out with `without_size_limits()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::mysql::MySqlConnectOptions;
+    /// let options = MySqlConnectOptions::new()
+    ///     .max_row_size(16 * 1024 * 1024);
+    /// ```
+    pub fn max_row_size(mut self, size: impl Into<Option<usize>>) -> Self {
+        self.max_row_size = size.into();
+        self
+    }
+
+    /// Sets the most bytes of rows that a query may return, added up over all of its result
+    /// sets.
+    ///
+    /// Once the rows exceed it, the query returns
+    /// [`Error::ResultTooLarge`][crate::Error::ResultTooLarge] and the rest of the result is
+    /// discarded.
+    ///
+    /// Can also be set with the `max-result-size` URL parameter. Defaults to `None`, for no
+    /// limit. A query can opt out with `without_size_limits()`.
+    pub fn max_result_size(mut self, size: impl Into<Option<usize>>) -> Self {
+        self.max_result_size = size.into();
+        self
+    }
 }
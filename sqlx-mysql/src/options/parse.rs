@@ -1,6 +1,7 @@
 use std::str::FromStr;
 
 use percent_encoding::percent_decode_str;
+use sqlx_core::connection::read_password_file;
 use sqlx_core::Url;
 
 use crate::error::Error;
@@ -72,6 +73,27 @@ impl MySqlConnectOptions {
                     options = options.socket(&*value);
                 }
 
+                "password-file" | "password_file" | "passfile" => {
+                    options = options.password(&read_password_file(&*value)?);
+                }
+
+                "compatibility-mode" => {
+                    options = options.compatibility_mode(value.parse().map_err(Error::config)?);
+                }
+
+                "exact-decimals" => {
+                    options = options.exact_decimals(value.parse().map_err(Error::config)?);
+                }
+
+                "max-row-size" => {
+                    options = options.max_row_size(value.parse::<usize>().map_err(Error::config)?);
+                }
+
+                "max-result-size" => {
+                    options =
+                        options.max_result_size(value.parse::<usize>().map_err(Error::config)?);
+                }
+
                 _ => {}
             }
         }
@@ -104,3 +126,45 @@ fn it_parses_password_with_non_ascii_chars_correctly() {
 
     assert_eq!(Some("p@ssw0rd".into()), opts.password);
 }
+
+#[test]
+fn it_parses_compatibility_mode() {
+    let url = "mysql://username@hostname/database?compatibility-mode=true";
+    let opts = MySqlConnectOptions::from_str(url).unwrap();
+
+    assert!(opts.compatibility_mode);
+
+    let url = "mysql://username@hostname/database?compatibility-mode=maybe";
+    assert!(MySqlConnectOptions::from_str(url).is_err());
+}
+
+#[test]
+fn it_parses_exact_decimals() {
+    let url = "mysql://username@hostname/database?exact-decimals=true";
+    let opts = MySqlConnectOptions::from_str(url).unwrap();
+
+    assert!(opts.exact_decimals);
+    assert!(!MySqlConnectOptions::new().exact_decimals);
+}
+
+#[test]
+fn it_parses_size_limits() {
+    let url = "mysql://username@hostname/database?max-row-size=1024&max-result-size=65536";
+    let opts = MySqlConnectOptions::from_str(url).unwrap();
+
+    assert_eq!(opts.max_row_size, Some(1024));
+    assert_eq!(opts.max_result_size, Some(65536));
+    assert_eq!(MySqlConnectOptions::new().max_row_size, None);
+}
+
+#[test]
+fn it_parses_password_file() {
+    let path = std::env::temp_dir().join(format!("sqlx-mysql-password-{}", std::process::id()));
+    std::fs::write(&path, "p@ssw0rd\r\n").unwrap();
+
+    let url = format!("mysql://root@localhost/db?password-file={}", path.display());
+    let opts = MySqlConnectOptions::from_str(&url).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(Some("p@ssw0rd".into()), opts.password);
+}
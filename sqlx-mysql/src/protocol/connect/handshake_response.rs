@@ -3,6 +3,7 @@ use crate::io::{BufMutExt, Encode};
 use crate::protocol::auth::AuthPlugin;
 use crate::protocol::connect::ssl_request::SslRequest;
 use crate::protocol::Capabilities;
+use sqlx_core::IndexMap;
 
 // https://dev.mysql.com/doc/internals/en/connection-phase-packets.html#packet-Protocol::HandshakeResponse
 // https://mariadb.com/kb/en/connection/#client-handshake-response
@@ -25,6 +26,9 @@ pub struct HandshakeResponse<'a> {
 
     /// Opaque authentication response
     pub auth_response: Option<&'a [u8]>,
+
+    /// Key-value attributes describing the client
+    pub connect_attrs: &'a IndexMap<String, String>,
 }
 
 impl Encode<'_, Capabilities> for HandshakeResponse<'_> {
@@ -69,5 +73,16 @@ impl Encode<'_, Capabilities> for HandshakeResponse<'_> {
                 buf.push(0);
             }
         }
+
+        if capabilities.contains(Capabilities::CONNECT_ATTRS) {
+            let mut attrs = Vec::new();
+
+            for (key, value) in self.connect_attrs {
+                attrs.put_str_lenenc(key);
+                attrs.put_str_lenenc(value);
+            }
+
+            buf.put_bytes_lenenc(&attrs);
+        }
     }
 }
@@ -106,7 +106,6 @@ pub(crate) struct ColumnDefinition {
     catalog: Bytes,
     #[allow(unused)]
     schema: Bytes,
-    #[allow(unused)]
     table_alias: Bytes,
     #[allow(unused)]
     table: Bytes,
@@ -131,6 +130,10 @@ impl ColumnDefinition {
     pub(crate) fn alias(&self) -> Result<&str, Error> {
         from_utf8(&self.alias).map_err(Error::protocol)
     }
+
+    pub(crate) fn table_alias(&self) -> Result<&str, Error> {
+        from_utf8(&self.table_alias).map_err(Error::protocol)
+    }
 }
 
 impl Decode<'_, Capabilities> for ColumnDefinition {
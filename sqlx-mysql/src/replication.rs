@@ -0,0 +1,32 @@
+use futures_core::future::BoxFuture;
+use sqlx_core::pool::ReplicationPosition;
+
+use crate::error::Error;
+use crate::query_scalar::query_scalar;
+use crate::MySqlConnection;
+
+/// Replication positions for [`ReplicaSession`][sqlx_core::pool::ReplicaSession], using the set of
+/// executed GTIDs, which requires `gtid_mode=ON`.
+impl ReplicationPosition for MySqlConnection {
+    fn replication_position(&mut self) -> BoxFuture<'_, Result<String, Error>> {
+        Box::pin(async move {
+            // language=MySQL
+            query_scalar("SELECT CAST(@@GLOBAL.gtid_executed AS CHAR)")
+                .fetch_one(self)
+                .await
+        })
+    }
+
+    fn has_replayed<'e>(&'e mut self, position: &'e str) -> BoxFuture<'e, Result<bool, Error>> {
+        Box::pin(async move {
+            // language=MySQL
+            let replayed: i64 =
+                query_scalar("SELECT CAST(GTID_SUBSET(?, @@GLOBAL.gtid_executed) AS SIGNED)")
+                    .bind(position)
+                    .fetch_one(self)
+                    .await?;
+
+            Ok(replayed == 1)
+        })
+    }
+}
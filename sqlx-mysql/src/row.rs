@@ -15,6 +15,8 @@ pub struct MySqlRow {
     pub(crate) format: MySqlValueFormat,
     pub(crate) columns: Arc<Vec<MySqlColumn>>,
     pub(crate) column_names: Arc<HashMap<UStr, usize>>,
+    // see `MySqlConnectOptions::exact_decimals()`
+    pub(crate) exact_decimals: bool,
 }
 
 impl Row for MySqlRow {
@@ -37,6 +39,7 @@ impl Row for MySqlRow {
             row: Some(&self.row.storage),
             type_info: column.type_info.clone(),
             value,
+            exact_decimals: self.exact_decimals,
         })
     }
 }
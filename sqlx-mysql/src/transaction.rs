@@ -25,6 +25,29 @@ impl TransactionManager for MySqlTransactionManager {
         })
     }
 
+    fn begin_with(
+        conn: &mut MySqlConnection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let Some(modes) = options.to_sql() else {
+                return Self::begin(conn).await;
+            };
+
+            let depth = conn.transaction_depth;
+
+            options.check_not_nested(depth)?;
+
+            // `SET TRANSACTION` without `GLOBAL` or `SESSION` only applies to the next transaction.
+            conn.execute(&*format!("SET TRANSACTION {modes}")).await?;
+
+            conn.execute("START TRANSACTION").await?;
+            conn.transaction_depth = depth + 1;
+
+            Ok(())
+        })
+    }
+
     fn commit(conn: &mut MySqlConnection) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             let depth = conn.transaction_depth;
@@ -3,6 +3,8 @@ use std::fmt::{self, Display, Formatter};
 pub(crate) use sqlx_core::type_info::*;
 
 use crate::protocol::text::{ColumnDefinition, ColumnFlags, ColumnType};
+use crate::types::Type;
+use crate::MySql;
 
 /// Type information for a MySql type.
 #[derive(Debug, Clone)]
@@ -37,6 +39,15 @@ impl MySqlTypeInfo {
         }
     }
 
+    /// Whether a column can be decoded by an enum with `#[derive(sqlx::Type)]`: `ENUM` columns,
+    /// which MySQL sends as strings with the `ENUM` flag, and other strings.
+    #[doc(hidden)]
+    pub fn __enum_compatible(ty: &Self) -> bool {
+        ty.r#type == ColumnType::Enum
+            || ty.flags.contains(ColumnFlags::ENUM)
+            || <str as Type<MySql>>::compatible(ty)
+    }
+
     #[doc(hidden)]
     pub fn __type_feature_gate(&self) -> Option<&'static str> {
         match self.r#type {
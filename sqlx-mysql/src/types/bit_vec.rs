@@ -0,0 +1,52 @@
+use bit_vec::BitVec;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::io::MySqlBufMutExt;
+use crate::protocol::text::ColumnType;
+use crate::types::Type;
+use crate::{MySql, MySqlTypeInfo, MySqlValueRef};
+
+impl Type<MySql> for BitVec {
+    fn type_info() -> MySqlTypeInfo {
+        MySqlTypeInfo::binary(ColumnType::Bit)
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        ty.r#type == ColumnType::Bit || <&[u8] as Type<MySql>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, MySql> for BitVec {
+    fn produces(&self) -> Option<MySqlTypeInfo> {
+        // MySQL converts a binary string to `BIT(n)` as a big-endian number
+        Some(<&[u8] as Type<MySql>>::type_info())
+    }
+
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        // pad with leading zeroes to whole bytes, so the last bit is the least significant
+        let mut bits = BitVec::from_elem((8 - self.len() % 8) % 8, false);
+        bits.extend(self.iter());
+
+        buf.put_bytes_lenenc(&bits.to_bytes());
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, MySql> for BitVec {
+    fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        let bits = BitVec::from_bytes(value.as_bytes()?);
+
+        // `BIT(n)` is sent as whole bytes, padded with leading zeroes
+        let len = match value.type_info.max_size {
+            Some(n) if value.type_info.r#type == ColumnType::Bit && (n as usize) < bits.len() => {
+                n as usize
+            }
+            _ => return Ok(bits),
+        };
+
+        Ok(bits.iter().skip(bits.len() - len).collect())
+    }
+}
@@ -1,4 +1,5 @@
 use byteorder::{ByteOrder, LittleEndian};
+use sqlx_core::io::text::parse_float;
 
 use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
@@ -62,7 +63,7 @@ impl Decode<'_, MySql> for f32 {
                 }
             }
 
-            MySqlValueFormat::Text => value.as_str()?.parse()?,
+            MySqlValueFormat::Text => parse_float(value.as_bytes()?)?,
         })
     }
 }
@@ -71,7 +72,7 @@ impl Decode<'_, MySql> for f64 {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(match value.format() {
             MySqlValueFormat::Binary => LittleEndian::read_f64(value.as_bytes()?),
-            MySqlValueFormat::Text => value.as_str()?.parse()?,
+            MySqlValueFormat::Text => parse_float(value.as_bytes()?)?,
         })
     }
 }
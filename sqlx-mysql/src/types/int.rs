@@ -1,4 +1,7 @@
+use std::fmt::Display;
+
 use byteorder::{ByteOrder, LittleEndian};
+use sqlx_core::io::text::parse_int;
 
 use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
@@ -8,6 +11,7 @@ use crate::types::Type;
 use crate::{MySql, MySqlTypeInfo, MySqlValueFormat, MySqlValueRef};
 
 fn int_compatible(ty: &MySqlTypeInfo) -> bool {
+    // unsigned columns are decoded with a checked conversion, see `int_decode()`
     matches!(
         ty.r#type,
         ColumnType::Tiny
@@ -15,7 +19,24 @@ fn int_compatible(ty: &MySqlTypeInfo) -> bool {
             | ColumnType::Long
             | ColumnType::Int24
             | ColumnType::LongLong
-    ) && !ty.flags.contains(ColumnFlags::UNSIGNED)
+            | ColumnType::Bit
+    )
+}
+
+/// Converts a decoded integer into `T`, failing with an error that names the value and `T` if it's
+/// out of range, e.g. a `BIGINT UNSIGNED` above `i64::MAX` decoded as `i64`.
+pub(super) fn checked_int<T, I>(value: I) -> Result<T, BoxDynError>
+where
+    T: TryFrom<I>,
+    I: Copy + Display,
+{
+    T::try_from(value).map_err(|_| {
+        format!(
+            "value {value} is out of range for `{}`",
+            std::any::type_name::<T>()
+        )
+        .into()
+    })
 }
 
 impl Type<MySql> for i8 {
@@ -91,8 +112,15 @@ impl Encode<'_, MySql> for i64 {
 }
 
 fn int_decode(value: MySqlValueRef<'_>) -> Result<i64, BoxDynError> {
+    // `BIT(n)` is always unsigned
+    if value.type_info.r#type == ColumnType::Bit
+        || value.type_info.flags.contains(ColumnFlags::UNSIGNED)
+    {
+        return checked_int(<u64 as Decode<MySql>>::decode(value)?);
+    }
+
     Ok(match value.format() {
-        MySqlValueFormat::Text => value.as_str()?.parse()?,
+        MySqlValueFormat::Text => parse_int(value.as_bytes()?)?,
         MySqlValueFormat::Binary => {
             let buf = value.as_bytes()?;
             LittleEndian::read_int(buf, buf.len())
@@ -102,19 +130,19 @@ fn int_decode(value: MySqlValueRef<'_>) -> Result<i64, BoxDynError> {
 
 impl Decode<'_, MySql> for i8 {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        int_decode(value)?.try_into().map_err(Into::into)
+        checked_int(int_decode(value)?)
     }
 }
 
 impl Decode<'_, MySql> for i16 {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        int_decode(value)?.try_into().map_err(Into::into)
+        checked_int(int_decode(value)?)
     }
 }
 
 impl Decode<'_, MySql> for i32 {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        int_decode(value)?.try_into().map_err(Into::into)
+        checked_int(int_decode(value)?)
     }
 }
 
@@ -123,3 +151,18 @@ impl Decode<'_, MySql> for i64 {
         int_decode(value)
     }
 }
+
+#[test]
+fn test_checked_int() {
+    assert_eq!(checked_int::<i64, u64>(i64::MAX as u64).unwrap(), i64::MAX);
+    assert_eq!(checked_int::<u8, i64>(255).unwrap(), 255);
+
+    assert_eq!(
+        checked_int::<i64, u64>(u64::MAX).unwrap_err().to_string(),
+        "value 18446744073709551615 is out of range for `i64`"
+    );
+    assert_eq!(
+        checked_int::<u32, i64>(-1).unwrap_err().to_string(),
+        "value -1 is out of range for `u32`"
+    );
+}
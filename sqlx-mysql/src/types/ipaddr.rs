@@ -0,0 +1,46 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::io::MySqlBufMutExt;
+use crate::types::Type;
+use crate::{MySql, MySqlTypeInfo, MySqlValueRef};
+
+// MySQL has no address types, but MariaDB's `INET4` and `INET6` are sent as strings,
+// so these are encoded and decoded in their textual form.
+
+macro_rules! impl_ip_addr {
+    ($ty:ty) => {
+        impl Type<MySql> for $ty {
+            fn type_info() -> MySqlTypeInfo {
+                <&str as Type<MySql>>::type_info()
+            }
+
+            fn compatible(ty: &MySqlTypeInfo) -> bool {
+                <&str as Type<MySql>>::compatible(ty)
+            }
+        }
+
+        impl Encode<'_, MySql> for $ty {
+            fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+                buf.put_str_lenenc(&self.to_string());
+
+                IsNull::No
+            }
+        }
+
+        impl Decode<'_, MySql> for $ty {
+            fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+                // delegate to the &str type to decode from MySQL
+                let text = <&str as Decode<MySql>>::decode(value)?;
+
+                text.parse().map_err(Into::into)
+            }
+        }
+    };
+}
+
+impl_ip_addr!(IpAddr);
+impl_ip_addr!(Ipv4Addr);
+impl_ip_addr!(Ipv6Addr);
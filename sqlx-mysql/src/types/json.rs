@@ -1,12 +1,143 @@
+use std::fmt::{self, Display, Formatter, Write};
+
 use serde::{Deserialize, Serialize};
 
 use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
 use crate::protocol::text::ColumnType;
+use crate::query_builder::QueryBuilder;
 use crate::types::{Json, Type};
 use crate::{MySql, MySqlTypeInfo, MySqlValueRef};
 
+/// A path into a JSON document, like `$."items"[0]."name"`, for `JSON_EXTRACT()`.
+///
+/// Keys are always quoted and escaped, so they can contain any character, including `.`, `"`,
+/// `*` and spaces.
+///
+/// ```rust
+/// use sqlx::mysql::types::MySqlJsonPath;
+///
+/// let path = MySqlJsonPath::new().key("items").index(0).key("unit \"price\"");
+/// assert_eq!(path.as_str(), r#"$."items"[0]."unit \"price\"""#);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MySqlJsonPath(String);
+
+/// Helpers for JSON in queries, implemented for [`QueryBuilder<MySql>`][QueryBuilder].
+///
+/// Paths are bound as parameters, so they are never quoted into the SQL. That's also why there's
+/// no helper for `column->>'path'`, which only accepts a literal path; it's short for
+/// `JSON_UNQUOTE(JSON_EXTRACT(column, 'path'))`.
+///
+/// ```rust,ignore
+/// use sqlx::mysql::types::{MySqlJsonPath, MySqlJsonQueryBuilder};
+/// use sqlx::QueryBuilder;
+///
+/// let mut query = QueryBuilder::new("SELECT id FROM orders WHERE ");
+/// query
+///     .push_json_unquote_extract("doc", &MySqlJsonPath::new().key("status"))
+///     .push(" = ")
+///     .push_bind("shipped")
+///     .push(" AND ")
+///     .push_json_extract("doc", &MySqlJsonPath::new().key("customer"))
+///     .push(" = ")
+///     .push_bind_json(customer);
+/// ```
+pub trait MySqlJsonQueryBuilder<'args> {
+    /// Binds `value` as JSON, cast to the `JSON` type so that comparing it with a `JSON` value
+    /// compares JSON: `CAST(? AS JSON)`.
+    fn push_bind_json<T>(&mut self, value: T) -> &mut Self
+    where
+        T: 'args + Serialize + Send;
+
+    /// Pushes the value at `path` in `column`, as JSON: `JSON_EXTRACT(column, ?)`.
+    fn push_json_extract(&mut self, column: impl Display, path: &MySqlJsonPath) -> &mut Self;
+
+    /// Pushes the value at `path` in `column`, with strings unquoted, like `column->>'path'`:
+    /// `JSON_UNQUOTE(JSON_EXTRACT(column, ?))`.
+    fn push_json_unquote_extract(
+        &mut self,
+        column: impl Display,
+        path: &MySqlJsonPath,
+    ) -> &mut Self;
+}
+
+impl MySqlJsonPath {
+    /// The root of the document, `$`.
+    pub fn new() -> Self {
+        MySqlJsonPath("$".into())
+    }
+
+    /// The member named `key` of an object.
+    pub fn key(mut self, key: &str) -> Self {
+        // a quoted key is parsed as a JSON string
+        self.0.push_str(".\"");
+
+        for c in key.chars() {
+            match c {
+                '"' => self.0.push_str(r#"\""#),
+                '\\' => self.0.push_str(r"\\"),
+                c if c.is_control() => {
+                    let _ = write!(self.0, "\\u{:04x}", c as u32);
+                }
+                c => self.0.push(c),
+            }
+        }
+
+        self.0.push('"');
+        self
+    }
+
+    /// The element at `index` of an array.
+    pub fn index(mut self, index: usize) -> Self {
+        let _ = write!(self.0, "[{index}]");
+        self
+    }
+
+    /// The path as a string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for MySqlJsonPath {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for MySqlJsonPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'args> MySqlJsonQueryBuilder<'args> for QueryBuilder<'args, MySql> {
+    fn push_bind_json<T>(&mut self, value: T) -> &mut Self
+    where
+        T: 'args + Serialize + Send,
+    {
+        self.push("CAST(").push_bind(Json(value)).push(" AS JSON)")
+    }
+
+    fn push_json_extract(&mut self, column: impl Display, path: &MySqlJsonPath) -> &mut Self {
+        self.push(format_args!("JSON_EXTRACT({column}, "))
+            .push_bind(path.to_string())
+            .push(")")
+    }
+
+    fn push_json_unquote_extract(
+        &mut self,
+        column: impl Display,
+        path: &MySqlJsonPath,
+    ) -> &mut Self {
+        self.push("JSON_UNQUOTE(")
+            .push_json_extract(column, path)
+            .push(")")
+    }
+}
+
 impl<T> Type<MySql> for Json<T> {
     fn type_info() -> MySqlTypeInfo {
         // MySql uses the `CHAR` type to pass JSON data from and to the client
@@ -22,6 +153,9 @@ impl<T> Type<MySql> for Json<T> {
     }
 }
 
+// MySQL doesn't accept `JSON` as the type of a parameter, so a comparison of a `JSON` value with a
+// bound `Json<T>` compares it with a JSON string scalar, unless the parameter is cast to `JSON`;
+// see `MySqlJsonQueryBuilder::push_bind_json()`.
 impl<T> Encode<'_, MySql> for Json<T>
 where
     T: Serialize,
@@ -66,3 +200,35 @@ where
         Json::decode_from_string(value.as_str()?)
     }
 }
+
+#[test]
+fn test_json_path() {
+    assert_eq!(MySqlJsonPath::new().as_str(), "$");
+    assert_eq!(
+        MySqlJsonPath::new()
+            .key("a")
+            .index(2)
+            .key("b.c")
+            .to_string(),
+        r#"$."a"[2]."b.c""#
+    );
+    assert_eq!(
+        MySqlJsonPath::new().key("x\\y\"z\n").as_str(),
+        r#"$."x\\y\"z\u000a""#
+    );
+}
+
+#[test]
+fn test_push_json() {
+    let mut query = QueryBuilder::<MySql>::new("SELECT id FROM orders WHERE ");
+    query
+        .push_json_unquote_extract("doc", &MySqlJsonPath::new().key("status"))
+        .push(" = 'shipped' AND doc = ")
+        .push_bind_json(vec![1, 2]);
+
+    assert_eq!(
+        query.sql(),
+        "SELECT id FROM orders WHERE JSON_UNQUOTE(JSON_EXTRACT(doc, ?)) = 'shipped' \
+         AND doc = CAST(? AS JSON)"
+    );
+}
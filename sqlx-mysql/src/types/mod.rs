@@ -17,6 +17,18 @@
 //! | `f64`                                 | DOUBLE                                               |
 //! | `&str`, [`String`]                    | VARCHAR, CHAR, TEXT                                  |
 //! | `&[u8]`, `Vec<u8>`                    | VARBINARY, BINARY, BLOB                              |
+//! | `IpAddr`                              | INET4, INET6 (MariaDB), VARCHAR, CHAR, TEXT          |
+//! | `Ipv4Addr`                            | INET4 (MariaDB), VARCHAR, CHAR, TEXT                 |
+//! | `Ipv6Addr`                            | INET6 (MariaDB), VARCHAR, CHAR, TEXT                 |
+//! | [`MySqlSet`]                          | SET, VARCHAR, CHAR, TEXT                             |
+//!
+//! The integer types can also be decoded from `BIT(n)` and from integer columns of the other
+//! signedness or another width. Such conversions are checked: a value that doesn't fit, like a
+//! `BIGINT UNSIGNED` above `i64::MAX` decoded as `i64` or a negative `INT` decoded as `u32`, is
+//! an error rather than being wrapped around.
+//!
+//! Enums with `#[derive(sqlx::Type)]` map to `ENUM` by the names of their variants, and can also
+//! be decoded from VARCHAR, CHAR or TEXT.
 //!
 //! ### [`chrono`](https://crates.io/crates/chrono)
 //!
@@ -55,6 +67,14 @@
 //! |---------------------------------------|------------------------------------------------------|
 //! | `rust_decimal::Decimal`               | DECIMAL                                              |
 //!
+//! ### [`bit-vec`](https://crates.io/crates/bit-vec)
+//!
+//! Requires the `bit-vec` Cargo feature flag.
+//!
+//! | Rust type                             | MySQL type(s)                                        |
+//! |---------------------------------------|------------------------------------------------------|
+//! | `bit_vec::BitVec`                     | BIT                                                  |
+//!
 //! ### [`uuid`](https://crates.io/crates/uuid)
 //!
 //! Requires the `uuid` Cargo feature flag.
@@ -75,6 +95,10 @@
 //! | `serde_json::JsonValue`               | JSON                                                 |
 //! | `&serde_json::value::RawValue`        | JSON                                                 |
 //!
+//! JSON is bound as a string, which MySQL compares with a `JSON` value as a JSON string scalar,
+//! so comparisons in a `WHERE` clause must cast the parameter: `WHERE doc = CAST(? AS JSON)`.
+//! [`MySqlJsonQueryBuilder`] generates such casts, and `JSON_EXTRACT()` of a [`MySqlJsonPath`].
+//!
 //! # Nullable
 //!
 //! In addition, `Option<T>` is supported where `T` implements `Type`. An `Option<T>` represents
@@ -86,6 +110,8 @@ mod bool;
 mod bytes;
 mod float;
 mod int;
+mod ipaddr;
+mod set;
 mod str;
 mod uint;
 
@@ -106,3 +132,11 @@ mod time;
 
 #[cfg(feature = "uuid")]
 mod uuid;
+
+#[cfg(feature = "bit-vec")]
+mod bit_vec;
+
+pub use set::MySqlSet;
+
+#[cfg(feature = "json")]
+pub use json::{MySqlJsonPath, MySqlJsonQueryBuilder};
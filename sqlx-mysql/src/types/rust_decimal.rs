@@ -24,6 +24,18 @@ impl Encode<'_, MySql> for Decimal {
 
 impl Decode<'_, MySql> for Decimal {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        Ok(value.as_str()?.parse()?)
+        let s = value.as_str()?;
+
+        if value.exact_decimals {
+            // trailing zeros after the point don't need to fit
+            let s = match s.contains('.') {
+                true => s.trim_end_matches('0').trim_end_matches('.'),
+                false => s,
+            };
+
+            return Ok(Decimal::from_str_exact(s)?);
+        }
+
+        Ok(s.parse()?)
     }
 }
@@ -0,0 +1,127 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::Deref;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::io::MySqlBufMutExt;
+use crate::protocol::text::{ColumnFlags, ColumnType};
+use crate::types::Type;
+use crate::{MySql, MySqlTypeInfo, MySqlValueRef};
+
+/// The members of a `SET` value in MySQL, like a set of flags.
+///
+/// MySQL sends the members of a `SET` joined by commas, in the order of the column definition,
+/// and accepts them in any order. Members can't contain commas.
+///
+/// ```rust
+/// use sqlx::mysql::types::MySqlSet;
+///
+/// let mut set: MySqlSet = ["read", "write"].into_iter().collect();
+/// set.insert("read");
+/// set.remove("write");
+///
+/// assert!(set.contains("read"));
+/// assert_eq!(set.to_string(), "read");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MySqlSet(Vec<String>);
+
+impl MySqlSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `member` is in the set.
+    pub fn contains(&self, member: &str) -> bool {
+        self.0.iter().any(|m| m == member)
+    }
+
+    /// Adds `member` to the set, returning `false` if it was already in it.
+    pub fn insert(&mut self, member: impl Into<String>) -> bool {
+        let member = member.into();
+
+        if self.contains(&member) {
+            return false;
+        }
+
+        self.0.push(member);
+        true
+    }
+
+    /// Removes `member` from the set, returning `false` if it wasn't in it.
+    pub fn remove(&mut self, member: &str) -> bool {
+        let len = self.0.len();
+        self.0.retain(|m| m != member);
+
+        self.0.len() != len
+    }
+}
+
+impl Deref for MySqlSet {
+    type Target = [String];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S: Into<String>> FromIterator<S> for MySqlSet {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut set = MySqlSet::new();
+
+        for member in iter {
+            set.insert(member);
+        }
+
+        set
+    }
+}
+
+impl IntoIterator for MySqlSet {
+    type Item = String;
+    type IntoIter = std::vec::IntoIter<String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl Display for MySqlSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0.join(","))
+    }
+}
+
+impl Type<MySql> for MySqlSet {
+    fn type_info() -> MySqlTypeInfo {
+        <str as Type<MySql>>::type_info()
+    }
+
+    fn compatible(ty: &MySqlTypeInfo) -> bool {
+        ty.r#type == ColumnType::Set
+            || ty.flags.contains(ColumnFlags::SET)
+            || <str as Type<MySql>>::compatible(ty)
+    }
+}
+
+impl Encode<'_, MySql> for MySqlSet {
+    fn encode_by_ref(&self, buf: &mut Vec<u8>) -> IsNull {
+        buf.put_str_lenenc(&self.to_string());
+
+        IsNull::No
+    }
+}
+
+impl Decode<'_, MySql> for MySqlSet {
+    fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
+        let s = value.as_str()?;
+
+        if s.is_empty() {
+            return Ok(MySqlSet::new());
+        }
+
+        Ok(s.split(',').collect())
+    }
+}
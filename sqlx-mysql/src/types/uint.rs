@@ -2,9 +2,11 @@ use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
 use crate::protocol::text::{ColumnFlags, ColumnType};
+use crate::types::int::checked_int;
 use crate::types::Type;
 use crate::{MySql, MySqlTypeInfo, MySqlValueFormat, MySqlValueRef};
 use byteorder::{ByteOrder, LittleEndian};
+use sqlx_core::io::text::parse_int;
 
 fn uint_type_info(ty: ColumnType) -> MySqlTypeInfo {
     MySqlTypeInfo {
@@ -16,6 +18,7 @@ fn uint_type_info(ty: ColumnType) -> MySqlTypeInfo {
 }
 
 fn uint_compatible(ty: &MySqlTypeInfo) -> bool {
+    // signed columns are decoded with a checked conversion, see `uint_decode()`
     matches!(
         ty.r#type,
         ColumnType::Tiny
@@ -25,7 +28,7 @@ fn uint_compatible(ty: &MySqlTypeInfo) -> bool {
             | ColumnType::LongLong
             | ColumnType::Year
             | ColumnType::Bit
-    ) && ty.flags.contains(ColumnFlags::UNSIGNED)
+    )
 }
 
 impl Type<MySql> for u8 {
@@ -114,8 +117,12 @@ fn uint_decode(value: MySqlValueRef<'_>) -> Result<u64, BoxDynError> {
         return Ok(value);
     }
 
+    if !value.type_info.flags.contains(ColumnFlags::UNSIGNED) {
+        return checked_int(<i64 as Decode<MySql>>::decode(value)?);
+    }
+
     Ok(match value.format() {
-        MySqlValueFormat::Text => value.as_str()?.parse()?,
+        MySqlValueFormat::Text => parse_int(value.as_bytes()?)?,
 
         MySqlValueFormat::Binary => {
             let buf = value.as_bytes()?;
@@ -126,19 +133,19 @@ fn uint_decode(value: MySqlValueRef<'_>) -> Result<u64, BoxDynError> {
 
 impl Decode<'_, MySql> for u8 {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        uint_decode(value)?.try_into().map_err(Into::into)
+        checked_int(uint_decode(value)?)
     }
 }
 
 impl Decode<'_, MySql> for u16 {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        uint_decode(value)?.try_into().map_err(Into::into)
+        checked_int(uint_decode(value)?)
     }
 }
 
 impl Decode<'_, MySql> for u32 {
     fn decode(value: MySqlValueRef<'_>) -> Result<Self, BoxDynError> {
-        uint_decode(value)?.try_into().map_err(Into::into)
+        checked_int(uint_decode(value)?)
     }
 }
 
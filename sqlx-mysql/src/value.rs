@@ -1,7 +1,7 @@
 use std::borrow::Cow;
-use std::str::from_utf8;
 
 use bytes::Bytes;
+use sqlx_core::io::text::from_utf8;
 pub(crate) use sqlx_core::value::*;
 
 use crate::error::{BoxDynError, UnexpectedNullError};
@@ -21,6 +21,7 @@ pub struct MySqlValue {
     value: Option<Bytes>,
     type_info: MySqlTypeInfo,
     format: MySqlValueFormat,
+    exact_decimals: bool,
 }
 
 /// Implementation of [`ValueRef`] for MySQL.
@@ -30,6 +31,8 @@ pub struct MySqlValueRef<'r> {
     pub(crate) row: Option<&'r Bytes>,
     pub(crate) type_info: MySqlTypeInfo,
     pub(crate) format: MySqlValueFormat,
+    // see `MySqlConnectOptions::exact_decimals()`
+    pub(crate) exact_decimals: bool,
 }
 
 impl<'r> MySqlValueRef<'r> {
@@ -45,7 +48,7 @@ impl<'r> MySqlValueRef<'r> {
     }
 
     pub(crate) fn as_str(&self) -> Result<&'r str, BoxDynError> {
-        Ok(from_utf8(self.as_bytes()?)?)
+        from_utf8(self.as_bytes()?)
     }
 }
 
@@ -58,6 +61,7 @@ impl Value for MySqlValue {
             row: None,
             type_info: self.type_info.clone(),
             format: self.format,
+            exact_decimals: self.exact_decimals,
         }
     }
 
@@ -86,6 +90,7 @@ impl<'r> ValueRef<'r> for MySqlValueRef<'r> {
             value,
             format: self.format,
             type_info: self.type_info.clone(),
+            exact_decimals: self.exact_decimals,
         }
     }
 
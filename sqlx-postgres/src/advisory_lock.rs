@@ -1,9 +1,11 @@
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::Either;
 use crate::PgConnection;
+use futures_core::future::BoxFuture;
 use hkdf::Hkdf;
 use once_cell::sync::OnceCell;
 use sha2::Sha256;
+use sqlx_core::distributed_lock::DistributedLocking;
 use std::ops::{Deref, DerefMut};
 
 /// A mutex-like type utilizing [Postgres advisory locks].
@@ -419,3 +421,51 @@ impl<'lock, C: AsMut<PgConnection>> Drop for PgAdvisoryLockGuard<'lock, C> {
         }
     }
 }
+
+/// Named locks for [`DistributedLock`][sqlx_core::distributed_lock::DistributedLock], using the
+/// 64-bit advisory lock key that [`PgAdvisoryLock::new()`] derives from the name.
+impl DistributedLocking for PgConnection {
+    fn try_lock<'e>(&'e mut self, name: &'e str) -> BoxFuture<'e, Result<bool, Error>> {
+        Box::pin(async move {
+            crate::query_scalar::query_scalar("SELECT pg_try_advisory_lock($1)")
+                .bind(lock_key(name))
+                .fetch_one(self)
+                .await
+        })
+    }
+
+    fn unlock<'e>(&'e mut self, name: &'e str) -> BoxFuture<'e, Result<bool, Error>> {
+        Box::pin(async move {
+            crate::query_scalar::query_scalar("SELECT pg_advisory_unlock($1)")
+                .bind(lock_key(name))
+                .fetch_one(self)
+                .await
+        })
+    }
+
+    fn is_locked<'e>(&'e mut self, name: &'e str) -> BoxFuture<'e, Result<bool, Error>> {
+        Box::pin(async move {
+            // See the documentation of `pg_locks` for how a 64-bit key is split into `classid`
+            // and `objid`.
+            crate::query_scalar::query_scalar(
+                "SELECT EXISTS ( \
+                    SELECT 1 FROM pg_locks \
+                    WHERE locktype = 'advisory' AND pid = pg_backend_pid() AND granted \
+                    AND classid = (($1 >> 32) & 4294967295)::oid \
+                    AND objid = ($1 & 4294967295)::oid \
+                    AND objsubid = 1 \
+                )",
+            )
+            .bind(lock_key(name))
+            .fetch_one(self)
+            .await
+        })
+    }
+}
+
+fn lock_key(name: &str) -> i64 {
+    PgAdvisoryLock::new(name)
+        .key
+        .as_bigint()
+        .expect("BUG: `PgAdvisoryLock::new()` should use the `BigInt` keyspace")
+}
@@ -1,3 +1,4 @@
+use crate::connection::executor::FetchLimit;
 use crate::{
     Either, PgColumn, PgConnectOptions, PgConnection, PgQueryResult, PgRow, PgTransactionManager,
     PgTypeInfo, Postgres,
@@ -9,11 +10,19 @@ use futures_util::{StreamExt, TryFutureExt, TryStreamExt};
 pub use sqlx_core::any::*;
 
 use crate::type_info::PgType;
-use sqlx_core::connection::Connection;
+use crate::types::numeric::PgNumeric;
+use crate::{PgArgumentBuffer, PgArguments, PgValueFormat, PgValueRef};
+use sqlx_core::bytes::Buf;
+use sqlx_core::connection::{ConnectOptions, Connection, RunningQuery, ServerVersion};
 use sqlx_core::database::Database;
+use sqlx_core::decode::Decode;
 use sqlx_core::describe::Describe;
+use sqlx_core::encode::{Encode, IsNull};
+use sqlx_core::error::BoxDynError;
 use sqlx_core::executor::Executor;
-use sqlx_core::transaction::TransactionManager;
+use sqlx_core::transaction::{TransactionManager, TransactionOptions};
+use sqlx_core::types::Type;
+use sqlx_core::value::ValueRef;
 
 sqlx_core::declare_driver_with_optional_migrate!(DRIVER = Postgres);
 
@@ -34,10 +43,22 @@ impl AnyConnectionBackend for PgConnection {
         Connection::ping(self)
     }
 
+    fn is_open(&mut self) -> bool {
+        Connection::is_open(self)
+    }
+
+    fn is_poisoned(&self) -> bool {
+        Connection::is_poisoned(self)
+    }
+
     fn begin(&mut self) -> BoxFuture<'_, sqlx_core::Result<()>> {
         PgTransactionManager::begin(self)
     }
 
+    fn begin_with(&mut self, options: TransactionOptions) -> BoxFuture<'_, sqlx_core::Result<()>> {
+        PgTransactionManager::begin_with(self, options)
+    }
+
     fn commit(&mut self) -> BoxFuture<'_, sqlx_core::Result<()>> {
         PgTransactionManager::commit(self)
     }
@@ -54,6 +75,25 @@ impl AnyConnectionBackend for PgConnection {
         Connection::shrink_buffers(self);
     }
 
+    fn server_version(&self) -> ServerVersion {
+        Connection::server_version(self)
+    }
+
+    fn running_query(&self) -> Option<RunningQuery> {
+        Connection::running_query(self)
+    }
+
+    fn set_application_name<'c>(
+        &'c mut self,
+        name: Option<&'c str>,
+    ) -> BoxFuture<'c, sqlx_core::Result<()>> {
+        Connection::set_application_name(self, name)
+    }
+
+    fn set_type_registry(&mut self, registry: AnyTypeRegistry) {
+        self.any_type_registry = registry;
+    }
+
     fn flush(&mut self) -> BoxFuture<'_, sqlx_core::Result<()>> {
         Connection::flush(self)
     }
@@ -75,15 +115,23 @@ impl AnyConnectionBackend for PgConnection {
         arguments: Option<AnyArguments<'q>>,
     ) -> BoxStream<'q, sqlx_core::Result<Either<AnyQueryResult, AnyRow>>> {
         let persistent = arguments.is_some();
-        let args = arguments.as_ref().map(AnyArguments::convert_to);
+        let args = arguments.as_ref().map(pg_arguments).transpose();
+        let registry = self.any_type_registry.clone();
 
         Box::pin(
-            self.run(query, args, 0, persistent, None)
+            futures_util::future::ready(args)
+                .and_then(move |args| {
+                    self.run(query, args, FetchLimit::All, true, persistent, None)
+                })
                 .try_flatten_stream()
                 .map(
                     move |res: sqlx_core::Result<Either<PgQueryResult, PgRow>>| match res? {
                         Either::Left(result) => Ok(Either::Left(map_result(result))),
-                        Either::Right(row) => Ok(Either::Right(AnyRow::try_from(&row)?)),
+                        Either::Right(row) => Ok(Either::Right(AnyRow::map_from(
+                            &row,
+                            row.metadata.column_names.clone(),
+                            &registry,
+                        )?)),
                     },
                 ),
         )
@@ -95,14 +143,22 @@ impl AnyConnectionBackend for PgConnection {
         arguments: Option<AnyArguments<'q>>,
     ) -> BoxFuture<'q, sqlx_core::Result<Option<AnyRow>>> {
         let persistent = arguments.is_some();
-        let args = arguments.as_ref().map(AnyArguments::convert_to);
+        let args = arguments.as_ref().map(pg_arguments).transpose();
+        let registry = self.any_type_registry.clone();
 
         Box::pin(async move {
-            let stream = self.run(query, args, 1, persistent, None).await?;
+            let args = args?;
+            let stream = self
+                .run(query, args, FetchLimit::First, true, persistent, None)
+                .await?;
             futures_util::pin_mut!(stream);
 
             if let Some(Either::Right(row)) = stream.try_next().await? {
-                return Ok(Some(AnyRow::try_from(&row)?));
+                return Ok(Some(AnyRow::map_from(
+                    &row,
+                    row.metadata.column_names.clone(),
+                    &registry,
+                )?));
             }
 
             Ok(None)
@@ -114,24 +170,29 @@ impl AnyConnectionBackend for PgConnection {
         sql: &'q str,
         _parameters: &[AnyTypeInfo],
     ) -> BoxFuture<'c, sqlx_core::Result<AnyStatement<'q>>> {
+        let registry = self.any_type_registry.clone();
+
         Box::pin(async move {
             let statement = Executor::prepare_with(self, sql, &[]).await?;
             AnyStatement::try_from_statement(
                 sql,
                 &statement,
                 statement.metadata.column_names.clone(),
+                &registry,
             )
         })
     }
 
     fn describe<'q>(&'q mut self, sql: &'q str) -> BoxFuture<'q, sqlx_core::Result<Describe<Any>>> {
+        let registry = self.any_type_registry.clone();
+
         Box::pin(async move {
             let describe = Executor::describe(self, sql).await?;
 
             let columns = describe
                 .columns
                 .iter()
-                .map(AnyColumn::try_from)
+                .map(|column| registry.column(column))
                 .collect::<Result<Vec<_>, _>>()?;
 
             let parameters = match describe.parameters {
@@ -140,7 +201,7 @@ impl AnyConnectionBackend for PgConnection {
                         .iter()
                         .enumerate()
                         .map(|(i, type_info)| {
-                            AnyTypeInfo::try_from(type_info).map_err(|_| {
+                            registry.type_info(type_info).map_err(|_| {
                                 sqlx_core::Error::AnyDriverError(
                                     format!(
                                         "Any driver does not support type {type_info} of parameter {i}"
@@ -171,16 +232,36 @@ impl<'a> TryFrom<&'a PgTypeInfo> for AnyTypeInfo {
         Ok(AnyTypeInfo {
             kind: match &pg_type.0 {
                 PgType::Void => AnyTypeInfoKind::Null,
+                PgType::Bool => AnyTypeInfoKind::Bool,
                 PgType::Int2 => AnyTypeInfoKind::SmallInt,
                 PgType::Int4 => AnyTypeInfoKind::Integer,
                 PgType::Int8 => AnyTypeInfoKind::BigInt,
                 PgType::Float4 => AnyTypeInfoKind::Real,
                 PgType::Float8 => AnyTypeInfoKind::Double,
                 PgType::Bytea => AnyTypeInfoKind::Blob,
-                PgType::Text => AnyTypeInfoKind::Text,
+                PgType::Text | PgType::Varchar | PgType::Bpchar | PgType::Name => {
+                    AnyTypeInfoKind::Text
+                }
+                PgType::Numeric => AnyTypeInfoKind::Numeric,
+                PgType::Date => AnyTypeInfoKind::Date,
+                PgType::Time => AnyTypeInfoKind::Time,
+                PgType::Timestamp => AnyTypeInfoKind::Timestamp,
+                PgType::Timestamptz => AnyTypeInfoKind::TimestampTz,
+                PgType::Uuid => AnyTypeInfoKind::Uuid,
+                PgType::Json | PgType::Jsonb => AnyTypeInfoKind::Json,
+                _ if pg_type
+                    .try_array_element()
+                    .map_or(false, |element| AnyTypeInfo::try_from(&*element).is_ok()) =>
+                {
+                    AnyTypeInfoKind::Array
+                }
                 _ => {
                     return Err(sqlx_core::Error::AnyDriverError(
-                        format!("Any driver does not support the Postgres type {pg_type:?}").into(),
+                        format!(
+                            "Any driver does not support the Postgres type {pg_type:?}; \
+                             it can be mapped with `AnyTypeRegistry`"
+                        )
+                        .into(),
                     ))
                 }
             },
@@ -210,7 +291,11 @@ impl<'a> TryFrom<&'a PgRow> for AnyRow {
     type Error = sqlx_core::Error;
 
     fn try_from(row: &'a PgRow) -> Result<Self, Self::Error> {
-        AnyRow::map_from(row, row.metadata.column_names.clone())
+        AnyRow::map_from(
+            row,
+            row.metadata.column_names.clone(),
+            &AnyTypeRegistry::default(),
+        )
     }
 }
 
@@ -220,10 +305,321 @@ impl<'a> TryFrom<&'a AnyConnectOptions> for PgConnectOptions {
     fn try_from(value: &'a AnyConnectOptions) -> Result<Self, Self::Error> {
         let mut opts = PgConnectOptions::parse_from_url(&value.database_url)?;
         opts.log_settings = value.log_settings.clone();
+        if let Some(application_name) = value.get_application_name() {
+            opts = opts.application_name(application_name);
+        }
         Ok(opts)
     }
 }
 
+impl Type<Postgres> for AnyArgumentValue<'_> {
+    fn type_info() -> PgTypeInfo {
+        // overridden by `produces()`
+        PgTypeInfo::TEXT
+    }
+}
+
+impl<'q> Encode<'q, Postgres> for AnyArgumentValue<'q> {
+    fn produces(&self) -> Option<PgTypeInfo> {
+        Some(match self.0 {
+            AnyValueKind::Array { element, .. } => array_type_info(*element),
+            value => type_info(value.type_info().kind),
+        })
+    }
+
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        match self.0 {
+            AnyValueKind::Null => return IsNull::Yes,
+            AnyValueKind::Bool(b) => return Encode::<Postgres>::encode_by_ref(b, buf),
+            AnyValueKind::SmallInt(i) => return Encode::<Postgres>::encode_by_ref(i, buf),
+            AnyValueKind::Integer(i) => return Encode::<Postgres>::encode_by_ref(i, buf),
+            AnyValueKind::BigInt(i) => return Encode::<Postgres>::encode_by_ref(i, buf),
+            AnyValueKind::Real(r) => return Encode::<Postgres>::encode_by_ref(r, buf),
+            AnyValueKind::Double(d) => return Encode::<Postgres>::encode_by_ref(d, buf),
+            AnyValueKind::Text(t) => buf.extend(t.as_bytes()),
+            AnyValueKind::Blob(b) => buf.extend(&**b),
+            AnyValueKind::Numeric(n) => PgNumeric::from_decimal_str(n)
+                .expect("BUG: decimal number not checked by `check_argument()`")
+                .encode(buf),
+            AnyValueKind::Date(days) => buf.extend(
+                &pg_date(*days)
+                    .expect("BUG: date not checked by `check_argument()`")
+                    .to_be_bytes(),
+            ),
+            AnyValueKind::Time(micros) => buf.extend(&micros.to_be_bytes()),
+            AnyValueKind::Timestamp(micros) | AnyValueKind::TimestampTz(micros) => buf.extend(
+                &pg_timestamp(*micros)
+                    .expect("BUG: timestamp not checked by `check_argument()`")
+                    .to_be_bytes(),
+            ),
+            AnyValueKind::Uuid(uuid) => buf.extend(uuid),
+            AnyValueKind::Json(json) => {
+                // JSONB version
+                buf.push(1);
+                buf.extend(json.as_bytes());
+            }
+            AnyValueKind::Array { element, values } => {
+                buf.extend(&1_i32.to_be_bytes()); // number of dimensions
+                buf.extend(&0_i32.to_be_bytes()); // flags
+                buf.extend(&type_info(*element).0.oid().0.to_be_bytes());
+                buf.extend(&(values.len() as i32).to_be_bytes()); // len
+                buf.extend(&1_i32.to_be_bytes()); // lower bound
+
+                for value in values {
+                    match value {
+                        // multidimensional arrays aren't supported, so send nested arrays as text
+                        AnyValueKind::Array { .. } => buf.encode(value.to_string()),
+                        value => buf.encode(AnyArgumentValue(value)),
+                    }
+                }
+            }
+            // AnyValueKind is `#[non_exhaustive]` but we should have covered everything
+            value => unreachable!("BUG: missing mapping for {value:?}"),
+        }
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for AnyDecodedValue {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        decode_value(value).map(AnyDecodedValue)
+    }
+}
+
+/// Days from the Unix epoch to the Postgres epoch, `2000-01-01`.
+const PG_EPOCH_DAYS: i64 = 10_957;
+
+/// Convert `arguments`, first checking that each can be encoded for Postgres, as `Encode` can't
+/// return an error.
+fn pg_arguments(arguments: &AnyArguments<'_>) -> sqlx_core::Result<PgArguments> {
+    arguments
+        .values
+        .0
+        .iter()
+        .try_for_each(check_argument)
+        .map_err(sqlx_core::Error::Encode)?;
+
+    Ok(arguments.convert_to())
+}
+
+fn check_argument(value: &AnyValueKind<'_>) -> Result<(), BoxDynError> {
+    match value {
+        AnyValueKind::Numeric(n) => PgNumeric::from_decimal_str(n).map(drop),
+        AnyValueKind::Date(days) => pg_date(*days).map(drop),
+        AnyValueKind::Timestamp(micros) | AnyValueKind::TimestampTz(micros) => {
+            pg_timestamp(*micros).map(drop)
+        }
+        AnyValueKind::Array { values, .. } => values.iter().try_for_each(check_argument),
+        _ => Ok(()),
+    }
+}
+
+/// Days since the Postgres epoch.
+fn pg_date(days: i32) -> Result<i32, BoxDynError> {
+    i32::try_from(i64::from(days) - PG_EPOCH_DAYS).map_err(|_| {
+        format!(
+            "date out of range for Postgres DATE: {}",
+            AnyValueKind::Date(days)
+        )
+        .into()
+    })
+}
+
+/// Microseconds since the Postgres epoch.
+fn pg_timestamp(micros: i64) -> Result<i64, BoxDynError> {
+    micros
+        .checked_sub(PG_EPOCH_DAYS * text::MICROS_PER_DAY)
+        .ok_or_else(|| {
+            format!(
+                "timestamp out of range for Postgres TIMESTAMP: {}",
+                AnyValueKind::Timestamp(micros)
+            )
+            .into()
+        })
+}
+
+fn decode_value(value: PgValueRef<'_>) -> Result<AnyValueKind<'static>, BoxDynError> {
+    if value.is_null() {
+        return Ok(AnyValueKind::Null);
+    }
+
+    let kind = AnyTypeInfo::try_from(&value.type_info)?.kind;
+    let format = value.format();
+
+    Ok(match kind {
+        AnyTypeInfoKind::Null => AnyValueKind::Null,
+        AnyTypeInfoKind::Bool => AnyValueKind::Bool(Decode::<Postgres>::decode(value)?),
+        AnyTypeInfoKind::SmallInt => AnyValueKind::SmallInt(Decode::<Postgres>::decode(value)?),
+        AnyTypeInfoKind::Integer => AnyValueKind::Integer(Decode::<Postgres>::decode(value)?),
+        AnyTypeInfoKind::BigInt => AnyValueKind::BigInt(Decode::<Postgres>::decode(value)?),
+        AnyTypeInfoKind::Real => AnyValueKind::Real(Decode::<Postgres>::decode(value)?),
+        AnyTypeInfoKind::Double => AnyValueKind::Double(Decode::<Postgres>::decode(value)?),
+        AnyTypeInfoKind::Text => {
+            AnyValueKind::Text(<String as Decode<Postgres>>::decode(value)?.into())
+        }
+        AnyTypeInfoKind::Blob => {
+            AnyValueKind::Blob(<Vec<u8> as Decode<Postgres>>::decode(value)?.into())
+        }
+        AnyTypeInfoKind::Numeric => AnyValueKind::Numeric(
+            match format {
+                PgValueFormat::Binary => PgNumeric::decode(value.as_bytes()?)?.to_decimal_string(),
+                PgValueFormat::Text => value.as_str()?.to_owned(),
+            }
+            .into(),
+        ),
+        AnyTypeInfoKind::Date => AnyValueKind::Date(match format {
+            PgValueFormat::Binary => {
+                let days = i64::from(<i32 as Decode<Postgres>>::decode(value)?) + PG_EPOCH_DAYS;
+                i32::try_from(days).map_err(|_| "date out of range")?
+            }
+            PgValueFormat::Text => text::parse_date(value.as_str()?)?,
+        }),
+        AnyTypeInfoKind::Time => AnyValueKind::Time(match format {
+            PgValueFormat::Binary => <i64 as Decode<Postgres>>::decode(value)?,
+            PgValueFormat::Text => text::parse_time(value.as_str()?)?,
+        }),
+        AnyTypeInfoKind::Timestamp | AnyTypeInfoKind::TimestampTz => {
+            let micros = match format {
+                PgValueFormat::Binary => <i64 as Decode<Postgres>>::decode(value)?
+                    .checked_add(PG_EPOCH_DAYS * text::MICROS_PER_DAY)
+                    .ok_or("timestamp out of range")?,
+                PgValueFormat::Text if kind == AnyTypeInfoKind::Timestamp => {
+                    text::parse_timestamp(value.as_str()?)?
+                }
+                PgValueFormat::Text => text::parse_timestamptz(value.as_str()?)?,
+            };
+
+            if kind == AnyTypeInfoKind::Timestamp {
+                AnyValueKind::Timestamp(micros)
+            } else {
+                AnyValueKind::TimestampTz(micros)
+            }
+        }
+        AnyTypeInfoKind::Uuid => AnyValueKind::Uuid(match format {
+            PgValueFormat::Binary => value.as_bytes()?.try_into()?,
+            PgValueFormat::Text => text::parse_uuid(value.as_str()?)?,
+        }),
+        AnyTypeInfoKind::Json => {
+            let mut json = value.as_bytes()?;
+
+            if format == PgValueFormat::Binary && *value.type_info == PgType::Jsonb {
+                // JSONB version
+                if json.first() != Some(&1) {
+                    return Err("unsupported JSONB format version; please open an issue".into());
+                }
+
+                json = &json[1..];
+            }
+
+            AnyValueKind::Json(std::str::from_utf8(json)?.to_owned().into())
+        }
+        AnyTypeInfoKind::Array => decode_array(value)?,
+        _ => return Err(format!("unsupported type {kind:?}").into()),
+    })
+}
+
+fn decode_array(value: PgValueRef<'_>) -> Result<AnyValueKind<'static>, BoxDynError> {
+    let element_type_info = value
+        .type_info
+        .try_array_element()
+        .ok_or("expected an array type")?
+        .into_owned();
+    let element = AnyTypeInfo::try_from(&element_type_info)?.kind;
+    let clamp_infinite_timestamps = value.clamp_infinite_timestamps;
+    let exact_decimals = value.exact_decimals;
+
+    let values = match value.format() {
+        PgValueFormat::Binary => {
+            let mut buf = value.as_bytes()?;
+
+            let ndim = buf.get_i32();
+
+            if ndim == 0 {
+                Vec::new()
+            } else if ndim != 1 {
+                return Err(format!("encountered an array of {ndim} dimensions; only one-dimensional arrays are supported").into());
+            } else {
+                let _flags = buf.get_i32();
+                let _element_type_oid = buf.get_u32();
+                let len = buf.get_i32();
+                let _lower = buf.get_i32();
+
+                (0..len)
+                    .map(|_| {
+                        decode_value(PgValueRef::get(
+                            &mut buf,
+                            PgValueFormat::Binary,
+                            element_type_info.clone(),
+                            clamp_infinite_timestamps,
+                            exact_decimals,
+                        ))
+                    })
+                    .collect::<Result<_, _>>()?
+            }
+        }
+        PgValueFormat::Text => <Vec<Option<String>> as Decode<Postgres>>::decode(value)?
+            .iter()
+            .map(|element| {
+                decode_value(PgValueRef {
+                    value: element.as_deref().map(str::as_bytes),
+                    row: None,
+                    type_info: element_type_info.clone(),
+                    format: PgValueFormat::Text,
+                    clamp_infinite_timestamps,
+                    exact_decimals,
+                })
+            })
+            .collect::<Result<_, _>>()?,
+    };
+
+    Ok(AnyValueKind::Array { element, values })
+}
+
+fn type_info(kind: AnyTypeInfoKind) -> PgTypeInfo {
+    match kind {
+        AnyTypeInfoKind::Bool => PgTypeInfo::BOOL,
+        AnyTypeInfoKind::SmallInt => PgTypeInfo::INT2,
+        AnyTypeInfoKind::Integer => PgTypeInfo::INT4,
+        AnyTypeInfoKind::BigInt => PgTypeInfo::INT8,
+        AnyTypeInfoKind::Real => PgTypeInfo::FLOAT4,
+        AnyTypeInfoKind::Double => PgTypeInfo::FLOAT8,
+        AnyTypeInfoKind::Blob => PgTypeInfo::BYTEA,
+        AnyTypeInfoKind::Numeric => PgTypeInfo::NUMERIC,
+        AnyTypeInfoKind::Date => PgTypeInfo::DATE,
+        AnyTypeInfoKind::Time => PgTypeInfo::TIME,
+        AnyTypeInfoKind::Timestamp => PgTypeInfo::TIMESTAMP,
+        AnyTypeInfoKind::TimestampTz => PgTypeInfo::TIMESTAMPTZ,
+        AnyTypeInfoKind::Uuid => PgTypeInfo::UUID,
+        AnyTypeInfoKind::Json => PgTypeInfo::JSONB,
+        AnyTypeInfoKind::Null | AnyTypeInfoKind::Text | AnyTypeInfoKind::Array => PgTypeInfo::TEXT,
+        _ => PgTypeInfo::TEXT,
+    }
+}
+
+fn array_type_info(element: AnyTypeInfoKind) -> PgTypeInfo {
+    match element {
+        AnyTypeInfoKind::Bool => PgTypeInfo::BOOL_ARRAY,
+        AnyTypeInfoKind::SmallInt => PgTypeInfo::INT2_ARRAY,
+        AnyTypeInfoKind::Integer => PgTypeInfo::INT4_ARRAY,
+        AnyTypeInfoKind::BigInt => PgTypeInfo::INT8_ARRAY,
+        AnyTypeInfoKind::Real => PgTypeInfo::FLOAT4_ARRAY,
+        AnyTypeInfoKind::Double => PgTypeInfo::FLOAT8_ARRAY,
+        AnyTypeInfoKind::Blob => PgTypeInfo::BYTEA_ARRAY,
+        AnyTypeInfoKind::Numeric => PgTypeInfo::NUMERIC_ARRAY,
+        AnyTypeInfoKind::Date => PgTypeInfo::DATE_ARRAY,
+        AnyTypeInfoKind::Time => PgTypeInfo::TIME_ARRAY,
+        AnyTypeInfoKind::Timestamp => PgTypeInfo::TIMESTAMP_ARRAY,
+        AnyTypeInfoKind::TimestampTz => PgTypeInfo::TIMESTAMPTZ_ARRAY,
+        AnyTypeInfoKind::Uuid => PgTypeInfo::UUID_ARRAY,
+        AnyTypeInfoKind::Json => PgTypeInfo::JSONB_ARRAY,
+        AnyTypeInfoKind::Null | AnyTypeInfoKind::Text | AnyTypeInfoKind::Array => {
+            PgTypeInfo::TEXT_ARRAY
+        }
+        _ => PgTypeInfo::TEXT_ARRAY,
+    }
+}
+
 fn map_result(res: PgQueryResult) -> AnyQueryResult {
     AnyQueryResult {
         rows_affected: res.rows_affected(),
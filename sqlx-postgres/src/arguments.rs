@@ -1,6 +1,9 @@
 use std::fmt::{self, Write};
+use std::mem;
 use std::ops::{Deref, DerefMut};
 
+use sqlx_core::buffer_pool::buffer_pool;
+
 use crate::encode::{Encode, IsNull};
 use crate::error::Error;
 use crate::ext::ustr::UStr;
@@ -74,6 +77,11 @@ impl PgArguments {
         self.buffer.count += 1;
     }
 
+    // Whether `apply_patches` may have to ask postgres for the OID of a type
+    pub(crate) fn has_type_holes(&self) -> bool {
+        !self.buffer.type_holes.is_empty()
+    }
+
     // Apply patches
     // This should only go out and ask postgres if we have not seen the type name yet
     pub(crate) async fn apply_patches(
@@ -109,7 +117,7 @@ impl<'q> Arguments<'q> for PgArguments {
 
     fn reserve(&mut self, additional: usize, size: usize) {
         self.types.reserve(additional);
-        self.buffer.reserve(size);
+        buffer_pool().reserve(&mut self.buffer, size);
     }
 
     fn add<T>(&mut self, value: T)
@@ -122,6 +130,13 @@ impl<'q> Arguments<'q> for PgArguments {
     fn format_placeholder<W: Write>(&self, writer: &mut W) -> fmt::Result {
         write!(writer, "${}", self.buffer.count)
     }
+
+    fn shorten<'a>(self) -> Option<PgArguments>
+    where
+        'q: 'a,
+    {
+        Some(self)
+    }
 }
 
 impl PgArgumentBuffer {
@@ -129,6 +144,11 @@ impl PgArgumentBuffer {
     where
         T: Encode<'q, Postgres>,
     {
+        // the first value takes a pooled buffer, unless one was reserved
+        if self.buffer.capacity() == 0 {
+            self.buffer = buffer_pool().take(0);
+        }
+
         // reserve space to write the prefixed length of the value
         let offset = self.len();
         self.extend(&[0; 4]);
@@ -169,6 +189,12 @@ impl PgArgumentBuffer {
     }
 }
 
+impl Drop for PgArgumentBuffer {
+    fn drop(&mut self) {
+        buffer_pool().recycle(mem::take(&mut self.buffer));
+    }
+}
+
 impl Deref for PgArgumentBuffer {
     type Target = Vec<u8>;
 
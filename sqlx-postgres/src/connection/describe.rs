@@ -418,8 +418,8 @@ WHERE rngtypid = $1
 
     /// Infer nullability for columns of this statement using EXPLAIN VERBOSE.
     ///
-    /// This currently only marks columns that are on the inner half of an outer join
-    /// and returns `None` for all others.
+    /// This currently only marks columns that are on the nullable half of an outer join or are
+    /// aggregates without `GROUP BY`, and returns `None` for all others.
     async fn nullables_from_explain(
         &mut self,
         stmt_id: Oid,
@@ -469,26 +469,58 @@ WHERE rngtypid = $1
 
 fn visit_plan(plan: &Plan, outputs: &[String], nullables: &mut Vec<Option<bool>>) {
     if let Some(plan_outputs) = &plan.output {
-        // all outputs of a Full Join must be marked nullable
-        // otherwise, all outputs of the inner half of an outer join must be marked nullable
-        if plan.join_type.as_deref() == Some("Full")
-            || plan.parent_relation.as_deref() == Some("Inner")
+        // an aggregate without `GROUP BY` returns a row even if there are no input rows,
+        // in which case every aggregate but `count()` is NULL
+        if plan.node_type.as_deref() == Some("Aggregate")
+            && plan.strategy.as_deref() == Some("Plain")
         {
-            for output in plan_outputs {
-                if let Some(i) = outputs.iter().position(|o| o == output) {
-                    // N.B. this may produce false positives but those don't cause runtime errors
-                    nullables[i] = Some(true);
-                }
-            }
+            let aggregates = plan_outputs.iter().filter(|o| !o.starts_with("count("));
+            mark_nullable(aggregates, outputs, nullables);
+        }
+
+        // all outputs of a Full Join must be marked nullable
+        if plan.join_type.as_deref() == Some("Full") {
+            mark_nullable(plan_outputs, outputs, nullables);
         }
     }
 
-    if let Some(plans) = &plan.plans {
-        if let Some("Left") | Some("Right") = plan.join_type.as_deref() {
-            for plan in plans {
-                visit_plan(plan, outputs, nullables);
+    // otherwise, all outputs of the inner half of a Left Join or the outer half of a Right Join
+    // must be marked nullable
+    let nullable_half = match plan.join_type.as_deref() {
+        Some("Left") => Some("Inner"),
+        Some("Right") => Some("Outer"),
+        _ => None,
+    };
+
+    for plan in plan.plans.iter().flatten() {
+        let relation = plan.parent_relation.as_deref();
+
+        // the outputs of subqueries in expressions aren't outputs of the statement
+        if let Some("SubPlan") | Some("InitPlan") = relation {
+            continue;
+        }
+
+        if relation.is_some() && relation == nullable_half {
+            if let Some(plan_outputs) = &plan.output {
+                mark_nullable(plan_outputs, outputs, nullables);
             }
         }
+
+        // outer joins can be nested under any node, e.g. a sort or another join
+        visit_plan(plan, outputs, nullables);
+    }
+}
+
+fn mark_nullable<'a>(
+    plan_outputs: impl IntoIterator<Item = &'a String>,
+    outputs: &[String],
+    nullables: &mut [Option<bool>],
+) {
+    for output in plan_outputs {
+        if let Some(i) = outputs.iter().position(|o| o == output) {
+            // N.B. this may produce false positives but those don't cause runtime errors
+            nullables[i] = Some(true);
+        }
     }
 }
 
@@ -517,6 +549,10 @@ enum Explain {
 
 #[derive(serde::Deserialize, Debug)]
 struct Plan {
+    #[serde(rename = "Node Type")]
+    node_type: Option<String>,
+    #[serde(rename = "Strategy")]
+    strategy: Option<String>,
     #[serde(rename = "Join Type")]
     join_type: Option<String>,
     #[serde(rename = "Parent Relationship")]
@@ -583,3 +619,83 @@ fn explain_parsing() {
         "unexpected parse from {utility_statement:?}: {utility_statement_parsed:?}"
     )
 }
+
+#[test]
+fn explain_nullables() {
+    fn nullables(explain: &str) -> Vec<Option<bool>> {
+        let [explain] = serde_json::from_str::<[Explain; 1]>(explain).unwrap();
+
+        let Explain::Plan { plan } = explain else {
+            panic!("expected a plan: {explain:?}");
+        };
+
+        let outputs = plan.output.clone().unwrap();
+        let mut nullables = vec![None; outputs.len()];
+        visit_plan(&plan, &outputs, &mut nullables);
+
+        nullables
+    }
+
+    // `tweet1 LEFT JOIN tweet2 ... ORDER BY tweet1.id`, with the join reversed by the planner
+    let right_join = r#"[
+   {
+     "Plan": {
+       "Node Type": "Sort",
+       "Output": ["tweet1.id", "tweet2.id"],
+       "Plans": [
+         {
+           "Node Type": "Hash Join",
+           "Parent Relationship": "Outer",
+           "Join Type": "Right",
+           "Output": ["tweet1.id", "tweet2.id"],
+           "Plans": [
+             {
+               "Node Type": "Seq Scan",
+               "Parent Relationship": "Outer",
+               "Relation Name": "tweet",
+               "Alias": "tweet2",
+               "Output": ["tweet2.id", "tweet2.text"]
+             },
+             {
+               "Node Type": "Hash",
+               "Parent Relationship": "Inner",
+               "Output": ["tweet1.id"],
+               "Plans": [
+                 {
+                   "Node Type": "Seq Scan",
+                   "Parent Relationship": "Outer",
+                   "Relation Name": "tweet",
+                   "Alias": "tweet1",
+                   "Output": ["tweet1.id"]
+                 }
+               ]
+             }
+           ]
+         }
+       ]
+     }
+   }
+]"#;
+
+    assert_eq!(nullables(right_join), [None, Some(true)]);
+
+    let aggregate = r#"[
+   {
+     "Plan": {
+       "Node Type": "Aggregate",
+       "Strategy": "Plain",
+       "Output": ["max(id)", "count(*)"],
+       "Plans": [
+         {
+           "Node Type": "Seq Scan",
+           "Parent Relationship": "Outer",
+           "Relation Name": "tweet",
+           "Output": ["id", "text"]
+         }
+       ]
+     }
+   }
+]"#;
+
+    assert_eq!(nullables(aggregate), [Some(true), None]);
+}
@@ -1,7 +1,7 @@
 use crate::HashMap;
 
 use crate::common::StatementCache;
-use crate::connection::{sasl, stream::PgStream};
+use crate::connection::{sasl, stream::PgStream, RunningQuery};
 use crate::error::Error;
 use crate::io::Decode;
 use crate::message::{
@@ -142,11 +142,20 @@ impl PgConnection {
             transaction_status,
             transaction_depth: 0,
             pending_ready_for_query_count: 0,
+            sync_deferred: false,
             next_statement_id: Oid(1),
             cache_statement: StatementCache::new(options.statement_cache_capacity),
             cache_type_oid: HashMap::new(),
             cache_type_info: HashMap::new(),
             log_settings: options.log_settings.clone(),
+            clamp_infinite_timestamps: options.clamp_infinite_timestamps,
+            exact_decimals: options.exact_decimals,
+            max_row_size: options.max_row_size,
+            max_result_size: options.max_result_size,
+            reprepare_statements: options.reprepare_statements,
+            #[cfg(feature = "any")]
+            any_type_registry: Default::default(),
+            running_query: RunningQuery::default(),
         })
     }
 }
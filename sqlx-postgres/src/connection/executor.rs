@@ -4,7 +4,7 @@ use crate::executor::{Execute, Executor};
 use crate::logger::QueryLogger;
 use crate::message::{
     self, Bind, Close, CommandComplete, DataRow, MessageFormat, ParameterDescription, Parse, Query,
-    RowDescription,
+    RowDescription, TransactionStatus,
 };
 use crate::statement::PgStatementMetadata;
 use crate::type_info::PgType;
@@ -20,6 +20,17 @@ use futures_util::{pin_mut, TryStreamExt};
 use sqlx_core::Either;
 use std::{borrow::Cow, sync::Arc};
 
+/// How many rows of a portal are fetched by each `Execute`.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum FetchLimit {
+    /// All of the rows.
+    All,
+    /// Only the first row, for `fetch_optional()`.
+    First,
+    /// Batches of rows, resuming the portal as the stream consumes them.
+    Batches(u32),
+}
+
 async fn prepare(
     conn: &mut PgConnection,
     sql: &str,
@@ -52,10 +63,12 @@ async fn prepare(
         query: sql,
         statement: id,
     });
+    conn.stream.wire_stats_mut().parses += 1;
 
     if metadata.is_none() {
         // get the statement columns and parameters
         conn.stream.write(message::Describe::Statement(id));
+        conn.stream.wire_stats_mut().describes += 1;
     }
 
     // we ask for the server to immediately send us the result of the PARSE command
@@ -125,6 +138,12 @@ async fn recv_desc_rows(conn: &mut PgConnection) -> Result<Option<RowDescription
     Ok(rows)
 }
 
+/// The most executions [`PgConnection::run_batch()`] sends before reading their results.
+const BATCH_WINDOW: usize = 256;
+
+/// The most bytes of arguments [`PgConnection::run_batch()`] sends before reading their results.
+const BATCH_WINDOW_SIZE: usize = 64 * 1024;
+
 impl PgConnection {
     // wait for CloseComplete to indicate a statement was closed
     pub(super) async fn wait_for_close_complete(&mut self, mut count: usize) -> Result<(), Error> {
@@ -160,7 +179,17 @@ impl PgConnection {
         self.pending_ready_for_query_count += 1;
     }
 
-    async fn get_or_prepare<'a>(
+    // close the unnamed portal kept open by `run()` and write the [Sync] that was deferred,
+    // whose ReadyForQuery was already counted
+    pub(crate) fn write_deferred_sync(&mut self) {
+        if self.sync_deferred {
+            self.stream.write(message::Close::Portal(None));
+            self.stream.write(message::Sync);
+            self.sync_deferred = false;
+        }
+    }
+
+    pub(super) async fn get_or_prepare<'a>(
         &mut self,
         sql: &str,
         parameters: &[PgTypeInfo],
@@ -191,75 +220,272 @@ impl PgConnection {
         Ok(statement)
     }
 
+    /// Writes the messages to bind the patched `arguments` to `statement` and execute it.
+    async fn write_execute(
+        &mut self,
+        statement: Oid,
+        arguments: &PgArguments,
+        limit: u32,
+        resume: bool,
+    ) -> Result<(), Error> {
+        // consume messages till `ReadyForQuery` before bind and execute
+        self.wait_until_ready().await?;
+
+        // bind to attach the arguments to the statement and create a portal
+        self.stream.write(Bind {
+            portal: None,
+            statement,
+            formats: &[PgValueFormat::Binary],
+            num_params: arguments.types.len() as i16,
+            params: &*arguments.buffer,
+            result_formats: &[PgValueFormat::Binary],
+        });
+
+        // executes the portal up to the passed limit
+        // the protocol-level limit acts nearly identically to the `LIMIT` in SQL
+        self.stream.write(message::Execute {
+            portal: None,
+            limit,
+        });
+        self.stream.wire_stats_mut().executes += 1;
+
+        if resume {
+            // [Sync] would end the implicit transaction and so destroy the portal,
+            // so it's deferred until the portal completes and [Flush] is sent instead
+            self.stream.write(message::Flush);
+            self.pending_ready_for_query_count += 1;
+            self.sync_deferred = true;
+        } else {
+            // From https://www.postgresql.org/docs/current/protocol-flow.html:
+            //
+            // "An unnamed portal is destroyed at the end of the transaction, or as
+            // soon as the next Bind statement specifying the unnamed portal as
+            // destination is issued. (Note that a simple Query message also
+            // destroys the unnamed portal."
+
+            // we ask the database server to close the unnamed portal and free the associated resources
+            // earlier - after the execution of the current query.
+            self.stream.write(message::Close::Portal(None));
+
+            // finally, [Sync] asks postgres to process the messages that we sent and respond with
+            // a [ReadyForQuery] message when it's completely done. Theoretically, we could send
+            // dozens of queries before a [Sync] and postgres can handle that. Execution on the server
+            // is still serial but it would reduce round-trips. Some kind of builder pattern that is
+            // termed batching might suit this.
+            self.write_sync();
+        }
+
+        Ok(())
+    }
+
+    /// Executes the query once with each set of arguments in `batch`, pipelining the executions.
+    ///
+    /// The executions are sent in windows of up to [`BATCH_WINDOW`] of them, or
+    /// [`BATCH_WINDOW_SIZE`] bytes of arguments. After each window a `Flush` is sent and its
+    /// results are read before the next one is written, so neither side blocks on a full socket
+    /// buffer; the last one ends with a `Sync`. The arguments are taken from the iterator only as
+    /// they're sent.
+    ///
+    /// If an execution fails, the server skips the rest of them.
+    pub(crate) async fn run_batch(
+        &mut self,
+        query: &str,
+        mut batch: Box<dyn Iterator<Item = PgArguments> + Send + '_>,
+    ) -> Result<PgQueryResult, Error> {
+        let Some(first) = batch.next() else {
+            return Ok(PgQueryResult::default());
+        };
+
+        let mut logger = QueryLogger::new(query, self.log_settings.clone());
+        let _running_query = self.running_query.start(query);
+
+        let (statement, metadata) = self.get_or_prepare(query, &first.types, true, None).await?;
+
+        self.wait_until_ready().await?;
+
+        let wire_stats_start = self.stream.wire_stats();
+
+        let mut rows_affected = 0;
+        let mut next = Some(first);
+
+        while next.is_some() {
+            let mut executions = 0;
+            let mut size = 0;
+
+            while let Some(mut arguments) = next.take() {
+                // patching may query the server for type OIDs, which can't be done while the
+                // results of the window are pending
+                if executions > 0 && arguments.has_type_holes() {
+                    next = Some(arguments);
+                    break;
+                }
+
+                arguments.apply_patches(self, &metadata.parameters).await?;
+
+                self.stream.write(Bind {
+                    portal: None,
+                    statement,
+                    formats: &[PgValueFormat::Binary],
+                    num_params: arguments.types.len() as i16,
+                    params: &*arguments.buffer,
+                    result_formats: &[PgValueFormat::Binary],
+                });
+
+                self.stream.write(message::Execute {
+                    portal: None,
+                    limit: 0,
+                });
+                self.stream.wire_stats_mut().executes += 1;
+
+                executions += 1;
+                size += arguments.buffer.len();
+
+                next = batch.next();
+
+                if executions == BATCH_WINDOW || size >= BATCH_WINDOW_SIZE {
+                    break;
+                }
+            }
+
+            // the last window ends the pipeline
+            let synced = next.is_none();
+
+            if synced {
+                self.stream.write(message::Close::Portal(None));
+                self.write_sync();
+            } else {
+                // asks the server to send the results so far without ending the pipeline
+                self.stream.write(message::Flush);
+            }
+
+            self.stream.flush().await?;
+
+            let mut completed = 0;
+
+            while synced || completed < executions {
+                let message = match self.stream.recv().await {
+                    Ok(message) => message,
+
+                    // rows are discarded anyway
+                    Err(Error::RowTooLarge { .. }) => continue,
+
+                    Err(error @ Error::Database(_)) => {
+                        // the server skips everything up to the next [Sync]
+                        if !synced {
+                            self.write_sync();
+                        }
+
+                        self.wait_until_ready().await?;
+
+                        return Err(error);
+                    }
+
+                    Err(error) => return Err(error),
+                };
+
+                match message.format {
+                    MessageFormat::BindComplete
+                    | MessageFormat::CloseComplete
+                    // rows returned by the statement, e.g. with `RETURNING`, are discarded
+                    | MessageFormat::DataRow => {}
+
+                    MessageFormat::CommandComplete => {
+                        let cc: CommandComplete = message.decode()?;
+                        rows_affected += cc.rows_affected();
+                        completed += 1;
+                    }
+
+                    MessageFormat::EmptyQueryResponse => {
+                        completed += 1;
+                    }
+
+                    MessageFormat::ReadyForQuery => {
+                        self.handle_ready_for_query(message)?;
+                        break;
+                    }
+
+                    _ => {
+                        return Err(err_protocol!(
+                            "execute_batch: unexpected message: {:?}",
+                            message.format
+                        ));
+                    }
+                }
+            }
+        }
+
+        logger.increase_rows_affected(rows_affected);
+        logger.set_wire_stats(self.stream.wire_stats().since(&wire_stats_start));
+
+        Ok(PgQueryResult { rows_affected })
+    }
+
+    /// Executes the query, returning up to `limit` rows unless it's `0`.
+    ///
+    /// If `resume` is set, all rows are returned but only `limit` are fetched at a time, by
+    /// executing the portal again each time it's suspended.
     pub(crate) async fn run<'e, 'c: 'e, 'q: 'e>(
         &'c mut self,
         query: &'q str,
         arguments: Option<PgArguments>,
-        limit: u8,
+        fetch: FetchLimit,
+        size_limited: bool,
         persistent: bool,
         metadata_opt: Option<Arc<PgStatementMetadata>>,
     ) -> Result<impl Stream<Item = Result<Either<PgQueryResult, PgRow>, Error>> + 'e, Error> {
         let mut logger = QueryLogger::new(query, self.log_settings.clone());
+        let running_query = self.running_query.start(query);
+
+        let (limit, resume) = match fetch {
+            FetchLimit::All => (0, false),
+            FetchLimit::First => (1, false),
+            FetchLimit::Batches(rows) => (rows, true),
+        };
 
         // before we continue, wait until we are "ready" to accept more queries
         self.wait_until_ready().await?;
 
+        // unless the query opted out, rows over the limits are rejected
+        let max_result_size = self.max_result_size.filter(|_| size_limited);
+        self.stream.max_row_size = self.max_row_size.filter(|_| size_limited);
+
+        // anything before this belongs to the previous query
+        let wire_stats_start = self.stream.wire_stats();
+
         let mut metadata: Arc<PgStatementMetadata>;
 
+        // the arguments to prepare and execute the query with again if its cached statement no
+        // longer exists on the server; see `PgConnectOptions::reprepare_statements()`
+        let mut reprepare = None;
+
         let format = if let Some(mut arguments) = arguments {
+            let cached = persistent && self.cache_statement.contains_key(query);
+
             // prepare the statement if this our first time executing it
             // always return the statement ID here
             let (statement, metadata_) = self
-                .get_or_prepare(query, &arguments.types, persistent, metadata_opt)
+                .get_or_prepare(query, &arguments.types, persistent, metadata_opt.clone())
                 .await?;
 
             metadata = metadata_;
 
-            // patch holes created during encoding
+            // patch holes created during encoding, once: a re-prepared statement takes the same
+            // parameters
             arguments.apply_patches(self, &metadata.parameters).await?;
 
-            // consume messages till `ReadyForQuery` before bind and execute
-            self.wait_until_ready().await?;
-
-            // bind to attach the arguments to the statement and create a portal
-            self.stream.write(Bind {
-                portal: None,
-                statement,
-                formats: &[PgValueFormat::Binary],
-                num_params: arguments.types.len() as i16,
-                params: &*arguments.buffer,
-                result_formats: &[PgValueFormat::Binary],
-            });
-
-            // executes the portal up to the passed limit
-            // the protocol-level limit acts nearly identically to the `LIMIT` in SQL
-            self.stream.write(message::Execute {
-                portal: None,
-                limit: limit.into(),
-            });
-            // From https://www.postgresql.org/docs/current/protocol-flow.html:
-            //
-            // "An unnamed portal is destroyed at the end of the transaction, or as
-            // soon as the next Bind statement specifying the unnamed portal as
-            // destination is issued. (Note that a simple Query message also
-            // destroys the unnamed portal."
-
-            // we ask the database server to close the unnamed portal and free the associated resources
-            // earlier - after the execution of the current query.
-            self.stream.write(message::Close::Portal(None));
+            self.write_execute(statement, &arguments, limit, resume)
+                .await?;
 
-            // finally, [Sync] asks postgres to process the messages that we sent and respond with
-            // a [ReadyForQuery] message when it's completely done. Theoretically, we could send
-            // dozens of queries before a [Sync] and postgres can handle that. Execution on the server
-            // is still serial but it would reduce round-trips. Some kind of builder pattern that is
-            // termed batching might suit this.
-            self.write_sync();
+            if cached && self.reprepare_statements {
+                reprepare = Some((arguments, metadata_opt));
+            }
 
             // prepared statements are binary
             PgValueFormat::Binary
         } else {
             // Query will trigger a ReadyForQuery
             self.stream.write(Query(query));
+            self.stream.wire_stats_mut().executes += 1;
             self.pending_ready_for_query_count += 1;
 
             // metadata starts out as "nothing"
@@ -272,8 +498,49 @@ impl PgConnection {
         self.stream.flush().await?;
 
         Ok(try_stream! {
+            // cleared once the query completes or the stream is dropped
+            let _running_query = running_query;
+
+            // the size of the rows received so far
+            let mut result_size = 0;
+
             loop {
-                let message = self.stream.recv().await?;
+                let message = match self.stream.recv().await {
+                    Ok(message) => message,
+
+                    // the cached statement no longer exists on the server, e.g. after a failover
+                    Err(Error::Database(error))
+                        if reprepare.is_some() && error.code().as_deref() == Some("26000") =>
+                    {
+                        let (arguments, metadata_opt) = reprepare.take().unwrap();
+
+                        // the server skips the rest of the query after the error
+                        self.wait_until_ready().await?;
+
+                        // an explicit transaction was aborted by the error
+                        if !matches!(self.transaction_status, TransactionStatus::Idle) {
+                            return Err(Error::Database(error));
+                        }
+
+                        tracing::debug!(query, "preparing a statement lost by the server again");
+
+                        // there's nothing to close on the server
+                        self.cache_statement.remove(query);
+
+                        let (statement, metadata_) = self
+                            .get_or_prepare(query, &arguments.types, persistent, metadata_opt)
+                            .await?;
+
+                        metadata = metadata_;
+
+                        self.write_execute(statement, &arguments, limit, resume).await?;
+
+                        self.stream.flush().await?;
+                        continue;
+                    }
+
+                    Err(e) => return Err(e),
+                };
 
                 match message.format {
                     MessageFormat::BindComplete
@@ -296,6 +563,13 @@ impl PgConnection {
 
                         let rows_affected = cc.rows_affected();
                         logger.increase_rows_affected(rows_affected);
+
+                        if self.sync_deferred {
+                            // the portal has completed
+                            self.write_deferred_sync();
+                            self.stream.flush().await?;
+                        }
+
                         r#yield!(Either::Left(PgQueryResult {
                             rows_affected,
                         }));
@@ -308,7 +582,18 @@ impl PgConnection {
                     // Message::ErrorResponse is handled in self.stream.recv()
 
                     // incomplete query execution has finished
-                    MessageFormat::PortalSuspended => {}
+                    MessageFormat::PortalSuspended => {
+                        if self.sync_deferred {
+                            // the stream has caught up, so fetch the next batch of rows
+                            self.stream.write(message::Execute {
+                                portal: None,
+                                limit,
+                            });
+                            self.stream.write(message::Flush);
+                            self.stream.wire_stats_mut().executes += 1;
+                            self.stream.flush().await?;
+                        }
+                    }
 
                     MessageFormat::RowDescription => {
                         // indicates that a *new* set of rows are about to be returned
@@ -326,12 +611,21 @@ impl PgConnection {
                     MessageFormat::DataRow => {
                         logger.increment_rows_returned();
 
+                        result_size += message.contents.len();
+
+                        if let Some(limit) = max_result_size.filter(|&limit| result_size > limit) {
+                            // the rest of the rows are discarded before the next query
+                            return Err(Error::ResultTooLarge { limit });
+                        }
+
                         // one of the set of rows returned by a SELECT, FETCH, etc query
                         let data: DataRow = message.decode()?;
                         let row = PgRow {
                             data,
                             format,
                             metadata: Arc::clone(&metadata),
+                            clamp_infinite_timestamps: self.clamp_infinite_timestamps,
+                            exact_decimals: self.exact_decimals,
                         };
 
                         r#yield!(Either::Right(row));
@@ -340,6 +634,7 @@ impl PgConnection {
                     MessageFormat::ReadyForQuery => {
                         // processing of the query string is complete
                         self.handle_ready_for_query(message)?;
+                        logger.set_wire_stats(self.stream.wire_stats().since(&wire_stats_start));
                         break;
                     }
 
@@ -372,9 +667,15 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         let metadata = query.statement().map(|s| Arc::clone(&s.metadata));
         let arguments = query.take_arguments();
         let persistent = query.persistent();
+        let fetch = query
+            .fetch_size()
+            .map_or(FetchLimit::All, FetchLimit::Batches);
+        let size_limited = query.size_limited();
 
         Box::pin(try_stream! {
-            let s = self.run(sql, arguments, 0, persistent, metadata).await?;
+            let s = self
+                .run(sql, arguments, fetch, size_limited, persistent, metadata)
+                .await?;
             pin_mut!(s);
 
             while let Some(v) = s.try_next().await? {
@@ -397,9 +698,19 @@ impl<'c> Executor<'c> for &'c mut PgConnection {
         let metadata = query.statement().map(|s| Arc::clone(&s.metadata));
         let arguments = query.take_arguments();
         let persistent = query.persistent();
+        let size_limited = query.size_limited();
 
         Box::pin(async move {
-            let s = self.run(sql, arguments, 1, persistent, metadata).await?;
+            let s = self
+                .run(
+                    sql,
+                    arguments,
+                    FetchLimit::First,
+                    size_limited,
+                    persistent,
+                    metadata,
+                )
+                .await?;
             pin_mut!(s);
 
             while let Some(s) = s.try_next().await? {
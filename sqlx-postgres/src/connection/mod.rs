@@ -13,17 +13,19 @@ use crate::message::{
     Close, Message, MessageFormat, Query, ReadyForQuery, Terminate, TransactionStatus,
 };
 use crate::statement::PgStatementMetadata;
-use crate::transaction::Transaction;
+use crate::transaction::{Transaction, TransactionOptions};
 use crate::types::Oid;
-use crate::{PgConnectOptions, PgTypeInfo, Postgres};
+use crate::{PgArguments, PgConnectOptions, PgQueryResult, PgTypeInfo, Postgres};
 
 pub(crate) use sqlx_core::connection::*;
 
+pub use self::multiplexer::PgSessionMultiplexer;
 pub use self::stream::PgStream;
 
 pub(crate) mod describe;
 mod establish;
-mod executor;
+pub(crate) mod executor;
+mod multiplexer;
 mod sasl;
 mod stream;
 mod tls;
@@ -59,11 +61,34 @@ pub struct PgConnection {
     // number of ReadyForQuery messages that we are currently expecting
     pub(crate) pending_ready_for_query_count: usize,
 
+    // set while the unnamed portal is kept open to fetch its rows in batches,
+    // until the [Sync] (already counted above) is sent
+    pub(crate) sync_deferred: bool,
+
     // current transaction status
     transaction_status: TransactionStatus,
     pub(crate) transaction_depth: usize,
 
     log_settings: LogSettings,
+
+    // see `PgConnectOptions::clamp_infinite_timestamps()`
+    pub(crate) clamp_infinite_timestamps: bool,
+    // see `PgConnectOptions::exact_decimals()`
+    pub(crate) exact_decimals: bool,
+
+    // see `PgConnectOptions::max_row_size()` and `PgConnectOptions::max_result_size()`
+    pub(crate) max_row_size: Option<usize>,
+    pub(crate) max_result_size: Option<usize>,
+
+    // see `PgConnectOptions::reprepare_statements()`
+    pub(crate) reprepare_statements: bool,
+
+    // see `AnyConnection::set_type_registry()`
+    #[cfg(feature = "any")]
+    pub(crate) any_type_registry: sqlx_core::any::AnyTypeRegistry,
+
+    // the query being executed, for `Connection::running_query()`
+    pub(crate) running_query: RunningQuery,
 }
 
 impl PgConnection {
@@ -74,12 +99,20 @@ impl PgConnection {
 
     // will return when the connection is ready for another query
     pub(crate) async fn wait_until_ready(&mut self) -> Result<(), Error> {
+        // the rest of the rows of a portal that wasn't fetched to the end are discarded
+        self.write_deferred_sync();
+
         if !self.stream.write_buffer_mut().is_empty() {
             self.stream.flush().await?;
         }
 
         while self.pending_ready_for_query_count > 0 {
-            let message = self.stream.recv().await?;
+            let message = match self.stream.recv().await {
+                Ok(message) => message,
+                // a row that was too large has already been skipped
+                Err(Error::RowTooLarge { .. }) => continue,
+                Err(e) => return Err(e),
+            };
 
             if let MessageFormat::ReadyForQuery = message.format {
                 self.handle_ready_for_query(message)?;
@@ -163,6 +196,14 @@ impl Connection for PgConnection {
         })
     }
 
+    fn is_open(&mut self) -> bool {
+        self.stream.is_open()
+    }
+
+    fn is_poisoned(&self) -> bool {
+        self.stream.is_poisoned()
+    }
+
     fn begin(&mut self) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
     where
         Self: Sized,
@@ -170,10 +211,54 @@ impl Connection for PgConnection {
         Transaction::begin(self)
     }
 
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
+    fn execute_batch<'e, 'q: 'e>(
+        &'e mut self,
+        sql: &'q str,
+        arguments: Box<dyn Iterator<Item = PgArguments> + Send + 'e>,
+    ) -> BoxFuture<'e, Result<PgQueryResult, Error>> {
+        Box::pin(self.run_batch(sql, arguments))
+    }
+
     fn cached_statements_size(&self) -> usize {
         self.cache_statement.len()
     }
 
+    fn set_application_name<'c>(
+        &'c mut self,
+        name: Option<&'c str>,
+    ) -> BoxFuture<'c, Result<(), Error>> {
+        Box::pin(async move {
+            match name {
+                // `SET` does not accept bind parameters
+                Some(name) => {
+                    sqlx_core::query::query("SELECT set_config('application_name', $1, false)")
+                        .bind(name)
+                        .execute(self)
+                        .await?;
+                }
+
+                // back to the name sent when connecting, if any
+                None => {
+                    sqlx_core::query::query("RESET application_name")
+                        .execute(self)
+                        .await?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+
     fn clear_cached_statements(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             self.cache_type_oid.clear();
@@ -203,6 +288,23 @@ impl Connection for PgConnection {
         self.stream.shrink_buffers();
     }
 
+    fn server_version(&self) -> ServerVersion {
+        let statuses = &self.stream.parameter_statuses;
+
+        // CockroachDB reports the version of Postgres it is compatible with as `server_version`
+        match statuses.get("crdb_version") {
+            Some(version) => ServerVersion::parse(ServerFlavor::CockroachDb, version),
+            None => ServerVersion::parse(
+                ServerFlavor::Postgres,
+                statuses.get("server_version").map_or("", String::as_str),
+            ),
+        }
+    }
+
+    fn running_query(&self) -> Option<RunningQuery> {
+        Some(self.running_query.clone())
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         self.wait_until_ready().boxed()
@@ -0,0 +1,433 @@
+use std::cmp;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+
+use futures_core::future::BoxFuture;
+use futures_core::stream::BoxStream;
+use futures_intrusive::sync::{Mutex, MutexGuard};
+use sqlx_core::Either;
+
+use crate::connection::LogSettings;
+use crate::describe::Describe;
+use crate::error::Error;
+use crate::executor::{Execute, Executor};
+use crate::logger::QueryLogger;
+use crate::message::{self, Bind, CommandComplete, DataRow, MessageFormat};
+use crate::statement::PgStatementMetadata;
+use crate::transaction::PgTransactionManager;
+use crate::types::Oid;
+use crate::{
+    PgArguments, PgConnection, PgQueryResult, PgRow, PgStatement, PgTypeInfo, PgValueFormat,
+    Postgres,
+};
+use sqlx_core::transaction::TransactionManager;
+
+const DEFAULT_BATCH_SIZE: u32 = 128;
+
+const TAKEN: &str = "BUG: PgSessionMultiplexer used after finish()";
+
+/// **Experimental**: interleave many concurrent queries over a single connection.
+///
+/// Every query executed through `&PgSessionMultiplexer` is bound to its own named portal
+/// (a server-side cursor) and its rows are fetched in batches of [`batch_size`][Self::batch_size].
+/// The connection is only held for the duration of a single batch, so several result sets can be
+/// streamed concurrently with each getting a fair turn at the connection. This is useful for
+/// read-heavy workloads where the number of server connections is the limiting factor.
+///
+/// Named portals only live as long as the transaction that created them, so the multiplexer
+/// runs everything inside a single `READ ONLY` transaction which is committed by
+/// [`finish()`][Self::finish]. This has some consequences to be aware of:
+///
+/// * all queries see the same snapshot semantics as any other statement in a transaction;
+/// * statements that write to the database will fail;
+/// * an error in _any_ query aborts the transaction, failing all queries that follow it;
+/// * each query must be a single statement, as they are executed with the extended protocol.
+///
+/// ```rust,no_run
+/// # async fn example(conn: sqlx::PgConnection) -> sqlx::Result<()> {
+/// use futures::TryStreamExt;
+/// use sqlx::postgres::PgSessionMultiplexer;
+///
+/// let mux = PgSessionMultiplexer::new(conn).await?.batch_size(64);
+///
+/// // both queries make progress at the same time over the one connection
+/// let (users, orders) = futures::try_join!(
+///     sqlx::query("SELECT * FROM users").fetch(&mux).try_collect::<Vec<_>>(),
+///     sqlx::query("SELECT * FROM orders").fetch(&mux).try_collect::<Vec<_>>(),
+/// )?;
+///
+/// let conn = mux.finish().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct PgSessionMultiplexer {
+    // a fair mutex hands the connection to waiting queries in FIFO order,
+    // which gives us round-robin scheduling between them
+    // only `None` once `finish()` has taken the connection back
+    state: Mutex<Option<MultiplexerState>>,
+    // portals of queries whose stream or future was dropped before they were exhausted,
+    // to be closed the next time the connection is locked
+    abandoned_portals: StdMutex<Vec<Oid>>,
+    batch_size: u32,
+    log_settings: LogSettings,
+}
+
+struct MultiplexerState {
+    conn: PgConnection,
+
+    // sequence of portal IDs; portal names do not share a namespace with statements
+    next_portal_id: Oid,
+}
+
+impl PgSessionMultiplexer {
+    /// Start a read-only transaction on `conn` and wrap it for multiplexed use.
+    ///
+    /// Returns an error if `conn` is already in a transaction.
+    pub async fn new(mut conn: PgConnection) -> Result<Self, Error> {
+        if conn.transaction_depth > 0 {
+            return Err(Error::Configuration(
+                "PgSessionMultiplexer cannot be created from a connection in a transaction".into(),
+            ));
+        }
+
+        conn.execute("BEGIN READ ONLY").await?;
+        conn.transaction_depth += 1;
+
+        Ok(Self {
+            log_settings: conn.log_settings.clone(),
+            state: Mutex::new(
+                Some(MultiplexerState {
+                    conn,
+                    next_portal_id: Oid(1),
+                }),
+                true,
+            ),
+            abandoned_portals: StdMutex::new(Vec::new()),
+            batch_size: DEFAULT_BATCH_SIZE,
+        })
+    }
+
+    /// Set the maximum number of rows fetched for a query each time it holds the connection.
+    ///
+    /// Smaller batches let concurrent queries take turns more often at the cost of more
+    /// round-trips. Defaults to 128. A value of `0` is treated as `1`.
+    pub fn batch_size(mut self, batch_size: u32) -> Self {
+        self.batch_size = cmp::max(batch_size, 1);
+        self
+    }
+
+    /// Commit the transaction, closing any open portals, and return the underlying connection.
+    pub async fn finish(self) -> Result<PgConnection, Error> {
+        let mut conn = self.state.lock().await.take().expect(TAKEN).conn;
+
+        PgTransactionManager::commit(&mut conn).await?;
+
+        Ok(conn)
+    }
+
+    /// Lock the connection, queueing a `Close` for each abandoned portal, like a transaction
+    /// dropped without being committed or rolled back queues its `ROLLBACK`.
+    async fn lock(&self) -> MutexGuard<'_, Option<MultiplexerState>> {
+        let mut state = self.state.lock().await;
+        let conn = &mut state.as_mut().expect(TAKEN).conn;
+
+        let abandoned = std::mem::take(
+            &mut *self
+                .abandoned_portals
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+        );
+
+        for portal in abandoned {
+            queue_close_portal(conn, portal);
+        }
+
+        state
+    }
+
+    /// Prepare `sql` if necessary and bind `arguments` to a new named portal.
+    async fn bind_portal(
+        &self,
+        sql: &str,
+        arguments: Option<PgArguments>,
+        persistent: bool,
+        metadata: Option<Arc<PgStatementMetadata>>,
+    ) -> Result<(Oid, Arc<PgStatementMetadata>), Error> {
+        let mut state = self.lock().await;
+        let MultiplexerState {
+            conn,
+            next_portal_id,
+        } = state.as_mut().expect(TAKEN);
+
+        conn.wait_until_ready().await?;
+
+        // always use the extended protocol so the query can be suspended
+        let mut arguments = arguments.unwrap_or_default();
+
+        let (statement, metadata) = conn
+            .get_or_prepare(sql, &arguments.types, persistent, metadata)
+            .await?;
+
+        // patch holes created during encoding
+        arguments.apply_patches(conn, &metadata.parameters).await?;
+
+        conn.wait_until_ready().await?;
+
+        let portal = *next_portal_id;
+        next_portal_id.incr_one();
+
+        conn.stream.write(Bind {
+            portal: Some(portal),
+            statement,
+            formats: &[PgValueFormat::Binary],
+            num_params: arguments.types.len() as i16,
+            params: &arguments.buffer,
+            result_formats: &[PgValueFormat::Binary],
+        });
+
+        // Sync does not destroy named portals inside of an explicit transaction
+        conn.write_sync();
+
+        // flushes, receives `BindComplete` and surfaces any error from binding
+        conn.wait_until_ready().await?;
+
+        Ok((portal, metadata))
+    }
+
+    /// Fetch up to `limit` rows from `portal`.
+    ///
+    /// Returns the result of the command once the portal has been exhausted, at which point the
+    /// portal is closed.
+    async fn fetch_batch(
+        &self,
+        portal: Oid,
+        limit: u32,
+        metadata: &Arc<PgStatementMetadata>,
+        logger: &mut QueryLogger<'_>,
+    ) -> Result<(Vec<PgRow>, Option<PgQueryResult>), Error> {
+        let mut state = self.lock().await;
+        let conn = &mut state.as_mut().expect(TAKEN).conn;
+
+        conn.wait_until_ready().await?;
+
+        conn.stream.write(message::Execute {
+            portal: Some(portal),
+            limit,
+        });
+        conn.write_sync();
+        conn.stream.flush().await?;
+
+        let mut rows = Vec::new();
+        let mut done = None;
+
+        loop {
+            let message = conn.stream.recv().await?;
+
+            match message.format {
+                MessageFormat::DataRow => {
+                    logger.increment_rows_returned();
+
+                    let data: DataRow = message.decode()?;
+
+                    rows.push(PgRow {
+                        data,
+                        format: PgValueFormat::Binary,
+                        metadata: Arc::clone(metadata),
+                        clamp_infinite_timestamps: conn.clamp_infinite_timestamps,
+                        exact_decimals: conn.exact_decimals,
+                    });
+                }
+
+                // the portal has more rows; we will pick it back up on our next turn
+                MessageFormat::PortalSuspended => {}
+
+                MessageFormat::CommandComplete => {
+                    let cc: CommandComplete = message.decode()?;
+
+                    let rows_affected = cc.rows_affected();
+                    logger.increase_rows_affected(rows_affected);
+
+                    done = Some(PgQueryResult { rows_affected });
+                }
+
+                MessageFormat::EmptyQueryResponse => {
+                    done = Some(PgQueryResult::default());
+                }
+
+                MessageFormat::ReadyForQuery => {
+                    conn.handle_ready_for_query(message)?;
+                    break;
+                }
+
+                _ => {
+                    return Err(err_protocol!(
+                        "execute: unexpected message: {:?}",
+                        message.format
+                    ));
+                }
+            }
+        }
+
+        if done.is_some() {
+            queue_close_portal(conn, portal);
+        }
+
+        Ok((rows, done))
+    }
+}
+
+/// Closes a portal that was not run to completion when dropped, e.g. along with the stream of its
+/// rows.
+struct PortalGuard<'a> {
+    mux: &'a PgSessionMultiplexer,
+    portal: Option<Oid>,
+}
+
+impl PortalGuard<'_> {
+    /// The portal was exhausted and closed by [`PgSessionMultiplexer::fetch_batch()`].
+    fn closed(&mut self) {
+        self.portal = None;
+    }
+}
+
+impl Drop for PortalGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(portal) = self.portal.take() {
+            self.mux
+                .abandoned_portals
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(portal);
+        }
+    }
+}
+
+// the response is consumed by `wait_until_ready()` before the next command
+fn queue_close_portal(conn: &mut PgConnection, portal: Oid) {
+    conn.stream.write(message::Close::Portal(Some(portal)));
+    conn.write_sync();
+}
+
+impl Debug for PgSessionMultiplexer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PgSessionMultiplexer")
+            .field("batch_size", &self.batch_size)
+            .finish()
+    }
+}
+
+impl<'c> Executor<'c> for &'c PgSessionMultiplexer {
+    type Database = Postgres;
+
+    fn fetch_many<'e, 'q: 'e, E>(
+        self,
+        mut query: E,
+    ) -> BoxStream<'e, Result<Either<PgQueryResult, PgRow>, Error>>
+    where
+        'c: 'e,
+        E: 'q + Execute<'q, Self::Database>,
+    {
+        let sql = query.sql();
+        let metadata = query.statement().map(|s| Arc::clone(&s.metadata));
+        let arguments = query.take_arguments();
+        let persistent = query.persistent();
+
+        Box::pin(try_stream! {
+            let mut logger = QueryLogger::new(sql, self.log_settings.clone());
+
+            let (portal, metadata) = self
+                .bind_portal(sql, arguments, persistent, metadata)
+                .await?;
+
+            let mut guard = PortalGuard { mux: self, portal: Some(portal) };
+
+            loop {
+                let (rows, done) = self
+                    .fetch_batch(portal, self.batch_size, &metadata, &mut logger)
+                    .await?;
+
+                if done.is_some() {
+                    guard.closed();
+                }
+
+                for row in rows {
+                    r#yield!(Either::Right(row));
+                }
+
+                if let Some(done) = done {
+                    r#yield!(Either::Left(done));
+                    break;
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    fn fetch_optional<'e, 'q: 'e, E>(
+        self,
+        mut query: E,
+    ) -> BoxFuture<'e, Result<Option<PgRow>, Error>>
+    where
+        'c: 'e,
+        E: 'q + Execute<'q, Self::Database>,
+    {
+        let sql = query.sql();
+        let metadata = query.statement().map(|s| Arc::clone(&s.metadata));
+        let arguments = query.take_arguments();
+        let persistent = query.persistent();
+
+        Box::pin(async move {
+            let mut logger = QueryLogger::new(sql, self.log_settings.clone());
+
+            let (portal, metadata) = self
+                .bind_portal(sql, arguments, persistent, metadata)
+                .await?;
+
+            let mut guard = PortalGuard {
+                mux: self,
+                portal: Some(portal),
+            };
+
+            let (rows, done) = self.fetch_batch(portal, 1, &metadata, &mut logger).await?;
+
+            if done.is_some() {
+                guard.closed();
+            }
+
+            Ok(rows.into_iter().next())
+        })
+    }
+
+    fn prepare_with<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+        parameters: &'e [PgTypeInfo],
+    ) -> BoxFuture<'e, Result<PgStatement<'q>, Error>>
+    where
+        'c: 'e,
+    {
+        Box::pin(async move {
+            let mut state = self.lock().await;
+            let conn = &mut state.as_mut().expect(TAKEN).conn;
+
+            conn.prepare_with(sql, parameters).await
+        })
+    }
+
+    fn describe<'e, 'q: 'e>(
+        self,
+        sql: &'q str,
+    ) -> BoxFuture<'e, Result<Describe<Self::Database>, Error>>
+    where
+        'c: 'e,
+    {
+        Box::pin(async move {
+            let mut state = self.lock().await;
+            let conn = &mut state.as_mut().expect(TAKEN).conn;
+
+            conn.describe(sql).await
+        })
+    }
+}
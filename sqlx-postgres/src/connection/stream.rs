@@ -36,6 +36,9 @@ pub struct PgStream {
     pub(crate) parameter_statuses: BTreeMap<String, String>,
 
     pub(crate) server_version_num: Option<u32>,
+
+    // the largest `DataRow` to read; set by `PgConnection::run()` for each query
+    pub(crate) max_row_size: Option<usize>,
 }
 
 impl PgStream {
@@ -52,6 +55,7 @@ impl PgStream {
             notifications: None,
             parameter_statuses: BTreeMap::default(),
             server_version_num: None,
+            max_row_size: None,
         })
     }
 
@@ -90,7 +94,21 @@ impl PgStream {
         let format = MessageFormat::try_from_u8(header.get_u8())?;
         let size = (header.get_u32() - 4) as usize;
 
+        // the header is consumed, so the stream is out of sync if the body isn't read
+        self.inner.begin_atomic();
+
+        if format == MessageFormat::DataRow {
+            if let Some(limit) = self.max_row_size.filter(|&limit| size > limit) {
+                // the row is discarded as it arrives, rather than read into memory
+                self.inner.skip(size).await?;
+                self.inner.end_atomic();
+
+                return Err(Error::RowTooLarge { size, limit });
+            }
+        }
+
         let contents = self.inner.read(size).await?;
+        self.inner.end_atomic();
 
         Ok(Message { format, contents })
     }
@@ -126,6 +144,7 @@ impl PgStream {
                     match name.as_str() {
                         "server_version" => {
                             self.server_version_num = parse_server_version(&value);
+                            self.parameter_statuses.insert(name, value);
                         }
                         _ => {
                             self.parameter_statuses.insert(name, value);
@@ -31,6 +31,8 @@ impl Database for Postgres {
     const NAME: &'static str = "PostgreSQL";
 
     const URL_SCHEMES: &'static [&'static str] = &["postgres", "postgresql"];
+
+    const BIND_LIMIT: usize = 65535;
 }
 
 impl<'r> HasValueRef<'r> for Postgres {
@@ -16,7 +16,9 @@ mod io;
 mod listener;
 mod message;
 mod options;
+mod prepared_transaction;
 mod query_result;
+mod replication;
 mod row;
 mod statement;
 mod transaction;
@@ -38,13 +40,14 @@ pub(crate) use sqlx_core::driver_prelude::*;
 pub use advisory_lock::{PgAdvisoryLock, PgAdvisoryLockGuard, PgAdvisoryLockKey};
 pub use arguments::{PgArgumentBuffer, PgArguments};
 pub use column::PgColumn;
-pub use connection::PgConnection;
+pub use connection::{PgConnection, PgSessionMultiplexer};
 pub use copy::PgCopyIn;
 pub use database::Postgres;
 pub use error::{PgDatabaseError, PgErrorPosition};
 pub use listener::{PgListener, PgNotification};
 pub use message::PgSeverity;
 pub use options::{PgConnectOptions, PgSslMode};
+pub use prepared_transaction::{PgPreparedTransaction, PgPreparedTransactionInfo};
 pub use query_result::PgQueryResult;
 pub use row::PgRow;
 pub use statement::PgStatement;
@@ -65,6 +68,7 @@ impl<'c, T: Executor<'c, Database = Postgres>> PgExecutor<'c> for T {}
 
 impl_into_arguments_for_arguments!(PgArguments);
 impl_acquire!(Postgres, PgConnection);
+impl_middleware_connection!(Postgres);
 impl_column_index_for_row!(PgRow);
 impl_column_index_for_statement!(PgStatement);
 impl_encode_for_option!(Postgres);
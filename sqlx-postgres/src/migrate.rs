@@ -4,9 +4,11 @@ use std::time::Instant;
 
 use futures_core::future::BoxFuture;
 
+use sqlx_core::migrate::execute_timed;
 pub(crate) use sqlx_core::migrate::MigrateError;
 pub(crate) use sqlx_core::migrate::{AppliedMigration, Migration};
 pub(crate) use sqlx_core::migrate::{Migrate, MigrateDatabase};
+pub(crate) use sqlx_core::migrate::{MigrationLogEntry, MigrationLogRow};
 
 use crate::connection::{ConnectOptions, Connection};
 use crate::error::Error;
@@ -184,6 +186,13 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         &'e mut self,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
+        Box::pin(async move { Ok(self.apply_timed(migration).await?.0) })
+    }
+
+    fn apply_timed<'e: 'm, 'm>(
+        &'e mut self,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(Duration, Vec<Duration>), MigrateError>> {
         Box::pin(async move {
             let mut tx = self.begin().await?;
             let start = Instant::now();
@@ -193,7 +202,7 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
             // The `execution_time` however can only be measured for the whole transaction. This value _only_ exists for
             // data lineage and debugging reasons, so it is not super important if it is lost. So we initialize it to -1
             // and update it once the actual transaction completed.
-            let _ = tx.execute(&*migration.sql).await?;
+            let statements = execute_timed(&mut *tx, &migration.sql).await?;
 
             // language=SQL
             let _ = query(
@@ -229,7 +238,7 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
             .execute(self)
             .await?;
 
-            Ok(elapsed)
+            Ok((elapsed, statements))
         })
     }
 
@@ -237,13 +246,20 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
         &'e mut self,
         migration: &'m Migration,
     ) -> BoxFuture<'m, Result<Duration, MigrateError>> {
+        Box::pin(async move { Ok(self.revert_timed(migration).await?.0) })
+    }
+
+    fn revert_timed<'e: 'm, 'm>(
+        &'e mut self,
+        migration: &'m Migration,
+    ) -> BoxFuture<'m, Result<(Duration, Vec<Duration>), MigrateError>> {
         Box::pin(async move {
             // Use a single transaction for the actual migration script and the essential bookeeping so we never
             // execute migrations twice. See https://github.com/launchbadge/sqlx/issues/1966.
             let mut tx = self.begin().await?;
             let start = Instant::now();
 
-            let _ = tx.execute(&*migration.sql).await?;
+            let statements = execute_timed(&mut *tx, &migration.sql).await?;
 
             // language=SQL
             let _ = query(r#"DELETE FROM _sqlx_migrations WHERE version = $1"#)
@@ -255,7 +271,79 @@ CREATE TABLE IF NOT EXISTS _sqlx_migrations (
 
             let elapsed = start.elapsed();
 
-            Ok(elapsed)
+            Ok((elapsed, statements))
+        })
+    }
+
+    fn ensure_migrations_log_table(&mut self) -> BoxFuture<'_, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=SQL
+            self.execute(
+                r#"
+CREATE TABLE IF NOT EXISTS _sqlx_migrations_log (
+    id BIGSERIAL PRIMARY KEY,
+    version BIGINT NOT NULL,
+    description TEXT NOT NULL,
+    reverted BOOLEAN NOT NULL,
+    applied_at TIMESTAMPTZ NOT NULL,
+    execution_time BIGINT NOT NULL,
+    hostname TEXT,
+    os_user TEXT,
+    tool_version TEXT,
+    git_commit TEXT
+);
+                "#,
+            )
+            .await?;
+
+            Ok(())
+        })
+    }
+
+    fn log_migration<'e: 'm, 'm>(
+        &'e mut self,
+        entry: &'m MigrationLogEntry,
+    ) -> BoxFuture<'m, Result<(), MigrateError>> {
+        Box::pin(async move {
+            // language=SQL
+            let _ = query(
+                r#"
+    INSERT INTO _sqlx_migrations_log ( version, description, reverted, applied_at, execution_time, hostname, os_user, tool_version, git_commit )
+    VALUES ( $1, $2, $3, to_timestamp($4), $5, $6, $7, $8, $9 )
+                "#,
+            )
+            .bind(entry.version)
+            .bind(&entry.description)
+            .bind(entry.reverted)
+            .bind(entry.applied_at_secs())
+            .bind(entry.execution_time.as_nanos() as i64)
+            .bind(&entry.hostname)
+            .bind(&entry.os_user)
+            .bind(&entry.tool_version)
+            .bind(&entry.git_commit)
+            .execute(self)
+            .await?;
+
+            Ok(())
+        })
+    }
+
+    fn list_migrations_log(
+        &mut self,
+    ) -> BoxFuture<'_, Result<Vec<MigrationLogEntry>, MigrateError>> {
+        Box::pin(async move {
+            // language=SQL
+            let rows: Vec<MigrationLogRow> = query_as(
+                r#"
+    SELECT version, description, reverted, EXTRACT(EPOCH FROM applied_at)::BIGINT, execution_time, hostname, os_user, tool_version, git_commit
+    FROM _sqlx_migrations_log
+    ORDER BY id
+                "#,
+            )
+            .fetch_all(self)
+            .await?;
+
+            Ok(rows.into_iter().map(MigrationLogEntry::from).collect())
         })
     }
 }
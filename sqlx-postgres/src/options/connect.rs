@@ -29,4 +29,17 @@ impl ConnectOptions for PgConnectOptions {
         self.log_settings.log_slow_statements(level, duration);
         self
     }
+
+    fn log_wire_stats(mut self, level: LevelFilter) -> Self {
+        self.log_settings.log_wire_stats(level);
+        self
+    }
+
+    fn application_name(self, name: &str) -> Self {
+        PgConnectOptions::application_name(self, name)
+    }
+
+    fn get_application_name(&self) -> Option<&str> {
+        self.application_name.as_deref()
+    }
 }
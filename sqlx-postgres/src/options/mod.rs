@@ -38,9 +38,16 @@ mod ssl_mode;
 /// | `application-name` | `None` | The name will be displayed in the pg_stat_activity view and included in CSV log entries. |
 /// | `user` | result of `whoami` | PostgreSQL user name to connect as. |
 /// | `password` | `None` | Password to be used if the server demands password authentication. |
+/// | `password-file` | `None` | Path to a file containing only the password, e.g. a Docker or Kubernetes secret. A trailing newline is ignored. |
+/// | `passfile` | `None` | Path to a [password file](https://www.postgresql.org/docs/current/libpq-pgpass.html) to read the password from, instead of `PGPASSFILE` or `~/.pgpass`, if it isn't otherwise set. |
 /// | `port` | `5432` | Port number to connect to at the server host, or socket file name extension for Unix-domain connections. |
 /// | `dbname` | `None` | The database name. |
 /// | `options` | `None` | The runtime parameters to send to the server at connection start. |
+/// | `clamp-infinite-timestamps` | `false` | Decode `infinity` and `-infinity` timestamps as the latest and earliest values of the Rust type. See [`PgConnectOptions::clamp_infinite_timestamps()`]. |
+/// | `exact-decimals` | `false` | Return an error instead of rounding a `NUMERIC` decoded as `rust_decimal::Decimal`. See [`PgConnectOptions::exact_decimals()`]. |
+/// | `max-row-size` | `None` | The largest row in bytes that a query may return. See [`PgConnectOptions::max_row_size()`]. |
+/// | `max-result-size` | `None` | The most bytes of rows that a query may return. See [`PgConnectOptions::max_result_size()`]. |
+/// | `reprepare-statements` | `true` | Prepare and execute a query again if its cached statement no longer exists on the server. See [`PgConnectOptions::reprepare_statements()`]. |
 ///
 /// The URL scheme designator can be either `postgresql://` or `postgres://`.
 /// Each of the URL parts is optional.
@@ -102,6 +109,11 @@ pub struct PgConnectOptions {
     pub(crate) log_settings: LogSettings,
     pub(crate) extra_float_digits: Option<Cow<'static, str>>,
     pub(crate) options: Option<String>,
+    pub(crate) clamp_infinite_timestamps: bool,
+    pub(crate) exact_decimals: bool,
+    pub(crate) max_row_size: Option<usize>,
+    pub(crate) max_result_size: Option<usize>,
+    pub(crate) reprepare_statements: bool,
 }
 
 impl Default for PgConnectOptions {
@@ -168,6 +180,11 @@ impl PgConnectOptions {
             extra_float_digits: Some("3".into()),
             log_settings: Default::default(),
             options: var("PGOPTIONS").ok(),
+            clamp_infinite_timestamps: false,
+            exact_decimals: false,
+            max_row_size: None,
+            max_result_size: None,
+            reprepare_statements: true,
         }
     }
 
@@ -184,6 +201,22 @@ impl PgConnectOptions {
         self
     }
 
+    /// Like [`apply_pgpass()`][Self::apply_pgpass], but only reads `passfile`, as for the
+    /// `passfile` connection URL parameter.
+    pub(crate) fn apply_passfile(mut self, passfile: &Path) -> Self {
+        if self.password.is_none() {
+            self.password = pgpass::load_password_from_file(
+                passfile.to_owned(),
+                &self.host,
+                self.port,
+                &self.username,
+                self.database.as_deref(),
+            );
+        }
+
+        self
+    }
+
     /// Sets the name of the host to connect to.
     ///
     /// If a host name begins with a slash, it specifies
@@ -533,6 +566,106 @@ impl PgConnectOptions {
         self
     }
 
+    /// Sets whether the special values `infinity` and `-infinity` of `TIMESTAMP`, `TIMESTAMPTZ`
+    /// and `DATE` are decoded as the latest and earliest values of the Rust type, like
+    /// `NaiveDateTime::MAX` and `NaiveDateTime::MIN`, instead of returning an error.
+    ///
+    /// Defaults to `false`. The values stay distinguishable when decoded as
+    /// [`PgTimestamp`](crate::types::PgTimestamp), whether this is set or not.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .clamp_infinite_timestamps(true);
+    /// ```
+    pub fn clamp_infinite_timestamps(mut self, clamp: bool) -> Self {
+        self.clamp_infinite_timestamps = clamp;
+        self
+    }
+
+    /// Sets whether decoding a `NUMERIC` as `rust_decimal::Decimal` returns an error if the
+    /// value can't be represented exactly, instead of rounding it.
+    ///
+    /// `Decimal` holds at most 28 digits after the point in a 96-bit integer, so other values are
+    /// rounded half away from zero by default. Values too large for it are always an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .exact_decimals(true);
+    /// ```
+    pub fn exact_decimals(mut self, exact: bool) -> Self {
+        self.exact_decimals = exact;
+        self
+    }
+
+    /// Sets the largest row, in bytes, that a query may return.
+    ///
+    /// A larger row is skipped without reading it into memory, and the query returns
+    /// [`Error::RowTooLarge`][crate::Error::RowTooLarge], so a query that selects a huge value
+    /// can't exhaust the memory of the process. The connection stays usable.
+    ///
+    /// Defaults to `None`, for no limit. A query can opt out with `without_size_limits()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .max_row_size(16 * 1024 * 1024);
+    /// ```
+    pub fn max_row_size(mut self, size: impl Into<Option<usize>>) -> Self {
+        self.max_row_size = size.into();
+        self
+    }
+
+    /// Sets the most bytes of rows that a query may return, added up over the result.
+    ///
+    /// Once the rows exceed it, the query returns
+    /// [`Error::ResultTooLarge`][crate::Error::ResultTooLarge] and the rest of the result is
+    /// discarded. Rows already yielded by a stream are not counted as freed, so this also caps
+    /// streaming queries.
+    ///
+    /// Defaults to `None`, for no limit. A query can opt out with `without_size_limits()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .max_result_size(256 * 1024 * 1024);
+    /// ```
+    pub fn max_result_size(mut self, size: impl Into<Option<usize>>) -> Self {
+        self.max_result_size = size.into();
+        self
+    }
+
+    /// Sets whether a query whose cached prepared statement no longer exists on the server is
+    /// prepared and executed again, once, instead of returning an error.
+    ///
+    /// Prepared statements are lost when the connection fails over to another server, or when a
+    /// pooler like PgBouncer hands it a different server connection. The query is only executed
+    /// again if the server rejected its statement (SQLSTATE `26000`) before executing it, and
+    /// the connection isn't in a transaction, which the error has aborted.
+    ///
+    /// Defaults to `true`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use sqlx_core::postgres::PgConnectOptions;
+    /// let options = PgConnectOptions::new()
+    ///     .reprepare_statements(false);
+    /// ```
+    pub fn reprepare_statements(mut self, reprepare: bool) -> Self {
+        self.reprepare_statements = reprepare;
+        self
+    }
+
     /// We try using a socket if hostname starts with `/` or if socket parameter
     /// is specified.
     pub(crate) fn fetch_socket(&self) -> Option<String> {
@@ -1,8 +1,10 @@
 use crate::error::Error;
 use crate::PgConnectOptions;
+use sqlx_core::connection::read_password_file;
 use sqlx_core::percent_encoding::percent_decode_str;
 use sqlx_core::Url;
 use std::net::IpAddr;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 impl PgConnectOptions {
@@ -43,6 +45,8 @@ impl PgConnectOptions {
             options = options.database(path);
         }
 
+        let mut passfile = None;
+
         for (key, value) in url.query_pairs().into_iter() {
             match &*key {
                 "sslmode" | "ssl-mode" => {
@@ -62,6 +66,28 @@ impl PgConnectOptions {
                         options.statement_cache_capacity(value.parse().map_err(Error::config)?);
                 }
 
+                "clamp-infinite-timestamps" => {
+                    options =
+                        options.clamp_infinite_timestamps(value.parse().map_err(Error::config)?);
+                }
+
+                "exact-decimals" => {
+                    options = options.exact_decimals(value.parse().map_err(Error::config)?);
+                }
+
+                "reprepare-statements" => {
+                    options = options.reprepare_statements(value.parse().map_err(Error::config)?);
+                }
+
+                "max-row-size" => {
+                    options = options.max_row_size(value.parse::<usize>().map_err(Error::config)?);
+                }
+
+                "max-result-size" => {
+                    options =
+                        options.max_result_size(value.parse::<usize>().map_err(Error::config)?);
+                }
+
                 "host" => {
                     if value.starts_with("/") {
                         options = options.socket(&*value);
@@ -83,6 +109,12 @@ impl PgConnectOptions {
 
                 "password" => options = options.password(&*value),
 
+                "password-file" | "password_file" => {
+                    options = options.password(&read_password_file(&*value)?)
+                }
+
+                "passfile" => passfile = Some(PathBuf::from(&*value)),
+
                 "application_name" => options = options.application_name(&*value),
 
                 "options" => {
@@ -104,7 +136,10 @@ impl PgConnectOptions {
             }
         }
 
-        let options = options.apply_pgpass();
+        let options = match passfile {
+            Some(passfile) => options.apply_passfile(&passfile),
+            None => options.apply_pgpass(),
+        };
 
         Ok(options)
     }
@@ -242,3 +277,77 @@ fn it_parses_sqlx_options_correctly() {
         opts.options
     );
 }
+
+#[test]
+fn it_parses_password_files() {
+    let dir = std::env::temp_dir().join(format!("sqlx-pg-password-file-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let password_file = dir.join("db_password");
+    std::fs::write(&password_file, "s3cr:et\n").unwrap();
+
+    let url = format!(
+        "postgres://user@host/db?password-file={}",
+        password_file.display()
+    );
+    let opts = PgConnectOptions::from_str(&url).unwrap();
+    assert_eq!(Some("s3cr:et"), opts.password.as_deref());
+
+    let passfile = dir.join("pgpass");
+    std::fs::write(&passfile, "other:*:*:*:wrong\nhost:5432:db:user:right\n").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&passfile, std::fs::Permissions::from_mode(0o600)).unwrap();
+    }
+
+    let url = format!("postgres://user@host/db?passfile={}", passfile.display());
+    let opts = PgConnectOptions::from_str(&url).unwrap();
+    assert_eq!(Some("right"), opts.password.as_deref());
+
+    // a password in the URL takes precedence
+    let url = format!("postgres://user:pw@host/db?passfile={}", passfile.display());
+    let opts = PgConnectOptions::from_str(&url).unwrap();
+    assert_eq!(Some("pw"), opts.password.as_deref());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(PgConnectOptions::from_str("postgres:///?password-file=/does/not/exist").is_err());
+}
+
+#[test]
+fn it_parses_clamp_infinite_timestamps() {
+    let url = "postgres:///?clamp-infinite-timestamps=true";
+    let opts = PgConnectOptions::from_str(url).unwrap();
+
+    assert!(opts.clamp_infinite_timestamps);
+    assert!(!PgConnectOptions::new().clamp_infinite_timestamps);
+}
+
+#[test]
+fn it_parses_exact_decimals() {
+    let url = "postgres:///?exact-decimals=true";
+    let opts = PgConnectOptions::from_str(url).unwrap();
+
+    assert!(opts.exact_decimals);
+    assert!(!PgConnectOptions::new().exact_decimals);
+}
+
+#[test]
+fn it_parses_reprepare_statements() {
+    let url = "postgres:///?reprepare-statements=false";
+    let opts = PgConnectOptions::from_str(url).unwrap();
+
+    assert!(!opts.reprepare_statements);
+    assert!(PgConnectOptions::new().reprepare_statements);
+}
+
+#[test]
+fn it_parses_size_limits() {
+    let url = "postgres:///?max-row-size=1024&max-result-size=65536";
+    let opts = PgConnectOptions::from_str(url).unwrap();
+
+    assert_eq!(opts.max_row_size, Some(1024));
+    assert_eq!(opts.max_result_size, Some(65536));
+    assert_eq!(PgConnectOptions::new().max_row_size, None);
+}
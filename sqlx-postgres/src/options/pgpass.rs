@@ -34,7 +34,7 @@ pub fn load_password(
 }
 
 /// try to extract a password from a pgpass file
-fn load_password_from_file(
+pub fn load_password_from_file(
     path: PathBuf,
     host: &str,
     port: u16,
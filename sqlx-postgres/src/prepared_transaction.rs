@@ -0,0 +1,180 @@
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::executor::Executor;
+use crate::transaction::Transaction;
+use crate::{PgConnection, Postgres};
+
+/// A transaction prepared for a two-phase commit with [`PREPARE TRANSACTION`].
+///
+/// Once prepared, a transaction is no longer associated with the connection that began it, and
+/// survives that connection closing or even the server restarting. It can later be committed or
+/// rolled back from any connection to the same database using its global identifier (GID).
+///
+/// This is the building block for coordinating a transaction across several databases: prepare
+/// the transaction on each, and only commit them all once every one of them was prepared
+/// successfully, or roll them all back otherwise. If the coordinator crashes in between,
+/// the transactions left behind can be found with [`PgPreparedTransaction::list()`] and
+/// recreated with [`PgPreparedTransaction::from_gid()`] to finish them.
+///
+/// Note that the `max_prepared_transactions` server setting defaults to `0`, which disables
+/// prepared transactions, and that prepared transactions hold their locks until they're
+/// finished.
+///
+/// ```rust,no_run
+/// # async fn example(pool: &sqlx::PgPool) -> sqlx::Result<()> {
+/// use sqlx::postgres::PgPreparedTransaction;
+///
+/// let mut tx = pool.begin().await?;
+///
+/// sqlx::query("UPDATE accounts SET balance = balance - 100 WHERE id = 1")
+///     .execute(&mut *tx)
+///     .await?;
+///
+/// let prepared = PgPreparedTransaction::prepare(tx, "transfer-42").await?;
+///
+/// // ... prepare the other participants ...
+///
+/// prepared.commit(pool.acquire().await?).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`PREPARE TRANSACTION`]: https://www.postgresql.org/docs/current/sql-prepare-transaction.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgPreparedTransaction {
+    gid: String,
+}
+
+/// A prepared transaction listed by [`PgPreparedTransaction::list()`].
+///
+/// See [`pg_prepared_xacts`] for details.
+///
+/// [`pg_prepared_xacts`]: https://www.postgresql.org/docs/current/view-pg-prepared-xacts.html
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct PgPreparedTransactionInfo {
+    /// The global identifier given when the transaction was prepared.
+    pub gid: String,
+    /// The numeric transaction identifier.
+    pub xid: i64,
+    /// The name of the user that prepared the transaction.
+    pub owner: String,
+    /// How long ago the transaction was prepared.
+    pub age: Duration,
+}
+
+impl PgPreparedTransactionInfo {
+    /// The prepared transaction, for committing or rolling it back.
+    pub fn transaction(&self) -> PgPreparedTransaction {
+        PgPreparedTransaction::from_gid(self.gid.clone())
+    }
+}
+
+impl PgPreparedTransaction {
+    /// Prepares the transaction for a two-phase commit with `PREPARE TRANSACTION`.
+    ///
+    /// The global identifier (GID) must be unique among the prepared transactions on the server,
+    /// and shorter than 200 bytes. The connection is released from the transaction, and can be
+    /// used (or returned to the pool) as usual.
+    ///
+    /// Returns an error for a nested transaction (savepoint). If preparing fails, the transaction
+    /// is rolled back.
+    pub async fn prepare(
+        mut tx: Transaction<'_, Postgres>,
+        gid: impl Into<String>,
+    ) -> Result<Self> {
+        let gid = gid.into();
+
+        if tx.transaction_depth != 1 {
+            return Err(Error::Configuration(
+                "a nested transaction (savepoint) cannot be prepared".into(),
+            ));
+        }
+
+        tx.execute(&*format!("PREPARE TRANSACTION {}", quote_literal(&gid)))
+            .await?;
+
+        // The session is no longer in a transaction, whether or not it was prepared.
+        tx.transaction_depth = 0;
+        tx.forget();
+
+        Ok(Self { gid })
+    }
+
+    /// Refers to an already prepared transaction by its global identifier, e.g. to finish it
+    /// after recovering from a crash.
+    pub fn from_gid(gid: impl Into<String>) -> Self {
+        Self { gid: gid.into() }
+    }
+
+    /// The global identifier (GID) of this transaction.
+    pub fn gid(&self) -> &str {
+        &self.gid
+    }
+
+    /// Commits this transaction with `COMMIT PREPARED`.
+    ///
+    /// A connection-like type is required to execute the call. Allowed types include
+    /// `PgConnection` and `PoolConnection<Postgres>`, as well as mutable references to these.
+    /// It must be connected to the same database as the transaction, and not be in a transaction.
+    pub async fn commit<C: AsMut<PgConnection>>(self, mut conn: C) -> Result<()> {
+        conn.as_mut()
+            .execute(&*format!("COMMIT PREPARED {}", quote_literal(&self.gid)))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Rolls back this transaction with `ROLLBACK PREPARED`.
+    ///
+    /// The same connection requirements as [`Self::commit()`] apply.
+    pub async fn rollback<C: AsMut<PgConnection>>(self, mut conn: C) -> Result<()> {
+        conn.as_mut()
+            .execute(&*format!("ROLLBACK PREPARED {}", quote_literal(&self.gid)))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Lists the prepared transactions in the current database from `pg_prepared_xacts`,
+    /// oldest first.
+    ///
+    /// Only transactions in the current database can be committed or rolled back from this
+    /// connection, so those in other databases aren't listed.
+    pub async fn list<C: AsMut<PgConnection>>(
+        mut conn: C,
+    ) -> Result<Vec<PgPreparedTransactionInfo>> {
+        let rows: Vec<(String, i64, String, f64)> = crate::query_as::query_as(
+            "SELECT gid, transaction::text::int8, owner::text, \
+                    extract(epoch FROM now() - prepared)::float8 \
+             FROM pg_prepared_xacts \
+             WHERE database = current_database() \
+             ORDER BY prepared",
+        )
+        .fetch_all(conn.as_mut())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(gid, xid, owner, age)| PgPreparedTransactionInfo {
+                gid,
+                xid,
+                owner,
+                age: Duration::try_from_secs_f64(age).unwrap_or_default(),
+            })
+            .collect())
+    }
+}
+
+/// Quotes `value` as a string literal, since `PREPARE TRANSACTION` and friends don't accept bind
+/// parameters.
+fn quote_literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[test]
+fn test_quote_literal() {
+    assert_eq!(quote_literal("transfer-42"), "'transfer-42'");
+    assert_eq!(quote_literal("it's"), "'it''s'");
+}
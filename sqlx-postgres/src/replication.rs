@@ -0,0 +1,30 @@
+use futures_core::future::BoxFuture;
+use sqlx_core::pool::ReplicationPosition;
+
+use crate::error::Error;
+use crate::query_scalar::query_scalar;
+use crate::PgConnection;
+
+/// Replication positions for [`ReplicaSession`][sqlx_core::pool::ReplicaSession], using the
+/// write-ahead log location (LSN).
+impl ReplicationPosition for PgConnection {
+    fn replication_position(&mut self) -> BoxFuture<'_, Result<String, Error>> {
+        Box::pin(async move {
+            query_scalar("SELECT pg_current_wal_lsn()::text")
+                .fetch_one(self)
+                .await
+        })
+    }
+
+    fn has_replayed<'e>(&'e mut self, position: &'e str) -> BoxFuture<'e, Result<bool, Error>> {
+        Box::pin(async move {
+            // `pg_last_wal_replay_lsn()` is `NULL` unless the server is a standby
+            query_scalar(
+                "SELECT COALESCE(pg_last_wal_replay_lsn(), pg_current_wal_lsn()) >= $1::pg_lsn",
+            )
+            .bind(position)
+            .fetch_one(self)
+            .await
+        })
+    }
+}
@@ -13,6 +13,8 @@ pub struct PgRow {
     pub(crate) data: DataRow,
     pub(crate) format: PgValueFormat,
     pub(crate) metadata: Arc<PgStatementMetadata>,
+    pub(crate) clamp_infinite_timestamps: bool,
+    pub(crate) exact_decimals: bool,
 }
 
 impl Row for PgRow {
@@ -35,6 +37,8 @@ impl Row for PgRow {
             row: Some(&self.data.storage),
             type_info: column.type_info.clone(),
             value,
+            clamp_infinite_timestamps: self.clamp_infinite_timestamps,
+            exact_decimals: self.exact_decimals,
         })
     }
 }
@@ -24,6 +24,25 @@ impl TransactionManager for PgTransactionManager {
         })
     }
 
+    fn begin_with(
+        conn: &mut PgConnection,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let Some(modes) = options.to_sql() else {
+                return Self::begin(conn).await;
+            };
+
+            options.check_not_nested(conn.transaction_depth)?;
+
+            conn.execute(&*format!("BEGIN {modes}")).await?;
+
+            conn.transaction_depth += 1;
+
+            Ok(())
+        })
+    }
+
     fn commit(conn: &mut PgConnection) -> BoxFuture<'_, Result<(), Error>> {
         Box::pin(async move {
             if conn.transaction_depth > 0 {
@@ -38,6 +38,8 @@ pub enum PgType {
     Oid,
     Json,
     JsonArray,
+    Xml,
+    XmlArray,
     Point,
     Lseg,
     Path,
@@ -268,6 +270,8 @@ impl PgType {
             26 => PgType::Oid,
             114 => PgType::Json,
             199 => PgType::JsonArray,
+            142 => PgType::Xml,
+            143 => PgType::XmlArray,
             600 => PgType::Point,
             601 => PgType::Lseg,
             602 => PgType::Path,
@@ -376,6 +380,8 @@ impl PgType {
             PgType::Oid => Oid(26),
             PgType::Json => Oid(114),
             PgType::JsonArray => Oid(199),
+            PgType::Xml => Oid(142),
+            PgType::XmlArray => Oid(143),
             PgType::Point => Oid(600),
             PgType::Lseg => Oid(601),
             PgType::Path => Oid(602),
@@ -479,6 +485,8 @@ impl PgType {
             PgType::Oid => "OID",
             PgType::Json => "JSON",
             PgType::JsonArray => "JSON[]",
+            PgType::Xml => "XML",
+            PgType::XmlArray => "XML[]",
             PgType::Point => "POINT",
             PgType::Lseg => "LSEG",
             PgType::Path => "PATH",
@@ -579,6 +587,8 @@ impl PgType {
             PgType::Oid => "oid",
             PgType::Json => "json",
             PgType::JsonArray => "_json",
+            PgType::Xml => "xml",
+            PgType::XmlArray => "_xml",
             PgType::Point => "point",
             PgType::Lseg => "lseg",
             PgType::Path => "path",
@@ -679,6 +689,8 @@ impl PgType {
             PgType::Oid => &PgTypeKind::Simple,
             PgType::Json => &PgTypeKind::Simple,
             PgType::JsonArray => &PgTypeKind::Array(PgTypeInfo(PgType::Json)),
+            PgType::Xml => &PgTypeKind::Simple,
+            PgType::XmlArray => &PgTypeKind::Array(PgTypeInfo(PgType::Xml)),
             PgType::Point => &PgTypeKind::Simple,
             PgType::Lseg => &PgTypeKind::Simple,
             PgType::Path => &PgTypeKind::Simple,
@@ -800,6 +812,8 @@ impl PgType {
             PgType::OidArray => Some(Cow::Owned(PgTypeInfo(PgType::Oid))),
             PgType::Json => None,
             PgType::JsonArray => Some(Cow::Owned(PgTypeInfo(PgType::Json))),
+            PgType::Xml => None,
+            PgType::XmlArray => Some(Cow::Owned(PgTypeInfo(PgType::Xml))),
             PgType::Point => None,
             PgType::PointArray => Some(Cow::Owned(PgTypeInfo(PgType::Point))),
             PgType::Lseg => None,
@@ -930,6 +944,10 @@ impl PgTypeInfo {
     pub(crate) const RECORD: Self = Self(PgType::Record);
     pub(crate) const RECORD_ARRAY: Self = Self(PgType::RecordArray);
 
+    // xml
+    pub(crate) const XML: Self = Self(PgType::Xml);
+    pub(crate) const XML_ARRAY: Self = Self(PgType::XmlArray);
+
     //
     // JSON types
     // https://www.postgresql.org/docs/current/datatype-json.html
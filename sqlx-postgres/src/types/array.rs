@@ -232,6 +232,8 @@ where
                         &mut buf,
                         format,
                         element_type_info.clone(),
+                        value.clamp_infinite_timestamps,
+                        value.exact_decimals,
                     ))?)
                 }
 
@@ -241,6 +243,8 @@ where
             PgValueFormat::Text => {
                 // no type is provided from the database for the element
                 let element_type_info = T::type_info();
+                let clamp_infinite_timestamps = value.clamp_infinite_timestamps;
+                let exact_decimals = value.exact_decimals;
 
                 let s = value.as_str()?;
 
@@ -312,6 +316,8 @@ where
                         row: None,
                         type_info: element_type_info.clone(),
                         format,
+                        clamp_infinite_timestamps,
+                        exact_decimals,
                     })?);
 
                     value.clear();
@@ -3,6 +3,7 @@ use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
 use crate::types::Type;
 use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+use sqlx_core::io::text::{decode_bytea, decode_escape, decode_hex_to_slice};
 
 impl PgHasArrayType for u8 {
     fn array_type_info() -> PgTypeInfo {
@@ -77,20 +78,11 @@ impl<'r> Decode<'r, Postgres> for &'r [u8] {
     }
 }
 
-fn text_hex_decode_input(value: PgValueRef<'_>) -> Result<&[u8], BoxDynError> {
-    // BYTEA is formatted as \x followed by hex characters
-    value
-        .as_bytes()?
-        .strip_prefix(b"\\x")
-        .ok_or("text does not start with \\x")
-        .map_err(Into::into)
-}
-
 impl Decode<'_, Postgres> for Box<[u8]> {
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(match value.format() {
             PgValueFormat::Binary => Box::from(value.as_bytes()?),
-            PgValueFormat::Text => Box::from(hex::decode(text_hex_decode_input(value)?)?),
+            PgValueFormat::Text => decode_bytea(value.as_bytes()?)?.into_boxed_slice(),
         })
     }
 }
@@ -99,7 +91,7 @@ impl Decode<'_, Postgres> for Vec<u8> {
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(match value.format() {
             PgValueFormat::Binary => value.as_bytes()?.to_owned(),
-            PgValueFormat::Text => hex::decode(text_hex_decode_input(value)?)?,
+            PgValueFormat::Text => decode_bytea(value.as_bytes()?)?,
         })
     }
 }
@@ -111,7 +103,10 @@ impl<const N: usize> Decode<'_, Postgres> for [u8; N] {
             PgValueFormat::Binary => {
                 bytes = value.as_bytes()?.try_into()?;
             }
-            PgValueFormat::Text => hex::decode_to_slice(text_hex_decode_input(value)?, &mut bytes)?,
+            PgValueFormat::Text => match value.as_bytes()?.strip_prefix(b"\\x") {
+                Some(hex) => decode_hex_to_slice(hex, &mut bytes)?,
+                None => bytes = decode_escape(value.as_bytes()?)?.as_slice().try_into()?,
+            },
         };
         Ok(bytes)
     }
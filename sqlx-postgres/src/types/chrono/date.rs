@@ -1,6 +1,7 @@
 use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
+use crate::types::timestamp::clamp_infinity;
 use crate::types::Type;
 use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use chrono::{Duration, NaiveDate};
@@ -32,6 +33,10 @@ impl Encode<'_, Postgres> for NaiveDate {
 
 impl<'r> Decode<'r, Postgres> for NaiveDate {
     fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        if let Some(clamped) = clamp_infinity(&value, NaiveDate::MIN, NaiveDate::MAX)? {
+            return Ok(clamped);
+        }
+
         Ok(match value.format() {
             PgValueFormat::Binary => {
                 // DATE is encoded as the days since epoch
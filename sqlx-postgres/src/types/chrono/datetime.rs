@@ -1,6 +1,7 @@
 use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
+use crate::types::timestamp::clamp_infinity;
 use crate::types::Type;
 use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use chrono::{
@@ -50,6 +51,10 @@ impl Encode<'_, Postgres> for NaiveDateTime {
 
 impl<'r> Decode<'r, Postgres> for NaiveDateTime {
     fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        if let Some(clamped) = clamp_infinity(&value, NaiveDateTime::MIN, NaiveDateTime::MAX)? {
+            return Ok(clamped);
+        }
+
         Ok(match value.format() {
             PgValueFormat::Binary => {
                 // TIMESTAMP is encoded as the microseconds since the epoch
@@ -0,0 +1,113 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::Deref;
+use std::str::FromStr;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueRef, Postgres};
+
+/// A case-insensitive string (`citext`) in Postgres.
+///
+/// See https://www.postgresql.org/docs/current/citext.html
+///
+/// `citext` values compare case-insensitively in Postgres, as if `lower()` were applied to both
+/// sides, so `'Alice'::citext = 'alice'::citext`. That only applies when both sides of the
+/// comparison are `citext`: a `String` is bound as `TEXT`, which makes Postgres compare
+/// case-sensitively. Bind a `PgCiText` to compare with a `citext` column:
+///
+/// ```rust,ignore
+/// use sqlx::postgres::types::PgCiText;
+///
+/// // matches `alice@example.com` too
+/// let user: User = sqlx::query_as("SELECT * FROM users WHERE email = $1")
+///     .bind(PgCiText::from("Alice@Example.com"))
+///     .fetch_one(&pool)
+///     .await?;
+/// ```
+///
+/// In Rust, `PgCiText` is just a string: it keeps the case it was created or decoded with, and
+/// its `PartialEq` is case-sensitive. `citext` values can also be decoded as `String` or `&str`.
+///
+/// ### Note: Extension Required
+/// The `citext` extension is not enabled by default in Postgres. You will need to do so explicitly:
+///
+/// ```ignore
+/// CREATE EXTENSION IF NOT EXISTS citext;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PgCiText(pub String);
+
+impl Deref for PgCiText {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<String> for PgCiText {
+    fn from(s: String) -> Self {
+        PgCiText(s)
+    }
+}
+
+impl From<&str> for PgCiText {
+    fn from(s: &str) -> Self {
+        PgCiText(s.to_owned())
+    }
+}
+
+impl From<PgCiText> for String {
+    fn from(citext: PgCiText) -> Self {
+        citext.0
+    }
+}
+
+impl FromStr for PgCiText {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(PgCiText::from(s))
+    }
+}
+
+impl Display for PgCiText {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Type<Postgres> for PgCiText {
+    fn type_info() -> PgTypeInfo {
+        // Since `citext` is enabled by an extension, it does not have a stable OID.
+        PgTypeInfo::with_name("citext")
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl PgHasArrayType for PgCiText {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_citext")
+    }
+
+    fn array_compatible(ty: &PgTypeInfo) -> bool {
+        <&str as PgHasArrayType>::array_compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for PgCiText {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <&str as Encode<Postgres>>::encode(&**self, buf)
+    }
+}
+
+impl Decode<'_, Postgres> for PgCiText {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        Ok(PgCiText(value.as_str()?.to_owned()))
+    }
+}
@@ -1,4 +1,5 @@
 use byteorder::{BigEndian, ByteOrder};
+use sqlx_core::io::text::parse_float;
 
 use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
@@ -30,7 +31,7 @@ impl Decode<'_, Postgres> for f32 {
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(match value.format() {
             PgValueFormat::Binary => BigEndian::read_f32(value.as_bytes()?),
-            PgValueFormat::Text => value.as_str()?.parse()?,
+            PgValueFormat::Text => parse_float(value.as_bytes()?)?,
         })
     }
 }
@@ -59,7 +60,7 @@ impl Decode<'_, Postgres> for f64 {
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(match value.format() {
             PgValueFormat::Binary => BigEndian::read_f64(value.as_bytes()?),
-            PgValueFormat::Text => value.as_str()?.parse()?,
+            PgValueFormat::Text => parse_float(value.as_bytes()?)?,
         })
     }
 }
@@ -0,0 +1,276 @@
+use byteorder::{BigEndian, ByteOrder};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::{Deref, DerefMut};
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+
+/// A set of key/value pairs (`hstore`) in Postgres, where the values may be `NULL`.
+///
+/// See https://www.postgresql.org/docs/current/hstore.html
+///
+/// The pairs are kept sorted by key. Convert from and to a `HashMap<String, Option<String>>`
+/// with `From`:
+///
+/// ```rust
+/// # use std::collections::HashMap;
+/// use sqlx::postgres::types::PgHstore;
+///
+/// let mut hstore = PgHstore::default();
+/// hstore.insert("color".into(), Some("red".into()));
+/// hstore.insert("size".into(), None);
+///
+/// let map: HashMap<String, Option<String>> = hstore.into();
+/// assert_eq!(map["color"].as_deref(), Some("red"));
+/// ```
+///
+/// ### Note: Extension Required
+/// The `hstore` extension is not enabled by default in Postgres. You will need to do so explicitly:
+///
+/// ```ignore
+/// CREATE EXTENSION IF NOT EXISTS hstore;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PgHstore(pub BTreeMap<String, Option<String>>);
+
+impl Deref for PgHstore {
+    type Target = BTreeMap<String, Option<String>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for PgHstore {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl FromIterator<(String, Option<String>)> for PgHstore {
+    fn from_iter<I: IntoIterator<Item = (String, Option<String>)>>(iter: I) -> Self {
+        PgHstore(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for PgHstore {
+    type Item = (String, Option<String>);
+    type IntoIter = std::collections::btree_map::IntoIter<String, Option<String>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl From<BTreeMap<String, Option<String>>> for PgHstore {
+    fn from(map: BTreeMap<String, Option<String>>) -> Self {
+        PgHstore(map)
+    }
+}
+
+impl From<HashMap<String, Option<String>>> for PgHstore {
+    fn from(map: HashMap<String, Option<String>>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl From<PgHstore> for HashMap<String, Option<String>> {
+    fn from(hstore: PgHstore) -> Self {
+        hstore.0.into_iter().collect()
+    }
+}
+
+impl From<PgHstore> for BTreeMap<String, Option<String>> {
+    fn from(hstore: PgHstore) -> Self {
+        hstore.0
+    }
+}
+
+impl Type<Postgres> for PgHstore {
+    fn type_info() -> PgTypeInfo {
+        // Since `hstore` is enabled by an extension, it does not have a stable OID.
+        PgTypeInfo::with_name("hstore")
+    }
+}
+
+impl PgHasArrayType for PgHstore {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_hstore")
+    }
+}
+
+impl Encode<'_, Postgres> for PgHstore {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        // https://github.com/postgres/postgres/blob/REL_15_0/contrib/hstore/hstore_io.c#L1226
+        buf.extend(&(self.0.len() as i32).to_be_bytes());
+
+        for (key, value) in &self.0 {
+            buf.extend(&(key.len() as i32).to_be_bytes());
+            buf.extend(key.as_bytes());
+
+            match value {
+                Some(value) => {
+                    buf.extend(&(value.len() as i32).to_be_bytes());
+                    buf.extend(value.as_bytes());
+                }
+                None => buf.extend(&(-1_i32).to_be_bytes()),
+            }
+        }
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgHstore {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => decode_binary(value.as_bytes()?),
+            PgValueFormat::Text => decode_text(value.as_str()?),
+        }
+    }
+}
+
+fn decode_binary(mut buf: &[u8]) -> Result<PgHstore, BoxDynError> {
+    fn read_i32(buf: &mut &[u8]) -> Result<i32, BoxDynError> {
+        if buf.len() < 4 {
+            return Err("unexpected end of hstore data".into());
+        }
+
+        let value = BigEndian::read_i32(buf);
+        *buf = &buf[4..];
+
+        Ok(value)
+    }
+
+    fn read_string(buf: &mut &[u8], len: i32) -> Result<String, BoxDynError> {
+        let len = usize::try_from(len)?;
+
+        if buf.len() < len {
+            return Err("unexpected end of hstore data".into());
+        }
+
+        let (bytes, rest) = buf.split_at(len);
+        *buf = rest;
+
+        Ok(std::str::from_utf8(bytes)?.to_owned())
+    }
+
+    let count = read_i32(&mut buf)?;
+    let mut hstore = PgHstore::default();
+
+    for _ in 0..count {
+        let len = read_i32(&mut buf)?;
+        let key = read_string(&mut buf, len)?;
+
+        let value = match read_i32(&mut buf)? {
+            -1 => None,
+            len => Some(read_string(&mut buf, len)?),
+        };
+
+        hstore.insert(key, value);
+    }
+
+    Ok(hstore)
+}
+
+/// Parses the text format, e.g. `"a"=>"1", "b"=>NULL`.
+fn decode_text(s: &str) -> Result<PgHstore, BoxDynError> {
+    let mut chars = s.chars().peekable();
+    let mut hstore = PgHstore::default();
+
+    fn skip_whitespace(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    // returns the string and whether it was quoted, since only an unquoted `NULL` is `NULL`
+    fn parse_string(
+        chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    ) -> Result<(String, bool), BoxDynError> {
+        let mut string = String::new();
+
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next().ok_or("unterminated string in hstore")? {
+                    '"' => return Ok((string, true)),
+                    '\\' => string.push(chars.next().ok_or("unterminated string in hstore")?),
+                    c => string.push(c),
+                }
+            }
+        }
+
+        while let Some(c) = chars.next_if(|&c| !c.is_whitespace() && c != '=' && c != ',') {
+            string.push(c);
+        }
+
+        if string.is_empty() {
+            return Err("expected a string in hstore".into());
+        }
+
+        Ok((string, false))
+    }
+
+    loop {
+        skip_whitespace(&mut chars);
+
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let (key, _) = parse_string(&mut chars)?;
+
+        skip_whitespace(&mut chars);
+        if chars.next() != Some('=') || chars.next() != Some('>') {
+            return Err("expected `=>` in hstore".into());
+        }
+        skip_whitespace(&mut chars);
+
+        let value = match parse_string(&mut chars)? {
+            (value, false) if value.eq_ignore_ascii_case("NULL") => None,
+            (value, _) => Some(value),
+        };
+
+        hstore.insert(key, value);
+
+        skip_whitespace(&mut chars);
+        match chars.next() {
+            Some(',') => continue,
+            None => break,
+            Some(c) => return Err(format!("unexpected character {c:?} in hstore").into()),
+        }
+    }
+
+    Ok(hstore)
+}
+
+#[test]
+fn test_decode_text() {
+    let hstore = decode_text(r#""a"=>"1", "b"=>NULL, "c \"d\""=>"e\\f", "g"=>"NULL""#).unwrap();
+
+    assert_eq!(hstore.len(), 4);
+    assert_eq!(hstore["a"].as_deref(), Some("1"));
+    assert_eq!(hstore["b"], None);
+    assert_eq!(hstore["c \"d\""].as_deref(), Some("e\\f"));
+    assert_eq!(hstore["g"].as_deref(), Some("NULL"));
+
+    assert_eq!(decode_text("").unwrap(), PgHstore::default());
+    assert_eq!(decode_text("a=>1").unwrap()["a"].as_deref(), Some("1"));
+    assert!(decode_text(r#""a"=>"#).is_err());
+}
+
+#[test]
+fn test_binary_round_trip() {
+    let hstore: PgHstore = [
+        ("a".to_owned(), Some("1".to_owned())),
+        ("b".to_owned(), None),
+    ]
+    .into_iter()
+    .collect();
+
+    let mut buf = PgArgumentBuffer::default();
+    let _ = hstore.encode_by_ref(&mut buf);
+
+    assert_eq!(decode_binary(&buf).unwrap(), hstore);
+}
@@ -1,4 +1,5 @@
 use byteorder::{BigEndian, ByteOrder};
+use sqlx_core::io::text::parse_int;
 
 use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
@@ -57,7 +58,7 @@ impl Decode<'_, Postgres> for i16 {
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(match value.format() {
             PgValueFormat::Binary => BigEndian::read_i16(value.as_bytes()?),
-            PgValueFormat::Text => value.as_str()?.parse()?,
+            PgValueFormat::Text => parse_int(value.as_bytes()?)?,
         })
     }
 }
@@ -86,7 +87,7 @@ impl Decode<'_, Postgres> for i32 {
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(match value.format() {
             PgValueFormat::Binary => BigEndian::read_i32(value.as_bytes()?),
-            PgValueFormat::Text => value.as_str()?.parse()?,
+            PgValueFormat::Text => parse_int(value.as_bytes()?)?,
         })
     }
 }
@@ -115,7 +116,7 @@ impl Decode<'_, Postgres> for i64 {
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
         Ok(match value.format() {
             PgValueFormat::Binary => BigEndian::read_i64(value.as_bytes()?),
-            PgValueFormat::Text => value.as_str()?.parse()?,
+            PgValueFormat::Text => parse_int(value.as_bytes()?)?,
         })
     }
 }
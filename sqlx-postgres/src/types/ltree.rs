@@ -6,6 +6,7 @@ use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValue
 use std::fmt::{self, Display, Formatter};
 use std::io::Write;
 use std::ops::Deref;
+use std::slice::SliceIndex;
 use std::str::FromStr;
 
 /// Represents ltree specific errors
@@ -122,6 +123,53 @@ impl PgLTree {
     pub fn pop(&mut self) -> Option<PgLTreeLabel> {
         self.labels.pop()
     }
+
+    /// the path without its last label, or `None` if it's empty
+    pub fn parent(&self) -> Option<Self> {
+        let (_, parent) = self.labels.split_last()?;
+        Some(Self::from(parent.to_vec()))
+    }
+
+    /// the labels in `range` as a new path, like `subpath()` in Postgres, or `None` if `range`
+    /// is out of bounds
+    pub fn subpath<R>(&self, range: R) -> Option<Self>
+    where
+        R: SliceIndex<[PgLTreeLabel], Output = [PgLTreeLabel]>,
+    {
+        self.labels
+            .get(range)
+            .map(|labels| Self::from(labels.to_vec()))
+    }
+
+    /// this path followed by the labels of `other`, like `||` in Postgres
+    pub fn concat(&self, other: &PgLTree) -> Self {
+        Self::from([&self.labels[..], &other.labels[..]].concat())
+    }
+
+    /// whether this path is an ancestor of `other` or equal to it, like `@>` in Postgres
+    pub fn is_ancestor_of(&self, other: &PgLTree) -> bool {
+        other.labels.starts_with(&self.labels)
+    }
+
+    /// whether this path is a descendant of `other` or equal to it, like `<@` in Postgres
+    pub fn is_descendant_of(&self, other: &PgLTree) -> bool {
+        other.is_ancestor_of(self)
+    }
+
+    /// the longest common ancestor of both paths, like `lca()` in Postgres, which is empty if
+    /// their first labels differ
+    ///
+    /// Unlike `lca()`, the common ancestor of a path and itself is the path itself.
+    pub fn common_ancestor(&self, other: &PgLTree) -> Self {
+        let len = self
+            .labels
+            .iter()
+            .zip(&other.labels)
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        Self::from(self.labels[..len].to_vec())
+    }
 }
 
 impl IntoIterator for PgLTree {
@@ -205,3 +253,32 @@ impl<'r> Decode<'r, Postgres> for PgLTree {
         }
     }
 }
+
+#[test]
+fn test_path_helpers() {
+    let path = PgLTree::from_str("Top.Science.Astronomy").unwrap();
+    let ltree = |s: &str| PgLTree::from_str(s).unwrap();
+
+    assert_eq!(path.parent(), Some(ltree("Top.Science")));
+    assert_eq!(PgLTree::new().parent(), None);
+
+    assert_eq!(path.subpath(1..), Some(ltree("Science.Astronomy")));
+    assert_eq!(path.subpath(..1), Some(ltree("Top")));
+    assert_eq!(path.subpath(2..4), None);
+
+    assert_eq!(
+        ltree("Top.Science").concat(&ltree("Astronomy")),
+        path.clone()
+    );
+
+    assert!(ltree("Top.Science").is_ancestor_of(&path));
+    assert!(path.is_ancestor_of(&path));
+    assert!(!ltree("Top.Sci").is_ancestor_of(&path));
+    assert!(path.is_descendant_of(&ltree("Top")));
+
+    assert_eq!(
+        path.common_ancestor(&ltree("Top.Science.Biology")),
+        ltree("Top.Science")
+    );
+    assert_eq!(path.common_ancestor(&ltree("Hobbies")), PgLTree::new());
+}
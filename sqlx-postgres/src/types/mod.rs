@@ -11,7 +11,7 @@
 //! | `i64`                                 | BIGINT, BIGSERIAL, INT8                              |
 //! | `f32`                                 | REAL, FLOAT4                                         |
 //! | `f64`                                 | DOUBLE PRECISION, FLOAT8                             |
-//! | `&str`, [`String`]                    | VARCHAR, CHAR(N), TEXT, NAME                         |
+//! | `&str`, [`String`]                    | VARCHAR, CHAR(N), TEXT, NAME, CITEXT, XML            |
 //! | `&[u8]`, `Vec<u8>`                    | BYTEA                                                |
 //! | `()`                                  | VOID                                                 |
 //! | [`PgInterval`]                        | INTERVAL                                             |
@@ -19,6 +19,11 @@
 //! | [`PgMoney`]                           | MONEY                                                |
 //! | [`PgLTree`]                           | LTREE                                                |
 //! | [`PgLQuery`]                          | LQUERY                                               |
+//! | [`PgHstore`]                          | HSTORE                                               |
+//! | [`PgCiText`]                          | CITEXT                                               |
+//! | [`PgXml`]                             | XML                                                  |
+//! | [`PgVector`]                          | VECTOR                                               |
+//! | [`PgTimestamp<T>`](PgTimestamp)       | TIMESTAMP, TIMESTAMPTZ, DATE, with `infinity`        |
 //!
 //! ### [`bigdecimal`](https://crates.io/crates/bigdecimal)
 //! Requires the `bigdecimal` Cargo feature flag.
@@ -59,6 +64,10 @@
 //! | `time::Time`                          | TIME                                                 |
 //! | [`PgTimeTz`]                          | TIMETZ                                               |
 //!
+//! Decoding `infinity` or `-infinity` as one of the `chrono` or `time` types is an error, unless
+//! [`PgConnectOptions::clamp_infinite_timestamps()`][crate::PgConnectOptions::clamp_infinite_timestamps]
+//! is set. Wrap the type in [`PgTimestamp`] to tell them apart from other values.
+//!
 //! ### [`uuid`](https://crates.io/crates/uuid)
 //!
 //! Requires the `uuid` Cargo feature flag.
@@ -175,7 +184,9 @@ pub(crate) use sqlx_core::types::{Json, Type};
 mod array;
 mod bool;
 mod bytes;
+mod citext;
 mod float;
+mod hstore;
 mod int;
 mod interval;
 mod lquery;
@@ -187,8 +198,11 @@ mod oid;
 mod range;
 mod record;
 mod str;
+mod timestamp;
 mod tuple;
+mod vector;
 mod void;
+mod xml;
 
 #[cfg(any(feature = "chrono", feature = "time"))]
 mod time_tz;
@@ -196,8 +210,8 @@ mod time_tz;
 #[cfg(feature = "bigdecimal")]
 mod bigdecimal;
 
-#[cfg(any(feature = "bigdecimal", feature = "rust_decimal"))]
-mod numeric;
+#[cfg(any(feature = "any", feature = "bigdecimal", feature = "rust_decimal"))]
+pub(crate) mod numeric;
 
 #[cfg(feature = "rust_decimal")]
 mod rust_decimal;
@@ -224,6 +238,8 @@ mod mac_address;
 mod bit_vec;
 
 pub use array::PgHasArrayType;
+pub use citext::PgCiText;
+pub use hstore::PgHstore;
 pub use interval::PgInterval;
 pub use lquery::PgLQuery;
 pub use lquery::PgLQueryLevel;
@@ -235,6 +251,9 @@ pub use ltree::PgLTreeParseError;
 pub use money::PgMoney;
 pub use oid::Oid;
 pub use range::PgRange;
+pub use timestamp::PgTimestamp;
+pub use vector::{PgVector, PgVectorDistance, PgVectorQueryBuilder};
+pub use xml::PgXml;
 
 #[cfg(any(feature = "chrono", feature = "time"))]
 pub use time_tz::PgTimeTz;
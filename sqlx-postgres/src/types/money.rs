@@ -6,10 +6,7 @@ use crate::{
     {PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres},
 };
 use byteorder::{BigEndian, ByteOrder};
-use std::{
-    io,
-    ops::{Add, AddAssign, Sub, SubAssign},
-};
+use std::ops::{Add, AddAssign, Sub, SubAssign};
 
 /// The PostgreSQL [`MONEY`] type stores a currency amount with a fixed fractional
 /// precision. The fractional precision is determined by the database's
@@ -18,7 +15,10 @@ use std::{
 /// Data is read and written as 64-bit signed integers, and conversion into a
 /// decimal should be done using the right precision.
 ///
-/// Reading `MONEY` value in text format is not supported and will cause an error.
+/// In text format, e.g. from a query that isn't prepared, `MONEY` is formatted for the
+/// `lc_monetary` locale, like `$1,234.56` or `-1.234,56 €`. This is decoded without knowing the
+/// locale: the text always has all the fractional digits, so the digits make up the raw value, and
+/// a negative amount has a `-` sign or is in parentheses.
 ///
 /// ### `locale_frac_digits`
 /// This parameter corresponds to the number of digits after the decimal separator.
@@ -124,6 +124,7 @@ impl PgMoney {
         locale_frac_digits: u32,
     ) -> Result<Self, BoxDynError> {
         use bigdecimal::ToPrimitive;
+        use std::io;
 
         let multiplier = bigdecimal::BigDecimal::new(
             num_bigint::BigInt::from(10i128.pow(locale_frac_digits)),
@@ -180,18 +181,37 @@ impl Decode<'_, Postgres> for PgMoney {
 
                 Ok(PgMoney(cents))
             }
-            PgValueFormat::Text => {
-                let error = io::Error::new(
-                    io::ErrorKind::InvalidData,
-                    "Reading a `MONEY` value in text format is not supported.",
-                );
-
-                Err(Box::new(error))
-            }
+            PgValueFormat::Text => parse_text(value.as_str()?),
         }
     }
 }
 
+fn parse_text(s: &str) -> Result<PgMoney, BoxDynError> {
+    let negative = s.contains(['-', '(']);
+
+    let mut digits = s.bytes().filter(u8::is_ascii_digit).peekable();
+
+    if digits.peek().is_none() {
+        return Err(format!("invalid `MONEY` value: {s:?}").into());
+    }
+
+    // accumulate with the sign so that `i64::MIN` doesn't overflow
+    let value = digits.try_fold(0_i64, |value, digit| {
+        let digit = i64::from(digit - b'0');
+        let value = value.checked_mul(10)?;
+
+        if negative {
+            value.checked_sub(digit)
+        } else {
+            value.checked_add(digit)
+        }
+    });
+
+    value
+        .map(PgMoney)
+        .ok_or_else(|| format!("`MONEY` value out of range: {s:?}").into())
+}
+
 impl Add<PgMoney> for PgMoney {
     type Output = PgMoney;
 
@@ -304,6 +324,24 @@ mod tests {
         money -= PgMoney(1);
     }
 
+    #[test]
+    fn parsing_text_works() {
+        use super::parse_text;
+
+        assert_eq!(PgMoney(12345), parse_text("$123.45").unwrap());
+        assert_eq!(PgMoney(123456), parse_text("1.234,56 €").unwrap());
+        assert_eq!(PgMoney(-123456), parse_text("-$1,234.56").unwrap());
+        assert_eq!(PgMoney(-123456), parse_text("($1,234.56)").unwrap());
+        assert_eq!(PgMoney(1000), parse_text("￥1,000").unwrap());
+        assert_eq!(
+            PgMoney(i64::MIN),
+            parse_text("-$92,233,720,368,547,758.08").unwrap()
+        );
+
+        assert!(parse_text("$92,233,720,368,547,758.08").is_err());
+        assert!(parse_text("$").is_err());
+    }
+
     #[test]
     #[cfg(feature = "bigdecimal")]
     fn conversion_to_bigdecimal_works() {
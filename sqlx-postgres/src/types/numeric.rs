@@ -134,3 +134,177 @@ impl PgNumeric {
         }
     }
 }
+
+/// Converts to and from the decimal text form used by the `Any` driver, which doesn't depend
+/// on `bigdecimal` or `rust_decimal`.
+#[cfg(feature = "any")]
+impl PgNumeric {
+    pub(crate) fn to_decimal_string(&self) -> String {
+        let (sign, digits, weight, scale) = match self {
+            PgNumeric::NotANumber => return "NaN".to_string(),
+            PgNumeric::Number {
+                sign,
+                digits,
+                weight,
+                scale,
+            } => (
+                *sign,
+                digits,
+                i32::from(*weight),
+                usize::try_from(*scale).unwrap_or(0),
+            ),
+        };
+
+        // the base-10000 digit with the exponent `weight - i`, or `0` if it's not stored
+        let digit = |i: i32| {
+            usize::try_from(i)
+                .ok()
+                .and_then(|i| digits.get(i))
+                .copied()
+                .unwrap_or(0)
+        };
+
+        let mut s = String::new();
+
+        if sign == PgNumericSign::Negative {
+            s.push('-');
+        }
+
+        if weight < 0 {
+            s.push('0');
+        } else {
+            s.push_str(&digit(0).to_string());
+
+            for i in 1..=weight {
+                s.push_str(&format!("{:04}", digit(i)));
+            }
+        }
+
+        if scale > 0 {
+            let mut fraction = String::with_capacity(scale + 4);
+            let mut i = weight + 1;
+
+            while fraction.len() < scale {
+                fraction.push_str(&format!("{:04}", digit(i)));
+                i += 1;
+            }
+
+            fraction.truncate(scale);
+            s.push('.');
+            s.push_str(&fraction);
+        }
+
+        s
+    }
+
+    pub(crate) fn from_decimal_str(s: &str) -> Result<Self, BoxDynError> {
+        if s.eq_ignore_ascii_case("NaN") {
+            return Ok(PgNumeric::NotANumber);
+        }
+
+        let invalid = || format!("invalid decimal number: {s:?}");
+
+        let (sign, unsigned) = match s.as_bytes().first() {
+            Some(b'-') => (PgNumericSign::Negative, &s[1..]),
+            Some(b'+') => (PgNumericSign::Positive, &s[1..]),
+            _ => (PgNumericSign::Positive, s),
+        };
+
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+        if integer.len() + fraction.len() == 0
+            || !integer
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return Err(invalid().into());
+        }
+
+        let scale = i16::try_from(fraction.len()).map_err(|_| invalid())?;
+
+        // pad both parts to whole base-10000 digits around the decimal point
+        let integer_pad = (4 - integer.len() % 4) % 4;
+        let fraction_pad = (4 - fraction.len() % 4) % 4;
+
+        let padded: Vec<u8> = std::iter::repeat(b'0')
+            .take(integer_pad)
+            .chain(integer.bytes())
+            .chain(fraction.bytes())
+            .chain(std::iter::repeat(b'0').take(fraction_pad))
+            .collect();
+
+        let mut digits: Vec<i16> = padded
+            .chunks(4)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0, |acc, b| acc * 10 + i16::from(b - b'0'))
+            })
+            .collect();
+
+        let mut weight =
+            i16::try_from((integer.len() + integer_pad) / 4).map_err(|_| invalid())? - 1;
+
+        let leading_zeros = digits.iter().take_while(|digit| **digit == 0).count();
+        digits.drain(..leading_zeros);
+        weight -= leading_zeros as i16;
+
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+
+        if digits.is_empty() {
+            return Ok(PgNumeric::Number {
+                sign: PgNumericSign::Positive,
+                digits,
+                weight: 0,
+                scale,
+            });
+        }
+
+        Ok(PgNumeric::Number {
+            sign,
+            digits,
+            weight,
+            scale,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "any"))]
+mod decimal_strings {
+    use super::{PgNumeric, PgNumericSign};
+
+    #[test]
+    fn round_trip() {
+        for s in [
+            "0",
+            "0.00",
+            "1",
+            "-1",
+            "10000",
+            "12345.6789",
+            "-0.0001",
+            "0.00012",
+            "100000000.5",
+            "NaN",
+        ] {
+            let numeric = PgNumeric::from_decimal_str(s).unwrap();
+            assert_eq!(numeric.to_decimal_string(), s);
+        }
+
+        assert_eq!(
+            PgNumeric::from_decimal_str("12345.6789").unwrap(),
+            PgNumeric::Number {
+                sign: PgNumericSign::Positive,
+                digits: vec![1, 2345, 6789],
+                weight: 1,
+                scale: 4,
+            }
+        );
+
+        assert!(PgNumeric::from_decimal_str("1e5").is_err());
+        assert!(PgNumeric::from_decimal_str("-").is_err());
+    }
+}
@@ -349,8 +349,13 @@ where
                 }
 
                 if !flags.contains(RangeFlags::LB_INF) {
-                    let value =
-                        T::decode(PgValueRef::get(&mut buf, value.format, element_ty.clone()))?;
+                    let value = T::decode(PgValueRef::get(
+                        &mut buf,
+                        value.format,
+                        element_ty.clone(),
+                        value.clamp_infinite_timestamps,
+                        value.exact_decimals,
+                    ))?;
 
                     start = if flags.contains(RangeFlags::LB_INC) {
                         Bound::Included(value)
@@ -360,8 +365,13 @@ where
                 }
 
                 if !flags.contains(RangeFlags::UB_INF) {
-                    let value =
-                        T::decode(PgValueRef::get(&mut buf, value.format, element_ty.clone()))?;
+                    let value = T::decode(PgValueRef::get(
+                        &mut buf,
+                        value.format,
+                        element_ty.clone(),
+                        value.clamp_infinite_timestamps,
+                        value.exact_decimals,
+                    ))?;
 
                     end = if flags.contains(RangeFlags::UB_INC) {
                         Bound::Included(value)
@@ -451,6 +461,8 @@ where
                             format: PgValueFormat::Text,
                             value: Some(element.as_bytes()),
                             row: None,
+                            clamp_infinite_timestamps: value.clamp_infinite_timestamps,
+                            exact_decimals: value.exact_decimals,
                         })?);
 
                         if count == 1 {
@@ -63,6 +63,8 @@ pub struct PgRecordDecoder<'r> {
     typ: PgTypeInfo,
     fmt: PgValueFormat,
     ind: usize,
+    clamp_infinite_timestamps: bool,
+    exact_decimals: bool,
 }
 
 impl<'r> PgRecordDecoder<'r> {
@@ -71,6 +73,8 @@ impl<'r> PgRecordDecoder<'r> {
         let fmt = value.format();
         let mut buf = value.as_bytes()?;
         let typ = value.type_info;
+        let clamp_infinite_timestamps = value.clamp_infinite_timestamps;
+        let exact_decimals = value.exact_decimals;
 
         match fmt {
             PgValueFormat::Binary => {
@@ -88,6 +92,8 @@ impl<'r> PgRecordDecoder<'r> {
             fmt,
             typ,
             ind: 0,
+            clamp_infinite_timestamps,
+            exact_decimals,
         })
     }
 
@@ -137,7 +143,13 @@ impl<'r> PgRecordDecoder<'r> {
 
                 self.ind += 1;
 
-                T::decode(PgValueRef::get(&mut self.buf, self.fmt, element_type))
+                T::decode(PgValueRef::get(
+                    &mut self.buf,
+                    self.fmt,
+                    element_type,
+                    self.clamp_infinite_timestamps,
+                    self.exact_decimals,
+                ))
             }
 
             PgValueFormat::Text => {
@@ -198,6 +210,8 @@ impl<'r> PgRecordDecoder<'r> {
                     format: self.fmt,
                     value: buf,
                     row: None,
+                    clamp_infinite_timestamps: self.clamp_infinite_timestamps,
+                    exact_decimals: self.exact_decimals,
                 })
             }
         }
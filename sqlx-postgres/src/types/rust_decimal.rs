@@ -1,6 +1,8 @@
+use std::cmp;
+
 use num_bigint::{BigInt, Sign};
 use rust_decimal::{
-    prelude::{ToPrimitive, Zero},
+    prelude::{Signed, ToPrimitive, Zero},
     Decimal,
 };
 
@@ -11,6 +13,12 @@ use crate::types::numeric::{PgNumeric, PgNumericSign};
 use crate::types::Type;
 use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 
+// the most digits that `Decimal` can hold after the point
+const MAX_SCALE: u32 = 28;
+
+// the number of digits of `2^96`, which bounds the mantissa of `Decimal`
+const MAX_MANTISSA_DIGITS: u32 = 29;
+
 impl Type<Postgres> for Decimal {
     fn type_info() -> PgTypeInfo {
         PgTypeInfo::NUMERIC
@@ -27,53 +35,102 @@ impl TryFrom<PgNumeric> for Decimal {
     type Error = BoxDynError;
 
     fn try_from(numeric: PgNumeric) -> Result<Self, BoxDynError> {
-        let (digits, sign, weight) = match numeric {
-            PgNumeric::Number {
-                digits,
-                sign,
-                weight,
-                ..
-            } => (digits, sign, weight),
-
-            PgNumeric::NotANumber => {
-                return Err("Decimal does not support NaN values".into());
-            }
-        };
+        decimal_from_numeric(numeric, false)
+    }
+}
 
-        if digits.is_empty() {
-            // Postgres returns an empty digit array for 0 but BigInt expects at least one zero
-            return Ok(0u64.into());
+/// Converts `numeric` to a `Decimal`, rounding it half away from zero to at most 28 digits after
+/// the point and a 96-bit mantissa, or returning an error if `exact` and it had to be rounded.
+fn decimal_from_numeric(numeric: PgNumeric, exact: bool) -> Result<Decimal, BoxDynError> {
+    let (digits, sign, weight) = match numeric {
+        PgNumeric::Number {
+            digits,
+            sign,
+            weight,
+            ..
+        } => (digits, sign, weight),
+
+        PgNumeric::NotANumber => {
+            return Err("Decimal does not support NaN values".into());
         }
+    };
 
-        let sign = match sign {
-            PgNumericSign::Positive => Sign::Plus,
-            PgNumericSign::Negative => Sign::Minus,
-        };
+    if digits.is_empty() {
+        // Postgres returns an empty digit array for 0 but BigInt expects at least one zero
+        return Ok(0u64.into());
+    }
+
+    let sign = match sign {
+        PgNumericSign::Positive => Sign::Plus,
+        PgNumericSign::Negative => Sign::Minus,
+    };
+
+    // weight is 0 if the decimal point falls after the first base-10000 digit
+    let scale = (digits.len() as i64 - weight as i64 - 1) * 4;
 
-        // weight is 0 if the decimal point falls after the first base-10000 digit
-        let scale = (digits.len() as i64 - weight as i64 - 1) * 4;
+    // no optimized algorithm for base-10 so use base-100 for faster processing
+    let mut cents = Vec::with_capacity(digits.len() * 2);
+    for digit in &digits {
+        cents.push((digit / 100) as u8);
+        cents.push((digit % 100) as u8);
+    }
+
+    let mut bigint = BigInt::from_radix_be(sign, &cents, 100)
+        .ok_or("PgNumeric contained an out-of-range digit")?;
+
+    // A negative scale, meaning we have nothing on the right and must
+    // add zeroes to the left.
+    if scale < 0 {
+        bigint *= BigInt::from(10u8).pow(scale.unsigned_abs() as u32);
+    }
 
-        // no optimized algorithm for base-10 so use base-100 for faster processing
-        let mut cents = Vec::with_capacity(digits.len() * 2);
-        for digit in &digits {
-            cents.push((digit / 100) as u8);
-            cents.push((digit % 100) as u8);
+    let scale = cmp::max(scale, 0) as u32;
+    let mantissa_limit = BigInt::from(1u128 << 96);
+
+    // the number of digits to drop from the right: enough to leave at most `MAX_SCALE` after
+    // the point, and to leave no more digits than `2^96` has, going by a lower bound on the
+    // number of digits from the bit length; rounding and the bound being short may take a
+    // couple more
+    let min_digits = (bigint.bits().saturating_sub(1) as f64 * std::f64::consts::LOG10_2) as u32;
+    let mut dropped = cmp::max(
+        scale.saturating_sub(MAX_SCALE),
+        min_digits.saturating_sub(MAX_MANTISSA_DIGITS),
+    );
+
+    if dropped > scale {
+        return Err("Decimal's integer part out of range.".into());
+    }
+
+    loop {
+        let divisor = BigInt::from(10u8).pow(dropped);
+        let mut mantissa = &bigint / &divisor;
+        let remainder = &bigint % &divisor;
+
+        if remainder.magnitude() * 2u8 >= *divisor.magnitude() {
+            mantissa += remainder.signum();
         }
 
-        let bigint = BigInt::from_radix_be(sign, &cents, 100)
-            .ok_or("PgNumeric contained an out-of-range digit")?;
-
-        match (bigint.to_i128(), scale) {
-            // A negative scale, meaning we have nothing on the right and must
-            // add zeroes to the left.
-            (Some(num), scale) if scale < 0 => Ok(Decimal::from_i128_with_scale(
-                num * 10i128.pow(scale.abs() as u32),
-                0,
-            )),
-            // A positive scale, so we have decimals on the right.
-            (Some(num), _) => Ok(Decimal::from_i128_with_scale(num, scale as u32)),
-            (None, _) => Err("Decimal's integer part out of range.".into()),
+        if mantissa.magnitude() < mantissa_limit.magnitude() {
+            if exact && !remainder.is_zero() {
+                return Err(format!(
+                    "NUMERIC value cannot be represented exactly by Decimal, \
+                     which has at most {MAX_SCALE} digits after the point"
+                )
+                .into());
+            }
+
+            let mantissa = mantissa
+                .to_i128()
+                .ok_or("Decimal's mantissa out of range.")?;
+
+            return Ok(Decimal::from_i128_with_scale(mantissa, scale - dropped));
+        }
+
+        if dropped == scale {
+            return Err("Decimal's integer part out of range.".into());
         }
+
+        dropped += 1;
     }
 }
 
@@ -163,7 +220,22 @@ impl Encode<'_, Postgres> for Decimal {
 impl Decode<'_, Postgres> for Decimal {
     fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
         match value.format() {
-            PgValueFormat::Binary => PgNumeric::decode(value.as_bytes()?)?.try_into(),
+            PgValueFormat::Binary => {
+                decimal_from_numeric(PgNumeric::decode(value.as_bytes()?)?, value.exact_decimals)
+            }
+
+            PgValueFormat::Text if value.exact_decimals => {
+                let s = value.as_str()?;
+
+                // trailing zeros after the point don't need to fit
+                let s = match s.contains('.') {
+                    true => s.trim_end_matches('0').trim_end_matches('.'),
+                    false => s,
+                };
+
+                Ok(Decimal::from_str_exact(s)?)
+            }
+
             PgValueFormat::Text => Ok(value.as_str()?.parse::<Decimal>()?),
         }
     }
@@ -404,3 +476,92 @@ mod decimal_to_pgnumeric {
         );
     }
 }
+
+#[cfg(test)]
+mod pgnumeric_to_decimal {
+    use super::{decimal_from_numeric, Decimal, PgNumeric, PgNumericSign};
+
+    fn numeric(sign: PgNumericSign, weight: i16, digits: &[i16]) -> PgNumeric {
+        PgNumeric::Number {
+            sign,
+            scale: 0,
+            weight,
+            digits: digits.to_vec(),
+        }
+    }
+
+    #[test]
+    fn exact() {
+        let n = numeric(PgNumericSign::Negative, 1, &[1234, 5678, 9000]);
+
+        assert_eq!(
+            decimal_from_numeric(n, true).unwrap(),
+            "-12345678.9".parse::<Decimal>().unwrap()
+        );
+        assert_eq!(
+            decimal_from_numeric(numeric(PgNumericSign::Positive, 2, &[1]), true).unwrap(),
+            Decimal::from(100_000_000u64)
+        );
+    }
+
+    #[test]
+    fn rounds_scale() {
+        // 0.1234 5678 9012 3456 7890 1234 5678 5 (29 digits after the point)
+        let n = || {
+            numeric(
+                PgNumericSign::Positive,
+                -1,
+                &[1234, 5678, 9012, 3456, 7890, 1234, 5678, 5000],
+            )
+        };
+
+        assert_eq!(
+            decimal_from_numeric(n(), false).unwrap(),
+            "0.1234567890123456789012345679".parse::<Decimal>().unwrap()
+        );
+        assert!(decimal_from_numeric(n(), true).is_err());
+    }
+
+    #[test]
+    fn rounds_mantissa() {
+        // 123456789012.1234567890123456789 has too many digits for 96 bits
+        let n = || {
+            numeric(
+                PgNumericSign::Negative,
+                2,
+                &[1234, 5678, 9012, 1234, 5678, 9012, 3456, 7890],
+            )
+        };
+
+        assert_eq!(
+            decimal_from_numeric(n(), false).unwrap(),
+            "-123456789012.12345678901234568"
+                .parse::<Decimal>()
+                .unwrap()
+        );
+        assert!(decimal_from_numeric(n(), true).is_err());
+    }
+
+    #[test]
+    fn rounds_long_fractions() {
+        // 1234567890.1111 1111 .. with 10,000 digits after the point
+        let mut digits = vec![12, 3456, 7890];
+        digits.resize(2503, 1111);
+
+        let n = numeric(PgNumericSign::Positive, 2, &digits);
+
+        assert_eq!(
+            decimal_from_numeric(n, false).unwrap(),
+            "1234567890.1111111111111111111".parse::<Decimal>().unwrap()
+        );
+    }
+
+    #[test]
+    fn out_of_range() {
+        // 10^32
+        let n = || numeric(PgNumericSign::Positive, 8, &[1]);
+
+        assert!(decimal_from_numeric(n(), false).is_err());
+        assert!(decimal_from_numeric(PgNumeric::NotANumber, false).is_err());
+    }
+}
@@ -2,6 +2,7 @@ use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
 use crate::types::time::PG_EPOCH;
+use crate::types::timestamp::clamp_infinity;
 use crate::types::Type;
 use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use std::mem;
@@ -34,6 +35,10 @@ impl Encode<'_, Postgres> for Date {
 
 impl<'r> Decode<'r, Postgres> for Date {
     fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        if let Some(clamped) = clamp_infinity(&value, Date::MIN, Date::MAX)? {
+            return Ok(clamped);
+        }
+
         Ok(match value.format() {
             PgValueFormat::Binary => {
                 // DATE is encoded as the days since epoch
@@ -2,6 +2,7 @@ use crate::decode::Decode;
 use crate::encode::{Encode, IsNull};
 use crate::error::BoxDynError;
 use crate::types::time::PG_EPOCH;
+use crate::types::timestamp::clamp_infinity;
 use crate::types::Type;
 use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
 use std::borrow::Cow;
@@ -48,6 +49,12 @@ impl Encode<'_, Postgres> for PrimitiveDateTime {
 
 impl<'r> Decode<'r, Postgres> for PrimitiveDateTime {
     fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        if let Some(clamped) =
+            clamp_infinity(&value, PrimitiveDateTime::MIN, PrimitiveDateTime::MAX)?
+        {
+            return Ok(clamped);
+        }
+
         Ok(match value.format() {
             PgValueFormat::Binary => {
                 // TIMESTAMP is encoded as the microseconds since the epoch
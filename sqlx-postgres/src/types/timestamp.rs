@@ -0,0 +1,232 @@
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+
+/// A `TIMESTAMP`, `TIMESTAMPTZ` or `DATE` that can also be `infinity` or `-infinity`.
+///
+/// Postgres has the special values `infinity` and `-infinity` for these types, which are later
+/// and earlier than all other values. The Rust date and time types can't represent them, so
+/// decoding them as one of those is an error unless
+/// [`PgConnectOptions::clamp_infinite_timestamps()`][crate::PgConnectOptions::clamp_infinite_timestamps]
+/// is set. `PgTimestamp<T>` decodes them as [`PgTimestamp::Infinity`] and
+/// [`PgTimestamp::NegInfinity`], and any other value as `T`.
+///
+/// The variants are ordered like the values in Postgres.
+///
+/// ```rust,ignore
+/// use sqlx::postgres::types::PgTimestamp;
+/// use sqlx::types::chrono::NaiveDateTime;
+///
+/// let valid_until: PgTimestamp<NaiveDateTime> =
+///     sqlx::query_scalar("SELECT valid_until FROM licenses WHERE id = $1")
+///         .bind(id)
+///         .fetch_one(&mut conn)
+///         .await?;
+///
+/// if let PgTimestamp::Infinity = valid_until {
+///     println!("never expires");
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PgTimestamp<T> {
+    /// `-infinity`, earlier than all other values.
+    NegInfinity,
+    /// A finite value.
+    Value(T),
+    /// `infinity`, later than all other values.
+    Infinity,
+}
+
+impl<T> PgTimestamp<T> {
+    /// The finite value, or `None` for `infinity` and `-infinity`.
+    pub fn value(&self) -> Option<&T> {
+        match self {
+            PgTimestamp::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Converts into the finite value, or `None` for `infinity` and `-infinity`.
+    pub fn into_value(self) -> Option<T> {
+        match self {
+            PgTimestamp::Value(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Whether this isn't `infinity` or `-infinity`.
+    pub fn is_finite(&self) -> bool {
+        matches!(self, PgTimestamp::Value(_))
+    }
+}
+
+impl<T> From<T> for PgTimestamp<T> {
+    fn from(value: T) -> Self {
+        PgTimestamp::Value(value)
+    }
+}
+
+impl<T> Type<Postgres> for PgTimestamp<T>
+where
+    T: Type<Postgres>,
+{
+    fn type_info() -> PgTypeInfo {
+        T::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        T::compatible(ty)
+    }
+}
+
+impl<T> PgHasArrayType for PgTimestamp<T>
+where
+    T: PgHasArrayType,
+{
+    fn array_type_info() -> PgTypeInfo {
+        T::array_type_info()
+    }
+
+    fn array_compatible(ty: &PgTypeInfo) -> bool {
+        T::array_compatible(ty)
+    }
+}
+
+impl<'q, T> Encode<'q, Postgres> for PgTimestamp<T>
+where
+    T: Encode<'q, Postgres> + Type<Postgres>,
+{
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        // `DATE` is encoded as an `INT4` of days, the others as an `INT8` of microseconds
+        let is_date = T::type_info() == PgTypeInfo::DATE;
+
+        match self {
+            PgTimestamp::Value(value) => return value.encode_by_ref(buf),
+            PgTimestamp::Infinity if is_date => buf.extend(&i32::MAX.to_be_bytes()),
+            PgTimestamp::Infinity => buf.extend(&i64::MAX.to_be_bytes()),
+            PgTimestamp::NegInfinity if is_date => buf.extend(&i32::MIN.to_be_bytes()),
+            PgTimestamp::NegInfinity => buf.extend(&i64::MIN.to_be_bytes()),
+        }
+
+        IsNull::No
+    }
+
+    fn size_hint(&self) -> usize {
+        match self {
+            PgTimestamp::Value(value) => value.size_hint(),
+            _ => std::mem::size_of::<i64>(),
+        }
+    }
+}
+
+impl<'r, T> Decode<'r, Postgres> for PgTimestamp<T>
+where
+    T: Decode<'r, Postgres>,
+{
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(match decode_infinity(&value)? {
+            Some(true) => PgTimestamp::Infinity,
+            Some(false) => PgTimestamp::NegInfinity,
+            None => PgTimestamp::Value(T::decode(value)?),
+        })
+    }
+}
+
+/// Whether `value` is `infinity` (`Some(true)`), `-infinity` (`Some(false)`) or neither.
+fn decode_infinity(value: &PgValueRef<'_>) -> Result<Option<bool>, BoxDynError> {
+    let infinity = match value.format() {
+        PgValueFormat::Binary => match *value.as_bytes()? {
+            [a, b, c, d] => match i32::from_be_bytes([a, b, c, d]) {
+                i32::MAX => Some(true),
+                i32::MIN => Some(false),
+                _ => None,
+            },
+
+            [a, b, c, d, e, f, g, h] => match i64::from_be_bytes([a, b, c, d, e, f, g, h]) {
+                i64::MAX => Some(true),
+                i64::MIN => Some(false),
+                _ => None,
+            },
+
+            _ => None,
+        },
+
+        PgValueFormat::Text => match value.as_str()? {
+            "infinity" => Some(true),
+            "-infinity" => Some(false),
+            _ => None,
+        },
+    };
+
+    Ok(infinity)
+}
+
+/// Returns `min` for `-infinity` and `max` for `infinity` if the connection clamps them, or an
+/// error if it doesn't; `None` for any other value.
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub(crate) fn clamp_infinity<T>(
+    value: &PgValueRef<'_>,
+    min: T,
+    max: T,
+) -> Result<Option<T>, BoxDynError> {
+    let positive = match decode_infinity(value)? {
+        Some(positive) => positive,
+        None => return Ok(None),
+    };
+
+    if !value.clamp_infinite_timestamps {
+        return Err(format!(
+            "`{}infinity` can't be represented by `{}`; decode it as `PgTimestamp` or set \
+             `PgConnectOptions::clamp_infinite_timestamps()`",
+            if positive { "" } else { "-" },
+            std::any::type_name::<T>(),
+        )
+        .into());
+    }
+
+    Ok(Some(if positive { max } else { min }))
+}
+
+#[test]
+fn test_decode_infinity() {
+    fn value(bytes: &[u8], format: PgValueFormat) -> PgValueRef<'_> {
+        PgValueRef {
+            value: Some(bytes),
+            row: None,
+            type_info: PgTypeInfo::TIMESTAMP,
+            format,
+            clamp_infinite_timestamps: false,
+            exact_decimals: false,
+        }
+    }
+
+    let decode = |bytes: &[u8], format| {
+        PgTimestamp::<i64>::decode(value(bytes, format)).map_err(|e| e.to_string())
+    };
+
+    assert_eq!(
+        decode(&i64::MAX.to_be_bytes(), PgValueFormat::Binary),
+        Ok(PgTimestamp::Infinity)
+    );
+    assert_eq!(
+        decode(&i64::MIN.to_be_bytes(), PgValueFormat::Binary),
+        Ok(PgTimestamp::NegInfinity)
+    );
+    assert_eq!(
+        decode(&42_i64.to_be_bytes(), PgValueFormat::Binary),
+        Ok(PgTimestamp::Value(42))
+    );
+    assert_eq!(
+        decode(b"-infinity", PgValueFormat::Text),
+        Ok(PgTimestamp::NegInfinity)
+    );
+    assert_eq!(
+        decode_infinity(&value(&i32::MAX.to_be_bytes(), PgValueFormat::Binary)).unwrap(),
+        Some(true)
+    );
+
+    assert!(PgTimestamp::NegInfinity < PgTimestamp::Value(i64::MIN));
+    assert!(PgTimestamp::Value(i64::MAX) < PgTimestamp::Infinity);
+}
@@ -0,0 +1,273 @@
+use byteorder::{BigEndian, ByteOrder};
+use std::fmt::{self, Display, Formatter};
+use std::ops::Deref;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::query_builder::QueryBuilder;
+use crate::types::Type;
+use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueFormat, PgValueRef, Postgres};
+
+/// A vector of single-precision floats (`vector`), from the pgvector extension.
+///
+/// See https://github.com/pgvector/pgvector
+///
+/// ```rust,ignore
+/// use sqlx::postgres::types::{PgVector, PgVectorDistance, PgVectorQueryBuilder};
+/// use sqlx::QueryBuilder;
+///
+/// sqlx::query("INSERT INTO items (embedding) VALUES ($1)")
+///     .bind(PgVector::from(vec![1.0, 2.0, 3.0]))
+///     .execute(&pool)
+///     .await?;
+///
+/// // the 5 items nearest to the query embedding
+/// let mut query = QueryBuilder::new("SELECT id FROM items ");
+/// query
+///     .push_order_by_distance("embedding", PgVectorDistance::Cosine, embedding)
+///     .push(" LIMIT 5");
+///
+/// let ids: Vec<i64> = query.build_query_scalar().fetch_all(&pool).await?;
+/// ```
+///
+/// ### Note: Extension Required
+/// The `vector` extension is not enabled by default in Postgres. You will need to install it and
+/// enable it explicitly:
+///
+/// ```ignore
+/// CREATE EXTENSION IF NOT EXISTS vector;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PgVector(pub Vec<f32>);
+
+/// The distance operators of pgvector, for ordering by similarity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PgVectorDistance {
+    /// Euclidean distance, `<->`.
+    L2,
+    /// Negative inner product, `<#>`; smaller is more similar.
+    InnerProduct,
+    /// Cosine distance, `<=>`.
+    Cosine,
+    /// Taxicab distance, `<+>`; requires pgvector 0.7+.
+    L1,
+}
+
+/// Helpers for pgvector queries, implemented for [`QueryBuilder<Postgres>`][QueryBuilder].
+pub trait PgVectorQueryBuilder {
+    /// Pushes the distance between `column` and `vector`, which is bound as a parameter,
+    /// e.g. `embedding <=> $1`.
+    fn push_distance(
+        &mut self,
+        column: impl Display,
+        distance: PgVectorDistance,
+        vector: impl Into<PgVector>,
+    ) -> &mut Self;
+
+    /// Pushes `ORDER BY` the distance between `column` and `vector`, nearest first, which an
+    /// index on `column` with the matching operator class can answer.
+    fn push_order_by_distance(
+        &mut self,
+        column: impl Display,
+        distance: PgVectorDistance,
+        vector: impl Into<PgVector>,
+    ) -> &mut Self;
+}
+
+impl PgVector {
+    /// The number of dimensions.
+    pub fn dimensions(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl PgVectorDistance {
+    /// The SQL operator.
+    pub fn operator(&self) -> &'static str {
+        match self {
+            PgVectorDistance::L2 => "<->",
+            PgVectorDistance::InnerProduct => "<#>",
+            PgVectorDistance::Cosine => "<=>",
+            PgVectorDistance::L1 => "<+>",
+        }
+    }
+}
+
+impl Display for PgVectorDistance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.operator())
+    }
+}
+
+impl<'args> PgVectorQueryBuilder for QueryBuilder<'args, Postgres> {
+    fn push_distance(
+        &mut self,
+        column: impl Display,
+        distance: PgVectorDistance,
+        vector: impl Into<PgVector>,
+    ) -> &mut Self {
+        self.push(format_args!("{column} {distance} "))
+            .push_bind(vector.into())
+    }
+
+    fn push_order_by_distance(
+        &mut self,
+        column: impl Display,
+        distance: PgVectorDistance,
+        vector: impl Into<PgVector>,
+    ) -> &mut Self {
+        self.push("ORDER BY ")
+            .push_distance(column, distance, vector)
+    }
+}
+
+impl Deref for PgVector {
+    type Target = [f32];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Vec<f32>> for PgVector {
+    fn from(v: Vec<f32>) -> Self {
+        PgVector(v)
+    }
+}
+
+impl From<&[f32]> for PgVector {
+    fn from(v: &[f32]) -> Self {
+        PgVector(v.to_vec())
+    }
+}
+
+impl From<PgVector> for Vec<f32> {
+    fn from(v: PgVector) -> Self {
+        v.0
+    }
+}
+
+impl Display for PgVector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("[")?;
+
+        for (i, x) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+
+            write!(f, "{x}")?;
+        }
+
+        f.write_str("]")
+    }
+}
+
+impl Type<Postgres> for PgVector {
+    fn type_info() -> PgTypeInfo {
+        // Since `vector` is enabled by an extension, it does not have a stable OID.
+        PgTypeInfo::with_name("vector")
+    }
+}
+
+impl PgHasArrayType for PgVector {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_vector")
+    }
+}
+
+impl Encode<'_, Postgres> for PgVector {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        // https://github.com/pgvector/pgvector/blob/v0.7.0/src/vector.c#L349
+        let dim = i16::try_from(self.0.len()).expect("too many dimensions for pgvector `vector`");
+
+        buf.extend(&dim.to_be_bytes());
+        buf.extend(&0_i16.to_be_bytes()); // unused
+
+        for x in &self.0 {
+            buf.extend(&x.to_be_bytes());
+        }
+
+        IsNull::No
+    }
+}
+
+impl<'r> Decode<'r, Postgres> for PgVector {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        match value.format() {
+            PgValueFormat::Binary => decode_binary(value.as_bytes()?),
+            PgValueFormat::Text => decode_text(value.as_str()?),
+        }
+    }
+}
+
+fn decode_binary(buf: &[u8]) -> Result<PgVector, BoxDynError> {
+    if buf.len() < 4 {
+        return Err("unexpected end of vector data".into());
+    }
+
+    let dim = usize::try_from(BigEndian::read_i16(buf))?;
+    let data = &buf[4..];
+
+    if data.len() != dim * 4 {
+        return Err(format!("expected {dim} dimensions in vector data").into());
+    }
+
+    Ok(PgVector(
+        data.chunks_exact(4).map(BigEndian::read_f32).collect(),
+    ))
+}
+
+/// Parses the text format, e.g. `[1,2.5,3]`.
+fn decode_text(s: &str) -> Result<PgVector, BoxDynError> {
+    let s = s
+        .trim()
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .ok_or_else(|| format!("expected a vector in brackets, got {s:?}"))?;
+
+    if s.trim().is_empty() {
+        return Ok(PgVector::default());
+    }
+
+    s.split(',')
+        .map(|x| Ok(x.trim().parse::<f32>()?))
+        .collect::<Result<_, BoxDynError>>()
+        .map(PgVector)
+}
+
+#[test]
+fn test_decode_text() {
+    assert_eq!(
+        decode_text("[1,2.5,-3]").unwrap(),
+        PgVector(vec![1.0, 2.5, -3.0])
+    );
+    assert_eq!(decode_text("[]").unwrap(), PgVector::default());
+    assert!(decode_text("1,2").is_err());
+    assert!(decode_text("[1,a]").is_err());
+}
+
+#[test]
+fn test_binary_round_trip() {
+    let vector = PgVector(vec![1.0, -0.5, 1e-3]);
+
+    let mut buf = PgArgumentBuffer::default();
+    let _ = vector.encode_by_ref(&mut buf);
+
+    assert_eq!(&buf[..4], &[0, 3, 0, 0]);
+    assert_eq!(decode_binary(&buf).unwrap(), vector);
+    assert!(decode_binary(&buf[..8]).is_err());
+}
+
+#[test]
+fn test_push_distance() {
+    let mut query = QueryBuilder::<Postgres>::new("SELECT id FROM items ");
+    query.push_order_by_distance("embedding", PgVectorDistance::Cosine, vec![1.0, 2.0]);
+
+    assert_eq!(
+        query.sql(),
+        "SELECT id FROM items ORDER BY embedding <=> $1"
+    );
+}
@@ -0,0 +1,94 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::Deref;
+
+use crate::decode::Decode;
+use crate::encode::{Encode, IsNull};
+use crate::error::BoxDynError;
+use crate::types::Type;
+use crate::{PgArgumentBuffer, PgHasArrayType, PgTypeInfo, PgValueRef, Postgres};
+
+/// An XML document or content fragment (`xml`) in Postgres.
+///
+/// See https://www.postgresql.org/docs/current/datatype-xml.html
+///
+/// `xml` values can be decoded as `String`, `&str` or `Cow<str>`, but those are bound as `TEXT`,
+/// which Postgres won't implicitly cast to `xml`. Bind a `PgXml` instead, or cast the parameter
+/// with `$1::xml`:
+///
+/// ```rust,ignore
+/// use sqlx::postgres::types::PgXml;
+///
+/// sqlx::query("INSERT INTO feeds (body) VALUES ($1)")
+///     .bind(PgXml::from("<feed><title>News</title></feed>"))
+///     .execute(&pool)
+///     .await?;
+/// ```
+///
+/// The XML is not checked on the client; Postgres rejects values that are not well-formed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PgXml(pub String);
+
+impl Deref for PgXml {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<String> for PgXml {
+    fn from(s: String) -> Self {
+        PgXml(s)
+    }
+}
+
+impl From<&str> for PgXml {
+    fn from(s: &str) -> Self {
+        PgXml(s.to_owned())
+    }
+}
+
+impl From<PgXml> for String {
+    fn from(xml: PgXml) -> Self {
+        xml.0
+    }
+}
+
+impl Display for PgXml {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Type<Postgres> for PgXml {
+    fn type_info() -> PgTypeInfo {
+        PgTypeInfo::XML
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <&str as Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl PgHasArrayType for PgXml {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::XML_ARRAY
+    }
+
+    fn array_compatible(ty: &PgTypeInfo) -> bool {
+        <&str as PgHasArrayType>::array_compatible(ty)
+    }
+}
+
+impl Encode<'_, Postgres> for PgXml {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        // the binary format of `xml` is its text, in the client encoding
+        <&str as Encode<Postgres>>::encode(&**self, buf)
+    }
+}
+
+impl Decode<'_, Postgres> for PgXml {
+    fn decode(value: PgValueRef<'_>) -> Result<Self, BoxDynError> {
+        Ok(PgXml(value.as_str()?.to_owned()))
+    }
+}
@@ -1,8 +1,8 @@
 use crate::error::{BoxDynError, UnexpectedNullError};
 use crate::{PgTypeInfo, Postgres};
 use sqlx_core::bytes::{Buf, Bytes};
+use sqlx_core::io::text::from_utf8;
 use std::borrow::Cow;
-use std::str::from_utf8;
 
 pub(crate) use sqlx_core::value::{Value, ValueRef};
 
@@ -20,6 +20,10 @@ pub struct PgValueRef<'r> {
     pub(crate) row: Option<&'r Bytes>,
     pub(crate) type_info: PgTypeInfo,
     pub(crate) format: PgValueFormat,
+    // see `PgConnectOptions::clamp_infinite_timestamps()`
+    pub(crate) clamp_infinite_timestamps: bool,
+    // see `PgConnectOptions::exact_decimals()`
+    pub(crate) exact_decimals: bool,
 }
 
 /// Implementation of [`Value`] for PostgreSQL.
@@ -28,10 +32,18 @@ pub struct PgValue {
     pub(crate) value: Option<Bytes>,
     pub(crate) type_info: PgTypeInfo,
     pub(crate) format: PgValueFormat,
+    pub(crate) clamp_infinite_timestamps: bool,
+    pub(crate) exact_decimals: bool,
 }
 
 impl<'r> PgValueRef<'r> {
-    pub(crate) fn get(buf: &mut &'r [u8], format: PgValueFormat, ty: PgTypeInfo) -> Self {
+    pub(crate) fn get(
+        buf: &mut &'r [u8],
+        format: PgValueFormat,
+        ty: PgTypeInfo,
+        clamp_infinite_timestamps: bool,
+        exact_decimals: bool,
+    ) -> Self {
         let mut element_len = buf.get_i32();
 
         let element_val = if element_len == -1 {
@@ -48,6 +60,8 @@ impl<'r> PgValueRef<'r> {
             row: None,
             type_info: ty,
             format,
+            clamp_infinite_timestamps,
+            exact_decimals,
         }
     }
 
@@ -63,7 +77,7 @@ impl<'r> PgValueRef<'r> {
     }
 
     pub fn as_str(&self) -> Result<&'r str, BoxDynError> {
-        Ok(from_utf8(self.as_bytes()?)?)
+        from_utf8(self.as_bytes()?)
     }
 }
 
@@ -77,6 +91,8 @@ impl Value for PgValue {
             row: None,
             type_info: self.type_info.clone(),
             format: self.format,
+            clamp_infinite_timestamps: self.clamp_infinite_timestamps,
+            exact_decimals: self.exact_decimals,
         }
     }
 
@@ -105,6 +121,8 @@ impl<'r> ValueRef<'r> for PgValueRef<'r> {
             value,
             format: self.format,
             type_info: self.type_info.clone(),
+            clamp_infinite_timestamps: self.clamp_infinite_timestamps,
+            exact_decimals: self.exact_decimals,
         }
     }
 
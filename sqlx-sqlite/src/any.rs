@@ -7,16 +7,22 @@ use futures_core::stream::BoxStream;
 use futures_util::{StreamExt, TryFutureExt, TryStreamExt};
 
 use sqlx_core::any::{
-    Any, AnyArguments, AnyColumn, AnyConnectOptions, AnyConnectionBackend, AnyQueryResult, AnyRow,
-    AnyStatement, AnyTypeInfo, AnyTypeInfoKind, AnyValueKind,
+    Any, AnyArguments, AnyColumn, AnyConnectOptions, AnyConnectionBackend, AnyDecodedValue,
+    AnyQueryResult, AnyRow, AnyStatement, AnyTypeInfo, AnyTypeInfoKind, AnyTypeRegistry,
+    AnyValueKind,
 };
 
 use crate::type_info::DataType;
-use sqlx_core::connection::{ConnectOptions, Connection};
+use crate::SqliteValueRef;
+use sqlx_core::connection::{ConnectOptions, Connection, RunningQuery, ServerVersion};
 use sqlx_core::database::Database;
+use sqlx_core::decode::Decode;
 use sqlx_core::describe::Describe;
+use sqlx_core::error::BoxDynError;
 use sqlx_core::executor::Executor;
 use sqlx_core::transaction::TransactionManager;
+use sqlx_core::value::ValueRef;
+use std::borrow::Cow;
 
 sqlx_core::declare_driver_with_optional_migrate!(DRIVER = Sqlite);
 
@@ -57,6 +63,14 @@ impl AnyConnectionBackend for SqliteConnection {
         // NO-OP.
     }
 
+    fn server_version(&self) -> ServerVersion {
+        Connection::server_version(self)
+    }
+
+    fn running_query(&self) -> Option<RunningQuery> {
+        Connection::running_query(self)
+    }
+
     fn flush(&mut self) -> BoxFuture<'_, sqlx_core::Result<()>> {
         Connection::flush(self)
     }
@@ -125,12 +139,21 @@ impl AnyConnectionBackend for SqliteConnection {
     ) -> BoxFuture<'c, sqlx_core::Result<AnyStatement<'q>>> {
         Box::pin(async move {
             let statement = Executor::prepare_with(self, sql, &[]).await?;
-            AnyStatement::try_from_statement(sql, &statement, statement.column_names.clone())
+            AnyStatement::try_from_statement(
+                sql,
+                &statement,
+                statement.column_names.clone(),
+                &AnyTypeRegistry::default(),
+            )
         })
     }
 
     fn describe<'q>(&'q mut self, sql: &'q str) -> BoxFuture<'q, sqlx_core::Result<Describe<Any>>> {
-        Box::pin(async move { Executor::describe(self, sql).await?.try_into_any() })
+        Box::pin(async move {
+            Executor::describe(self, sql)
+                .await?
+                .try_into_any(&AnyTypeRegistry::default())
+        })
     }
 }
 
@@ -146,12 +169,11 @@ impl<'a> TryFrom<&'a SqliteTypeInfo> for AnyTypeInfo {
                 DataType::Float => AnyTypeInfoKind::Double,
                 DataType::Blob => AnyTypeInfoKind::Blob,
                 DataType::Text => AnyTypeInfoKind::Text,
-                _ => {
-                    return Err(sqlx_core::Error::AnyDriverError(
-                        format!("Any driver does not support the SQLite type {sqlite_type:?}")
-                            .into(),
-                    ))
-                }
+                DataType::Numeric => AnyTypeInfoKind::Numeric,
+                DataType::Bool => AnyTypeInfoKind::Bool,
+                DataType::Date => AnyTypeInfoKind::Date,
+                DataType::Time => AnyTypeInfoKind::Time,
+                DataType::Datetime => AnyTypeInfoKind::Timestamp,
             },
         })
     }
@@ -179,7 +201,7 @@ impl<'a> TryFrom<&'a SqliteRow> for AnyRow {
     type Error = sqlx_core::Error;
 
     fn try_from(row: &'a SqliteRow) -> Result<Self, Self::Error> {
-        AnyRow::map_from(row, row.column_names.clone())
+        AnyRow::map_from(row, row.column_names.clone(), &AnyTypeRegistry::default())
     }
 }
 
@@ -210,6 +232,15 @@ fn map_arguments(args: AnyArguments<'_>) -> SqliteArguments<'_> {
                 AnyValueKind::Double(d) => SqliteArgumentValue::Double(d),
                 AnyValueKind::Text(t) => SqliteArgumentValue::Text(t),
                 AnyValueKind::Blob(b) => SqliteArgumentValue::Blob(b),
+                AnyValueKind::Numeric(n) => SqliteArgumentValue::Text(n),
+                AnyValueKind::Uuid(u) => SqliteArgumentValue::Blob(Cow::Owned(u.to_vec())),
+                AnyValueKind::Json(j) => SqliteArgumentValue::Text(j),
+                // dates and times as ISO 8601 text, and arrays as JSON text
+                AnyValueKind::Date(_)
+                | AnyValueKind::Time(_)
+                | AnyValueKind::Timestamp(_)
+                | AnyValueKind::TimestampTz(_)
+                | AnyValueKind::Array { .. } => SqliteArgumentValue::Text(val.to_string().into()),
                 // AnyValueKind is `#[non_exhaustive]` but we should have covered everything
                 _ => unreachable!("BUG: missing mapping for {val:?}"),
             })
@@ -217,9 +248,17 @@ fn map_arguments(args: AnyArguments<'_>) -> SqliteArguments<'_> {
     }
 }
 
+impl<'r> Decode<'r, Sqlite> for AnyDecodedValue {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        // SQLite values only have the storage classes `INTEGER`, `REAL`, `TEXT` and `BLOB`,
+        // which are all mapped to the standard kinds
+        Err(format!("unexpected SQLite value of type {}", value.type_info()).into())
+    }
+}
+
 fn map_result(res: SqliteQueryResult) -> AnyQueryResult {
     AnyQueryResult {
         rows_affected: res.rows_affected(),
-        last_insert_id: None,
+        last_insert_id: Some(res.last_insert_rowid()),
     }
 }
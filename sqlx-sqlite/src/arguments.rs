@@ -57,6 +57,13 @@ impl<'q> Arguments<'q> for SqliteArguments<'q> {
     {
         self.add(value)
     }
+
+    fn shorten<'a>(self) -> Option<SqliteArguments<'a>>
+    where
+        'q: 'a,
+    {
+        Some(self)
+    }
 }
 
 impl SqliteArguments<'_> {
@@ -33,7 +33,7 @@ pub(crate) fn describe(conn: &mut ConnectionState, query: &str) -> Result<Descri
         // next we try to use [column_decltype] to inspect the type of each column
         columns.reserve(num);
 
-        // as a last resort, we explain the original query and attempt to
+        // then we explain the original query and attempt to
         // infer what would the expression types be as a fallback
         // to [column_decltype]
 
@@ -52,6 +52,9 @@ pub(crate) fn describe(conn: &mut ConnectionState, query: &str) -> Result<Descri
 
             let type_info = if let Some(ty) = stmt.handle.column_decltype(col) {
                 ty
+            } else if let Some(ty) = fallback.get(col).filter(|ty| ty.0 != DataType::Null) {
+                // the type inferred from the expression doesn't depend on the data in the tables
+                ty.clone()
             } else {
                 // if that fails, we back up and attempt to step the statement
                 // once *if* its read-only and then use [column_type] as a
@@ -61,15 +64,7 @@ pub(crate) fn describe(conn: &mut ConnectionState, query: &str) -> Result<Descri
                     let _ = stmt.handle.step();
                 }
 
-                let mut ty = stmt.handle.column_type_info(col);
-
-                if ty.0 == DataType::Null {
-                    if let Some(fallback) = fallback.get(col).cloned() {
-                        ty = fallback;
-                    }
-                }
-
-                ty
+                stmt.handle.column_type_info(col)
             };
 
             // check explain
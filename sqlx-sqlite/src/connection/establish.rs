@@ -1,4 +1,5 @@
 use crate::connection::handle::ConnectionHandle;
+use crate::connection::serialize;
 use crate::connection::LogSettings;
 use crate::connection::{ConnectionState, Statements};
 use crate::error::Error;
@@ -15,6 +16,7 @@ use std::io;
 use std::os::raw::c_int;
 use std::ptr::{addr_of_mut, null, null_mut};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 static THREAD_ID: AtomicU64 = AtomicU64::new(0);
@@ -44,6 +46,8 @@ pub struct EstablishParams {
     extensions: IndexMap<CString, Option<CString>>,
     pub(crate) thread_name: String,
     pub(crate) command_channel_size: usize,
+    deserialize_from: Option<Arc<[u8]>>,
+    read_only: bool,
     #[cfg(feature = "regexp")]
     register_regexp_function: bool,
 }
@@ -147,6 +151,11 @@ impl EstablishParams {
             extensions,
             thread_name: (options.thread_name)(THREAD_ID.fetch_add(1, Ordering::AcqRel)),
             command_channel_size: options.command_channel_size,
+            deserialize_from: options
+                .deserialize_from
+                .as_ref()
+                .map(|data| Arc::clone(&data.0)),
+            read_only: options.read_only,
             #[cfg(feature = "regexp")]
             register_regexp_function: options.register_regexp_function,
         })
@@ -192,7 +201,7 @@ impl EstablishParams {
 
         // SAFE: tested for NULL just above
         // This allows any returns below to close this handle with RAII
-        let handle = unsafe { ConnectionHandle::new(handle) };
+        let mut handle = unsafe { ConnectionHandle::new(handle) };
 
         if status != SQLITE_OK {
             return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
@@ -277,12 +286,18 @@ impl EstablishParams {
             return Err(Error::Database(Box::new(SqliteError::new(handle.as_ptr()))));
         }
 
+        // Replace the `main` database with the serialized copy, before any `PRAGMA`s are applied
+        if let Some(data) = &self.deserialize_from {
+            serialize::deserialize(&mut handle, "main", data, self.read_only)?;
+        }
+
         Ok(ConnectionState {
             handle,
             statements: Statements::new(self.statement_cache_capacity),
             transaction_depth: 0,
             log_settings: self.log_settings.clone(),
             progress_handler_callback: None,
+            update_hook_subscribers: None,
         })
     }
 }
@@ -23,10 +23,13 @@ impl<'c> Executor<'c> for &'c mut SqliteConnection {
         let sql = query.sql();
         let arguments = query.take_arguments();
         let persistent = query.persistent() && arguments.is_some();
+        let row_channel_size = query
+            .fetch_size()
+            .map_or(self.row_channel_size, |rows| rows as usize);
 
         Box::pin(
             self.worker
-                .execute(sql, arguments, self.row_channel_size, persistent)
+                .execute(sql, arguments, row_channel_size, persistent)
                 .map_ok(flume::Receiver::into_stream)
                 .try_flatten_stream(),
         )
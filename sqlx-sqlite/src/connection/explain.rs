@@ -332,6 +332,77 @@ fn opcode_to_type(op: &str) -> DataType {
     }
 }
 
+/// The type of the result of an arithmetic, bitwise, logical or concatenation operator.
+fn operator_type(op: &str, a: DataType, b: DataType) -> DataType {
+    match (op, a, b) {
+        (OP_CONCAT, ..) => DataType::Text,
+        (OP_AND | OP_OR, ..) => DataType::Bool,
+        (OP_BIT_AND | OP_BIT_OR | OP_SHIFT_LEFT | OP_SHIFT_RIGHT, ..) => DataType::Int64,
+
+        // if either operand is REAL, so is the result
+        (_, DataType::Float, _) | (_, _, DataType::Float) => DataType::Float,
+        (_, DataType::Null, other) | (_, other, DataType::Null) => other,
+        (_, DataType::Int, DataType::Int) => DataType::Int,
+        (
+            _,
+            DataType::Int | DataType::Int64 | DataType::Bool,
+            DataType::Int | DataType::Int64 | DataType::Bool,
+        ) => DataType::Int64,
+        (_, a, _) => a,
+    }
+}
+
+/// The type of the result of a built-in scalar function.
+enum FunctionType {
+    /// Always this type, and never `NULL`.
+    Fixed(DataType),
+    /// This type, and `NULL` if any argument is.
+    Strict(DataType),
+    /// This type, and possibly `NULL` for any arguments.
+    Nullable(DataType),
+    /// The type of the first argument, and `NULL` if any argument is.
+    FirstArg,
+    /// The type of the first argument, and possibly `NULL` for any arguments.
+    NullableFirstArg,
+}
+
+// https://www.sqlite.org/lang_corefunc.html
+// https://www.sqlite.org/lang_mathfunc.html
+// https://www.sqlite.org/json1.html
+fn function_type(name: &str) -> Option<FunctionType> {
+    use FunctionType::*;
+
+    Some(match name {
+        "changes" | "random" | "total_changes" => Fixed(DataType::Int64),
+        "char" | "concat" | "hex" | "json_array" | "json_object" | "json_quote" | "quote"
+        | "sqlite_version" | "typeof" => Fixed(DataType::Text),
+        "pi" => Fixed(DataType::Float),
+        "randomblob" | "zeroblob" => Fixed(DataType::Blob),
+
+        "instr" | "length" | "octet_length" => Strict(DataType::Int64),
+        "glob" | "json_valid" | "like" => Strict(DataType::Bool),
+        "concat_ws" | "format" | "json" | "lower" | "ltrim" | "printf" | "replace" | "rtrim"
+        | "soundex" | "substr" | "substring" | "trim" | "unistr" | "upper" => {
+            Strict(DataType::Text)
+        }
+        "degrees" | "radians" | "round" => Strict(DataType::Float),
+
+        "json_array_length" | "sign" | "unicode" => Nullable(DataType::Int64),
+        "json_type" => Nullable(DataType::Text),
+        "unhex" => Nullable(DataType::Blob),
+        // NULL for arguments outside of their domain
+        "acos" | "acosh" | "asin" | "asinh" | "atan" | "atan2" | "atanh" | "cos" | "cosh"
+        | "exp" | "ln" | "log" | "log10" | "log2" | "mod" | "pow" | "power" | "sin" | "sinh"
+        | "sqrt" | "tan" | "tanh" => Nullable(DataType::Float),
+
+        "abs" | "ceil" | "ceiling" | "floor" | "trunc" => FirstArg,
+        // NULL if any argument is
+        "max" | "min" | "nullif" => NullableFirstArg,
+
+        _ => return None,
+    })
+}
+
 fn root_block_columns(
     conn: &mut ConnectionState,
 ) -> Result<HashMap<(i64, i64), IntMap<ColumnType>>, Error> {
@@ -531,8 +602,15 @@ pub(super) fn explain(
                 OP_NOT_NULL => {
                     // goto <p2> or next instruction (depending on actual values)
 
+                    // unless the value is known to be NULL, it might not be
                     let might_branch = match state.mem.r.get(&p1) {
-                        Some(r_p1) => !matches!(r_p1.map_to_datatype(), DataType::Null),
+                        Some(r_p1) => !matches!(
+                            r_p1.map_to_columntype(),
+                            ColumnType::Single {
+                                datatype: DataType::Null,
+                                nullable: Some(true),
+                            }
+                        ),
                         _ => false,
                     };
 
@@ -1035,7 +1113,59 @@ pub(super) fn explain(
                             );
                         }
 
-                        _ => logger.add_unknown_operation(&program[state.mem.program_i]),
+                        function => {
+                            // e.g. `substr(3)`, or `-1` for a variable number of arguments
+                            let (name, nargs) = function.split_once('(').unwrap_or_default();
+                            let nargs: i64 = nargs.trim_end_matches(')').parse().unwrap_or(-1);
+
+                            let first_arg = state.mem.r.get(&p2).filter(|_| nargs != 0);
+
+                            // NULL if any argument is, which can only be proven not to be the case
+                            // if there's a fixed number of them
+                            let args_nullable: Vec<_> = (p2..p2
+                                + if nargs < 0 { 1 } else { nargs })
+                                .map(|r| state.mem.r.get(&r).and_then(|r| r.map_to_nullable()))
+                                .collect();
+
+                            let strict_nullable = if args_nullable.contains(&Some(true)) {
+                                Some(true)
+                            } else if nargs >= 0 && !args_nullable.contains(&None) {
+                                Some(false)
+                            } else {
+                                None
+                            };
+
+                            let value = match (function_type(name), first_arg) {
+                                (Some(FunctionType::Fixed(datatype)), _) => ColumnType::Single {
+                                    datatype,
+                                    nullable: Some(false),
+                                },
+                                (Some(FunctionType::Strict(datatype)), _) => ColumnType::Single {
+                                    datatype,
+                                    nullable: strict_nullable,
+                                },
+                                (Some(FunctionType::Nullable(datatype)), _) => ColumnType::Single {
+                                    datatype,
+                                    nullable: Some(true),
+                                },
+                                (Some(FunctionType::FirstArg), Some(arg)) => ColumnType::Single {
+                                    datatype: arg.map_to_datatype(),
+                                    nullable: strict_nullable,
+                                },
+                                (Some(FunctionType::NullableFirstArg), Some(arg)) => {
+                                    ColumnType::Single {
+                                        datatype: arg.map_to_datatype(),
+                                        nullable: Some(true),
+                                    }
+                                }
+                                _ => {
+                                    logger.add_unknown_operation(&program[state.mem.program_i]);
+                                    ColumnType::default()
+                                }
+                            };
+
+                            state.mem.r.insert(p3, RegDataType::Single(value));
+                        }
                     }
                 }
 
@@ -1100,6 +1230,31 @@ pub(super) fn explain(
                                 RegDataType::Single(ColumnType::Single { datatype, nullable }),
                             );
                         }
+                    } else if p4.starts_with("total(") {
+                        // total(_) -> REAL, 0.0 if there are no rows
+                        state.mem.r.insert(
+                            p3,
+                            RegDataType::Single(ColumnType::Single {
+                                datatype: DataType::Float,
+                                nullable: Some(false),
+                            }),
+                        );
+                    } else if p4.starts_with("avg(")
+                        || p4.starts_with("group_concat(")
+                        || p4.starts_with("string_agg(")
+                    {
+                        // avg(_) -> REAL, group_concat(_) -> TEXT
+                        let datatype = if p4.starts_with("avg(") {
+                            DataType::Float
+                        } else {
+                            DataType::Text
+                        };
+
+                        let nullable = state.mem.r.get(&p2).and_then(|r| r.map_to_nullable());
+                        state.mem.r.insert(
+                            p3,
+                            RegDataType::Single(ColumnType::Single { datatype, nullable }),
+                        );
                     } else if p4.starts_with("lead(") || p4.starts_with("lag(") {
                         if let Some(r_p2) = state.mem.r.get(&p2) {
                             let datatype = r_p2.map_to_datatype();
@@ -1134,6 +1289,15 @@ pub(super) fn explain(
                                 nullable: Some(false),
                             }),
                         );
+                    } else if p4.starts_with("total(") {
+                        // total(_) -> REAL, 0.0 if there are no rows
+                        state.mem.r.insert(
+                            p1,
+                            RegDataType::Single(ColumnType::Single {
+                                datatype: DataType::Float,
+                                nullable: Some(false),
+                            }),
+                        );
                     } else if p4.starts_with("percent_rank(") || p4.starts_with("cume_dist") {
                         // percent_rank(_) -> REAL
                         state.mem.r.insert(
@@ -1244,11 +1408,11 @@ pub(super) fn explain(
                     // r[p3] = r[p1] + r[p2]
                     let value = match (state.mem.r.get(&p1), state.mem.r.get(&p2)) {
                         (Some(a), Some(b)) => RegDataType::Single(ColumnType::Single {
-                            datatype: if matches!(a.map_to_datatype(), DataType::Null) {
-                                b.map_to_datatype()
-                            } else {
-                                a.map_to_datatype()
-                            },
+                            datatype: operator_type(
+                                opcode,
+                                a.map_to_datatype(),
+                                b.map_to_datatype(),
+                            ),
                             nullable: match (a.map_to_nullable(), b.map_to_nullable()) {
                                 (Some(a_n), Some(b_n)) => Some(a_n | b_n),
                                 (Some(a_n), None) => Some(a_n),
@@ -1256,14 +1420,16 @@ pub(super) fn explain(
                                 (None, None) => None,
                             },
                         }),
-                        (Some(v), None) => RegDataType::Single(ColumnType::Single {
-                            datatype: v.map_to_datatype(),
-                            nullable: None,
-                        }),
-                        (None, Some(v)) => RegDataType::Single(ColumnType::Single {
-                            datatype: v.map_to_datatype(),
-                            nullable: None,
-                        }),
+                        (Some(v), None) | (None, Some(v)) => {
+                            RegDataType::Single(ColumnType::Single {
+                                datatype: operator_type(
+                                    opcode,
+                                    v.map_to_datatype(),
+                                    DataType::Null,
+                                ),
+                                nullable: None,
+                            })
+                        }
                         _ => RegDataType::default(),
                     };
 
@@ -1,17 +1,20 @@
 use futures_core::future::BoxFuture;
+use futures_core::Stream;
 use futures_intrusive::sync::MutexGuard;
 use futures_util::future;
-use libsqlite3_sys::{sqlite3, sqlite3_progress_handler};
+use libsqlite3_sys::{sqlite3, sqlite3_libversion, sqlite3_progress_handler};
 use sqlx_core::common::StatementCache;
 use sqlx_core::error::Error;
-use sqlx_core::transaction::Transaction;
+use sqlx_core::transaction::{Transaction, TransactionOptions};
 use std::cmp::Ordering;
+use std::ffi::CStr;
 use std::fmt::{self, Debug, Formatter};
 use std::os::raw::{c_int, c_void};
 use std::panic::catch_unwind;
 use std::ptr::NonNull;
 
 use crate::connection::establish::EstablishParams;
+use crate::connection::update_hook::UpdateHookSubscribers;
 use crate::connection::worker::ConnectionWorker;
 use crate::options::OptimizeOnClose;
 use crate::statement::VirtualStatement;
@@ -22,6 +25,7 @@ use std::fmt::Write;
 pub(crate) use sqlx_core::connection::*;
 
 pub(crate) use handle::{ConnectionHandle, ConnectionHandleRaw};
+pub use update_hook::{SqliteOperation, SqliteUpdateEvent};
 
 pub(crate) mod collation;
 pub(crate) mod describe;
@@ -31,6 +35,8 @@ mod executor;
 mod explain;
 mod handle;
 mod intmap;
+mod serialize;
+mod update_hook;
 
 mod worker;
 
@@ -71,6 +77,9 @@ pub(crate) struct ConnectionState {
     /// Stores the progress handler set on the current connection. If the handler returns `false`,
     /// the query is interrupted.
     progress_handler_callback: Option<Handler>,
+
+    /// Receivers of row change events from the update hook, if it has been installed.
+    update_hook_subscribers: Option<Box<UpdateHookSubscribers>>,
 }
 
 impl ConnectionState {
@@ -112,6 +121,48 @@ impl SqliteConnection {
 
         Ok(LockedSqliteHandle { guard })
     }
+
+    /// Copy the contents of a database attached to this connection into a byte vector.
+    ///
+    /// `schema` is the name of the attached database to copy, or `main` if `None`.
+    ///
+    /// The result is the same as the contents of a database file, and can be written to disk or
+    /// loaded into new connections with
+    /// [`SqliteConnectOptions::deserialize_from()`][crate::SqliteConnectOptions::deserialize_from].
+    /// This is especially useful for snapshotting in-memory databases.
+    ///
+    /// See [`sqlite3_serialize()`](https://www.sqlite.org/c3ref/serialize.html) for details.
+    ///
+    /// Returns an error if the schema does not exist or the worker thread crashed.
+    pub async fn serialize(&mut self, schema: Option<&str>) -> Result<Vec<u8>, Error> {
+        let mut locked = self.lock_handle().await?;
+
+        serialize::serialize(&mut locked.guard.handle, schema.unwrap_or("main"))
+    }
+
+    /// Subscribe to row changes made through this connection.
+    ///
+    /// Returns a stream which yields a [`SqliteUpdateEvent`] for every row inserted, updated or
+    /// deleted by statements executed on this connection, as reported by
+    /// [`sqlite3_update_hook()`](https://www.sqlite.org/c3ref/update_hook.html).
+    /// This makes it easy to drive cache invalidation or UI refreshes from data changes.
+    ///
+    /// The stream can be polled independently of the connection and ends when the connection
+    /// is closed. Any number of streams may be active at once; dropping one unsubscribes it.
+    ///
+    /// ### Note
+    /// Events are emitted as soon as the change is made, which means changes that are later
+    /// rolled back are reported as well. Changes to `WITHOUT ROWID` tables, or rows deleted
+    /// by the truncate optimization, are not reported by SQLite.
+    ///
+    /// Returns an error if the worker thread crashed.
+    pub async fn updates(
+        &mut self,
+    ) -> Result<impl Stream<Item = SqliteUpdateEvent> + Send + Unpin + 'static, Error> {
+        let rx = self.lock_handle().await?.guard.subscribe_updates();
+
+        Ok(rx.into_stream())
+    }
 }
 
 impl Debug for SqliteConnection {
@@ -166,6 +217,16 @@ impl Connection for SqliteConnection {
         Transaction::begin(self)
     }
 
+    fn begin_with(
+        &mut self,
+        options: TransactionOptions,
+    ) -> BoxFuture<'_, Result<Transaction<'_, Self::Database>, Error>>
+    where
+        Self: Sized,
+    {
+        Transaction::begin_with(self, options)
+    }
+
     fn cached_statements_size(&self) -> usize {
         self.worker
             .shared
@@ -185,6 +246,18 @@ impl Connection for SqliteConnection {
         // No-op.
     }
 
+    /// The version of the SQLite library in use, which is shared by every connection.
+    fn server_version(&self) -> ServerVersion {
+        // SAFETY: returns a pointer to a static, nul-terminated string
+        let version = unsafe { CStr::from_ptr(sqlite3_libversion()) };
+
+        ServerVersion::parse(ServerFlavor::Sqlite, &version.to_string_lossy())
+    }
+
+    fn running_query(&self) -> Option<RunningQuery> {
+        Some(self.worker.shared.running_query.clone())
+    }
+
     #[doc(hidden)]
     fn flush(&mut self) -> BoxFuture<'_, Result<(), Error>> {
         // For SQLite, FLUSH does effectively nothing...
@@ -290,6 +363,7 @@ impl Drop for ConnectionState {
         // explicitly drop statements before the connection handle is dropped
         self.statements.clear();
         self.remove_progress_handler();
+        self.remove_update_hook();
     }
 }
 
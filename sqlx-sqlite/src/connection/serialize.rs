@@ -0,0 +1,105 @@
+use std::cmp;
+use std::ffi::CString;
+use std::io;
+use std::os::raw::c_void;
+use std::ptr;
+use std::slice;
+
+use libsqlite3_sys::{
+    sqlite3_deserialize, sqlite3_free, sqlite3_int64, sqlite3_malloc64, sqlite3_serialize,
+    SQLITE_DESERIALIZE_FREEONCLOSE, SQLITE_DESERIALIZE_READONLY, SQLITE_DESERIALIZE_RESIZEABLE,
+    SQLITE_OK,
+};
+
+use crate::connection::ConnectionHandle;
+use crate::error::Error;
+use crate::SqliteError;
+
+/// Copy the contents of the database `schema` (e.g. `main`) out into a `Vec<u8>`.
+///
+/// See <https://www.sqlite.org/c3ref/serialize.html>
+pub(crate) fn serialize(handle: &mut ConnectionHandle, schema: &str) -> Result<Vec<u8>, Error> {
+    let schema_c = schema_cstring(schema)?;
+    let mut size: sqlite3_int64 = 0;
+
+    // SAFETY: we have exclusive access to the database handle.
+    // With no flags set, SQLite returns a copy of the database allocated with `sqlite3_malloc64()`
+    // which we become responsible for freeing.
+    unsafe {
+        let data = sqlite3_serialize(handle.as_ptr(), schema_c.as_ptr(), &mut size, 0);
+
+        if data.is_null() {
+            return Err(err_protocol!(
+                "sqlite3_serialize() failed for schema {schema:?}: \
+                 the schema does not exist or SQLite ran out of memory"
+            ));
+        }
+
+        let len = usize::try_from(size).unwrap_or_default();
+        let bytes = slice::from_raw_parts(data, len).to_vec();
+
+        sqlite3_free(data as *mut c_void);
+
+        Ok(bytes)
+    }
+}
+
+/// Replace the contents of the database `schema` with a private, in-memory copy of `data`.
+///
+/// See <https://www.sqlite.org/c3ref/deserialize.html>
+pub(crate) fn deserialize(
+    handle: &mut ConnectionHandle,
+    schema: &str,
+    data: &[u8],
+    read_only: bool,
+) -> Result<(), Error> {
+    let schema_c = schema_cstring(schema)?;
+
+    let mut flags = SQLITE_DESERIALIZE_FREEONCLOSE;
+
+    flags |= if read_only {
+        SQLITE_DESERIALIZE_READONLY
+    } else {
+        SQLITE_DESERIALIZE_RESIZEABLE
+    };
+
+    let len = sqlite3_int64::try_from(data.len())
+        .map_err(|_| err_protocol!("serialized database is too large for SQLite"))?;
+
+    // SAFETY: we have exclusive access to the database handle.
+    // The buffer must be allocated by SQLite because it takes ownership of it with
+    // `SQLITE_DESERIALIZE_FREEONCLOSE`, and may reallocate it with `SQLITE_DESERIALIZE_RESIZEABLE`.
+    // If `sqlite3_deserialize()` fails, SQLite frees the buffer before returning.
+    unsafe {
+        // `sqlite3_malloc64(0)` returns NULL, so always request at least one byte
+        let buf = sqlite3_malloc64(cmp::max(data.len(), 1) as u64) as *mut u8;
+
+        if buf.is_null() {
+            return Err(Error::Io(io::Error::new(
+                io::ErrorKind::OutOfMemory,
+                "SQLite is unable to allocate memory to hold the deserialized database",
+            )));
+        }
+
+        ptr::copy_nonoverlapping(data.as_ptr(), buf, data.len());
+
+        let status = sqlite3_deserialize(
+            handle.as_ptr(),
+            schema_c.as_ptr(),
+            buf,
+            len,
+            len,
+            flags as u32,
+        );
+
+        if status != SQLITE_OK {
+            return Err(SqliteError::new(handle.as_ptr()).into());
+        }
+    }
+
+    Ok(())
+}
+
+fn schema_cstring(schema: &str) -> Result<CString, Error> {
+    CString::new(schema).map_err(|_| err_protocol!("schema name contains nul bytes"))
+}
@@ -0,0 +1,111 @@
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+
+use libsqlite3_sys::{
+    sqlite3_int64, sqlite3_update_hook, SQLITE_DELETE, SQLITE_INSERT, SQLITE_UPDATE,
+};
+
+use crate::connection::ConnectionState;
+
+/// The kind of change that triggered a [`SqliteUpdateEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SqliteOperation {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A row change reported by SQLite's [update hook](https://www.sqlite.org/c3ref/update_hook.html).
+///
+/// See [`SqliteConnection::updates()`][crate::SqliteConnection::updates] for details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SqliteUpdateEvent {
+    /// The name of the database containing the affected row, e.g. `main` or `temp`.
+    pub database: String,
+    /// The name of the table containing the affected row.
+    pub table: String,
+    /// The `rowid` of the affected row.
+    pub rowid: i64,
+    /// The kind of change made to the row.
+    pub operation: SqliteOperation,
+}
+
+/// The subscribers registered with the update hook of a connection.
+///
+/// Boxed and owned by [`ConnectionState`] so its address is stable while SQLite holds a pointer
+/// to it.
+pub(crate) struct UpdateHookSubscribers(Vec<flume::Sender<SqliteUpdateEvent>>);
+
+impl ConnectionState {
+    /// Register a new subscriber, installing the update hook if this is the first one.
+    pub(crate) fn subscribe_updates(&mut self) -> flume::Receiver<SqliteUpdateEvent> {
+        let (tx, rx) = flume::unbounded();
+
+        if let Some(subscribers) = &mut self.update_hook_subscribers {
+            subscribers.0.retain(|tx| !tx.is_disconnected());
+            subscribers.0.push(tx);
+            return rx;
+        }
+
+        let mut subscribers = Box::new(UpdateHookSubscribers(vec![tx]));
+
+        // SAFETY: the boxed subscriber list lives until the hook is removed in
+        // `remove_update_hook()`, which is also called when the connection state is dropped.
+        unsafe {
+            sqlite3_update_hook(
+                self.handle.as_ptr(),
+                Some(update_hook_callback),
+                &mut *subscribers as *mut UpdateHookSubscribers as *mut c_void,
+            );
+        }
+
+        self.update_hook_subscribers = Some(subscribers);
+
+        rx
+    }
+
+    /// Uninstalls the update hook and drops all subscribers, ending their streams.
+    pub(crate) fn remove_update_hook(&mut self) {
+        if self.update_hook_subscribers.take().is_some() {
+            // SAFETY: we have exclusive access to the database handle
+            unsafe {
+                sqlite3_update_hook(self.handle.as_ptr(), None, ptr::null_mut());
+            }
+        }
+    }
+}
+
+/// Invoked by SQLite on the worker thread for every row inserted, updated or deleted
+/// in a rowid table.
+extern "C" fn update_hook_callback(
+    data: *mut c_void,
+    op: c_int,
+    database: *const c_char,
+    table: *const c_char,
+    rowid: sqlite3_int64,
+) {
+    let operation = match op {
+        SQLITE_INSERT => SqliteOperation::Insert,
+        SQLITE_UPDATE => SqliteOperation::Update,
+        SQLITE_DELETE => SqliteOperation::Delete,
+        // not documented as possible, but we can't do anything useful with it
+        _ => return,
+    };
+
+    // SAFETY: `data` is the pointer registered in `subscribe_updates()`, and SQLite guarantees
+    // that `database` and `table` are valid, NUL-terminated UTF-8 strings for this call.
+    unsafe {
+        let subscribers = &mut *(data as *mut UpdateHookSubscribers);
+
+        let event = SqliteUpdateEvent {
+            database: CStr::from_ptr(database).to_string_lossy().into_owned(),
+            table: CStr::from_ptr(table).to_string_lossy().into_owned(),
+            rowid,
+            operation,
+        };
+
+        // drop any subscribers whose streams have gone away
+        subscribers.0.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
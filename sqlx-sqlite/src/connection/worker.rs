@@ -17,7 +17,7 @@ use sqlx_core::Either;
 use crate::connection::describe::describe;
 use crate::connection::establish::EstablishParams;
 use crate::connection::ConnectionState;
-use crate::connection::{execute, ConnectionHandleRaw};
+use crate::connection::{execute, ConnectionHandleRaw, RunningQuery};
 use crate::{Sqlite, SqliteArguments, SqliteQueryResult, SqliteRow, SqliteStatement};
 
 // Each SQLite connection has a dedicated thread.
@@ -37,6 +37,7 @@ pub(crate) struct ConnectionWorker {
 pub(crate) struct WorkerSharedState {
     pub(crate) cached_statements_size: AtomicUsize,
     pub(crate) conn: Mutex<ConnectionState>,
+    pub(crate) running_query: RunningQuery,
 }
 
 enum Command {
@@ -98,6 +99,7 @@ impl ConnectionWorker {
                     // and then immediately try to relock it; an unfair mutex would immediately
                     // grant us the lock even if another task is waiting.
                     conn: Mutex::new(conn, true),
+                    running_query: RunningQuery::default(),
                 });
                 let mut conn = shared.conn.try_lock().unwrap();
 
@@ -138,6 +140,8 @@ impl ConnectionWorker {
                             persistent,
                             tx,
                         } => {
+                            let _running_query = shared.running_query.start(&query);
+
                             let iter = match execute::iter(&mut conn, &query, arguments, persistent)
                             {
                                 Ok(iter) => iter,
@@ -30,6 +30,9 @@ impl Database for Sqlite {
     const NAME: &'static str = "SQLite";
 
     const URL_SCHEMES: &'static [&'static str] = &["sqlite"];
+
+    // `SQLITE_MAX_VARIABLE_NUMBER` for SQLite 3.32.0 and later
+    const BIND_LIMIT: usize = 32766;
 }
 
 impl<'r> HasValueRef<'r> for Sqlite {
@@ -33,7 +33,7 @@ use std::sync::atomic::AtomicBool;
 
 pub use arguments::{SqliteArgumentValue, SqliteArguments};
 pub use column::SqliteColumn;
-pub use connection::{LockedSqliteHandle, SqliteConnection};
+pub use connection::{LockedSqliteHandle, SqliteConnection, SqliteOperation, SqliteUpdateEvent};
 pub use database::Sqlite;
 pub use error::SqliteError;
 pub use options::{
@@ -96,6 +96,7 @@ impl_into_arguments_for_arguments!(SqliteArguments<'q>);
 impl_column_index_for_row!(SqliteRow);
 impl_column_index_for_statement!(SqliteStatement);
 impl_acquire!(Sqlite, SqliteConnection);
+impl_middleware_connection!(Sqlite);
 
 // required because some databases have a different handling of NULL
 impl_encode_for_option!(Sqlite);
@@ -12,6 +12,7 @@ pub use auto_vacuum::SqliteAutoVacuum;
 pub use journal_mode::SqliteJournalMode;
 pub use locking_mode::SqliteLockingMode;
 use std::cmp::Ordering;
+use std::fmt::{self, Debug, Formatter};
 use std::sync::Arc;
 use std::{borrow::Cow, time::Duration};
 pub use synchronous::SqliteSynchronous;
@@ -82,10 +83,25 @@ pub struct SqliteConnectOptions {
 
     pub(crate) optimize_on_close: OptimizeOnClose,
 
+    pub(crate) deserialize_from: Option<SerializedDatabase>,
+
     #[cfg(feature = "regexp")]
     pub(crate) register_regexp_function: bool,
 }
 
+/// The contents of a serialized database, as passed to
+/// [`SqliteConnectOptions::deserialize_from()`].
+#[derive(Clone)]
+pub(crate) struct SerializedDatabase(pub(crate) Arc<[u8]>);
+
+impl Debug for SerializedDatabase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SerializedDatabase")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum OptimizeOnClose {
     Enabled { analysis_limit: Option<u32> },
@@ -200,6 +216,7 @@ impl SqliteConnectOptions {
             command_channel_size: 50,
             row_channel_size: 50,
             optimize_on_close: OptimizeOnClose::Disabled,
+            deserialize_from: None,
             #[cfg(feature = "regexp")]
             register_regexp_function: false,
         }
@@ -524,6 +541,40 @@ impl SqliteConnectOptions {
         self
     }
 
+    /// Load the `main` database from the contents of a serialized database on connect, e.g. as
+    /// returned by [`SqliteConnection::serialize()`][crate::SqliteConnection::serialize].
+    ///
+    /// Every connection receives its own private, in-memory copy of the data, so changes made by
+    /// one connection are not visible to other connections nor written back anywhere. This makes
+    /// for cheap, isolated test databases cloned from a prepared template, or for shipping a
+    /// prebuilt dataset inside a binary with [`include_bytes!()`].
+    ///
+    /// Any file named by [`.filename()`][Self::filename] is replaced as the `main` database
+    /// and left untouched; use an in-memory database (the default) to avoid opening it at all.
+    /// If [`.read_only()`][Self::read_only] is set, the deserialized database is read-only as well.
+    ///
+    /// See [`sqlite3_deserialize()`](https://www.sqlite.org/c3ref/deserialize.html) for details.
+    ///
+    /// ```rust,no_run
+    /// # async fn example() -> sqlx::Result<()> {
+    /// use sqlx::ConnectOptions;
+    /// use sqlx::sqlite::SqliteConnectOptions;
+    ///
+    /// let dataset = std::fs::read("dataset.db")?;
+    ///
+    /// let conn = SqliteConnectOptions::new()
+    ///     .deserialize_from(dataset)
+    ///     .connect()
+    ///     .await?;
+    /// #
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn deserialize_from(mut self, data: impl Into<Arc<[u8]>>) -> Self {
+        self.deserialize_from = Some(SerializedDatabase(data.into()));
+        self
+    }
+
     /// Register a regexp function that allows using regular expressions in queries.
     ///
     /// ```
@@ -4,10 +4,12 @@
 use std::sync::Once;
 
 pub use sqlx_core::any::driver::install_drivers;
+pub use sqlx_core::any::query_with_values;
 
 pub use sqlx_core::any::{
-    Any, AnyArguments, AnyConnectOptions, AnyExecutor, AnyKind, AnyPoolOptions, AnyQueryResult,
-    AnyRow, AnyStatement, AnyTransactionManager, AnyTypeInfo, AnyValue, AnyValueRef,
+    Any, AnyArguments, AnyCapabilities, AnyConnectOptions, AnyExecutor, AnyKind, AnyOnConflict,
+    AnyPoolOptions, AnyQueryResult, AnyRow, AnyStatement, AnyTransactionManager, AnyTypeInfo,
+    AnyTypeInfoKind, AnyTypeRegistry, AnyValue, AnyValueKind, AnyValueRef,
 };
 
 pub(crate) mod reexports {
@@ -5,7 +5,9 @@ pub use sqlx_core::acquire::Acquire;
 pub use sqlx_core::arguments::{Arguments, IntoArguments};
 pub use sqlx_core::column::Column;
 pub use sqlx_core::column::ColumnIndex;
-pub use sqlx_core::connection::{ConnectOptions, Connection};
+pub use sqlx_core::connection::{
+    ConnectOptions, Connection, ReturningStatement, ServerFlavor, ServerVersion, UpsertSyntax,
+};
 pub use sqlx_core::database::{self, Database};
 pub use sqlx_core::describe::Describe;
 pub use sqlx_core::executor::{Execute, Executor};
@@ -14,10 +16,13 @@ pub use sqlx_core::pool::{self, Pool};
 pub use sqlx_core::query::{query, query_with};
 pub use sqlx_core::query_as::{query_as, query_as_with};
 pub use sqlx_core::query_builder::{self, QueryBuilder};
+pub use sqlx_core::query_grouped::collect_children;
 pub use sqlx_core::query_scalar::{query_scalar, query_scalar_with};
 pub use sqlx_core::row::Row;
 pub use sqlx_core::statement::Statement;
-pub use sqlx_core::transaction::{Transaction, TransactionManager};
+pub use sqlx_core::transaction::{
+    IsolationLevel, Transaction, TransactionManager, TransactionOptions,
+};
 pub use sqlx_core::type_info::TypeInfo;
 pub use sqlx_core::types::Type;
 pub use sqlx_core::value::{Value, ValueRef};
@@ -29,6 +34,38 @@ pub use sqlx_core::error::{self, Error, Result};
 #[cfg(feature = "migrate")]
 pub use sqlx_core::migrate;
 
+#[cfg(feature = "backfill")]
+pub use sqlx_core::backfill;
+
+#[cfg(feature = "outbox")]
+pub use sqlx_core::outbox;
+
+#[cfg(feature = "queue")]
+pub use sqlx_core::queue;
+
+pub use sqlx_core::buffer_pool;
+pub use sqlx_core::middleware;
+pub use sqlx_core::pagination;
+
+pub use sqlx_core::query_data;
+
+/// Running SQLx on an executor other than Tokio or async-std.
+///
+/// Implement [`Runtime`](rt::Runtime) for the executor and install it with
+/// [`install_runtime()`](rt::install_runtime); no `runtime-*` feature needs to be enabled.
+pub mod rt {
+    pub use sqlx_core::io::ReadBuf;
+    pub use sqlx_core::net::Socket;
+    pub use sqlx_core::rt::{install_runtime, Runtime};
+}
+
+/// Synchronization between processes using the database.
+pub mod sync {
+    pub use sqlx_core::distributed_lock::{
+        DistributedLock, DistributedLockGuard, DistributedLocking,
+    };
+}
+
 #[cfg(feature = "mysql")]
 #[cfg_attr(docsrs, doc(cfg(feature = "mysql")))]
 pub use sqlx_mysql::{self as mysql, MySql, MySqlConnection, MySqlExecutor, MySqlPool};
@@ -52,7 +89,7 @@ pub extern crate sqlx_macros;
 // derives
 #[cfg(feature = "macros")]
 #[doc(hidden)]
-pub use sqlx_macros::{FromRow, Type};
+pub use sqlx_macros::{FromRow, Id, Type};
 
 // We can't do our normal facade approach with an attribute, but thankfully we can now
 // have docs out-of-line quite easily.
@@ -81,6 +118,11 @@ pub mod ty_match;
 #[doc(hidden)]
 pub use sqlx_core::rt as __rt;
 
+// used by `#[derive(Id)]`
+#[cfg(all(feature = "macros", feature = "json"))]
+#[doc(hidden)]
+pub use sqlx_core::serde as __serde;
+
 /// Conversions between Rust and SQL types.
 ///
 /// To see how each SQL type maps to a Rust type, see the corresponding `types` module for each
@@ -130,6 +172,7 @@ pub use self::decode::Decode;
 pub mod query {
     pub use sqlx_core::query::{Map, Query};
     pub use sqlx_core::query_as::QueryAs;
+    pub use sqlx_core::query_grouped::Grouped;
     pub use sqlx_core::query_scalar::QueryScalar;
 }
 
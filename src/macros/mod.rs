@@ -100,6 +100,10 @@
 /// * Postgres: `$N` where `N` is the 1-based positional argument index
 /// * MySQL/SQLite: `?` which matches arguments in order that it appears in the query
 ///
+/// The number of arguments is checked against the default bind parameter limit of the database
+/// ([`Database::BIND_LIMIT`][crate::Database::BIND_LIMIT]). If your server is configured with a
+/// different limit, set the `SQLX_BIND_LIMIT` environment variable at build-time to override it.
+///
 /// ## Nullability: Bind Parameters
 /// For a given expected type `T`, both `T` and `Option<T>` are allowed (as well as either
 /// behind references). `Option::None` will be bound as `NULL`, so if binding a type behind `Option`
@@ -131,6 +135,10 @@
 /// `NULL` which then depends on the semantics of what functions are used. Consult the MySQL
 /// manual for the functions you are using to find the cases in which they return `NULL`.
 ///
+/// On MariaDB 10.5 and newer, `INSERT .. RETURNING` and `DELETE .. RETURNING` produce a record
+/// like a `SELECT` does, with the same nullability rules. The next value of a sequence,
+/// `NEXTVAL(seq)` or `NEXT VALUE FOR seq`, is never `NULL`.
+///
 /// For SQLite we perform a similar check to Postgres, looking for `NOT NULL` constraints
 /// on columns that come from tables. However, for SQLite we also can step through the output
 /// of `EXPLAIN` to identify columns that may or may not be `NULL`.
@@ -154,6 +162,16 @@
 /// Using `expr as _` simply signals to the macro to not type-check that bind expression,
 /// and then that syntax is stripped from the expression so as to not trigger type errors.
 ///
+/// No override is needed for identifiers using `#[derive(sqlx::Id)]`; they are typechecked
+/// as their inner type:
+///
+/// ```rust,ignore
+/// #[derive(sqlx::Id)]
+/// struct UserId(i64);
+///
+/// sqlx::query!("select * from users where id = $1", UserId(1))
+/// ```
+///
 /// **NOTE:** type ascription syntax (`expr: _`) is deprecated and will be removed in a
 /// future release. This is due to Rust's [RFC 3307](https://github.com/rust-lang/rfcs/pull/3307)
 /// officially dropping support for the syntax.
@@ -271,13 +289,92 @@
 ///
 /// ##### Overrides cheatsheet
 ///
-/// | Syntax    | Nullability     | Type       |
-/// | --------- | --------------- | ---------- |
-/// | `foo!`    | Forced not-null | Inferred   |
-/// | `foo?`    | Forced nullable | Inferred   |
-/// | `foo: T`  | Inferred        | Overridden |
-/// | `foo!: T` | Forced not-null | Overridden |
-/// | `foo?: T` | Forced nullable | Overridden |
+/// | Syntax      | Nullability     | Type                               |
+/// | ----------- | --------------- | ---------------------------------- |
+/// | `foo!`      | Forced not-null | Inferred                           |
+/// | `foo?`      | Forced nullable | Inferred                           |
+/// | `foo: T`    | Inferred        | Overridden                         |
+/// | `foo!: T`   | Forced not-null | Overridden                         |
+/// | `foo?: T`   | Forced nullable | Overridden                         |
+/// | `foo: time` | Inferred        | Inferred with `time` (or `chrono`) |
+///
+/// ## Dates and Times with both `chrono` and `time`
+/// If both features are enabled, dates and times are inferred as `chrono` types for Postgres and
+/// SQLite, and as `time` types for MySQL. The crate can be chosen for all queries in a `sqlx.toml`
+/// next to your `Cargo.toml`, or with the `SQLX_MACROS_DATETIME_CRATE` environment variable,
+/// which takes precedence:
+///
+/// ```toml
+/// [macros]
+/// datetime-crate = "time"
+/// ```
+///
+/// A single column can use the other crate by overriding its type with just the crate name,
+/// keeping the rest of the inference:
+///
+/// ```rust,ignore
+/// sqlx::query!(r#"select created_at as "created_at: time", updated_at from posts"#)
+/// ```
+///
+/// Bind parameters follow the same choice; bind the other crate's type with `value as _`.
+///
+/// ## Decimals with both `bigdecimal` and `rust_decimal`
+/// Likewise, `NUMERIC` and `DECIMAL` are inferred as `BigDecimal` if both features are enabled,
+/// unless `decimal-crate` under `[macros]` or the `SQLX_MACROS_DECIMAL_CRATE` environment variable
+/// says otherwise. A column can be overridden with `"price: rust_decimal"` or `"price: bigdecimal"`.
+///
+/// ```toml
+/// [macros]
+/// decimal-crate = "rust_decimal"
+/// ```
+///
+/// `rust_decimal::Decimal` holds at most 28 digits after the point, so other values are rounded
+/// when decoded. To get an error instead, set `exact_decimals` on the connect options of
+/// Postgres or MySQL.
+///
+/// ## Soft-Delete Lint
+/// If your crate has a `sqlx.toml` next to its `Cargo.toml` listing tables that use soft deletes,
+/// the query macros warn when a query selects from one of them (with `FROM` or `JOIN`) without
+/// filtering on `<column> IS NULL` or `<column> IS NOT NULL`:
+///
+/// ```toml
+/// [soft-delete]
+/// tables = ["users", "posts"]
+/// # optional, defaults to `deleted_at`
+/// column = "deleted_at"
+/// ```
+///
+/// `INSERT` and `DELETE` statements are not checked. If a query intentionally includes deleted
+/// rows, add `sqlx:include-deleted` to it in a comment to silence the warning. For queries built
+/// at runtime, see [`QueryBuilder::push_not_deleted()`][crate::QueryBuilder::push_not_deleted]
+/// and [`soft_delete_column!()`][crate::soft_delete_column].
+///
+/// ## Naming the Record
+/// The anonymous record can't be named, so it can't be returned from a function. Invoked as an
+/// item with `as` and a struct name, [query!] instead declares the struct, with a field for each
+/// column of the query typed as its record would be, and a `query()` function on it that runs the
+/// query and reads each row into the struct. Bind parameters become the arguments of `query()`,
+/// so they are given with their types:
+///
+/// ```rust,ignore
+/// sqlx::query!(
+///     /// An account and its email address.
+///     #[derive(Debug, Clone)]
+///     as pub AccountRecord,
+///     "SELECT id, name, email FROM accounts WHERE id = $1",
+///     id: i64,
+/// );
+///
+/// pub async fn account(conn: &mut PgConnection, id: i64) -> sqlx::Result<AccountRecord> {
+///     AccountRecord::query(id).fetch_one(conn).await
+/// }
+/// ```
+///
+/// The fields and `query()` have the same visibility as the struct. Nothing is derived unless you
+/// add the derives yourself, and the struct is documented with its query unless you document it.
+/// A borrowed argument must use the lifetime `'q` of the returned query, e.g. `name: &'q str`.
+/// Overrides are the same as for the anonymous record; wildcards are not allowed, as the struct
+/// needs a type for each column.
 ///
 /// ## Offline Mode (requires the `offline` feature)
 /// The macros can be configured to not require a live database connection for compilation,
@@ -293,6 +390,13 @@
 /// else it will still try to connect). To update the generated file simply run `cargo sqlx prepare`
 /// again.
 ///
+/// Setting `SQLX_OFFLINE=true` forces offline mode even if `DATABASE_URL` is set, and
+/// `SQLX_OFFLINE=error` also makes missing query data an error naming the file that was expected.
+///
+/// In sandboxed builds that don't allow the compiler to connect to the database, setting
+/// `SQLX_DESCRIBE_SOCKET` to the socket of a running `cargo sqlx describe-daemon` makes the macros
+/// describe their queries with the daemon instead.
+///
 /// To ensure that your `.sqlx` directory is kept up-to-date, both with the queries in your
 /// project and your database schema itself, run
 /// `cargo install sqlx-cli && cargo sqlx prepare --check` in your Continuous Integration script.
@@ -306,6 +410,10 @@
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query (
+    // `query!(as Name, ..)` is an item declaring the struct `Name` and `Name::query()`
+    ($(#[$meta:meta])* as $vis:vis $name:ident, $query:expr $(, $param:ident : $ty:ty)* $(,)?) => (
+        $crate::sqlx_macros::expand_query!(declare = { $(#[$meta])* $vis struct $name($($param: $ty),*) }, source = $query);
+    );
     // in Rust 1.45 we can now invoke proc macros in expression position
     ($query:expr) => ({
         $crate::sqlx_macros::expand_query!(source = $query)
@@ -504,6 +612,26 @@ macro_rules! query_file_unchecked (
 /// assert_eq!(record.id, MyInt4(1));
 /// ```
 ///
+/// ### Skipping Columns
+/// Every column of the query must have a field of the same name in the record struct, and every
+/// field a column, so a column added to a table breaks a `SELECT *` at compile time with an error
+/// like "struct `Account` has no field named `created_at`".
+///
+/// Columns which are intentionally not read can be listed with `skip(...)` before the query:
+///
+/// ```rust,ignore
+/// struct Account {
+///     id: i64,
+///     name: String,
+/// }
+///
+/// let account = sqlx::query_as!(Account, skip(created_at), "SELECT * FROM accounts WHERE id = $1", id)
+///     .fetch_one(&mut conn)
+///     .await?;
+/// ```
+///
+/// Naming a column that the query doesn't return is an error, so the list can't go stale.
+///
 /// ### Troubleshooting: "error: mismatched types"
 /// If you get a "mismatched types" error from an invocation of this macro and the error
 /// isn't pointing specifically at a parameter.
@@ -557,6 +685,12 @@ macro_rules! query_file_unchecked (
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_as (
+    ($out_struct:path, skip($($skip:ident),* $(,)?), $query:expr) => ( {
+        $crate::sqlx_macros::expand_query!(record = $out_struct, skip = [$($skip),*], source = $query)
+    });
+    ($out_struct:path, skip($($skip:ident),* $(,)?), $query:expr, $($args:tt)*) => ( {
+        $crate::sqlx_macros::expand_query!(record = $out_struct, skip = [$($skip),*], source = $query, args = [$($args)*])
+    });
     ($out_struct:path, $query:expr) => ( {
         $crate::sqlx_macros::expand_query!(record = $out_struct, source = $query)
     });
@@ -565,6 +699,104 @@ macro_rules! query_as (
     })
 );
 
+/// A variant of [query_as!] which groups joined rows into parent records with a list of children.
+///
+/// Each parent record has one `Vec` field holding its children, named along with the type of
+/// the child record after the parent record type. The columns for the children are selected with
+/// that field name as a prefix, e.g. `comments.body` for a field `comments` with a child
+/// having a field `body`. All other columns are read into the parent record.
+///
+/// Rows are grouped by the **first** column of the parent record, which should be its primary key.
+/// If the first column of the child is `NULL`, as with a parent without any children in a
+/// `LEFT JOIN`, the row adds no child. You may need to override the nullability of the other
+/// child columns with `!` in this case.
+///
+/// ```rust,ignore
+/// struct Post {
+///     id: i64,
+///     title: String,
+///     comments: Vec<Comment>,
+/// }
+///
+/// struct Comment {
+///     id: i64,
+///     body: String,
+/// }
+///
+/// let posts: Vec<Post> = sqlx::query_grouped_as!(
+///     Post,
+///     comments: Comment,
+///     r#"SELECT posts.id, posts.title, comments.id as "comments.id", comments.body as "comments.body!"
+///        FROM posts LEFT JOIN comments ON comments.post_id = posts.id
+///        WHERE posts.author_id = $1"#,
+///     author_id
+/// )
+///     .fetch_all(&mut conn)
+///     .await?;
+/// ```
+///
+/// As no parent is complete until every row has been read, only `.fetch_all()` is supported.
+///
+/// If you fetch the parents and children with separate queries instead, see
+/// [collect_children()][crate::collect_children].
+#[macro_export]
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+macro_rules! query_grouped_as (
+    ($out_struct:path, $field:ident: $child_struct:path, $query:expr) => ( {
+        $crate::sqlx_macros::expand_query!(record = $out_struct, group = $field: $child_struct, source = $query)
+    });
+    ($out_struct:path, $field:ident: $child_struct:path, $query:expr, $($args:tt)*) => ( {
+        $crate::sqlx_macros::expand_query!(record = $out_struct, group = $field: $child_struct, source = $query, args = [$($args)*])
+    })
+);
+
+/// A variant of [query!] with optional filters, each of which is appended to the query with a
+/// bind parameter for its value only if the value is `Some`.
+///
+/// The arguments of the query come first, then a `;` and the filters, as the SQL to append
+/// followed by `=>` and an `Option` of the value to bind after it:
+///
+/// ```rust,ignore
+/// let name: Option<&str> = None;
+/// let min_id: Option<i64> = Some(10);
+///
+/// // Postgres: `$1`, MySQL and SQLite: `?`
+/// let accounts = sqlx::query_filtered!(
+///     "SELECT id, name FROM accounts WHERE is_active = $1", true;
+///     " AND name = " => name,
+///     " AND id >= " => min_id,
+/// )
+///     // SELECT id, name FROM accounts WHERE is_active = $1 AND id >= $2
+///     .fetch_all(&mut conn)
+///     .await?;
+/// ```
+///
+/// Every combination of the filters is checked against the database at compile time, just as
+/// [query!] checks its query, and the one for the filters that are `Some` is executed. All of them
+/// must return the same columns, so a filter can only change which rows are returned. As each
+/// filter doubles the number of queries to check, at most 6 filters are supported. The combinations
+/// are all saved by `cargo sqlx prepare` for building offline.
+///
+/// For filters more complex than a single bind parameter at the end of the SQL, see
+/// [`QueryBuilder::push_maybe_bind()`][crate::QueryBuilder::push_maybe_bind], which builds the
+/// query at runtime without checking it.
+#[macro_export]
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+macro_rules! query_filtered (
+    ($query:expr $(, $args:expr)* ; $($filter:literal => $value:expr),* $(,)?) => ( {
+        $crate::sqlx_macros::expand_query!(source = $query, args = [$($args),*], filters = [$($filter => $value),*])
+    })
+);
+
+/// A variant of [query_filtered!] which reads the rows into a given record type, like [query_as!].
+#[macro_export]
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+macro_rules! query_filtered_as (
+    ($out_struct:path, $query:expr $(, $args:expr)* ; $($filter:literal => $value:expr),* $(,)?) => ( {
+        $crate::sqlx_macros::expand_query!(record = $out_struct, source = $query, args = [$($args),*], filters = [$($filter => $value),*])
+    })
+);
+
 /// Combines the syntaxes of [query_as!] and [query_file!].
 ///
 /// Enforces requirements of both macros; see them for details.
@@ -601,6 +833,12 @@ macro_rules! query_as (
 #[macro_export]
 #[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
 macro_rules! query_file_as (
+    ($out_struct:path, skip($($skip:ident),* $(,)?), $path:literal) => ( {
+        $crate::sqlx_macros::expand_query!(record = $out_struct, skip = [$($skip),*], source_file = $path)
+    });
+    ($out_struct:path, skip($($skip:ident),* $(,)?), $path:literal, $($args:tt)*) => ( {
+        $crate::sqlx_macros::expand_query!(record = $out_struct, skip = [$($skip),*], source_file = $path, args = [$($args)*])
+    });
     ($out_struct:path, $path:literal) => ( {
         $crate::sqlx_macros::expand_query!(record = $out_struct, source_file = $path)
     });
@@ -758,7 +996,9 @@ macro_rules! query_file_scalar_unchecked (
 /// }
 /// ```
 ///
-/// You can run `sqlx migrate build-script` to generate this file automatically.
+/// You can run `sqlx migrate build-script` to generate this file automatically. Pass
+/// `--watch fixtures` to also watch other directories, and `--validate` to have it fail the
+/// build if a migration is misnamed or shares its version with another.
 ///
 /// See: [The Cargo Book: 3.8 Build Scripts; Outputs of the Build Script](https://doc.rust-lang.org/stable/cargo/reference/build-scripts.html#outputs-of-the-build-script)
 ///
@@ -801,3 +1041,21 @@ macro_rules! migrate {
         $crate::sqlx_macros::migrate!("./migrations")
     }};
 }
+
+/// The column marking soft-deleted rows, set with `column` under `[soft-delete]` in the
+/// `sqlx.toml` next to your crate's `Cargo.toml`, as a string literal. Defaults to `"deleted_at"`.
+///
+/// For filtering queries built at runtime consistently with the soft-delete lint of the query
+/// macros (see [`query!()`][crate::query]):
+///
+/// ```rust,ignore
+/// let mut query_builder: QueryBuilder<Postgres> = QueryBuilder::new("SELECT * FROM users u WHERE ");
+/// query_builder.push_not_deleted("u", sqlx::soft_delete_column!());
+/// ```
+#[macro_export]
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+macro_rules! soft_delete_column (
+    () => ({
+        $crate::sqlx_macros::expand_soft_delete_column!()
+    })
+);
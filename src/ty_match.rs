@@ -118,6 +118,45 @@ impl<T, U> MatchBorrowExt for &'_ MatchBorrow<T, U> {
     type Matched = U;
 }
 
+// Newtypes deriving `Id` are accepted wherever their inner type is expected, so a parameter
+// expecting `i64` can be given a `UserId`, `&UserId` or `Option<UserId>`.
+
+pub trait IdType {
+    type Inner;
+}
+
+impl<'a, T: IdType> IdType for &'a T {
+    type Inner = &'a T::Inner;
+}
+
+impl<T: IdType> IdType for Option<T> {
+    type Inner = Option<T::Inner>;
+}
+
+pub struct MatchId<T>(PhantomData<T>);
+
+impl<T> MatchId<T> {
+    pub fn new(_arg: &T) -> Self {
+        MatchId(PhantomData)
+    }
+}
+
+pub trait MatchIdExt: Sized {
+    type Inner;
+
+    fn match_id(self) -> Self::Inner {
+        panic!("only for type resolution")
+    }
+}
+
+impl<T: IdType> MatchIdExt for MatchId<T> {
+    type Inner = T::Inner;
+}
+
+impl<T> MatchIdExt for &'_ MatchId<T> {
+    type Inner = T;
+}
+
 pub fn conjure_value<T>() -> T {
     panic!()
 }
@@ -146,6 +185,23 @@ fn test_wrap_same() {
     }
 }
 
+#[test]
+fn test_match_id() {
+    struct UserId(i64);
+
+    impl IdType for UserId {
+        type Inner = i64;
+    }
+
+    if false {
+        let _: i64 = MatchId::new(&UserId(0)).match_id();
+        let _: &i64 = MatchId::new(&&UserId(0)).match_id();
+        let _: Option<i64> = MatchId::new(&Some(UserId(0))).match_id();
+        let _: i64 = MatchId::new(&0i64).match_id();
+        let _: Option<String> = MatchId::new(&Some(String::new())).match_id();
+    }
+}
+
 #[test]
 fn test_match_borrow() {
     if false {
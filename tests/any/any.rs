@@ -1,5 +1,5 @@
-use sqlx::any::AnyRow;
-use sqlx::{Any, Connection, Executor, Row};
+use sqlx::any::{AnyConnectOptions, AnyRow};
+use sqlx::{Any, AnyConnection, Connection, Executor, Row};
 use sqlx_test::new;
 
 #[sqlx_macros::test]
@@ -20,6 +20,22 @@ async fn it_connects() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_connects_with_jdbc_url() -> anyhow::Result<()> {
+    sqlx::any::install_default_drivers();
+
+    let url = format!("jdbc:{}", std::env::var("DATABASE_URL")?);
+    let options: AnyConnectOptions = url.parse()?;
+    let backend_name = options.backend_name()?;
+
+    let conn = AnyConnection::connect_with(&options).await?;
+    assert_eq!(conn.backend_name(), backend_name);
+
+    conn.close().await?;
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_pings() -> anyhow::Result<()> {
     sqlx::any::install_default_drivers();
@@ -141,3 +157,199 @@ async fn it_can_fail_and_recover_with_pool() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_decodes_dynamic_values() -> anyhow::Result<()> {
+    use sqlx::any::{AnyArguments, AnyTypeInfoKind, AnyValueKind};
+    use sqlx::{Column, TypeInfo, Value, ValueRef};
+
+    sqlx::any::install_default_drivers();
+
+    let mut conn = new::<Any>().await?;
+
+    let row = conn.fetch_one("SELECT NULL").await?;
+    assert!(row.try_get_raw(0)?.is_null());
+    assert_eq!(row.try_get::<Option<i32>, _>(0)?, None);
+
+    let sql = match conn.backend_name() {
+        "PostgreSQL" => {
+            "SELECT 12345.6789::numeric, '2024-02-29'::date, '12:34:56.5'::time, \
+             '2024-02-29 12:34:56.5'::timestamp, '2024-02-29 13:34:56.5+01'::timestamptz, \
+             '67e55044-10b1-426f-9247-bb680e5fe0c8'::uuid, '{\"a\": [1]}'::jsonb, \
+             ARRAY[1, NULL, 3]::int4[]"
+        }
+        "MySQL" => {
+            "SELECT CAST(12345.6789 AS DECIMAL(9, 4)), DATE '2024-02-29', TIME '12:34:56.5', \
+             CAST('2024-02-29 12:34:56.5' AS DATETIME(1))"
+        }
+        _ => {
+            // SQLite only has the standard kinds, but columns keep their declared types
+            conn.execute("CREATE TEMPORARY TABLE dynamic_values (d DATE, ts DATETIME)")
+                .await?;
+            conn.execute("INSERT INTO dynamic_values VALUES ('2024-02-29', '2024-02-29 12:34:56')")
+                .await?;
+
+            let row = conn.fetch_one("SELECT d, ts FROM dynamic_values").await?;
+            assert_eq!(row.columns()[0].type_info().kind(), AnyTypeInfoKind::Date);
+            assert_eq!(
+                row.columns()[1].type_info().kind(),
+                AnyTypeInfoKind::Timestamp
+            );
+            assert_eq!(
+                row.try_get_raw(0)?.kind(),
+                &AnyValueKind::Text("2024-02-29".into())
+            );
+
+            return Ok(());
+        }
+    };
+
+    // the simple query protocol returns text and prepared statements return binary values
+    for row in [
+        conn.fetch_one(sql).await?,
+        conn.fetch_one(sqlx::query_with(sql, AnyArguments::default()))
+            .await?,
+    ] {
+        let values: Vec<_> = (0..row.len())
+            .map(|i| row.try_get_raw(i).map(|value| ValueRef::to_owned(&value)))
+            .collect::<Result<_, _>>()?;
+
+        let expected = [
+            "12345.6789",
+            "2024-02-29",
+            "12:34:56.5",
+            "2024-02-29 12:34:56.5",
+            "2024-02-29 12:34:56.5+00:00",
+            "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "{\"a\": [1]}",
+            "[1,null,3]",
+        ];
+
+        for (value, expected) in values.iter().zip(expected) {
+            assert_eq!(
+                value.kind().to_string(),
+                expected,
+                "{}",
+                value.type_info().name()
+            );
+        }
+
+        assert_eq!(values[1].kind(), &AnyValueKind::Date(19_782));
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_decodes_registered_types() -> anyhow::Result<()> {
+    use sqlx::any::{AnyArguments, AnyTypeInfoKind, AnyTypeRegistry};
+    use sqlx::ValueRef;
+
+    sqlx::any::install_default_drivers();
+
+    let registry = AnyTypeRegistry::new()
+        .register("xml", AnyTypeInfoKind::Text)
+        .register("year", AnyTypeInfoKind::SmallInt);
+
+    let url = std::env::var("DATABASE_URL")?;
+    let options = url.parse::<AnyConnectOptions>()?;
+
+    let mut conn = AnyConnection::connect_with(&options).await?;
+
+    let (sql, expected) = match conn.backend_name() {
+        "PostgreSQL" => ("SELECT '<a/>'::xml", "<a/>"),
+        "MySQL" => {
+            conn.execute("CREATE TEMPORARY TABLE registered_types (y YEAR)")
+                .await?;
+            conn.execute("INSERT INTO registered_types VALUES (2024)")
+                .await?;
+
+            ("SELECT y FROM registered_types", "2024")
+        }
+        // SQLite maps every type
+        _ => return Ok(()),
+    };
+
+    let err = conn.fetch_one(sql).await.err().unwrap();
+    assert!(err.to_string().contains("AnyTypeRegistry"), "{err}");
+
+    conn.set_type_registry(registry.clone());
+
+    let row = conn.fetch_one(sql).await?;
+    assert_eq!(row.try_get_raw(0)?.kind().to_string(), expected);
+
+    // the registry can also be set when connecting
+    let mut conn = AnyConnection::connect_with(&options.type_registry(registry)).await?;
+
+    let row = conn
+        .fetch_one(sqlx::query_with(sql, AnyArguments::default()))
+        .await?;
+    assert_eq!(row.try_get_raw(0)?.kind().to_string(), expected);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_binds_values() -> anyhow::Result<()> {
+    use sqlx::any::{AnyValue, AnyValueKind};
+
+    sqlx::any::install_default_drivers();
+
+    let pool = sqlx_test::pool::<Any>().await?;
+    let conn = pool.acquire().await?;
+
+    let sql = match conn.backend_name() {
+        "PostgreSQL" => "SELECT $1::int8 + 1, $2::text, $3::int8",
+        _ => "SELECT ? + 1, ?, ?",
+    };
+
+    drop(conn);
+
+    let values = vec![
+        AnyValue::new(AnyValueKind::BigInt(41)),
+        AnyValue::new(AnyValueKind::Text("forty-two".into())),
+        AnyValue::new(AnyValueKind::Null),
+    ];
+
+    let row = sqlx::any::query_with_values(sql, values)
+        .fetch_one(&pool)
+        .await?;
+
+    assert_eq!(row.try_get::<i64, _>(0)?, 42);
+    assert_eq!(row.try_get::<String, _>(1)?, "forty-two");
+
+    // `AnyValue` decodes any column losslessly
+    let value: AnyValue = row.try_get(2)?;
+    assert_eq!(value.kind(), &AnyValueKind::Null);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_rejects_values_out_of_range() -> anyhow::Result<()> {
+    use sqlx::any::{AnyValue, AnyValueKind};
+
+    sqlx::any::install_default_drivers();
+
+    let mut conn = new::<Any>().await?;
+
+    let (sql, value) = match conn.backend_name() {
+        "PostgreSQL" => ("SELECT $1::numeric", AnyValueKind::Numeric("1.2.3".into())),
+        // 10000-01-01
+        "MySQL" => ("SELECT ?", AnyValueKind::Date(2_932_897)),
+        // SQLite binds dates and decimals as text
+        _ => return Ok(()),
+    };
+
+    let err = sqlx::any::query_with_values(sql, vec![AnyValue::new(value)])
+        .execute(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, sqlx::Error::Encode(_)), "{err}");
+
+    // nothing was sent, so the connection is still usable
+    conn.execute("SELECT 1").await?;
+
+    Ok(())
+}
@@ -216,3 +216,132 @@ async fn test_pool_callbacks() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn pool_should_scale_up_when_acquire_is_slow() -> anyhow::Result<()> {
+    sqlx::any::install_default_drivers();
+
+    // every acquire takes longer than zero, so the pool should keep growing until `max`
+    let pool = AnyPoolOptions::new()
+        .autoscale(1, 3, Duration::ZERO)
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    let _a = pool.acquire().await?;
+    let _b = pool.acquire().await?;
+
+    // connections are opened in the background
+    for _ in 0..50 {
+        if pool.size() == 3 {
+            break;
+        }
+
+        sqlx_core::rt::sleep(Duration::from_millis(20)).await;
+    }
+
+    assert_eq!(pool.size(), 3);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn pool_should_report_connection_leaks() -> anyhow::Result<()> {
+    sqlx::any::install_default_drivers();
+
+    let leaks = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+    let pool = AnyPoolOptions::new()
+        .leak_detection_threshold(Duration::from_millis(50))
+        .on_connection_leak({
+            let leaks = leaks.clone();
+            move |leak| leaks.lock().unwrap().push(leak.clone())
+        })
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    // returned in time
+    drop(pool.acquire().await?);
+
+    let conn = pool.acquire().await?;
+    sqlx_core::rt::sleep(Duration::from_millis(200)).await;
+
+    {
+        let leaks = leaks.lock().unwrap();
+        assert_eq!(leaks.len(), 1, "the leak should be reported once");
+        assert!(leaks[0].held_for >= Duration::from_millis(50));
+        assert!(!leaks[0].pool_closed);
+    }
+
+    // reported again when closing the pool, which waits for the connection
+    let close = pool.close();
+    assert!(leaks.lock().unwrap()[1].pool_closed);
+
+    drop(conn);
+    close.await;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn pool_should_report_state_on_timeout() -> anyhow::Result<()> {
+    sqlx::any::install_default_drivers();
+
+    // without leak detection, checkouts aren't tracked
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_millis(100))
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    assert!(pool.last_timeout_report().is_none());
+
+    let conn = pool.acquire().await?;
+
+    assert!(matches!(
+        pool.acquire().await,
+        Err(sqlx::Error::PoolTimedOut)
+    ));
+
+    let report = pool
+        .last_timeout_report()
+        .expect("the timeout should be reported");
+    assert_eq!(report.size, 1);
+    assert_eq!(report.num_idle, 0);
+    assert_eq!(report.num_waiting, 1);
+    assert_eq!(report.oldest_checkout, None);
+    assert!(report.running_queries.is_empty());
+
+    // the last waiter to time out leaves its report
+    let (a, b) = futures::join!(pool.acquire(), async {
+        sqlx_core::rt::sleep(Duration::from_millis(50)).await;
+        pool.acquire().await
+    });
+
+    assert!(matches!(a, Err(sqlx::Error::PoolTimedOut)));
+    assert!(matches!(b, Err(sqlx::Error::PoolTimedOut)));
+    assert_eq!(pool.last_timeout_report().unwrap().num_waiting, 1);
+
+    drop(conn);
+
+    // with leak detection
+    let pool = AnyPoolOptions::new()
+        .max_connections(1)
+        .acquire_timeout(Duration::from_millis(100))
+        .leak_detection_threshold(Duration::from_secs(60))
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    let conn = pool.acquire().await?;
+
+    assert!(matches!(
+        pool.acquire().await,
+        Err(sqlx::Error::PoolTimedOut)
+    ));
+
+    let report = pool.last_timeout_report().unwrap();
+    assert!(report.oldest_checkout >= Some(Duration::from_millis(100)));
+
+    drop(conn);
+
+    Ok(())
+}
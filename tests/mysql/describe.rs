@@ -1,5 +1,5 @@
 use sqlx::mysql::MySql;
-use sqlx::{Column, Executor, Type, TypeInfo};
+use sqlx::{Column, Connection, Executor, ServerFlavor, Type, TypeInfo};
 use sqlx_test::new;
 
 #[sqlx_macros::test]
@@ -69,3 +69,75 @@ async fn uses_alias_name() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_describes_outer_join_nullable() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let d = conn
+        .describe("SELECT t1.id, t2.id FROM tweet t1 LEFT JOIN tweet t2 ON false")
+        .await?;
+
+    assert_eq!(d.nullable(0), Some(false));
+    assert_eq!(d.nullable(1), Some(true));
+
+    let d = conn
+        .describe("SELECT t1.id, t2.id FROM tweet t1 RIGHT JOIN tweet t2 ON false")
+        .await?;
+
+    assert_eq!(d.nullable(0), Some(true));
+    assert_eq!(d.nullable(1), Some(false));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_describes_aggregate_nullable() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // without `GROUP BY`, aggregates are NULL if there are no rows
+    let d = conn
+        .describe("SELECT MAX(id), MIN(id) AS min_id, COUNT(*) FROM tweet")
+        .await?;
+
+    assert_eq!(d.nullable(0), Some(true));
+    assert_eq!(d.nullable(1), Some(true));
+    assert_eq!(d.nullable(2), Some(false));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_describes_mariadb_returning_and_sequences() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // MariaDB 10.5+
+    if conn.server_version().flavor != ServerFlavor::MariaDb
+        || !conn.server_version().at_least(10, 5, 0)
+    {
+        return Ok(());
+    }
+
+    conn.execute("CREATE TEMPORARY SEQUENCE describe_seq")
+        .await?;
+
+    let d = conn
+        .describe("INSERT INTO tweet (text) VALUES (?) RETURNING id, text, owner_id")
+        .await?;
+
+    assert_eq!(d.columns().len(), 3);
+    assert_eq!(d.column(0).name(), "id");
+    assert_eq!(d.nullable(0), Some(false));
+    assert_eq!(d.nullable(1), Some(false));
+    assert_eq!(d.nullable(2), Some(true));
+
+    let d = conn
+        .describe("SELECT NEXTVAL(describe_seq), NEXT VALUE FOR describe_seq AS id")
+        .await?;
+
+    assert_eq!(d.column(0).type_info().name(), "BIGINT");
+    assert_eq!(d.nullable(0), Some(false));
+    assert_eq!(d.nullable(1), Some(false));
+
+    Ok(())
+}
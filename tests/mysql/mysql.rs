@@ -1,6 +1,11 @@
 use futures::TryStreamExt;
-use sqlx::mysql::{MySql, MySqlConnection, MySqlPool, MySqlPoolOptions, MySqlRow};
-use sqlx::{Column, Connection, Executor, Row, Statement, TypeInfo};
+use sqlx::mysql::{
+    MySql, MySqlConnectOptions, MySqlConnection, MySqlPool, MySqlPoolOptions, MySqlRow,
+};
+use sqlx::{
+    Column, Connection, Executor, QueryBuilder, ReturningStatement, Row, ServerFlavor, Statement,
+    TypeInfo,
+};
 use sqlx_test::{new, setup_if_needed};
 use std::env;
 
@@ -98,6 +103,28 @@ async fn it_executes_with_pool() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_executes_with_many_argument_sets() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE batch (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+        .await?;
+
+    let result = sqlx::query("INSERT INTO batch (id, name) VALUES (?, ?)")
+        .execute_many_args(&mut conn, (0..100_i32).map(|id| (id, format!("row {id}"))))
+        .await?;
+
+    assert_eq!(result.rows_affected(), 100);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM batch")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 100);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_works_with_cache_disabled() -> anyhow::Result<()> {
     setup_if_needed();
@@ -388,6 +415,32 @@ async fn test_issue_622() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_can_begin_with_transaction_options() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE _sqlx_read_only (id INTEGER PRIMARY KEY)")
+        .await?;
+
+    let options = sqlx::TransactionOptions::new()
+        .isolation(sqlx::IsolationLevel::Serializable)
+        .read_only(true);
+
+    let mut tx = conn.begin_with(options).await?;
+
+    sqlx::query("SELECT COUNT(*) FROM _sqlx_read_only")
+        .fetch_one(&mut *tx)
+        .await?;
+
+    tx.rollback().await?;
+
+    // options can't be applied to a savepoint
+    let mut tx = conn.begin().await?;
+    assert!(tx.begin_with(options).await.is_err());
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_can_work_with_transactions() -> anyhow::Result<()> {
     let mut conn = new::<MySql>().await?;
@@ -477,3 +530,225 @@ async fn test_shrink_buffers() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_counts_rows_with_returning() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    // MariaDB 10.5+
+    if !conn
+        .server_version()
+        .supports_returning(ReturningStatement::Insert)
+    {
+        return Ok(());
+    }
+
+    conn.execute(
+        "CREATE TEMPORARY TABLE users (id INTEGER PRIMARY KEY AUTO_INCREMENT, name TEXT NOT NULL)",
+    )
+    .await?;
+
+    let ids: Vec<i32> = sqlx::query_scalar("INSERT INTO users (name) VALUES (?), (?) RETURNING id")
+        .bind("alice")
+        .bind("bob")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(ids, [1, 2]);
+
+    let done = conn.execute("DELETE FROM users RETURNING id").await?;
+    assert_eq!(done.rows_affected(), 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_fetches_returning_columns() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        "CREATE TEMPORARY TABLE users (id INTEGER PRIMARY KEY AUTO_INCREMENT, name TEXT NOT NULL)",
+    )
+    .await?;
+
+    // emulated with `LAST_INSERT_ID()` on MySQL
+    let rows = QueryBuilder::<MySql>::new("INSERT INTO users (name) VALUES (")
+        .push_bind("alice")
+        .push(")")
+        .fetch_returning(&mut conn, &["id", "name"])
+        .await?;
+
+    let rows: Vec<(i32, String)> = rows
+        .iter()
+        .map(|row| (row.get("id"), row.get("name")))
+        .collect();
+
+    assert_eq!(rows, [(1, "alice".into())]);
+
+    // the keys of a multi-row insert are only known to be contiguous without "interleaved" locking
+    let lock_mode: i64 = sqlx::query_scalar("SELECT CAST(@@innodb_autoinc_lock_mode AS SIGNED)")
+        .fetch_one(&mut conn)
+        .await?;
+
+    let result = QueryBuilder::<MySql>::new("INSERT INTO users (name) ")
+        .push_values(["bob", "carol"], |mut b, name| {
+            b.push_bind(name);
+        })
+        .fetch_returning(&mut conn, &["id", "name"])
+        .await;
+
+    if lock_mode < 2 {
+        let names: Vec<String> = result?.iter().map(|row| row.get("name")).collect();
+        assert_eq!(names, ["bob", "carol"]);
+    } else {
+        assert!(matches!(result, Err(sqlx::Error::Configuration(_))));
+
+        // and the insert is rolled back
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&mut conn)
+            .await?;
+        assert_eq!(count, 1);
+    }
+
+    // the keys aren't consecutive if `auto_increment_increment` isn't 1
+    conn.execute("SET SESSION auto_increment_increment = 5")
+        .await?;
+
+    let rows = QueryBuilder::<MySql>::new("INSERT INTO users (name) VALUES (")
+        .push_bind("dave")
+        .push(")")
+        .fetch_returning(&mut conn, &["id", "name"])
+        .await?;
+
+    let names: Vec<String> = rows.iter().map(|row| row.get("name")).collect();
+
+    assert_eq!(names, ["dave"]);
+
+    conn.execute("SET SESSION auto_increment_increment = 1")
+        .await?;
+
+    // updated rows keep their keys
+    let result = QueryBuilder::<MySql>::new("INSERT INTO users (id, name) VALUES (1, ")
+        .push_bind("erin")
+        .push(") ON DUPLICATE KEY UPDATE name = VALUES(name)")
+        .fetch_returning(&mut conn, &["id", "name"])
+        .await;
+
+    if !conn
+        .server_version()
+        .supports_returning(ReturningStatement::Insert)
+    {
+        assert!(matches!(result, Err(sqlx::Error::Configuration(_))));
+    }
+
+    // not emulated for other statements
+    if !conn
+        .server_version()
+        .supports_returning(ReturningStatement::Delete)
+    {
+        let result = QueryBuilder::<MySql>::new("DELETE FROM users")
+            .fetch_returning(&mut conn, &["id"])
+            .await;
+
+        assert!(matches!(result, Err(sqlx::Error::Configuration(_))));
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_distributed_lock() -> anyhow::Result<()> {
+    let pool = MySqlPoolOptions::new()
+        .max_connections(2)
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    let lock = sqlx::sync::DistributedLock::new(&pool, "sqlx-mysql-tests-leader")
+        .retry_interval(std::time::Duration::from_millis(10));
+
+    let mut leader = lock.try_acquire().await?.expect("lock should be free");
+    assert!(leader.is_held().await?);
+
+    // another process can't acquire it
+    assert!(lock.try_acquire().await?.is_none());
+
+    assert!(leader.release().await?);
+
+    // dropping the guard releases the lock too, once the server notices the connection closed
+    drop(lock.acquire().await?);
+
+    let mut leader = lock.acquire().await?;
+    assert!(leader.is_held().await?);
+    assert!(leader.release().await?);
+
+    pool.close().await;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_decodes_mariadb_inet6() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let version = conn.server_version();
+    if version.flavor != ServerFlavor::MariaDb || !version.at_least(10, 5, 0) {
+        return Ok(());
+    }
+
+    let addr: std::net::Ipv6Addr = "2001:db8::1".parse()?;
+
+    let decoded: std::net::Ipv6Addr = sqlx::query_scalar("SELECT CAST(? AS INET6)")
+        .bind(addr)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(decoded, addr);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_rejects_rows_over_the_size_limits() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let options = env::var("DATABASE_URL")?
+        .parse::<MySqlConnectOptions>()?
+        .max_row_size(1024)
+        .max_result_size(64 * 1024);
+
+    let mut conn = MySqlConnection::connect_with(&options).await?;
+
+    let res = sqlx::query_scalar::<_, String>("SELECT REPEAT('x', ?) UNION ALL SELECT 'y'")
+        .bind(4096_i32)
+        .fetch_all(&mut conn)
+        .await;
+    assert!(matches!(
+        res,
+        Err(sqlx::Error::RowTooLarge { limit: 1024, .. })
+    ));
+
+    // the rest of the rows are skipped, and the connection stays usable
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    let res = conn
+        .fetch_all(
+            "WITH RECURSIVE n (i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 900) \
+             SELECT REPEAT('x', 100) FROM n",
+        )
+        .await;
+    assert!(matches!(
+        res,
+        Err(sqlx::Error::ResultTooLarge { limit: 65536 })
+    ));
+
+    // a query can opt out of the limits
+    let value: String = sqlx::query_scalar("SELECT REPEAT('x', ?)")
+        .bind(4096_i32)
+        .without_size_limits()
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value.len(), 4096);
+
+    Ok(())
+}
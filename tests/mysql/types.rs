@@ -18,9 +18,38 @@ test_type!(i16(MySql, "21415" == 21415_i16));
 test_type!(u32(MySql, "CAST(2141512 AS UNSIGNED)" == 2141512_u32));
 test_type!(i32(MySql, "2141512" == 2141512_i32));
 
-test_type!(u64(MySql, "CAST(2141512 AS UNSIGNED)" == 2141512_u64));
+test_type!(u64(
+    MySql,
+    "CAST(2141512 AS UNSIGNED)" == 2141512_u64,
+    "CAST(18446744073709551615 AS UNSIGNED)" == u64::MAX
+));
 test_type!(i64(MySql, "2141512" == 2141512_i64));
 
+#[sqlx_macros::test]
+async fn test_checked_integer_conversions() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    let sql = "SELECT CAST(18446744073709551615 AS UNSIGNED), CAST(-1 AS SIGNED), 42";
+
+    // BINARY
+    let row = sqlx::query(sql).fetch_one(&mut conn).await?;
+
+    assert!(row.try_get::<i64, _>(0).is_err());
+    assert!(row.try_get::<u32, _>(1).is_err());
+    assert_eq!(row.try_get::<i8, _>(1)?, -1);
+    assert_eq!(row.try_get::<u8, _>(2)?, 42);
+
+    // TEXT
+    let row = conn.fetch_one(sql).await?;
+
+    assert!(row.try_get::<i64, _>(0).is_err());
+    assert!(row.try_get::<u32, _>(1).is_err());
+    assert_eq!(row.try_get::<i8, _>(1)?, -1);
+    assert_eq!(row.try_get::<u8, _>(2)?, 42);
+
+    Ok(())
+}
+
 test_type!(f64(MySql, "3.14159265e0" == 3.14159265_f64));
 
 // NOTE: This behavior can be very surprising. MySQL implicitly widens FLOAT bind parameters
@@ -33,6 +62,11 @@ test_type!(string<String>(MySql,
     "''" == ""
 ));
 
+test_type!(ipaddr<std::net::IpAddr>(MySql,
+    "'127.0.0.1'" == "127.0.0.1".parse::<std::net::IpAddr>().unwrap(),
+    "'2001:db8::1'" == "2001:db8::1".parse::<std::net::IpAddr>().unwrap()
+));
+
 test_type!(bytes<Vec<u8>>(MySql,
     "X'DEADBEEF'"
         == vec![0xDE_u8, 0xAD, 0xBE, 0xEF],
@@ -234,6 +268,40 @@ test_type!(decimal<sqlx::types::Decimal>(MySql,
     "CAST(12345.6789 AS DECIMAL(9, 4))" == sqlx::types::Decimal::from_str("12345.6789").unwrap(),
 ));
 
+#[cfg(feature = "rust_decimal")]
+#[sqlx_macros::test]
+async fn test_decimal_exact_decimals() -> anyhow::Result<()> {
+    use sqlx::mysql::{MySqlConnectOptions, MySqlConnection};
+    use sqlx::types::Decimal;
+    use sqlx::Connection;
+
+    // 30 digits after the point, of which `Decimal` holds 28
+    let sql = "SELECT CAST(0.123456789012345678901234567890 AS DECIMAL(30, 30))";
+
+    let mut conn = new::<MySql>().await?;
+
+    let rounded: Decimal = sqlx::query_scalar(sql).fetch_one(&mut conn).await?;
+    assert_eq!(
+        rounded,
+        "0.1234567890123456789012345679".parse::<Decimal>()?
+    );
+
+    let options: MySqlConnectOptions = std::env::var("DATABASE_URL")?.parse()?;
+    let mut conn = MySqlConnection::connect_with(&options.exact_decimals(true)).await?;
+
+    assert!(sqlx::query_scalar::<_, Decimal>(sql)
+        .fetch_one(&mut conn)
+        .await
+        .is_err());
+
+    let exact: Decimal = sqlx::query_scalar("SELECT CAST(12.5 AS DECIMAL(32, 30))")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(exact, "12.5".parse::<Decimal>()?);
+
+    Ok(())
+}
+
 #[cfg(feature = "json")]
 mod json_tests {
     use super::*;
@@ -276,6 +344,53 @@ mod json_tests {
         MySql,
         "\'{\"json_column\":[1,2]}\'" == Json(Customer { json_column: Json(vec![1, 2]) })
     ));
+
+    #[sqlx_macros::test]
+    async fn test_json_query_builder() -> anyhow::Result<()> {
+        use sqlx::mysql::types::{MySqlJsonPath, MySqlJsonQueryBuilder};
+        use sqlx::QueryBuilder;
+
+        let mut conn = new::<MySql>().await?;
+
+        conn.execute(
+            r#"
+CREATE TEMPORARY TABLE with_json (
+    id INT PRIMARY KEY,
+    doc JSON NOT NULL
+);
+            "#,
+        )
+        .await?;
+
+        sqlx::query("INSERT INTO with_json (id, doc) VALUES (?, ?), (?, ?)")
+            .bind(1)
+            .bind(Json(json!({ "name": "Joe", "the \"tags\"": ["a", "b"] })))
+            .bind(2)
+            .bind(Json(json!({ "name": "Ann", "the \"tags\"": ["c"] })))
+            .execute(&mut conn)
+            .await?;
+
+        let tags = MySqlJsonPath::new().key("the \"tags\"");
+
+        let mut query = QueryBuilder::new("SELECT id FROM with_json WHERE ");
+        query
+            .push_json_extract("doc", &tags)
+            .push(" = ")
+            .push_bind_json(json!(["c"]));
+
+        let id: i32 = query.build_query_scalar().fetch_one(&mut conn).await?;
+        assert_eq!(id, 2);
+
+        let mut query = QueryBuilder::new("SELECT ");
+        query
+            .push_json_unquote_extract("doc", &tags.index(1))
+            .push(" FROM with_json WHERE id = 1");
+
+        let tag: String = query.build_query_scalar().fetch_one(&mut conn).await?;
+        assert_eq!(tag, "b");
+
+        Ok(())
+    }
 }
 
 #[sqlx_macros::test]
@@ -319,3 +434,107 @@ CREATE TEMPORARY TABLE with_bits (
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn test_bits_as_signed_integers() -> anyhow::Result<()> {
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE with_signed_bits (value_n BIT(20) NOT NULL)")
+        .await?;
+
+    sqlx::query("INSERT INTO with_signed_bits (value_n) VALUES (?)")
+        .bind(510202_i32)
+        .execute(&mut conn)
+        .await?;
+
+    let vn: i32 = sqlx::query_scalar("SELECT value_n FROM with_signed_bits")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(vn, 510202);
+
+    Ok(())
+}
+
+#[cfg(feature = "bit-vec")]
+#[sqlx_macros::test]
+async fn test_bits_as_bit_vec() -> anyhow::Result<()> {
+    use sqlx::types::BitVec;
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE with_bit_vec (value_n BIT(10) NOT NULL)")
+        .await?;
+
+    let mut bits = BitVec::from_elem(10, false);
+    bits.set(0, true);
+    bits.set(9, true);
+
+    sqlx::query("INSERT INTO with_bit_vec (value_n) VALUES (?)")
+        .bind(&bits)
+        .execute(&mut conn)
+        .await?;
+
+    // BINARY
+    let (decoded, value): (BitVec, u16) =
+        sqlx::query_as("SELECT value_n, value_n FROM with_bit_vec")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(decoded, bits);
+    assert_eq!(value, 0b10_0000_0001);
+
+    // TEXT
+    let row = conn.fetch_one("SELECT value_n FROM with_bit_vec").await?;
+    let decoded: BitVec = row.try_get(0)?;
+
+    assert_eq!(decoded, bits);
+
+    Ok(())
+}
+
+#[derive(Debug, PartialEq, sqlx::Type)]
+#[sqlx(rename_all = "lowercase")]
+enum Size {
+    Small,
+    Large,
+}
+
+#[sqlx_macros::test]
+async fn test_set_and_enum() -> anyhow::Result<()> {
+    use sqlx::mysql::types::MySqlSet;
+
+    let mut conn = new::<MySql>().await?;
+
+    conn.execute(
+        r#"
+CREATE TEMPORARY TABLE with_set_and_enum (
+    perms SET('read', 'write', 'admin') NOT NULL,
+    size ENUM('small', 'large') NOT NULL
+);
+    "#,
+    )
+    .await?;
+
+    sqlx::query("INSERT INTO with_set_and_enum (perms, size) VALUES (?, ?), (?, ?)")
+        .bind(["admin", "read"].into_iter().collect::<MySqlSet>())
+        .bind(Size::Large)
+        .bind(MySqlSet::new())
+        .bind(Size::Small)
+        .execute(&mut conn)
+        .await?;
+
+    let rows: Vec<(MySqlSet, Size)> =
+        sqlx::query_as("SELECT perms, size FROM with_set_and_enum ORDER BY size")
+            .fetch_all(&mut conn)
+            .await?;
+
+    // members are returned in the order of the column definition
+    assert_eq!(rows[0].0.to_string(), "");
+    assert_eq!(rows[0].1, Size::Small);
+    assert_eq!(rows[1].0.to_string(), "read,admin");
+    assert!(rows[1].0.contains("admin"));
+    assert_eq!(rows[1].1, Size::Large);
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use sqlx::postgres::{PgPool, PgPoolOptions};
+use sqlx::testing::chaos::ChaosProxy;
+use sqlx::{Connection, Executor, PgConnection};
+use sqlx_core::rt::timeout;
+use sqlx_test::setup_if_needed;
+use std::env;
+use std::time::Duration;
+use url::Url;
+
+/// Start a proxy to the database at `DATABASE_URL`, returning it with the URL to connect to it.
+fn proxy() -> anyhow::Result<(ChaosProxy, String)> {
+    setup_if_needed();
+
+    let mut url = Url::parse(&env::var("DATABASE_URL")?)?;
+    let host = url.host_str().unwrap_or("localhost").to_owned();
+    let port = url.port().unwrap_or(5432);
+
+    let proxy = ChaosProxy::start((host.as_str(), port))?;
+
+    url.set_host(Some("127.0.0.1"))?;
+    url.set_port(Some(proxy.local_addr().port()))
+        .map_err(|_| anyhow::anyhow!("cannot set port of DATABASE_URL"))?;
+
+    Ok((proxy, url.into()))
+}
+
+async fn pool(url: &str) -> anyhow::Result<PgPool> {
+    Ok(PgPoolOptions::new()
+        .max_connections(1)
+        .test_before_acquire(true)
+        .acquire_timeout(Duration::from_secs(5))
+        .connect(url)
+        .await?)
+}
+
+async fn select(pool: &PgPool, value: i32) -> anyhow::Result<i32> {
+    Ok(sqlx::query_scalar("SELECT $1::int4")
+        .bind(value)
+        .fetch_one(pool)
+        .await?)
+}
+
+#[sqlx_macros::test]
+async fn it_recovers_from_disconnects() -> anyhow::Result<()> {
+    let (proxy, url) = proxy()?;
+    let pool = pool(&url).await?;
+
+    assert_eq!(select(&pool, 1).await?, 1);
+
+    let mut conn = pool.acquire().await?;
+    proxy.disconnect_all();
+    assert!(sqlx::query("SELECT 1").execute(&mut *conn).await.is_err());
+    drop(conn);
+
+    // the broken connection is replaced
+    assert_eq!(select(&pool, 2).await?, 2);
+
+    // an idle connection is replaced too, as it fails the test before it's acquired
+    proxy.disconnect_all();
+    assert_eq!(select(&pool, 3).await?, 3);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_recovers_after_connections_are_refused() -> anyhow::Result<()> {
+    let (proxy, url) = proxy()?;
+
+    proxy.refuse_connections(true);
+    assert!(PgConnection::connect(&url).await.is_err());
+
+    proxy.refuse_connections(false);
+    let pool = pool(&url).await?;
+    assert_eq!(select(&pool, 1).await?, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_is_cancellation_safe() -> anyhow::Result<()> {
+    let (proxy, url) = proxy()?;
+    let pool = pool(&url).await?;
+
+    assert_eq!(select(&pool, 1).await?, 1);
+
+    // the query is cancelled while its response is still on the way, and the connection is
+    // returned to the pool
+    proxy.set_latency(Duration::from_millis(500));
+    assert!(timeout(Duration::from_millis(100), select(&pool, 2))
+        .await
+        .is_err());
+    proxy.clear_faults();
+
+    // the next query on the connection doesn't get the response of the cancelled one
+    for value in 3..10 {
+        assert_eq!(select(&pool, value).await?, value);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_poisons_connections_cancelled_mid_message() -> anyhow::Result<()> {
+    let (proxy, url) = proxy()?;
+    let pool = pool(&url).await?;
+
+    let mut conn = pool.acquire().await?;
+    assert!(!conn.is_poisoned());
+
+    // the row takes about a second to arrive, so the query is cancelled while reading it
+    proxy.set_slow_reads(1000, Duration::from_millis(10));
+    assert!(timeout(
+        Duration::from_millis(300),
+        conn.execute("SELECT repeat('x', 100000)")
+    )
+    .await
+    .is_err());
+    proxy.clear_faults();
+
+    assert!(conn.is_poisoned());
+
+    // the pool closes the connection rather than taking it back
+    drop(conn);
+    for value in 1..5 {
+        assert_eq!(select(&pool, value).await?, value);
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_fails_on_truncated_responses() -> anyhow::Result<()> {
+    let (proxy, url) = proxy()?;
+    let pool = pool(&url).await?;
+
+    let mut conn = pool.acquire().await?;
+    proxy.truncate_after(100);
+
+    let res = timeout(
+        Duration::from_secs(5),
+        sqlx::query_scalar::<_, i32>("SELECT generate_series(1, 1000)").fetch_all(&mut *conn),
+    )
+    .await
+    .expect("a truncated response should fail the query rather than hang");
+    assert!(res.is_err());
+    drop(conn);
+
+    assert_eq!(select(&pool, 1).await?, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reads_slowly() -> anyhow::Result<()> {
+    let (proxy, url) = proxy()?;
+    let mut conn = PgConnection::connect(&url).await?;
+
+    proxy.set_slow_reads(7, Duration::from_millis(1));
+
+    let values: Vec<i32> = sqlx::query_scalar("SELECT generate_series(1, 100)")
+        .fetch_all(&mut conn)
+        .await?;
+    assert_eq!(values, (1..=100).collect::<Vec<_>>());
+
+    conn.close().await?;
+
+    Ok(())
+}
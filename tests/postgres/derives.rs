@@ -17,6 +17,20 @@ struct Transparent(i32);
 #[sqlx(transparent, no_pg_array)]
 struct TransparentArray(Vec<i64>);
 
+// Newtypes over arrays, `Option` and `Json` don't need `no_pg_array`
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(transparent)]
+struct TransparentVec(Vec<String>);
+
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(transparent)]
+struct TransparentOption(Option<i32>);
+
+#[cfg(feature = "json")]
+#[derive(PartialEq, Debug, sqlx::Type)]
+#[sqlx(transparent)]
+struct TransparentJson(sqlx::types::Json<Vec<i32>>);
+
 #[sqlx_macros::test]
 async fn test_transparent_slice_to_array() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -151,6 +165,21 @@ test_type!(transparent_array<TransparentArray>(Postgres,
     "'{ 23523, 123456, 789 }'::int8[]" == TransparentArray(vec![23523, 123456, 789])
 ));
 
+test_type!(transparent_vec<TransparentVec>(Postgres,
+    "'{}'::text[]" == TransparentVec(vec![]),
+    "'{ foo, bar }'::text[]" == TransparentVec(vec!["foo".into(), "bar".into()])
+));
+
+test_type!(transparent_option<TransparentOption>(Postgres,
+    "NULL::int4" == TransparentOption(None),
+    "42::int4" == TransparentOption(Some(42))
+));
+
+#[cfg(feature = "json")]
+test_type!(transparent_json<TransparentJson>(Postgres,
+    "'[1, 2, 3]'::jsonb" == TransparentJson(sqlx::types::Json(vec![1, 2, 3]))
+));
+
 test_type!(weak_enum<Weak>(Postgres,
     "0::int4" == Weak::One,
     "2::int4" == Weak::Two,
@@ -151,6 +151,30 @@ async fn test_query_as() -> anyhow::Result<()> {
     Ok(())
 }
 
+sqlx::query!(
+    as AccountRecord,
+    r#"SELECT id "id!", name FROM (VALUES (1, $1), (2, $2)) accounts(id, name) WHERE id > $3"#,
+    first: Option<&'q str>,
+    second: String,
+    after: i32,
+);
+
+#[sqlx_macros::test]
+async fn test_query_as_declared() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let accounts = AccountRecord::query(None, "Herp".to_owned(), 0)
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(accounts.len(), 2);
+    assert_eq!(accounts[0].id, 1);
+    assert_eq!(accounts[0].name, None);
+    assert_eq!(accounts[1].name.as_deref(), Some("Herp"));
+
+    Ok(())
+}
+
 #[derive(Debug)]
 struct RawAccount {
     r#type: i32,
@@ -611,3 +635,23 @@ async fn test_bind_arg_override_wildcard() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(all(feature = "chrono", feature = "time"))]
+#[sqlx_macros::test]
+async fn test_column_override_datetime_crate() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let record = sqlx::query!(
+        r#"select '2020-01-02'::date as "chrono!", '2020-01-02'::date as "time!: time""#
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    let chrono: sqlx::types::chrono::NaiveDate = record.chrono;
+    let time: sqlx::types::time::Date = record.time;
+
+    assert_eq!(chrono.to_string(), "2020-01-02");
+    assert_eq!(time.to_string(), "2020-01-02");
+
+    Ok(())
+}
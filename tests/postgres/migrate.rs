@@ -1,9 +1,10 @@
-use sqlx::migrate::Migrator;
+use sqlx::migrate::{AuditInfo, Migrate, Migrator};
 use sqlx::pool::PoolConnection;
 use sqlx::postgres::{PgConnection, Postgres};
 use sqlx::Executor;
 use sqlx::Row;
 use std::path::Path;
+use std::time::Duration;
 
 #[sqlx::test(migrations = false)]
 async fn simple(mut conn: PoolConnection<Postgres>) -> anyhow::Result<()> {
@@ -66,6 +67,39 @@ async fn reversible(mut conn: PoolConnection<Postgres>) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx::test(migrations = false)]
+async fn audit_log(mut conn: PoolConnection<Postgres>) -> anyhow::Result<()> {
+    clean_up(&mut conn).await?;
+
+    let mut migrator = Migrator::new(Path::new("tests/postgres/migrations_reversible")).await?;
+    migrator.set_audit_log(Some(AuditInfo {
+        tool_version: Some("sqlx-test".into()),
+        ..AuditInfo::default()
+    }));
+
+    migrator.run(&mut conn).await?;
+    migrator.undo(&mut conn, 20220721124650).await?;
+
+    let log = conn.list_migrations_log().await?;
+
+    let versions: Vec<_> = log.iter().map(|e| (e.version, e.reverted)).collect();
+    assert_eq!(
+        versions,
+        [
+            (20220721124650, false),
+            (20220721125033, false),
+            (20220721125033, true)
+        ]
+    );
+    assert_eq!(log[0].description, "add table");
+    assert_eq!(log[0].tool_version.as_deref(), Some("sqlx-test"));
+    assert_eq!(log[0].git_commit, None);
+    // stored as a timestamp, to the second
+    assert!(log[0].applied_at.elapsed()? < Duration::from_secs(60));
+
+    Ok(())
+}
+
 /// Ensure that we have a clean initial state.
 async fn clean_up(conn: &mut PgConnection) -> anyhow::Result<()> {
     conn.execute("DROP TABLE migrations_simple_test").await.ok();
@@ -73,6 +107,7 @@ async fn clean_up(conn: &mut PgConnection) -> anyhow::Result<()> {
         .await
         .ok();
     conn.execute("DROP TABLE _sqlx_migrations").await.ok();
+    conn.execute("DROP TABLE _sqlx_migrations_log").await.ok();
 
     Ok(())
 }
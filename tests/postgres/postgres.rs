@@ -2,7 +2,7 @@ use futures::{StreamExt, TryStreamExt};
 use sqlx::postgres::types::Oid;
 use sqlx::postgres::{
     PgAdvisoryLock, PgConnectOptions, PgConnection, PgDatabaseError, PgErrorPosition, PgListener,
-    PgPoolOptions, PgRow, PgSeverity, Postgres,
+    PgPoolOptions, PgPreparedTransaction, PgRow, PgSessionMultiplexer, PgSeverity, Postgres,
 };
 use sqlx::{Column, Connection, Executor, Row, Statement, TypeInfo};
 use sqlx_test::{new, pool, setup_if_needed};
@@ -259,6 +259,121 @@ async fn it_executes_with_pool() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_executes_with_many_argument_sets() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE batch (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+        .await?;
+
+    let result = sqlx::query("INSERT INTO batch (id, name) VALUES ($1, $2)")
+        .execute_many_args(&mut conn, (0..100_i32).map(|id| (id, format!("row {id}"))))
+        .await?;
+
+    assert_eq!(result.rows_affected(), 100);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM batch")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 100);
+
+    // a failed execution rolls back the ones before it, and the connection is usable after
+    let result = sqlx::query("INSERT INTO batch (id, name) VALUES ($1, $2)")
+        .execute_many_args(
+            &mut conn,
+            [(100_i32, "new"), (0, "duplicate"), (101, "skipped")],
+        )
+        .await;
+
+    assert_eq!(
+        result
+            .unwrap_err()
+            .into_database_error()
+            .unwrap()
+            .code()
+            .unwrap(),
+        "23505"
+    );
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM batch")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 100);
+
+    // values bound to the query would be ignored
+    let result = sqlx::query("INSERT INTO batch (id, name) VALUES ($1, $2)")
+        .bind(200_i32)
+        .execute_many_args(&mut conn, [(201_i32, "new")])
+        .await;
+
+    assert!(matches!(result, Err(sqlx::Error::Configuration(_))));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_executes_large_batches() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE batch (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+        .await?;
+
+    // far more executions and results than fit in one window or the socket buffers
+    let result = sqlx::query("INSERT INTO batch (id, name) VALUES ($1, $2)")
+        .execute_many_args(
+            &mut conn,
+            (0..20_000_i32).map(|id| (id, format!("row {id}"))),
+        )
+        .await?;
+
+    assert_eq!(result.rows_affected(), 20_000);
+
+    // with a row returned by each execution
+    let result = sqlx::query("UPDATE batch SET name = $2 WHERE id = $1 RETURNING *")
+        .execute_many_args(&mut conn, (0..20_000_i32).map(|id| (id, "x".repeat(1000))))
+        .await?;
+
+    assert_eq!(result.rows_affected(), 20_000);
+
+    let (count, size): (i64, i64) = sqlx::query_as("SELECT COUNT(*), SUM(LENGTH(name)) FROM batch")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 20_000);
+    assert_eq!(size, 20_000 * 1000);
+
+    // a failure in a later window rolls back the windows before it
+    let result = sqlx::query("INSERT INTO batch (id, name) VALUES ($1, $2)")
+        .execute_many_args(
+            &mut conn,
+            (20_000..25_000_i32)
+                .chain([0])
+                .chain(25_000..30_000)
+                .map(|id| (id, "new")),
+        )
+        .await;
+
+    assert_eq!(
+        result
+            .unwrap_err()
+            .into_database_error()
+            .unwrap()
+            .code()
+            .unwrap(),
+        "23505"
+    );
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM batch")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 20_000);
+
+    Ok(())
+}
+
 // https://github.com/launchbadge/sqlx/issues/104
 #[sqlx_macros::test]
 async fn it_can_return_interleaved_nulls_issue_104() -> anyhow::Result<()> {
@@ -496,6 +611,117 @@ async fn it_can_work_with_nested_transactions() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_can_begin_with_transaction_options() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let options = sqlx::TransactionOptions::new()
+        .isolation(sqlx::IsolationLevel::Serializable)
+        .read_only(true);
+
+    let mut tx = conn.begin_with(options).await?;
+
+    let (isolation, read_only): (String, String) = sqlx::query_as(
+        "SELECT current_setting('transaction_isolation'), current_setting('transaction_read_only')",
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    assert_eq!(isolation, "serializable");
+    assert_eq!(read_only, "on");
+
+    let err = tx
+        .execute("CREATE TEMPORARY TABLE _sqlx_read_only (id INTEGER)")
+        .await
+        .unwrap_err();
+
+    assert_eq!(err.into_database_error().unwrap().code().unwrap(), "25006");
+
+    tx.rollback().await?;
+
+    // options can't be applied to a savepoint
+    let mut tx = conn.begin().await?;
+    assert!(tx.begin_with(options).await.is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_run_transaction_with_options() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let options = sqlx::TransactionOptions::new().isolation(sqlx::IsolationLevel::RepeatableRead);
+
+    let isolation: String = conn
+        .transaction_with(options, |txn| {
+            Box::pin(async move {
+                sqlx::query_scalar("SELECT current_setting('transaction_isolation')")
+                    .fetch_one(&mut **txn)
+                    .await
+            })
+        })
+        .await?;
+
+    assert_eq!(isolation, "repeatable read");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_can_prepare_transactions() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let max: String = sqlx::query_scalar("SHOW max_prepared_transactions")
+        .fetch_one(&mut conn)
+        .await?;
+
+    if max == "0" {
+        // prepared transactions are disabled on this server
+        return Ok(());
+    }
+
+    conn.execute("CREATE TABLE IF NOT EXISTS _sqlx_prepared_3114 (id INTEGER PRIMARY KEY)")
+        .await?;
+
+    conn.execute("TRUNCATE _sqlx_prepared_3114").await?;
+
+    for (gid, commit) in [
+        ("sqlx-prepared-commit", true),
+        ("sqlx-prepared-rollback", false),
+    ] {
+        let mut tx = conn.begin().await?;
+
+        sqlx::query("INSERT INTO _sqlx_prepared_3114 (id) VALUES ($1)")
+            .bind(commit as i32)
+            .execute(&mut *tx)
+            .await?;
+
+        let prepared = PgPreparedTransaction::prepare(tx, gid).await?;
+
+        // the connection is free to use again
+        let mut other = new::<Postgres>().await?;
+
+        let listed = PgPreparedTransaction::list(&mut other).await?;
+        assert!(listed.iter().any(|info| info.gid == gid));
+
+        if commit {
+            prepared.commit(&mut other).await?;
+        } else {
+            PgPreparedTransaction::from_gid(gid)
+                .rollback(&mut other)
+                .await?;
+        }
+    }
+
+    let ids: Vec<i32> = sqlx::query_scalar("SELECT id FROM _sqlx_prepared_3114")
+        .fetch_all(&mut conn)
+        .await?;
+
+    assert_eq!(ids, [1]);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_can_drop_multiple_transactions() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -927,16 +1153,43 @@ from (values (null)) vals(val)
     assert_eq!(describe.nullable(0), Some(true));
     assert_eq!(describe.nullable(1), Some(true));
 
+    // outer joins nested under other nodes are still inferred
+    // language=PostgreSQL
+    let describe = conn
+        .describe(
+            "select tweet1.id, tweet2.id
+    from tweet tweet1
+    inner join tweet tweet3 on tweet3.id = tweet1.id
+    left join tweet tweet2 on false
+    order by tweet1.id",
+        )
+        .await?;
+
+    assert_eq!(describe.nullable(0), Some(false));
+    assert_eq!(describe.nullable(1), Some(true));
+
     Ok(())
 }
 
 #[sqlx_macros::test]
-async fn test_listener_cleanup() -> anyhow::Result<()> {
-    #[cfg(feature = "_rt-tokio")]
-    use tokio::time::timeout;
+async fn test_describe_aggregate_nullable() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
 
-    #[cfg(feature = "_rt-async-std")]
-    use async_std::future::timeout;
+    // without `group by`, aggregates return NULL if there are no rows
+    // language=PostgreSQL
+    let describe = conn
+        .describe("select max(tweet.id), count(*) from tweet")
+        .await?;
+
+    assert_eq!(describe.nullable(0), Some(true));
+    assert_eq!(describe.nullable(1), None);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_listener_cleanup() -> anyhow::Result<()> {
+    use sqlx_core::rt::timeout;
 
     use sqlx::pool::PoolOptions;
     use sqlx::postgres::PgListener;
@@ -1771,6 +2024,76 @@ async fn test_advisory_locks() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn test_distributed_lock() -> anyhow::Result<()> {
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    let lock = sqlx::sync::DistributedLock::new(&pool, "sqlx-postgres-tests-leader")
+        .retry_interval(std::time::Duration::from_millis(10));
+
+    let mut leader = lock.try_acquire().await?.expect("lock should be free");
+    assert!(leader.is_held().await?);
+
+    // another process can't acquire it
+    assert!(lock.try_acquire().await?.is_none());
+
+    leader.release().await?;
+
+    // dropping the guard releases the lock too, once the server notices the connection closed
+    drop(lock.acquire().await?);
+
+    let mut leader = lock.acquire().await?;
+    assert!(leader.is_held().await?);
+    leader.release().await?;
+
+    pool.close().await;
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reads_its_own_writes_through_a_replica_session() -> anyhow::Result<()> {
+    use sqlx::pool::ReplicaPool;
+
+    let url = dotenvy::var("DATABASE_URL")?;
+
+    // the primary doubles as its own replica, which has always replayed everything
+    let pool = ReplicaPool::new(
+        PgPoolOptions::new().connect(&url).await?,
+        vec![PgPoolOptions::new().connect(&url).await?],
+    );
+
+    let session = pool.session();
+    assert_eq!(session.position(), None);
+
+    // reads don't record a position
+    sqlx::query("SELECT 1").execute(&session).await?;
+    assert_eq!(session.position(), None);
+
+    sqlx::query("CREATE TABLE IF NOT EXISTS _sqlx_test_replica_session (id INT)")
+        .execute(&session)
+        .await?;
+    assert!(session.position().is_some());
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM _sqlx_test_replica_session")
+        .fetch_one(&session)
+        .await?;
+    assert_eq!(count, 0);
+
+    assert!(std::ptr::eq(session.read_pool().await, &pool.replicas()[0]));
+
+    sqlx::query("DROP TABLE _sqlx_test_replica_session")
+        .execute(pool.primary())
+        .await?;
+
+    pool.close().await;
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn test_postgres_bytea_hex_deserialization_errors() -> anyhow::Result<()> {
     let mut conn = new::<Postgres>().await?;
@@ -1813,3 +2136,276 @@ async fn test_shrink_buffers() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_labels_checked_out_connections() -> anyhow::Result<()> {
+    use std::sync::Mutex;
+
+    setup_if_needed();
+
+    let label = Arc::new(Mutex::new(Some("/invoices".to_string())));
+
+    let options = env::var("DATABASE_URL")?
+        .parse::<PgConnectOptions>()?
+        .application_name("sqlx-test");
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .checkout_label({
+            let label = label.clone();
+            move || label.lock().unwrap().clone()
+        })
+        .connect_with(options)
+        .await?;
+
+    let application_name: String = sqlx::query_scalar("SHOW application_name")
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(application_name, "sqlx-test:/invoices");
+
+    // the same label isn't set again
+    pool.execute("SET application_name = 'changed'").await?;
+
+    let application_name: String = sqlx::query_scalar("SHOW application_name")
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(application_name, "changed");
+
+    *label.lock().unwrap() = None;
+
+    let application_name: String = sqlx::query_scalar("SHOW application_name")
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(application_name, "sqlx-test");
+
+    // without a label or an application name, the connection keeps its name
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .checkout_label(|| Some(String::new()))
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    pool.execute("SET application_name = 'unlabelled'").await?;
+
+    let application_name: String = sqlx::query_scalar("SHOW application_name")
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(application_name, "unlabelled");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_multiplexes_queries_over_one_connection() -> anyhow::Result<()> {
+    let conn = new::<Postgres>().await?;
+    let mux = PgSessionMultiplexer::new(conn).await?.batch_size(3);
+
+    let a = sqlx::query_scalar::<_, i32>("SELECT generate_series(1, 10)")
+        .fetch(&mux)
+        .try_collect::<Vec<_>>();
+
+    let b = sqlx::query_scalar::<_, i32>("SELECT generate_series($1, $2)")
+        .bind(100_i32)
+        .bind(107_i32)
+        .fetch(&mux)
+        .try_collect::<Vec<_>>();
+
+    let (a, b) = futures::try_join!(a, b)?;
+
+    assert_eq!(a, (1..=10).collect::<Vec<_>>());
+    assert_eq!(b, (100..=107).collect::<Vec<_>>());
+
+    let first: Option<i32> = sqlx::query_scalar("SELECT generate_series(5, 50)")
+        .fetch_optional(&mux)
+        .await?;
+    assert_eq!(first, Some(5));
+
+    // a stream dropped before it's exhausted closes its portal
+    let mut stream = sqlx::query_scalar::<_, i32>("SELECT generate_series(1, 10)").fetch(&mux);
+    assert_eq!(stream.try_next().await?, Some(1));
+    drop(stream);
+
+    let open: i64 = sqlx::query_scalar("SELECT count(*) FROM pg_cursors")
+        .fetch_one(&mux)
+        .await?;
+    // the query counting them is the only one open
+    assert_eq!(open, 1);
+
+    let mut conn = mux.finish().await?;
+
+    // the multiplexer's transaction has been committed
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_replaces_closed_connections_with_fast_acquire_test() -> anyhow::Result<()> {
+    use sqlx::pool::AcquireTest;
+
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .acquire_test(AcquireTest::Fast)
+        .connect(&env::var("DATABASE_URL")?)
+        .await?;
+
+    let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+        .fetch_one(&pool)
+        .await?;
+
+    let mut conn = new::<Postgres>().await?;
+    sqlx::query("SELECT pg_terminate_backend($1)")
+        .bind(pid)
+        .execute(&mut conn)
+        .await?;
+
+    // give the server time to close the connection
+    sqlx_core::rt::sleep(Duration::from_millis(100)).await;
+
+    // the closed connection is noticed on acquire, without an error from the query
+    let new_pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+        .fetch_one(&pool)
+        .await?;
+    assert_ne!(pid, new_pid);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_fetches_rows_in_batches_with_fetch_size() -> anyhow::Result<()> {
+    let mut conn = new::<Postgres>().await?;
+
+    let rows: Vec<i32> = sqlx::query_scalar::<_, i32>("SELECT generate_series(1, $1)")
+        .bind(10_i32)
+        .fetch_size(3)
+        .fetch(&mut conn)
+        .try_collect()
+        .await?;
+    assert_eq!(rows, (1..=10).collect::<Vec<_>>());
+
+    // stop partway through a batch; the rest of the rows are discarded
+    {
+        let mut s = sqlx::query_scalar::<_, i32>("SELECT generate_series(1, 100)")
+            .fetch_size(4)
+            .fetch(&mut conn);
+
+        assert_eq!(s.try_next().await?, Some(1));
+        assert_eq!(s.try_next().await?, Some(2));
+    }
+
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    // an error in a later batch leaves the connection usable
+    let res: Result<Vec<i32>, _> =
+        sqlx::query_scalar::<_, i32>("SELECT 10 / (5 - generate_series(1, 10))::int")
+            .fetch_size(2)
+            .fetch(&mut conn)
+            .try_collect()
+            .await;
+    assert!(res.is_err());
+
+    let value: i32 = sqlx::query_scalar("SELECT 2").fetch_one(&mut conn).await?;
+    assert_eq!(value, 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_rejects_rows_over_the_size_limits() -> anyhow::Result<()> {
+    setup_if_needed();
+
+    let options = env::var("DATABASE_URL")?
+        .parse::<PgConnectOptions>()?
+        .max_row_size(1024)
+        .max_result_size(64 * 1024);
+
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    let res = sqlx::query_scalar::<_, String>(
+        "SELECT repeat('x', n * 1000) FROM generate_series(1, 3) n",
+    )
+    .fetch_all(&mut conn)
+    .await;
+    assert!(matches!(
+        res,
+        Err(sqlx::Error::RowTooLarge { limit: 1024, .. })
+    ));
+
+    // the rest of the rows are skipped, and the connection stays usable
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 1);
+
+    let res = conn.fetch_all("SELECT repeat('x', 4096)").await;
+    assert!(matches!(res, Err(sqlx::Error::RowTooLarge { .. })));
+
+    let res = sqlx::query("SELECT repeat('x', 100) FROM generate_series(1, 1000)")
+        .fetch_all(&mut conn)
+        .await;
+    assert!(matches!(
+        res,
+        Err(sqlx::Error::ResultTooLarge { limit: 65536 })
+    ));
+
+    // a query can opt out of the limits
+    let value: String = sqlx::query_scalar("SELECT repeat('x', $1)")
+        .bind(4096_i32)
+        .without_size_limits()
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(value.len(), 4096);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reprepares_statements_lost_by_the_server() -> anyhow::Result<()> {
+    let options: PgConnectOptions = env::var("DATABASE_URL")?.parse()?;
+    let mut conn = PgConnection::connect_with(&options).await?;
+
+    let select = |value: i32| sqlx::query_scalar::<_, i32>("SELECT $1::int4").bind(value);
+
+    assert_eq!(select(1).fetch_one(&mut conn).await?, 1);
+    assert_eq!(1, conn.cached_statements_size());
+
+    // as if a pooler handed the connection a different server connection
+    conn.execute("DEALLOCATE ALL").await?;
+    assert_eq!(select(2).fetch_one(&mut conn).await?, 2);
+
+    conn.execute("DEALLOCATE ALL").await?;
+    let values: Vec<i32> = select(3).fetch_all(&mut conn).await?;
+    assert_eq!(values, [3]);
+
+    // and with the rows fetched in batches
+    let series = || {
+        sqlx::query("SELECT generate_series(1, $1)")
+            .bind(10_i32)
+            .fetch_size(3)
+    };
+    assert_eq!(series().fetch_all(&mut conn).await?.len(), 10);
+    conn.execute("DEALLOCATE ALL").await?;
+    assert_eq!(series().fetch_all(&mut conn).await?.len(), 10);
+
+    // the error has aborted the transaction, so the query isn't executed again
+    let mut tx = conn.begin().await?;
+    tx.execute("DEALLOCATE ALL").await?;
+    let err = select(4).fetch_one(&mut *tx).await.unwrap_err();
+    assert_eq!(
+        err.into_database_error()
+            .and_then(|e| e.code().map(|code| code.into_owned())),
+        Some("26000".to_owned())
+    );
+    tx.rollback().await?;
+
+    assert_eq!(select(5).fetch_one(&mut conn).await?, 5);
+
+    // unless it's turned off
+    let mut conn = PgConnection::connect_with(&options.reprepare_statements(false)).await?;
+
+    assert_eq!(select(6).fetch_one(&mut conn).await?, 6);
+    conn.execute("DEALLOCATE ALL").await?;
+    assert!(select(7).fetch_one(&mut conn).await.is_err());
+
+    Ok(())
+}
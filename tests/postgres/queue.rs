@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use sqlx::queue::Queue;
+use sqlx::{Executor, Postgres};
+use sqlx_test::pool;
+
+async fn setup(queue: &Queue) -> anyhow::Result<sqlx::PgPool> {
+    let pool = pool::<Postgres>().await?;
+
+    pool.execute(&*format!("DROP TABLE IF EXISTS {}", queue.table()))
+        .await?;
+    pool.execute(&*queue.migration("PostgreSQL").unwrap())
+        .await?;
+
+    Ok(pool)
+}
+
+#[sqlx_macros::test]
+async fn it_claims_each_job_once() -> anyhow::Result<()> {
+    let queue = Queue::new("_sqlx_test_jobs_1", "emails");
+    let pool = setup(&queue).await?;
+
+    queue.enqueue(&pool, "first").await?;
+    queue.enqueue(&pool, "second").await?;
+
+    // other queues in the same table are separate
+    Queue::new("_sqlx_test_jobs_1", "sms")
+        .enqueue(&pool, "other")
+        .await?;
+
+    let first = queue.dequeue(&pool).await?.unwrap();
+    let second = queue.dequeue(&pool).await?.unwrap();
+
+    assert_eq!(first.payload, "first");
+    assert_eq!(first.attempts, 1);
+    assert_eq!(second.payload, "second");
+
+    // both are hidden until the visibility timeout
+    assert_eq!(queue.dequeue(&pool).await?, None);
+
+    assert!(queue.complete(&pool, &first).await?);
+    assert!(queue.complete(&pool, &second).await?);
+
+    let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM _sqlx_test_jobs_1")
+        .fetch_one(&pool)
+        .await?;
+
+    assert_eq!(count, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_retries_failed_jobs() -> anyhow::Result<()> {
+    let queue = Queue::new("_sqlx_test_jobs_2", "emails")
+        .max_attempts(2)
+        .retry_delay(Duration::ZERO);
+    let pool = setup(&queue).await?;
+
+    queue.enqueue(&pool, "flaky").await?;
+
+    let job = queue.dequeue(&pool).await?.unwrap();
+    assert!(queue.fail(&pool, &job, "timed out").await?);
+
+    let job = queue.dequeue(&pool).await?.unwrap();
+    assert_eq!(job.attempts, 2);
+    assert!(queue.fail(&pool, &job, "timed out again").await?);
+
+    // out of attempts
+    assert_eq!(queue.dequeue(&pool).await?, None);
+
+    let (last_error,): (String,) =
+        sqlx::query_as("SELECT last_error FROM _sqlx_test_jobs_2 WHERE id = $1")
+            .bind(job.id)
+            .fetch_one(&pool)
+            .await?;
+
+    assert_eq!(last_error, "timed out again");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_does_not_complete_jobs_claimed_again() -> anyhow::Result<()> {
+    let queue = Queue::new("_sqlx_test_jobs_3", "emails").visibility_timeout(Duration::ZERO);
+    let pool = setup(&queue).await?;
+
+    queue.enqueue(&pool, "slow").await?;
+
+    let stale = queue.dequeue(&pool).await?.unwrap();
+
+    // the visibility timeout passed, so another worker claimed the job
+    let job = queue.dequeue(&pool).await?.unwrap();
+    assert_eq!(job.id, stale.id);
+    assert_eq!(job.attempts, 2);
+
+    assert!(!queue.complete(&pool, &stale).await?);
+    assert!(!queue.fail(&pool, &stale, "timed out").await?);
+
+    let (last_error,): (Option<String>,) =
+        sqlx::query_as("SELECT last_error FROM _sqlx_test_jobs_3 WHERE id = $1")
+            .bind(job.id)
+            .fetch_one(&pool)
+            .await?;
+
+    assert_eq!(last_error, None);
+
+    assert!(queue.complete(&pool, &job).await?);
+
+    Ok(())
+}
@@ -2,7 +2,7 @@ extern crate time_ as time;
 
 use std::ops::Bound;
 
-use sqlx::postgres::types::{Oid, PgInterval, PgMoney, PgRange};
+use sqlx::postgres::types::{Oid, PgInterval, PgMoney, PgRange, PgTimestamp};
 use sqlx::postgres::Postgres;
 use sqlx_test::{test_decode_type, test_prepared_type, test_type};
 
@@ -303,6 +303,50 @@ mod chrono {
         "TIMETZ '05:10:20.115100-05'" == PgTimeTz { time: NaiveTime::from_hms_micro(5, 10, 20, 115100), offset: FixedOffset::west(60 * 60 * 5) },
         "TIMETZ '05:10:20+02'" == PgTimeTz { time: NaiveTime::from_hms(5, 10, 20), offset: FixedOffset::east(60 * 60 * 2 )}
     ));
+
+    test_type!(chrono_date_time_infinity<PgTimestamp<NaiveDateTime>>(Postgres,
+        "'infinity'::timestamp" == PgTimestamp::<NaiveDateTime>::Infinity,
+        "'-infinity'::timestamp" == PgTimestamp::<NaiveDateTime>::NegInfinity,
+        "'2019-01-02 05:10:20'::timestamp"
+            == PgTimestamp::Value(NaiveDate::from_ymd(2019, 1, 2).and_hms(5, 10, 20))
+    ));
+
+    test_type!(chrono_date_infinity_vec<Vec<PgTimestamp<NaiveDate>>>(Postgres,
+        "array['-infinity', '2001-01-05', 'infinity']::date[]"
+            == vec![
+                PgTimestamp::NegInfinity,
+                PgTimestamp::Value(NaiveDate::from_ymd(2001, 1, 5)),
+                PgTimestamp::Infinity,
+            ]
+    ));
+
+    #[sqlx_macros::test]
+    async fn test_chrono_clamp_infinite_timestamps() -> anyhow::Result<()> {
+        use sqlx::postgres::{PgConnectOptions, PgConnection};
+        use sqlx::{Connection, Row};
+
+        let sql = "SELECT 'infinity'::timestamptz, '-infinity'::timestamp, \
+                   array['infinity', '2001-01-05']::date[]";
+
+        let mut conn = sqlx_test::new::<Postgres>().await?;
+
+        let row = sqlx::query(sql).fetch_one(&mut conn).await?;
+        assert!(row.try_get::<DateTime<Utc>, _>(0).is_err());
+        assert!(row.try_get::<NaiveDateTime, _>(1).is_err());
+        assert!(row.try_get::<Vec<NaiveDate>, _>(2).is_err());
+
+        let options: PgConnectOptions = std::env::var("DATABASE_URL")?.parse()?;
+        let mut conn = PgConnection::connect_with(&options.clamp_infinite_timestamps(true)).await?;
+
+        let (max, min, dates): (DateTime<Utc>, NaiveDateTime, Vec<NaiveDate>) =
+            sqlx::query_as(sql).fetch_one(&mut conn).await?;
+
+        assert_eq!(max.naive_utc(), NaiveDateTime::MAX);
+        assert_eq!(min, NaiveDateTime::MIN);
+        assert_eq!(dates, [NaiveDate::MAX, NaiveDate::from_ymd(2001, 1, 5)]);
+
+        Ok(())
+    }
 }
 
 #[cfg(feature = "time")]
@@ -491,6 +535,40 @@ test_type!(numrange_decimal<PgRange<sqlx::types::Decimal>>(Postgres,
          Bound::Excluded(sqlx::types::Decimal::from_str("2.4").unwrap()))),
 ));
 
+#[cfg(feature = "rust_decimal")]
+#[sqlx_macros::test]
+async fn test_decimal_exact_decimals() -> anyhow::Result<()> {
+    use sqlx::postgres::{PgConnectOptions, PgConnection};
+    use sqlx::types::Decimal;
+    use sqlx::Connection;
+
+    // 30 digits after the point, of which `Decimal` holds 28
+    let sql = "SELECT 0.123456789012345678901234567890::numeric";
+
+    let mut conn = sqlx_test::new::<Postgres>().await?;
+
+    let rounded: Decimal = sqlx::query_scalar(sql).fetch_one(&mut conn).await?;
+    assert_eq!(
+        rounded,
+        "0.1234567890123456789012345679".parse::<Decimal>()?
+    );
+
+    let options: PgConnectOptions = std::env::var("DATABASE_URL")?.parse()?;
+    let mut conn = PgConnection::connect_with(&options.exact_decimals(true)).await?;
+
+    assert!(sqlx::query_scalar::<_, Decimal>(sql)
+        .fetch_one(&mut conn)
+        .await
+        .is_err());
+
+    let exact: Decimal = sqlx::query_scalar("SELECT 12.50000000000000000000000000000000::numeric")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(exact, "12.5".parse::<Decimal>()?);
+
+    Ok(())
+}
+
 const EXC2: Bound<i32> = Bound::Excluded(2);
 const EXC3: Bound<i32> = Bound::Excluded(3);
 const INC1: Bound<i32> = Bound::Included(1);
@@ -543,10 +621,59 @@ test_prepared_type!(interval<PgInterval>(
         },
 ));
 
-test_prepared_type!(money<PgMoney>(Postgres, "123.45::money" == PgMoney(12345)));
+test_type!(money<PgMoney>(Postgres,
+    "123.45::money" == PgMoney(12345),
+    "'-1234.56'::money" == PgMoney(-123456),
+));
+
+test_type!(money_vec<Vec<PgMoney>>(Postgres,
+    "array[123.45,420.00,666.66,1234.56]::money[]"
+        == vec![PgMoney(12345), PgMoney(42000), PgMoney(66666), PgMoney(123456)],
+));
+
+test_type!(hstore<sqlx::postgres::types::PgHstore>(Postgres,
+    "'a=>1, b=>NULL, \"c d\"=>\"e\\\"f\"'::hstore" == sqlx::postgres::types::PgHstore::from_iter([
+        ("a".to_owned(), Some("1".to_owned())),
+        ("b".to_owned(), None),
+        ("c d".to_owned(), Some("e\"f".to_owned())),
+    ]),
+    "''::hstore" == sqlx::postgres::types::PgHstore::default(),
+));
+
+test_type!(hstore_vec<Vec<sqlx::postgres::types::PgHstore>>(Postgres,
+    "array['a=>1', 'b=>NULL']::hstore[]" == vec![
+        sqlx::postgres::types::PgHstore::from_iter([("a".to_owned(), Some("1".to_owned()))]),
+        sqlx::postgres::types::PgHstore::from_iter([("b".to_owned(), None)]),
+    ]
+));
+
+test_type!(citext<sqlx::postgres::types::PgCiText>(Postgres,
+    "'Hello World'::citext" == sqlx::postgres::types::PgCiText::from("Hello World"),
+));
+
+test_type!(citext_vec<Vec<sqlx::postgres::types::PgCiText>>(Postgres,
+    "array['Hello', 'World']::citext[]" == vec![
+        sqlx::postgres::types::PgCiText::from("Hello"),
+        sqlx::postgres::types::PgCiText::from("World"),
+    ]
+));
+
+test_decode_type!(citext_string<String>(Postgres,
+    "'Hello World'::citext" == "Hello World",
+));
+
+// `xml` has no equality operator, so it's compared as text
+test_type!(xml<sqlx::postgres::types::PgXml>(Postgres,
+    "SELECT ({0}::text is not distinct from $1::text)::int4, {0}, $2",
+    "'<a href=\"x\">b</a>'::xml" == sqlx::postgres::types::PgXml::from("<a href=\"x\">b</a>"),
+));
+
+test_decode_type!(xml_string<String>(Postgres,
+    "'<a>b</a>'::xml" == "<a>b</a>",
+));
 
-test_prepared_type!(money_vec<Vec<PgMoney>>(Postgres,
-    "array[123.45,420.00,666.66]::money[]" == vec![PgMoney(12345), PgMoney(42000), PgMoney(66666)],
+test_decode_type!(xml_cow<std::borrow::Cow<'_, str>>(Postgres,
+    "'<a>b</a>'::xml" == std::borrow::Cow::<str>::Borrowed("<a>b</a>"),
 ));
 
 // FIXME: needed to disable `ltree` tests in version that don't have a binary format for it
@@ -17,3 +17,99 @@ async fn it_encodes_bool_with_any() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_inserts_returning_id_with_any() -> anyhow::Result<()> {
+    use sqlx::any::{AnyOnConflict, AnyValue, AnyValueKind};
+    use sqlx::Executor;
+
+    sqlx::any::install_default_drivers();
+    let mut conn = new::<Any>().await?;
+
+    // not a temporary table, as `RETURNING` with an upsert doesn't return the updated row for
+    // those in some versions of SQLite
+    conn.execute("DROP TABLE IF EXISTS any_users").await?;
+    conn.execute(
+        "CREATE TABLE any_users (id INTEGER PRIMARY KEY, email TEXT NOT NULL UNIQUE, name TEXT)",
+    )
+    .await?;
+
+    let values = |email: &str, name: &str| {
+        vec![
+            AnyValue::new(AnyValueKind::Text(email.to_owned().into())),
+            AnyValue::new(AnyValueKind::Text(name.to_owned().into())),
+        ]
+    };
+
+    let columns = ["email", "name"];
+
+    let alice = conn
+        .insert_returning_id(
+            "any_users",
+            "id",
+            &columns,
+            values("alice@example.com", "Alice"),
+            AnyOnConflict::Error,
+        )
+        .await?;
+
+    let bob = conn
+        .insert_returning_id(
+            "any_users",
+            "id",
+            &columns,
+            values("bob@example.com", "Bob"),
+            AnyOnConflict::Error,
+        )
+        .await?;
+
+    assert_eq!(alice, Some(1));
+    assert_eq!(bob, Some(2));
+
+    let ignored = conn
+        .insert_returning_id(
+            "any_users",
+            "id",
+            &columns,
+            values("alice@example.com", "Not Alice"),
+            AnyOnConflict::Ignore,
+        )
+        .await?;
+
+    assert_eq!(ignored, None);
+
+    let updated = conn
+        .insert_returning_id(
+            "any_users",
+            "id",
+            &columns,
+            values("bob@example.com", "Robert"),
+            AnyOnConflict::Update(&["email"]),
+        )
+        .await?;
+
+    assert_eq!(updated, Some(2));
+
+    let name: String = sqlx::query_scalar("SELECT name FROM any_users WHERE id = 2")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(name, "Robert");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_capabilities_with_any() -> anyhow::Result<()> {
+    sqlx::any::install_default_drivers();
+    let conn = new::<Any>().await?;
+
+    let capabilities = conn.capabilities();
+
+    assert!(capabilities.returning);
+    assert!(capabilities.savepoints);
+    assert!(!capabilities.arrays);
+    assert!(!capabilities.listen_notify);
+
+    Ok(())
+}
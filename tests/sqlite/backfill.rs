@@ -0,0 +1,143 @@
+use sqlx::backfill::{Backfill, BackfillBatch, BackfillError, BackfillProgress};
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::{Executor, SqliteConnection, SqlitePool};
+
+async fn pool(backfill: &Backfill) -> anyhow::Result<SqlitePool> {
+    // a single connection, so every query sees the same in-memory database
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+
+    pool.execute(&*backfill.migration("SQLite").unwrap())
+        .await?;
+    pool.execute(
+        "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL, upper_name TEXT);
+         INSERT INTO users (id, name) VALUES (1, 'a'), (2, 'b'), (3, 'c'), (4, 'd'), (5, 'e');",
+    )
+    .await?;
+
+    Ok(pool)
+}
+
+async fn upper_names(
+    conn: &mut SqliteConnection,
+    batch: BackfillBatch,
+) -> sqlx::Result<Option<i64>> {
+    sqlx::query_scalar(
+        "UPDATE users SET upper_name = upper(name)
+         WHERE id IN (SELECT id FROM users WHERE id > ?1 ORDER BY id LIMIT ?2)
+         RETURNING id",
+    )
+    .bind(batch.last_key.unwrap_or(0))
+    .bind(batch.size)
+    .fetch_all(conn)
+    .await
+    .map(|ids: Vec<i64>| ids.into_iter().max())
+}
+
+#[sqlx_macros::test]
+async fn it_runs_in_batches() -> anyhow::Result<()> {
+    let backfill = Backfill::new("backfills", "upper_names").batch_size(2);
+    let pool = pool(&backfill).await?;
+
+    let progress = backfill
+        .run(&pool, |conn, batch| Box::pin(upper_names(conn, batch)))
+        .await?;
+
+    // three batches of rows, and an empty one
+    assert_eq!(
+        progress,
+        BackfillProgress {
+            last_key: Some(5),
+            batches: 4,
+            completed: true,
+        }
+    );
+    assert_eq!(backfill.progress(&pool).await?, progress);
+
+    let count: i64 =
+        sqlx::query_scalar("SELECT count(*) FROM users WHERE upper_name = upper(name)")
+            .fetch_one(&pool)
+            .await?;
+    assert_eq!(count, 5);
+
+    // a completed backfill does nothing
+    let progress = backfill
+        .run(&pool, |_, _| {
+            Box::pin(async { Err(std::io::Error::other("ran again")) })
+        })
+        .await?;
+    assert_eq!(progress.batches, 4);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_resumes_after_a_failure() -> anyhow::Result<()> {
+    let backfill = Backfill::new("backfills", "upper_names").batch_size(2);
+    let pool = pool(&backfill).await?;
+
+    let progress = backfill
+        .run_batch(&pool, |conn, batch| Box::pin(upper_names(conn, batch)))
+        .await?;
+    assert_eq!(progress.last_key, Some(2));
+
+    let err = backfill
+        .run(&pool, |conn, batch| {
+            Box::pin(async move {
+                upper_names(conn, batch).await?;
+                Err::<Option<i64>, _>(sqlx::Error::Protocol("interrupted".into()))
+            })
+        })
+        .await
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        BackfillError::Handler {
+            last_key: Some(2),
+            ..
+        }
+    ));
+
+    // the failed batch was rolled back
+    let count: i64 = sqlx::query_scalar("SELECT count(*) FROM users WHERE upper_name IS NOT NULL")
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(count, 2);
+
+    let progress = backfill
+        .run(&pool, |conn, batch| Box::pin(upper_names(conn, batch)))
+        .await?;
+    assert_eq!(progress.last_key, Some(5));
+    assert!(progress.completed);
+
+    backfill.reset(&pool).await?;
+    assert_eq!(backfill.progress(&pool).await?, BackfillProgress::default());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_stops_when_cancelled() -> anyhow::Result<()> {
+    let backfill = Backfill::new("backfills", "upper_names").batch_size(2);
+    let pool = pool(&backfill).await?;
+
+    let progress = backfill
+        .run_until(&pool, async {}, |conn, batch| {
+            Box::pin(upper_names(conn, batch))
+        })
+        .await?;
+
+    assert_eq!(
+        progress,
+        BackfillProgress {
+            last_key: Some(2),
+            batches: 1,
+            completed: false,
+        }
+    );
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use sqlx::Sqlite;
-use sqlx_test::test_type;
+use sqlx_test::{new, test_type};
 
 #[derive(Debug, PartialEq, sqlx::Type)]
 #[repr(u32)]
@@ -12,3 +12,138 @@ test_type!(origin_enum<Origin>(Sqlite,
     "1" == Origin::Foo,
     "2" == Origin::Bar,
 ));
+
+#[sqlx_macros::test]
+async fn test_from_row_tagged_enum() -> anyhow::Result<()> {
+    #[derive(Debug, PartialEq, sqlx::FromRow)]
+    #[sqlx(tag = "kind")]
+    enum Shape {
+        #[sqlx(rename = "circle")]
+        Circle { radius: f64 },
+        #[sqlx(rename = "rect")]
+        Rectangle { width: f64, height: f64 },
+        #[sqlx(rename = "point")]
+        Point,
+    }
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let shapes: Vec<Shape> = sqlx::query_as(
+        "SELECT 'circle' AS kind, 1.5 AS radius, NULL AS width, NULL AS height \
+         UNION ALL SELECT 'rect', NULL, 2.0, 3.0 \
+         UNION ALL SELECT 'point', NULL, NULL, NULL",
+    )
+    .fetch_all(&mut conn)
+    .await?;
+
+    assert_eq!(
+        shapes,
+        [
+            Shape::Circle { radius: 1.5 },
+            Shape::Rectangle {
+                width: 2.0,
+                height: 3.0
+            },
+            Shape::Point,
+        ]
+    );
+
+    let err = sqlx::query_as::<_, Shape>("SELECT 'triangle' AS kind")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, sqlx::Error::Decode(_)), "{err:?}");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_from_row_rename_all_and_alias() -> anyhow::Result<()> {
+    #[derive(Debug, PartialEq, sqlx::FromRow)]
+    #[sqlx(rename_all = "camelCase")]
+    struct Account {
+        account_id: i64,
+        #[sqlx(alias = "name", alias = "legacy_name")]
+        display_name: String,
+    }
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let account: Account = sqlx::query_as("SELECT 1 AS accountId, 'alice' AS displayName")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(
+        account,
+        Account {
+            account_id: 1,
+            display_name: "alice".into()
+        }
+    );
+
+    let account: Account = sqlx::query_as("SELECT 2 AS accountId, 'bob' AS legacy_name")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(account.display_name, "bob");
+
+    let err = sqlx::query_as::<_, Account>("SELECT 3 AS accountId")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, sqlx::Error::ColumnNotFound(_)), "{err:?}");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_from_row_deny_unknown_columns() -> anyhow::Result<()> {
+    #[derive(Debug, PartialEq, sqlx::FromRow)]
+    #[sqlx(deny_unknown_columns)]
+    struct Account {
+        id: i64,
+        #[sqlx(rename = "display_name")]
+        name: String,
+        #[sqlx(skip)]
+        cached: bool,
+    }
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let account: Account = sqlx::query_as("SELECT 1 AS id, 'alice' AS display_name")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(account.name, "alice");
+
+    let err = sqlx::query_as::<_, Account>("SELECT 1 AS id, 'alice' AS display_name, 2 AS extra")
+        .fetch_one(&mut conn)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, sqlx::Error::Decode(_)), "{err:?}");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_from_row_allow_missing() -> anyhow::Result<()> {
+    #[derive(Debug, PartialEq, sqlx::FromRow)]
+    #[sqlx(allow_missing)]
+    struct Account {
+        id: i64,
+        name: Option<String>,
+    }
+
+    let mut conn = new::<Sqlite>().await?;
+
+    let account: Account = sqlx::query_as("SELECT 1 AS id")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(account, Account { id: 1, name: None });
+
+    Ok(())
+}
@@ -968,3 +968,57 @@ async fn it_describes_analytical_function() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_describes_expressions() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let d = conn
+        .describe(
+            "SELECT id + 1.5, lower(text), length(text), text LIKE 'a%', ifnull(owner_id, 0), \
+             CASE WHEN is_sent THEN abs(id) ELSE 0 END, substr(text, ?), owner_id * 2, \
+             text || owner_id, nullif(id, 1) \
+             FROM tweet",
+        )
+        .await?;
+
+    let expected = [
+        ("REAL", Some(false)),
+        ("TEXT", Some(false)),
+        ("INTEGER", Some(false)),
+        ("BOOLEAN", Some(false)),
+        ("INTEGER", Some(false)),
+        ("INTEGER", Some(false)),
+        ("TEXT", Some(true)),
+        ("INTEGER", Some(true)),
+        ("TEXT", Some(true)),
+        ("INTEGER", Some(true)),
+    ];
+
+    for (i, (name, nullable)) in expected.into_iter().enumerate() {
+        assert_eq!(d.column(i).type_info().name(), name, "column {i}");
+        assert_eq!(d.nullable(i), nullable, "column {i}");
+    }
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_describes_expression_aggregates() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let d = conn
+        .describe("SELECT avg(id), total(owner_id), group_concat(text) FROM tweet")
+        .await?;
+
+    assert_eq!(d.column(0).type_info().name(), "REAL");
+    assert_eq!(d.nullable(0), Some(true));
+
+    assert_eq!(d.column(1).type_info().name(), "REAL");
+    assert_eq!(d.nullable(1), Some(false));
+
+    assert_eq!(d.column(2).type_info().name(), "TEXT");
+    assert_eq!(d.nullable(2), Some(true));
+
+    Ok(())
+}
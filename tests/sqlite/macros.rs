@@ -110,6 +110,98 @@ async fn test_query_as_raw() -> anyhow::Result<()> {
     Ok(())
 }
 
+sqlx::query!(
+    #[derive(Debug)]
+    as pub AccountRecord,
+    "SELECT id, name, is_active FROM accounts WHERE id = ? AND name <> ?",
+    id: i64,
+    not_name: &'q str,
+);
+
+async fn account_record(conn: &mut sqlx::SqliteConnection, id: i64) -> sqlx::Result<AccountRecord> {
+    AccountRecord::query(id, "nobody").fetch_one(conn).await
+}
+
+#[sqlx_macros::test]
+async fn test_query_record() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let account = account_record(&mut conn, 1).await?;
+
+    let AccountRecord {
+        id,
+        name,
+        is_active,
+    }: AccountRecord = account;
+
+    assert_eq!(id, 1);
+    assert_eq!(name, "Herp Derpinson");
+    assert_eq!(is_active, Some(true));
+
+    Ok(())
+}
+
+#[derive(Debug)]
+struct AccountName {
+    id: i64,
+    name: String,
+}
+
+#[sqlx_macros::test]
+async fn test_query_as_skip() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let account = sqlx::query_as!(
+        AccountName,
+        skip(is_active),
+        "SELECT * FROM accounts WHERE id = ?",
+        1i64
+    )
+    .fetch_one(&mut conn)
+    .await?;
+
+    assert_eq!(account.id, 1);
+    assert_eq!(account.name, "Herp Derpinson");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_query_filtered() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    // other tests, e.g. `it_encodes_bool_with_any`, insert more accounts
+    for (name, min_id, expected) in [
+        (None, None, 1),
+        (Some("Herp Derpinson"), None, 1),
+        (Some("Nobody"), None, 0),
+        (None, Some(2i64), 0),
+        (Some("Herp Derpinson"), Some(1), 1),
+    ] {
+        let accounts = sqlx::query_filtered!(
+            "SELECT id, name FROM accounts WHERE id > ? AND id < 10", 0i64;
+            " AND name = " => name,
+            " AND id >= " => min_id,
+        )
+        .fetch_all(&mut conn)
+        .await?;
+
+        assert_eq!(accounts.len(), expected);
+    }
+
+    let accounts = sqlx::query_filtered_as!(
+        AccountName,
+        "SELECT id, name FROM accounts WHERE id > ? AND id < 10", 0i64;
+        " AND id >= " => Some(1i64),
+    )
+    .fetch_all(&mut conn)
+    .await?;
+
+    assert_eq!(accounts[0].name, "Herp Derpinson");
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn test_query_scalar() -> anyhow::Result<()> {
     let mut conn = new::<Sqlite>().await?;
@@ -334,3 +426,67 @@ async fn test_column_override_exact_nullable() -> anyhow::Result<()> {
 }
 
 // we don't emit bind parameter typechecks for SQLite so testing the overrides is redundant
+
+#[derive(PartialEq, Eq, Debug, sqlx::Id)]
+struct TweetId(i64);
+
+#[sqlx_macros::test]
+async fn test_id_bind_and_override() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let id: TweetId = "1".parse()?;
+
+    let record = sqlx::query!(r#"select id as "id: TweetId" from tweet where id = ?"#, id)
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(record.id, TweetId(1));
+    assert_eq!(record.id.to_string(), "1");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn test_query_grouped_as() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    #[derive(Debug, PartialEq)]
+    struct Parent {
+        id: i64,
+        name: String,
+        children: Vec<Child>,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Child {
+        value: i32,
+    }
+
+    let parents = sqlx::query_grouped_as!(
+        Parent,
+        children: Child,
+        r#"SELECT 1 AS "id!", 'a' AS "name!", 10 AS "children.value!"
+           UNION ALL SELECT 2, 'b', NULL
+           UNION ALL SELECT 1, 'a', 11"#
+    )
+    .fetch_all(&mut conn)
+    .await?;
+
+    assert_eq!(
+        parents,
+        [
+            Parent {
+                id: 1,
+                name: "a".into(),
+                children: vec![Child { value: 10 }, Child { value: 11 }],
+            },
+            Parent {
+                id: 2,
+                name: "b".into(),
+                children: vec![],
+            },
+        ]
+    );
+
+    Ok(())
+}
@@ -1,9 +1,10 @@
-use sqlx::migrate::Migrator;
+use sqlx::migrate::{AuditInfo, Migrate, Migrator};
 use sqlx::pool::PoolConnection;
 use sqlx::sqlite::{Sqlite, SqliteConnection};
 use sqlx::Executor;
 use sqlx::Row;
 use std::path::Path;
+use std::time::Duration;
 
 #[sqlx::test(migrations = false)]
 async fn simple(mut conn: PoolConnection<Sqlite>) -> anyhow::Result<()> {
@@ -66,6 +67,90 @@ async fn reversible(mut conn: PoolConnection<Sqlite>) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx::test(migrations = false)]
+async fn audit_log(mut conn: PoolConnection<Sqlite>) -> anyhow::Result<()> {
+    clean_up(&mut conn).await?;
+
+    let mut migrator = Migrator::new(Path::new("tests/sqlite/migrations_reversible")).await?;
+    migrator.set_audit_log(Some(AuditInfo {
+        tool_version: Some("sqlx-test".into()),
+        ..AuditInfo::default()
+    }));
+
+    migrator.run(&mut conn).await?;
+    migrator.undo(&mut conn, 20220721124650).await?;
+
+    let log = conn.list_migrations_log().await?;
+
+    let versions: Vec<_> = log.iter().map(|e| (e.version, e.reverted)).collect();
+    assert_eq!(
+        versions,
+        [
+            (20220721124650, false),
+            (20220721125033, false),
+            (20220721125033, true)
+        ]
+    );
+    assert_eq!(log[0].description, "add table");
+    assert_eq!(log[0].tool_version.as_deref(), Some("sqlx-test"));
+    assert_eq!(log[0].git_commit, None);
+    // stored as a timestamp, to the second
+    assert!(log[0].applied_at.elapsed()? < Duration::from_secs(60));
+
+    Ok(())
+}
+
+#[sqlx::test(migrations = false)]
+async fn filter_and_targets(mut conn: PoolConnection<Sqlite>) -> anyhow::Result<()> {
+    clean_up(&mut conn).await?;
+
+    let migrator = Migrator::new(Path::new("tests/sqlite/migrations_reversible")).await?;
+
+    // only the first migration
+    migrator.up_to(&mut conn, 20220721124650).await?;
+
+    let res: i64 = conn
+        .fetch_one("SELECT some_payload FROM migrations_reversible_test")
+        .await?
+        .get(0);
+    assert_eq!(res, 100);
+
+    migrator.up_to(&mut conn, 20220721125033).await?;
+    migrator.down_to(&mut conn, 20220721124650).await?;
+
+    let res: i64 = conn
+        .fetch_one("SELECT some_payload FROM migrations_reversible_test")
+        .await?
+        .get(0);
+    assert_eq!(res, 100);
+
+    // the skipped migration is neither applied nor reported as missing
+    let filtered = migrator.filter(|m| m.description != "modify column");
+    filtered.run(&mut conn).await?;
+
+    let applied: Vec<_> = conn
+        .list_applied_migrations()
+        .await?
+        .iter()
+        .map(|m| m.version)
+        .collect();
+    assert_eq!(applied, [20220721124650]);
+
+    migrator.run(&mut conn).await?;
+    filtered.run(&mut conn).await?;
+    filtered.undo(&mut conn, 0).await?;
+
+    let applied: Vec<_> = conn
+        .list_applied_migrations()
+        .await?
+        .iter()
+        .map(|m| m.version)
+        .collect();
+    assert_eq!(applied, [20220721125033]);
+
+    Ok(())
+}
+
 /// Ensure that we have a clean initial state.
 async fn clean_up(conn: &mut SqliteConnection) -> anyhow::Result<()> {
     conn.execute("DROP TABLE migrations_simple_test").await.ok();
@@ -73,6 +158,7 @@ async fn clean_up(conn: &mut SqliteConnection) -> anyhow::Result<()> {
         .await
         .ok();
     conn.execute("DROP TABLE _sqlx_migrations").await.ok();
+    conn.execute("DROP TABLE _sqlx_migrations_log").await.ok();
 
     Ok(())
 }
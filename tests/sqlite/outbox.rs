@@ -0,0 +1,120 @@
+use sqlx::outbox::{Outbox, OutboxError, OutboxMessage};
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::{Executor, SqlitePool};
+
+async fn pool(outbox: &Outbox) -> anyhow::Result<SqlitePool> {
+    // a single connection, so every query sees the same in-memory database
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+
+    pool.execute(&*outbox.migration("SQLite").unwrap()).await?;
+
+    Ok(pool)
+}
+
+#[sqlx_macros::test]
+async fn it_delivers_committed_messages() -> anyhow::Result<()> {
+    let outbox = Outbox::new("outbox");
+    let pool = pool(&outbox).await?;
+
+    let mut tx = pool.begin().await?;
+    outbox.enqueue(&mut tx, "created", "1").await?;
+    outbox.enqueue(&mut tx, "updated", "1").await?;
+    tx.commit().await?;
+
+    let mut tx = pool.begin().await?;
+    outbox.enqueue(&mut tx, "deleted", "1").await?;
+    tx.rollback().await?;
+
+    let mut delivered = Vec::new();
+
+    let count = outbox
+        .process_batch(&pool, |message| {
+            delivered.push(message);
+            async { Ok::<_, std::io::Error>(()) }
+        })
+        .await?;
+
+    assert_eq!(count, 2);
+    assert_eq!(
+        delivered
+            .iter()
+            .map(|message| message.topic.as_str())
+            .collect::<Vec<_>>(),
+        ["created", "updated"]
+    );
+
+    // delivered messages are deleted
+    let count = outbox
+        .process_batch(&pool, |_| async { Ok::<_, std::io::Error>(()) })
+        .await?;
+
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_redelivers_messages_if_the_handler_fails() -> anyhow::Result<()> {
+    let outbox = Outbox::new("outbox");
+    let pool = pool(&outbox).await?;
+
+    let mut tx = pool.begin().await?;
+    outbox.enqueue(&mut tx, "created", "1").await?;
+    tx.commit().await?;
+
+    let err = outbox
+        .process_batch(&pool, |_| async {
+            Err(std::io::Error::other("broker is down"))
+        })
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, OutboxError::Handler { .. }));
+
+    let mut delivered: Vec<OutboxMessage> = Vec::new();
+
+    outbox
+        .process_batch(&pool, |message| {
+            delivered.push(message);
+            async { Ok::<_, std::io::Error>(()) }
+        })
+        .await?;
+
+    assert_eq!(delivered.len(), 1);
+    assert_eq!(delivered[0].payload, "1");
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_keeps_polling_after_the_wakeups_end() -> anyhow::Result<()> {
+    let outbox = Outbox::new("outbox").poll_interval(std::time::Duration::from_millis(10));
+    let pool = pool(&outbox).await?;
+
+    let enqueue = async {
+        sqlx_core::rt::sleep(std::time::Duration::from_millis(50)).await;
+
+        let mut tx = pool.begin().await?;
+        outbox.enqueue(&mut tx, "created", "1").await?;
+        tx.commit().await?;
+
+        anyhow::Ok(())
+    };
+
+    // the handler fails so that `run_with_wakeups()` returns once the message is delivered
+    let run = outbox.run_with_wakeups(
+        &pool,
+        futures::stream::empty::<Result<(), sqlx::Error>>(),
+        |_| async { Err(std::io::Error::other("stop")) },
+    );
+
+    let (err, enqueued) = futures::join!(run, enqueue);
+    enqueued?;
+
+    assert!(matches!(err, Err(OutboxError::Handler { .. })));
+
+    Ok(())
+}
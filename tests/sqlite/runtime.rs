@@ -0,0 +1,68 @@
+use std::io;
+use std::time::Duration;
+
+use futures::channel::oneshot;
+use futures::executor::block_on;
+use futures::future::BoxFuture;
+use sqlx::rt::{Runtime, Socket};
+use sqlx::sqlite::SqlitePoolOptions;
+use sqlx::Error;
+
+/// Runs each task on a thread of its own.
+struct ThreadRuntime;
+
+impl Runtime for ThreadRuntime {
+    fn spawn(&self, future: BoxFuture<'static, ()>) {
+        std::thread::spawn(move || block_on(future));
+    }
+
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        let (tx, rx) = oneshot::channel();
+
+        std::thread::spawn(move || {
+            std::thread::sleep(duration);
+            let _ = tx.send(());
+        });
+
+        Box::pin(async move {
+            let _ = rx.await;
+        })
+    }
+
+    fn connect_tcp<'a>(
+        &'a self,
+        _host: &'a str,
+        _port: u16,
+    ) -> BoxFuture<'a, io::Result<Box<dyn Socket>>> {
+        Box::pin(async { Err(io::ErrorKind::Unsupported.into()) })
+    }
+}
+
+// the installed runtime is used for every test in this binary, so there's only one
+#[test]
+fn it_runs_on_an_installed_runtime() -> anyhow::Result<()> {
+    sqlx::rt::install_runtime(&ThreadRuntime).unwrap();
+    assert!(sqlx::rt::install_runtime(&ThreadRuntime).is_err());
+
+    block_on(async {
+        let pool = SqlitePoolOptions::new()
+            .min_connections(1)
+            .max_connections(1)
+            .acquire_timeout(Duration::from_millis(100))
+            .connect("sqlite::memory:")
+            .await?;
+
+        let sum: i64 = sqlx::query_scalar("SELECT 1 + 1").fetch_one(&pool).await?;
+        assert_eq!(sum, 2);
+
+        // acquiring times out with the sleep of the runtime
+        let conn = pool.acquire().await?;
+        assert!(matches!(pool.acquire().await, Err(Error::PoolTimedOut)));
+        drop(conn);
+
+        pool.acquire().await?;
+        pool.close().await;
+
+        Ok(())
+    })
+}
@@ -3,8 +3,8 @@ use rand::{Rng, SeedableRng};
 use rand_xoshiro::Xoshiro256PlusPlus;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use sqlx::{
-    query, sqlite::Sqlite, sqlite::SqliteRow, Column, ConnectOptions, Connection, Executor, Row,
-    SqliteConnection, SqlitePool, Statement, TypeInfo,
+    query, sqlite::Sqlite, sqlite::SqliteRow, Column, ConnectOptions, Connection, Database,
+    Executor, QueryBuilder, Row, SqliteConnection, SqlitePool, Statement, TypeInfo,
 };
 use sqlx_test::new;
 use std::sync::Arc;
@@ -14,6 +14,47 @@ async fn it_connects() -> anyhow::Result<()> {
     Ok(new::<Sqlite>().await?.ping().await?)
 }
 
+#[sqlx_macros::test]
+async fn it_reports_server_version() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let version = conn.server_version();
+    assert_eq!(version.flavor, sqlx::ServerFlavor::Sqlite);
+
+    let reported: String = conn.fetch_one("SELECT sqlite_version()").await?.get(0);
+    assert_eq!(version.raw, reported);
+    assert!(version.at_least(3, 0, 0));
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_reports_running_query() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let running = conn
+        .running_query()
+        .expect("SQLite tracks the running query");
+    assert_eq!(running.get(), None);
+
+    let sql = "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c WHERE x < 1000) \
+               SELECT x FROM c";
+
+    {
+        let mut rows = conn.fetch(sql);
+
+        // the worker is blocked sending the rest of the rows
+        assert!(rows.try_next().await?.is_some());
+        assert_eq!(running.get().as_deref(), Some(sql));
+
+        while rows.try_next().await?.is_some() {}
+    }
+
+    assert_eq!(running.get(), None);
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_fetches_and_inflates_row() -> anyhow::Result<()> {
     let mut conn = new::<Sqlite>().await?;
@@ -205,6 +246,49 @@ async fn it_executes_with_pool() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_warms_pool() -> anyhow::Result<()> {
+    let pool: SqlitePool = SqlitePoolOptions::new()
+        .max_connections(4)
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    pool.warm(3).await?;
+    assert_eq!(pool.size(), 3);
+    assert_eq!(pool.num_idle(), 3);
+
+    // never more than `max_connections`
+    pool.warm(10).await?;
+    assert_eq!(pool.size(), 4);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_replaces_closed_min_connections() -> anyhow::Result<()> {
+    let pool: SqlitePool = SqlitePoolOptions::new()
+        .min_connections(2)
+        .max_connections(4)
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    assert_eq!(pool.size(), 2);
+
+    pool.acquire().await?.close().await?;
+
+    for _ in 0..50 {
+        if pool.size() == 2 {
+            break;
+        }
+
+        sqlx_core::rt::sleep(std::time::Duration::from_millis(20)).await;
+    }
+
+    assert_eq!(pool.size(), 2);
+
+    Ok(())
+}
+
 #[cfg(sqlite_ipaddr)]
 #[sqlx_macros::test]
 async fn it_opens_with_extension() -> anyhow::Result<()> {
@@ -490,6 +574,25 @@ async fn it_caches_statements() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[sqlx_macros::test]
+async fn it_ignores_transaction_options() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let options = sqlx::TransactionOptions::new()
+        .isolation(sqlx::IsolationLevel::Serializable)
+        .read_only(true);
+
+    let mut tx = conn.begin_with(options).await?;
+
+    let (value,): (i32,) = sqlx::query_as("SELECT 1").fetch_one(&mut *tx).await?;
+
+    assert_eq!(value, 1);
+
+    tx.rollback().await?;
+
+    Ok(())
+}
+
 #[sqlx_macros::test]
 async fn it_can_prepare_then_execute() -> anyhow::Result<()> {
     let mut conn = new::<Sqlite>().await?;
@@ -794,3 +897,555 @@ async fn test_multiple_set_progress_handler_calls_drop_old_handler() -> anyhow::
     assert_eq!(1, Arc::strong_count(&ref_counted_object));
     Ok(())
 }
+
+#[sqlx_macros::test]
+async fn it_streams_row_updates() -> anyhow::Result<()> {
+    use futures::StreamExt;
+    use sqlx::sqlite::{SqliteOperation, SqliteUpdateEvent};
+
+    let mut conn = new::<Sqlite>().await?;
+    let mut updates = conn.updates().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE watched (id INTEGER PRIMARY KEY, name TEXT)")
+        .await?;
+    conn.execute("INSERT INTO watched (id, name) VALUES (7, 'foo')")
+        .await?;
+    conn.execute("UPDATE watched SET name = 'bar' WHERE id = 7")
+        .await?;
+    conn.execute("DELETE FROM watched WHERE id = 7").await?;
+
+    for operation in [
+        SqliteOperation::Insert,
+        SqliteOperation::Update,
+        SqliteOperation::Delete,
+    ] {
+        assert_eq!(
+            updates.next().await,
+            Some(SqliteUpdateEvent {
+                database: "temp".into(),
+                table: "watched".into(),
+                rowid: 7,
+                operation,
+            })
+        );
+    }
+
+    conn.close().await?;
+    assert_eq!(updates.next().await, None);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_serializes_and_deserializes_in_memory_database() -> anyhow::Result<()> {
+    let mut conn = SqliteConnection::connect(":memory:").await?;
+
+    conn.execute("CREATE TABLE snapshot (id INTEGER PRIMARY KEY, name TEXT)")
+        .await?;
+    conn.execute("INSERT INTO snapshot (name) VALUES ('foo'), ('bar')")
+        .await?;
+
+    let data = conn.serialize(None).await?;
+    assert!(conn.serialize(Some("does_not_exist")).await.is_err());
+
+    let options = SqliteConnectOptions::new().deserialize_from(data);
+
+    // each connection gets its own copy of the data
+    let mut copy = options.connect().await?;
+    copy.execute("DELETE FROM snapshot WHERE name = 'foo'")
+        .await?;
+
+    let names: Vec<String> = sqlx::query_scalar("SELECT name FROM snapshot ORDER BY id")
+        .fetch_all(&mut options.connect().await?)
+        .await?;
+    assert_eq!(names, ["foo", "bar"]);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM snapshot")
+        .fetch_one(&mut copy)
+        .await?;
+    assert_eq!(count, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_splits_values_over_the_bind_limit() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE bulk (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+        .await?;
+
+    // 2 parameters per row means this needs at least 2 statements
+    let rows = (Sqlite::BIND_LIMIT / 2 + 100) as i64;
+
+    let result = QueryBuilder::<Sqlite>::new("INSERT INTO bulk(id, name) ")
+        .execute_values_chunked(&mut conn, 0..rows, |mut b, id| {
+            b.push_bind(id).push_bind(format!("row {id}"));
+        })
+        .await?;
+
+    assert_eq!(result.rows_affected(), rows as u64);
+
+    let (count, max): (i64, i64) = sqlx::query_as("SELECT COUNT(*), MAX(id) FROM bulk")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, rows);
+    assert_eq!(max, rows - 1);
+
+    // bind arguments before the `VALUES` clause can't be repeated for each statement
+    let mut builder = QueryBuilder::<Sqlite>::new("WITH t AS (SELECT ");
+    builder
+        .push_bind(1_i64)
+        .push(") INSERT INTO bulk(id, name) ");
+
+    let res = builder
+        .execute_values_chunked(&mut conn, [rows], |mut b, id| {
+            b.push_bind(id).push_bind("bound");
+        })
+        .await;
+    assert!(matches!(res, Err(sqlx::Error::Configuration(_))));
+
+    // nor can the arguments the builder was created with
+    let builder = QueryBuilder::<Sqlite>::with_arguments(
+        "WITH t AS (SELECT ?) INSERT INTO bulk(id, name) ",
+        (1_i64,),
+    );
+
+    let res = builder
+        .execute_values_chunked(&mut conn, [rows], |mut b, id| {
+            b.push_bind(id).push_bind("bound");
+        })
+        .await;
+    assert!(matches!(res, Err(sqlx::Error::Configuration(_))));
+
+    // every tuple must bind the same number of values, or nothing is inserted
+    let res = QueryBuilder::<Sqlite>::new("INSERT INTO bulk(id, name) ")
+        .execute_values_chunked(&mut conn, [rows, rows + 1], |mut b, id| {
+            b.push_bind(id);
+
+            if id == rows {
+                b.push_bind("bound");
+            } else {
+                b.push("'inline'");
+            }
+        })
+        .await;
+    assert!(matches!(res, Err(sqlx::Error::Configuration(_))));
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM bulk")
+        .fetch_one(&mut conn)
+        .await?;
+    assert_eq!(count, rows);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_executes_with_many_argument_sets() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE batch (id INTEGER PRIMARY KEY, name TEXT NOT NULL)")
+        .await?;
+
+    let result = sqlx::query("INSERT INTO batch(id, name) VALUES (?, ?)")
+        .execute_many_args(&mut conn, (0..100_i64).map(|id| (id, format!("row {id}"))))
+        .await?;
+
+    assert_eq!(result.rows_affected(), 100);
+
+    let (count, name): (i64, String) =
+        sqlx::query_as("SELECT COUNT(*), MAX(name) FROM batch WHERE id < 10")
+            .fetch_one(&mut conn)
+            .await?;
+
+    assert_eq!(count, 10);
+    assert_eq!(name, "row 9");
+
+    // a failed execution rolls back the ones before it
+    let result = sqlx::query("INSERT INTO batch(id, name) VALUES (?, ?)")
+        .execute_many_args(&mut conn, [(100_i64, "new"), (0, "duplicate")])
+        .await;
+
+    assert!(result.is_err());
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM batch")
+        .fetch_one(&mut conn)
+        .await?;
+
+    assert_eq!(count, 100);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_fetches_returning_columns() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute(
+        "CREATE TEMPORARY TABLE generated (id INTEGER PRIMARY KEY, name TEXT NOT NULL, \
+         created_at TEXT NOT NULL DEFAULT 'today')",
+    )
+    .await?;
+
+    let rows = QueryBuilder::<Sqlite>::new("INSERT INTO generated(name) ")
+        .push_values(["alice", "bob"], |mut b, name| {
+            b.push_bind(name);
+        })
+        .fetch_returning(&mut conn, &["id", "created_at"])
+        .await?;
+
+    let rows: Vec<(i64, String)> = rows
+        .iter()
+        .map(|row| (row.get("id"), row.get("created_at")))
+        .collect();
+
+    assert_eq!(rows, [(1, "today".into()), (2, "today".into())]);
+
+    Ok(())
+}
+
+#[cfg(feature = "json")]
+#[sqlx_macros::test]
+async fn it_paginates_with_a_keyset() -> anyhow::Result<()> {
+    use sqlx::pagination::{Keyset, Order};
+
+    let mut conn = new::<Sqlite>().await?;
+
+    conn.execute("CREATE TEMPORARY TABLE pages (id INTEGER PRIMARY KEY, rank INTEGER NOT NULL)")
+        .await?;
+
+    // ranks repeat so pages must break ties on `id`
+    QueryBuilder::<Sqlite>::new("INSERT INTO pages(id, rank) ")
+        .push_values(0..10i64, |mut b, id| {
+            b.push_bind(id).push_bind(id / 3);
+        })
+        .build()
+        .execute(&mut conn)
+        .await?;
+
+    let keyset = Keyset::new(&["rank", "id"], 4).order(Order::Descending);
+
+    let mut cursor: Option<String> = None;
+    let mut seen = Vec::new();
+
+    loop {
+        let after: Option<(i64, i64)> = cursor.as_deref().map(Keyset::decode_cursor).transpose()?;
+
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT rank, id FROM pages");
+        keyset.push_where(&mut query, after);
+        keyset.push_order_by(&mut query);
+
+        let rows: Vec<(i64, i64)> = query.build_query_as().fetch_all(&mut conn).await?;
+        let page = keyset.page(rows, |&(rank, id)| (rank, id))?;
+
+        assert!(page.items.len() <= 4);
+        seen.extend(page.items.into_iter().map(|(_, id)| id));
+
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+
+    assert_eq!(seen, [9, 8, 7, 6, 5, 4, 3, 2, 1, 0]);
+
+    assert!(Keyset::decode_cursor::<(i64, i64)>("not a cursor").is_err());
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_sheds_load_with_resilient_pool() -> anyhow::Result<()> {
+    use sqlx::pool::{CircuitState, ResilientPoolOptions};
+    use std::time::Duration;
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect("sqlite::memory:")
+        .await?;
+
+    // every query counts as slow, so the breaker opens after two queries
+    let pool = ResilientPoolOptions::new()
+        .limit("select", 1)
+        .slow_threshold(Duration::ZERO)
+        .min_requests(2)
+        .open_duration(Duration::from_millis(50))
+        .build(pool);
+
+    for _ in 0..2 {
+        let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&pool).await?;
+        assert_eq!(value, 1);
+    }
+
+    let metrics = pool.metrics();
+    assert_eq!(metrics.circuit, CircuitState::Open);
+    assert_eq!(metrics.classes[0].class, "select");
+    assert_eq!(metrics.classes[0].limit, 1);
+    assert_eq!(metrics.classes[0].running, 0);
+
+    let err = sqlx::query_scalar::<_, i32>("SELECT 1")
+        .fetch_one(&pool)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, sqlx::Error::CircuitOpen));
+
+    // after the open duration, a trial query is let through
+    sqlx_core::rt::sleep(Duration::from_millis(60)).await;
+    assert_eq!(pool.metrics().circuit, CircuitState::HalfOpen);
+
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&pool).await?;
+    assert_eq!(value, 1);
+
+    // which was slow, so it opens again
+    assert_eq!(pool.metrics().circuit, CircuitState::Open);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_stops_counting_cancelled_queries_as_waiting() -> anyhow::Result<()> {
+    use futures::TryStreamExt;
+    use sqlx::pool::ResilientPoolOptions;
+    use std::time::Duration;
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(2)
+        .connect("sqlite::memory:")
+        .await?;
+
+    let pool = ResilientPoolOptions::new().limit("select", 1).build(pool);
+
+    // holds the only permit until the stream is dropped
+    let mut running = sqlx::query("SELECT 1 UNION ALL SELECT 2").fetch(&pool);
+    running.try_next().await?;
+
+    let waiting = sqlx::query_scalar::<_, i32>("SELECT 1").fetch_one(&pool);
+    assert!(sqlx_core::rt::timeout(Duration::from_millis(20), waiting)
+        .await
+        .is_err());
+
+    let metrics = pool.metrics();
+    assert_eq!(metrics.classes[0].running, 1);
+    assert_eq!(metrics.classes[0].waiting, 0);
+
+    drop(running);
+    assert_eq!(pool.metrics().classes[0].running, 0);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_runs_queries_through_middleware() -> anyhow::Result<()> {
+    use sqlx::middleware::{ExecutorMiddleware, MiddlewareQuery, Next, QueryResults};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Count(Arc<AtomicUsize>);
+
+    impl ExecutorMiddleware<Sqlite> for Count {
+        fn fetch_many<'e, 'q: 'e>(
+            &'e self,
+            query: MiddlewareQuery<'q, Sqlite>,
+            next: Next<'e, 'q, Sqlite>,
+        ) -> QueryResults<'e, Sqlite> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            next.run(query)
+        }
+    }
+
+    // increments the selected value, and answers `SELECT 'cached'` without the database
+    struct Rewrite;
+
+    impl ExecutorMiddleware<Sqlite> for Rewrite {
+        fn fetch_many<'e, 'q: 'e>(
+            &'e self,
+            mut query: MiddlewareQuery<'q, Sqlite>,
+            next: Next<'e, 'q, Sqlite>,
+        ) -> QueryResults<'e, Sqlite> {
+            if query.sql() == "SELECT 'cached'" {
+                return Box::pin(futures::stream::empty());
+            }
+
+            let sql = format!("{} + 1", query.sql());
+            query.set_sql(sql);
+            next.run(query)
+        }
+    }
+
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let pool = SqlitePoolOptions::new()
+        .connect("sqlite::memory:")
+        .await?
+        .with_middleware(Count(count.clone()))
+        .with_middleware(Rewrite);
+
+    let value: i32 = sqlx::query_scalar("SELECT ?")
+        .bind(41)
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(value, 42);
+
+    let cached: Option<String> = sqlx::query_scalar("SELECT 'cached'")
+        .fetch_optional(&pool)
+        .await?;
+    assert_eq!(cached, None);
+    assert_eq!(count.load(Ordering::SeqCst), 2);
+
+    // the pool itself still runs queries as they are
+    let value: i32 = sqlx::query_scalar("SELECT 41")
+        .fetch_one(pool.inner())
+        .await?;
+    assert_eq!(value, 41);
+
+    let mut conn = new::<Sqlite>().await?;
+    let mut conn = conn.with_middleware(Rewrite);
+
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&mut conn).await?;
+    assert_eq!(value, 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_routes_queries_across_shards() -> anyhow::Result<()> {
+    use sqlx::pool::ShardedPool;
+
+    let mut shards = Vec::new();
+
+    for _ in 0..3 {
+        // every in-memory connection is a separate database
+        shards.push(
+            SqlitePoolOptions::new()
+                .max_connections(1)
+                .connect("sqlite::memory:")
+                .await?,
+        );
+    }
+
+    let pool = ShardedPool::<_, i64>::new(shards);
+
+    pool.broadcast("CREATE TABLE orders (tenant INTEGER NOT NULL)")
+        .await?;
+
+    for tenant in 0..12_i64 {
+        sqlx::query("INSERT INTO orders VALUES (?)")
+            .bind(tenant)
+            .execute(pool.shard(&tenant))
+            .await?;
+    }
+
+    let mut tx = pool.transaction_on_shard(&5).await?;
+    sqlx::query("INSERT INTO orders VALUES (5)")
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    for (index, shard) in pool.shards().iter().enumerate() {
+        let tenants: Vec<i64> = sqlx::query_scalar("SELECT tenant FROM orders")
+            .fetch_all(shard)
+            .await?;
+
+        assert!(tenants
+            .iter()
+            .all(|tenant| pool.shard_index(tenant) == index));
+    }
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM orders WHERE tenant = ?")
+        .bind(5_i64)
+        .fetch_one(pool.shard(&5))
+        .await?;
+    assert_eq!(count, 2);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_routes_reads_to_replicas() -> anyhow::Result<()> {
+    use sqlx::pool::ReplicaPool;
+
+    let primary = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+
+    // without replication, so writes are never seen on the replica
+    let replica = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+
+    for pool in [&primary, &replica] {
+        pool.execute("CREATE TABLE items (id INTEGER PRIMARY KEY)")
+            .await?;
+    }
+
+    let pool = ReplicaPool::new(primary, vec![replica]);
+
+    sqlx::query("INSERT INTO items (id) VALUES (1)")
+        .execute(&pool)
+        .await?;
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(&pool)
+        .await?;
+    assert_eq!(count, 0);
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM items")
+        .fetch_one(pool.primary())
+        .await?;
+    assert_eq!(count, 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_acquires_with_fast_acquire_test() -> anyhow::Result<()> {
+    use sqlx::pool::AcquireTest;
+
+    let pool: SqlitePool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .acquire_test(AcquireTest::Fast)
+        .acquire_test_after_idle(std::time::Duration::from_secs(1))
+        .connect(&dotenvy::var("DATABASE_URL")?)
+        .await?;
+
+    for _ in 0..3 {
+        let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&pool).await?;
+        assert_eq!(value, 1);
+    }
+
+    assert_eq!(pool.size(), 1);
+
+    Ok(())
+}
+
+#[sqlx_macros::test]
+async fn it_fetches_with_fetch_size() -> anyhow::Result<()> {
+    let mut conn = new::<Sqlite>().await?;
+
+    let rows: Vec<i64> = sqlx::query_scalar::<_, i64>(
+        "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 100) \
+         SELECT x FROM n",
+    )
+    .fetch_size(4)
+    .fetch(&mut conn)
+    .try_collect()
+    .await?;
+
+    assert_eq!(rows, (1..=100).collect::<Vec<_>>());
+
+    Ok(())
+}
+
+#[test]
+fn it_pushes_the_configured_soft_delete_column() {
+    let mut builder = QueryBuilder::<Sqlite>::new("SELECT * FROM users u WHERE ");
+    builder.push_not_deleted("u", sqlx::soft_delete_column!());
+
+    // no `sqlx.toml` configures the column
+    assert_eq!(
+        builder.sql(),
+        "SELECT * FROM users u WHERE u.deleted_at IS NULL"
+    );
+}
@@ -75,6 +75,18 @@ mod json_tests {
         "\'{\"json_column\":[1,2]}\'" == Json(Customer { json_column: Json(vec![1, 2]) })
     ));
 
+    #[derive(serde::Deserialize, serde::Serialize, sqlx::Type, Debug, PartialEq)]
+    #[sqlx(json)]
+    struct Pet {
+        name: String,
+        legs: u32,
+    }
+
+    test_type!(json_derive<Pet>(
+        Sqlite,
+        "\'{\"name\":\"Rex\",\"legs\":4}\'" == Pet { name: "Rex".to_string(), legs: 4 }
+    ));
+
     #[sqlx_macros::test]
     async fn it_json_extracts() -> anyhow::Result<()> {
         let mut conn = new::<Sqlite>().await?;